@@ -0,0 +1,90 @@
+//! Style resolution and layout throughput on large documents.
+//!
+//! Compares the sequential pipeline against [`LayoutBatcher`]'s
+//! rayon-parallel selector matching on a synthetic page shaped like the
+//! worst real offenders (Wikipedia articles, the single-page HTML spec):
+//! thousands of elements, a few hundred rules.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+
+use binix::renderer::frame::FrameTree;
+use binix::renderer::html;
+use binix::renderer::layout::{layout_document, HeuristicMeasurer, LayoutBatcher};
+use binix::renderer::media::MediaEnvironment;
+use binix::renderer::style::{self, StyleEngine};
+
+/// A page with `sections` sections of headed prose and lists, styled by a
+/// rule set large enough that matching dominates.
+fn synthetic_page(sections: usize) -> String {
+    let mut page = String::from("<html><head><style>\n");
+    for i in 0..150 {
+        page.push_str(&format!(
+            ".section-{i} p {{ color: #333; margin: 0.5em 0; }}\n\
+             .section-{i} li.item {{ font-size: 14px; }}\n\
+             div.section-{i} > h2 {{ font-weight: bold; }}\n"
+        ));
+    }
+    page.push_str("</style></head><body>\n");
+    for i in 0..sections {
+        page.push_str(&format!("<div class=\"section section-{}\">", i % 150));
+        page.push_str("<h2>Heading text for the section</h2>");
+        for _ in 0..4 {
+            page.push_str("<p class=\"prose\">Paragraph with enough words to need line breaking when laid out at viewport width.</p>");
+        }
+        page.push_str("<ul>");
+        for _ in 0..6 {
+            page.push_str("<li class=\"item\">List item content</li>");
+        }
+        page.push_str("</ul></div>\n");
+    }
+    page.push_str("</body></html>");
+    page
+}
+
+fn bench_style_resolution(c: &mut Criterion) {
+    let document = html::parse(&synthetic_page(400));
+    let mut styles = StyleEngine::new();
+    style::collect_styles(&document, &mut styles);
+    let env = MediaEnvironment::default();
+
+    let mut group = c.benchmark_group("style-resolution");
+    group.sample_size(20);
+    group.bench_function("sequential", |b| {
+        b.iter(|| styles.resolve(&document, &env));
+    });
+    group.bench_function("parallel", |b| {
+        b.iter(|| styles.resolve_parallel(&document, &env));
+    });
+    group.finish();
+}
+
+fn bench_full_layout(c: &mut Criterion) {
+    let document = html::parse(&synthetic_page(400));
+    let mut styles = StyleEngine::new();
+    style::collect_styles(&document, &mut styles);
+    let env = MediaEnvironment::default();
+    let measurer = HeuristicMeasurer;
+    let frames = FrameTree::default();
+
+    let mut group = c.benchmark_group("full-layout");
+    group.sample_size(20);
+    group.bench_function("sequential", |b| {
+        b.iter_batched(
+            || (),
+            |()| layout_document(&document, &styles, &env, &measurer),
+            BatchSize::SmallInput,
+        );
+    });
+    group.bench_function("batched", |b| {
+        let batcher = LayoutBatcher::new(&measurer);
+        b.iter_batched(
+            || (),
+            |()| batcher.layout(&document, &styles, &env, &frames),
+            BatchSize::SmallInput,
+        );
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_style_resolution, bench_full_layout);
+criterion_main!(benches);
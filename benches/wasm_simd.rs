@@ -0,0 +1,110 @@
+//! Scalar vs SIMD throughput through the wasm interpreter.
+//!
+//! Two hand-assembled modules do the same arithmetic — a long chain of
+//! i32 additions — one lane at a time and four lanes per `i32x4.add`.
+//! The vector module dispatches a quarter of the instructions, which is
+//! where the interpreter's speedup comes from; the benchmark exists to
+//! keep that ratio honest as the opcode loop evolves.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use binix::wasm::{Value, WasmRuntime};
+
+/// Additions per invocation, in scalar terms.
+const ADDS: usize = 4096;
+
+fn leb(mut value: u32) -> Vec<u8> {
+    let mut out = Vec::new();
+    loop {
+        let byte = (value & 0x7F) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return out;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn section(id: u8, mut payload: Vec<u8>) -> Vec<u8> {
+    let mut out = vec![id];
+    out.extend(leb(payload.len() as u32));
+    out.append(&mut payload);
+    out
+}
+
+/// A module exporting `run: (i32) -> i32` with the given body.
+fn module(body: &[u8]) -> Vec<u8> {
+    let mut bytes = b"\0asm\x01\0\0\0".to_vec();
+    bytes.extend(section(1, vec![1, 0x60, 1, 0x7F, 1, 0x7F]));
+    bytes.extend(section(3, vec![1, 0]));
+    let mut exports = vec![1, 3];
+    exports.extend(b"run");
+    exports.extend([0x00, 0]);
+    bytes.extend(section(7, exports));
+    let mut function = vec![0];
+    function.extend_from_slice(body);
+    function.push(0x0B);
+    let mut code = vec![1];
+    code.extend(leb(function.len() as u32));
+    code.extend(function);
+    bytes.extend(section(10, code));
+    bytes
+}
+
+/// `x + 1 + 1 + …`, one add per step.
+fn scalar_body() -> Vec<u8> {
+    let mut body = vec![0x20, 0]; // local.get 0
+    for _ in 0..ADDS {
+        body.extend([0x41, 1, 0x6A]); // i32.const 1; i32.add
+    }
+    body
+}
+
+/// The same additions, four lanes at a time on a splat of the argument.
+fn simd_body() -> Vec<u8> {
+    let ones = {
+        let mut op = vec![0xFD];
+        op.extend(leb(0x0C)); // v128.const
+        op.extend(1i32.to_le_bytes().repeat(4));
+        op
+    };
+    let mut body = vec![0x20, 0]; // local.get 0
+    body.extend([0xFD, 0x11]); // i32x4.splat
+    for _ in 0..ADDS / 4 {
+        body.extend(&ones);
+        body.extend([0xFD]);
+        body.extend(leb(0xAE)); // i32x4.add
+    }
+    body.extend([0xFD, 0x1B, 0]); // i32x4.extract_lane 0
+    body
+}
+
+fn bench_interpreter(c: &mut Criterion) {
+    let scalar = WasmRuntime::compile(&module(&scalar_body())).expect("scalar module");
+    let simd = WasmRuntime::compile(&module(&simd_body())).expect("simd module");
+    let mut scalar = WasmRuntime::instantiate(scalar).expect("scalar instance");
+    let mut simd = WasmRuntime::instantiate(simd).expect("simd instance");
+
+    c.bench_function("wasm_add_chain_scalar", |b| {
+        b.iter(|| {
+            let result = scalar
+                .invoke_export("run", &[Value::I32(7)], &mut |_, _| Ok(Vec::new()))
+                .expect("scalar run");
+            assert_eq!(result, vec![Value::I32(7 + ADDS as i32)]);
+        });
+    });
+    c.bench_function("wasm_add_chain_i32x4", |b| {
+        b.iter(|| {
+            // A quarter of the additions land in lane 0; the other
+            // lanes carry the rest of the element work.
+            let result = simd
+                .invoke_export("run", &[Value::I32(7)], &mut |_, _| Ok(Vec::new()))
+                .expect("simd run");
+            assert_eq!(result, vec![Value::I32(7 + (ADDS / 4) as i32)]);
+        });
+    });
+}
+
+criterion_group!(benches, bench_interpreter);
+criterion_main!(benches);
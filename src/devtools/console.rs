@@ -0,0 +1,57 @@
+//! The devtools console message stream.
+//!
+//! Page scripts write here through the `console.*` bindings; the
+//! devtools UI drains pending messages each frame, same shape as
+//! [`super::network_inspector`].
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+const MAX_MESSAGES: usize = 512;
+
+/// Severity of a console message, mirroring the `console` method used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConsoleLevel {
+    Debug,
+    Log,
+    Info,
+    Warn,
+    Error,
+}
+
+/// One `console.*` call, formatted.
+#[derive(Debug, Clone)]
+pub struct ConsoleMessage {
+    pub level: ConsoleLevel,
+    /// The arguments rendered and space-joined, `console.log` style.
+    pub text: String,
+    /// Best-effort script location (the executing function), when the
+    /// engine could recover one.
+    pub location: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TimestampedMessage {
+    pub at: SystemTime,
+    pub message: ConsoleMessage,
+}
+
+static MESSAGES: Mutex<VecDeque<TimestampedMessage>> = Mutex::new(VecDeque::new());
+
+/// Record a console message from page script.
+pub fn report(message: ConsoleMessage) {
+    let mut messages = MESSAGES.lock().unwrap();
+    if messages.len() >= MAX_MESSAGES {
+        messages.pop_front();
+    }
+    messages.push_back(TimestampedMessage {
+        at: SystemTime::now(),
+        message,
+    });
+}
+
+/// Take all pending messages, oldest first.
+pub fn drain() -> Vec<TimestampedMessage> {
+    MESSAGES.lock().unwrap().drain(..).collect()
+}
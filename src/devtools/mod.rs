@@ -0,0 +1,6 @@
+//! Developer tooling surfaces: the network inspector, console, and the
+//! hooks the rest of the engine reports into.
+
+pub mod console;
+pub mod network_inspector;
+pub mod page;
@@ -0,0 +1,50 @@
+//! Event log backing the devtools network panel.
+//!
+//! Engine components report noteworthy network events here; the devtools UI
+//! drains them each frame. The log is process-wide and bounded.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+const MAX_EVENTS: usize = 4096;
+
+/// One entry in the network inspector's event stream.
+#[derive(Debug, Clone)]
+pub enum NetworkEvent {
+    /// A certificate pin check failed for `host`; `presented` holds the
+    /// hex SPKI hashes that were actually in the chain.
+    PinViolation { host: String, presented: String },
+}
+
+#[derive(Debug, Clone)]
+pub struct TimestampedEvent {
+    pub at: SystemTime,
+    pub event: NetworkEvent,
+}
+
+static EVENTS: Mutex<VecDeque<TimestampedEvent>> = Mutex::new(VecDeque::new());
+
+fn push(event: NetworkEvent) {
+    let mut events = EVENTS.lock().unwrap();
+    if events.len() >= MAX_EVENTS {
+        events.pop_front();
+    }
+    events.push_back(TimestampedEvent {
+        at: SystemTime::now(),
+        event,
+    });
+}
+
+/// Report a pin validation failure (see [`crate::security::pinning`]).
+pub fn report_pin_violation(host: &str, presented: &str) {
+    push(NetworkEvent::PinViolation {
+        host: host.to_owned(),
+        presented: presented.to_owned(),
+    });
+}
+
+/// Take all pending events, oldest first.
+pub fn drain() -> Vec<TimestampedEvent> {
+    EVENTS.lock().unwrap().drain(..).collect()
+}
@@ -0,0 +1,53 @@
+//! Per-document diagnostics for the devtools elements/console panels.
+//!
+//! Engine components report document-level facts here — currently the
+//! rendering mode a page parsed into — and the devtools UI drains them
+//! each frame, same shape as [`super::network_inspector`].
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use crate::renderer::dom::QuirksMode;
+
+const MAX_EVENTS: usize = 256;
+
+/// One entry in the page diagnostics stream.
+#[derive(Debug, Clone)]
+pub enum PageEvent {
+    /// The document at `url` committed in `mode`; anything but
+    /// [`QuirksMode::NoQuirks`] is worth a console warning.
+    DocumentMode { url: String, mode: QuirksMode },
+}
+
+#[derive(Debug, Clone)]
+pub struct TimestampedEvent {
+    pub at: SystemTime,
+    pub event: PageEvent,
+}
+
+static EVENTS: Mutex<VecDeque<TimestampedEvent>> = Mutex::new(VecDeque::new());
+
+fn push(event: PageEvent) {
+    let mut events = EVENTS.lock().unwrap();
+    if events.len() >= MAX_EVENTS {
+        events.pop_front();
+    }
+    events.push_back(TimestampedEvent {
+        at: SystemTime::now(),
+        event,
+    });
+}
+
+/// Report the rendering mode a committed document parsed into.
+pub fn report_document_mode(url: &str, mode: QuirksMode) {
+    push(PageEvent::DocumentMode {
+        url: url.to_owned(),
+        mode,
+    });
+}
+
+/// Take all pending events, oldest first.
+pub fn drain() -> Vec<TimestampedEvent> {
+    EVENTS.lock().unwrap().drain(..).collect()
+}
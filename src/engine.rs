@@ -0,0 +1,335 @@
+//! The engine front door: one navigation, from URL to renderable page.
+//!
+//! [`BrowserEngine`] owns the subsystems a page load crosses — security
+//! policy and the network stack built on it — and
+//! [`BrowserEngine::process_page`] runs the full pipeline: stream the
+//! document through the parser with the preload scanner ahead of it,
+//! fetch linked stylesheets, load nested frames. Because every fetch goes
+//! through [`NetworkStack`], HSTS upgrades, interception, caching and
+//! devtools capture apply to real browsing, not just to tests. The UI
+//! drives this from [`crate::ui::navigation::Navigator`], which spawns
+//! the future on the tokio runtime and commits the result into a tab.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use std::collections::HashMap;
+
+use crate::js_engine::{modules, JavaScriptEngine, JsRuntime};
+use crate::network::{NetworkError, NetworkStack, Request};
+use crate::renderer::dom::Document;
+use crate::renderer::frame::{FrameLoader, FrameTree};
+use crate::renderer::html::StreamingParser;
+use crate::renderer::loader::{resolve_url, StylesheetLoader};
+use crate::renderer::preload::PreloadScanner;
+use crate::renderer::script::{self, ScriptKind, ScriptSource};
+use crate::renderer::style::{self, StyleEngine};
+use crate::security::SecurityManager;
+use crate::storage::LocalStorage;
+
+/// The long-lived engine state shared by every tab.
+pub struct BrowserEngine {
+    stack: Arc<NetworkStack>,
+    security: Arc<SecurityManager>,
+    local_storage: Arc<LocalStorage>,
+}
+
+/// A finished navigation, ready to be committed into a tab.
+pub struct PageLoad {
+    /// Final URL, after redirects and HSTS upgrades.
+    pub url: String,
+    pub status: u16,
+    pub document: Document,
+    pub styles: StyleEngine,
+    pub frames: FrameTree,
+    /// The document's scripts, fetched and in execution order.
+    pub scripts: Vec<PreparedScript>,
+    /// Module sources by resolved URL, for the runtime's module loader.
+    pub modules: HashMap<String, String>,
+    /// Whether the response opted into cross-origin isolation (COOP
+    /// `same-origin` plus COEP `require-corp`), unlocking shared memory.
+    pub cross_origin_isolated: bool,
+}
+
+/// A script ready to run: external sources fetched, modules keyed into
+/// [`PageLoad::modules`].
+pub struct PreparedScript {
+    /// Resolved URL for external scripts and modules; the document URL
+    /// for inline classics.
+    pub url: String,
+    /// Classic source text; empty for modules, whose source lives in
+    /// the module graph.
+    pub source: String,
+    pub module: bool,
+}
+
+impl BrowserEngine {
+    pub fn new(profile_dir: PathBuf) -> Result<Self, NetworkError> {
+        let security = Arc::new(SecurityManager::new(profile_dir.clone()));
+        let local_storage = Arc::new(LocalStorage::new(profile_dir.join("local_storage")));
+        let stack = Arc::new(NetworkStack::new(profile_dir, Arc::clone(&security))?);
+        Ok(Self {
+            stack,
+            security,
+            local_storage,
+        })
+    }
+
+    pub fn network(&self) -> &Arc<NetworkStack> {
+        &self.stack
+    }
+
+    pub fn security(&self) -> &Arc<SecurityManager> {
+        &self.security
+    }
+
+    pub fn local_storage(&self) -> &Arc<LocalStorage> {
+        &self.local_storage
+    }
+
+    /// Load `url` into a renderable page.
+    ///
+    /// The document streams through the parser as chunks land, with the
+    /// preload scanner warming subresource fetches ahead of it. Once the
+    /// markup is complete, `<link rel="stylesheet">` sheets are fetched
+    /// (imports flattened) and nested frames loaded. Cancellation is the
+    /// caller dropping or aborting the future; every await point is a
+    /// clean stop.
+    pub async fn process_page(&self, url: &str) -> Result<PageLoad, NetworkError> {
+        self.process_request(Request::get(url.to_owned())).await
+    }
+
+    /// Like [`process_page`](Self::process_page), but for a caller-built
+    /// request: form submissions POST their serialized body through the
+    /// same pipeline instead of a query-string-only side path.
+    pub async fn process_request(&self, request: Request) -> Result<PageLoad, NetworkError> {
+        let (head, mut body) = self.stack.fetch_streaming(request).await?;
+        let base_url = head.url.clone();
+        let cross_origin_isolated =
+            header_is(&head.headers, "Cross-Origin-Opener-Policy", "same-origin")
+                && header_is(&head.headers, "Cross-Origin-Embedder-Policy", "require-corp");
+
+        let mut parser = StreamingParser::new();
+        let mut scanner = PreloadScanner::new();
+        // Carry for a UTF-8 sequence split across chunk boundaries.
+        let mut pending: Vec<u8> = Vec::new();
+        while let Some(chunk) = body.next_chunk().await {
+            pending.extend_from_slice(&chunk?);
+            let text = take_utf8_prefix(&mut pending);
+            if text.is_empty() {
+                continue;
+            }
+            scanner.scan_and_fetch(&text, &base_url, &self.stack);
+            parser.feed(&text);
+        }
+        if !pending.is_empty() {
+            // Trailing bytes that never became valid UTF-8.
+            parser.feed(&String::from_utf8_lossy(&pending));
+        }
+        let document = parser.finish();
+        crate::devtools::page::report_document_mode(&base_url, document.quirks_mode());
+
+        let mut styles = StyleEngine::new();
+        style::collect_styles(&document, &mut styles);
+        let loader = StylesheetLoader::new(Arc::clone(&self.stack));
+        for href in stylesheet_links(&document) {
+            let sheet = loader.load(&resolve_url(&base_url, &href)).await;
+            styles.add_stylesheet(sheet);
+        }
+
+        let frames = FrameLoader::new(Arc::clone(&self.stack))
+            .load_frames(&document, &base_url)
+            .await;
+
+        let (scripts, modules) = self.prepare_scripts(&document, &base_url).await;
+
+        Ok(PageLoad {
+            url: base_url,
+            status: head.status,
+            document,
+            styles,
+            frames,
+            scripts,
+            modules,
+            cross_origin_isolated,
+        })
+    }
+
+    /// Fetch the document's scripts into execution order per classic
+    /// semantics: synchronous classics at their parse position (document
+    /// order), `async` ones fetched concurrently and ordered by fetch
+    /// completion, then `defer`red ones in document order, then modules
+    /// (always deferred), each with its whole import graph.
+    /// [`run_scripts`] fires `DOMContentLoaded` once the deferred block
+    /// has run. A script that fails to fetch is dropped — the page runs
+    /// without it, like every other browser.
+    async fn prepare_scripts(
+        &self,
+        document: &Document,
+        base_url: &str,
+    ) -> (Vec<PreparedScript>, HashMap<String, String>) {
+        let mut immediate = Vec::new();
+        // async scripts don't block the synchronous pass: their fetches
+        // run concurrently and they execute in completion order.
+        let mut async_fetches: tokio::task::JoinSet<Option<PreparedScript>> =
+            tokio::task::JoinSet::new();
+        let mut deferred = Vec::new();
+        let mut module_scripts = Vec::new();
+        let mut module_sources: HashMap<String, String> = HashMap::new();
+        let mut inline_modules = 0;
+        for page_script in script::extract_scripts(document) {
+            match (page_script.kind, page_script.source) {
+                (ScriptKind::Classic { defer, .. }, ScriptSource::Inline(source)) => {
+                    let prepared = PreparedScript {
+                        url: base_url.to_owned(),
+                        source,
+                        module: false,
+                    };
+                    if defer {
+                        deferred.push(prepared);
+                    } else {
+                        immediate.push(prepared);
+                    }
+                }
+                (ScriptKind::Classic { defer, r#async }, ScriptSource::External(src)) => {
+                    let url = resolve_url(base_url, &src);
+                    if r#async {
+                        let stack = Arc::clone(&self.stack);
+                        async_fetches.spawn(async move {
+                            let response = stack.fetch(Request::get(url.clone())).await.ok()?;
+                            Some(PreparedScript {
+                                url,
+                                source: response.text(),
+                                module: false,
+                            })
+                        });
+                        continue;
+                    }
+                    let Ok(response) = self.stack.fetch(Request::get(url.clone())).await else {
+                        continue;
+                    };
+                    let prepared = PreparedScript {
+                        url,
+                        source: response.text(),
+                        module: false,
+                    };
+                    if defer {
+                        deferred.push(prepared);
+                    } else {
+                        immediate.push(prepared);
+                    }
+                }
+                (ScriptKind::Module, ScriptSource::External(src)) => {
+                    let url = resolve_url(base_url, &src);
+                    let Ok(graph) = modules::fetch_module_graph(&self.stack, &url).await else {
+                        continue;
+                    };
+                    module_sources.extend(graph);
+                    module_scripts.push(PreparedScript {
+                        url,
+                        source: String::new(),
+                        module: true,
+                    });
+                }
+                (ScriptKind::Module, ScriptSource::Inline(source)) => {
+                    // Inline modules get a synthetic URL; their imports
+                    // still resolve against the document.
+                    inline_modules += 1;
+                    let url = format!("{base_url}#module-{inline_modules}");
+                    for specifier in modules::import_specifiers(&source) {
+                        let import_url = resolve_url(base_url, &specifier);
+                        if let Ok(graph) =
+                            modules::fetch_module_graph(&self.stack, &import_url).await
+                        {
+                            module_sources.extend(graph);
+                        }
+                    }
+                    module_sources.insert(url.clone(), source);
+                    module_scripts.push(PreparedScript {
+                        url,
+                        source: String::new(),
+                        module: true,
+                    });
+                }
+            }
+        }
+        // Completed-fetch order, the closest post-parse serialisation of
+        // "async runs as soon as it arrives".
+        while let Some(finished) = async_fetches.join_next().await {
+            if let Ok(Some(prepared)) = finished {
+                immediate.push(prepared);
+            }
+        }
+        immediate.extend(deferred);
+        immediate.extend(module_scripts);
+        (immediate, module_sources)
+    }
+}
+
+/// Run a committed page's scripts on its runtime, in the order
+/// [`BrowserEngine::process_page`] prepared them. Module sources are
+/// installed first so static and dynamic imports resolve from the
+/// pre-fetched graph. Once the deferred block at the tail has run,
+/// `DOMContentLoaded` fires at the document, per spec.
+pub fn run_scripts(runtime: &mut JsRuntime, page: &PageLoad) {
+    runtime.install_modules(&page.url, page.modules.clone());
+    for script in &page.scripts {
+        crate::js_engine::errors::set_source(&script.url);
+        if script.module {
+            let _ = runtime.execute_module(&script.url);
+        } else {
+            let _ = runtime.execute_cached(&script.url, &script.source);
+        }
+    }
+    let root = crate::js_engine::dom::with_document(|document| document.root());
+    let _ = crate::js_engine::events::dispatch(
+        runtime.context(),
+        root,
+        "DOMContentLoaded",
+        Vec::new(),
+    );
+}
+
+/// The `href`s of the document's `<link rel="stylesheet">` elements, in
+/// document order.
+fn stylesheet_links(document: &Document) -> Vec<String> {
+    document
+        .descendants(document.root())
+        .into_iter()
+        .filter_map(|node| {
+            let element = document.element(node)?;
+            if element.tag_name != "link" {
+                return None;
+            }
+            let is_stylesheet = element
+                .attr("rel")?
+                .split_whitespace()
+                .any(|token| token.eq_ignore_ascii_case("stylesheet"));
+            if !is_stylesheet {
+                return None;
+            }
+            element.attr("href").map(str::to_owned)
+        })
+        .collect()
+}
+
+/// Whether `headers` carries `name` with exactly `value`, ignoring
+/// surrounding whitespace and case.
+fn header_is(headers: &crate::network::Headers, name: &str, value: &str) -> bool {
+    headers
+        .get(name)
+        .is_some_and(|header| header.trim().eq_ignore_ascii_case(value))
+}
+
+/// Split the longest valid UTF-8 prefix out of `buffer`, leaving any
+/// trailing partial sequence for the next chunk.
+fn take_utf8_prefix(buffer: &mut Vec<u8>) -> String {
+    match std::str::from_utf8(buffer) {
+        Ok(_) => String::from_utf8(std::mem::take(buffer)).unwrap_or_default(),
+        Err(error) => {
+            let rest = buffer.split_off(error.valid_up_to());
+            let prefix = std::mem::replace(buffer, rest);
+            String::from_utf8(prefix).unwrap_or_default()
+        }
+    }
+}
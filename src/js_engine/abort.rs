@@ -0,0 +1,266 @@
+//! `AbortController` and `AbortSignal`.
+//!
+//! Signal state lives in a thread-local registry keyed by `__signalId`,
+//! like the other bindings; the wrapper object is kept alongside so
+//! aborting can sync its `aborted`/`reason` snapshot and fire its
+//! `onabort` handler and `abort` listeners. Sibling bindings check
+//! signals through [`signal_id`]/[`is_aborted`]: fetch rejects pending
+//! requests whose signal fired, and event listeners registered with
+//! `{ signal }` drop out when it aborts. `AbortSignal.timeout()` runs
+//! off this module's own due-list, pumped each tick like the timer
+//! queue.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use boa_engine::{
+    js_string, Context, JsArgs, JsObject, JsResult, JsString, JsValue, NativeFunction,
+};
+
+struct SignalState {
+    object: JsObject,
+    aborted: bool,
+    reason: JsValue,
+    /// `addEventListener("abort", …)` callbacks.
+    listeners: Vec<JsObject>,
+}
+
+thread_local! {
+    static SIGNALS: RefCell<HashMap<u64, SignalState>> = RefCell::new(HashMap::new());
+    static NEXT_ID: RefCell<u64> = const { RefCell::new(1) };
+    // (deadline, signal) pairs from AbortSignal.timeout().
+    static TIMEOUTS: RefCell<Vec<(Instant, u64)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Install the `AbortController` constructor and the `AbortSignal`
+/// statics.
+pub fn register(context: &mut Context) {
+    context
+        .register_global_callable(
+            js_string!("AbortController"),
+            0,
+            NativeFunction::from_fn_ptr(construct_controller),
+        )
+        .expect("registering AbortController");
+    let signal_statics = JsObject::with_null_proto();
+    method(&signal_statics, "abort", signal_abort_static, context)
+        .expect("installing AbortSignal.abort");
+    method(&signal_statics, "timeout", signal_timeout, context)
+        .expect("installing AbortSignal.timeout");
+    context
+        .register_global_property(
+            js_string!("AbortSignal"),
+            signal_statics,
+            boa_engine::property::Attribute::all(),
+        )
+        .expect("registering AbortSignal");
+}
+
+/// Drop every signal (navigation replaced the page).
+pub fn clear() {
+    SIGNALS.with(|signals| signals.borrow_mut().clear());
+    TIMEOUTS.with(|timeouts| timeouts.borrow_mut().clear());
+}
+
+fn construct_controller(
+    _this: &JsValue,
+    _args: &[JsValue],
+    context: &mut Context,
+) -> JsResult<JsValue> {
+    let signal = new_signal(context)?;
+    let controller = JsObject::with_null_proto();
+    controller.set(js_string!("signal"), signal, false, context)?;
+    method(&controller, "abort", controller_abort, context)?;
+    Ok(controller.into())
+}
+
+/// `AbortSignal.abort(reason)`: an already-aborted signal.
+fn signal_abort_static(
+    _this: &JsValue,
+    args: &[JsValue],
+    context: &mut Context,
+) -> JsResult<JsValue> {
+    let signal = new_signal(context)?;
+    let id = signal_id(&signal.clone().into(), context).expect("fresh signal has an id");
+    fire(id, args.get_or_undefined(0).clone(), context);
+    Ok(signal.into())
+}
+
+/// `AbortSignal.timeout(ms)`: aborts with a timeout reason once due.
+fn signal_timeout(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let millis = args.get_or_undefined(0).to_number(context)?.max(0.0);
+    let signal = new_signal(context)?;
+    let id = signal_id(&signal.clone().into(), context).expect("fresh signal has an id");
+    let due = Instant::now() + Duration::from_millis(millis as u64);
+    TIMEOUTS.with(|timeouts| timeouts.borrow_mut().push((due, id)));
+    Ok(signal.into())
+}
+
+fn controller_abort(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let signal = this
+        .as_object()
+        .map(|o| o.get(js_string!("signal"), context))
+        .transpose()?
+        .unwrap_or_default();
+    if let Some(id) = signal_id(&signal, context) {
+        fire(id, args.get_or_undefined(0).clone(), context);
+    }
+    Ok(JsValue::undefined())
+}
+
+/// Fire due `AbortSignal.timeout()` signals. Driven from
+/// [`JsRuntime::pump`](super::JsRuntime::pump).
+pub fn pump(context: &mut Context) {
+    let now = Instant::now();
+    let due: Vec<u64> = TIMEOUTS.with(|timeouts| {
+        let mut timeouts = timeouts.borrow_mut();
+        let (ready, waiting): (Vec<_>, Vec<_>) =
+            timeouts.drain(..).partition(|(deadline, _)| *deadline <= now);
+        *timeouts = waiting;
+        ready.into_iter().map(|(_, id)| id).collect()
+    });
+    for id in due {
+        let reason: JsValue = JsString::from("TimeoutError: signal timed out").into();
+        fire(id, reason, context);
+        context.run_jobs();
+    }
+}
+
+/// The registry id behind a signal wrapper, for sibling bindings.
+pub(crate) fn signal_id(value: &JsValue, context: &mut Context) -> Option<u64> {
+    let object = value.as_object()?;
+    let id = object.get(js_string!("__signalId"), context).ok()?;
+    if id.is_undefined() {
+        return None;
+    }
+    Some(id.to_number(context).ok()? as u64)
+}
+
+/// Whether the signal behind `id` has aborted.
+pub(crate) fn is_aborted(id: u64) -> bool {
+    SIGNALS.with(|signals| {
+        signals
+            .borrow()
+            .get(&id)
+            .map_or(true, |state| state.aborted)
+    })
+}
+
+fn new_signal(context: &mut Context) -> JsResult<JsObject> {
+    let id = NEXT_ID.with(|next| {
+        let mut next = next.borrow_mut();
+        let id = *next;
+        *next += 1;
+        id
+    });
+    let object = JsObject::with_null_proto();
+    object.set(js_string!("__signalId"), id, false, context)?;
+    object.set(js_string!("aborted"), false, false, context)?;
+    object.set(js_string!("reason"), JsValue::undefined(), false, context)?;
+    method(&object, "addEventListener", signal_add_listener, context)?;
+    method(&object, "throwIfAborted", throw_if_aborted, context)?;
+    SIGNALS.with(|signals| {
+        signals.borrow_mut().insert(
+            id,
+            SignalState {
+                object: object.clone(),
+                aborted: false,
+                reason: JsValue::undefined(),
+                listeners: Vec::new(),
+            },
+        );
+    });
+    Ok(object)
+}
+
+fn signal_add_listener(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let event_type = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    if event_type != "abort" {
+        return Ok(JsValue::undefined());
+    }
+    let Some(callback) = args.get_or_undefined(1).as_object().cloned() else {
+        return Ok(JsValue::undefined());
+    };
+    let Some(id) = signal_id(this, context) else {
+        return Ok(JsValue::undefined());
+    };
+    SIGNALS.with(|signals| {
+        if let Some(state) = signals.borrow_mut().get_mut(&id) {
+            state.listeners.push(callback);
+        }
+    });
+    Ok(JsValue::undefined())
+}
+
+fn throw_if_aborted(this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let Some(id) = signal_id(this, context) else {
+        return Ok(JsValue::undefined());
+    };
+    let reason = SIGNALS.with(|signals| {
+        signals
+            .borrow()
+            .get(&id)
+            .filter(|state| state.aborted)
+            .map(|state| state.reason.clone())
+    });
+    match reason {
+        Some(reason) => Err(boa_engine::JsError::from_opaque(reason)),
+        None => Ok(JsValue::undefined()),
+    }
+}
+
+/// Abort the signal behind `id`: record the reason, sync the wrapper,
+/// and fire its handlers. Idempotent, per spec.
+fn fire(id: u64, reason: JsValue, context: &mut Context) {
+    let reason = if reason.is_undefined() {
+        JsString::from("AbortError: the operation was aborted").into()
+    } else {
+        reason
+    };
+    let fired = SIGNALS.with(|signals| {
+        let mut signals = signals.borrow_mut();
+        let Some(state) = signals.get_mut(&id) else {
+            return None;
+        };
+        if state.aborted {
+            return None;
+        }
+        state.aborted = true;
+        state.reason = reason.clone();
+        Some((state.object.clone(), std::mem::take(&mut state.listeners)))
+    });
+    let Some((object, listeners)) = fired else {
+        return;
+    };
+    let _ = object.set(js_string!("aborted"), true, false, context);
+    let _ = object.set(js_string!("reason"), reason, false, context);
+    let event = JsObject::with_null_proto();
+    let _ = event.set(js_string!("type"), js_string!("abort"), false, context);
+    let _ = event.set(js_string!("target"), object.clone(), false, context);
+    let event: JsValue = event.into();
+    if let Ok(handler) = object.get(js_string!("onabort"), context) {
+        if let Some(handler) = handler.as_callable() {
+            let _ = handler.call(&object.clone().into(), &[event.clone()], context);
+        }
+    }
+    for listener in listeners {
+        let _ = listener.call(&object.clone().into(), &[event.clone()], context);
+    }
+}
+
+/// Install a native method on `object`.
+fn method(
+    object: &JsObject,
+    name: &str,
+    function: fn(&JsValue, &[JsValue], &mut Context) -> JsResult<JsValue>,
+    context: &mut Context,
+) -> JsResult<()> {
+    object.set(
+        JsString::from(name),
+        NativeFunction::from_fn_ptr(function).to_js_function(context.realm()),
+        false,
+        context,
+    )?;
+    Ok(())
+}
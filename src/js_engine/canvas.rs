@@ -0,0 +1,310 @@
+//! Canvas 2D binding over [`crate::renderer::canvas`].
+//!
+//! Exposes `OffscreenCanvas` with a `getContext("2d")` method; DOM element
+//! bindings reuse [`create_context`] to hand `<canvas>` elements the same
+//! context objects. Each context records into a [`Canvas2D`] kept in a
+//! thread-local registry; the compositor drains recorded commands per
+//! frame through [`drain_commands`].
+//!
+//! Style attributes (`fillStyle`, `strokeStyle`, `lineWidth`, `font`,
+//! `globalAlpha`) are plain JS properties, synced into the recorder at
+//! every draw call and written back on `restore()`, rather than accessor
+//! pairs.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use boa_engine::{
+    js_string, Context, JsArgs, JsObject, JsResult, JsString, JsValue, NativeFunction,
+};
+
+use crate::renderer::canvas::{Canvas2D, DrawCommand};
+use crate::renderer::transform::Transform2D;
+
+thread_local! {
+    static CANVASES: RefCell<HashMap<u64, Canvas2D>> = RefCell::new(HashMap::new());
+    static NEXT_ID: RefCell<u64> = const { RefCell::new(1) };
+}
+
+/// Install the `OffscreenCanvas` constructor on the global object.
+pub fn register(context: &mut Context) {
+    let constructor = NativeFunction::from_fn_ptr(construct_canvas);
+    context
+        .register_global_callable(js_string!("OffscreenCanvas"), 2, constructor)
+        .expect("registering OffscreenCanvas");
+}
+
+/// Drain the commands every canvas recorded since the last frame.
+pub fn drain_commands() -> Vec<(u64, Vec<DrawCommand>)> {
+    CANVASES.with(|canvases| {
+        canvases
+            .borrow_mut()
+            .iter_mut()
+            .map(|(&id, canvas)| (id, canvas.take_commands()))
+            .filter(|(_, commands)| !commands.is_empty())
+            .collect()
+    })
+}
+
+fn construct_canvas(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let width = args.get_or_undefined(0).to_number(context)? as f32;
+    let height = args.get_or_undefined(1).to_number(context)? as f32;
+    let object = JsObject::with_null_proto();
+    object.set(js_string!("width"), width, false, context)?;
+    object.set(js_string!("height"), height, false, context)?;
+    method(&object, "getContext", get_context, context)?;
+    Ok(object.into())
+}
+
+fn get_context(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let kind = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    if kind != "2d" {
+        return Ok(JsValue::null());
+    }
+    let Some(canvas) = this.as_object() else {
+        return Ok(JsValue::null());
+    };
+    // One context per canvas, created on first request.
+    let cached = canvas.get(js_string!("__context2d"), context)?;
+    if cached.is_object() {
+        return Ok(cached);
+    }
+    let width = canvas.get(js_string!("width"), context)?.to_number(context)? as f32;
+    let height = canvas.get(js_string!("height"), context)?.to_number(context)? as f32;
+    let ctx = create_context(width, height, context)?;
+    canvas.set(js_string!("__context2d"), ctx.clone(), false, context)?;
+    ctx.set(js_string!("canvas"), this.clone(), false, context)?;
+    Ok(ctx.into())
+}
+
+/// Build a `CanvasRenderingContext2D`-shaped object backed by a fresh
+/// recorder. Also used by the DOM bindings for `<canvas>` elements.
+pub fn create_context(width: f32, height: f32, context: &mut Context) -> JsResult<JsObject> {
+    let id = NEXT_ID.with(|n| {
+        let mut n = n.borrow_mut();
+        let id = *n;
+        *n += 1;
+        id
+    });
+    CANVASES.with(|canvases| {
+        canvases.borrow_mut().insert(id, Canvas2D::new(width, height));
+    });
+
+    let object = JsObject::with_null_proto();
+    object.set(js_string!("__canvasId"), id, false, context)?;
+    object.set(js_string!("fillStyle"), js_string!("#000000"), false, context)?;
+    object.set(js_string!("strokeStyle"), js_string!("#000000"), false, context)?;
+    object.set(js_string!("lineWidth"), 1.0, false, context)?;
+    object.set(js_string!("font"), js_string!("10px sans-serif"), false, context)?;
+    object.set(js_string!("globalAlpha"), 1.0, false, context)?;
+
+    method(&object, "beginPath", |this, _, context| {
+        with_canvas(this, context, |canvas| canvas.begin_path())
+    }, context)?;
+    method(&object, "closePath", |this, _, context| {
+        with_canvas(this, context, |canvas| canvas.close_path())
+    }, context)?;
+    method(&object, "moveTo", |this, args, context| {
+        let (x, y) = (number(args, 0, context)?, number(args, 1, context)?);
+        with_canvas(this, context, |canvas| canvas.move_to(x, y))
+    }, context)?;
+    method(&object, "lineTo", |this, args, context| {
+        let (x, y) = (number(args, 0, context)?, number(args, 1, context)?);
+        with_canvas(this, context, |canvas| canvas.line_to(x, y))
+    }, context)?;
+    method(&object, "bezierCurveTo", |this, args, context| {
+        let n: Vec<f32> = numbers(args, 6, context)?;
+        with_canvas(this, context, |canvas| {
+            canvas.bezier_curve_to(n[0], n[1], n[2], n[3], n[4], n[5]);
+        })
+    }, context)?;
+    method(&object, "quadraticCurveTo", |this, args, context| {
+        let n = numbers(args, 4, context)?;
+        with_canvas(this, context, |canvas| {
+            canvas.quadratic_curve_to(n[0], n[1], n[2], n[3]);
+        })
+    }, context)?;
+    method(&object, "rect", |this, args, context| {
+        let n = numbers(args, 4, context)?;
+        with_canvas(this, context, |canvas| canvas.rect(n[0], n[1], n[2], n[3]))
+    }, context)?;
+    method(&object, "arc", |this, args, context| {
+        let n = numbers(args, 5, context)?;
+        let anticlockwise = args.get_or_undefined(5).to_boolean();
+        with_canvas(this, context, |canvas| {
+            canvas.arc(n[0], n[1], n[2], n[3], n[4], anticlockwise);
+        })
+    }, context)?;
+    method(&object, "fill", |this, _, context| {
+        sync_state(this, context)?;
+        with_canvas(this, context, |canvas| canvas.fill())
+    }, context)?;
+    method(&object, "stroke", |this, _, context| {
+        sync_state(this, context)?;
+        with_canvas(this, context, |canvas| canvas.stroke())
+    }, context)?;
+    method(&object, "fillRect", |this, args, context| {
+        sync_state(this, context)?;
+        let n = numbers(args, 4, context)?;
+        with_canvas(this, context, |canvas| canvas.fill_rect(n[0], n[1], n[2], n[3]))
+    }, context)?;
+    method(&object, "strokeRect", |this, args, context| {
+        sync_state(this, context)?;
+        let n = numbers(args, 4, context)?;
+        with_canvas(this, context, |canvas| canvas.stroke_rect(n[0], n[1], n[2], n[3]))
+    }, context)?;
+    method(&object, "clearRect", |this, args, context| {
+        let n = numbers(args, 4, context)?;
+        with_canvas(this, context, |canvas| canvas.clear_rect(n[0], n[1], n[2], n[3]))
+    }, context)?;
+    method(&object, "fillText", |this, args, context| {
+        sync_state(this, context)?;
+        let text = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+        let (x, y) = (number(args, 1, context)?, number(args, 2, context)?);
+        with_canvas(this, context, |canvas| canvas.fill_text(&text, x, y))
+    }, context)?;
+    method(&object, "drawImage", |this, args, context| {
+        sync_state(this, context)?;
+        // The image argument is anything with a `src`; width/height
+        // default to the destination size already given.
+        let src = match args.get(0).and_then(JsValue::as_object) {
+            Some(image) => image
+                .get(js_string!("src"), context)?
+                .to_string(context)?
+                .to_std_string_escaped(),
+            None => return Ok(JsValue::undefined()),
+        };
+        let (x, y) = (number(args, 1, context)?, number(args, 2, context)?);
+        let width = number(args, 3, context).unwrap_or(0.0);
+        let height = number(args, 4, context).unwrap_or(0.0);
+        with_canvas(this, context, |canvas| {
+            canvas.draw_image(&src, x, y, width, height);
+        })
+    }, context)?;
+    method(&object, "save", |this, _, context| {
+        sync_state(this, context)?;
+        with_canvas(this, context, |canvas| canvas.save())
+    }, context)?;
+    method(&object, "restore", |this, _, context| {
+        with_canvas(this, context, |canvas| canvas.restore())?;
+        write_back_state(this, context)
+    }, context)?;
+    method(&object, "translate", |this, args, context| {
+        let (x, y) = (number(args, 0, context)?, number(args, 1, context)?);
+        with_canvas(this, context, |canvas| canvas.translate(x, y))
+    }, context)?;
+    method(&object, "scale", |this, args, context| {
+        let (x, y) = (number(args, 0, context)?, number(args, 1, context)?);
+        with_canvas(this, context, |canvas| canvas.scale(x, y))
+    }, context)?;
+    method(&object, "rotate", |this, args, context| {
+        let radians = number(args, 0, context)?;
+        with_canvas(this, context, |canvas| canvas.rotate(radians))
+    }, context)?;
+    method(&object, "setTransform", |this, args, context| {
+        let n = numbers(args, 6, context)?;
+        with_canvas(this, context, |canvas| {
+            canvas.set_transform(Transform2D {
+                m: [n[0], n[1], n[2], n[3], n[4], n[5]],
+            });
+        })
+    }, context)?;
+    method(&object, "resetTransform", |this, _, context| {
+        with_canvas(this, context, |canvas| canvas.reset_transform())
+    }, context)?;
+
+    Ok(object)
+}
+
+/// Install a native method on `object`.
+fn method(
+    object: &JsObject,
+    name: &str,
+    function: fn(&JsValue, &[JsValue], &mut Context) -> JsResult<JsValue>,
+    context: &mut Context,
+) -> JsResult<()> {
+    object.set(
+        JsString::from(name),
+        NativeFunction::from_fn_ptr(function).to_js_function(context.realm()),
+        false,
+        context,
+    )?;
+    Ok(())
+}
+
+fn number(args: &[JsValue], index: usize, context: &mut Context) -> JsResult<f32> {
+    Ok(args.get_or_undefined(index).to_number(context)? as f32)
+}
+
+fn numbers(args: &[JsValue], count: usize, context: &mut Context) -> JsResult<Vec<f32>> {
+    (0..count).map(|index| number(args, index, context)).collect()
+}
+
+/// Run `apply` against the recorder behind `this`.
+fn with_canvas(
+    this: &JsValue,
+    context: &mut Context,
+    apply: impl FnOnce(&mut Canvas2D),
+) -> JsResult<JsValue> {
+    let id = canvas_id(this, context)?;
+    CANVASES.with(|canvases| {
+        if let Some(canvas) = canvases.borrow_mut().get_mut(&id) {
+            apply(canvas);
+        }
+    });
+    Ok(JsValue::undefined())
+}
+
+fn canvas_id(this: &JsValue, context: &mut Context) -> JsResult<u64> {
+    let id = this
+        .as_object()
+        .map(|o| o.get(js_string!("__canvasId"), context))
+        .transpose()?
+        .unwrap_or_default();
+    Ok(id.to_number(context)? as u64)
+}
+
+/// Copy the style properties from the JS object into the recorder. Called
+/// before every draw so plain property assignment behaves like the spec's
+/// accessors.
+fn sync_state(this: &JsValue, context: &mut Context) -> JsResult<()> {
+    let Some(object) = this.as_object() else {
+        return Ok(());
+    };
+    let id = canvas_id(this, context)?;
+    let fill = object.get(js_string!("fillStyle"), context)?.to_string(context)?;
+    let stroke = object.get(js_string!("strokeStyle"), context)?.to_string(context)?;
+    let line_width = object.get(js_string!("lineWidth"), context)?.to_number(context)? as f32;
+    let font = object.get(js_string!("font"), context)?.to_string(context)?;
+    let alpha = object.get(js_string!("globalAlpha"), context)?.to_number(context)? as f32;
+    CANVASES.with(|canvases| {
+        if let Some(canvas) = canvases.borrow_mut().get_mut(&id) {
+            canvas.set_fill_style(&fill.to_std_string_escaped());
+            canvas.set_stroke_style(&stroke.to_std_string_escaped());
+            canvas.set_line_width(line_width);
+            canvas.set_font(&font.to_std_string_escaped());
+            canvas.set_global_alpha(alpha);
+        }
+    });
+    Ok(())
+}
+
+/// After `restore()`, reflect the recorder's state back onto the JS
+/// properties so scripts read the restored values.
+fn write_back_state(this: &JsValue, context: &mut Context) -> JsResult<JsValue> {
+    let Some(object) = this.as_object() else {
+        return Ok(JsValue::undefined());
+    };
+    let id = canvas_id(this, context)?;
+    let state = CANVASES.with(|canvases| {
+        canvases.borrow().get(&id).map(|canvas| canvas.style_snapshot())
+    });
+    if let Some((fill, stroke, line_width, font, alpha)) = state {
+        object.set(js_string!("fillStyle"), JsString::from(fill), false, context)?;
+        object.set(js_string!("strokeStyle"), JsString::from(stroke), false, context)?;
+        object.set(js_string!("lineWidth"), line_width, false, context)?;
+        object.set(js_string!("font"), JsString::from(font), false, context)?;
+        object.set(js_string!("globalAlpha"), alpha, false, context)?;
+    }
+    Ok(JsValue::undefined())
+}
@@ -0,0 +1,280 @@
+//! The structured clone algorithm, plus the `structuredClone` global.
+//!
+//! [`CloneValue`] is an engine-neutral snapshot that can cross threads
+//! and contexts: primitives, arrays, plain objects, `Map`/`Set`,
+//! `ArrayBuffer` and the typed array views. Functions and platform
+//! objects don't clone, per spec; they become `Undefined` (a real
+//! browser throws `DataCloneError` — we degrade instead, matching the
+//! permissive tone of the other bindings). `ArrayBuffer`s named in a
+//! transfer list are detached in the source context after snapshotting,
+//! so transfer semantics hold even though the bytes are copied.
+
+use std::cell::RefCell;
+
+use boa_engine::object::builtins::{JsArray, JsArrayBuffer};
+use boa_engine::{js_string, Context, JsArgs, JsObject, JsResult, JsString, JsValue, NativeFunction};
+
+/// A structured-clone value, detached from any JS context.
+#[derive(Debug, Clone)]
+pub enum CloneValue {
+    Undefined,
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<CloneValue>),
+    Object(Vec<(String, CloneValue)>),
+    Map(Vec<(CloneValue, CloneValue)>),
+    Set(Vec<CloneValue>),
+    ArrayBuffer(Vec<u8>),
+    /// A typed array view: constructor name plus its elements as
+    /// numbers, rebuilt through the same constructor on arrival.
+    TypedArray {
+        kind: &'static str,
+        elements: Vec<f64>,
+    },
+}
+
+/// The typed array constructors the clone recognises.
+const TYPED_ARRAYS: &[&str] = &[
+    "Int8Array",
+    "Uint8Array",
+    "Uint8ClampedArray",
+    "Int16Array",
+    "Uint16Array",
+    "Int32Array",
+    "Uint32Array",
+    "Float32Array",
+    "Float64Array",
+];
+
+/// Install the `structuredClone` global.
+pub fn register(context: &mut Context) {
+    context
+        .register_global_callable(
+            js_string!("structuredClone"),
+            1,
+            NativeFunction::from_fn_ptr(structured_clone),
+        )
+        .expect("registering structuredClone");
+}
+
+/// `structuredClone(value, { transfer })`: snapshot and rebuild in the
+/// same context, detaching transferred buffers.
+fn structured_clone(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let snapshot = to_clone(args.get_or_undefined(0), context)?;
+    if let Some(options) = args.get_or_undefined(1).as_object() {
+        let transfer = options.get(js_string!("transfer"), context)?;
+        detach_transferred(&transfer, context)?;
+    }
+    from_clone(&snapshot, context)
+}
+
+/// Detach every `ArrayBuffer` in a transfer list (an array value).
+pub(crate) fn detach_transferred(list: &JsValue, context: &mut Context) -> JsResult<()> {
+    let Some(list) = list.as_object() else {
+        return Ok(());
+    };
+    let length = list.get(js_string!("length"), context)?.to_number(context)? as usize;
+    for index in 0..length {
+        let item = list.get(index, context)?;
+        let Some(object) = item.as_object() else {
+            continue;
+        };
+        if let Ok(buffer) = JsArrayBuffer::from_object(object.clone()) {
+            let _ = buffer.detach(&JsValue::undefined());
+        }
+    }
+    Ok(())
+}
+
+/// Structured-clone a JS value for transfer. Callables and unsupported
+/// exotica clone to `Undefined`.
+pub(crate) fn to_clone(value: &JsValue, context: &mut Context) -> JsResult<CloneValue> {
+    Ok(match value {
+        JsValue::Undefined => CloneValue::Undefined,
+        JsValue::Null => CloneValue::Null,
+        JsValue::Boolean(b) => CloneValue::Bool(*b),
+        JsValue::Integer(i) => CloneValue::Number(f64::from(*i)),
+        JsValue::Rational(r) => CloneValue::Number(*r),
+        JsValue::String(s) => CloneValue::String(s.to_std_string_escaped()),
+        JsValue::Object(object) if object.is_callable() => CloneValue::Undefined,
+        JsValue::Object(object) if is_instance_of(object, "Map", context)? => {
+            let mut entries = Vec::new();
+            for (key, value) in collect_pairs(object, context)? {
+                entries.push((to_clone(&key, context)?, to_clone(&value, context)?));
+            }
+            CloneValue::Map(entries)
+        }
+        JsValue::Object(object) if is_instance_of(object, "Set", context)? => {
+            let mut items = Vec::new();
+            for (_, value) in collect_pairs(object, context)? {
+                items.push(to_clone(&value, context)?);
+            }
+            CloneValue::Set(items)
+        }
+        JsValue::Object(object) if is_instance_of(object, "ArrayBuffer", context)? => {
+            CloneValue::ArrayBuffer(buffer_bytes(object, context)?)
+        }
+        JsValue::Object(object) => {
+            if let Some(kind) = typed_array_kind(object, context)? {
+                let length =
+                    object.get(js_string!("length"), context)?.to_number(context)? as usize;
+                let mut elements = Vec::with_capacity(length);
+                for index in 0..length {
+                    elements.push(object.get(index, context)?.to_number(context)?);
+                }
+                return Ok(CloneValue::TypedArray { kind, elements });
+            }
+            if object.is_array() {
+                let length =
+                    object.get(js_string!("length"), context)?.to_number(context)? as usize;
+                let mut items = Vec::with_capacity(length);
+                for index in 0..length {
+                    let item = object.get(index, context)?;
+                    items.push(to_clone(&item, context)?);
+                }
+                return Ok(CloneValue::Array(items));
+            }
+            let mut entries = Vec::new();
+            for key in object.own_property_keys(context)? {
+                let item = object.get(key.clone(), context)?;
+                entries.push((key.to_string(), to_clone(&item, context)?));
+            }
+            CloneValue::Object(entries)
+        }
+        _ => CloneValue::Undefined,
+    })
+}
+
+/// Rebuild a transferred value in `context`.
+pub(crate) fn from_clone(value: &CloneValue, context: &mut Context) -> JsResult<JsValue> {
+    Ok(match value {
+        CloneValue::Undefined => JsValue::undefined(),
+        CloneValue::Null => JsValue::null(),
+        CloneValue::Bool(b) => (*b).into(),
+        CloneValue::Number(n) => (*n).into(),
+        CloneValue::String(s) => JsString::from(s.as_str()).into(),
+        CloneValue::Array(items) => {
+            let array = JsArray::new(context);
+            for item in items {
+                let value = from_clone(item, context)?;
+                array.push(value, context)?;
+            }
+            array.into()
+        }
+        CloneValue::Object(entries) => {
+            let object = JsObject::with_null_proto();
+            for (key, item) in entries {
+                let value = from_clone(item, context)?;
+                object.set(JsString::from(key.as_str()), value, false, context)?;
+            }
+            object.into()
+        }
+        CloneValue::Map(entries) => {
+            let map = construct_global("Map", &[], context)?;
+            let set = map.get(js_string!("set"), context)?;
+            for (key, item) in entries {
+                let key = from_clone(key, context)?;
+                let item = from_clone(item, context)?;
+                if let Some(set) = set.as_callable() {
+                    set.call(&map.clone().into(), &[key, item], context)?;
+                }
+            }
+            map.into()
+        }
+        CloneValue::Set(items) => {
+            let set = construct_global("Set", &[], context)?;
+            let add = set.get(js_string!("add"), context)?;
+            for item in items {
+                let item = from_clone(item, context)?;
+                if let Some(add) = add.as_callable() {
+                    add.call(&set.clone().into(), &[item], context)?;
+                }
+            }
+            set.into()
+        }
+        CloneValue::ArrayBuffer(bytes) => JsArrayBuffer::from_byte_block(bytes.clone(), context)?
+            .into(),
+        CloneValue::TypedArray { kind, elements } => {
+            let array = construct_global(kind, &[(elements.len() as f64).into()], context)?;
+            for (index, element) in elements.iter().enumerate() {
+                array.set(index, *element, false, context)?;
+            }
+            array.into()
+        }
+    })
+}
+
+/// Whether `object`'s prototype is `<name>.prototype` of this realm.
+fn is_instance_of(object: &JsObject, name: &str, context: &mut Context) -> JsResult<bool> {
+    let Some(proto) = object.prototype() else {
+        return Ok(false);
+    };
+    let constructor = context.global_object().get(JsString::from(name), context)?;
+    let Some(constructor) = constructor.as_object() else {
+        return Ok(false);
+    };
+    let expected = constructor.get(js_string!("prototype"), context)?;
+    Ok(expected
+        .as_object()
+        .map_or(false, |expected| JsObject::equals(&proto, expected)))
+}
+
+/// The typed array constructor name `object` was built with, if any.
+fn typed_array_kind(object: &JsObject, context: &mut Context) -> JsResult<Option<&'static str>> {
+    for kind in TYPED_ARRAYS {
+        if is_instance_of(object, kind, context)? {
+            return Ok(Some(kind));
+        }
+    }
+    Ok(None)
+}
+
+/// The bytes of an `ArrayBuffer`, read through a `Uint8Array` view so
+/// the buffer stays attached.
+fn buffer_bytes(buffer: &JsObject, context: &mut Context) -> JsResult<Vec<u8>> {
+    let view = construct_global("Uint8Array", &[buffer.clone().into()], context)?;
+    let length = view.get(js_string!("length"), context)?.to_number(context)? as usize;
+    let mut bytes = Vec::with_capacity(length);
+    for index in 0..length {
+        bytes.push(view.get(index, context)?.to_number(context)? as u8);
+    }
+    Ok(bytes)
+}
+
+thread_local! {
+    // Scratch for collect_pairs: the forEach callback is a plain fn
+    // pointer, so it hands pairs over through here.
+    static PAIRS: RefCell<Vec<(JsValue, JsValue)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Collect `(key, value)` pairs out of a `Map` or `Set` via its own
+/// `forEach` (which calls back with `(value, key)`).
+fn collect_pairs(object: &JsObject, context: &mut Context) -> JsResult<Vec<(JsValue, JsValue)>> {
+    let for_each = object.get(js_string!("forEach"), context)?;
+    let Some(for_each) = for_each.as_callable() else {
+        return Ok(Vec::new());
+    };
+    let collector = NativeFunction::from_fn_ptr(|_this, args, _context| {
+        let value = args.get_or_undefined(0).clone();
+        let key = args.get_or_undefined(1).clone();
+        PAIRS.with(|pairs| pairs.borrow_mut().push((key, value)));
+        Ok(JsValue::undefined())
+    })
+    .to_js_function(context.realm());
+    PAIRS.with(|pairs| pairs.borrow_mut().clear());
+    for_each.call(&object.clone().into(), &[collector.into()], context)?;
+    Ok(PAIRS.with(|pairs| std::mem::take(&mut *pairs.borrow_mut())))
+}
+
+/// Construct the global constructor `name` with `args`.
+fn construct_global(name: &str, args: &[JsValue], context: &mut Context) -> JsResult<JsObject> {
+    let constructor = context.global_object().get(JsString::from(name), context)?;
+    let Some(constructor) = constructor.as_object().filter(|o| o.is_constructor()) else {
+        return Err(boa_engine::JsNativeError::typ()
+            .with_message(format!("{name} is not constructible"))
+            .into());
+    };
+    constructor.construct(args, Some(constructor), context)
+}
@@ -0,0 +1,92 @@
+//! `console` binding, streaming into [`crate::devtools::console`].
+//!
+//! Arguments are rendered eagerly — strings as-is, everything else
+//! through its display form — because the devtools panel shows text,
+//! not live object graphs. Each call also records the innermost script
+//! frame as a best-effort source location.
+
+use boa_engine::{js_string, Context, JsObject, JsResult, JsString, JsValue, NativeFunction};
+
+use crate::devtools::console::{ConsoleLevel, ConsoleMessage};
+
+/// Install the `console` global.
+pub fn register(context: &mut Context) {
+    let object = JsObject::with_null_proto();
+    for (name, function) in [
+        ("debug", debug as NativeCall),
+        ("log", log),
+        ("info", info),
+        ("warn", warn),
+        ("error", error),
+    ] {
+        object
+            .set(
+                JsString::from(name),
+                NativeFunction::from_fn_ptr(function).to_js_function(context.realm()),
+                false,
+                context,
+            )
+            .expect("installing console method");
+    }
+    context
+        .register_global_property(
+            js_string!("console"),
+            object,
+            boa_engine::property::Attribute::all(),
+        )
+        .expect("registering console global");
+}
+
+type NativeCall = fn(&JsValue, &[JsValue], &mut Context) -> JsResult<JsValue>;
+
+fn debug(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    report(ConsoleLevel::Debug, args, context)
+}
+
+fn log(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    report(ConsoleLevel::Log, args, context)
+}
+
+fn info(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    report(ConsoleLevel::Info, args, context)
+}
+
+fn warn(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    report(ConsoleLevel::Warn, args, context)
+}
+
+fn error(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    report(ConsoleLevel::Error, args, context)
+}
+
+fn report(level: ConsoleLevel, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let text = args
+        .iter()
+        .map(|value| format_value(value))
+        .collect::<Vec<_>>()
+        .join(" ");
+    crate::devtools::console::report(ConsoleMessage {
+        level,
+        text,
+        location: location(context),
+    });
+    Ok(JsValue::undefined())
+}
+
+/// A value the way `console.log` shows it: strings bare, everything
+/// else through its display form.
+fn format_value(value: &JsValue) -> String {
+    match value {
+        JsValue::String(s) => s.to_std_string_escaped(),
+        other => other.display().to_string(),
+    }
+}
+
+/// The innermost named script frame, when the engine exposes one.
+fn location(context: &mut Context) -> Option<String> {
+    context
+        .stack_trace()
+        .next()
+        .map(|frame| frame.code_block().name().to_std_string_escaped())
+        .filter(|name| !name.is_empty())
+}
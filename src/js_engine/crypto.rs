@@ -0,0 +1,233 @@
+//! Web Crypto: `crypto.getRandomValues`, `randomUUID`, and the
+//! `subtle.digest`/`importKey`/`sign` basics.
+//!
+//! Randomness comes from the OS CSPRNG via `rand`, the same source the
+//! WebSocket handshake uses. `subtle` covers what login flows actually
+//! call: SHA-256/384/512 digests and HMAC signing over raw imported
+//! keys, through the RustCrypto crates. Everything async-shaped in the
+//! spec returns an already-resolved promise — the work is synchronous
+//! here, and callers only ever `await` it.
+
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+use boa_engine::object::builtins::{JsArrayBuffer, JsPromise};
+use boa_engine::{
+    js_string, Context, JsArgs, JsNativeError, JsObject, JsResult, JsString, JsValue,
+    NativeFunction,
+};
+
+use super::encoding::input_bytes;
+
+/// `getRandomValues` refuses requests past this, per spec.
+const MAX_RANDOM_BYTES: usize = 65536;
+
+/// Install the `crypto` global.
+pub fn register(context: &mut Context) {
+    let crypto = JsObject::with_null_proto();
+    method(&crypto, "getRandomValues", get_random_values, context)
+        .expect("installing crypto method");
+    method(&crypto, "randomUUID", random_uuid, context).expect("installing crypto method");
+    let subtle = JsObject::with_null_proto();
+    method(&subtle, "digest", digest, context).expect("installing subtle method");
+    method(&subtle, "importKey", import_key, context).expect("installing subtle method");
+    method(&subtle, "sign", sign, context).expect("installing subtle method");
+    crypto
+        .set(js_string!("subtle"), subtle, false, context)
+        .expect("installing crypto.subtle");
+    context
+        .register_global_property(
+            js_string!("crypto"),
+            crypto,
+            boa_engine::property::Attribute::all(),
+        )
+        .expect("registering crypto global");
+}
+
+/// Fill a typed array view with CSPRNG output and return it.
+fn get_random_values(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let view_value = args.get_or_undefined(0).clone();
+    let Some(view) = view_value.as_object().cloned() else {
+        return Err(JsNativeError::typ()
+            .with_message("getRandomValues: expected a typed array")
+            .into());
+    };
+    let length = view.get(js_string!("length"), context)?.to_number(context)? as usize;
+    let bytes_per_element = {
+        let value = view.get(js_string!("BYTES_PER_ELEMENT"), context)?;
+        if value.is_undefined() {
+            1
+        } else {
+            value.to_number(context)? as usize
+        }
+    };
+    if length * bytes_per_element > MAX_RANDOM_BYTES {
+        return Err(JsNativeError::error()
+            .with_message("QuotaExceededError: getRandomValues request exceeds 65536 bytes")
+            .into());
+    }
+    let mut rng = rand::rngs::OsRng;
+    // Full-range per element: compose the element's bytes from the CSPRNG.
+    let element_bits = 8 * bytes_per_element.clamp(1, 8) as u32;
+    for index in 0..length {
+        let mut bytes = [0u8; 8];
+        rng.fill_bytes(&mut bytes);
+        let raw = u64::from_le_bytes(bytes);
+        let value = if element_bits >= 64 { raw } else { raw & ((1 << element_bits) - 1) };
+        view.set(index, value as f64, false, context)?;
+    }
+    Ok(view_value)
+}
+
+/// A version-4 UUID from CSPRNG bytes.
+fn random_uuid(_this: &JsValue, _args: &[JsValue], _context: &mut Context) -> JsResult<JsValue> {
+    let mut bytes = [0u8; 16];
+    rand::rngs::OsRng.fill_bytes(&mut bytes);
+    bytes[6] = (bytes[6] & 0x0F) | 0x40;
+    bytes[8] = (bytes[8] & 0x3F) | 0x80;
+    let hex: Vec<String> = bytes.iter().map(|b| format!("{b:02x}")).collect();
+    let uuid = format!(
+        "{}-{}-{}-{}-{}",
+        hex[0..4].join(""),
+        hex[4..6].join(""),
+        hex[6..8].join(""),
+        hex[8..10].join(""),
+        hex[10..16].join(""),
+    );
+    Ok(JsString::from(uuid).into())
+}
+
+/// `subtle.digest(algorithm, data)` → promise of an `ArrayBuffer`.
+fn digest(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let algorithm = algorithm_name(args.get_or_undefined(0), context)?;
+    let data = input_bytes(args.get_or_undefined(1), context)?;
+    let hashed = hash(&algorithm, &data)?;
+    resolved_buffer(hashed, context)
+}
+
+/// `subtle.importKey("raw", keyData, {name: "HMAC", hash}, …)` → a key
+/// object carrying the raw bytes and hash for [`sign`].
+fn import_key(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let format = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    if format != "raw" {
+        return Err(JsNativeError::error()
+            .with_message(format!("importKey: only \"raw\" keys are supported, got \"{format}\""))
+            .into());
+    }
+    let key_data = input_bytes(args.get_or_undefined(1), context)?;
+    let algorithm = args.get_or_undefined(2);
+    let (name, hash) = match algorithm.as_object() {
+        Some(object) => {
+            let name = object.get(js_string!("name"), context)?;
+            let hash = object.get(js_string!("hash"), context)?;
+            (
+                name.to_string(context)?.to_std_string_escaped(),
+                algorithm_name(&hash, context).unwrap_or_else(|_| "SHA-256".to_owned()),
+            )
+        }
+        None => (
+            algorithm.to_string(context)?.to_std_string_escaped(),
+            "SHA-256".to_owned(),
+        ),
+    };
+    if !name.eq_ignore_ascii_case("HMAC") {
+        return Err(JsNativeError::error()
+            .with_message(format!("importKey: unsupported algorithm \"{name}\""))
+            .into());
+    }
+    let key = JsObject::with_null_proto();
+    key.set(js_string!("type"), js_string!("secret"), false, context)?;
+    key.set(js_string!("__hmacHash"), JsString::from(hash), false, context)?;
+    let data = boa_engine::object::builtins::JsUint8Array::from_iter(key_data, context)?;
+    key.set(js_string!("__keyData"), data, false, context)?;
+    Ok(JsPromise::resolve(key, context).into())
+}
+
+/// `subtle.sign("HMAC", key, data)` → promise of the MAC bytes.
+fn sign(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let algorithm = algorithm_name(args.get_or_undefined(0), context)?;
+    if !algorithm.eq_ignore_ascii_case("HMAC") {
+        return Err(JsNativeError::error()
+            .with_message(format!("sign: unsupported algorithm \"{algorithm}\""))
+            .into());
+    }
+    let Some(key) = args.get_or_undefined(1).as_object() else {
+        return Err(JsNativeError::typ().with_message("sign: expected a CryptoKey").into());
+    };
+    let hash = key
+        .get(js_string!("__hmacHash"), context)?
+        .to_string(context)?
+        .to_std_string_escaped();
+    let key_data = input_bytes(&key.get(js_string!("__keyData"), context)?, context)?;
+    let data = input_bytes(args.get_or_undefined(2), context)?;
+    let mac = match hash.as_str() {
+        "SHA-256" => hmac_bytes::<Sha256>(&key_data, &data),
+        "SHA-384" => hmac_bytes::<Sha384>(&key_data, &data),
+        "SHA-512" => hmac_bytes::<Sha512>(&key_data, &data),
+        other => {
+            return Err(JsNativeError::error()
+                .with_message(format!("sign: unsupported hash \"{other}\""))
+                .into())
+        }
+    };
+    resolved_buffer(mac, context)
+}
+
+fn hmac_bytes<D>(key: &[u8], data: &[u8]) -> Vec<u8>
+where
+    D: Digest + hmac::digest::core_api::BlockSizeUser,
+    Hmac<D>: Mac + hmac::digest::KeyInit,
+{
+    let mut mac = <Hmac<D> as Mac>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hash(algorithm: &str, data: &[u8]) -> JsResult<Vec<u8>> {
+    Ok(match algorithm {
+        "SHA-256" => Sha256::digest(data).to_vec(),
+        "SHA-384" => Sha384::digest(data).to_vec(),
+        "SHA-512" => Sha512::digest(data).to_vec(),
+        other => {
+            return Err(JsNativeError::error()
+                .with_message(format!("digest: unsupported algorithm \"{other}\""))
+                .into())
+        }
+    })
+}
+
+/// An algorithm argument: a string, or a `{ name }` object. Normalised
+/// to upper case with the `SHA-` prefix intact.
+fn algorithm_name(value: &JsValue, context: &mut Context) -> JsResult<String> {
+    let name = match value.as_object() {
+        Some(object) => object
+            .get(js_string!("name"), context)?
+            .to_string(context)?
+            .to_std_string_escaped(),
+        None => value.to_string(context)?.to_std_string_escaped(),
+    };
+    Ok(name.to_ascii_uppercase())
+}
+
+/// Wrap `bytes` in an `ArrayBuffer` inside a resolved promise.
+fn resolved_buffer(bytes: Vec<u8>, context: &mut Context) -> JsResult<JsValue> {
+    let buffer = JsArrayBuffer::from_byte_block(bytes, context)?;
+    Ok(JsPromise::resolve(buffer, context).into())
+}
+
+/// Install a native method on `object`.
+fn method(
+    object: &JsObject,
+    name: &str,
+    function: fn(&JsValue, &[JsValue], &mut Context) -> JsResult<JsValue>,
+    context: &mut Context,
+) -> JsResult<()> {
+    object.set(
+        JsString::from(name),
+        NativeFunction::from_fn_ptr(function).to_js_function(context.realm()),
+        false,
+        context,
+    )?;
+    Ok(())
+}
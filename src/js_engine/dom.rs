@@ -0,0 +1,778 @@
+//! DOM bindings: the query side of `document` for page scripts.
+//!
+//! The tab installs its parsed [`Document`] before running scripts and
+//! takes it back afterwards through [`take_document`], so attribute
+//! writes flow into the real tree. Wrappers follow the same pragmatism as
+//! the canvas binding: element objects share one prototype holding the
+//! live methods (`getAttribute`, `querySelector`, …) and the accessor
+//! properties (`innerHTML`, `textContent`), while cheap reflected fields
+//! (`tagName`, `id`, `dataset`) are snapshots taken at wrap time.
+//! Assigned markup goes through the real parser in
+//! [`crate::renderer::html`]. Query results come back as arrays rather than
+//! `NodeList`s; selector matching is the real engine's, via
+//! [`crate::renderer::css`].
+
+use std::cell::RefCell;
+
+use boa_engine::{
+    js_string, Context, JsArgs, JsObject, JsResult, JsString, JsValue, NativeFunction,
+};
+
+use crate::renderer::css::{self, InteractionState, Selector};
+use crate::renderer::dirty::LayoutChange;
+use crate::renderer::dom::{Document, NodeData, NodeId};
+
+use super::canvas;
+
+thread_local! {
+    static DOCUMENT: RefCell<Document> = RefCell::new(Document::new());
+    // Nodes scripts mutated since the last drain, for dirty tracking.
+    static DAMAGE: RefCell<Vec<(NodeId, LayoutChange)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Install `document` bound to `page` on the global object. Replaces any
+/// previously installed document.
+pub fn install(context: &mut Context, page: Document) -> JsResult<()> {
+    DOCUMENT.with(|slot| *slot.borrow_mut() = page);
+    let document = JsObject::with_null_proto();
+    method(&document, "getElementById", get_element_by_id, context)?;
+    method(&document, "querySelector", query_selector, context)?;
+    method(&document, "querySelectorAll", query_selector_all, context)?;
+    method(&document, "getElementsByTagName", get_elements_by_tag_name, context)?;
+    method(&document, "getElementsByClassName", get_elements_by_class_name, context)?;
+    method(&document, "createElement", create_element, context)?;
+    method(&document, "createTextNode", create_text_node, context)?;
+    context
+        .global_object()
+        .set(js_string!("document"), document, false, context)?;
+    Ok(())
+}
+
+/// Take the document back after script execution, mutations included.
+pub fn take_document() -> Document {
+    DOCUMENT.with(|slot| std::mem::take(&mut *slot.borrow_mut()))
+}
+
+/// Run `read` against the installed document. For sibling bindings (the
+/// event system walks ancestor chains through this).
+pub(crate) fn with_document<R>(read: impl FnOnce(&Document) -> R) -> R {
+    DOCUMENT.with(|slot| read(&slot.borrow()))
+}
+
+/// The node behind a wrapper, for sibling bindings.
+pub(crate) fn node_of(this: &JsValue, context: &mut Context) -> JsResult<NodeId> {
+    scope_of(this, context)
+}
+
+/// Nodes scripts mutated since the last call, with how invasively. The
+/// UI drains this each frame into its
+/// [`DirtyTracker`](crate::renderer::dirty::DirtyTracker), so scripted
+/// changes re-render incrementally instead of forcing a reload.
+pub fn take_damage() -> Vec<(NodeId, LayoutChange)> {
+    DAMAGE.with(|damage| std::mem::take(&mut *damage.borrow_mut()))
+}
+
+fn mark_damage(node: NodeId, change: LayoutChange) {
+    DAMAGE.with(|damage| damage.borrow_mut().push((node, change)));
+}
+
+fn get_element_by_id(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let id = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    let found = DOCUMENT.with(|slot| {
+        let document = slot.borrow();
+        document
+            .descendants(document.root())
+            .into_iter()
+            .find(|&node| document.element(node).and_then(|e| e.id()) == Some(id.as_str()))
+    });
+    match found {
+        Some(node) => Ok(wrap_element(node, context)?.into()),
+        None => Ok(JsValue::null()),
+    }
+}
+
+fn query_selector(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let matches = query(this, args, context, true)?;
+    match matches.into_iter().next() {
+        Some(node) => Ok(wrap_element(node, context)?.into()),
+        None => Ok(JsValue::null()),
+    }
+}
+
+fn query_selector_all(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let matches = query(this, args, context, false)?;
+    wrap_list(&matches, context)
+}
+
+fn get_elements_by_tag_name(
+    this: &JsValue,
+    args: &[JsValue],
+    context: &mut Context,
+) -> JsResult<JsValue> {
+    let name = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    let name = name.to_ascii_lowercase();
+    let matches = elements_under(this, context, |document, node| {
+        document.element(node).map_or(false, |element| {
+            name == "*" || element.tag_name == name
+        })
+    })?;
+    wrap_list(&matches, context)
+}
+
+fn get_elements_by_class_name(
+    this: &JsValue,
+    args: &[JsValue],
+    context: &mut Context,
+) -> JsResult<JsValue> {
+    let class = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    let matches = elements_under(this, context, |document, node| {
+        document
+            .element(node)
+            .map_or(false, |element| element.has_class(&class))
+    })?;
+    wrap_list(&matches, context)
+}
+
+/// Run a selector-list query under `this`'s subtree, in document order.
+fn query(
+    this: &JsValue,
+    args: &[JsValue],
+    context: &mut Context,
+    first_only: bool,
+) -> JsResult<Vec<NodeId>> {
+    let text = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    let selectors = css::parse_selector_list(&text);
+    if selectors.is_empty() {
+        return Ok(Vec::new());
+    }
+    let state = InteractionState::default();
+    let matches_any = |document: &Document, node: NodeId, selectors: &[Selector]| {
+        selectors
+            .iter()
+            .any(|selector| selector.matches(document, node, &state))
+    };
+    let scope = scope_of(this, context)?;
+    Ok(DOCUMENT.with(|slot| {
+        let document = slot.borrow();
+        let mut found = Vec::new();
+        for node in document.descendants(scope) {
+            if node == scope {
+                continue;
+            }
+            if matches!(document.node(node).data, NodeData::Element(_))
+                && matches_any(&document, node, &selectors)
+            {
+                found.push(node);
+                if first_only {
+                    break;
+                }
+            }
+        }
+        found
+    }))
+}
+
+/// Element descendants of `this`'s scope satisfying `keep`.
+fn elements_under(
+    this: &JsValue,
+    context: &mut Context,
+    keep: impl Fn(&Document, NodeId) -> bool,
+) -> JsResult<Vec<NodeId>> {
+    let scope = scope_of(this, context)?;
+    Ok(DOCUMENT.with(|slot| {
+        let document = slot.borrow();
+        document
+            .descendants(scope)
+            .into_iter()
+            .filter(|&node| node != scope && keep(&document, node))
+            .collect()
+    }))
+}
+
+/// The subtree a method searches: the wrapper's element for element
+/// methods, the root for the `document` object.
+fn scope_of(this: &JsValue, context: &mut Context) -> JsResult<NodeId> {
+    let node = this
+        .as_object()
+        .map(|object| object.get(js_string!("__nodeId"), context))
+        .transpose()?
+        .unwrap_or_default();
+    if node.is_undefined() {
+        return Ok(DOCUMENT.with(|slot| slot.borrow().root()));
+    }
+    Ok(NodeId(node.to_number(context)? as usize))
+}
+
+/// Build (or fetch the cached) shared element prototype for this context.
+fn element_prototype(context: &mut Context) -> JsResult<JsObject> {
+    let global = context.global_object();
+    let cached = global.get(js_string!("__binixElementProto"), context)?;
+    if let Some(proto) = cached.as_object() {
+        return Ok(proto.clone());
+    }
+    let proto = JsObject::with_null_proto();
+    method(&proto, "getAttribute", get_attribute, context)?;
+    method(&proto, "setAttribute", set_attribute, context)?;
+    method(&proto, "hasAttribute", has_attribute, context)?;
+    method(&proto, "querySelector", query_selector, context)?;
+    method(&proto, "querySelectorAll", query_selector_all, context)?;
+    method(&proto, "getElementsByTagName", get_elements_by_tag_name, context)?;
+    method(&proto, "getElementsByClassName", get_elements_by_class_name, context)?;
+    method(&proto, "getContext", get_context, context)?;
+    method(&proto, "appendChild", append_child, context)?;
+    method(&proto, "removeChild", remove_child, context)?;
+    install_inner_html(&proto, context)?;
+    install_text_content(&proto, context)?;
+    super::events::register_methods(&proto, context)?;
+    super::forms::install_form_methods(&proto, context)?;
+    global.set(js_string!("__binixElementProto"), proto.clone(), false, context)?;
+    Ok(proto)
+}
+
+/// Wrap `node` for script: prototype methods and accessors stay live
+/// against the installed document, reflected fields are snapshots.
+pub(crate) fn wrap_element(node: NodeId, context: &mut Context) -> JsResult<JsObject> {
+    let proto = element_prototype(context)?;
+    let object = JsObject::with_null_proto();
+    object.set_prototype(Some(proto));
+    object.set(js_string!("__nodeId"), node.0 as f64, false, context)?;
+    let (tag, id) = DOCUMENT.with(|slot| {
+        let document = slot.borrow();
+        let element = document.element(node);
+        (
+            element.map(|e| e.tag_name.to_ascii_uppercase()).unwrap_or_default(),
+            element.and_then(|e| e.id()).unwrap_or_default().to_owned(),
+        )
+    });
+    object.set(js_string!("tagName"), JsString::from(tag), false, context)?;
+    object.set(js_string!("id"), JsString::from(id), false, context)?;
+    object.set(js_string!("classList"), class_list(node, context)?, false, context)?;
+    object.set(js_string!("style"), style_object(node, context)?, false, context)?;
+    object.set(js_string!("dataset"), dataset(node, context)?, false, context)?;
+    Ok(object)
+}
+
+fn wrap_list(nodes: &[NodeId], context: &mut Context) -> JsResult<JsValue> {
+    let wrapped: Vec<JsValue> = nodes
+        .iter()
+        .map(|&node| wrap_element(node, context).map(JsValue::from))
+        .collect::<JsResult<_>>()?;
+    Ok(boa_engine::object::builtins::JsArray::from_iter(wrapped, context).into())
+}
+
+fn get_attribute(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let name = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    let node = scope_of(this, context)?;
+    let value = DOCUMENT.with(|slot| {
+        slot.borrow()
+            .element(node)
+            .and_then(|element| element.attr(&name).map(str::to_owned))
+    });
+    match value {
+        Some(value) => Ok(JsString::from(value).into()),
+        None => Ok(JsValue::null()),
+    }
+}
+
+fn set_attribute(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let name = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    let value = args.get_or_undefined(1).to_string(context)?.to_std_string_escaped();
+    let node = scope_of(this, context)?;
+    let old_value = DOCUMENT.with(|slot| {
+        let mut document = slot.borrow_mut();
+        match &mut document.node_mut(node).data {
+            NodeData::Element(element) => {
+                let old = element.attr(&name).map(str::to_owned);
+                element.set_attr(&name, &value);
+                old
+            }
+            _ => None,
+        }
+    });
+    super::mutation::record(
+        node,
+        super::mutation::Mutation::Attributes { name, old_value },
+        context,
+    );
+    // Attributes can change which rules match: restyle and re-lay-out
+    // the element's subtree.
+    mark_damage(node, LayoutChange::SelfLayout);
+    Ok(JsValue::undefined())
+}
+
+fn create_element(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let tag = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    let node = DOCUMENT.with(|slot| {
+        slot.borrow_mut().create(NodeData::Element(
+            crate::renderer::dom::ElementData::new(&tag),
+        ))
+    });
+    Ok(wrap_element(node, context)?.into())
+}
+
+fn create_text_node(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let text = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    let node = DOCUMENT.with(|slot| slot.borrow_mut().create(NodeData::Text(text)));
+    Ok(wrap_element(node, context)?.into())
+}
+
+fn append_child(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let parent = scope_of(this, context)?;
+    let child_value = args.get_or_undefined(0).clone();
+    let child = node_of(&child_value, context)?;
+    DOCUMENT.with(|slot| slot.borrow_mut().attach(parent, child));
+    super::mutation::record(parent, super::mutation::Mutation::ChildList, context);
+    mark_damage(parent, LayoutChange::Subtree);
+    Ok(child_value)
+}
+
+fn remove_child(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let parent = scope_of(this, context)?;
+    let child_value = args.get_or_undefined(0).clone();
+    let child = node_of(&child_value, context)?;
+    let removed = DOCUMENT.with(|slot| {
+        let mut document = slot.borrow_mut();
+        if document.parent(child) == Some(parent) {
+            document.detach(child);
+            true
+        } else {
+            false
+        }
+    });
+    if !removed {
+        return Err(boa_engine::JsNativeError::error()
+            .with_message("removeChild: node is not a child of this element")
+            .into());
+    }
+    super::mutation::record(parent, super::mutation::Mutation::ChildList, context);
+    mark_damage(parent, LayoutChange::Subtree);
+    Ok(child_value)
+}
+
+/// `innerHTML` is the one accessor property on the prototype: reads
+/// re-serialise the live subtree, writes parse the fragment and replace
+/// the children.
+fn install_inner_html(proto: &JsObject, context: &mut Context) -> JsResult<()> {
+    let getter = NativeFunction::from_fn_ptr(inner_html_get).to_js_function(context.realm());
+    let setter = NativeFunction::from_fn_ptr(inner_html_set).to_js_function(context.realm());
+    proto.define_property_or_throw(
+        js_string!("innerHTML"),
+        boa_engine::property::PropertyDescriptor::builder()
+            .get(getter)
+            .set(setter)
+            .enumerable(false)
+            .configurable(true),
+        context,
+    )?;
+    Ok(())
+}
+
+fn inner_html_get(this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let node = scope_of(this, context)?;
+    let html = DOCUMENT.with(|slot| slot.borrow().inner_html(node));
+    Ok(JsString::from(html).into())
+}
+
+fn inner_html_set(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let node = scope_of(this, context)?;
+    let markup = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    let fragment = crate::renderer::html::parse(&markup);
+    DOCUMENT.with(|slot| {
+        let mut document = slot.borrow_mut();
+        for child in document.node(node).children.clone() {
+            document.detach(child);
+        }
+        transplant(&mut document, &fragment, fragment.root(), node);
+    });
+    super::mutation::record(node, super::mutation::Mutation::ChildList, context);
+    mark_damage(node, LayoutChange::Subtree);
+    Ok(JsValue::undefined())
+}
+
+/// Copy `from`'s children out of `fragment` under `into` in `document`.
+fn transplant(document: &mut Document, fragment: &Document, from: NodeId, into: NodeId) {
+    for &child in &fragment.node(from).children {
+        let data = fragment.node(child).data.clone();
+        let new = document.append(into, data);
+        transplant(document, fragment, child, new);
+    }
+}
+
+/// `textContent` as a live accessor: reads concatenate the subtree's
+/// text, writes replace the children with one text node.
+fn install_text_content(proto: &JsObject, context: &mut Context) -> JsResult<()> {
+    let getter = NativeFunction::from_fn_ptr(text_content_get).to_js_function(context.realm());
+    let setter = NativeFunction::from_fn_ptr(text_content_set).to_js_function(context.realm());
+    proto.define_property_or_throw(
+        js_string!("textContent"),
+        boa_engine::property::PropertyDescriptor::builder()
+            .get(getter)
+            .set(setter)
+            .enumerable(false)
+            .configurable(true),
+        context,
+    )?;
+    Ok(())
+}
+
+fn text_content_get(this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let node = scope_of(this, context)?;
+    let text = DOCUMENT.with(|slot| slot.borrow().text_content(node));
+    Ok(JsString::from(text).into())
+}
+
+fn text_content_set(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let node = scope_of(this, context)?;
+    let text = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    DOCUMENT.with(|slot| {
+        let mut document = slot.borrow_mut();
+        for child in document.node(node).children.clone() {
+            document.detach(child);
+        }
+        document.append(node, NodeData::Text(text));
+    });
+    super::mutation::record(node, super::mutation::Mutation::ChildList, context);
+    mark_damage(node, LayoutChange::Subtree);
+    Ok(JsValue::undefined())
+}
+
+/// The `classList` object: live methods over the `class` attribute.
+fn class_list(node: NodeId, context: &mut Context) -> JsResult<JsObject> {
+    let object = JsObject::with_null_proto();
+    object.set(js_string!("__nodeId"), node.0 as f64, false, context)?;
+    method(&object, "add", class_add, context)?;
+    method(&object, "remove", class_remove, context)?;
+    method(&object, "toggle", class_toggle, context)?;
+    method(&object, "contains", class_contains, context)?;
+    Ok(object)
+}
+
+fn class_add(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let class = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    update_classes(this, context, |classes| {
+        if !classes.contains(&class) {
+            classes.push(class.clone());
+        }
+        JsValue::undefined()
+    })
+}
+
+fn class_remove(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let class = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    update_classes(this, context, |classes| {
+        classes.retain(|c| *c != class);
+        JsValue::undefined()
+    })
+}
+
+fn class_toggle(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let class = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    update_classes(this, context, |classes| {
+        if classes.contains(&class) {
+            classes.retain(|c| *c != class);
+            false.into()
+        } else {
+            classes.push(class.clone());
+            true.into()
+        }
+    })
+}
+
+fn class_contains(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let class = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    let node = scope_of(this, context)?;
+    let present = DOCUMENT.with(|slot| {
+        slot.borrow()
+            .element(node)
+            .map_or(false, |element| element.has_class(&class))
+    });
+    Ok(present.into())
+}
+
+/// Rewrite the `class` attribute through `change`, recording the
+/// mutation and damage.
+fn update_classes(
+    this: &JsValue,
+    context: &mut Context,
+    change: impl FnOnce(&mut Vec<String>) -> JsValue,
+) -> JsResult<JsValue> {
+    let node = scope_of(this, context)?;
+    let (result, old_value) = DOCUMENT.with(|slot| {
+        let mut document = slot.borrow_mut();
+        let NodeData::Element(element) = &mut document.node_mut(node).data else {
+            return (JsValue::undefined(), None);
+        };
+        let old = element.attr("class").map(str::to_owned);
+        let mut classes: Vec<String> = element.classes().map(str::to_owned).collect();
+        let result = change(&mut classes);
+        element.set_attr("class", &classes.join(" "));
+        (result, old)
+    });
+    super::mutation::record(
+        node,
+        super::mutation::Mutation::Attributes {
+            name: "class".to_owned(),
+            old_value,
+        },
+        context,
+    );
+    mark_damage(node, LayoutChange::SelfLayout);
+    Ok(result)
+}
+
+/// CSS properties worth camelCase accessors on `style`. Writes to
+/// anything else go through `setProperty`.
+const STYLE_PROPERTIES: &[&str] = &[
+    "background",
+    "background-color",
+    "border",
+    "bottom",
+    "color",
+    "cursor",
+    "display",
+    "flex-direction",
+    "font-family",
+    "font-size",
+    "font-weight",
+    "height",
+    "left",
+    "line-height",
+    "margin",
+    "opacity",
+    "overflow",
+    "padding",
+    "position",
+    "right",
+    "text-align",
+    "top",
+    "transform",
+    "transition",
+    "visibility",
+    "width",
+    "z-index",
+];
+
+/// The `style` object: live accessors for the common properties (in
+/// their camelCase spelling) plus `setProperty`/`getPropertyValue`, all
+/// reading and writing the element's `style` attribute so layout sees
+/// scripted styles the same way it sees markup ones.
+fn style_object(node: NodeId, context: &mut Context) -> JsResult<JsObject> {
+    let object = JsObject::with_null_proto();
+    object.set(js_string!("__nodeId"), node.0 as f64, false, context)?;
+    method(&object, "setProperty", style_set_property, context)?;
+    method(&object, "getPropertyValue", style_get_property, context)?;
+    for property in STYLE_PROPERTIES {
+        let getter = NativeFunction::from_copy_closure(move |this, _args, context| {
+            let node = scope_of(this, context)?;
+            Ok(JsString::from(read_style(node, property)).into())
+        })
+        .to_js_function(context.realm());
+        let setter = NativeFunction::from_copy_closure(move |this, args, context| {
+            let value = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+            write_style(this, property, &value, context)?;
+            Ok(JsValue::undefined())
+        })
+        .to_js_function(context.realm());
+        object.define_property_or_throw(
+            JsString::from(camel_case(property)),
+            boa_engine::property::PropertyDescriptor::builder()
+                .get(getter)
+                .set(setter)
+                .enumerable(true)
+                .configurable(true),
+            context,
+        )?;
+    }
+    Ok(object)
+}
+
+fn style_set_property(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let name = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    let value = args.get_or_undefined(1).to_string(context)?.to_std_string_escaped();
+    write_style(this, &name, &value, context)?;
+    Ok(JsValue::undefined())
+}
+
+fn style_get_property(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let name = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    let node = scope_of(this, context)?;
+    Ok(JsString::from(read_style(node, &name)).into())
+}
+
+/// The value of one declaration in the element's `style` attribute.
+fn read_style(node: NodeId, property: &str) -> String {
+    DOCUMENT.with(|slot| {
+        slot.borrow()
+            .element(node)
+            .and_then(|element| element.attr("style").map(str::to_owned))
+            .map(|style| {
+                parse_inline_style(&style)
+                    .into_iter()
+                    .find(|(name, _)| name == property)
+                    .map(|(_, value)| value)
+                    .unwrap_or_default()
+            })
+            .unwrap_or_default()
+    })
+}
+
+/// Insert or replace one declaration in the element's `style`
+/// attribute; an empty value removes it.
+fn write_style(this: &JsValue, property: &str, value: &str, context: &mut Context) -> JsResult<()> {
+    let node = scope_of(this, context)?;
+    let old_value = DOCUMENT.with(|slot| {
+        let mut document = slot.borrow_mut();
+        let NodeData::Element(element) = &mut document.node_mut(node).data else {
+            return None;
+        };
+        let old = element.attr("style").map(str::to_owned);
+        let mut declarations = parse_inline_style(old.as_deref().unwrap_or_default());
+        declarations.retain(|(name, _)| name != property);
+        if !value.is_empty() {
+            declarations.push((property.to_owned(), value.to_owned()));
+        }
+        let serialised = declarations
+            .iter()
+            .map(|(name, value)| format!("{name}: {value}"))
+            .collect::<Vec<_>>()
+            .join("; ");
+        element.set_attr("style", &serialised);
+        old
+    });
+    super::mutation::record(
+        node,
+        super::mutation::Mutation::Attributes {
+            name: "style".to_owned(),
+            old_value,
+        },
+        context,
+    );
+    mark_damage(node, LayoutChange::SelfLayout);
+    Ok(())
+}
+
+/// `a: b; c: d` → pairs, names lowercased.
+fn parse_inline_style(style: &str) -> Vec<(String, String)> {
+    style
+        .split(';')
+        .filter_map(|declaration| {
+            let (name, value) = declaration.split_once(':')?;
+            let name = name.trim().to_ascii_lowercase();
+            let value = value.trim().to_owned();
+            (!name.is_empty() && !value.is_empty()).then_some((name, value))
+        })
+        .collect()
+}
+
+/// `background-color` → `backgroundColor`.
+fn camel_case(property: &str) -> String {
+    let mut out = String::with_capacity(property.len());
+    let mut upper_next = false;
+    for c in property.chars() {
+        if c == '-' {
+            upper_next = true;
+        } else if upper_next {
+            out.extend(c.to_uppercase());
+            upper_next = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// The `dataset` object: `data-*` attributes with camelCased keys. A
+/// snapshot, like the other reflected fields.
+fn dataset(node: NodeId, context: &mut Context) -> JsResult<JsObject> {
+    let object = JsObject::with_null_proto();
+    let entries: Vec<(String, String)> = DOCUMENT.with(|slot| {
+        slot.borrow()
+            .element(node)
+            .map(|element| {
+                element
+                    .attributes
+                    .iter()
+                    .filter_map(|(name, value)| {
+                        let rest = name.strip_prefix("data-")?;
+                        Some((camel_case(rest), value.clone()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    });
+    for (key, value) in entries {
+        object.set(
+            JsString::from(key),
+            JsString::from(value),
+            false,
+            context,
+        )?;
+    }
+    Ok(object)
+}
+
+fn has_attribute(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let name = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    let node = scope_of(this, context)?;
+    let present = DOCUMENT.with(|slot| {
+        slot.borrow()
+            .element(node)
+            .map_or(false, |element| element.attr(&name).is_some())
+    });
+    Ok(present.into())
+}
+
+/// `<canvas>.getContext("2d")`, reusing the OffscreenCanvas context
+/// machinery so DOM and offscreen canvases record identically.
+fn get_context(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let kind = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    if kind != "2d" {
+        return Ok(JsValue::null());
+    }
+    let node = scope_of(this, context)?;
+    let Some(object) = this.as_object() else {
+        return Ok(JsValue::null());
+    };
+    let cached = object.get(js_string!("__context2d"), context)?;
+    if cached.is_object() {
+        return Ok(cached);
+    }
+    let (is_canvas, width, height) = DOCUMENT.with(|slot| {
+        let document = slot.borrow();
+        let element = document.element(node);
+        let size = |name: &str, default: f32| -> f32 {
+            element
+                .and_then(|e| e.attr(name))
+                .and_then(|v| v.trim().parse().ok())
+                .unwrap_or(default)
+        };
+        (
+            element.map_or(false, |e| e.tag_name == "canvas"),
+            size("width", 300.0),
+            size("height", 150.0),
+        )
+    });
+    if !is_canvas {
+        return Ok(JsValue::null());
+    }
+    let ctx = canvas::create_context(width, height, context)?;
+    object.set(js_string!("__context2d"), ctx.clone(), false, context)?;
+    ctx.set(js_string!("canvas"), this.clone(), false, context)?;
+    Ok(ctx.into())
+}
+
+/// Install a native method on `object`.
+fn method(
+    object: &JsObject,
+    name: &str,
+    function: fn(&JsValue, &[JsValue], &mut Context) -> JsResult<JsValue>,
+    context: &mut Context,
+) -> JsResult<()> {
+    object.set(
+        JsString::from(name),
+        NativeFunction::from_fn_ptr(function).to_js_function(context.realm()),
+        false,
+        context,
+    )?;
+    Ok(())
+}
@@ -0,0 +1,176 @@
+//! The encoding globals: `TextEncoder`/`TextDecoder` and `atob`/`btoa`.
+//!
+//! `TextEncoder` is UTF-8 only, per spec. `TextDecoder` accepts the
+//! UTF-8 labels and honours `{ fatal }`: lossy replacement by default, a
+//! `TypeError` on invalid input when fatal. `atob`/`btoa` do real
+//! base64 through the same crate the network stack uses, with the
+//! spec's `InvalidCharacterError` shape on bad input.
+
+use base64::Engine as _;
+use boa_engine::object::builtins::JsUint8Array;
+use boa_engine::{
+    js_string, Context, JsArgs, JsNativeError, JsObject, JsResult, JsString, JsValue,
+    NativeFunction,
+};
+
+/// Install the encoding globals.
+pub fn register(context: &mut Context) {
+    context
+        .register_global_callable(
+            js_string!("TextEncoder"),
+            0,
+            NativeFunction::from_fn_ptr(construct_encoder),
+        )
+        .expect("registering TextEncoder");
+    context
+        .register_global_callable(
+            js_string!("TextDecoder"),
+            0,
+            NativeFunction::from_fn_ptr(construct_decoder),
+        )
+        .expect("registering TextDecoder");
+    context
+        .register_global_callable(js_string!("atob"), 1, NativeFunction::from_fn_ptr(atob))
+        .expect("registering atob");
+    context
+        .register_global_callable(js_string!("btoa"), 1, NativeFunction::from_fn_ptr(btoa))
+        .expect("registering btoa");
+}
+
+fn construct_encoder(_this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let object = JsObject::with_null_proto();
+    object.set(js_string!("encoding"), js_string!("utf-8"), false, context)?;
+    method(&object, "encode", encode, context)?;
+    Ok(object.into())
+}
+
+fn construct_decoder(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let label = args.get_or_undefined(0);
+    if !label.is_undefined() {
+        let label = label.to_string(context)?.to_std_string_escaped();
+        let normalized = label.trim().to_ascii_lowercase();
+        if !matches!(normalized.as_str(), "utf-8" | "utf8" | "unicode-1-1-utf-8") {
+            return Err(JsNativeError::range()
+                .with_message(format!("TextDecoder: unsupported encoding \"{label}\""))
+                .into());
+        }
+    }
+    let fatal = match args.get_or_undefined(1).as_object() {
+        Some(options) => options.get(js_string!("fatal"), context)?.to_boolean(),
+        None => false,
+    };
+    let object = JsObject::with_null_proto();
+    object.set(js_string!("encoding"), js_string!("utf-8"), false, context)?;
+    object.set(js_string!("fatal"), fatal, false, context)?;
+    method(&object, "decode", decode, context)?;
+    Ok(object.into())
+}
+
+/// `TextEncoder.encode(string)` → a `Uint8Array` of the UTF-8 bytes.
+fn encode(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let text = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    Ok(JsUint8Array::from_iter(text.into_bytes(), context)?.into())
+}
+
+/// `TextDecoder.decode(buffer | view)` → the decoded string.
+fn decode(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let input = args.get_or_undefined(0);
+    if input.is_undefined() {
+        return Ok(js_string!("").into());
+    }
+    let bytes = input_bytes(input, context)?;
+    let fatal = this
+        .as_object()
+        .map(|o| o.get(js_string!("fatal"), context))
+        .transpose()?
+        .map_or(false, |v| v.to_boolean());
+    if fatal {
+        match String::from_utf8(bytes) {
+            Ok(text) => Ok(JsString::from(text).into()),
+            Err(_) => Err(JsNativeError::typ()
+                .with_message("TextDecoder: invalid UTF-8 with fatal set")
+                .into()),
+        }
+    } else {
+        Ok(JsString::from(String::from_utf8_lossy(&bytes).into_owned()).into())
+    }
+}
+
+/// The bytes behind a `Uint8Array` (or any indexed view) or a bare
+/// `ArrayBuffer`. Shared with the crypto binding, which takes the same
+/// buffer-or-view inputs.
+pub(crate) fn input_bytes(input: &JsValue, context: &mut Context) -> JsResult<Vec<u8>> {
+    let Some(object) = input.as_object() else {
+        return Err(JsNativeError::typ()
+            .with_message("TextDecoder: expected an ArrayBuffer or view")
+            .into());
+    };
+    let view = if object.get(js_string!("length"), context)?.is_undefined() {
+        // A bare buffer: view it so the bytes stay attached.
+        let constructor = context
+            .global_object()
+            .get(js_string!("Uint8Array"), context)?;
+        let Some(constructor) = constructor.as_object().filter(|o| o.is_constructor()) else {
+            return Err(JsNativeError::typ()
+                .with_message("Uint8Array is not constructible")
+                .into());
+        };
+        constructor.construct(&[input.clone()], Some(constructor), context)?
+    } else {
+        object.clone()
+    };
+    let length = view.get(js_string!("length"), context)?.to_number(context)? as usize;
+    let mut bytes = Vec::with_capacity(length);
+    for index in 0..length {
+        bytes.push(view.get(index, context)?.to_number(context)? as u8);
+    }
+    Ok(bytes)
+}
+
+/// `atob(base64)` → a latin-1 string of the decoded bytes.
+fn atob(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let encoded = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    // Whitespace is stripped before decoding, per spec.
+    let compact: String = encoded.chars().filter(|c| !c.is_ascii_whitespace()).collect();
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(&compact)
+        .map_err(|_| {
+            JsNativeError::error()
+                .with_message("InvalidCharacterError: atob input is not valid base64")
+        })?;
+    let text: String = bytes.into_iter().map(char::from).collect();
+    Ok(JsString::from(text).into())
+}
+
+/// `btoa(string)` → the base64 of the string's latin-1 bytes.
+fn btoa(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let text = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    let mut bytes = Vec::with_capacity(text.len());
+    for c in text.chars() {
+        let code = c as u32;
+        if code > 0xFF {
+            return Err(JsNativeError::error()
+                .with_message("InvalidCharacterError: btoa input contains a character above U+00FF")
+                .into());
+        }
+        bytes.push(code as u8);
+    }
+    let encoded = base64::engine::general_purpose::STANDARD.encode(bytes);
+    Ok(JsString::from(encoded).into())
+}
+
+/// Install a native method on `object`.
+fn method(
+    object: &JsObject,
+    name: &str,
+    function: fn(&JsValue, &[JsValue], &mut Context) -> JsResult<JsValue>,
+    context: &mut Context,
+) -> JsResult<()> {
+    object.set(
+        JsString::from(name),
+        NativeFunction::from_fn_ptr(function).to_js_function(context.realm()),
+        false,
+        context,
+    )?;
+    Ok(())
+}
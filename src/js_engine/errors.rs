@@ -0,0 +1,218 @@
+//! Structured script errors: `window.onerror`, `unhandledrejection`,
+//! and the devtools console.
+//!
+//! Execution failures come out of Boa as one string; [`report`] pulls
+//! the pieces back apart — message, the source URL the runtime was told
+//! it was running ([`set_source`]), line/column when the message carries
+//! them, a stack when the error object has one — fires the global
+//! `onerror` handler, and forwards unhandled errors to
+//! [`crate::devtools::console`] with a source link. Promise rejections
+//! are tracked through Boa's host hooks: [`ReportingHooks`] queues every
+//! rejection, handling removes it again, and whatever is left at the
+//! [`pump`] checkpoint fires `onunhandledrejection`.
+
+use std::cell::RefCell;
+
+use boa_engine::builtins::promise::{OperationType, PromiseState};
+use boa_engine::object::builtins::JsPromise;
+use boa_engine::{js_string, Context, JsObject, JsValue};
+
+use crate::devtools::console::{ConsoleLevel, ConsoleMessage};
+
+/// One script error, structured.
+#[derive(Debug, Clone)]
+pub struct ScriptError {
+    pub message: String,
+    /// The script's URL (the document URL for inline scripts).
+    pub source_url: String,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    pub stack: Option<String>,
+}
+
+thread_local! {
+    // The URL of the script currently executing, for attribution.
+    static SOURCE_URL: RefCell<String> = RefCell::new(String::new());
+    // Promises rejected without a handler yet; handling removes them.
+    static UNHANDLED: RefCell<Vec<JsObject>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Host hooks wiring Boa's promise rejection tracker into this module.
+/// Installed once at context construction.
+pub struct ReportingHooks;
+
+impl boa_engine::context::HostHooks for ReportingHooks {
+    fn promise_rejection_tracker(
+        &self,
+        promise: &JsObject,
+        operation: OperationType,
+        _context: &mut Context,
+    ) {
+        UNHANDLED.with(|unhandled| {
+            let mut unhandled = unhandled.borrow_mut();
+            match operation {
+                OperationType::Reject => unhandled.push(promise.clone()),
+                OperationType::Handle => {
+                    unhandled.retain(|p| !JsObject::equals(p, promise));
+                }
+            }
+        });
+    }
+}
+
+/// Attribute subsequent errors to the script at `url`. Set before each
+/// script a page runs.
+pub fn set_source(url: &str) {
+    SOURCE_URL.with(|source| *source.borrow_mut() = url.to_owned());
+}
+
+/// Forget pending rejections and attribution (navigation replaced the
+/// page).
+pub fn clear() {
+    SOURCE_URL.with(|source| source.borrow_mut().clear());
+    UNHANDLED.with(|unhandled| unhandled.borrow_mut().clear());
+}
+
+/// Structure `error`, fire the global `onerror` handler, and — unless
+/// the handler returned `true` to mark it handled — report it to the
+/// devtools console.
+pub fn report(context: &mut Context, error: &boa_engine::JsError) -> ScriptError {
+    let structured = structure(context, error);
+    let handled = fire_onerror(context, error, &structured).unwrap_or(false);
+    if !handled {
+        report_to_console(&structured.message, &structured);
+    }
+    structured
+}
+
+/// Fire `onunhandledrejection` for every promise still rejected and
+/// unhandled, then report the unsuppressed ones. Called once per
+/// [`pump`](super::JsRuntime::pump) tick, after the microtask
+/// checkpoint — a handler attached in a microtask counts as handling.
+pub fn pump(context: &mut Context) {
+    let pending = UNHANDLED.with(|unhandled| std::mem::take(&mut *unhandled.borrow_mut()));
+    for promise in pending {
+        let PromiseState::Rejected(reason) = JsPromise::from_object(promise.clone())
+            .map(|p| p.state())
+            .unwrap_or(PromiseState::Pending)
+        else {
+            continue;
+        };
+        let suppressed = fire_unhandled_rejection(context, &promise, &reason).unwrap_or(false);
+        if !suppressed {
+            let structured = ScriptError {
+                message: format!("Uncaught (in promise) {}", reason.display()),
+                source_url: SOURCE_URL.with(|source| source.borrow().clone()),
+                line: None,
+                column: None,
+                stack: stack_of(&reason, context),
+            };
+            report_to_console(&structured.message, &structured);
+        }
+        context.run_jobs();
+    }
+}
+
+/// Pull message, position, and stack out of a Boa error.
+fn structure(context: &mut Context, error: &boa_engine::JsError) -> ScriptError {
+    let message = error.to_string();
+    let (line, column) = parse_position(&message);
+    let stack = stack_of(&error.to_opaque(context), context);
+    ScriptError {
+        message,
+        source_url: SOURCE_URL.with(|source| source.borrow().clone()),
+        line,
+        column,
+        stack,
+    }
+}
+
+/// `onerror(message, source, lineno, colno, error)`; a `true` return
+/// suppresses default reporting, per spec.
+fn fire_onerror(
+    context: &mut Context,
+    error: &boa_engine::JsError,
+    structured: &ScriptError,
+) -> boa_engine::JsResult<bool> {
+    let handler = context.global_object().get(js_string!("onerror"), context)?;
+    let Some(handler) = handler.as_callable() else {
+        return Ok(false);
+    };
+    let args = [
+        boa_engine::JsString::from(structured.message.as_str()).into(),
+        boa_engine::JsString::from(structured.source_url.as_str()).into(),
+        structured.line.map_or(JsValue::undefined(), |l| l.into()),
+        structured.column.map_or(JsValue::undefined(), |c| c.into()),
+        error.to_opaque(context),
+    ];
+    let result = handler.call(&JsValue::undefined(), &args, context);
+    Ok(result.map(|v| v.to_boolean()).unwrap_or(false))
+}
+
+/// `onunhandledrejection` with a `{type, reason, promise}` event; calling
+/// `preventDefault` (or returning `true`) suppresses default reporting.
+fn fire_unhandled_rejection(
+    context: &mut Context,
+    promise: &JsObject,
+    reason: &JsValue,
+) -> boa_engine::JsResult<bool> {
+    let handler = context
+        .global_object()
+        .get(js_string!("onunhandledrejection"), context)?;
+    let Some(handler) = handler.as_callable() else {
+        return Ok(false);
+    };
+    let event = JsObject::with_null_proto();
+    event.set(js_string!("type"), js_string!("unhandledrejection"), false, context)?;
+    event.set(js_string!("reason"), reason.clone(), false, context)?;
+    event.set(js_string!("promise"), promise.clone(), false, context)?;
+    event.set(js_string!("defaultPrevented"), false, false, context)?;
+    let prevent = boa_engine::NativeFunction::from_fn_ptr(|this, _, context| {
+        if let Some(event) = this.as_object() {
+            event.set(js_string!("defaultPrevented"), true, false, context)?;
+        }
+        Ok(JsValue::undefined())
+    })
+    .to_js_function(context.realm());
+    event.set(js_string!("preventDefault"), prevent, false, context)?;
+    let returned = handler.call(&JsValue::undefined(), &[event.clone().into()], context);
+    let prevented = event
+        .get(js_string!("defaultPrevented"), context)?
+        .to_boolean();
+    Ok(prevented || returned.map(|v| v.to_boolean()).unwrap_or(false))
+}
+
+/// The error object's `stack` property, when it carries one.
+fn stack_of(value: &JsValue, context: &mut Context) -> Option<String> {
+    let object = value.as_object()?;
+    let stack = object.get(js_string!("stack"), context).ok()?;
+    if stack.is_undefined() || stack.is_null() {
+        return None;
+    }
+    Some(stack.to_string(context).ok()?.to_std_string_escaped())
+}
+
+/// Best-effort line/column out of Boa's error text ("… at line 3, col 7").
+fn parse_position(message: &str) -> (Option<u32>, Option<u32>) {
+    let number_after = |keyword: &str| -> Option<u32> {
+        let at = message.find(keyword)? + keyword.len();
+        let rest = message[at..].trim_start();
+        let digits: String = rest.chars().take_while(char::is_ascii_digit).collect();
+        digits.parse().ok()
+    };
+    (number_after("line"), number_after("col"))
+}
+
+fn report_to_console(text: &str, error: &ScriptError) {
+    let location = match (error.line, error.column) {
+        (Some(line), Some(column)) => Some(format!("{}:{line}:{column}", error.source_url)),
+        (Some(line), None) => Some(format!("{}:{line}", error.source_url)),
+        _ if error.source_url.is_empty() => None,
+        _ => Some(error.source_url.clone()),
+    };
+    crate::devtools::console::report(ConsoleMessage {
+        level: ConsoleLevel::Error,
+        text: text.to_owned(),
+        location,
+    });
+}
@@ -0,0 +1,280 @@
+//! DOM events: listener registration and capture/bubble dispatch.
+//!
+//! Listeners live in a thread-local registry keyed by node, like the
+//! canvas recorder registry. [`dispatch`] runs the three spec phases over
+//! the ancestor chain of the installed document — capture down, target,
+//! bubble up — honouring `stopPropagation` and `preventDefault`, and also
+//! runs `on<type>` attribute handlers so inline `onclick` markup works.
+//! The UI feeds real input through [`crate::ui::events`], which hit-tests
+//! the layout tree and calls [`dispatch`] with the struck node.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use boa_engine::{
+    js_string, Context, JsArgs, JsObject, JsResult, JsString, JsValue, NativeFunction, Source,
+};
+
+use crate::renderer::dom::NodeId;
+
+use super::dom;
+
+struct Listener {
+    event_type: String,
+    callback: JsObject,
+    capture: bool,
+    /// An abort signal from the `{ signal }` option; the listener drops
+    /// out once it fires.
+    signal: Option<u64>,
+}
+
+thread_local! {
+    static LISTENERS: RefCell<HashMap<usize, Vec<Listener>>> = RefCell::new(HashMap::new());
+}
+
+/// Install the `EventTarget` methods on the shared element prototype.
+pub(crate) fn register_methods(proto: &JsObject, context: &mut Context) -> JsResult<()> {
+    method(proto, "addEventListener", add_event_listener, context)?;
+    method(proto, "removeEventListener", remove_event_listener, context)?;
+    method(proto, "dispatchEvent", dispatch_event, context)?;
+    Ok(())
+}
+
+/// Drop every registered listener (navigation replaced the document).
+pub fn clear_listeners() {
+    LISTENERS.with(|listeners| listeners.borrow_mut().clear());
+}
+
+fn add_event_listener(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let node = dom::node_of(this, context)?;
+    let event_type = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    let Some(callback) = args.get_or_undefined(1).as_object().cloned() else {
+        return Ok(JsValue::undefined());
+    };
+    let capture = capture_option(args.get_or_undefined(2), context)?;
+    let signal = signal_option(args.get_or_undefined(2), context);
+    // An already-aborted signal means the listener never registers.
+    if signal.is_some_and(super::abort::is_aborted) {
+        return Ok(JsValue::undefined());
+    }
+    LISTENERS.with(|listeners| {
+        let mut listeners = listeners.borrow_mut();
+        let entries = listeners.entry(node.0).or_default();
+        // Same type/callback/capture registers once, per spec.
+        let duplicate = entries.iter().any(|l| {
+            l.event_type == event_type && l.capture == capture && JsObject::equals(&l.callback, &callback)
+        });
+        if !duplicate {
+            entries.push(Listener {
+                event_type,
+                callback,
+                capture,
+                signal,
+            });
+        }
+    });
+    Ok(JsValue::undefined())
+}
+
+fn remove_event_listener(
+    this: &JsValue,
+    args: &[JsValue],
+    context: &mut Context,
+) -> JsResult<JsValue> {
+    let node = dom::node_of(this, context)?;
+    let event_type = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    let Some(callback) = args.get_or_undefined(1).as_object().cloned() else {
+        return Ok(JsValue::undefined());
+    };
+    let capture = capture_option(args.get_or_undefined(2), context)?;
+    LISTENERS.with(|listeners| {
+        if let Some(entries) = listeners.borrow_mut().get_mut(&node.0) {
+            entries.retain(|l| {
+                !(l.event_type == event_type
+                    && l.capture == capture
+                    && JsObject::equals(&l.callback, &callback))
+            });
+        }
+    });
+    Ok(JsValue::undefined())
+}
+
+/// The `capture` flag from the third listener argument: a boolean, or an
+/// options object with a `capture` member.
+fn capture_option(value: &JsValue, context: &mut Context) -> JsResult<bool> {
+    if let Some(options) = value.as_object() {
+        return Ok(options.get(js_string!("capture"), context)?.to_boolean());
+    }
+    Ok(value.to_boolean())
+}
+
+/// The `signal` member of an options-object third argument, if present.
+fn signal_option(value: &JsValue, context: &mut Context) -> Option<u64> {
+    let options = value.as_object()?;
+    let signal = options.get(js_string!("signal"), context).ok()?;
+    super::abort::signal_id(&signal, context)
+}
+
+fn dispatch_event(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let node = dom::node_of(this, context)?;
+    let Some(event) = args.get_or_undefined(0).as_object().cloned() else {
+        return Ok(true.into());
+    };
+    let event_type = event.get(js_string!("type"), context)?.to_string(context)?;
+    let not_cancelled = dispatch(
+        context,
+        node,
+        &event_type.to_std_string_escaped(),
+        Vec::new(),
+    )?;
+    Ok(not_cancelled.into())
+}
+
+/// Dispatch an `event_type` event at `target` with `init` extra
+/// properties (`clientX`, `key`, …). Returns false when a handler called
+/// `preventDefault`, so the caller can suppress the default action.
+pub fn dispatch(
+    context: &mut Context,
+    target: NodeId,
+    event_type: &str,
+    init: Vec<(&str, JsValue)>,
+) -> JsResult<bool> {
+    let event = build_event(target, event_type, init, context)?;
+    // Root-to-target ancestor chain, target included.
+    let mut path = dom::with_document(|document| {
+        let mut chain = vec![target];
+        let mut current = document.parent(target);
+        while let Some(node) = current {
+            if document.element(node).is_some() {
+                chain.push(node);
+            }
+            current = document.parent(node);
+        }
+        chain
+    });
+    path.reverse();
+
+    // Capture phase, ancestors top-down.
+    for &node in &path[..path.len().saturating_sub(1)] {
+        invoke_listeners(context, node, event_type, &event, true)?;
+        if stopped(&event, context)? {
+            return default_allowed(&event, context);
+        }
+    }
+    // Target phase: capture and bubble listeners in registration order,
+    // plus the inline attribute handler.
+    invoke_listeners(context, target, event_type, &event, true)?;
+    if !stopped(&event, context)? {
+        invoke_listeners(context, target, event_type, &event, false)?;
+    }
+    // Bubble phase, ancestors bottom-up.
+    for &node in path[..path.len().saturating_sub(1)].iter().rev() {
+        if stopped(&event, context)? {
+            break;
+        }
+        invoke_listeners(context, node, event_type, &event, false)?;
+    }
+    // The dispatch was one task: microtask checkpoint before control
+    // returns to the caller (and from there to paint).
+    context.run_jobs();
+    default_allowed(&event, context)
+}
+
+fn build_event(
+    target: NodeId,
+    event_type: &str,
+    init: Vec<(&str, JsValue)>,
+    context: &mut Context,
+) -> JsResult<JsObject> {
+    let event = JsObject::with_null_proto();
+    event.set(js_string!("type"), JsString::from(event_type), false, context)?;
+    event.set(js_string!("bubbles"), true, false, context)?;
+    event.set(js_string!("isTrusted"), true, false, context)?;
+    event.set(js_string!("defaultPrevented"), false, false, context)?;
+    event.set(js_string!("__stopped"), false, false, context)?;
+    let wrapper = dom::wrap_element(target, context)?;
+    event.set(js_string!("target"), wrapper, false, context)?;
+    for (name, value) in init {
+        event.set(JsString::from(name), value, false, context)?;
+    }
+    method(&event, "stopPropagation", |this, _, context| {
+        if let Some(event) = this.as_object() {
+            event.set(js_string!("__stopped"), true, false, context)?;
+        }
+        Ok(JsValue::undefined())
+    }, context)?;
+    method(&event, "preventDefault", |this, _, context| {
+        if let Some(event) = this.as_object() {
+            event.set(js_string!("defaultPrevented"), true, false, context)?;
+        }
+        Ok(JsValue::undefined())
+    }, context)?;
+    Ok(event)
+}
+
+/// Run `node`'s listeners for one phase. Bubble-phase invocation also
+/// runs the element's `on<type>` attribute, compiled on the spot.
+fn invoke_listeners(
+    context: &mut Context,
+    node: NodeId,
+    event_type: &str,
+    event: &JsObject,
+    capture: bool,
+) -> JsResult<()> {
+    let current = dom::wrap_element(node, context)?;
+    event.set(js_string!("currentTarget"), current.clone(), false, context)?;
+    let callbacks: Vec<JsObject> = LISTENERS.with(|listeners| {
+        let mut listeners = listeners.borrow_mut();
+        let Some(entries) = listeners.get_mut(&node.0) else {
+            return Vec::new();
+        };
+        // Listeners whose abort signal fired are gone, per spec.
+        entries.retain(|l| !l.signal.is_some_and(super::abort::is_aborted));
+        entries
+            .iter()
+            .filter(|l| l.event_type == event_type && l.capture == capture)
+            .map(|l| l.callback.clone())
+            .collect()
+    });
+    for callback in callbacks {
+        // A throwing handler doesn't stop the rest, per spec.
+        let _ = callback.call(&current.clone().into(), &[event.clone().into()], context);
+    }
+    if !capture {
+        let attribute = dom::with_document(|document| {
+            document
+                .element(node)
+                .and_then(|element| element.attr(&format!("on{event_type}")).map(str::to_owned))
+        });
+        if let Some(source) = attribute {
+            let _ = context.eval(Source::from_bytes(&source));
+        }
+    }
+    Ok(())
+}
+
+fn stopped(event: &JsObject, context: &mut Context) -> JsResult<bool> {
+    Ok(event.get(js_string!("__stopped"), context)?.to_boolean())
+}
+
+fn default_allowed(event: &JsObject, context: &mut Context) -> JsResult<bool> {
+    Ok(!event
+        .get(js_string!("defaultPrevented"), context)?
+        .to_boolean())
+}
+
+/// Install a native method on `object`.
+fn method(
+    object: &JsObject,
+    name: &str,
+    function: fn(&JsValue, &[JsValue], &mut Context) -> JsResult<JsValue>,
+    context: &mut Context,
+) -> JsResult<()> {
+    object.set(
+        JsString::from(name),
+        NativeFunction::from_fn_ptr(function).to_js_function(context.realm()),
+        false,
+        context,
+    )?;
+    Ok(())
+}
@@ -0,0 +1,336 @@
+//! `fetch()` binding over [`crate::network::NetworkStack`].
+//!
+//! Requests go through the full stack — cache, interceptors, cookies,
+//! devtools capture — never a side channel. Each call spawns a tokio task
+//! holding the stack; the result crosses back into the single-threaded
+//! Boa context through a oneshot drained by [`pump`], which settles the
+//! returned `Promise` with a spec-shaped `Response` object.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use boa_engine::object::builtins::{JsArrayBuffer, JsFunction, JsPromise};
+use boa_engine::{
+    js_string, Context, JsArgs, JsObject, JsResult, JsString, JsValue, NativeFunction,
+};
+use tokio::sync::oneshot;
+
+use crate::network::request::{Method, Request};
+use crate::network::response::Response;
+use crate::network::{NetworkError, NetworkStack};
+use crate::renderer::loader::resolve_url;
+
+/// What the page's `fetch` runs against: the stack, and the document URL
+/// relative requests resolve from.
+struct FetchEnvironment {
+    stack: Arc<NetworkStack>,
+    base_url: String,
+}
+
+struct PendingFetch {
+    result: oneshot::Receiver<Result<Response, NetworkError>>,
+    resolve: JsFunction,
+    reject: JsFunction,
+    /// The request's abort signal, when the init carried one.
+    signal: Option<u64>,
+}
+
+thread_local! {
+    static ENVIRONMENT: RefCell<Option<FetchEnvironment>> = const { RefCell::new(None) };
+    static PENDING: RefCell<Vec<PendingFetch>> = const { RefCell::new(Vec::new()) };
+    // Response bodies, keyed by the `__bodyId` on the wrapper, so the
+    // body readers can be called any number of times.
+    static BODIES: RefCell<HashMap<u64, Vec<u8>>> = RefCell::new(HashMap::new());
+    static NEXT_BODY: RefCell<u64> = const { RefCell::new(1) };
+}
+
+/// Install the `fetch` global.
+pub fn register(context: &mut Context) {
+    context
+        .register_global_callable(js_string!("fetch"), 1, NativeFunction::from_fn_ptr(fetch))
+        .expect("registering fetch");
+}
+
+/// Point the binding at the page's network stack and document URL.
+/// Called on every navigation commit; until then `fetch` rejects.
+pub fn configure(stack: Arc<NetworkStack>, base_url: &str) {
+    ENVIRONMENT.with(|env| {
+        *env.borrow_mut() = Some(FetchEnvironment {
+            stack,
+            base_url: base_url.to_owned(),
+        });
+    });
+}
+
+/// The configured stack and document URL, for bindings that share
+/// fetch's network path (XHR).
+pub(crate) fn environment() -> Option<(Arc<NetworkStack>, String)> {
+    ENVIRONMENT.with(|env| {
+        env.borrow()
+            .as_ref()
+            .map(|env| (env.stack.clone(), env.base_url.clone()))
+    })
+}
+
+/// Drop in-flight fetches and stored bodies (navigation replaced the
+/// page). The promises they would settle are gone with the old realm.
+pub fn clear() {
+    PENDING.with(|pending| pending.borrow_mut().clear());
+    BODIES.with(|bodies| bodies.borrow_mut().clear());
+}
+
+fn fetch(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let input = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    let (promise, resolve, reject) = JsPromise::new_pending(context);
+
+    let Some((stack, url)) = ENVIRONMENT.with(|env| {
+        env.borrow()
+            .as_ref()
+            .map(|env| (env.stack.clone(), resolve_url(&env.base_url, &input)))
+    }) else {
+        let error = JsString::from("fetch: no document loaded");
+        reject.call(&JsValue::undefined(), &[error.into()], context)?;
+        return Ok(promise.into());
+    };
+
+    let mut request = Request::get(url);
+    let mut signal = None;
+    if let Some(init) = args.get_or_undefined(1).as_object() {
+        apply_init(&mut request, init, context)?;
+        let value = init.get(js_string!("signal"), context)?;
+        signal = super::abort::signal_id(&value, context);
+    }
+    if signal.is_some_and(super::abort::is_aborted) {
+        // Already aborted: reject without touching the network, per spec.
+        let error = JsString::from("AbortError: the operation was aborted");
+        reject.call(&JsValue::undefined(), &[error.into()], context)?;
+        return Ok(promise.into());
+    }
+
+    let (result_tx, result_rx) = oneshot::channel();
+    tokio::spawn(async move {
+        let _ = result_tx.send(stack.fetch(request).await);
+    });
+    PENDING.with(|pending| {
+        pending.borrow_mut().push(PendingFetch {
+            result: result_rx,
+            resolve,
+            reject,
+            signal,
+        });
+    });
+    Ok(promise.into())
+}
+
+/// Apply the `init` argument: `method`, `headers`, `body`.
+fn apply_init(request: &mut Request, init: &JsObject, context: &mut Context) -> JsResult<()> {
+    let method = init.get(js_string!("method"), context)?;
+    if !method.is_undefined() {
+        let name = method.to_string(context)?.to_std_string_escaped();
+        request.method = parse_method(&name);
+    }
+    if let Some(headers) = init.get(js_string!("headers"), context)?.as_object().cloned() {
+        for key in headers.own_property_keys(context)? {
+            let value = headers.get(key.clone(), context)?;
+            request.headers.set(
+                &key.to_string(),
+                &value.to_string(context)?.to_std_string_escaped(),
+            );
+        }
+    }
+    let body = init.get(js_string!("body"), context)?;
+    if !body.is_undefined() && !body.is_null() {
+        let text = body.to_string(context)?.to_std_string_escaped();
+        request.body = Some(text.into_bytes());
+    }
+    Ok(())
+}
+
+pub(crate) fn parse_method(name: &str) -> Method {
+    match name.to_ascii_uppercase().as_str() {
+        "HEAD" => Method::Head,
+        "POST" => Method::Post,
+        "PUT" => Method::Put,
+        "DELETE" => Method::Delete,
+        "OPTIONS" => Method::Options,
+        "PATCH" => Method::Patch,
+        _ => Method::Get,
+    }
+}
+
+/// Settle the promises of fetches whose network task has finished.
+/// Driven from [`JsRuntime::pump`](super::JsRuntime::pump).
+pub fn pump(context: &mut Context) {
+    let mut settled: Vec<(Result<Response, NetworkError>, JsFunction, JsFunction)> = Vec::new();
+    PENDING.with(|pending| {
+        pending.borrow_mut().retain_mut(|fetch| {
+            if fetch.signal.is_some_and(super::abort::is_aborted) {
+                // Aborted mid-flight: reject now, let the task finish
+                // into a dropped receiver.
+                settled.push((
+                    Err(NetworkError::ConnectionFailed(
+                        "AbortError: the operation was aborted".into(),
+                    )),
+                    fetch.resolve.clone(),
+                    fetch.reject.clone(),
+                ));
+                return false;
+            }
+            match fetch.result.try_recv() {
+                Ok(result) => {
+                    settled.push((result, fetch.resolve.clone(), fetch.reject.clone()));
+                    false
+                }
+                Err(oneshot::error::TryRecvError::Empty) => true,
+                // Task panicked or was dropped: surface as a network error.
+                Err(oneshot::error::TryRecvError::Closed) => {
+                    settled.push((
+                        Err(NetworkError::ConnectionFailed("fetch task dropped".into())),
+                        fetch.resolve.clone(),
+                        fetch.reject.clone(),
+                    ));
+                    false
+                }
+            }
+        });
+    });
+
+    for (result, resolve, reject) in settled {
+        let outcome = match result {
+            Ok(response) => build_response(&response, context)
+                .map(JsValue::from)
+                .map(|value| (resolve.clone(), value)),
+            Err(error) => Ok((reject.clone(), JsString::from(error.to_string()).into())),
+        };
+        if let Ok((function, value)) = outcome {
+            let _ = function.call(&JsValue::undefined(), &[value], context);
+        }
+        // Each settled fetch is a task; checkpoint its reactions.
+        context.run_jobs();
+    }
+}
+
+/// A spec-shaped `Response`: `ok`/`status`/`url`/`headers` plus the body
+/// readers, each returning an already-resolved promise.
+fn build_response(response: &Response, context: &mut Context) -> JsResult<JsObject> {
+    let object = JsObject::with_null_proto();
+    object.set(js_string!("ok"), response.is_success(), false, context)?;
+    object.set(js_string!("status"), response.status, false, context)?;
+    object.set(js_string!("redirected"), false, false, context)?;
+    object.set(
+        js_string!("url"),
+        JsString::from(response.url.as_str()),
+        false,
+        context,
+    )?;
+    object.set(
+        js_string!("headers"),
+        build_headers(response, context)?,
+        false,
+        context,
+    )?;
+    let body_id = NEXT_BODY.with(|next| {
+        let mut next = next.borrow_mut();
+        let id = *next;
+        *next += 1;
+        id
+    });
+    BODIES.with(|bodies| {
+        bodies.borrow_mut().insert(body_id, response.body.clone());
+    });
+    object.set(js_string!("__bodyId"), body_id, false, context)?;
+
+    method(&object, "text", |this, _, context| {
+        let text = body_text(this, context)?;
+        Ok(JsPromise::resolve(JsString::from(text), context).into())
+    }, context)?;
+    method(&object, "json", |this, _, context| {
+        let text = body_text(this, context)?;
+        let json = context.global_object().get(js_string!("JSON"), context)?;
+        let parse = json
+            .as_object()
+            .map(|json| json.get(js_string!("parse"), context))
+            .transpose()?
+            .unwrap_or_default();
+        match parse.as_callable() {
+            Some(parse) => {
+                let promise = match parse.call(&json, &[JsString::from(text).into()], context) {
+                    Ok(value) => JsPromise::resolve(value, context),
+                    Err(error) => JsPromise::reject(error.to_opaque(context), context),
+                };
+                Ok(promise.into())
+            }
+            None => Ok(JsValue::undefined()),
+        }
+    }, context)?;
+    method(&object, "arrayBuffer", |this, _, context| {
+        let bytes = body_bytes(this, context)?;
+        let buffer = JsArrayBuffer::from_byte_block(bytes, context)?;
+        Ok(JsPromise::resolve(buffer, context).into())
+    }, context)?;
+    Ok(object)
+}
+
+/// The stored body for a `Response` wrapper, located by its `__bodyId`.
+fn body_bytes(this: &JsValue, context: &mut Context) -> JsResult<Vec<u8>> {
+    let id = this
+        .as_object()
+        .map(|o| o.get(js_string!("__bodyId"), context))
+        .transpose()?
+        .unwrap_or_default()
+        .to_number(context)? as u64;
+    Ok(BODIES.with(|bodies| bodies.borrow().get(&id).cloned().unwrap_or_default()))
+}
+
+/// The response body decoded as UTF-8.
+fn body_text(this: &JsValue, context: &mut Context) -> JsResult<String> {
+    Ok(String::from_utf8_lossy(&body_bytes(this, context)?).into_owned())
+}
+
+/// Header names lowercased as properties, plus a case-insensitive `get`.
+fn build_headers(response: &Response, context: &mut Context) -> JsResult<JsObject> {
+    let headers = JsObject::with_null_proto();
+    for (name, value) in response.headers.iter() {
+        headers.set(
+            JsString::from(name.to_ascii_lowercase()),
+            JsString::from(value),
+            false,
+            context,
+        )?;
+    }
+    method(&headers, "get", |this, args, context| {
+        let name = args
+            .get_or_undefined(0)
+            .to_string(context)?
+            .to_std_string_escaped()
+            .to_ascii_lowercase();
+        let value = this
+            .as_object()
+            .map(|o| o.get(JsString::from(name), context))
+            .transpose()?
+            .unwrap_or_default();
+        Ok(if value.is_undefined() {
+            JsValue::null()
+        } else {
+            value
+        })
+    }, context)?;
+    Ok(headers)
+}
+
+/// Install a native method on `object`.
+fn method(
+    object: &JsObject,
+    name: &str,
+    function: fn(&JsValue, &[JsValue], &mut Context) -> JsResult<JsValue>,
+    context: &mut Context,
+) -> JsResult<()> {
+    object.set(
+        JsString::from(name),
+        NativeFunction::from_fn_ptr(function).to_js_function(context.realm()),
+        false,
+        context,
+    )?;
+    Ok(())
+}
@@ -0,0 +1,354 @@
+//! `FormData` and JS-driven form submission.
+//!
+//! `FormData` keeps its entries in a registry keyed by `__formDataId`,
+//! like `URLSearchParams`. Constructing one from a form element walks
+//! the installed document for the form's submittable controls — the
+//! same collection `form.submit()` uses, so script-built and native
+//! submissions serialize identically. POST submissions build their body
+//! through [`crate::network::form`] and leave through the navigation
+//! hand-off in [`super::window`], never a side channel.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use boa_engine::object::builtins::JsArray;
+use boa_engine::{
+    js_string, Context, JsArgs, JsNativeError, JsObject, JsResult, JsString, JsValue,
+    NativeFunction,
+};
+
+use crate::network::form::UrlEncodedForm;
+use crate::renderer::dom::{Document, NodeId};
+use crate::renderer::loader::resolve_url;
+
+use super::{dom, fetch, window};
+
+thread_local! {
+    static FORM_DATA: RefCell<HashMap<u64, Vec<(String, String)>>> = RefCell::new(HashMap::new());
+    static NEXT_ID: RefCell<u64> = const { RefCell::new(1) };
+}
+
+/// Install the `FormData` constructor.
+pub fn register(context: &mut Context) {
+    context
+        .register_global_callable(
+            js_string!("FormData"),
+            0,
+            NativeFunction::from_fn_ptr(construct),
+        )
+        .expect("registering FormData");
+}
+
+/// Drop every registry entry (navigation replaced the page).
+pub fn clear() {
+    FORM_DATA.with(|data| data.borrow_mut().clear());
+}
+
+/// Install the form methods on the shared element prototype: `submit()`
+/// and the `elements` accessor.
+pub(crate) fn install_form_methods(proto: &JsObject, context: &mut Context) -> JsResult<()> {
+    method(proto, "submit", submit, context)?;
+    let getter = NativeFunction::from_fn_ptr(elements_get).to_js_function(context.realm());
+    proto.define_property_or_throw(
+        js_string!("elements"),
+        boa_engine::property::PropertyDescriptor::builder()
+            .get(getter)
+            .enumerable(false)
+            .configurable(true),
+        context,
+    )?;
+    Ok(())
+}
+
+fn construct(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let form = args.get_or_undefined(0);
+    let entries = if form.is_undefined() {
+        Vec::new()
+    } else {
+        let node = dom::node_of(form, context)?;
+        dom::with_document(|document| form_entries(document, node))
+    };
+    Ok(build_form_data(entries, context)?.into())
+}
+
+/// `form.submit()`: serialize the controls and hand the UI a navigation
+/// — a query-string GET, or a POST whose body goes through the same
+/// [`UrlEncodedForm`] builder native submission uses.
+fn submit(this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let node = dom::node_of(this, context)?;
+    let (action, http_method, entries) = dom::with_document(|document| {
+        let element = document.element(node);
+        (
+            element
+                .and_then(|e| e.attr("action"))
+                .map(str::to_owned)
+                .unwrap_or_default(),
+            element
+                .and_then(|e| e.attr("method"))
+                .map(|m| m.to_ascii_lowercase())
+                .unwrap_or_default(),
+            form_entries(document, node),
+        )
+    });
+    let base = fetch::environment().map(|(_, base)| base).unwrap_or_default();
+    let target = if action.is_empty() {
+        base
+    } else {
+        resolve_url(&base, &action)
+    };
+    if http_method == "post" {
+        let mut form = UrlEncodedForm::new();
+        for (name, value) in &entries {
+            form = form.field(name, value);
+        }
+        window::request_navigation(window::NavigationRequest::Submit(form.into_request(&target)));
+    } else {
+        // GET: the entries become the query string, replacing any there.
+        let stripped = target.split(['?', '#']).next().unwrap_or(&target);
+        let query = crate::url::serialize_query(&entries);
+        window::request_navigation(window::NavigationRequest::Load(format!("{stripped}?{query}")));
+    }
+    Ok(JsValue::undefined())
+}
+
+/// `form.elements`: the form's controls as element wrappers, in
+/// document order.
+fn elements_get(this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let node = dom::node_of(this, context)?;
+    let controls: Vec<NodeId> = dom::with_document(|document| {
+        document
+            .descendants(node)
+            .into_iter()
+            .filter(|&control| {
+                document.element(control).is_some_and(|element| {
+                    matches!(
+                        element.tag_name.as_str(),
+                        "input" | "textarea" | "select" | "button"
+                    )
+                })
+            })
+            .collect()
+    });
+    let mut wrapped = Vec::new();
+    for control in controls {
+        wrapped.push(JsValue::from(dom::wrap_element(control, context)?));
+    }
+    Ok(JsArray::from_iter(wrapped, context).into())
+}
+
+/// The form's submittable entries: named, non-disabled controls, with
+/// the per-type rules pages rely on (unchecked boxes skipped, buttons
+/// and file inputs never submitted from script).
+fn form_entries(document: &Document, form: NodeId) -> Vec<(String, String)> {
+    let mut entries = Vec::new();
+    for node in document.descendants(form) {
+        let Some(element) = document.element(node) else {
+            continue;
+        };
+        let Some(name) = element.attr("name") else {
+            continue;
+        };
+        if name.is_empty() || element.attr("disabled").is_some() {
+            continue;
+        }
+        match element.tag_name.as_str() {
+            "input" => {
+                let kind = element
+                    .attr("type")
+                    .map(|t| t.to_ascii_lowercase())
+                    .unwrap_or_default();
+                match kind.as_str() {
+                    "submit" | "button" | "reset" | "image" | "file" => {}
+                    "checkbox" | "radio" => {
+                        if element.attr("checked").is_some() {
+                            entries.push((
+                                name.to_owned(),
+                                element.attr("value").unwrap_or("on").to_owned(),
+                            ));
+                        }
+                    }
+                    _ => entries.push((
+                        name.to_owned(),
+                        element.attr("value").unwrap_or_default().to_owned(),
+                    )),
+                }
+            }
+            "textarea" => entries.push((name.to_owned(), document.text_content(node))),
+            "select" => {
+                if let Some(value) = selected_option(document, node) {
+                    entries.push((name.to_owned(), value));
+                }
+            }
+            _ => {}
+        }
+    }
+    entries
+}
+
+/// The submitted value of a `<select>`: the first `selected` option,
+/// else the first option, per the parser's single-select model.
+fn selected_option(document: &Document, select: NodeId) -> Option<String> {
+    let options: Vec<NodeId> = document
+        .descendants(select)
+        .into_iter()
+        .filter(|&node| {
+            document
+                .element(node)
+                .is_some_and(|element| element.tag_name == "option")
+        })
+        .collect();
+    let chosen = options
+        .iter()
+        .copied()
+        .find(|&node| {
+            document
+                .element(node)
+                .is_some_and(|element| element.attr("selected").is_some())
+        })
+        .or_else(|| options.first().copied())?;
+    let element = document.element(chosen)?;
+    Some(match element.attr("value") {
+        Some(value) => value.to_owned(),
+        None => document.text_content(chosen).trim().to_owned(),
+    })
+}
+
+/// Build a `FormData` wrapper around `entries`.
+fn build_form_data(entries: Vec<(String, String)>, context: &mut Context) -> JsResult<JsObject> {
+    let id = NEXT_ID.with(|next| {
+        let mut next = next.borrow_mut();
+        let id = *next;
+        *next += 1;
+        id
+    });
+    FORM_DATA.with(|data| data.borrow_mut().insert(id, entries));
+    let object = JsObject::with_null_proto();
+    object.set(js_string!("__formDataId"), id, false, context)?;
+    method(&object, "append", data_append, context)?;
+    method(&object, "get", data_get, context)?;
+    method(&object, "getAll", data_get_all, context)?;
+    method(&object, "set", data_set, context)?;
+    method(&object, "delete", data_delete, context)?;
+    method(&object, "has", data_has, context)?;
+    Ok(object)
+}
+
+fn data_append(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let name = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    let value = args.get_or_undefined(1).to_string(context)?.to_std_string_escaped();
+    with_entries_mut(this, context, |entries| entries.push((name.clone(), value.clone())))?;
+    Ok(JsValue::undefined())
+}
+
+fn data_get(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let name = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    let value = with_entries(this, context, |entries| {
+        entries
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, v)| v.clone())
+    })?;
+    match value {
+        Some(value) => Ok(JsString::from(value).into()),
+        None => Ok(JsValue::null()),
+    }
+}
+
+fn data_get_all(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let name = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    let values = with_entries(this, context, |entries| {
+        entries
+            .iter()
+            .filter(|(n, _)| *n == name)
+            .map(|(_, v)| JsValue::from(JsString::from(v.as_str())))
+            .collect::<Vec<_>>()
+    })?;
+    Ok(JsArray::from_iter(values, context).into())
+}
+
+fn data_set(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let name = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    let value = args.get_or_undefined(1).to_string(context)?.to_std_string_escaped();
+    with_entries_mut(this, context, |entries| {
+        // Replace at the first match's position, drop the rest, per spec.
+        match entries.iter().position(|(n, _)| *n == name) {
+            Some(index) => {
+                entries.retain(|(n, _)| *n != name);
+                entries.insert(index.min(entries.len()), (name.clone(), value.clone()));
+            }
+            None => entries.push((name.clone(), value.clone())),
+        }
+    })?;
+    Ok(JsValue::undefined())
+}
+
+fn data_delete(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let name = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    with_entries_mut(this, context, |entries| entries.retain(|(n, _)| *n != name))?;
+    Ok(JsValue::undefined())
+}
+
+fn data_has(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let name = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    let present = with_entries(this, context, |entries| entries.iter().any(|(n, _)| *n == name))?;
+    Ok(present.into())
+}
+
+fn with_entries<R>(
+    this: &JsValue,
+    context: &mut Context,
+    read: impl FnOnce(&Vec<(String, String)>) -> R,
+) -> JsResult<R> {
+    let id = form_data_id(this, context)?;
+    FORM_DATA.with(|data| {
+        data.borrow()
+            .get(&id)
+            .map(read)
+            .ok_or_else(missing_form_data)
+    })
+}
+
+fn with_entries_mut(
+    this: &JsValue,
+    context: &mut Context,
+    change: impl FnOnce(&mut Vec<(String, String)>),
+) -> JsResult<()> {
+    let id = form_data_id(this, context)?;
+    FORM_DATA.with(|data| {
+        data.borrow_mut()
+            .get_mut(&id)
+            .map(change)
+            .ok_or_else(missing_form_data)
+    })
+}
+
+fn missing_form_data() -> boa_engine::JsError {
+    JsNativeError::typ()
+        .with_message("FormData: detached from its registry")
+        .into()
+}
+
+fn form_data_id(this: &JsValue, context: &mut Context) -> JsResult<u64> {
+    let id = this
+        .as_object()
+        .map(|o| o.get(js_string!("__formDataId"), context))
+        .transpose()?
+        .unwrap_or_default();
+    Ok(id.to_number(context)? as u64)
+}
+
+/// Install a native method on `object`.
+fn method(
+    object: &JsObject,
+    name: &str,
+    function: fn(&JsValue, &[JsValue], &mut Context) -> JsResult<JsValue>,
+    context: &mut Context,
+) -> JsResult<()> {
+    object.set(
+        JsString::from(name),
+        NativeFunction::from_fn_ptr(function).to_js_function(context.realm()),
+        false,
+        context,
+    )?;
+    Ok(())
+}
@@ -0,0 +1,234 @@
+//! The History API: `pushState`/`replaceState` and `popstate`.
+//!
+//! The session history is a thread-local entry list — URL plus the
+//! page's state object — like the other per-page registries. Real
+//! navigations append entries through [`record_navigation`];
+//! `pushState`/`replaceState` grow or rewrite the list without touching
+//! the network, and the UI picks the new address up via
+//! [`take_url_change`] so the URL bar follows SPA navigations without a
+//! refetch. Back/forward traversal moves the index and fires the global
+//! `onpopstate` handler with the entry's state.
+
+use std::cell::RefCell;
+
+use boa_engine::{
+    js_string, Context, JsArgs, JsObject, JsResult, JsString, JsValue, NativeFunction,
+};
+
+use crate::renderer::loader::resolve_url;
+
+struct Entry {
+    url: String,
+    state: JsValue,
+}
+
+#[derive(Default)]
+struct SessionHistory {
+    entries: Vec<Entry>,
+    /// Index of the current entry; meaningless while `entries` is empty.
+    index: usize,
+}
+
+thread_local! {
+    static HISTORY: RefCell<SessionHistory> = RefCell::new(SessionHistory::default());
+    // Set when script or traversal changed the current URL; the UI
+    // drains it into the address bar.
+    static URL_CHANGE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Install the `history` global.
+pub fn register(context: &mut Context) {
+    let object = JsObject::with_null_proto();
+    method(&object, "pushState", push_state, context).expect("installing history method");
+    method(&object, "replaceState", replace_state, context).expect("installing history method");
+    method(&object, "back", |_, _, context| traverse_js(-1, context), context)
+        .expect("installing history method");
+    method(&object, "forward", |_, _, context| traverse_js(1, context), context)
+        .expect("installing history method");
+    method(
+        &object,
+        "go",
+        |_, args, context| {
+            let delta = args.get_or_undefined(0).to_number(context)? as i64;
+            traverse_js(delta, context)
+        },
+        context,
+    )
+    .expect("installing history method");
+    sync(&object, context).expect("initialising history properties");
+    context
+        .register_global_property(
+            js_string!("history"),
+            object,
+            boa_engine::property::Attribute::all(),
+        )
+        .expect("registering history global");
+}
+
+/// Record a real (network) navigation to `url`: the forward entries are
+/// discarded and the new entry becomes current. Called on every commit.
+pub fn record_navigation(url: &str) {
+    HISTORY.with(|history| {
+        let mut history = history.borrow_mut();
+        if !history.entries.is_empty() {
+            let keep = history.index + 1;
+            history.entries.truncate(keep);
+        }
+        history.entries.push(Entry {
+            url: url.to_owned(),
+            state: JsValue::undefined(),
+        });
+        history.index = history.entries.len() - 1;
+    });
+}
+
+/// The URL the page navigated itself to (pushState or traversal), if
+/// any since the last call. The UI applies it to the address bar; no
+/// fetch happens.
+pub fn take_url_change() -> Option<String> {
+    URL_CHANGE.with(|change| change.borrow_mut().take())
+}
+
+/// Move `delta` entries through the session history, firing `popstate`.
+/// Returns the new current URL, or `None` when the move ran off either
+/// end (the UI then falls back to a real navigation, e.g. leaving the
+/// page).
+pub fn traverse(context: &mut Context, delta: i64) -> Option<String> {
+    let moved = HISTORY.with(|history| {
+        let mut history = history.borrow_mut();
+        let target = history.index as i64 + delta;
+        if history.entries.is_empty() || target < 0 || target as usize >= history.entries.len() {
+            return None;
+        }
+        history.index = target as usize;
+        let entry = &history.entries[history.index];
+        Some((entry.url.clone(), entry.state.clone()))
+    });
+    let (url, state) = moved?;
+    URL_CHANGE.with(|change| *change.borrow_mut() = Some(url.clone()));
+    let _ = fire_popstate(&state, context);
+    let _ = sync_global(context);
+    Some(url)
+}
+
+fn traverse_js(delta: i64, context: &mut Context) -> JsResult<JsValue> {
+    traverse(context, delta);
+    Ok(JsValue::undefined())
+}
+
+fn push_state(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    change_state(args, context, false)
+}
+
+fn replace_state(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    change_state(args, context, true)
+}
+
+/// `pushState`/`replaceState` common path; the title argument is
+/// accepted and ignored, as everywhere else.
+fn change_state(args: &[JsValue], context: &mut Context, replace: bool) -> JsResult<JsValue> {
+    let state = args.get_or_undefined(0).clone();
+    let url_arg = args.get_or_undefined(2);
+    HISTORY.with(|history| {
+        let mut history = history.borrow_mut();
+        let current_url = history
+            .entries
+            .get(history.index)
+            .map(|e| e.url.clone())
+            .unwrap_or_default();
+        let url = if url_arg.is_undefined() || url_arg.is_null() {
+            current_url.clone()
+        } else {
+            let href = url_arg.to_string(context)?.to_std_string_escaped();
+            resolve_url(&current_url, &href)
+        };
+        if replace {
+            match history.entries.get_mut(history.index) {
+                Some(entry) => {
+                    entry.url = url.clone();
+                    entry.state = state;
+                }
+                None => {
+                    history.entries.push(Entry {
+                        url: url.clone(),
+                        state,
+                    });
+                    history.index = 0;
+                }
+            }
+        } else {
+            let keep = if history.entries.is_empty() {
+                0
+            } else {
+                history.index + 1
+            };
+            history.entries.truncate(keep);
+            history.entries.push(Entry {
+                url: url.clone(),
+                state,
+            });
+            history.index = history.entries.len() - 1;
+        }
+        URL_CHANGE.with(|change| *change.borrow_mut() = Some(url));
+        Ok(())
+    })?;
+    sync_global(context)?;
+    Ok(JsValue::undefined())
+}
+
+fn fire_popstate(state: &JsValue, context: &mut Context) -> JsResult<()> {
+    let handler = context
+        .global_object()
+        .get(js_string!("onpopstate"), context)?;
+    let Some(handler) = handler.as_callable() else {
+        return Ok(());
+    };
+    let event = JsObject::with_null_proto();
+    event.set(js_string!("type"), js_string!("popstate"), false, context)?;
+    event.set(js_string!("state"), state.clone(), false, context)?;
+    let _ = handler.call(&JsValue::undefined(), &[event.into()], context);
+    Ok(())
+}
+
+/// Reflect `length` and `state` on the `history` global.
+fn sync_global(context: &mut Context) -> JsResult<()> {
+    let history = context
+        .global_object()
+        .get(js_string!("history"), context)?;
+    match history.as_object() {
+        Some(object) => sync(&object.clone(), context),
+        None => Ok(()),
+    }
+}
+
+fn sync(object: &JsObject, context: &mut Context) -> JsResult<()> {
+    let (length, state) = HISTORY.with(|history| {
+        let history = history.borrow();
+        (
+            history.entries.len().max(1),
+            history
+                .entries
+                .get(history.index)
+                .map_or(JsValue::undefined(), |e| e.state.clone()),
+        )
+    });
+    object.set(js_string!("length"), length, false, context)?;
+    object.set(js_string!("state"), state, false, context)?;
+    Ok(())
+}
+
+/// Install a native method on `object`.
+fn method(
+    object: &JsObject,
+    name: &str,
+    function: fn(&JsValue, &[JsValue], &mut Context) -> JsResult<JsValue>,
+    context: &mut Context,
+) -> JsResult<()> {
+    object.set(
+        JsString::from(name),
+        NativeFunction::from_fn_ptr(function).to_js_function(context.realm()),
+        false,
+        context,
+    )?;
+    Ok(())
+}
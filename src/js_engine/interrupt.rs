@@ -0,0 +1,86 @@
+//! Slow-script interruption: the per-page execution budget and the
+//! handle the UI uses to stop a runaway script.
+//!
+//! Boa has no preemption of its own, so the runtime leans on its loop
+//! iteration limit: a `while (true)` trips the limit instead of hanging
+//! the loading thread, control returns here, and [`tripped`] decides
+//! whether the script was merely long or actually over budget. The
+//! [`InterruptHandle`] side is cross-thread — the UI keeps a clone and
+//! flips it from the "stop this script?" dialog — and is checked at the
+//! same points. Budget state is thread-local like the other binding
+//! registries; the handle is the one shared piece.
+
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How long one script evaluation may run before it counts as slow.
+pub const DEFAULT_BUDGET: Duration = Duration::from_secs(10);
+
+/// Iterations a single loop may run between checkpoints. Large enough
+/// that real pages never see it; small enough that a runaway loop
+/// returns to the host in well under a second.
+pub const LOOP_ITERATION_LIMIT: u64 = 100_000_000;
+
+/// Cross-thread stop switch for a runtime's scripts. The UI holds a
+/// clone and calls [`interrupt`](InterruptHandle::interrupt) when the
+/// user chooses to stop; the runtime checks it at its checkpoints.
+#[derive(Clone, Default)]
+pub struct InterruptHandle {
+    interrupted: Arc<AtomicBool>,
+}
+
+impl InterruptHandle {
+    /// Ask the runtime to abandon the current script.
+    pub fn interrupt(&self) {
+        self.interrupted.store(true, Ordering::Relaxed);
+    }
+
+    /// Re-arm the handle after an interruption was delivered.
+    pub fn clear(&self) {
+        self.interrupted.store(false, Ordering::Relaxed);
+    }
+
+    pub fn is_interrupted(&self) -> bool {
+        self.interrupted.load(Ordering::Relaxed)
+    }
+}
+
+struct Budget {
+    limit: Duration,
+    started: Option<Instant>,
+}
+
+thread_local! {
+    static BUDGET: RefCell<Budget> = const {
+        RefCell::new(Budget {
+            limit: DEFAULT_BUDGET,
+            started: None,
+        })
+    };
+}
+
+/// Change this runtime's per-evaluation budget.
+pub fn set_budget(limit: Duration) {
+    BUDGET.with(|budget| budget.borrow_mut().limit = limit);
+}
+
+/// Start the budget clock for one evaluation.
+pub(crate) fn begin() {
+    BUDGET.with(|budget| budget.borrow_mut().started = Some(Instant::now()));
+}
+
+/// Whether the current evaluation should be abandoned: the UI pulled
+/// `handle`, or the evaluation has outrun its budget.
+pub(crate) fn tripped(handle: &InterruptHandle) -> bool {
+    if handle.is_interrupted() {
+        return true;
+    }
+    BUDGET.with(|budget| {
+        let budget = budget.borrow();
+        budget
+            .started
+            .is_some_and(|started| started.elapsed() > budget.limit)
+    })
+}
@@ -0,0 +1,146 @@
+//! `window.postMessage` between same-process browsing contexts.
+//!
+//! Each tab's JS context lives on its own thread, so messages cross on
+//! a process-wide bus like the storage events do: `postMessage`
+//! snapshots the payload with the structured clone algorithm
+//! ([`super::clone`]), stamps it with the sender's origin and target
+//! origin, and every other context's [`pump`] delivers what matches its
+//! own origin to the global `onmessage` handler. Workers have their own
+//! dedicated channels ([`super::worker`]); this bus covers windows —
+//! tabs today, iframes when they grow script contexts.
+
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+use boa_engine::{js_string, Context, JsArgs, JsObject, JsString, JsValue, NativeFunction};
+
+use super::clone::{self, CloneValue};
+
+/// One posted message on the bus.
+struct Message {
+    /// `*` or an origin the receiver must match.
+    target_origin: String,
+    /// The sender's origin, exposed as `event.origin`.
+    source_origin: String,
+    data: CloneValue,
+    source_context: u64,
+    sequence: u64,
+}
+
+#[derive(Default)]
+struct MessageBus {
+    messages: Vec<Message>,
+    next_sequence: u64,
+}
+
+/// Old messages kept for late-polling contexts before being dropped.
+const MAX_MESSAGES: usize = 256;
+
+fn bus() -> &'static Mutex<MessageBus> {
+    static BUS: OnceLock<Mutex<MessageBus>> = OnceLock::new();
+    BUS.get_or_init(|| Mutex::new(MessageBus::default()))
+}
+
+static NEXT_CONTEXT: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    static CONTEXT_ID: u64 = NEXT_CONTEXT.fetch_add(1, Ordering::Relaxed);
+    // The newest bus sequence this context has delivered.
+    static LAST_SEEN: RefCell<u64> = const { RefCell::new(0) };
+}
+
+/// Install the `postMessage` global (reachable as `window.postMessage`).
+pub fn register(context: &mut Context) {
+    context
+        .register_global_callable(
+            js_string!("postMessage"),
+            2,
+            NativeFunction::from_fn_ptr(post_message),
+        )
+        .expect("registering postMessage");
+}
+
+/// Skip messages posted before now (navigation committed a new page, or
+/// the context just came up). Called from the commit path.
+pub fn configure() {
+    let sequence = bus().lock().unwrap().next_sequence;
+    LAST_SEEN.with(|seen| *seen.borrow_mut() = sequence);
+}
+
+fn post_message(
+    _this: &JsValue,
+    args: &[JsValue],
+    context: &mut Context,
+) -> boa_engine::JsResult<JsValue> {
+    let data = clone::to_clone(args.get_or_undefined(0), context)?;
+    let target = args.get_or_undefined(1);
+    let target_origin = if target.is_undefined() {
+        "*".to_owned()
+    } else {
+        target.to_string(context)?.to_std_string_escaped()
+    };
+    clone::detach_transferred(args.get_or_undefined(2), context)?;
+    let mut bus = bus().lock().unwrap();
+    let sequence = bus.next_sequence;
+    bus.next_sequence += 1;
+    bus.messages.push(Message {
+        target_origin,
+        source_origin: super::window::current_origin(),
+        data,
+        source_context: CONTEXT_ID.with(|id| *id),
+        sequence,
+    });
+    if bus.messages.len() > MAX_MESSAGES {
+        let drop = bus.messages.len() - MAX_MESSAGES;
+        bus.messages.drain(..drop);
+    }
+    Ok(JsValue::undefined())
+}
+
+/// Deliver pending messages addressed to this context's origin to the
+/// global `onmessage` handler. Driven from
+/// [`JsRuntime::pump`](super::JsRuntime::pump).
+pub fn pump(context: &mut Context) {
+    let own_origin = super::window::current_origin();
+    let own_id = CONTEXT_ID.with(|id| *id);
+    let last_seen = LAST_SEEN.with(|seen| *seen.borrow());
+    let pending: Vec<(CloneValue, String, u64)> = {
+        let bus = bus().lock().unwrap();
+        bus.messages
+            .iter()
+            .filter(|m| {
+                m.sequence >= last_seen
+                    && m.source_context != own_id
+                    && (m.target_origin == "*" || m.target_origin == own_origin)
+            })
+            .map(|m| (m.data.clone(), m.source_origin.clone(), m.sequence))
+            .collect()
+    };
+    for (data, origin, sequence) in pending {
+        LAST_SEEN.with(|seen| *seen.borrow_mut() = sequence + 1);
+        let _ = deliver(context, &data, &origin);
+        context.run_jobs();
+    }
+}
+
+/// Fire the global `onmessage` handler with a `{type, data, origin}`
+/// event.
+fn deliver(
+    context: &mut Context,
+    data: &CloneValue,
+    origin: &str,
+) -> boa_engine::JsResult<()> {
+    let handler = context
+        .global_object()
+        .get(js_string!("onmessage"), context)?;
+    let Some(handler) = handler.as_callable() else {
+        return Ok(());
+    };
+    let event = JsObject::with_null_proto();
+    event.set(js_string!("type"), js_string!("message"), false, context)?;
+    event.set(js_string!("data"), clone::from_clone(data, context)?, false, context)?;
+    event.set(js_string!("origin"), JsString::from(origin), false, context)?;
+    let _ = handler.call(&JsValue::undefined(), &[event.into()], context);
+    Ok(())
+}
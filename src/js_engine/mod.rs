@@ -0,0 +1,329 @@
+//! JavaScript execution for pages.
+//!
+//! [`JsRuntime`] wraps a Boa [`boa_engine::Context`] and carries the web
+//! platform bindings the engine exposes to page scripts. Every tab owns one
+//! runtime; bindings that need the outside world (network, UI) talk to the
+//! rest of the engine through channels rather than holding locks across
+//! script execution.
+
+pub mod abort;
+pub mod canvas;
+pub mod clone;
+pub mod console;
+pub mod crypto;
+pub mod dom;
+pub mod encoding;
+pub mod errors;
+pub mod events;
+pub mod fetch;
+pub mod forms;
+pub mod history;
+pub mod interrupt;
+pub mod messaging;
+pub mod modules;
+pub mod mutation;
+pub mod raf;
+pub mod shared;
+pub mod storage;
+pub mod timers;
+pub mod url;
+#[cfg(feature = "v8")]
+pub mod v8;
+pub mod wasm;
+pub mod websocket;
+pub mod window;
+pub mod worker;
+pub mod xhr;
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use boa_engine::builtins::promise::PromiseState;
+use boa_engine::{Context, Source};
+
+use crate::memory::script_cache;
+
+/// Errors surfaced from script execution.
+#[derive(Debug, thiserror::Error)]
+pub enum JsError {
+    #[error("script error: {0}")]
+    Execution(String),
+    /// The script outran its execution budget or the user stopped it
+    /// from the slow-script dialog.
+    #[error("script interrupted")]
+    Interrupted,
+}
+
+/// Abstraction over the underlying JavaScript engine, so the Boa-based
+/// runtime can be swapped for an alternative backend. Construct one for
+/// a chosen [`Backend`] with [`create_engine`].
+pub trait JavaScriptEngine {
+    /// Run a classic script to completion, returning the completion value
+    /// rendered as a string.
+    fn execute(&mut self, source: &str) -> Result<String, JsError>;
+}
+
+/// Which [`JavaScriptEngine`] implementation to construct.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Backend {
+    /// The built-in Boa runtime, with the full set of page bindings.
+    #[default]
+    Boa,
+    /// V8 via `deno_core` (the `v8` feature). Script-only — no page
+    /// bindings — so it fits pure-script callers like PAC evaluation.
+    #[cfg(feature = "v8")]
+    V8,
+}
+
+/// Construct the engine for `backend`.
+pub fn create_engine(backend: Backend) -> Box<dyn JavaScriptEngine> {
+    match backend {
+        Backend::Boa => Box::new(JsRuntime::new()),
+        #[cfg(feature = "v8")]
+        Backend::V8 => Box::new(v8::V8Runtime::new()),
+    }
+}
+
+/// Default JavaScript engine, backed by Boa.
+pub struct JsRuntime {
+    context: Context,
+    modules: Rc<modules::PageModuleLoader>,
+    /// Compiled scripts, keyed like [`crate::memory::script_cache`]
+    /// decides. Boa script objects are context-bound, so each runtime
+    /// keeps its own map and follows the shared policy's evictions.
+    compiled: HashMap<script_cache::CacheKey, boa_engine::Script>,
+    /// Stop switch shared with the UI's slow-script dialog.
+    interrupt: interrupt::InterruptHandle,
+}
+
+impl JsRuntime {
+    pub fn new() -> Self {
+        // Rejection tracking needs hooks with a 'static borrow.
+        static HOOKS: errors::ReportingHooks = errors::ReportingHooks;
+        let modules = modules::PageModuleLoader::new();
+        let mut context = Context::builder()
+            .host_hooks(&HOOKS)
+            .module_loader(Rc::clone(&modules))
+            .build()
+            .expect("building JS context");
+        // A runaway loop trips this limit and returns control to the
+        // host, where the budget check decides what to do with it.
+        context
+            .runtime_limits_mut()
+            .set_loop_iteration_limit(interrupt::LOOP_ITERATION_LIMIT);
+        abort::register(&mut context);
+        canvas::register(&mut context);
+        clone::register(&mut context);
+        console::register(&mut context);
+        crypto::register(&mut context);
+        encoding::register(&mut context);
+        fetch::register(&mut context);
+        forms::register(&mut context);
+        history::register(&mut context);
+        messaging::register(&mut context);
+        mutation::register(&mut context);
+        raf::register(&mut context);
+        shared::register(&mut context);
+        storage::register(&mut context);
+        timers::register(&mut context);
+        url::register(&mut context);
+        wasm::register(&mut context);
+        websocket::register(&mut context);
+        window::register(&mut context);
+        worker::register(&mut context);
+        xhr::register(&mut context);
+        Self {
+            context,
+            modules,
+            compiled: HashMap::new(),
+            interrupt: interrupt::InterruptHandle::default(),
+        }
+    }
+
+    /// Direct access to the Boa context for binding modules.
+    pub fn context(&mut self) -> &mut Context {
+        &mut self.context
+    }
+
+    /// The stop switch for this runtime's scripts. The UI keeps a clone
+    /// and pulls it from the slow-script dialog; the affected evaluation
+    /// returns [`JsError::Interrupted`].
+    pub fn interrupt_handle(&self) -> interrupt::InterruptHandle {
+        self.interrupt.clone()
+    }
+
+    /// Map a failed evaluation to its surfaced error: an interruption
+    /// when the budget ran out or the UI stopped the script, otherwise
+    /// the reported execution error.
+    fn failed(&mut self, error: &boa_engine::JsError) -> JsError {
+        if interrupt::tripped(&self.interrupt) {
+            self.interrupt.clear();
+            return JsError::Interrupted;
+        }
+        let structured = errors::report(&mut self.context, error);
+        JsError::Execution(structured.message)
+    }
+
+    /// Install the page's parsed document for script access. Take it back
+    /// — with whatever the scripts mutated — via [`dom::take_document`]
+    /// once execution is done.
+    pub fn install_document(&mut self, document: crate::renderer::dom::Document) {
+        dom::install(&mut self.context, document).expect("installing document binding");
+    }
+
+    /// Install the page's pre-fetched module graph: the document URL
+    /// import specifiers resolve against, and sources by resolved URL.
+    pub fn install_modules(&mut self, base_url: &str, sources: HashMap<String, String>) {
+        self.modules.install_graph(base_url, sources);
+    }
+
+    /// Run the module at `url` from the installed graph: parse, link
+    /// (imports resolve synchronously from the graph), evaluate, then a
+    /// microtask checkpoint so its top-level awaits settle when ready.
+    pub fn execute_module(&mut self, url: &str) -> Result<(), JsError> {
+        let module = self
+            .modules
+            .entry(url, &mut self.context)
+            .map_err(|e| JsError::Execution(e.to_string()))?;
+        errors::set_source(url);
+        interrupt::begin();
+        let promise = module.load_link_evaluate(&mut self.context);
+        self.context.run_jobs();
+        match promise.state() {
+            PromiseState::Rejected(error) => {
+                Err(self.failed(&boa_engine::JsError::from_opaque(error)))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Run the script at `url` through the compiled-script cache: a
+    /// byte-identical source seen before (this runtime, any page)
+    /// evaluates its cached compilation instead of re-parsing. The
+    /// [`crate::memory::script_cache`] policy decides what stays; its
+    /// evictions are applied here before admitting the new entry.
+    pub fn execute_cached(&mut self, url: &str, source: &str) -> Result<String, JsError> {
+        let key = script_cache::key(url, source);
+        let policy = script_cache::policy();
+        for evicted in policy.touch(&key, source.len() as u64) {
+            self.compiled.remove(&evicted);
+        }
+        // Another runtime's touches may have evicted our entries too.
+        self.compiled.retain(|k, _| policy.contains(k));
+        let script = match self.compiled.entry(key.clone()) {
+            std::collections::hash_map::Entry::Occupied(entry) => entry.get().clone(),
+            std::collections::hash_map::Entry::Vacant(entry) => {
+                let parsed =
+                    boa_engine::Script::parse(Source::from_bytes(source), None, &mut self.context)
+                        .map_err(|e| JsError::Execution(e.to_string()))?;
+                if policy.contains(&key) {
+                    entry.insert(parsed.clone());
+                }
+                parsed
+            }
+        };
+        interrupt::begin();
+        let result = script.evaluate(&mut self.context);
+        self.context.run_jobs();
+        match result {
+            Ok(value) => Ok(value.display().to_string()),
+            Err(error) => Err(self.failed(&error)),
+        }
+    }
+
+    /// Run this frame's `requestAnimationFrame` callbacks. The
+    /// compositor calls this once per produced frame, after [`pump`]
+    /// and before it paints, so callbacks observe a settled page and
+    /// their mutations land in the frame being built.
+    ///
+    /// [`pump`]: JsRuntime::pump
+    pub fn run_animation_frames(&mut self) {
+        raf::run_frame(&mut self.context);
+    }
+
+    /// Drive the page event loop one tick: deliver binding work
+    /// (WebSocket messages, fetch/XHR completions, storage events), run
+    /// every due timer, and drain the microtask queue. Every task source
+    /// checkpoints microtasks after each task it runs, and the trailing
+    /// `run_jobs` here guarantees the queue is empty when the frame loop
+    /// moves on to paint — async/await continuations are never visible
+    /// half-applied. The returned instant is the next timer deadline, so
+    /// an idle loop can sleep until then instead of spinning.
+    pub fn pump(&mut self) -> Option<std::time::Instant> {
+        abort::pump(&mut self.context);
+        websocket::pump(&mut self.context);
+        fetch::pump(&mut self.context);
+        wasm::pump(&mut self.context);
+        messaging::pump(&mut self.context);
+        storage::pump(&mut self.context);
+        worker::pump(&mut self.context);
+        xhr::pump(&mut self.context);
+        let next_deadline = timers::run_due(&mut self.context);
+        // Final microtask checkpoint for jobs queued outside timers.
+        self.context.run_jobs();
+        // Rejections nothing handled by now are unhandled for real.
+        errors::pump(&mut self.context);
+        next_deadline
+    }
+}
+
+impl JavaScriptEngine for JsRuntime {
+    fn execute(&mut self, source: &str) -> Result<String, JsError> {
+        interrupt::begin();
+        let result = self.context.eval(Source::from_bytes(source));
+        // Script execution is a task: microtask checkpoint before
+        // anything else (rendering included) observes its effects.
+        self.context.run_jobs();
+        match result {
+            Ok(value) => Ok(value.display().to_string()),
+            Err(error) => Err(self.failed(&error)),
+        }
+    }
+}
+
+impl Default for JsRuntime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Source/expected pairs every backend must agree on. Completion
+    /// values compare with string quoting stripped — Boa's `display`
+    /// quotes strings, and callers (PAC) already trim that.
+    const CONFORMANCE: &[(&str, &str)] = &[
+        ("1 + 2", "3"),
+        ("'a' + 1", "a1"),
+        ("typeof undefined", "undefined"),
+        ("Math.max(3, 7)", "7"),
+        ("[1, 2, 3].map(x => x * 2).join(',')", "2,4,6"),
+        ("JSON.stringify([1, 2, 3])", "[1,2,3]"),
+        ("(function() { return 'ok'; })()", "ok"),
+        ("(() => { try { null.x; } catch (e) { return 'caught'; } })()", "caught"),
+    ];
+
+    fn run_suite(engine: &mut dyn JavaScriptEngine) {
+        for (source, expected) in CONFORMANCE {
+            let result = engine
+                .execute(source)
+                .unwrap_or_else(|error| panic!("{source:?} failed: {error}"));
+            assert_eq!(result.trim_matches('"'), *expected, "source: {source:?}");
+        }
+        // A thrown error surfaces as Execution, never a panic.
+        assert!(engine.execute("throw new Error('boom')").is_err());
+    }
+
+    #[test]
+    fn boa_passes_conformance() {
+        run_suite(&mut JsRuntime::new());
+    }
+
+    #[cfg(feature = "v8")]
+    #[test]
+    fn v8_passes_conformance() {
+        run_suite(&mut *create_engine(Backend::V8));
+    }
+}
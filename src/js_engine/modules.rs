@@ -0,0 +1,172 @@
+//! ES module loading: the import graph, and Boa's module loader hook.
+//!
+//! Module sources are fetched ahead of execution — [`fetch_module_graph`]
+//! scans each file for static `import`/`export … from` specifiers and
+//! pulls the whole graph through the network stack, resolving specifiers
+//! against the document URL. Execution then happens synchronously on the
+//! JS thread: [`PageModuleLoader`] hands Boa the pre-fetched sources, so
+//! linking never blocks on the network. Dynamic `import()` goes through
+//! the same loader and resolves immediately when the target was in the
+//! graph; a specifier the scanner missed fails the promise rather than
+//! stalling the page.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use boa_engine::module::{Module, ModuleLoader, Referrer};
+use boa_engine::{Context, JsNativeError, JsResult, JsString, Source};
+
+use crate::network::{NetworkError, NetworkStack, Request};
+use crate::renderer::loader::resolve_url;
+
+/// Fetch `entry` and every module it statically imports, keyed by
+/// resolved URL. Cycles are fine: each URL is fetched once.
+pub async fn fetch_module_graph(
+    stack: &NetworkStack,
+    entry: &str,
+) -> Result<HashMap<String, String>, NetworkError> {
+    let mut sources: HashMap<String, String> = HashMap::new();
+    let mut queue = vec![entry.to_owned()];
+    while let Some(url) = queue.pop() {
+        if sources.contains_key(&url) {
+            continue;
+        }
+        let response = stack.fetch(Request::get(url.clone())).await?;
+        let text = response.text();
+        for specifier in import_specifiers(&text) {
+            queue.push(resolve_url(&url, &specifier));
+        }
+        sources.insert(url, text);
+    }
+    Ok(sources)
+}
+
+/// Scan `source` for import specifiers: static `import … from "x"`,
+/// bare `import "x"`, re-exports, and dynamic `import("x")`. A text
+/// scan, not a parse — string literals that merely look like imports
+/// cause a harmless extra fetch.
+pub fn import_specifiers(source: &str) -> Vec<String> {
+    let mut specifiers = Vec::new();
+    let bytes = source.as_bytes();
+    let mut pos = 0;
+    while let Some(found) = source[pos..].find("import").map(|i| pos + i) {
+        let keyword_end = found + "import".len();
+        // Must be a token boundary on both sides.
+        let starts_clean = found == 0 || !bytes[found - 1].is_ascii_alphanumeric();
+        if !starts_clean {
+            pos = keyword_end;
+            continue;
+        }
+        let rest = &source[keyword_end..];
+        if let Some(spec) = specifier_after_import(rest) {
+            specifiers.push(spec);
+        }
+        pos = keyword_end;
+    }
+    // export … from "x"
+    let mut pos = 0;
+    while let Some(found) = source[pos..].find("from").map(|i| pos + i) {
+        let keyword_end = found + "from".len();
+        let starts_clean = found == 0 || !bytes[found - 1].is_ascii_alphanumeric();
+        if starts_clean {
+            if let Some(spec) = quoted_string(source[keyword_end..].trim_start()) {
+                if !specifiers.contains(&spec) {
+                    specifiers.push(spec);
+                }
+            }
+        }
+        pos = keyword_end;
+    }
+    specifiers
+}
+
+/// The specifier in what follows an `import` keyword: either directly a
+/// string (bare or dynamic import), or after a `from` clause.
+fn specifier_after_import(rest: &str) -> Option<String> {
+    let trimmed = rest.trim_start();
+    // import "x";  or  import("x")
+    let direct = trimmed.strip_prefix('(').map(str::trim_start).unwrap_or(trimmed);
+    if let Some(spec) = quoted_string(direct) {
+        return Some(spec);
+    }
+    // import x from "x"  — only scan to the end of the statement.
+    let clause = trimmed.split([';', '\n']).next()?;
+    let after_from = clause.split(" from ").nth(1)?;
+    quoted_string(after_from.trim_start())
+}
+
+/// The leading quoted string of `input`, if it starts with one.
+fn quoted_string(input: &str) -> Option<String> {
+    let mut chars = input.chars();
+    let quote = chars.next().filter(|&c| c == '"' || c == '\'')?;
+    let rest = chars.as_str();
+    rest.find(quote).map(|end| rest[..end].to_owned())
+}
+
+/// Boa module loader over pre-fetched sources. Specifiers resolve
+/// against the importing module's URL (tracked as modules parse), or
+/// the document URL for the entry.
+pub struct PageModuleLoader {
+    base_url: RefCell<String>,
+    sources: RefCell<HashMap<String, String>>,
+    modules: RefCell<HashMap<String, Module>>,
+}
+
+impl PageModuleLoader {
+    pub fn new() -> Rc<Self> {
+        Rc::new(Self {
+            base_url: RefCell::new(String::new()),
+            sources: RefCell::new(HashMap::new()),
+            modules: RefCell::new(HashMap::new()),
+        })
+    }
+
+    /// Install a page's module graph: the document URL specifiers
+    /// resolve against, and the fetched sources. Replaces the previous
+    /// page's graph.
+    pub fn install_graph(&self, base_url: &str, sources: HashMap<String, String>) {
+        *self.base_url.borrow_mut() = base_url.to_owned();
+        *self.sources.borrow_mut() = sources;
+        self.modules.borrow_mut().clear();
+    }
+
+    /// Parse (or reuse) the module at `url`.
+    fn module_at(&self, url: &str, context: &mut Context) -> JsResult<Module> {
+        if let Some(module) = self.modules.borrow().get(url) {
+            return Ok(module.clone());
+        }
+        let Some(source) = self.sources.borrow().get(url).cloned() else {
+            return Err(JsNativeError::typ()
+                .with_message(format!("module not in graph: {url}"))
+                .into());
+        };
+        let module = Module::parse(Source::from_bytes(&source), None, context)?;
+        self.modules
+            .borrow_mut()
+            .insert(url.to_owned(), module.clone());
+        Ok(module)
+    }
+
+    /// Entry point for the runtime: the module for `url`, parsed from
+    /// the installed graph.
+    pub fn entry(&self, url: &str, context: &mut Context) -> JsResult<Module> {
+        self.module_at(url, context)
+    }
+}
+
+impl ModuleLoader for PageModuleLoader {
+    fn load_imported_module(
+        &self,
+        _referrer: Referrer,
+        specifier: JsString,
+        finish_load: Box<dyn FnOnce(JsResult<Module>, &mut Context)>,
+        context: &mut Context,
+    ) {
+        let specifier = specifier.to_std_string_escaped();
+        let url = resolve_url(&self.base_url.borrow(), &specifier);
+        let module = self.module_at(&url, context);
+        finish_load(module, context);
+    }
+}
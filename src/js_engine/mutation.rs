@@ -0,0 +1,319 @@
+//! `MutationObserver` over the DOM bindings.
+//!
+//! Observers live in a thread-local registry like the listener table.
+//! DOM mutation points call [`record`]; matching observers queue a
+//! record and, on the first record queued, a delivery job goes on Boa's
+//! job queue — so records arrive at the next microtask checkpoint, after
+//! the mutating task and before the frame paints, the batching contract
+//! frameworks rely on.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use boa_engine::job::NativeJob;
+use boa_engine::object::builtins::JsArray;
+use boa_engine::{
+    js_string, Context, JsArgs, JsObject, JsResult, JsString, JsValue, NativeFunction,
+};
+
+use crate::renderer::dom::NodeId;
+
+use super::dom;
+
+/// What one `observe()` call asked for.
+#[derive(Default, Clone, Copy)]
+struct ObserveOptions {
+    child_list: bool,
+    attributes: bool,
+    character_data: bool,
+    subtree: bool,
+}
+
+/// One DOM change, engine-side.
+#[derive(Clone)]
+pub(crate) enum Mutation {
+    Attributes { name: String, old_value: Option<String> },
+    CharacterData { old_value: String },
+    ChildList,
+}
+
+struct QueuedRecord {
+    target: NodeId,
+    mutation: Mutation,
+}
+
+struct Observer {
+    callback: JsObject,
+    object: JsObject,
+    targets: Vec<(NodeId, ObserveOptions)>,
+    queue: Vec<QueuedRecord>,
+}
+
+thread_local! {
+    static OBSERVERS: RefCell<HashMap<u64, Observer>> = RefCell::new(HashMap::new());
+    static NEXT_ID: RefCell<u64> = const { RefCell::new(1) };
+    static DELIVERY_SCHEDULED: RefCell<bool> = const { RefCell::new(false) };
+}
+
+/// Install the `MutationObserver` constructor on the global object.
+pub fn register(context: &mut Context) {
+    context
+        .register_global_callable(
+            js_string!("MutationObserver"),
+            1,
+            NativeFunction::from_fn_ptr(construct),
+        )
+        .expect("registering MutationObserver");
+}
+
+/// Drop every observer (navigation replaced the document).
+pub fn clear() {
+    OBSERVERS.with(|observers| observers.borrow_mut().clear());
+    DELIVERY_SCHEDULED.with(|flag| *flag.borrow_mut() = false);
+}
+
+fn construct(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let callback = args
+        .get_or_undefined(0)
+        .as_object()
+        .filter(|o| o.is_callable())
+        .cloned();
+    let Some(callback) = callback else {
+        return Err(boa_engine::JsNativeError::typ()
+            .with_message("MutationObserver: callback is not a function")
+            .into());
+    };
+    let object = JsObject::with_null_proto();
+    let id = NEXT_ID.with(|next| {
+        let mut next = next.borrow_mut();
+        let id = *next;
+        *next += 1;
+        id
+    });
+    object.set(js_string!("__observerId"), id, false, context)?;
+    method(&object, "observe", observe, context)?;
+    method(&object, "disconnect", disconnect, context)?;
+    method(&object, "takeRecords", take_records, context)?;
+    OBSERVERS.with(|observers| {
+        observers.borrow_mut().insert(
+            id,
+            Observer {
+                callback,
+                object: object.clone(),
+                targets: Vec::new(),
+                queue: Vec::new(),
+            },
+        );
+    });
+    Ok(object.into())
+}
+
+fn observe(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let id = observer_id(this, context)?;
+    let target = dom::node_of(args.get_or_undefined(0), context)?;
+    let mut options = ObserveOptions::default();
+    if let Some(init) = args.get_or_undefined(1).as_object() {
+        options.child_list = init.get(js_string!("childList"), context)?.to_boolean();
+        options.attributes = init.get(js_string!("attributes"), context)?.to_boolean();
+        options.character_data = init.get(js_string!("characterData"), context)?.to_boolean();
+        options.subtree = init.get(js_string!("subtree"), context)?.to_boolean();
+    }
+    OBSERVERS.with(|observers| {
+        if let Some(observer) = observers.borrow_mut().get_mut(&id) {
+            // Re-observing a target replaces its options, per spec.
+            observer.targets.retain(|(node, _)| *node != target);
+            observer.targets.push((target, options));
+        }
+    });
+    Ok(JsValue::undefined())
+}
+
+fn disconnect(this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let id = observer_id(this, context)?;
+    OBSERVERS.with(|observers| {
+        if let Some(observer) = observers.borrow_mut().get_mut(&id) {
+            observer.targets.clear();
+            observer.queue.clear();
+        }
+    });
+    Ok(JsValue::undefined())
+}
+
+fn take_records(this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let id = observer_id(this, context)?;
+    let queue = OBSERVERS.with(|observers| {
+        observers
+            .borrow_mut()
+            .get_mut(&id)
+            .map(|observer| std::mem::take(&mut observer.queue))
+            .unwrap_or_default()
+    });
+    records_array(&queue, context).map(JsValue::from)
+}
+
+/// Report a DOM change at `target`. Observers whose registration covers
+/// the node queue a record; the first queued record schedules delivery
+/// at the next microtask checkpoint.
+pub(crate) fn record(target: NodeId, mutation: Mutation, context: &mut Context) {
+    let interested: Vec<u64> = OBSERVERS.with(|observers| {
+        observers
+            .borrow()
+            .iter()
+            .filter(|(_, observer)| observer.targets.iter().any(|(node, options)| {
+                wants(mutation_kind(&mutation), *options)
+                    && (*node == target || (options.subtree && is_ancestor(*node, target)))
+            }))
+            .map(|(&id, _)| id)
+            .collect()
+    });
+    if interested.is_empty() {
+        return;
+    }
+    OBSERVERS.with(|observers| {
+        let mut observers = observers.borrow_mut();
+        for id in interested {
+            if let Some(observer) = observers.get_mut(&id) {
+                observer.queue.push(QueuedRecord {
+                    target,
+                    mutation: mutation.clone(),
+                });
+            }
+        }
+    });
+    schedule_delivery(context);
+}
+
+/// Which option gates a mutation kind.
+fn wants(kind: u8, options: ObserveOptions) -> bool {
+    match kind {
+        0 => options.attributes,
+        1 => options.character_data,
+        _ => options.child_list,
+    }
+}
+
+fn mutation_kind(mutation: &Mutation) -> u8 {
+    match mutation {
+        Mutation::Attributes { .. } => 0,
+        Mutation::CharacterData { .. } => 1,
+        Mutation::ChildList => 2,
+    }
+}
+
+fn is_ancestor(ancestor: NodeId, node: NodeId) -> bool {
+    dom::with_document(|document| {
+        let mut current = document.parent(node);
+        while let Some(candidate) = current {
+            if candidate == ancestor {
+                return true;
+            }
+            current = document.parent(candidate);
+        }
+        false
+    })
+}
+
+fn schedule_delivery(context: &mut Context) {
+    let already = DELIVERY_SCHEDULED.with(|flag| std::mem::replace(&mut *flag.borrow_mut(), true));
+    if already {
+        return;
+    }
+    context.enqueue_job(
+        NativeJob::new(|context| {
+            DELIVERY_SCHEDULED.with(|flag| *flag.borrow_mut() = false);
+            deliver(context);
+            Ok(JsValue::undefined())
+        })
+        .into(),
+    );
+}
+
+/// Invoke each observer's callback with its queued records.
+fn deliver(context: &mut Context) {
+    let pending: Vec<(JsObject, JsObject, Vec<QueuedRecord>)> = OBSERVERS.with(|observers| {
+        observers
+            .borrow_mut()
+            .values_mut()
+            .filter(|observer| !observer.queue.is_empty())
+            .map(|observer| {
+                (
+                    observer.callback.clone(),
+                    observer.object.clone(),
+                    std::mem::take(&mut observer.queue),
+                )
+            })
+            .collect()
+    });
+    for (callback, object, queue) in pending {
+        if let Ok(records) = records_array(&queue, context) {
+            // A throwing callback doesn't stop other observers.
+            let _ = callback.call(
+                &JsValue::undefined(),
+                &[records.into(), object.into()],
+                context,
+            );
+        }
+    }
+}
+
+fn records_array(queue: &[QueuedRecord], context: &mut Context) -> JsResult<JsArray> {
+    let array = JsArray::new(context);
+    for record in queue {
+        let object = JsObject::with_null_proto();
+        let (kind, attribute_name, old_value) = match &record.mutation {
+            Mutation::Attributes { name, old_value } => {
+                ("attributes", Some(name.clone()), old_value.clone())
+            }
+            Mutation::CharacterData { old_value } => {
+                ("characterData", None, Some(old_value.clone()))
+            }
+            Mutation::ChildList => ("childList", None, None),
+        };
+        object.set(js_string!("type"), JsString::from(kind), false, context)?;
+        object.set(
+            js_string!("target"),
+            dom::wrap_element(record.target, context)?,
+            false,
+            context,
+        )?;
+        object.set(
+            js_string!("attributeName"),
+            attribute_name.map_or(JsValue::null(), |n| JsString::from(n).into()),
+            false,
+            context,
+        )?;
+        object.set(
+            js_string!("oldValue"),
+            old_value.map_or(JsValue::null(), |v| JsString::from(v).into()),
+            false,
+            context,
+        )?;
+        array.push(object, context)?;
+    }
+    Ok(array)
+}
+
+fn observer_id(this: &JsValue, context: &mut Context) -> JsResult<u64> {
+    let id = this
+        .as_object()
+        .map(|o| o.get(js_string!("__observerId"), context))
+        .transpose()?
+        .unwrap_or_default();
+    Ok(id.to_number(context)? as u64)
+}
+
+/// Install a native method on `object`.
+fn method(
+    object: &JsObject,
+    name: &str,
+    function: fn(&JsValue, &[JsValue], &mut Context) -> JsResult<JsValue>,
+    context: &mut Context,
+) -> JsResult<()> {
+    object.set(
+        JsString::from(name),
+        NativeFunction::from_fn_ptr(function).to_js_function(context.realm()),
+        false,
+        context,
+    )?;
+    Ok(())
+}
@@ -0,0 +1,93 @@
+//! `requestAnimationFrame`, driven by the compositor's frame cadence.
+//!
+//! Registered callbacks sit in a thread-local list until the compositor
+//! produces a frame and calls
+//! [`JsRuntime::run_animation_frames`](super::JsRuntime::run_animation_frames).
+//! The whole batch runs with one high-resolution timestamp — milliseconds
+//! since the first frame, the `performance.now()` timebase — and a
+//! callback registering another callback sees it next frame, per spec.
+
+use std::cell::RefCell;
+use std::time::Instant;
+
+use boa_engine::{js_string, Context, JsArgs, JsObject, JsResult, JsValue, NativeFunction};
+
+thread_local! {
+    static CALLBACKS: RefCell<Vec<(u32, JsObject)>> = const { RefCell::new(Vec::new()) };
+    static NEXT_ID: RefCell<u32> = const { RefCell::new(1) };
+    static TIME_ORIGIN: RefCell<Option<Instant>> = const { RefCell::new(None) };
+}
+
+/// Install the `requestAnimationFrame`/`cancelAnimationFrame` globals.
+pub fn register(context: &mut Context) {
+    context
+        .register_global_callable(
+            js_string!("requestAnimationFrame"),
+            1,
+            NativeFunction::from_fn_ptr(request),
+        )
+        .expect("registering requestAnimationFrame");
+    context
+        .register_global_callable(
+            js_string!("cancelAnimationFrame"),
+            1,
+            NativeFunction::from_fn_ptr(cancel),
+        )
+        .expect("registering cancelAnimationFrame");
+}
+
+/// Drop pending callbacks (navigation replaced the page) and restart
+/// the timestamp origin for the next document.
+pub fn clear() {
+    CALLBACKS.with(|callbacks| callbacks.borrow_mut().clear());
+    TIME_ORIGIN.with(|origin| *origin.borrow_mut() = None);
+}
+
+fn request(_this: &JsValue, args: &[JsValue], _context: &mut Context) -> JsResult<JsValue> {
+    let callback = args
+        .get_or_undefined(0)
+        .as_object()
+        .filter(|o| o.is_callable())
+        .cloned();
+    let Some(callback) = callback else {
+        return Err(boa_engine::JsNativeError::typ()
+            .with_message("requestAnimationFrame: callback is not a function")
+            .into());
+    };
+    let id = NEXT_ID.with(|next| {
+        let mut next = next.borrow_mut();
+        let id = *next;
+        *next += 1;
+        id
+    });
+    CALLBACKS.with(|callbacks| callbacks.borrow_mut().push((id, callback)));
+    Ok(id.into())
+}
+
+fn cancel(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let id = args.get_or_undefined(0).to_number(context)? as u32;
+    CALLBACKS.with(|callbacks| callbacks.borrow_mut().retain(|(i, _)| *i != id));
+    Ok(JsValue::undefined())
+}
+
+/// Run the callbacks registered for this frame. Called by the
+/// compositor once per produced frame, at display refresh; the batch is
+/// taken first so re-registration lands in the next frame.
+pub fn run_frame(context: &mut Context) {
+    let timestamp = TIME_ORIGIN.with(|origin| {
+        let mut origin = origin.borrow_mut();
+        let start = *origin.get_or_insert_with(Instant::now);
+        start.elapsed().as_secs_f64() * 1000.0
+    });
+    let batch: Vec<JsObject> = CALLBACKS.with(|callbacks| {
+        std::mem::take(&mut *callbacks.borrow_mut())
+            .into_iter()
+            .map(|(_, callback)| callback)
+            .collect()
+    });
+    for callback in batch {
+        // A throwing callback doesn't stop the rest of the batch.
+        let _ = callback.call(&JsValue::undefined(), &[timestamp.into()], context);
+        context.run_jobs();
+    }
+}
@@ -0,0 +1,161 @@
+//! `SharedArrayBuffer`, gated on cross-origin isolation.
+//!
+//! Pages only get shared memory — `SharedArrayBuffer`, wasm shared
+//! memories, atomics — when their response carried
+//! `Cross-Origin-Opener-Policy: same-origin` and a conforming
+//! `Cross-Origin-Embedder-Policy`, the isolation bar browsers set after
+//! Spectre. [`configure`] records the committed page's verdict; the
+//! constructor refuses outside it. Buffers are `Arc<Mutex<Vec<u8>>>`
+//! handles in a registry keyed by the `__sharedBufferId` on the
+//! wrapper, and a shared wasm memory's `buffer` wraps the same handle,
+//! so both sides see the live pages.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use boa_engine::object::builtins::JsArrayBuffer;
+use boa_engine::{
+    js_string, Context, JsArgs, JsNativeError, JsObject, JsResult, JsString, JsValue,
+    NativeFunction,
+};
+
+thread_local! {
+    static ISOLATED: Cell<bool> = const { Cell::new(false) };
+    static BUFFERS: RefCell<HashMap<u64, Arc<Mutex<Vec<u8>>>>> = RefCell::new(HashMap::new());
+    static NEXT_ID: RefCell<u64> = const { RefCell::new(1) };
+}
+
+/// Install the `SharedArrayBuffer` constructor and the
+/// `crossOriginIsolated` global.
+pub fn register(context: &mut Context) {
+    context
+        .register_global_callable(
+            js_string!("SharedArrayBuffer"),
+            1,
+            NativeFunction::from_fn_ptr(construct),
+        )
+        .expect("registering SharedArrayBuffer");
+    let getter = NativeFunction::from_fn_ptr(|_this, _args, _context| {
+        Ok(ISOLATED.with(Cell::get).into())
+    })
+    .to_js_function(context.realm());
+    context
+        .global_object()
+        .define_property_or_throw(
+            js_string!("crossOriginIsolated"),
+            boa_engine::property::PropertyDescriptor::builder()
+                .get(getter)
+                .enumerable(false)
+                .configurable(true),
+            context,
+        )
+        .expect("registering crossOriginIsolated");
+}
+
+/// Record whether the committed page is cross-origin isolated. Called
+/// on every navigation commit, before scripts run.
+pub fn configure(isolated: bool) {
+    ISOLATED.with(|flag| flag.set(isolated));
+}
+
+/// Whether the current page may use shared memory.
+pub(crate) fn cross_origin_isolated() -> bool {
+    ISOLATED.with(Cell::get)
+}
+
+/// Drop every buffer handle (navigation replaced the page). Workers
+/// still holding an `Arc` keep their view until they terminate.
+pub fn clear() {
+    BUFFERS.with(|buffers| buffers.borrow_mut().clear());
+}
+
+fn construct(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    if !cross_origin_isolated() {
+        // The real gate hides the global entirely; a constructor that
+        // refuses is the closest a long-lived realm gets.
+        return Err(JsNativeError::typ()
+            .with_message("SharedArrayBuffer requires cross-origin isolation (COOP/COEP)")
+            .into());
+    }
+    let length = args.get_or_undefined(0).to_number(context)? as usize;
+    Ok(wrap(Arc::new(Mutex::new(vec![0; length])), context)?.into())
+}
+
+/// Build the wrapper for `handle`. Shared wasm memories pass their
+/// backing here, so `memory.buffer` aliases the instance's pages.
+pub(crate) fn wrap(handle: Arc<Mutex<Vec<u8>>>, context: &mut Context) -> JsResult<JsObject> {
+    let length = handle.lock().expect("shared buffer lock").len();
+    let id = NEXT_ID.with(|next| {
+        let mut next = next.borrow_mut();
+        let id = *next;
+        *next += 1;
+        id
+    });
+    BUFFERS.with(|buffers| {
+        buffers.borrow_mut().insert(id, handle);
+    });
+    let object = JsObject::with_null_proto();
+    object.set(js_string!("__sharedBufferId"), id, false, context)?;
+    object.set(js_string!("byteLength"), length, false, context)?;
+    method(&object, "slice", slice, context)?;
+    Ok(object)
+}
+
+/// `buffer.slice(begin, end)` — a copy of the range as a plain
+/// `ArrayBuffer`, like the spec's.
+fn slice(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let id = this
+        .as_object()
+        .map(|o| o.get(js_string!("__sharedBufferId"), context))
+        .transpose()?
+        .unwrap_or_default()
+        .to_number(context)? as u64;
+    let Some(handle) = BUFFERS.with(|buffers| buffers.borrow().get(&id).cloned()) else {
+        return Err(JsNativeError::typ()
+            .with_message("SharedArrayBuffer: no buffer behind this object")
+            .into());
+    };
+    let data = handle.lock().expect("shared buffer lock");
+    let begin = clamp_index(args.get_or_undefined(0), data.len(), 0, context)?;
+    let end = clamp_index(args.get_or_undefined(1), data.len(), data.len(), context)?;
+    let bytes = data.get(begin..end.max(begin)).unwrap_or_default().to_vec();
+    drop(data);
+    Ok(JsArrayBuffer::from_byte_block(bytes, context)?.into())
+}
+
+/// A slice bound: negative counts from the end, missing means
+/// `default`, everything clamps into the buffer.
+fn clamp_index(
+    value: &JsValue,
+    length: usize,
+    default: usize,
+    context: &mut Context,
+) -> JsResult<usize> {
+    if value.is_undefined() {
+        return Ok(default);
+    }
+    let index = value.to_number(context)? as i64;
+    let resolved = if index < 0 {
+        length as i64 + index
+    } else {
+        index
+    };
+    Ok(resolved.clamp(0, length as i64) as usize)
+}
+
+/// Install a native method on `object`.
+fn method(
+    object: &JsObject,
+    name: &str,
+    function: fn(&JsValue, &[JsValue], &mut Context) -> JsResult<JsValue>,
+    context: &mut Context,
+) -> JsResult<()> {
+    object.set(
+        JsString::from(name),
+        NativeFunction::from_fn_ptr(function).to_js_function(context.realm()),
+        false,
+        context,
+    )?;
+    Ok(())
+}
@@ -0,0 +1,299 @@
+//! `localStorage` and `sessionStorage` bindings over [`crate::storage`].
+//!
+//! Both globals share one `Storage`-shaped object; a private flag picks
+//! the backend. `localStorage` reads and writes the engine's per-origin
+//! [`LocalStorage`] store, so data survives restarts and is visible to
+//! other same-origin tabs; `sessionStorage` is a thread-local area that
+//! dies with the tab. Mutating `localStorage` publishes on the storage
+//! event bus, and [`pump`] turns other tabs' publications into the
+//! window's `onstorage` handler.
+
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use boa_engine::{
+    js_string, Context, JsArgs, JsObject, JsResult, JsString, JsValue, NativeFunction,
+};
+
+use crate::storage::{self, LocalStorage, StorageArea, StorageEvent};
+
+struct StorageEnvironment {
+    local: Arc<LocalStorage>,
+    origin: String,
+    /// This tab's id on the event bus, for self-suppression.
+    tab: u64,
+    /// Highest bus sequence already delivered to this page.
+    last_seen: u64,
+}
+
+thread_local! {
+    static ENVIRONMENT: RefCell<Option<StorageEnvironment>> = const { RefCell::new(None) };
+    static SESSION: RefCell<StorageArea> = RefCell::new(StorageArea::default());
+}
+
+static NEXT_TAB: AtomicU64 = AtomicU64::new(1);
+
+/// Install the `localStorage` and `sessionStorage` globals.
+pub fn register(context: &mut Context) {
+    for (name, local) in [("localStorage", true), ("sessionStorage", false)] {
+        let object = JsObject::with_null_proto();
+        object
+            .set(js_string!("__local"), local, false, context)
+            .expect("initialising storage object");
+        for (method_name, function) in [
+            ("getItem", get_item as NativeCall),
+            ("setItem", set_item),
+            ("removeItem", remove_item),
+            ("clear", clear),
+            ("key", key),
+        ] {
+            method(&object, method_name, function, context).expect("installing storage method");
+        }
+        sync_length(&object, context).expect("initialising storage length");
+        context
+            .register_global_property(
+                JsString::from(name),
+                object,
+                boa_engine::property::Attribute::all(),
+            )
+            .expect("registering storage global");
+    }
+}
+
+/// Point `localStorage` at the engine store and the page's origin.
+/// Called on every navigation commit; the session area is cleared, since
+/// this binding's session scope is the tab's current page lifetime.
+pub fn configure(local: Arc<LocalStorage>, origin: &str) {
+    ENVIRONMENT.with(|env| {
+        *env.borrow_mut() = Some(StorageEnvironment {
+            local,
+            origin: origin.to_owned(),
+            tab: NEXT_TAB.fetch_add(1, Ordering::Relaxed),
+            last_seen: 0,
+        });
+    });
+    SESSION.with(|session| session.borrow_mut().clear());
+}
+
+type NativeCall = fn(&JsValue, &[JsValue], &mut Context) -> JsResult<JsValue>;
+
+fn get_item(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let name = string_arg(args, 0, context)?;
+    let value = with_area(this, context, |area| {
+        area.get(&name).map(str::to_owned)
+    })?;
+    Ok(value.map_or(JsValue::null(), |v| JsString::from(v).into()))
+}
+
+fn set_item(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let name = string_arg(args, 0, context)?;
+    let value = string_arg(args, 1, context)?;
+    let outcome = with_area_mut(this, context, |area| area.set(&name, &value))?;
+    match outcome {
+        Ok(old_value) => {
+            publish_if_local(this, context, Some(name), old_value, Some(value))?;
+            sync_length_of(this, context)?;
+            Ok(JsValue::undefined())
+        }
+        Err(storage::QuotaExceeded) => Err(boa_engine::JsNativeError::error()
+            .with_message("QuotaExceededError: storage quota exceeded")
+            .into()),
+    }
+}
+
+fn remove_item(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let name = string_arg(args, 0, context)?;
+    let old_value = with_area_mut(this, context, |area| area.remove(&name))?;
+    if old_value.is_some() {
+        publish_if_local(this, context, Some(name), old_value, None)?;
+    }
+    sync_length_of(this, context)?;
+    Ok(JsValue::undefined())
+}
+
+fn clear(this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let was_empty = with_area_mut(this, context, |area| {
+        let was_empty = area.is_empty();
+        area.clear();
+        was_empty
+    })?;
+    if !was_empty {
+        publish_if_local(this, context, None, None, None)?;
+    }
+    sync_length_of(this, context)?;
+    Ok(JsValue::undefined())
+}
+
+fn key(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let index = args.get_or_undefined(0).to_number(context)? as usize;
+    let name = with_area(this, context, |area| area.key(index).map(str::to_owned))?;
+    Ok(name.map_or(JsValue::null(), |n| JsString::from(n).into()))
+}
+
+/// Deliver `storage` events published by other same-origin tabs to the
+/// global `onstorage` handler. Driven from
+/// [`JsRuntime::pump`](super::JsRuntime::pump).
+pub fn pump(context: &mut Context) {
+    let pending: Vec<StorageEvent> = ENVIRONMENT.with(|env| {
+        let mut env = env.borrow_mut();
+        let Some(env) = env.as_mut() else {
+            return Vec::new();
+        };
+        let events = storage::events_since(&env.origin, env.tab, env.last_seen);
+        if let Some(last) = events.last() {
+            env.last_seen = last.sequence;
+        }
+        events
+    });
+    for event in pending {
+        let _ = fire_storage_event(&event, context);
+        // Each delivery is a task; checkpoint its promise reactions.
+        context.run_jobs();
+    }
+}
+
+fn fire_storage_event(event: &StorageEvent, context: &mut Context) -> JsResult<()> {
+    let handler = context.global_object().get(js_string!("onstorage"), context)?;
+    let Some(handler) = handler.as_callable() else {
+        return Ok(());
+    };
+    let object = JsObject::with_null_proto();
+    object.set(js_string!("type"), js_string!("storage"), false, context)?;
+    object.set(
+        js_string!("key"),
+        optional_string(event.key.as_deref()),
+        false,
+        context,
+    )?;
+    object.set(
+        js_string!("oldValue"),
+        optional_string(event.old_value.as_deref()),
+        false,
+        context,
+    )?;
+    object.set(
+        js_string!("newValue"),
+        optional_string(event.new_value.as_deref()),
+        false,
+        context,
+    )?;
+    let _ = handler.call(&JsValue::undefined(), &[object.into()], context);
+    Ok(())
+}
+
+fn optional_string(value: Option<&str>) -> JsValue {
+    value.map_or(JsValue::null(), |v| JsString::from(v).into())
+}
+
+/// Whether `this` is the `localStorage` side of the shared shape.
+fn is_local(this: &JsValue, context: &mut Context) -> JsResult<bool> {
+    Ok(this
+        .as_object()
+        .map(|o| o.get(js_string!("__local"), context))
+        .transpose()?
+        .map_or(false, |v| v.to_boolean()))
+}
+
+fn with_area<R>(
+    this: &JsValue,
+    context: &mut Context,
+    read: impl FnOnce(&StorageArea) -> R,
+) -> JsResult<R> {
+    if is_local(this, context)? {
+        ENVIRONMENT.with(|env| match env.borrow().as_ref() {
+            Some(env) => Ok(env.local.with_area(&env.origin, read)),
+            None => Err(no_document()),
+        })
+    } else {
+        Ok(SESSION.with(|session| read(&session.borrow())))
+    }
+}
+
+fn with_area_mut<R>(
+    this: &JsValue,
+    context: &mut Context,
+    write: impl FnOnce(&mut StorageArea) -> R,
+) -> JsResult<R> {
+    if is_local(this, context)? {
+        ENVIRONMENT.with(|env| match env.borrow().as_ref() {
+            Some(env) => Ok(env.local.with_area_mut(&env.origin, write)),
+            None => Err(no_document()),
+        })
+    } else {
+        Ok(SESSION.with(|session| write(&mut session.borrow_mut())))
+    }
+}
+
+/// Publish a `localStorage` mutation for other same-origin tabs;
+/// `sessionStorage` mutations stay in this tab.
+fn publish_if_local(
+    this: &JsValue,
+    context: &mut Context,
+    key: Option<String>,
+    old_value: Option<String>,
+    new_value: Option<String>,
+) -> JsResult<()> {
+    if !is_local(this, context)? {
+        return Ok(());
+    }
+    ENVIRONMENT.with(|env| {
+        let mut env = env.borrow_mut();
+        if let Some(env) = env.as_mut() {
+            let sequence = storage::publish(StorageEvent {
+                origin: env.origin.clone(),
+                key,
+                old_value,
+                new_value,
+                source_tab: env.tab,
+                sequence: 0,
+            });
+            // Don't redeliver our own event to ourselves.
+            env.last_seen = env.last_seen.max(sequence);
+        }
+    });
+    Ok(())
+}
+
+/// Reflect the area's entry count as the `length` property.
+fn sync_length_of(this: &JsValue, context: &mut Context) -> JsResult<()> {
+    match this.as_object() {
+        Some(object) => sync_length(&object.clone(), context),
+        None => Ok(()),
+    }
+}
+
+fn sync_length(object: &JsObject, context: &mut Context) -> JsResult<()> {
+    let length = with_area(&object.clone().into(), context, StorageArea::len).unwrap_or(0);
+    object.set(js_string!("length"), length, false, context)?;
+    Ok(())
+}
+
+fn string_arg(args: &[JsValue], index: usize, context: &mut Context) -> JsResult<String> {
+    Ok(args
+        .get_or_undefined(index)
+        .to_string(context)?
+        .to_std_string_escaped())
+}
+
+fn no_document() -> boa_engine::JsError {
+    boa_engine::JsNativeError::error()
+        .with_message("localStorage: no document loaded")
+        .into()
+}
+
+/// Install a native method on `object`.
+fn method(
+    object: &JsObject,
+    name: &str,
+    function: NativeCall,
+    context: &mut Context,
+) -> JsResult<()> {
+    object.set(
+        JsString::from(name),
+        NativeFunction::from_fn_ptr(function).to_js_function(context.realm()),
+        false,
+        context,
+    )?;
+    Ok(())
+}
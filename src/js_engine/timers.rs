@@ -0,0 +1,202 @@
+//! Timers and the page task queue: `setTimeout`/`setInterval`.
+//!
+//! Scheduled callbacks sit in a thread-local queue, like the other
+//! binding registries. The UI frame loop drives them through
+//! [`JsRuntime::pump`](super::JsRuntime::pump) → [`run_due`], which runs
+//! every timer that has come due — each followed by a microtask
+//! checkpoint, per the event-loop model — and reports the next deadline
+//! so the loop can sleep instead of polling.
+//!
+//! Background tabs are throttled: while [`set_background`] is on,
+//! deadlines are aligned up to one-second batch wakeups measured from
+//! the moment the tab left the foreground, and intervals reschedule no
+//! faster than once a second. Timers keep their real deadlines, so
+//! foregrounding the tab restores full resolution immediately.
+
+use std::cell::{Cell, RefCell};
+use std::time::{Duration, Instant};
+
+use boa_engine::{Context, JsArgs, JsObject, JsResult, JsValue, NativeFunction, Source};
+
+enum Callback {
+    Function(JsObject, Vec<JsValue>),
+    /// A string handler (`setTimeout("code()", …)`), compiled when it
+    /// fires.
+    Source(String),
+}
+
+struct Timer {
+    id: u32,
+    callback: Callback,
+    due: Instant,
+    /// Set for intervals; the timer reschedules itself at this period.
+    repeat: Option<Duration>,
+}
+
+/// Minimum timer resolution while the tab is backgrounded, per the
+/// throttling browsers apply to save battery.
+const BACKGROUND_INTERVAL: Duration = Duration::from_secs(1);
+
+thread_local! {
+    static TIMERS: RefCell<Vec<Timer>> = const { RefCell::new(Vec::new()) };
+    static NEXT_ID: RefCell<u32> = const { RefCell::new(1) };
+    // Set while the tab is backgrounded: the instant throttling began,
+    // which batch wakeups align against.
+    static THROTTLED_SINCE: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+/// Install the timer globals.
+pub fn register(context: &mut Context) {
+    let install = |context: &mut Context, name, function, length| {
+        context
+            .register_global_callable(
+                boa_engine::js_string!(name),
+                length,
+                NativeFunction::from_fn_ptr(function),
+            )
+            .expect("registering timer global");
+    };
+    install(context, "setTimeout", set_timeout, 2);
+    install(context, "setInterval", set_interval, 2);
+    install(context, "clearTimeout", clear_timer, 1);
+    install(context, "clearInterval", clear_timer, 1);
+}
+
+/// Drop every pending timer (navigation replaced the page).
+pub fn clear() {
+    TIMERS.with(|timers| timers.borrow_mut().clear());
+}
+
+/// Throttle (or stop throttling) this runtime's timers. The UI flips
+/// this as the tab leaves and re-enters the foreground.
+pub fn set_background(background: bool) {
+    THROTTLED_SINCE.with(|since| {
+        if background {
+            if since.get().is_none() {
+                since.set(Some(Instant::now()));
+            }
+        } else {
+            since.set(None);
+        }
+    });
+}
+
+/// A timer's deadline as the loop observes it: the real deadline in the
+/// foreground, aligned up to the next one-second batch wakeup while
+/// backgrounded — so a burst of short timers wakes the loop once, not
+/// once per timer.
+fn effective_due(due: Instant) -> Instant {
+    let Some(epoch) = THROTTLED_SINCE.with(Cell::get) else {
+        return due;
+    };
+    let elapsed = due.saturating_duration_since(epoch);
+    let batches = elapsed.as_millis().div_ceil(BACKGROUND_INTERVAL.as_millis()) as u32;
+    epoch + BACKGROUND_INTERVAL * batches.max(1)
+}
+
+/// Run every timer that has come due, oldest deadline first, with a
+/// microtask checkpoint after each. Returns the next pending deadline,
+/// `None` when the queue is empty.
+pub fn run_due(context: &mut Context) -> Option<Instant> {
+    loop {
+        let now = Instant::now();
+        let next = TIMERS.with(|timers| {
+            let mut timers = timers.borrow_mut();
+            let position = timers
+                .iter()
+                .enumerate()
+                .filter(|(_, t)| effective_due(t.due) <= now)
+                .min_by_key(|(_, t)| t.due)
+                .map(|(i, _)| i);
+            let mut timer = position.map(|i| timers.remove(i))?;
+            // Intervals reschedule before running, so a clearInterval
+            // from inside the callback still finds an entry to remove.
+            // A backgrounded tab's intervals reschedule no faster than
+            // the batch cadence.
+            if let Some(period) = timer.repeat {
+                let next_period = if THROTTLED_SINCE.with(Cell::get).is_some() {
+                    period.max(BACKGROUND_INTERVAL)
+                } else {
+                    period
+                };
+                let callback = match &timer.callback {
+                    Callback::Function(f, args) => Callback::Function(f.clone(), args.clone()),
+                    Callback::Source(s) => Callback::Source(s.clone()),
+                };
+                timers.push(Timer {
+                    id: timer.id,
+                    callback,
+                    due: now + next_period,
+                    // The real period survives, so foregrounding the tab
+                    // restores the interval's full rate.
+                    repeat: Some(period),
+                });
+                timer.due = now;
+            }
+            Some(timer)
+        });
+        let Some(timer) = next else {
+            break;
+        };
+        match &timer.callback {
+            Callback::Function(function, args) => {
+                // A throwing callback doesn't take the loop down.
+                let _ = function.call(&JsValue::undefined(), args, context);
+            }
+            Callback::Source(source) => {
+                let _ = context.eval(Source::from_bytes(source));
+            }
+        }
+        context.run_jobs();
+    }
+    // The reported deadline is the throttled one: a backgrounded loop
+    // sleeps through to the batch wakeup instead of ticking per timer.
+    TIMERS.with(|timers| timers.borrow().iter().map(|t| effective_due(t.due)).min())
+}
+
+fn set_timeout(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    schedule(this, args, context, false)
+}
+
+fn set_interval(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    schedule(this, args, context, true)
+}
+
+fn schedule(
+    _this: &JsValue,
+    args: &[JsValue],
+    context: &mut Context,
+    repeat: bool,
+) -> JsResult<JsValue> {
+    let callback = match args.get_or_undefined(0) {
+        value if value.as_object().map_or(false, JsObject::is_callable) => {
+            let extra = args.get(2..).unwrap_or_default().to_vec();
+            Callback::Function(value.as_object().cloned().unwrap(), extra)
+        }
+        value => Callback::Source(value.to_string(context)?.to_std_string_escaped()),
+    };
+    let millis = args.get_or_undefined(1).to_number(context)?.max(0.0);
+    let delay = Duration::from_millis(millis as u64);
+    let id = NEXT_ID.with(|next| {
+        let mut next = next.borrow_mut();
+        let id = *next;
+        *next += 1;
+        id
+    });
+    TIMERS.with(|timers| {
+        timers.borrow_mut().push(Timer {
+            id,
+            callback,
+            due: Instant::now() + delay,
+            repeat: repeat.then_some(delay),
+        });
+    });
+    Ok(id.into())
+}
+
+/// `clearTimeout` and `clearInterval` share one id space, per spec.
+fn clear_timer(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let id = args.get_or_undefined(0).to_number(context)? as u32;
+    TIMERS.with(|timers| timers.borrow_mut().retain(|t| t.id != id));
+    Ok(JsValue::undefined())
+}
@@ -0,0 +1,259 @@
+//! The `URL` and `URLSearchParams` globals, over [`crate::url`].
+//!
+//! Both wrappers are snapshots built at construction, like the other
+//! reflected fields in the bindings: the components come straight out
+//! of the real parser, and `URLSearchParams` keeps its pairs in a
+//! registry keyed by `__paramsId` so the mutating methods work without
+//! accessor plumbing. `toString`/`href` re-serialise through the same
+//! code navigation uses, so script and loader agree on every URL.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use boa_engine::{
+    js_string, Context, JsArgs, JsNativeError, JsObject, JsResult, JsString, JsValue,
+    NativeFunction,
+};
+
+use crate::url::{parse_query, serialize_query, Url};
+
+thread_local! {
+    static PARAMS: RefCell<HashMap<u64, Vec<(String, String)>>> = RefCell::new(HashMap::new());
+    static NEXT_ID: RefCell<u64> = const { RefCell::new(1) };
+}
+
+/// Install the `URL` and `URLSearchParams` constructors.
+pub fn register(context: &mut Context) {
+    context
+        .register_global_callable(js_string!("URL"), 1, NativeFunction::from_fn_ptr(construct_url))
+        .expect("registering URL");
+    context
+        .register_global_callable(
+            js_string!("URLSearchParams"),
+            0,
+            NativeFunction::from_fn_ptr(construct_params),
+        )
+        .expect("registering URLSearchParams");
+}
+
+/// Drop every params registry entry (navigation replaced the page).
+pub fn clear() {
+    PARAMS.with(|params| params.borrow_mut().clear());
+}
+
+fn construct_url(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let input = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    let base = args.get_or_undefined(1);
+    let parsed = if base.is_undefined() {
+        Url::parse(&input)
+    } else {
+        let base = base.to_string(context)?.to_std_string_escaped();
+        Url::parse(&base).and_then(|base| base.join(&input))
+    };
+    let url = parsed.map_err(|e| JsNativeError::typ().with_message(format!("Invalid URL: {e}")))?;
+
+    let object = JsObject::with_null_proto();
+    let set = |name: &str, value: String, context: &mut Context| -> JsResult<()> {
+        object.set(JsString::from(name), JsString::from(value), false, context)?;
+        Ok(())
+    };
+    set("href", url.href(), context)?;
+    set("origin", url.origin(), context)?;
+    set("protocol", format!("{}:", url.scheme), context)?;
+    set("host", url.host_with_port(), context)?;
+    set("hostname", url.host.clone(), context)?;
+    set(
+        "port",
+        url.port.map(|p| p.to_string()).unwrap_or_default(),
+        context,
+    )?;
+    set("pathname", url.path.clone(), context)?;
+    set(
+        "search",
+        url.query.as_ref().map(|q| format!("?{q}")).unwrap_or_default(),
+        context,
+    )?;
+    set(
+        "hash",
+        url.fragment.as_ref().map(|f| format!("#{f}")).unwrap_or_default(),
+        context,
+    )?;
+    let params = build_params(parse_query(url.query.as_deref().unwrap_or_default()), context)?;
+    object.set(js_string!("searchParams"), params, false, context)?;
+    method(&object, "toString", |this, _, context| {
+        this.as_object()
+            .map(|o| o.get(js_string!("href"), context))
+            .unwrap_or_else(|| Ok(JsValue::undefined()))
+    }, context)?;
+    Ok(object.into())
+}
+
+fn construct_params(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let init = args.get_or_undefined(0);
+    let pairs = if init.is_undefined() {
+        Vec::new()
+    } else if let Some(object) = init.as_object().filter(|o| !o.is_array()) {
+        // A plain record of name → value.
+        let mut pairs = Vec::new();
+        for key in object.own_property_keys(context)? {
+            let value = object.get(key.clone(), context)?;
+            pairs.push((
+                key.to_string(),
+                value.to_string(context)?.to_std_string_escaped(),
+            ));
+        }
+        pairs
+    } else {
+        let text = init.to_string(context)?.to_std_string_escaped();
+        parse_query(text.strip_prefix('?').unwrap_or(&text))
+    };
+    Ok(build_params(pairs, context)?.into())
+}
+
+/// Build a `URLSearchParams` wrapper around `pairs`.
+fn build_params(pairs: Vec<(String, String)>, context: &mut Context) -> JsResult<JsObject> {
+    let id = NEXT_ID.with(|next| {
+        let mut next = next.borrow_mut();
+        let id = *next;
+        *next += 1;
+        id
+    });
+    PARAMS.with(|params| params.borrow_mut().insert(id, pairs));
+    let object = JsObject::with_null_proto();
+    object.set(js_string!("__paramsId"), id, false, context)?;
+    method(&object, "get", params_get, context)?;
+    method(&object, "getAll", params_get_all, context)?;
+    method(&object, "set", params_set, context)?;
+    method(&object, "append", params_append, context)?;
+    method(&object, "delete", params_delete, context)?;
+    method(&object, "has", params_has, context)?;
+    method(&object, "toString", params_to_string, context)?;
+    Ok(object)
+}
+
+fn params_get(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let name = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    let value = with_pairs(this, context, |pairs| {
+        pairs
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, v)| v.clone())
+    })?;
+    match value {
+        Some(value) => Ok(JsString::from(value).into()),
+        None => Ok(JsValue::null()),
+    }
+}
+
+fn params_get_all(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let name = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    let values = with_pairs(this, context, |pairs| {
+        pairs
+            .iter()
+            .filter(|(n, _)| *n == name)
+            .map(|(_, v)| JsValue::from(JsString::from(v.as_str())))
+            .collect::<Vec<_>>()
+    })?;
+    Ok(boa_engine::object::builtins::JsArray::from_iter(values, context).into())
+}
+
+fn params_set(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let name = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    let value = args.get_or_undefined(1).to_string(context)?.to_std_string_escaped();
+    with_pairs_mut(this, context, |pairs| {
+        // Replace at the first match's position, drop the rest, per spec.
+        match pairs.iter().position(|(n, _)| *n == name) {
+            Some(index) => {
+                pairs.retain(|(n, _)| *n != name);
+                pairs.insert(index.min(pairs.len()), (name.clone(), value.clone()));
+            }
+            None => pairs.push((name.clone(), value.clone())),
+        }
+    })?;
+    Ok(JsValue::undefined())
+}
+
+fn params_append(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let name = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    let value = args.get_or_undefined(1).to_string(context)?.to_std_string_escaped();
+    with_pairs_mut(this, context, |pairs| pairs.push((name.clone(), value.clone())))?;
+    Ok(JsValue::undefined())
+}
+
+fn params_delete(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let name = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    with_pairs_mut(this, context, |pairs| pairs.retain(|(n, _)| *n != name))?;
+    Ok(JsValue::undefined())
+}
+
+fn params_has(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let name = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    let present = with_pairs(this, context, |pairs| pairs.iter().any(|(n, _)| *n == name))?;
+    Ok(present.into())
+}
+
+fn params_to_string(this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let text = with_pairs(this, context, |pairs| serialize_query(pairs))?;
+    Ok(JsString::from(text).into())
+}
+
+fn with_pairs<R>(
+    this: &JsValue,
+    context: &mut Context,
+    read: impl FnOnce(&Vec<(String, String)>) -> R,
+) -> JsResult<R> {
+    let id = params_id(this, context)?;
+    PARAMS.with(|params| {
+        params
+            .borrow()
+            .get(&id)
+            .map(read)
+            .ok_or_else(missing_params)
+    })
+}
+
+fn with_pairs_mut(
+    this: &JsValue,
+    context: &mut Context,
+    change: impl FnOnce(&mut Vec<(String, String)>),
+) -> JsResult<()> {
+    let id = params_id(this, context)?;
+    PARAMS.with(|params| {
+        params
+            .borrow_mut()
+            .get_mut(&id)
+            .map(change)
+            .ok_or_else(missing_params)
+    })
+}
+
+fn missing_params() -> boa_engine::JsError {
+    JsNativeError::typ()
+        .with_message("URLSearchParams: detached from its registry")
+        .into()
+}
+
+fn params_id(this: &JsValue, context: &mut Context) -> JsResult<u64> {
+    let id = this
+        .as_object()
+        .map(|o| o.get(js_string!("__paramsId"), context))
+        .transpose()?
+        .unwrap_or_default();
+    Ok(id.to_number(context)? as u64)
+}
+
+/// Install a native method on `object`.
+fn method(
+    object: &JsObject,
+    name: &str,
+    function: fn(&JsValue, &[JsValue], &mut Context) -> JsResult<JsValue>,
+    context: &mut Context,
+) -> JsResult<()> {
+    object.set(
+        JsString::from(name),
+        NativeFunction::from_fn_ptr(function).to_js_function(context.realm()),
+        false,
+        context,
+    )?;
+    Ok(())
+}
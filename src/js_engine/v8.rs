@@ -0,0 +1,43 @@
+//! V8 backend for [`JavaScriptEngine`], behind the `v8` feature.
+//!
+//! A [`V8Runtime`] wraps a `deno_core` runtime and implements the same
+//! [`JavaScriptEngine`] contract as the Boa-based [`super::JsRuntime`].
+//! It is script-only: the web platform bindings (DOM, fetch, timers)
+//! live on the Boa side, so this backend suits the engine's pure-script
+//! callers — PAC evaluation, extension logic — rather than page
+//! execution. Pick a backend with [`super::create_engine`].
+
+use deno_core::{v8, JsRuntime as DenoRuntime, RuntimeOptions};
+
+use super::{JavaScriptEngine, JsError};
+
+/// [`JavaScriptEngine`] backed by V8 via `deno_core`.
+pub struct V8Runtime {
+    runtime: DenoRuntime,
+}
+
+impl V8Runtime {
+    pub fn new() -> Self {
+        Self {
+            runtime: DenoRuntime::new(RuntimeOptions::default()),
+        }
+    }
+}
+
+impl JavaScriptEngine for V8Runtime {
+    fn execute(&mut self, source: &str) -> Result<String, JsError> {
+        let value = self
+            .runtime
+            .execute_script("<script>", source.to_owned())
+            .map_err(|e| JsError::Execution(e.to_string()))?;
+        let scope = &mut self.runtime.handle_scope();
+        let local = v8::Local::new(scope, value);
+        Ok(local.to_rust_string_lossy(scope))
+    }
+}
+
+impl Default for V8Runtime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
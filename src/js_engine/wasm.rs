@@ -0,0 +1,756 @@
+//! The `WebAssembly` JS API over [`crate::wasm`].
+//!
+//! `WebAssembly.compile`/`instantiate`/`validate` plus the `Module`,
+//! `Instance`, and `Memory` constructors. Instances live in a
+//! thread-local registry keyed by the `__instanceId` on the wrapper;
+//! exported functions are native closures over that id, and imported
+//! functions are called back through the stored JS callables with
+//! values marshalled as numbers in both directions (i64 included — the
+//! engine has no BigInt bridge yet). Buffer compilation is synchronous
+//! under the promise: decoding in-memory bytes is cheap enough that a
+//! task buys nothing. The streaming entry points are the exception —
+//! `instantiateStreaming(fetch(url))` re-fetches through the network
+//! stack's chunk stream, feeding a [`crate::wasm::StreamingCompiler`]
+//! on a tokio
+//! task so the module is mostly compiled when the last byte lands, with
+//! the result crossing back through a oneshot drained by [`pump`], like
+//! fetch itself.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use boa_engine::object::builtins::{JsArrayBuffer, JsFunction, JsPromise};
+use boa_engine::{
+    js_string, Context, JsArgs, JsNativeError, JsObject, JsResult, JsString, JsValue,
+    NativeFunction,
+};
+use tokio::sync::oneshot;
+
+use crate::network::{NetworkStack, Request};
+use crate::renderer::loader::resolve_url;
+use crate::wasm::module::{ExportKind, FuncType, ImportKind, Limits, ValType};
+use crate::wasm::runtime::Memory;
+use crate::wasm::{Instance, Module, Value, WasmError, WasmFeatures, WasmRuntime};
+
+/// An instantiated module and the JS functions backing its imports, in
+/// function-import order.
+struct InstanceState {
+    instance: Instance,
+    imports: Vec<JsObject>,
+}
+
+/// A streaming compile waiting on its source promise (the `fetch(url)`
+/// in `instantiateStreaming(fetch(url))`), parked in [`WAITING`] so the
+/// `then` callback can capture only its Copy id.
+struct Waiter {
+    imports: JsValue,
+    resolve: JsFunction,
+    reject: JsFunction,
+    /// `instantiateStreaming` (true) vs `compileStreaming`.
+    want_instance: bool,
+}
+
+/// A streaming compile running on the network.
+struct PendingStream {
+    result: oneshot::Receiver<Result<Module, String>>,
+    imports: JsValue,
+    resolve: JsFunction,
+    reject: JsFunction,
+    want_instance: bool,
+}
+
+thread_local! {
+    static MODULES: RefCell<HashMap<u64, Module>> = RefCell::new(HashMap::new());
+    static INSTANCES: RefCell<HashMap<u64, InstanceState>> = RefCell::new(HashMap::new());
+    // Standalone `new WebAssembly.Memory(...)` objects; instance
+    // memories live inside their InstanceState.
+    static MEMORIES: RefCell<HashMap<u64, Memory>> = RefCell::new(HashMap::new());
+    static WAITING: RefCell<HashMap<u64, Waiter>> = RefCell::new(HashMap::new());
+    static PENDING_STREAMS: RefCell<Vec<PendingStream>> = const { RefCell::new(Vec::new()) };
+    static NEXT_ID: RefCell<u64> = const { RefCell::new(1) };
+}
+
+/// Install the `WebAssembly` global.
+pub fn register(context: &mut Context) {
+    let namespace = JsObject::with_null_proto();
+    method(&namespace, "Module", construct_module, context).expect("WebAssembly.Module");
+    method(&namespace, "Instance", construct_instance, context).expect("WebAssembly.Instance");
+    method(&namespace, "Memory", construct_memory, context).expect("WebAssembly.Memory");
+    method(&namespace, "compile", compile, context).expect("WebAssembly.compile");
+    method(&namespace, "compileStreaming", compile_streaming, context)
+        .expect("WebAssembly.compileStreaming");
+    method(&namespace, "instantiate", instantiate, context).expect("WebAssembly.instantiate");
+    method(&namespace, "instantiateStreaming", instantiate_streaming, context)
+        .expect("WebAssembly.instantiateStreaming");
+    method(&namespace, "validate", validate, context).expect("WebAssembly.validate");
+    context
+        .register_global_property(
+            js_string!("WebAssembly"),
+            namespace,
+            boa_engine::property::Attribute::all(),
+        )
+        .expect("registering WebAssembly");
+}
+
+/// Drop every module, instance, and memory (navigation replaced the
+/// page).
+pub fn clear() {
+    MODULES.with(|modules| modules.borrow_mut().clear());
+    INSTANCES.with(|instances| instances.borrow_mut().clear());
+    MEMORIES.with(|memories| memories.borrow_mut().clear());
+    WAITING.with(|waiting| waiting.borrow_mut().clear());
+    PENDING_STREAMS.with(|pending| pending.borrow_mut().clear());
+}
+
+fn next_id() -> u64 {
+    NEXT_ID.with(|next| {
+        let mut next = next.borrow_mut();
+        let id = *next;
+        *next += 1;
+        id
+    })
+}
+
+/// `new WebAssembly.Module(bytes)` — synchronous compile.
+fn construct_module(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let bytes = super::encoding::input_bytes(args.get_or_undefined(0), context)?;
+    let module = WasmRuntime::compile(&bytes)
+        .map_err(|error| JsNativeError::error().with_message(format!("CompileError: {error}")))?;
+    Ok(wrap_module(module, context)?.into())
+}
+
+/// `new WebAssembly.Instance(module, imports)`.
+fn construct_instance(
+    _this: &JsValue,
+    args: &[JsValue],
+    context: &mut Context,
+) -> JsResult<JsValue> {
+    let Some(module) = module_of(args.get_or_undefined(0), context)? else {
+        return Err(JsNativeError::typ()
+            .with_message("WebAssembly.Instance: expected a Module")
+            .into());
+    };
+    Ok(instantiate_module(module, args.get_or_undefined(1), context)?.into())
+}
+
+/// `new WebAssembly.Memory({ initial, maximum })`.
+fn construct_memory(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let Some(descriptor) = args.get_or_undefined(0).as_object().cloned() else {
+        return Err(JsNativeError::typ()
+            .with_message("WebAssembly.Memory: expected a descriptor")
+            .into());
+    };
+    let initial = descriptor.get(js_string!("initial"), context)?.to_number(context)? as u32;
+    let maximum = descriptor.get(js_string!("maximum"), context)?;
+    let max = if maximum.is_undefined() {
+        None
+    } else {
+        Some(maximum.to_number(context)? as u32)
+    };
+    let id = next_id();
+    MEMORIES.with(|memories| {
+        memories
+            .borrow_mut()
+            .insert(
+                id,
+                Memory::new(Limits {
+                    min: initial,
+                    max,
+                    shared: false,
+                }),
+            );
+    });
+    let object = JsObject::with_null_proto();
+    object.set(js_string!("__memoryId"), id, false, context)?;
+    install_memory_methods(&object, context)?;
+    Ok(object.into())
+}
+
+/// `WebAssembly.compile(bytes)` → a promise of a Module.
+fn compile(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let bytes = super::encoding::input_bytes(args.get_or_undefined(0), context)?;
+    let promise = match WasmRuntime::compile(&bytes) {
+        Ok(module) => JsPromise::resolve(wrap_module(module, context)?, context),
+        Err(error) => JsPromise::reject(
+            JsString::from(format!("CompileError: {error}")),
+            context,
+        ),
+    };
+    Ok(promise.into())
+}
+
+/// `WebAssembly.instantiate(bytes | module, imports)` → a promise of
+/// `{ module, instance }` for bytes, a bare instance for a Module.
+fn instantiate(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let input = args.get_or_undefined(0);
+    let imports = args.get_or_undefined(1).clone();
+    if let Some(module) = module_of(input, context)? {
+        let promise = match instantiate_module(module, &imports, context) {
+            Ok(instance) => JsPromise::resolve(instance, context),
+            Err(error) => JsPromise::reject(error.to_opaque(context), context),
+        };
+        return Ok(promise.into());
+    }
+    let bytes = super::encoding::input_bytes(input, context)?;
+    let module = match WasmRuntime::compile(&bytes) {
+        Ok(module) => module,
+        Err(error) => {
+            let promise = JsPromise::reject(
+                JsString::from(format!("CompileError: {error}")),
+                context,
+            );
+            return Ok(promise.into());
+        }
+    };
+    let wrapper = wrap_module(module.clone(), context)?;
+    let promise = match instantiate_module(module, &imports, context) {
+        Ok(instance) => {
+            let pair = JsObject::with_null_proto();
+            pair.set(js_string!("module"), wrapper, false, context)?;
+            pair.set(js_string!("instance"), instance, false, context)?;
+            JsPromise::resolve(pair, context)
+        }
+        Err(error) => JsPromise::reject(error.to_opaque(context), context),
+    };
+    Ok(promise.into())
+}
+
+/// `WebAssembly.validate(bytes)`.
+fn validate(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let bytes = super::encoding::input_bytes(args.get_or_undefined(0), context)?;
+    Ok(WasmRuntime::validate(&bytes).into())
+}
+
+/// `WebAssembly.compileStreaming(response | promise)`.
+fn compile_streaming(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    streaming(args, false, context)
+}
+
+/// `WebAssembly.instantiateStreaming(response | promise, imports)`.
+fn instantiate_streaming(
+    _this: &JsValue,
+    args: &[JsValue],
+    context: &mut Context,
+) -> JsResult<JsValue> {
+    streaming(args, true, context)
+}
+
+/// The shared streaming path. A thenable source — the canonical
+/// `instantiateStreaming(fetch(url))` — parks the waiter until the
+/// promise settles; a Response or URL starts the network stream
+/// immediately.
+fn streaming(args: &[JsValue], want_instance: bool, context: &mut Context) -> JsResult<JsValue> {
+    let source = args.get_or_undefined(0).clone();
+    let imports = args.get_or_undefined(1).clone();
+    let (promise, resolve, reject) = JsPromise::new_pending(context);
+    let waiter = Waiter {
+        imports,
+        resolve,
+        reject,
+        want_instance,
+    };
+
+    if let Some(object) = source.as_object() {
+        let then = object.get(js_string!("then"), context)?;
+        if let Some(then) = then.as_callable() {
+            let id = next_id();
+            WAITING.with(|waiting| {
+                waiting.borrow_mut().insert(id, waiter);
+            });
+            let on_resolved = NativeFunction::from_copy_closure(move |_this, args, context| {
+                stream_settled_source(id, args.get_or_undefined(0), context)
+            })
+            .to_js_function(context.realm());
+            let on_rejected = NativeFunction::from_copy_closure(move |_this, args, context| {
+                if let Some(waiter) = WAITING.with(|waiting| waiting.borrow_mut().remove(&id)) {
+                    waiter.reject.call(
+                        &JsValue::undefined(),
+                        &[args.get_or_undefined(0).clone()],
+                        context,
+                    )?;
+                }
+                Ok(JsValue::undefined())
+            })
+            .to_js_function(context.realm());
+            then.call(&source, &[on_resolved.into(), on_rejected.into()], context)?;
+            return Ok(promise.into());
+        }
+    }
+
+    begin_stream(&source, waiter, context)?;
+    Ok(promise.into())
+}
+
+/// The source promise resolved: pick up the parked waiter and start the
+/// network stream against what it produced.
+fn stream_settled_source(id: u64, source: &JsValue, context: &mut Context) -> JsResult<JsValue> {
+    if let Some(waiter) = WAITING.with(|waiting| waiting.borrow_mut().remove(&id)) {
+        begin_stream(source, waiter, context)?;
+    }
+    Ok(JsValue::undefined())
+}
+
+/// Start the chunked fetch-and-compile task for `source` — a Response
+/// wrapper (its final URL is re-fetched, hitting the now-warm cache) or
+/// a URL resolved against the document.
+fn begin_stream(source: &JsValue, waiter: Waiter, context: &mut Context) -> JsResult<()> {
+    let url = match source.as_object() {
+        Some(object) => {
+            let url = object.get(js_string!("url"), context)?;
+            if url.is_undefined() {
+                let error = JsString::from(
+                    "TypeError: expected a Response or URL to stream from",
+                );
+                waiter
+                    .reject
+                    .call(&JsValue::undefined(), &[error.into()], context)?;
+                return Ok(());
+            }
+            url.to_string(context)?.to_std_string_escaped()
+        }
+        None => {
+            let input = source.to_string(context)?.to_std_string_escaped();
+            match super::fetch::environment() {
+                Some((_, base_url)) => resolve_url(&base_url, &input),
+                None => input,
+            }
+        }
+    };
+    let Some((stack, _)) = super::fetch::environment() else {
+        let error = JsString::from("CompileError: no document loaded");
+        waiter
+            .reject
+            .call(&JsValue::undefined(), &[error.into()], context)?;
+        return Ok(());
+    };
+
+    let (result_tx, result_rx) = oneshot::channel();
+    tokio::spawn(async move {
+        let _ = result_tx.send(fetch_and_compile(stack, url).await);
+    });
+    PENDING_STREAMS.with(|pending| {
+        pending.borrow_mut().push(PendingStream {
+            result: result_rx,
+            imports: waiter.imports,
+            resolve: waiter.resolve,
+            reject: waiter.reject,
+            want_instance: waiter.want_instance,
+        });
+    });
+    Ok(())
+}
+
+/// Pull the module through the stack's chunk stream, compiling each
+/// section as its bytes land.
+async fn fetch_and_compile(stack: Arc<NetworkStack>, url: String) -> Result<Module, String> {
+    let (_head, mut body) = stack
+        .fetch_streaming(Request::get(url))
+        .await
+        .map_err(|error| error.to_string())?;
+    let mut compiler = WasmRuntime::compile_streaming();
+    while let Some(chunk) = body.next_chunk().await {
+        let chunk = chunk.map_err(|error| error.to_string())?;
+        compiler
+            .push(&chunk)
+            .map_err(|error| format!("CompileError: {error}"))?;
+    }
+    compiler
+        .finish()
+        .map_err(|error| format!("CompileError: {error}"))
+}
+
+/// Settle the promises of streaming compiles whose network task has
+/// finished. Driven from [`JsRuntime::pump`](super::JsRuntime::pump).
+pub fn pump(context: &mut Context) {
+    let mut settled: Vec<(Result<Module, String>, Waiter)> = Vec::new();
+    PENDING_STREAMS.with(|pending| {
+        pending.borrow_mut().retain_mut(|stream| {
+            let result = match stream.result.try_recv() {
+                Ok(result) => result,
+                Err(oneshot::error::TryRecvError::Empty) => return true,
+                // Task panicked or was dropped: surface as a compile error.
+                Err(oneshot::error::TryRecvError::Closed) => {
+                    Err("CompileError: stream task dropped".into())
+                }
+            };
+            settled.push((
+                result,
+                Waiter {
+                    imports: stream.imports.clone(),
+                    resolve: stream.resolve.clone(),
+                    reject: stream.reject.clone(),
+                    want_instance: stream.want_instance,
+                },
+            ));
+            false
+        });
+    });
+
+    for (result, waiter) in settled {
+        match result {
+            Ok(module) => {
+                if let Err(error) = settle_stream(module, &waiter, context) {
+                    let reason = error.to_opaque(context);
+                    let _ = waiter.reject.call(&JsValue::undefined(), &[reason], context);
+                }
+            }
+            Err(message) => {
+                let _ = waiter.reject.call(
+                    &JsValue::undefined(),
+                    &[JsString::from(message).into()],
+                    context,
+                );
+            }
+        }
+        // Each settled stream is a task; checkpoint its reactions.
+        context.run_jobs();
+    }
+}
+
+/// Resolve a finished stream: a Module wrapper for `compileStreaming`,
+/// the spec's `{ module, instance }` pair for `instantiateStreaming`.
+fn settle_stream(module: Module, waiter: &Waiter, context: &mut Context) -> JsResult<()> {
+    let wrapper = wrap_module(module.clone(), context)?;
+    let value: JsValue = if waiter.want_instance {
+        let instance = instantiate_module(module, &waiter.imports, context)?;
+        let pair = JsObject::with_null_proto();
+        pair.set(js_string!("module"), wrapper, false, context)?;
+        pair.set(js_string!("instance"), instance, false, context)?;
+        pair.into()
+    } else {
+        wrapper.into()
+    };
+    waiter
+        .resolve
+        .call(&JsValue::undefined(), &[value], context)?;
+    Ok(())
+}
+
+/// Store `module` and build its wrapper.
+fn wrap_module(module: Module, context: &mut Context) -> JsResult<JsObject> {
+    let id = next_id();
+    MODULES.with(|modules| {
+        modules.borrow_mut().insert(id, module);
+    });
+    let object = JsObject::with_null_proto();
+    object.set(js_string!("__wasmModuleId"), id, false, context)?;
+    Ok(object)
+}
+
+/// The decoded module behind a Module wrapper, if `value` is one.
+fn module_of(value: &JsValue, context: &mut Context) -> JsResult<Option<Module>> {
+    let Some(object) = value.as_object() else {
+        return Ok(None);
+    };
+    let id = object.get(js_string!("__wasmModuleId"), context)?;
+    if id.is_undefined() {
+        return Ok(None);
+    }
+    let id = id.to_number(context)? as u64;
+    Ok(MODULES.with(|modules| modules.borrow().get(&id).cloned()))
+}
+
+/// Instantiate `module` against the JS `imports` object and build the
+/// instance wrapper with its `exports`.
+fn instantiate_module(
+    module: Module,
+    imports: &JsValue,
+    context: &mut Context,
+) -> JsResult<JsObject> {
+    let mut import_functions = Vec::new();
+    for import in &module.imports {
+        if !matches!(import.kind, ImportKind::Function(_)) {
+            continue;
+        }
+        let function = imports
+            .as_object()
+            .map(|object| object.get(JsString::from(import.module.as_str()), context))
+            .transpose()?
+            .unwrap_or_default()
+            .as_object()
+            .map(|namespace| namespace.get(JsString::from(import.name.as_str()), context))
+            .transpose()?
+            .unwrap_or_default();
+        let Some(function) = function.as_object().filter(|f| f.is_callable()).cloned() else {
+            return Err(JsNativeError::typ()
+                .with_message(format!(
+                    "LinkError: import {}.{} is not a function",
+                    import.module, import.name
+                ))
+                .into());
+        };
+        import_functions.push(function);
+    }
+
+    let exports = module.exports.clone();
+    let features = WasmFeatures {
+        threads: super::shared::cross_origin_isolated(),
+        ..WasmFeatures::default()
+    };
+    let instance = WasmRuntime::instantiate_with(module, features).map_err(|error| {
+        JsNativeError::error().with_message(format!("LinkError: {error}"))
+    })?;
+    let id = next_id();
+    INSTANCES.with(|instances| {
+        instances.borrow_mut().insert(
+            id,
+            InstanceState {
+                instance,
+                imports: import_functions,
+            },
+        );
+    });
+
+    let object = JsObject::with_null_proto();
+    object.set(js_string!("__instanceId"), id, false, context)?;
+    let export_object = JsObject::with_null_proto();
+    for export in exports {
+        match export.kind {
+            ExportKind::Function(index) => {
+                // The closure captures only Copy data; everything else
+                // comes out of the registry at call time.
+                let function = NativeFunction::from_copy_closure(
+                    move |_this, args, context| call_export(id, index, args, context),
+                )
+                .to_js_function(context.realm());
+                export_object.set(
+                    JsString::from(export.name.as_str()),
+                    function,
+                    false,
+                    context,
+                )?;
+            }
+            ExportKind::Memory(_) => {
+                let memory = JsObject::with_null_proto();
+                memory.set(js_string!("__instanceId"), id, false, context)?;
+                install_memory_methods(&memory, context)?;
+                export_object.set(
+                    JsString::from(export.name.as_str()),
+                    memory,
+                    false,
+                    context,
+                )?;
+            }
+        }
+    }
+    object.set(js_string!("exports"), export_object, false, context)?;
+    Ok(object)
+}
+
+/// Run an exported function: marshal the arguments by the signature,
+/// interpret, marshal the results back. The instance is taken out of
+/// the registry for the duration, so imports that re-enter the same
+/// instance fail cleanly instead of aliasing its state.
+fn call_export(
+    instance_id: u64,
+    index: u32,
+    args: &[JsValue],
+    context: &mut Context,
+) -> JsResult<JsValue> {
+    let Some(mut state) = INSTANCES.with(|instances| instances.borrow_mut().remove(&instance_id))
+    else {
+        return Err(JsNativeError::error()
+            .with_message("RuntimeError: reentrant or dropped WebAssembly instance")
+            .into());
+    };
+    let result = run_export(&mut state, index, args, context);
+    INSTANCES.with(|instances| {
+        instances.borrow_mut().insert(instance_id, state);
+    });
+    result
+}
+
+fn run_export(
+    state: &mut InstanceState,
+    index: u32,
+    args: &[JsValue],
+    context: &mut Context,
+) -> JsResult<JsValue> {
+    let ty = state
+        .instance
+        .module()
+        .function_type(index as usize)
+        .map_err(runtime_error)?
+        .clone();
+    if ty.results.contains(&ValType::V128) {
+        return Err(JsNativeError::typ()
+            .with_message("v128 values cannot cross into JavaScript")
+            .into());
+    }
+    let mut values = Vec::with_capacity(ty.params.len());
+    for (position, param) in ty.params.iter().enumerate() {
+        values.push(to_wasm(args.get_or_undefined(position), *param, context)?);
+    }
+    // Import signatures, cloned up front: the interpreter holds the
+    // instance mutably while the host callback runs.
+    let import_types: Vec<FuncType> = (0..state.instance.module().imported_functions())
+        .map(|import| state.instance.module().function_type(import).cloned())
+        .collect::<Result<_, _>>()
+        .map_err(runtime_error)?;
+    let imports = state.imports.clone();
+
+    let context_cell = RefCell::new(context);
+    let mut host = |import: usize, arguments: &[Value]| -> Result<Vec<Value>, WasmError> {
+        let function = imports
+            .get(import)
+            .ok_or_else(|| WasmError::Trap(format!("missing import {import}")))?;
+        let ty = import_types
+            .get(import)
+            .ok_or_else(|| WasmError::Trap(format!("missing import type {import}")))?;
+        let mut context = context_cell.borrow_mut();
+        let js_args: Vec<JsValue> = arguments.iter().map(|value| to_js(*value)).collect();
+        let result = function
+            .call(&JsValue::undefined(), &js_args, &mut context)
+            .map_err(|error| WasmError::Trap(error.to_string()))?;
+        match ty.results.as_slice() {
+            [] => Ok(Vec::new()),
+            [result_type] => to_wasm(&result, *result_type, &mut context)
+                .map(|value| vec![value])
+                .map_err(|error| WasmError::Trap(error.to_string())),
+            _ => Err(WasmError::Trap("multi-result imports unsupported".into())),
+        }
+    };
+    let results = state
+        .instance
+        .call_function(index as usize, &values, &mut host)
+        .map_err(runtime_error);
+    drop(host);
+    let context = context_cell.into_inner();
+
+    match results?.as_slice() {
+        [] => Ok(JsValue::undefined()),
+        [value] => Ok(to_js(*value)),
+        many => {
+            let array = boa_engine::object::builtins::JsArray::from_iter(
+                many.iter().map(|value| to_js(*value)),
+                context,
+            );
+            Ok(array.into())
+        }
+    }
+}
+
+/// The `buffer` accessor and `grow` on a memory wrapper, backed by
+/// either an instance's memory (`__instanceId`) or a standalone one
+/// (`__memoryId`).
+fn install_memory_methods(object: &JsObject, context: &mut Context) -> JsResult<()> {
+    method(object, "grow", memory_grow, context)?;
+    let getter = NativeFunction::from_fn_ptr(memory_buffer).to_js_function(context.realm());
+    object.define_property_or_throw(
+        js_string!("buffer"),
+        boa_engine::property::PropertyDescriptor::builder()
+            .get(getter)
+            .enumerable(false)
+            .configurable(true),
+        context,
+    )?;
+    Ok(())
+}
+
+/// `memory.buffer` — a `SharedArrayBuffer` view for shared memories,
+/// otherwise a snapshot of the linear memory as an `ArrayBuffer`,
+/// consistent with the engine's other snapshot views.
+fn memory_buffer(this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    if let Some(handle) = with_memory(this, context, |memory| memory.shared_handle())? {
+        return Ok(super::shared::wrap(handle, context)?.into());
+    }
+    let bytes = with_memory(this, context, |memory| memory.snapshot())?;
+    Ok(JsArrayBuffer::from_byte_block(bytes, context)?.into())
+}
+
+/// `memory.grow(delta)` — the old size in pages, or -1 when refused.
+fn memory_grow(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let delta = args.get_or_undefined(0).to_number(context)? as u32;
+    let result = with_memory(this, context, |memory| memory.grow(delta))?;
+    Ok(result.into())
+}
+
+/// Run `f` against the memory a wrapper points at.
+fn with_memory<T>(
+    this: &JsValue,
+    context: &mut Context,
+    f: impl FnOnce(&mut Memory) -> T,
+) -> JsResult<T> {
+    let Some(object) = this.as_object() else {
+        return Err(missing_memory());
+    };
+    let instance_id = object.get(js_string!("__instanceId"), context)?;
+    if !instance_id.is_undefined() {
+        let id = instance_id.to_number(context)? as u64;
+        return INSTANCES.with(|instances| {
+            instances
+                .borrow_mut()
+                .get_mut(&id)
+                .and_then(|state| state.instance.memory_mut().map(f))
+                .ok_or_else(missing_memory)
+        });
+    }
+    let id = object.get(js_string!("__memoryId"), context)?.to_number(context)? as u64;
+    MEMORIES.with(|memories| {
+        memories
+            .borrow_mut()
+            .get_mut(&id)
+            .map(f)
+            .ok_or_else(missing_memory)
+    })
+}
+
+fn missing_memory() -> boa_engine::JsError {
+    JsNativeError::typ()
+        .with_message("WebAssembly.Memory: no memory behind this object")
+        .into()
+}
+
+fn runtime_error(error: WasmError) -> boa_engine::JsError {
+    JsNativeError::error()
+        .with_message(format!("RuntimeError: {error}"))
+        .into()
+}
+
+/// Convert a JS argument to a wasm value of the expected type.
+fn to_wasm(value: &JsValue, ty: ValType, context: &mut Context) -> JsResult<Value> {
+    if ty == ValType::V128 {
+        // Per spec: vectors never cross the JS boundary.
+        return Err(JsNativeError::typ()
+            .with_message("v128 values cannot cross into JavaScript")
+            .into());
+    }
+    let number = value.to_number(context)?;
+    Ok(match ty {
+        ValType::I32 => Value::I32(number as i64 as i32),
+        ValType::I64 => Value::I64(number as i64),
+        ValType::F32 => Value::F32(number as f32),
+        ValType::F64 => Value::F64(number),
+        ValType::V128 => unreachable!("rejected above"),
+    })
+}
+
+/// Convert a wasm value to JS. Everything goes out as a number; i64
+/// precision past 2^53 is the marshalling's known limit.
+fn to_js(value: Value) -> JsValue {
+    match value {
+        Value::I32(v) => JsValue::from(v),
+        Value::I64(v) => JsValue::from(v as f64),
+        Value::F32(v) => JsValue::from(f64::from(v)),
+        Value::F64(v) => JsValue::from(v),
+        // Guarded out in run_export; vectors stay inside wasm.
+        Value::V128(_) => JsValue::undefined(),
+    }
+}
+
+/// Install a native method on `object`.
+fn method(
+    object: &JsObject,
+    name: &str,
+    function: fn(&JsValue, &[JsValue], &mut Context) -> JsResult<JsValue>,
+    context: &mut Context,
+) -> JsResult<()> {
+    object.set(
+        JsString::from(name),
+        NativeFunction::from_fn_ptr(function).to_js_function(context.realm()),
+        false,
+        context,
+    )?;
+    Ok(())
+}
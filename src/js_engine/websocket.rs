@@ -0,0 +1,256 @@
+//! `WebSocket` binding over [`crate::network::websocket`].
+//!
+//! Each JS `WebSocket` owns a tokio task driving the underlying
+//! [`WebSocketConnection`]; events cross back into the single-threaded Boa
+//! context through an mpsc queue drained by [`pump`], which fires the
+//! `onopen`/`onmessage`/`onerror`/`onclose` handlers.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use boa_engine::object::builtins::JsArrayBuffer;
+use boa_engine::{js_string, Context, JsArgs, JsObject, JsResult, JsValue, NativeFunction};
+use tokio::sync::mpsc;
+
+use crate::network::websocket::{WebSocketConnection, WsMessage};
+
+/// CONNECTING / OPEN / CLOSING / CLOSED, per the spec constants.
+const CONNECTING: u8 = 0;
+const OPEN: u8 = 1;
+const CLOSED: u8 = 3;
+
+enum SocketEvent {
+    Open,
+    Message(WsMessage),
+    Error(String),
+    Closed { code: Option<u16>, reason: String },
+}
+
+struct SocketHandle {
+    object: JsObject,
+    events: mpsc::UnboundedReceiver<SocketEvent>,
+    outgoing: mpsc::UnboundedSender<WsMessage>,
+}
+
+thread_local! {
+    static SOCKETS: RefCell<HashMap<u64, SocketHandle>> = RefCell::new(HashMap::new());
+    static NEXT_ID: RefCell<u64> = const { RefCell::new(1) };
+}
+
+/// Install the `WebSocket` constructor on the global object.
+pub fn register(context: &mut Context) {
+    let constructor = NativeFunction::from_fn_ptr(construct);
+    context
+        .register_global_callable(js_string!("WebSocket"), 1, constructor)
+        .expect("registering WebSocket");
+}
+
+fn construct(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let url = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    let protocols: Vec<String> = match args.get(1) {
+        Some(JsValue::String(s)) => vec![s.to_std_string_escaped()],
+        _ => Vec::new(),
+    };
+
+    let object = JsObject::with_null_proto();
+    object.set(js_string!("url"), js_string!(url.clone()), false, context)?;
+    object.set(js_string!("readyState"), CONNECTING, false, context)?;
+
+    let (event_tx, event_rx) = mpsc::unbounded_channel();
+    let (out_tx, mut out_rx) = mpsc::unbounded_channel::<WsMessage>();
+    let id = NEXT_ID.with(|n| {
+        let mut n = n.borrow_mut();
+        let id = *n;
+        *n += 1;
+        id
+    });
+    object.set(js_string!("__socketId"), id, false, context)?;
+
+    // The connection task lives on the engine's tokio runtime; it owns the
+    // socket and forwards events back to the JS thread.
+    tokio::spawn(async move {
+        let protocol_refs: Vec<&str> = protocols.iter().map(String::as_str).collect();
+        let mut conn = match WebSocketConnection::connect(&url, &protocol_refs).await {
+            Ok(conn) => conn,
+            Err(err) => {
+                let _ = event_tx.send(SocketEvent::Error(err.to_string()));
+                return;
+            }
+        };
+        let _ = event_tx.send(SocketEvent::Open);
+        loop {
+            tokio::select! {
+                incoming = conn.recv() => match incoming {
+                    Ok(WsMessage::Close { code, reason }) => {
+                        let _ = event_tx.send(SocketEvent::Closed { code, reason });
+                        break;
+                    }
+                    Ok(message @ (WsMessage::Text(_) | WsMessage::Binary(_))) => {
+                        let _ = event_tx.send(SocketEvent::Message(message));
+                    }
+                    Ok(_) => {} // pings are answered inside recv()
+                    Err(err) => {
+                        let _ = event_tx.send(SocketEvent::Error(err.to_string()));
+                        break;
+                    }
+                },
+                outgoing = out_rx.recv() => match outgoing {
+                    Some(message) => {
+                        if conn.send(message).await.is_err() {
+                            break;
+                        }
+                    }
+                    None => break,
+                },
+            }
+        }
+    });
+
+    SOCKETS.with(|sockets| {
+        sockets.borrow_mut().insert(
+            id,
+            SocketHandle {
+                object: object.clone(),
+                events: event_rx,
+                outgoing: out_tx,
+            },
+        );
+    });
+
+    install_methods(&object, context)?;
+    Ok(object.into())
+}
+
+fn install_methods(object: &JsObject, context: &mut Context) -> JsResult<()> {
+    object.set(
+        js_string!("send"),
+        NativeFunction::from_fn_ptr(|this, args, context| {
+            let id = socket_id(this, context)?;
+            let data = args.get_or_undefined(0);
+            let message = match data {
+                JsValue::String(s) => WsMessage::Text(s.to_std_string_escaped()),
+                other => WsMessage::Binary(
+                    other
+                        .to_string(context)?
+                        .to_std_string_escaped()
+                        .into_bytes(),
+                ),
+            };
+            SOCKETS.with(|sockets| {
+                if let Some(handle) = sockets.borrow().get(&id) {
+                    let _ = handle.outgoing.send(message);
+                }
+            });
+            Ok(JsValue::undefined())
+        })
+        .to_js_function(context.realm()),
+        false,
+        context,
+    )?;
+
+    object.set(
+        js_string!("close"),
+        NativeFunction::from_fn_ptr(|this, args, context| {
+            let id = socket_id(this, context)?;
+            let code = args
+                .get(0)
+                .and_then(|v| v.as_number())
+                .map(|n| n as u16)
+                .or(Some(1000));
+            let reason = match args.get(1) {
+                Some(JsValue::String(s)) => s.to_std_string_escaped(),
+                _ => String::new(),
+            };
+            SOCKETS.with(|sockets| {
+                if let Some(handle) = sockets.borrow().get(&id) {
+                    let _ = handle.outgoing.send(WsMessage::Close { code, reason });
+                }
+            });
+            Ok(JsValue::undefined())
+        })
+        .to_js_function(context.realm()),
+        false,
+        context,
+    )?;
+    Ok(())
+}
+
+fn socket_id(this: &JsValue, context: &mut Context) -> JsResult<u64> {
+    let id = this
+        .as_object()
+        .map(|o| o.get(js_string!("__socketId"), context))
+        .transpose()?
+        .unwrap_or_default();
+    Ok(id.to_number(context)? as u64)
+}
+
+/// Drain pending socket events and dispatch them to the JS handlers.
+pub fn pump(context: &mut Context) {
+    let mut pending: Vec<(JsObject, SocketEvent)> = Vec::new();
+    let mut finished: Vec<u64> = Vec::new();
+    SOCKETS.with(|sockets| {
+        let mut sockets = sockets.borrow_mut();
+        for (id, handle) in sockets.iter_mut() {
+            while let Ok(event) = handle.events.try_recv() {
+                if matches!(event, SocketEvent::Closed { .. } | SocketEvent::Error(_)) {
+                    finished.push(*id);
+                }
+                pending.push((handle.object.clone(), event));
+            }
+        }
+        for id in &finished {
+            sockets.remove(id);
+        }
+    });
+
+    for (object, event) in pending {
+        let _ = dispatch(&object, event, context);
+        // Each delivery is a task; checkpoint its promise reactions.
+        context.run_jobs();
+    }
+}
+
+fn dispatch(object: &JsObject, event: SocketEvent, context: &mut Context) -> JsResult<()> {
+    let (handler, payload) = match event {
+        SocketEvent::Open => {
+            object.set(js_string!("readyState"), OPEN, false, context)?;
+            (js_string!("onopen"), JsValue::undefined())
+        }
+        SocketEvent::Message(WsMessage::Text(text)) => {
+            let event = JsObject::with_null_proto();
+            event.set(js_string!("data"), js_string!(text), false, context)?;
+            (js_string!("onmessage"), event.into())
+        }
+        SocketEvent::Message(WsMessage::Binary(bytes)) => {
+            let event = JsObject::with_null_proto();
+            let buffer = JsArrayBuffer::from_byte_block(bytes, context)?;
+            event.set(js_string!("data"), buffer, false, context)?;
+            (js_string!("onmessage"), event.into())
+        }
+        SocketEvent::Message(_) => return Ok(()),
+        SocketEvent::Error(message) => {
+            object.set(js_string!("readyState"), CLOSED, false, context)?;
+            let event = JsObject::with_null_proto();
+            event.set(js_string!("message"), js_string!(message), false, context)?;
+            (js_string!("onerror"), event.into())
+        }
+        SocketEvent::Closed { code, reason } => {
+            object.set(js_string!("readyState"), CLOSED, false, context)?;
+            let event = JsObject::with_null_proto();
+            event.set(
+                js_string!("code"),
+                code.map_or(JsValue::undefined(), JsValue::from),
+                false,
+                context,
+            )?;
+            event.set(js_string!("reason"), js_string!(reason), false, context)?;
+            (js_string!("onclose"), event.into())
+        }
+    };
+
+    let callback = object.get(handler, context)?;
+    if let Some(callback) = callback.as_callable() {
+        callback.call(&object.clone().into(), &[payload], context)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,269 @@
+//! The `window` family of globals: `location`, `navigator`, `screen`,
+//! and the viewport metrics.
+//!
+//! `window` and `self` are the global object itself, so everything
+//! registered elsewhere is reachable through them. The environment the
+//! accessors read — document URL, viewport, screen geometry — lives in a
+//! thread-local the UI keeps current: [`configure`] on commit,
+//! [`set_viewport`] on resize, [`set_screen`] on monitor or scale
+//! changes. `location.assign`/`reload` don't navigate themselves; they
+//! queue a request the UI drains via [`take_navigation`], the same
+//! hand-off the History API uses for its URL changes.
+
+use std::cell::RefCell;
+
+use boa_engine::{
+    js_string, Context, JsArgs, JsObject, JsResult, JsString, JsValue, NativeFunction,
+};
+
+use crate::renderer::loader::resolve_url;
+
+/// The UA string pages see. One browser, one string.
+pub const USER_AGENT: &str = "Mozilla/5.0 (X11; Linux x86_64) Binix/0.1";
+
+#[derive(Default)]
+struct Environment {
+    /// The committed document URL.
+    href: String,
+    viewport_width: f32,
+    viewport_height: f32,
+    screen_width: f32,
+    screen_height: f32,
+    device_pixel_ratio: f32,
+}
+
+/// A navigation the page requested through `location`.
+pub enum NavigationRequest {
+    /// `location.assign` / `location.href = …`: load this URL.
+    Load(String),
+    /// `location.reload()`: refetch the current document.
+    Reload,
+    /// `form.submit()`: load the prepared request (method, body,
+    /// content type) built by [`super::forms`].
+    Submit(crate::network::Request),
+}
+
+thread_local! {
+    static ENVIRONMENT: RefCell<Environment> = RefCell::new(Environment {
+        device_pixel_ratio: 1.0,
+        ..Environment::default()
+    });
+    // Set when script asked to navigate; the UI drains it.
+    static NAVIGATION: RefCell<Option<NavigationRequest>> = const { RefCell::new(None) };
+}
+
+/// Install `window`, `self`, `location`, `navigator`, `screen`, and the
+/// viewport metrics on the global object.
+pub fn register(context: &mut Context) {
+    let global = context.global_object();
+    for alias in ["window", "self"] {
+        global
+            .set(JsString::from(alias), global.clone(), false, context)
+            .expect("aliasing the global object");
+    }
+    viewport_accessor(&global, "innerWidth", |env| env.viewport_width, context);
+    viewport_accessor(&global, "innerHeight", |env| env.viewport_height, context);
+    viewport_accessor(&global, "devicePixelRatio", |env| env.device_pixel_ratio, context);
+
+    let navigator = JsObject::with_null_proto();
+    navigator
+        .set(js_string!("userAgent"), js_string!(USER_AGENT), false, context)
+        .and_then(|_| navigator.set(js_string!("language"), js_string!("en-US"), false, context))
+        .and_then(|_| navigator.set(js_string!("onLine"), true, false, context))
+        .expect("populating navigator");
+    global
+        .set(js_string!("navigator"), navigator, false, context)
+        .expect("registering navigator");
+
+    let screen = JsObject::with_null_proto();
+    viewport_accessor(&screen, "width", |env| env.screen_width, context);
+    viewport_accessor(&screen, "height", |env| env.screen_height, context);
+    viewport_accessor(&screen, "availWidth", |env| env.screen_width, context);
+    viewport_accessor(&screen, "availHeight", |env| env.screen_height, context);
+    global
+        .set(js_string!("screen"), screen, false, context)
+        .expect("registering screen");
+
+    let location = JsObject::with_null_proto();
+    install_href(&location, context).expect("installing location.href");
+    location_part(&location, "origin", origin_of, context);
+    location_part(&location, "protocol", protocol_of, context);
+    location_part(&location, "host", host_of, context);
+    location_part(&location, "pathname", pathname_of, context);
+    method(&location, "assign", assign, context).expect("installing location method");
+    method(&location, "reload", reload, context).expect("installing location method");
+    global
+        .set(js_string!("location"), location, false, context)
+        .expect("registering location");
+}
+
+/// Point `location` at the committed document. Called on every commit,
+/// and on SPA URL changes so `location.href` follows `pushState`.
+pub fn configure(url: &str) {
+    ENVIRONMENT.with(|env| env.borrow_mut().href = url.to_owned());
+}
+
+/// Update the viewport size scripts see.
+/// [`Tab::set_viewport`](crate::ui::tab::Tab::set_viewport) forwards
+/// resizes here.
+pub fn set_viewport(width: f32, height: f32) {
+    ENVIRONMENT.with(|env| {
+        let mut env = env.borrow_mut();
+        env.viewport_width = width;
+        env.viewport_height = height;
+    });
+}
+
+/// Update the screen geometry and scale factor scripts see. The UI
+/// calls this at startup and when the window changes monitors.
+pub fn set_screen(width: f32, height: f32, device_pixel_ratio: f32) {
+    ENVIRONMENT.with(|env| {
+        let mut env = env.borrow_mut();
+        env.screen_width = width;
+        env.screen_height = height;
+        env.device_pixel_ratio = device_pixel_ratio;
+    });
+}
+
+/// The navigation the page requested through `location`, if any since
+/// the last call. The UI turns it into a real load.
+pub fn take_navigation() -> Option<NavigationRequest> {
+    NAVIGATION.with(|slot| slot.borrow_mut().take())
+}
+
+/// Queue a navigation on behalf of a sibling binding (form submission
+/// uses this); newest wins, like `location` itself.
+pub(crate) fn request_navigation(request: NavigationRequest) {
+    NAVIGATION.with(|slot| *slot.borrow_mut() = Some(request));
+}
+
+/// `location.href`: getter reads the environment, setter navigates.
+fn install_href(location: &JsObject, context: &mut Context) -> JsResult<()> {
+    let getter = NativeFunction::from_fn_ptr(|_this, _args, _context| {
+        Ok(JsString::from(current_href()).into())
+    })
+    .to_js_function(context.realm());
+    let setter = NativeFunction::from_fn_ptr(assign).to_js_function(context.realm());
+    location.define_property_or_throw(
+        js_string!("href"),
+        boa_engine::property::PropertyDescriptor::builder()
+            .get(getter)
+            .set(setter)
+            .enumerable(true)
+            .configurable(true),
+        context,
+    )?;
+    Ok(())
+}
+
+/// A read-only accessor on `location` derived from the current URL.
+fn location_part(
+    location: &JsObject,
+    name: &str,
+    derive: fn(&str) -> String,
+    context: &mut Context,
+) {
+    let getter = NativeFunction::from_copy_closure(move |_this, _args, _context| {
+        Ok(JsString::from(derive(&current_href())).into())
+    })
+    .to_js_function(context.realm());
+    location
+        .define_property_or_throw(
+            JsString::from(name),
+            boa_engine::property::PropertyDescriptor::builder()
+                .get(getter)
+                .enumerable(true)
+                .configurable(true),
+            context,
+        )
+        .expect("installing location accessor");
+}
+
+/// A read-only accessor reading one environment field, for the viewport
+/// and screen metrics.
+fn viewport_accessor(
+    object: &JsObject,
+    name: &str,
+    read: fn(&Environment) -> f32,
+    context: &mut Context,
+) {
+    let getter = NativeFunction::from_copy_closure(move |_this, _args, _context| {
+        Ok(ENVIRONMENT.with(|env| f64::from(read(&env.borrow()))).into())
+    })
+    .to_js_function(context.realm());
+    object
+        .define_property_or_throw(
+            JsString::from(name),
+            boa_engine::property::PropertyDescriptor::builder()
+                .get(getter)
+                .enumerable(true)
+                .configurable(true),
+            context,
+        )
+        .expect("installing viewport accessor");
+}
+
+fn assign(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let target = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    let url = resolve_url(&current_href(), &target);
+    NAVIGATION.with(|slot| *slot.borrow_mut() = Some(NavigationRequest::Load(url)));
+    Ok(JsValue::undefined())
+}
+
+fn reload(_this: &JsValue, _args: &[JsValue], _context: &mut Context) -> JsResult<JsValue> {
+    NAVIGATION.with(|slot| *slot.borrow_mut() = Some(NavigationRequest::Reload));
+    Ok(JsValue::undefined())
+}
+
+fn current_href() -> String {
+    ENVIRONMENT.with(|env| env.borrow().href.clone())
+}
+
+/// The current document's origin, for sibling bindings (messaging
+/// checks target origins against it).
+pub(crate) fn current_origin() -> String {
+    origin_of(&current_href())
+}
+
+fn origin_of(url: &str) -> String {
+    crate::network::http3::origin_of(url).unwrap_or_default()
+}
+
+fn protocol_of(url: &str) -> String {
+    url.split_once("//")
+        .map(|(scheme, _)| scheme.to_owned())
+        .unwrap_or_default()
+}
+
+fn host_of(url: &str) -> String {
+    let rest = url.split_once("//").map_or(url, |(_, rest)| rest);
+    rest.split(['/', '?', '#']).next().unwrap_or_default().to_owned()
+}
+
+fn pathname_of(url: &str) -> String {
+    let rest = url.split_once("//").map_or(url, |(_, rest)| rest);
+    match rest.find('/') {
+        Some(slash) => rest[slash..]
+            .split(['?', '#'])
+            .next()
+            .unwrap_or("/")
+            .to_owned(),
+        None => "/".to_owned(),
+    }
+}
+
+/// Install a native method on `object`.
+fn method(
+    object: &JsObject,
+    name: &str,
+    function: fn(&JsValue, &[JsValue], &mut Context) -> JsResult<JsValue>,
+    context: &mut Context,
+) -> JsResult<()> {
+    object.set(
+        JsString::from(name),
+        NativeFunction::from_fn_ptr(function).to_js_function(context.realm()),
+        false,
+        context,
+    )?;
+    Ok(())
+}
@@ -0,0 +1,282 @@
+//! Dedicated Web Workers: one JS context per worker, on its own thread.
+//!
+//! Boa contexts are single-threaded, so each `Worker` gets a fresh
+//! context on a spawned thread with its own timer queue and console.
+//! Messages cross as [`CloneValue`]s — the structured clone snapshots
+//! from [`super::clone`] — over std channels: the worker thread blocks
+//! on its inbox between timer ticks, and the parent side drains worker
+//! output in [`pump`] like the other async bindings.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::time::Duration;
+
+use boa_engine::{
+    js_string, Context, JsArgs, JsObject, JsResult, JsString, JsValue, NativeFunction, Source,
+};
+
+use super::clone::{from_clone, to_clone, CloneValue};
+use super::fetch;
+
+struct WorkerHandle {
+    object: JsObject,
+    to_worker: Sender<CloneValue>,
+    from_worker: Receiver<CloneValue>,
+    terminated: Arc<AtomicBool>,
+}
+
+thread_local! {
+    static WORKERS: RefCell<HashMap<u64, WorkerHandle>> = RefCell::new(HashMap::new());
+    static NEXT_ID: RefCell<u64> = const { RefCell::new(1) };
+}
+
+/// Worker threads form a bounded pool: each `Worker` (and through it,
+/// each wasm thread) is an OS thread, so a page can't fork-bomb the
+/// host by constructing workers in a loop.
+const MAX_WORKER_THREADS: usize = 8;
+
+/// Live worker threads across every page (threads outlive the
+/// thread-local registry until they observe `terminated`).
+static ACTIVE_WORKERS: AtomicUsize = AtomicUsize::new(0);
+
+/// Releases a pool slot when the worker thread exits, however it exits.
+struct PoolSlot;
+
+impl Drop for PoolSlot {
+    fn drop(&mut self) {
+        ACTIVE_WORKERS.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Install the `Worker` constructor on the global object.
+pub fn register(context: &mut Context) {
+    context
+        .register_global_callable(js_string!("Worker"), 1, NativeFunction::from_fn_ptr(construct))
+        .expect("registering Worker");
+}
+
+/// Terminate every worker (navigation replaced the page).
+pub fn clear() {
+    WORKERS.with(|workers| {
+        for handle in workers.borrow_mut().values() {
+            handle.terminated.store(true, Ordering::Relaxed);
+        }
+        workers.borrow_mut().clear();
+    });
+}
+
+fn construct(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let specifier = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    let Some((stack, base_url)) = fetch::environment() else {
+        return Err(boa_engine::JsNativeError::error()
+            .with_message("Worker: no document loaded")
+            .into());
+    };
+    let url = crate::renderer::loader::resolve_url(&base_url, &specifier);
+
+    if ACTIVE_WORKERS.load(Ordering::Relaxed) >= MAX_WORKER_THREADS {
+        return Err(boa_engine::JsNativeError::error()
+            .with_message("Worker: worker pool exhausted")
+            .into());
+    }
+    ACTIVE_WORKERS.fetch_add(1, Ordering::Relaxed);
+
+    let (to_worker, worker_inbox) = mpsc::channel::<CloneValue>();
+    let (worker_outbox, from_worker) = mpsc::channel::<CloneValue>();
+    let terminated = Arc::new(AtomicBool::new(false));
+
+    let object = JsObject::with_null_proto();
+    let id = NEXT_ID.with(|next| {
+        let mut next = next.borrow_mut();
+        let id = *next;
+        *next += 1;
+        id
+    });
+    object.set(js_string!("__workerId"), id, false, context)?;
+    method(&object, "postMessage", post_message, context)?;
+    method(&object, "terminate", terminate, context)?;
+
+    let thread_terminated = Arc::clone(&terminated);
+    std::thread::spawn(move || {
+        let _slot = PoolSlot;
+        // The script fetch happens on the worker thread, through the
+        // same stack as every other load.
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build();
+        let Ok(runtime) = runtime else {
+            return;
+        };
+        let source = runtime.block_on(async {
+            stack
+                .fetch(crate::network::Request::get(url))
+                .await
+                .map(|response| response.text())
+        });
+        let Ok(source) = source else {
+            return;
+        };
+        run_worker(&source, worker_inbox, worker_outbox, thread_terminated);
+    });
+
+    WORKERS.with(|workers| {
+        workers.borrow_mut().insert(
+            id,
+            WorkerHandle {
+                object: object.clone(),
+                to_worker,
+                from_worker,
+                terminated,
+            },
+        );
+    });
+    Ok(object.into())
+}
+
+/// The worker thread body: its own context with `postMessage` back to
+/// the parent, an `onmessage` global handler, timers and console.
+fn run_worker(
+    source: &str,
+    inbox: Receiver<CloneValue>,
+    outbox: Sender<CloneValue>,
+    terminated: Arc<AtomicBool>,
+) {
+    let mut context = Context::default();
+    super::console::register(&mut context);
+    super::crypto::register(&mut context);
+    super::encoding::register(&mut context);
+    super::timers::register(&mut context);
+    install_worker_post_message(&mut context, outbox);
+    let _ = context.eval(Source::from_bytes(source));
+    context.run_jobs();
+
+    while !terminated.load(Ordering::Relaxed) {
+        match inbox.recv_timeout(Duration::from_millis(10)) {
+            Ok(message) => {
+                let _ = deliver_message(&mut context, &message);
+                context.run_jobs();
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            // Parent dropped the sender: the worker is done.
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+        super::timers::run_due(&mut context);
+        context.run_jobs();
+    }
+}
+
+thread_local! {
+    // The worker thread's channel back to its parent, installed before
+    // the worker script runs.
+    static OUTBOX: RefCell<Option<Sender<CloneValue>>> = const { RefCell::new(None) };
+}
+
+fn install_worker_post_message(context: &mut Context, outbox: Sender<CloneValue>) {
+    OUTBOX.with(|slot| *slot.borrow_mut() = Some(outbox));
+    context
+        .register_global_callable(
+            js_string!("postMessage"),
+            1,
+            NativeFunction::from_fn_ptr(|_this, args, context| {
+                let message = to_clone(args.get_or_undefined(0), context)?;
+                OUTBOX.with(|slot| {
+                    if let Some(outbox) = slot.borrow().as_ref() {
+                        let _ = outbox.send(message);
+                    }
+                });
+                Ok(JsValue::undefined())
+            }),
+        )
+        .expect("registering worker postMessage");
+}
+
+/// Fire the worker-global `onmessage` handler with `message`.
+fn deliver_message(context: &mut Context, message: &CloneValue) -> JsResult<()> {
+    let handler = context
+        .global_object()
+        .get(js_string!("onmessage"), context)?;
+    let Some(handler) = handler.as_callable() else {
+        return Ok(());
+    };
+    let event = JsObject::with_null_proto();
+    event.set(js_string!("data"), from_clone(message, context)?, false, context)?;
+    let _ = handler.call(&JsValue::undefined(), &[event.into()], context);
+    Ok(())
+}
+
+fn post_message(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let id = worker_id(this, context)?;
+    let message = to_clone(args.get_or_undefined(0), context)?;
+    WORKERS.with(|workers| {
+        if let Some(handle) = workers.borrow().get(&id) {
+            let _ = handle.to_worker.send(message);
+        }
+    });
+    Ok(JsValue::undefined())
+}
+
+fn terminate(this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let id = worker_id(this, context)?;
+    WORKERS.with(|workers| {
+        if let Some(handle) = workers.borrow_mut().remove(&id) {
+            handle.terminated.store(true, Ordering::Relaxed);
+        }
+    });
+    Ok(JsValue::undefined())
+}
+
+/// Deliver messages workers posted back, via each wrapper's `onmessage`.
+/// Driven from [`JsRuntime::pump`](super::JsRuntime::pump).
+pub fn pump(context: &mut Context) {
+    let mut pending: Vec<(JsObject, CloneValue)> = Vec::new();
+    WORKERS.with(|workers| {
+        for handle in workers.borrow().values() {
+            while let Ok(message) = handle.from_worker.try_recv() {
+                pending.push((handle.object.clone(), message));
+            }
+        }
+    });
+    for (object, message) in pending {
+        let _ = deliver_to_object(&object, &message, context);
+        context.run_jobs();
+    }
+}
+
+fn deliver_to_object(object: &JsObject, message: &CloneValue, context: &mut Context) -> JsResult<()> {
+    let handler = object.get(js_string!("onmessage"), context)?;
+    let Some(handler) = handler.as_callable() else {
+        return Ok(());
+    };
+    let event = JsObject::with_null_proto();
+    event.set(js_string!("data"), from_clone(message, context)?, false, context)?;
+    let _ = handler.call(&object.clone().into(), &[event.into()], context);
+    Ok(())
+}
+
+fn worker_id(this: &JsValue, context: &mut Context) -> JsResult<u64> {
+    let id = this
+        .as_object()
+        .map(|o| o.get(js_string!("__workerId"), context))
+        .transpose()?
+        .unwrap_or_default();
+    Ok(id.to_number(context)? as u64)
+}
+
+/// Install a native method on `object`.
+fn method(
+    object: &JsObject,
+    name: &str,
+    function: fn(&JsValue, &[JsValue], &mut Context) -> JsResult<JsValue>,
+    context: &mut Context,
+) -> JsResult<()> {
+    object.set(
+        JsString::from(name),
+        NativeFunction::from_fn_ptr(function).to_js_function(context.realm()),
+        false,
+        context,
+    )?;
+    Ok(())
+}
@@ -0,0 +1,356 @@
+//! `XMLHttpRequest` binding, for the legacy code that still uses it.
+//!
+//! XHR rides the same network path as [`super::fetch`] — the configured
+//! [`NetworkStack`](crate::network::NetworkStack) with its cache,
+//! cookies and devtools capture — it only differs in surface: readyState
+//! transitions with `onreadystatechange`, `responseText`/`responseXML`,
+//! and a per-request `timeout`. Completions cross back into the Boa
+//! context through a oneshot drained by [`pump`].
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use boa_engine::{
+    js_string, Context, JsArgs, JsObject, JsResult, JsString, JsValue, NativeFunction,
+};
+use tokio::sync::oneshot;
+
+use crate::network::request::{Headers, Request};
+use crate::network::response::Response;
+use crate::network::NetworkError;
+
+use super::fetch;
+
+/// `readyState` values, per the spec constants.
+const UNSENT: u8 = 0;
+const OPENED: u8 = 1;
+const DONE: u8 = 4;
+
+/// What `open` and `setRequestHeader` accumulated before `send`.
+#[derive(Default)]
+struct XhrConfig {
+    method: String,
+    url: String,
+    headers: Headers,
+}
+
+struct XhrHandle {
+    object: JsObject,
+    result: oneshot::Receiver<Result<Response, NetworkError>>,
+}
+
+thread_local! {
+    static CONFIGS: RefCell<HashMap<u64, XhrConfig>> = RefCell::new(HashMap::new());
+    static INFLIGHT: RefCell<HashMap<u64, XhrHandle>> = RefCell::new(HashMap::new());
+    static NEXT_ID: RefCell<u64> = const { RefCell::new(1) };
+}
+
+/// Install the `XMLHttpRequest` constructor on the global object.
+pub fn register(context: &mut Context) {
+    context
+        .register_global_callable(
+            js_string!("XMLHttpRequest"),
+            0,
+            NativeFunction::from_fn_ptr(construct),
+        )
+        .expect("registering XMLHttpRequest");
+}
+
+/// Drop every request, configured or in flight (navigation replaced the
+/// page).
+pub fn clear() {
+    CONFIGS.with(|configs| configs.borrow_mut().clear());
+    INFLIGHT.with(|inflight| inflight.borrow_mut().clear());
+}
+
+fn construct(_this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let object = JsObject::with_null_proto();
+    let id = NEXT_ID.with(|next| {
+        let mut next = next.borrow_mut();
+        let id = *next;
+        *next += 1;
+        id
+    });
+    object.set(js_string!("__xhrId"), id, false, context)?;
+    object.set(js_string!("readyState"), UNSENT, false, context)?;
+    object.set(js_string!("status"), 0, false, context)?;
+    object.set(js_string!("statusText"), js_string!(""), false, context)?;
+    object.set(js_string!("responseText"), js_string!(""), false, context)?;
+    object.set(js_string!("responseXML"), JsValue::null(), false, context)?;
+    object.set(js_string!("timeout"), 0, false, context)?;
+    method(&object, "open", open, context)?;
+    method(&object, "setRequestHeader", set_request_header, context)?;
+    method(&object, "send", send, context)?;
+    method(&object, "abort", abort, context)?;
+    Ok(object.into())
+}
+
+fn open(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let id = xhr_id(this, context)?;
+    let method = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    let url = args.get_or_undefined(1).to_string(context)?.to_std_string_escaped();
+    CONFIGS.with(|configs| {
+        configs.borrow_mut().insert(
+            id,
+            XhrConfig {
+                method,
+                url,
+                headers: Headers::new(),
+            },
+        );
+    });
+    if let Some(object) = this.as_object() {
+        set_ready_state(object, OPENED, context)?;
+    }
+    Ok(JsValue::undefined())
+}
+
+fn set_request_header(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let id = xhr_id(this, context)?;
+    let name = args.get_or_undefined(0).to_string(context)?.to_std_string_escaped();
+    let value = args.get_or_undefined(1).to_string(context)?.to_std_string_escaped();
+    CONFIGS.with(|configs| {
+        if let Some(config) = configs.borrow_mut().get_mut(&id) {
+            // Repeated calls for one name combine, per spec.
+            config.headers.append(&name, &value);
+        }
+    });
+    Ok(JsValue::undefined())
+}
+
+fn send(this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let id = xhr_id(this, context)?;
+    let Some(object) = this.as_object().cloned() else {
+        return Ok(JsValue::undefined());
+    };
+    let Some(config) = CONFIGS.with(|configs| configs.borrow_mut().remove(&id)) else {
+        return Err(boa_engine::JsNativeError::error()
+            .with_message("XMLHttpRequest: send() before open()")
+            .into());
+    };
+    let Some((stack, base_url)) = fetch::environment() else {
+        return Err(boa_engine::JsNativeError::error()
+            .with_message("XMLHttpRequest: no document loaded")
+            .into());
+    };
+
+    let mut request = Request::get(crate::renderer::loader::resolve_url(&base_url, &config.url));
+    request.method = fetch::parse_method(&config.method);
+    request.headers = config.headers;
+    let body = args.get_or_undefined(0);
+    if !body.is_undefined() && !body.is_null() {
+        let text = body.to_string(context)?.to_std_string_escaped();
+        request.body = Some(text.into_bytes());
+    }
+    let timeout_ms = object.get(js_string!("timeout"), context)?.to_number(context)?;
+
+    let (result_tx, result_rx) = oneshot::channel();
+    tokio::spawn(async move {
+        let outcome = if timeout_ms > 0.0 {
+            match tokio::time::timeout(
+                Duration::from_millis(timeout_ms as u64),
+                stack.fetch(request),
+            )
+            .await
+            {
+                Ok(outcome) => outcome,
+                Err(_) => Err(NetworkError::Timeout),
+            }
+        } else {
+            stack.fetch(request).await
+        };
+        let _ = result_tx.send(outcome);
+    });
+    INFLIGHT.with(|inflight| {
+        inflight.borrow_mut().insert(
+            id,
+            XhrHandle {
+                object,
+                result: result_rx,
+            },
+        );
+    });
+    Ok(JsValue::undefined())
+}
+
+fn abort(this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let id = xhr_id(this, context)?;
+    let aborted =
+        INFLIGHT.with(|inflight| inflight.borrow_mut().remove(&id)).is_some();
+    CONFIGS.with(|configs| configs.borrow_mut().remove(&id));
+    if let Some(object) = this.as_object() {
+        object.set(js_string!("readyState"), UNSENT, false, context)?;
+        if aborted {
+            fire(object, "onabort", context)?;
+        }
+    }
+    Ok(JsValue::undefined())
+}
+
+/// Complete the XHRs whose network task has finished. Driven from
+/// [`JsRuntime::pump`](super::JsRuntime::pump).
+pub fn pump(context: &mut Context) {
+    let mut completed: Vec<(JsObject, Result<Response, NetworkError>)> = Vec::new();
+    INFLIGHT.with(|inflight| {
+        let mut inflight = inflight.borrow_mut();
+        inflight.retain(|_, handle| match handle.result.try_recv() {
+            Ok(result) => {
+                completed.push((handle.object.clone(), result));
+                false
+            }
+            Err(oneshot::error::TryRecvError::Empty) => true,
+            Err(oneshot::error::TryRecvError::Closed) => {
+                completed.push((
+                    handle.object.clone(),
+                    Err(NetworkError::ConnectionFailed("request task dropped".into())),
+                ));
+                false
+            }
+        });
+    });
+
+    for (object, result) in completed {
+        let _ = complete(&object, result, context);
+        context.run_jobs();
+    }
+}
+
+fn complete(
+    object: &JsObject,
+    result: Result<Response, NetworkError>,
+    context: &mut Context,
+) -> JsResult<()> {
+    match result {
+        Ok(response) => {
+            object.set(js_string!("status"), response.status, false, context)?;
+            object.set(
+                js_string!("statusText"),
+                JsString::from(status_text(response.status)),
+                false,
+                context,
+            )?;
+            let text = response.text();
+            object.set(
+                js_string!("responseText"),
+                JsString::from(text.as_str()),
+                false,
+                context,
+            )?;
+            object.set(
+                js_string!("responseXML"),
+                response_xml(&response, &text, context)?,
+                false,
+                context,
+            )?;
+            set_ready_state(object, DONE, context)?;
+            fire(object, "onload", context)?;
+        }
+        Err(error) => {
+            set_ready_state(object, DONE, context)?;
+            let handler = if matches!(error, NetworkError::Timeout) {
+                "ontimeout"
+            } else {
+                "onerror"
+            };
+            fire(object, handler, context)?;
+        }
+    }
+    Ok(())
+}
+
+/// `responseXML` for markup responses: the parsed document, exposing its
+/// root as `documentElement` with the snapshot fields the DOM wrappers
+/// use. Null for other content types, per spec.
+fn response_xml(response: &Response, text: &str, context: &mut Context) -> JsResult<JsValue> {
+    let markup = response.content_type().map_or(false, |t| {
+        t.ends_with("/xml") || t.ends_with("+xml") || t == "text/html"
+    });
+    if !markup {
+        return Ok(JsValue::null());
+    }
+    let document = crate::renderer::html::parse(text);
+    let root = document.root();
+    let element = JsObject::with_null_proto();
+    let (tag, text_content) = document
+        .descendants(root)
+        .into_iter()
+        .find_map(|node| document.element(node).map(|e| (e.tag_name.clone(), node)))
+        .map(|(tag, node)| (tag, document.text_content(node)))
+        .unwrap_or_default();
+    element.set(
+        js_string!("tagName"),
+        JsString::from(tag.to_ascii_uppercase()),
+        false,
+        context,
+    )?;
+    element.set(
+        js_string!("textContent"),
+        JsString::from(text_content),
+        false,
+        context,
+    )?;
+    let wrapper = JsObject::with_null_proto();
+    wrapper.set(js_string!("documentElement"), element, false, context)?;
+    Ok(wrapper.into())
+}
+
+/// Set `readyState` and fire `onreadystatechange`.
+fn set_ready_state(object: &JsObject, state: u8, context: &mut Context) -> JsResult<()> {
+    object.set(js_string!("readyState"), state, false, context)?;
+    fire(object, "onreadystatechange", context)
+}
+
+/// Call the `handler` property if the page assigned one; handler errors
+/// don't propagate.
+fn fire(object: &JsObject, handler: &str, context: &mut Context) -> JsResult<()> {
+    let callback = object.get(JsString::from(handler), context)?;
+    if let Some(callback) = callback.as_callable() {
+        let _ = callback.call(&object.clone().into(), &[], context);
+    }
+    Ok(())
+}
+
+fn status_text(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        204 => "No Content",
+        301 => "Moved Permanently",
+        302 => "Found",
+        304 => "Not Modified",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        500 => "Internal Server Error",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        _ => "",
+    }
+}
+
+fn xhr_id(this: &JsValue, context: &mut Context) -> JsResult<u64> {
+    let id = this
+        .as_object()
+        .map(|o| o.get(js_string!("__xhrId"), context))
+        .transpose()?
+        .unwrap_or_default();
+    Ok(id.to_number(context)? as u64)
+}
+
+/// Install a native method on `object`.
+fn method(
+    object: &JsObject,
+    name: &str,
+    function: fn(&JsValue, &[JsValue], &mut Context) -> JsResult<JsValue>,
+    context: &mut Context,
+) -> JsResult<()> {
+    object.set(
+        JsString::from(name),
+        NativeFunction::from_fn_ptr(function).to_js_function(context.realm()),
+        false,
+        context,
+    )?;
+    Ok(())
+}
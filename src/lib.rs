@@ -0,0 +1,19 @@
+//! Binix — an experimental web browser engine.
+//!
+//! The crate is organised around a small number of subsystems:
+//!
+//! * [`network`] — resource loading: HTTP/3-first client, caching, DNS.
+//! * [`js_engine`] — per-page JavaScript execution and web API bindings.
+
+pub mod devtools;
+pub mod engine;
+pub mod js_engine;
+pub mod memory;
+pub mod network;
+pub mod process;
+pub mod renderer;
+pub mod security;
+pub mod storage;
+pub mod ui;
+pub mod url;
+pub mod wasm;
@@ -0,0 +1,9 @@
+//! Learned browsing state.
+//!
+//! This module holds what the browser remembers across page loads in order
+//! to get faster: which subresources a page pulls in, which links users
+//! follow next. The data is advisory — losing it costs speed, never
+//! correctness.
+
+pub mod prefetch;
+pub mod script_cache;
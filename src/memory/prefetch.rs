@@ -0,0 +1,179 @@
+//! Predictive prefetching.
+//!
+//! The [`Prefetcher`] watches which resources each page loads and which
+//! pages are navigated to next, then pre-warms those on later visits. How
+//! aggressively it warms a prediction depends on confidence: a resource
+//! seen on every visit earns a full fetch into the cache, a sometimes-seen
+//! one gets a connection, a rare one only a DNS lookup. Full fetches are
+//! capped by a per-session data budget so speculation never dominates a
+//! metered connection.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::network::hints::ResourceHint;
+use crate::network::{NetworkStack, Request, ResourcePriority};
+
+/// How a predicted resource is warmed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrefetchStrategy {
+    /// Resolve the host name only.
+    DnsOnly,
+    /// Resolve and complete the TLS handshake.
+    Preconnect,
+    /// Fetch the resource into the HTTP cache.
+    FullFetch,
+}
+
+/// Confidence thresholds above which each strategy applies.
+const FULL_FETCH_CONFIDENCE: f64 = 0.8;
+const PRECONNECT_CONFIDENCE: f64 = 0.4;
+const DNS_CONFIDENCE: f64 = 0.15;
+
+/// A resource predicted to be needed by a page, with the fraction of past
+/// visits that actually used it.
+#[derive(Debug, Clone)]
+pub struct Prediction {
+    pub url: String,
+    pub confidence: f64,
+    pub strategy: PrefetchStrategy,
+}
+
+#[derive(Default)]
+struct PageModel {
+    visits: u64,
+    /// Subresources and followed links, with how many visits used each.
+    uses: HashMap<String, u64>,
+}
+
+/// Learns per-page resource usage and pre-warms predictions.
+pub struct Prefetcher {
+    pages: Mutex<HashMap<String, PageModel>>,
+    /// Bytes of full-fetch speculation allowed this session.
+    budget: u64,
+    spent: AtomicU64,
+}
+
+impl Prefetcher {
+    /// 10 MiB of speculative fetching per session by default.
+    const DEFAULT_BUDGET: u64 = 10 * 1024 * 1024;
+
+    pub fn new() -> Arc<Self> {
+        Self::with_budget(Self::DEFAULT_BUDGET)
+    }
+
+    pub fn with_budget(budget: u64) -> Arc<Self> {
+        Arc::new(Self {
+            pages: Mutex::new(HashMap::new()),
+            budget,
+            spent: AtomicU64::new(0),
+        })
+    }
+
+    /// Note the start of a page load, so later observations are counted
+    /// against this visit.
+    pub fn record_visit(&self, page_url: &str) {
+        let mut pages = self.pages.lock().unwrap();
+        pages.entry(page_url.to_owned()).or_default().visits += 1;
+    }
+
+    /// Note a subresource fetched while `page_url` was loading.
+    pub fn record_fetch(&self, page_url: &str, resource_url: &str) {
+        self.record_use(page_url, resource_url);
+    }
+
+    /// Note a navigation from `page_url` to `next_url`, so likely next
+    /// pages are warmed too.
+    pub fn record_navigation(&self, page_url: &str, next_url: &str) {
+        self.record_use(page_url, next_url);
+    }
+
+    fn record_use(&self, page_url: &str, url: &str) {
+        let mut pages = self.pages.lock().unwrap();
+        let model = pages.entry(page_url.to_owned()).or_default();
+        *model.uses.entry(url.to_owned()).or_insert(0) += 1;
+    }
+
+    /// What the model expects `page_url` to need, most confident first.
+    pub fn predictions_for(&self, page_url: &str) -> Vec<Prediction> {
+        let pages = self.pages.lock().unwrap();
+        let Some(model) = pages.get(page_url) else {
+            return Vec::new();
+        };
+        if model.visits == 0 {
+            return Vec::new();
+        }
+        let mut predictions: Vec<Prediction> = model
+            .uses
+            .iter()
+            .filter_map(|(url, &count)| {
+                let confidence = count as f64 / model.visits as f64;
+                let strategy = if confidence >= FULL_FETCH_CONFIDENCE {
+                    PrefetchStrategy::FullFetch
+                } else if confidence >= PRECONNECT_CONFIDENCE {
+                    PrefetchStrategy::Preconnect
+                } else if confidence >= DNS_CONFIDENCE {
+                    PrefetchStrategy::DnsOnly
+                } else {
+                    return None;
+                };
+                Some(Prediction {
+                    url: url.clone(),
+                    confidence,
+                    strategy,
+                })
+            })
+            .collect();
+        predictions.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+        predictions
+    }
+
+    /// Pre-warm everything the model predicts for `page_url`. Called when
+    /// a navigation to the page starts; all work is fire-and-forget and
+    /// runs at the lowest priority.
+    pub fn prewarm(self: &Arc<Self>, stack: &Arc<NetworkStack>, page_url: &str) {
+        for prediction in self.predictions_for(page_url) {
+            match prediction.strategy {
+                PrefetchStrategy::DnsOnly => {
+                    stack.apply_hint(ResourceHint::DnsPrefetch {
+                        url: prediction.url,
+                    });
+                }
+                PrefetchStrategy::Preconnect => {
+                    stack.apply_hint(ResourceHint::Preconnect {
+                        url: prediction.url,
+                    });
+                }
+                PrefetchStrategy::FullFetch => {
+                    if self.spent.load(Ordering::Relaxed) >= self.budget {
+                        // Budget exhausted: degrade to a handshake, which
+                        // costs no data to speak of.
+                        stack.apply_hint(ResourceHint::Preconnect {
+                            url: prediction.url,
+                        });
+                        continue;
+                    }
+                    let stack = Arc::clone(stack);
+                    let this = Arc::clone(self);
+                    tokio::spawn(async move {
+                        let request = Request::get(prediction.url);
+                        if let Ok(response) = stack
+                            .fetch_prioritized(request, ResourcePriority::VeryLow)
+                            .await
+                        {
+                            this.spent
+                                .fetch_add(response.body.len() as u64, Ordering::Relaxed);
+                        }
+                    });
+                }
+            }
+        }
+    }
+
+    /// Bytes of full-fetch budget remaining this session.
+    pub fn budget_remaining(&self) -> u64 {
+        self.budget
+            .saturating_sub(self.spent.load(Ordering::Relaxed))
+    }
+}
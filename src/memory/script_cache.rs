@@ -0,0 +1,107 @@
+//! Compiled-script cache bookkeeping.
+//!
+//! Framework bundles are byte-identical across pages of a site, but the
+//! runtime re-parsed them on every navigation. The actual compiled
+//! scripts are engine objects and live with their JS context (see
+//! [`crate::js_engine::JsRuntime::execute_cached`]); what this module
+//! owns is the policy — which (URL, source hash) keys are worth keeping,
+//! sized by source bytes, evicted least-recently-used past a fixed
+//! budget. Like everything in [`crate::memory`], losing an entry costs a
+//! re-parse, never correctness.
+
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Mutex, OnceLock};
+
+/// 32 MiB of cached source by default: a handful of sites' bundles.
+pub const DEFAULT_CAPACITY: u64 = 32 * 1024 * 1024;
+
+/// Cache key: the script's URL plus a hash of its source, so a deployed
+/// update misses instead of running stale code.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    pub url: String,
+    pub source_hash: u64,
+}
+
+/// The key for `source` as fetched from `url`.
+pub fn key(url: &str, source: &str) -> CacheKey {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    source.hash(&mut hasher);
+    CacheKey {
+        url: url.to_owned(),
+        source_hash: hasher.finish(),
+    }
+}
+
+#[derive(Default)]
+struct CacheState {
+    /// Entry sizes in source bytes.
+    entries: HashMap<CacheKey, u64>,
+    /// Keys from least to most recently used.
+    order: Vec<CacheKey>,
+    used: u64,
+}
+
+/// Decides which compiled scripts stay cached. Shared process-wide; the
+/// per-runtime script maps follow its decisions lazily.
+pub struct ScriptCachePolicy {
+    capacity: u64,
+    state: Mutex<CacheState>,
+}
+
+impl ScriptCachePolicy {
+    pub fn with_capacity(capacity: u64) -> Self {
+        Self {
+            capacity,
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    /// Record a use of `key` (inserting it at `size` bytes if new) and
+    /// return the keys evicted to stay under budget. A script larger
+    /// than the whole budget is never admitted.
+    pub fn touch(&self, key: &CacheKey, size: u64) -> Vec<CacheKey> {
+        if size > self.capacity {
+            return vec![key.clone()];
+        }
+        let mut state = self.state.lock().unwrap();
+        state.order.retain(|k| k != key);
+        state.order.push(key.clone());
+        if state.entries.insert(key.clone(), size).is_none() {
+            state.used += size;
+        }
+        let mut evicted = Vec::new();
+        while state.used > self.capacity {
+            // The freshly touched key is at the tail, so it survives.
+            let victim = state.order.remove(0);
+            if let Some(size) = state.entries.remove(&victim) {
+                state.used -= size;
+            }
+            evicted.push(victim);
+        }
+        evicted
+    }
+
+    /// Whether `key` is still admitted. Runtimes drop local entries the
+    /// policy no longer lists.
+    pub fn contains(&self, key: &CacheKey) -> bool {
+        self.state.lock().unwrap().entries.contains_key(key)
+    }
+
+    /// Cached source bytes currently admitted.
+    pub fn used(&self) -> u64 {
+        self.state.lock().unwrap().used
+    }
+
+    pub fn clear(&self) {
+        let mut state = self.state.lock().unwrap();
+        *state = CacheState::default();
+    }
+}
+
+/// The process-wide policy instance.
+pub fn policy() -> &'static ScriptCachePolicy {
+    static POLICY: OnceLock<ScriptCachePolicy> = OnceLock::new();
+    POLICY.get_or_init(|| ScriptCachePolicy::with_capacity(DEFAULT_CAPACITY))
+}
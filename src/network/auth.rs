@@ -0,0 +1,225 @@
+//! HTTP authentication (RFC 7617 Basic, RFC 7616 Digest).
+//!
+//! A `401`/`407` answer is parsed into a [`Challenge`]; credentials come
+//! from the per-origin store or, failing that, from the UI via the
+//! [`CredentialPrompter`] the embedder installs. The request is then
+//! retried once with an `Authorization` header.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, RwLock};
+
+use base64::Engine as _;
+use md5::{Digest as _, Md5};
+
+use super::request::{Method, Request};
+use super::response::Response;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthScheme {
+    Basic,
+    Digest,
+}
+
+/// A parsed `WWW-Authenticate` / `Proxy-Authenticate` challenge.
+#[derive(Debug, Clone)]
+pub struct Challenge {
+    pub scheme: AuthScheme,
+    pub realm: String,
+    pub nonce: Option<String>,
+    pub opaque: Option<String>,
+    pub qop: Option<String>,
+}
+
+impl Challenge {
+    /// Parse the strongest challenge we support out of the header value.
+    pub fn parse(header: &str) -> Option<Self> {
+        let (scheme_word, params) = header.split_once(' ').unwrap_or((header, ""));
+        let scheme = match scheme_word.to_ascii_lowercase().as_str() {
+            "basic" => AuthScheme::Basic,
+            "digest" => AuthScheme::Digest,
+            _ => return None,
+        };
+        let mut fields: HashMap<String, String> = HashMap::new();
+        for part in split_params(params) {
+            if let Some((key, value)) = part.split_once('=') {
+                fields.insert(
+                    key.trim().to_ascii_lowercase(),
+                    value.trim().trim_matches('"').to_owned(),
+                );
+            }
+        }
+        Some(Self {
+            scheme,
+            realm: fields.remove("realm").unwrap_or_default(),
+            nonce: fields.remove("nonce"),
+            opaque: fields.remove("opaque"),
+            qop: fields.remove("qop"),
+        })
+    }
+}
+
+/// Split comma-separated auth params, respecting quoted strings.
+fn split_params(input: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth_quote = false;
+    let mut start = 0;
+    for (idx, ch) in input.char_indices() {
+        match ch {
+            '"' => depth_quote = !depth_quote,
+            ',' if !depth_quote => {
+                parts.push(input[start..idx].trim());
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(input[start..].trim());
+    parts
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Credentials {
+    pub username: String,
+    pub password: String,
+}
+
+/// Implemented by the UI: blockingly ask the user for credentials.
+/// Returning `None` cancels the authentication attempt.
+pub trait CredentialPrompter: Send + Sync {
+    fn prompt(&self, origin: &str, realm: &str) -> Option<Credentials>;
+}
+
+/// Remembered credentials keyed by (origin, realm).
+#[derive(Default)]
+pub struct AuthManager {
+    store: RwLock<HashMap<(String, String), Credentials>>,
+    prompter: Mutex<Option<Box<dyn CredentialPrompter>>>,
+    /// Digest nonce counter, shared across requests as the RFC requires.
+    nonce_count: Mutex<u32>,
+}
+
+impl AuthManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_prompter(&self, prompter: Box<dyn CredentialPrompter>) {
+        *self.prompter.lock().unwrap() = Some(prompter);
+    }
+
+    /// Credentials for a challenge: remembered ones first, then the prompt.
+    pub fn credentials_for(&self, origin: &str, challenge: &Challenge) -> Option<Credentials> {
+        let key = (origin.to_owned(), challenge.realm.clone());
+        if let Some(saved) = self.store.read().unwrap().get(&key) {
+            return Some(saved.clone());
+        }
+        let prompter = self.prompter.lock().unwrap();
+        let credentials = prompter.as_ref()?.prompt(origin, &challenge.realm)?;
+        drop(prompter);
+        self.store
+            .write()
+            .unwrap()
+            .insert(key, credentials.clone());
+        Some(credentials)
+    }
+
+    /// Forget credentials that the server rejected.
+    pub fn forget(&self, origin: &str, realm: &str) {
+        self.store
+            .write()
+            .unwrap()
+            .remove(&(origin.to_owned(), realm.to_owned()));
+    }
+
+    /// Build the `Authorization` header value answering `challenge`.
+    pub fn authorization(
+        &self,
+        challenge: &Challenge,
+        credentials: &Credentials,
+        method: Method,
+        uri: &str,
+    ) -> String {
+        match challenge.scheme {
+            AuthScheme::Basic => {
+                let token = base64::engine::general_purpose::STANDARD.encode(format!(
+                    "{}:{}",
+                    credentials.username, credentials.password
+                ));
+                format!("Basic {token}")
+            }
+            AuthScheme::Digest => self.digest_authorization(challenge, credentials, method, uri),
+        }
+    }
+
+    fn digest_authorization(
+        &self,
+        challenge: &Challenge,
+        credentials: &Credentials,
+        method: Method,
+        uri: &str,
+    ) -> String {
+        let nonce = challenge.nonce.clone().unwrap_or_default();
+        let cnonce = hex::encode(rand::random::<[u8; 8]>());
+        let nc = {
+            let mut count = self.nonce_count.lock().unwrap();
+            *count += 1;
+            format!("{:08x}", *count)
+        };
+
+        let ha1 = md5_hex(&format!(
+            "{}:{}:{}",
+            credentials.username, challenge.realm, credentials.password
+        ));
+        let ha2 = md5_hex(&format!("{}:{}", method.as_str(), uri));
+        let response = if challenge.qop.as_deref() == Some("auth") {
+            md5_hex(&format!("{ha1}:{nonce}:{nc}:{cnonce}:auth:{ha2}"))
+        } else {
+            md5_hex(&format!("{ha1}:{nonce}:{ha2}"))
+        };
+
+        let mut value = format!(
+            "Digest username=\"{}\", realm=\"{}\", nonce=\"{}\", uri=\"{}\", response=\"{}\"",
+            credentials.username, challenge.realm, nonce, uri, response
+        );
+        if challenge.qop.as_deref() == Some("auth") {
+            value.push_str(&format!(", qop=auth, nc={nc}, cnonce=\"{cnonce}\""));
+        }
+        if let Some(opaque) = &challenge.opaque {
+            value.push_str(&format!(", opaque=\"{opaque}\""));
+        }
+        value
+    }
+}
+
+fn md5_hex(input: &str) -> String {
+    hex::encode(Md5::digest(input.as_bytes()))
+}
+
+/// The challenge header matching the response status, if present.
+pub fn challenge_from(response: &Response) -> Option<(Challenge, &'static str)> {
+    match response.status {
+        401 => response
+            .headers
+            .get("www-authenticate")
+            .and_then(Challenge::parse)
+            .map(|c| (c, "authorization")),
+        407 => response
+            .headers
+            .get("proxy-authenticate")
+            .and_then(Challenge::parse)
+            .map(|c| (c, "proxy-authorization")),
+        _ => None,
+    }
+}
+
+/// Path-and-query portion of a URL, as Digest's `uri` parameter wants.
+pub fn request_uri(request: &Request) -> String {
+    let after_scheme = request
+        .url
+        .split_once("://")
+        .map_or(request.url.as_str(), |(_, rest)| rest);
+    match after_scheme.find('/') {
+        Some(idx) => after_scheme[idx..].to_owned(),
+        None => "/".to_owned(),
+    }
+}
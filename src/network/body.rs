@@ -0,0 +1,90 @@
+//! Streaming response bodies.
+//!
+//! Transports deliver bodies as a sequence of [`Bytes`] chunks through a
+//! bounded channel, so large documents, media and downloads are consumed
+//! incrementally instead of being buffered whole. Buffered callers use
+//! [`BodyStream::collect`]; the streaming HTML parser consumes chunks as
+//! they arrive.
+
+use bytes::Bytes;
+use tokio::sync::mpsc;
+
+use super::request::Headers;
+use super::response::Response;
+use super::NetworkError;
+
+/// Status line and headers of a response whose body is still arriving.
+#[derive(Debug, Clone)]
+pub struct ResponseHead {
+    pub url: String,
+    pub status: u16,
+    pub headers: Headers,
+}
+
+/// Producer half of a body channel, held by the transport.
+pub struct BodySink {
+    tx: mpsc::Sender<Result<Bytes, NetworkError>>,
+}
+
+/// Consumer half of a body channel.
+pub struct BodyStream {
+    rx: mpsc::Receiver<Result<Bytes, NetworkError>>,
+}
+
+/// Create a connected sink/stream pair. The small buffer provides
+/// backpressure against producers outrunning the parser.
+pub fn channel() -> (BodySink, BodyStream) {
+    let (tx, rx) = mpsc::channel(8);
+    (BodySink { tx }, BodyStream { rx })
+}
+
+/// A stream that yields `bytes` as its single chunk (cache hits,
+/// synthesized responses).
+pub fn single(bytes: Vec<u8>) -> BodyStream {
+    let (sink, stream) = channel();
+    // The buffer is large enough that this cannot block.
+    let _ = sink.tx.try_send(Ok(Bytes::from(bytes)));
+    stream
+}
+
+impl BodySink {
+    /// Deliver one chunk, waiting if the consumer is behind. Errors mean
+    /// the consumer is gone and the transport should stop reading.
+    pub async fn send(&self, chunk: Bytes) -> Result<(), ()> {
+        self.tx.send(Ok(chunk)).await.map_err(|_| ())
+    }
+
+    /// Terminate the stream with an error.
+    pub async fn fail(&self, error: NetworkError) {
+        let _ = self.tx.send(Err(error)).await;
+    }
+}
+
+impl BodyStream {
+    /// Next chunk, or `None` once the body is complete.
+    pub async fn next_chunk(&mut self) -> Option<Result<Bytes, NetworkError>> {
+        self.rx.recv().await
+    }
+
+    /// Drain the remaining chunks into one buffer.
+    pub async fn collect(mut self) -> Result<Vec<u8>, NetworkError> {
+        let mut body = Vec::new();
+        while let Some(chunk) = self.next_chunk().await {
+            body.extend_from_slice(&chunk?);
+        }
+        Ok(body)
+    }
+}
+
+/// Reassemble a buffered [`Response`] from streaming parts.
+pub async fn collect_response(
+    head: ResponseHead,
+    body: BodyStream,
+) -> Result<Response, NetworkError> {
+    Ok(Response {
+        url: head.url,
+        status: head.status,
+        headers: head.headers,
+        body: body.collect().await?,
+    })
+}
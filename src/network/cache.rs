@@ -0,0 +1,423 @@
+//! HTTP cache: a two-tier (memory + disk) store keyed by request URL.
+//!
+//! Freshness follows RFC 9111: `Cache-Control: max-age` wins over
+//! `Expires`, `no-store` is never cached, `no-cache` is stored but always
+//! revalidated, and responses with only a `Last-Modified` date get the
+//! standard 10% heuristic lifetime. Stale entries carrying validators are
+//! revalidated with `If-None-Match` / `If-Modified-Since` rather than
+//! refetched.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::sync::Mutex;
+
+use super::request::{Headers, Request};
+use super::response::Response;
+use super::NetworkError;
+
+/// Tuning knobs for [`HttpCache`].
+#[derive(Debug, Clone)]
+pub struct HttpCacheConfig {
+    /// Directory holding the disk tier.
+    pub disk_root: PathBuf,
+    /// Byte budget for the in-memory tier.
+    pub memory_budget: usize,
+    /// Byte budget for the disk tier.
+    pub disk_budget: u64,
+    /// Bodies larger than this skip the memory tier entirely.
+    pub max_memory_entry: usize,
+}
+
+impl Default for HttpCacheConfig {
+    fn default() -> Self {
+        Self {
+            disk_root: PathBuf::from("http-cache"),
+            memory_budget: 32 * 1024 * 1024,
+            disk_budget: 256 * 1024 * 1024,
+            max_memory_entry: 512 * 1024,
+        }
+    }
+}
+
+/// Result of a cache lookup for a request.
+pub enum CacheLookup {
+    /// Entry is fresh; serve it without touching the network.
+    Fresh(Response),
+    /// Entry is stale but has validators; issue a conditional request.
+    NeedsRevalidation {
+        /// `If-None-Match` / `If-Modified-Since` headers to add.
+        conditional_headers: Headers,
+        /// The stored entry, to be served again on `304 Not Modified`.
+        stored: Box<StoredEntry>,
+    },
+    Miss,
+}
+
+/// A cached response plus the metadata needed for freshness decisions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredEntry {
+    /// Storage key: the URL, prefixed by the top-level site when the
+    /// request was partitioned (see [`super::partition`]).
+    pub key: String,
+    pub url: String,
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    /// When the response was received, seconds since the Unix epoch.
+    pub received_at: u64,
+}
+
+/// Metadata about one entry, for about:cache and devtools.
+#[derive(Debug, Clone)]
+pub struct CacheEntryInfo {
+    pub url: String,
+    /// Top-level site the entry is partitioned under, if any.
+    pub top_level_site: Option<String>,
+    pub status: u16,
+    pub body_len: usize,
+    pub received_at: SystemTime,
+    pub fresh: bool,
+    pub in_memory: bool,
+}
+
+struct MemoryTier {
+    entries: HashMap<String, StoredEntry>,
+    /// Keys in least-recently-used order, oldest first.
+    lru: Vec<String>,
+    used: usize,
+}
+
+/// Two-tier HTTP response cache. All methods are async-safe; internal state
+/// is guarded by a single mutex since operations are short.
+pub struct HttpCache {
+    config: HttpCacheConfig,
+    memory: Mutex<MemoryTier>,
+}
+
+impl HttpCache {
+    pub fn new(config: HttpCacheConfig) -> Result<Self, NetworkError> {
+        std::fs::create_dir_all(&config.disk_root)?;
+        Ok(Self {
+            config,
+            memory: Mutex::new(MemoryTier {
+                entries: HashMap::new(),
+                lru: Vec::new(),
+                used: 0,
+            }),
+        })
+    }
+
+    /// Look up `request`, classifying the entry as fresh, revalidatable, or
+    /// a miss.
+    pub async fn lookup(&self, request: &Request) -> CacheLookup {
+        let key = super::partition::state_key(request.partition.as_deref(), &request.url);
+        let Some(entry) = self.load(&key).await else {
+            return CacheLookup::Miss;
+        };
+
+        let headers = entry_headers(&entry);
+        if is_fresh(&headers, entry.received_at) && !must_revalidate_always(&headers) {
+            return CacheLookup::Fresh(entry_to_response(&entry));
+        }
+
+        let mut conditional = Headers::new();
+        if let Some(etag) = headers.get("etag") {
+            conditional.set("if-none-match", etag);
+        }
+        if let Some(modified) = headers.get("last-modified") {
+            conditional.set("if-modified-since", modified);
+        }
+        if conditional.is_empty() {
+            // Stale with nothing to validate against: treat as a miss so the
+            // caller refetches unconditionally.
+            return CacheLookup::Miss;
+        }
+        CacheLookup::NeedsRevalidation {
+            conditional_headers: conditional,
+            stored: Box::new(entry),
+        }
+    }
+
+    /// Store a response if its headers permit caching.
+    pub async fn store(&self, request: &Request, response: &Response) -> Result<(), NetworkError> {
+        if !is_cacheable(request, response) {
+            return Ok(());
+        }
+        let entry = StoredEntry {
+            key: super::partition::state_key(request.partition.as_deref(), &request.url),
+            url: request.url.clone(),
+            status: response.status,
+            headers: response.headers.iter().map(|(n, v)| (n.to_owned(), v.to_owned())).collect(),
+            body: response.body.clone(),
+            received_at: now_secs(),
+        };
+        self.insert(entry).await
+    }
+
+    /// Merge a `304 Not Modified` answer into the stored entry, refreshing
+    /// its headers and timestamp, and return the response to serve.
+    pub async fn refresh(
+        &self,
+        request: &Request,
+        not_modified: &Response,
+        stored: Box<StoredEntry>,
+    ) -> Result<Response, NetworkError> {
+        let mut entry = *stored;
+        // Per RFC 9111 §3.2, updated header fields from the 304 replace the
+        // stored ones.
+        for (name, value) in not_modified.headers.iter() {
+            entry.headers.retain(|(n, _)| !n.eq_ignore_ascii_case(name));
+            entry.headers.push((name.to_owned(), value.to_owned()));
+        }
+        entry.received_at = now_secs();
+        entry.key = super::partition::state_key(request.partition.as_deref(), &request.url);
+        entry.url = request.url.clone();
+        let response = entry_to_response(&entry);
+        self.insert(entry).await?;
+        Ok(response)
+    }
+
+    /// Metadata for every entry currently cached, memory tier first.
+    pub async fn entries(&self) -> Vec<CacheEntryInfo> {
+        let mut infos = Vec::new();
+        let memory = self.memory.lock().await;
+        for entry in memory.entries.values() {
+            infos.push(describe(entry, true));
+        }
+        drop(memory);
+
+        if let Ok(dir) = std::fs::read_dir(&self.config.disk_root) {
+            for file in dir.flatten() {
+                if let Ok(bytes) = std::fs::read(file.path()) {
+                    if let Ok(entry) = bincode::deserialize::<StoredEntry>(&bytes) {
+                        let info = describe(&entry, false);
+                        if !infos
+                            .iter()
+                            .any(|i| i.url == info.url && i.top_level_site == info.top_level_site)
+                        {
+                            infos.push(info);
+                        }
+                    }
+                }
+            }
+        }
+        infos
+    }
+
+    /// Remove every copy of `url` from both tiers, across all partitions.
+    pub async fn evict(&self, url: &str) {
+        let mut memory = self.memory.lock().await;
+        let keys: Vec<String> = memory
+            .entries
+            .iter()
+            .filter(|(_, e)| e.url == url)
+            .map(|(k, _)| k.clone())
+            .collect();
+        for key in &keys {
+            if let Some(entry) = memory.entries.remove(key) {
+                memory.used = memory.used.saturating_sub(entry.body.len());
+            }
+            memory.lru.retain(|k| k != key);
+        }
+        drop(memory);
+        if let Ok(dir) = std::fs::read_dir(&self.config.disk_root) {
+            for file in dir.flatten() {
+                if let Ok(bytes) = std::fs::read(file.path()) {
+                    if let Ok(entry) = bincode::deserialize::<StoredEntry>(&bytes) {
+                        if entry.url == url {
+                            let _ = std::fs::remove_file(file.path());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Drop everything from both tiers.
+    pub async fn clear(&self) {
+        let mut memory = self.memory.lock().await;
+        memory.entries.clear();
+        memory.lru.clear();
+        memory.used = 0;
+        drop(memory);
+        let _ = std::fs::remove_dir_all(&self.config.disk_root);
+        let _ = std::fs::create_dir_all(&self.config.disk_root);
+    }
+
+    async fn load(&self, key: &str) -> Option<StoredEntry> {
+        {
+            let mut memory = self.memory.lock().await;
+            if let Some(entry) = memory.entries.get(key).cloned() {
+                memory.lru.retain(|k| k != key);
+                memory.lru.push(key.to_owned());
+                return Some(entry);
+            }
+        }
+        let bytes = std::fs::read(self.disk_path(key)).ok()?;
+        bincode::deserialize(&bytes).ok()
+    }
+
+    async fn insert(&self, entry: StoredEntry) -> Result<(), NetworkError> {
+        if entry.body.len() <= self.config.max_memory_entry {
+            let mut memory = self.memory.lock().await;
+            if let Some(old) = memory.entries.insert(entry.key.clone(), entry.clone()) {
+                memory.used = memory.used.saturating_sub(old.body.len());
+                memory.lru.retain(|k| k != &entry.key);
+            }
+            memory.used += entry.body.len();
+            memory.lru.push(entry.key.clone());
+            while memory.used > self.config.memory_budget {
+                let Some(oldest) = memory.lru.first().cloned() else { break };
+                memory.lru.remove(0);
+                if let Some(evicted) = memory.entries.remove(&oldest) {
+                    memory.used = memory.used.saturating_sub(evicted.body.len());
+                }
+            }
+        }
+
+        let bytes = bincode::serialize(&entry)
+            .map_err(|e| NetworkError::Protocol(format!("cache serialization: {e}")))?;
+        std::fs::write(self.disk_path(&entry.key), bytes)?;
+        self.enforce_disk_budget();
+        Ok(())
+    }
+
+    fn enforce_disk_budget(&self) {
+        let Ok(dir) = std::fs::read_dir(&self.config.disk_root) else { return };
+        let mut files: Vec<(PathBuf, SystemTime, u64)> = dir
+            .flatten()
+            .filter_map(|f| {
+                let meta = f.metadata().ok()?;
+                Some((f.path(), meta.modified().ok()?, meta.len()))
+            })
+            .collect();
+        let total: u64 = files.iter().map(|(_, _, len)| len).sum();
+        if total <= self.config.disk_budget {
+            return;
+        }
+        // Evict oldest-written entries until we are back under budget.
+        files.sort_by_key(|(_, modified, _)| *modified);
+        let mut over = total - self.config.disk_budget;
+        for (path, _, len) in files {
+            if std::fs::remove_file(path).is_ok() {
+                over = over.saturating_sub(len);
+                if over == 0 {
+                    break;
+                }
+            }
+        }
+    }
+
+    fn disk_path(&self, url: &str) -> PathBuf {
+        let hash = Sha256::digest(url.as_bytes());
+        self.config.disk_root.join(hex::encode(&hash[..16]))
+    }
+}
+
+fn describe(entry: &StoredEntry, in_memory: bool) -> CacheEntryInfo {
+    let headers = entry_headers(entry);
+    let (site, _) = super::partition::split_key(&entry.key);
+    CacheEntryInfo {
+        url: entry.url.clone(),
+        top_level_site: site.map(str::to_owned),
+        status: entry.status,
+        body_len: entry.body.len(),
+        received_at: UNIX_EPOCH + Duration::from_secs(entry.received_at),
+        fresh: is_fresh(&headers, entry.received_at),
+        in_memory,
+    }
+}
+
+fn entry_headers(entry: &StoredEntry) -> Headers {
+    let mut headers = Headers::new();
+    for (name, value) in &entry.headers {
+        headers.append(name, value);
+    }
+    headers
+}
+
+fn entry_to_response(entry: &StoredEntry) -> Response {
+    Response {
+        url: entry.url.clone(),
+        status: entry.status,
+        headers: entry_headers(entry),
+        body: entry.body.clone(),
+    }
+}
+
+fn is_cacheable(request: &Request, response: &Response) -> bool {
+    if request.method != super::request::Method::Get {
+        return false;
+    }
+    if !matches!(response.status, 200 | 203 | 204 | 301 | 308 | 404 | 410) {
+        return false;
+    }
+    let directives = cache_control(&response.headers);
+    if directives.iter().any(|d| d == "no-store") {
+        return false;
+    }
+    true
+}
+
+/// Whether the entry may be served without revalidation.
+fn is_fresh(headers: &Headers, received_at: u64) -> bool {
+    let age = now_secs().saturating_sub(received_at);
+    freshness_lifetime(headers).map_or(false, |lifetime| age < lifetime)
+}
+
+/// `no-cache` (or `max-age=0, must-revalidate`) forces revalidation even
+/// inside the freshness window.
+fn must_revalidate_always(headers: &Headers) -> bool {
+    cache_control(headers).iter().any(|d| d == "no-cache")
+}
+
+/// Freshness lifetime in seconds: max-age, then Expires − Date, then the
+/// Last-Modified heuristic.
+fn freshness_lifetime(headers: &Headers) -> Option<u64> {
+    for directive in cache_control(headers) {
+        if let Some(value) = directive.strip_prefix("max-age=") {
+            return value.trim().parse().ok();
+        }
+    }
+    if let (Some(expires), Some(date)) = (
+        headers.get("expires").and_then(parse_http_date),
+        headers.get("date").and_then(parse_http_date),
+    ) {
+        return Some(expires.saturating_sub(date));
+    }
+    if let (Some(modified), Some(date)) = (
+        headers.get("last-modified").and_then(parse_http_date),
+        headers.get("date").and_then(parse_http_date),
+    ) {
+        // Heuristic freshness: 10% of the time since last modification.
+        return Some(date.saturating_sub(modified) / 10);
+    }
+    None
+}
+
+fn cache_control(headers: &Headers) -> Vec<String> {
+    headers
+        .get_all("cache-control")
+        .flat_map(|v| v.split(','))
+        .map(|d| d.trim().to_ascii_lowercase())
+        .collect()
+}
+
+/// Parse an IMF-fixdate (`Sun, 06 Nov 1994 08:49:37 GMT`) to Unix seconds.
+fn parse_http_date(value: &str) -> Option<u64> {
+    httpdate::parse_http_date(value)
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
@@ -0,0 +1,483 @@
+//! Protocol-negotiating HTTP client.
+//!
+//! [`NetworkClient`] fronts the transport layer: it tries HTTP/3 first and
+//! falls back to HTTP/2 and then HTTP/1.1 over TLS, using ALPN to settle the
+//! TCP-side protocol. The outcome is remembered per origin so subsequent
+//! requests to an origin that cannot speak QUIC skip the doomed attempt.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::Mutex;
+
+use super::body::{BodyStream, ResponseHead};
+use super::http3::{origin_of, split_host_port, Http3Client};
+use super::proxy::{self, ProxySettings};
+use super::request::{Headers, Request};
+use super::response::Response;
+use super::NetworkError;
+
+/// How long a negative HTTP/3 verdict for an origin is trusted before QUIC
+/// is attempted again.
+const PROTOCOL_MEMORY_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// The wire protocol a response was carried over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HttpVersion {
+    H1,
+    H2,
+    H3,
+}
+
+impl HttpVersion {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HttpVersion::H1 => "http/1.1",
+            HttpVersion::H2 => "h2",
+            HttpVersion::H3 => "h3",
+        }
+    }
+}
+
+struct ProtocolVerdict {
+    version: HttpVersion,
+    decided_at: Instant,
+}
+
+/// Transport-selecting client used by [`super::NetworkStack`].
+pub struct NetworkClient {
+    h3: Http3Client,
+    tls_config: Arc<rustls::ClientConfig>,
+    /// Per-origin memory of which protocol last worked.
+    protocols: Mutex<HashMap<String, ProtocolVerdict>>,
+    proxy: ProxySettings,
+    stats: Arc<super::stats::ConnectionPoolStats>,
+    throttler: Arc<super::throttle::Throttler>,
+    /// Active HAR capture session, if devtools started one.
+    har: std::sync::Mutex<Option<Arc<super::har::HarRecorder>>>,
+}
+
+impl NetworkClient {
+    pub fn new() -> Self {
+        Self::with_pins(Arc::new(crate::security::PinStore::new()))
+    }
+
+    /// Build a client whose TLS verifier enforces the given certificate
+    /// pins (see [`crate::security::pinning`]).
+    pub fn with_pins(pins: Arc<crate::security::PinStore>) -> Self {
+        let mut roots = rustls::RootCertStore::empty();
+        roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+        let webpki = rustls::client::WebPkiServerVerifier::builder(Arc::new(roots))
+            .build()
+            .expect("building WebPKI verifier");
+        let verifier = Arc::new(crate::security::pinning::PinningVerifier::new(webpki, pins));
+        let mut tls_config = rustls::ClientConfig::builder()
+            .dangerous()
+            .with_custom_certificate_verifier(verifier)
+            .with_no_client_auth();
+        tls_config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+        let stats = Arc::new(super::stats::ConnectionPoolStats::new());
+        Self {
+            h3: Http3Client::with_stats(Arc::clone(&stats)),
+            tls_config: Arc::new(tls_config),
+            protocols: Mutex::new(HashMap::new()),
+            proxy: ProxySettings::default(),
+            stats,
+            throttler: Arc::new(super::throttle::Throttler::new()),
+            har: std::sync::Mutex::new(None),
+        }
+    }
+
+    /// Start or stop HAR capture. Requests in flight when capture starts
+    /// are not recorded.
+    pub fn set_har_recorder(&self, recorder: Option<Arc<super::har::HarRecorder>>) {
+        *self.har.lock().unwrap() = recorder;
+    }
+
+    /// Throttle control, for devtools' network conditions panel.
+    pub fn throttler(&self) -> &Arc<super::throttle::Throttler> {
+        &self.throttler
+    }
+
+    /// Replace the proxy configuration; applies to subsequent requests.
+    pub fn set_proxy(&mut self, proxy: ProxySettings) {
+        self.proxy = proxy;
+    }
+
+    /// Connection pool metrics, for devtools and `about:network`.
+    pub fn pool_stats(&self) -> &Arc<super::stats::ConnectionPoolStats> {
+        &self.stats
+    }
+
+    /// Warm the transport for `origin` ahead of any request: QUIC when
+    /// possible, honouring per-origin protocol memory. Failures are
+    /// ignored; this is purely speculative.
+    pub async fn preconnect(&self, origin: &str) {
+        if !self.proxy.is_direct() {
+            return;
+        }
+        match self.remembered(origin).await {
+            Some(HttpVersion::H3) | None => {
+                let _ = self.h3.preconnect(origin).await;
+            }
+            Some(_) => {
+                // A TCP-protocol origin: warming would need connection
+                // pooling on the TCP path, which we don't have yet; the DNS
+                // resolution alone is still worthwhile.
+                if let Ok((host, port)) = split_host_port(origin) {
+                    let _ = super::dns::DnsCache::shared().resolve(&host, port).await;
+                }
+            }
+        }
+    }
+
+    /// Send `request`, negotiating the best available protocol for its
+    /// origin, and buffer the whole body.
+    pub async fn send(&self, request: &Request) -> Result<Response, NetworkError> {
+        let (head, body) = self.send_streaming(request).await?;
+        super::body::collect_response(head, body).await
+    }
+
+    /// Send `request`, returning the head as soon as it arrives and the
+    /// body as a chunk stream.
+    pub async fn send_streaming(
+        &self,
+        request: &Request,
+    ) -> Result<(ResponseHead, BodyStream), NetworkError> {
+        let recorder = self.har.lock().unwrap().clone();
+        let timer = recorder.as_ref().map(|_| super::har::EntryTimer::start());
+        let ((head, body), version) = self.dispatch_streaming(request).await?;
+        let body = match (recorder, timer) {
+            (Some(recorder), Some(timer)) => {
+                super::har::observe(recorder, request, timer, &head, version, body)
+            }
+            _ => body,
+        };
+        Ok((head, body))
+    }
+
+    /// Pick a transport for `request`'s origin and dispatch it, reporting
+    /// which protocol carried the response.
+    async fn dispatch_streaming(
+        &self,
+        request: &Request,
+    ) -> Result<((ResponseHead, BodyStream), HttpVersion), NetworkError> {
+        let origin = origin_of(&request.url)?;
+
+        if self.throttler.simulated_offline() {
+            return Err(NetworkError::ConnectionFailed(
+                "network throttled to offline".into(),
+            ));
+        }
+        self.throttler.delay_request().await;
+
+        // Proxied origins cannot use the UDP-based HTTP/3 path.
+        let (host, _) = split_host_port(&origin)?;
+        let proxied = !self.proxy.is_direct() && !self.proxy.should_bypass(&host);
+        if proxied {
+            let ((head, body), version) = self.send_over_tcp(&origin, request).await?;
+            self.remember(&origin, version).await;
+            return Ok(((head, self.throttler.pace(body)), version));
+        }
+
+        match self.remembered(&origin).await {
+            Some(HttpVersion::H3) | None => {
+                match self.h3.send_streaming(request).await {
+                    Ok((head, body)) => {
+                        self.remember(&origin, HttpVersion::H3).await;
+                        return Ok(((head, self.throttler.pace(body)), HttpVersion::H3));
+                    }
+                    // QUIC may be blocked or unsupported; fall through to the
+                    // TCP path. Genuine HTTP-level failures are not retried.
+                    Err(NetworkError::ConnectionFailed(_))
+                    | Err(NetworkError::Timeout)
+                    | Err(NetworkError::Protocol(_)) => {}
+                    Err(other) => return Err(other),
+                }
+            }
+            Some(_) => {}
+        }
+
+        let ((head, body), version) = self.send_over_tcp(&origin, request).await?;
+        self.remember(&origin, version).await;
+        Ok(((head, self.throttler.pace(body)), version))
+    }
+
+    async fn remembered(&self, origin: &str) -> Option<HttpVersion> {
+        let mut protocols = self.protocols.lock().await;
+        match protocols.get(origin) {
+            Some(verdict) if verdict.decided_at.elapsed() < PROTOCOL_MEMORY_TTL => {
+                Some(verdict.version)
+            }
+            Some(_) => {
+                protocols.remove(origin);
+                None
+            }
+            None => None,
+        }
+    }
+
+    async fn remember(&self, origin: &str, version: HttpVersion) {
+        self.protocols.lock().await.insert(
+            origin.to_owned(),
+            ProtocolVerdict {
+                version,
+                decided_at: Instant::now(),
+            },
+        );
+    }
+
+    /// Dial the origin over TLS/TCP and dispatch on whatever protocol ALPN
+    /// selected.
+    async fn send_over_tcp(
+        &self,
+        origin: &str,
+        request: &Request,
+    ) -> Result<((ResponseHead, BodyStream), HttpVersion), NetworkError> {
+        let (host, port) = split_host_port(origin)?;
+        let via = self.proxy.proxy_for(&request.url, &host)?;
+        let tcp = proxy::connect(via.as_ref(), &host, port).await?;
+
+        let server_name = rustls::pki_types::ServerName::try_from(host.clone())
+            .map_err(|_| NetworkError::InvalidUrl(origin.to_owned()))?;
+        let connector = tokio_rustls::TlsConnector::from(Arc::clone(&self.tls_config));
+        let tls = connector.connect(server_name, tcp).await.map_err(|e| {
+            // Surface certificate problems as structured failures so the UI
+            // can show an interstitial with details.
+            if let Some(rustls_error) = e
+                .get_ref()
+                .and_then(|inner| inner.downcast_ref::<rustls::Error>())
+            {
+                if let Some(failure) = super::tls::classify(&host, rustls_error) {
+                    return NetworkError::Certificate(failure);
+                }
+            }
+            NetworkError::Tls(e.to_string())
+        })?;
+
+        let negotiated_h2 = tls
+            .get_ref()
+            .1
+            .alpn_protocol()
+            .map_or(false, |p| p == b"h2");
+        if negotiated_h2 {
+            self.stats.record_open(origin, HttpVersion::H2);
+            self.stats.record_request(origin);
+            let parts = self.send_h2(tls, request).await?;
+            Ok((parts, HttpVersion::H2))
+        } else {
+            self.stats.record_open(origin, HttpVersion::H1);
+            self.stats.record_request(origin);
+            let response = self.send_h1(tls, &host, request).await?;
+            let Response {
+                url,
+                status,
+                headers,
+                body,
+            } = response;
+            let head = ResponseHead {
+                url,
+                status,
+                headers,
+            };
+            Ok(((head, super::body::single(body)), HttpVersion::H1))
+        }
+    }
+
+    async fn send_h2(
+        &self,
+        tls: tokio_rustls::client::TlsStream<TcpStream>,
+        request: &Request,
+    ) -> Result<(ResponseHead, BodyStream), NetworkError> {
+        let (mut send_request, connection) = h2::client::handshake(tls)
+            .await
+            .map_err(|e| NetworkError::Protocol(e.to_string()))?;
+        tokio::spawn(async move {
+            let _ = connection.await;
+        });
+
+        let mut builder = http::Request::builder()
+            .method(request.method.as_str())
+            .uri(&request.url);
+        for (name, value) in request.headers.iter() {
+            builder = builder.header(name, value);
+        }
+        let h2_request = builder
+            .body(())
+            .map_err(|e| NetworkError::Protocol(e.to_string()))?;
+
+        let has_body = request.body.is_some();
+        let (response_future, mut stream) = send_request
+            .send_request(h2_request, !has_body)
+            .map_err(|e| NetworkError::Protocol(e.to_string()))?;
+        if let Some(body) = &request.body {
+            stream
+                .send_data(Bytes::copy_from_slice(body), true)
+                .map_err(|e| NetworkError::Protocol(e.to_string()))?;
+        }
+
+        let h2_response = response_future
+            .await
+            .map_err(|e| NetworkError::Protocol(e.to_string()))?;
+        let status = h2_response.status().as_u16();
+        let mut headers = Headers::new();
+        for (name, value) in h2_response.headers() {
+            headers.append(name.as_str(), value.to_str().unwrap_or_default());
+        }
+
+        let mut body_stream = h2_response.into_body();
+        let mut decoder = super::decompress::StreamingDecoder::for_response(&headers);
+        super::decompress::mark_decoded(&mut headers);
+        let head = ResponseHead {
+            url: request.url.clone(),
+            status,
+            headers,
+        };
+
+        let (sink, body) = super::body::channel();
+        tokio::spawn(async move {
+            while let Some(chunk) = body_stream.data().await {
+                let chunk = match chunk {
+                    Ok(chunk) => chunk,
+                    Err(err) => {
+                        sink.fail(NetworkError::Protocol(err.to_string())).await;
+                        return;
+                    }
+                };
+                let _ = body_stream.flow_control().release_capacity(chunk.len());
+                if let Err(err) = decoder.push(&chunk) {
+                    sink.fail(err).await;
+                    return;
+                }
+                let decoded = decoder.drain();
+                if !decoded.is_empty() && sink.send(decoded.into()).await.is_err() {
+                    return;
+                }
+            }
+            match decoder.finish() {
+                Ok(tail) if !tail.is_empty() => {
+                    let _ = sink.send(tail.into()).await;
+                }
+                Ok(_) => {}
+                Err(err) => sink.fail(err).await,
+            }
+        });
+
+        Ok((head, body))
+    }
+
+    async fn send_h1(
+        &self,
+        mut tls: tokio_rustls::client::TlsStream<TcpStream>,
+        host: &str,
+        request: &Request,
+    ) -> Result<Response, NetworkError> {
+        let path = path_and_query(&request.url);
+        let mut wire = format!("{} {} HTTP/1.1\r\n", request.method.as_str(), path);
+        wire.push_str(&format!("host: {host}\r\n"));
+        wire.push_str("connection: close\r\n");
+        for (name, value) in request.headers.iter() {
+            wire.push_str(&format!("{name}: {value}\r\n"));
+        }
+        if let Some(body) = &request.body {
+            wire.push_str(&format!("content-length: {}\r\n", body.len()));
+        }
+        wire.push_str("\r\n");
+
+        tls.write_all(wire.as_bytes()).await?;
+        if let Some(body) = &request.body {
+            tls.write_all(body).await?;
+        }
+
+        let mut raw = Vec::new();
+        tls.read_to_end(&mut raw).await?;
+        parse_h1_response(&raw, &request.url)
+    }
+}
+
+impl Default for NetworkClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn path_and_query(url: &str) -> &str {
+    let after_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+    match after_scheme.find('/') {
+        Some(idx) => &after_scheme[idx..],
+        None => "/",
+    }
+}
+
+fn parse_h1_response(raw: &[u8], url: &str) -> Result<Response, NetworkError> {
+    let header_end = raw
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or_else(|| NetworkError::Protocol("truncated HTTP/1.1 response".into()))?;
+    let head = String::from_utf8_lossy(&raw[..header_end]);
+    let mut lines = head.lines();
+    let status_line = lines
+        .next()
+        .ok_or_else(|| NetworkError::Protocol("missing status line".into()))?;
+    let status: u16 = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| NetworkError::Protocol(format!("bad status line: {status_line}")))?;
+
+    let mut headers = Headers::new();
+    for line in lines {
+        if let Some((name, value)) = line.split_once(':') {
+            headers.append(name.trim(), value.trim());
+        }
+    }
+
+    let mut body = raw[header_end + 4..].to_vec();
+    if headers
+        .get("transfer-encoding")
+        .map_or(false, |v| v.eq_ignore_ascii_case("chunked"))
+    {
+        body = decode_chunked(&body)?;
+    }
+
+    let mut decoder = super::decompress::StreamingDecoder::for_response(&headers);
+    if !decoder.is_identity() {
+        decoder.push(&body)?;
+        body = decoder.finish()?;
+        super::decompress::mark_decoded(&mut headers);
+    }
+
+    Ok(Response {
+        url: url.to_owned(),
+        status,
+        headers,
+        body,
+    })
+}
+
+/// Decode a chunked transfer coding body (trailers are discarded).
+fn decode_chunked(mut input: &[u8]) -> Result<Vec<u8>, NetworkError> {
+    let mut out = Vec::new();
+    loop {
+        let line_end = input
+            .windows(2)
+            .position(|w| w == b"\r\n")
+            .ok_or_else(|| NetworkError::Protocol("truncated chunk size".into()))?;
+        let size_str = String::from_utf8_lossy(&input[..line_end]);
+        let size = usize::from_str_radix(size_str.trim().split(';').next().unwrap_or("0"), 16)
+            .map_err(|_| NetworkError::Protocol(format!("bad chunk size: {size_str}")))?;
+        input = &input[line_end + 2..];
+        if size == 0 {
+            return Ok(out);
+        }
+        if input.len() < size + 2 {
+            return Err(NetworkError::Protocol("truncated chunk body".into()));
+        }
+        out.extend_from_slice(&input[..size]);
+        input = &input[size + 2..];
+    }
+}
@@ -0,0 +1,80 @@
+//! Connectivity tracking.
+//!
+//! There is no portable "am I online" signal, so the monitor infers state
+//! from request outcomes: a run of transport-level failures with no
+//! intervening success flips the state to offline, and any success flips it
+//! back. The UI uses this to choose between "this site is down" and "you
+//! are offline" error pages.
+
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::NetworkError;
+
+/// Failures in a row (across all origins) before we consider the machine
+/// offline.
+const OFFLINE_THRESHOLD: u32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectivityState {
+    Online,
+    Offline,
+}
+
+/// Process-wide connectivity estimator.
+pub struct ConnectivityMonitor {
+    consecutive_failures: AtomicU32,
+    last_success_at: AtomicU64,
+}
+
+impl ConnectivityMonitor {
+    pub fn shared() -> &'static ConnectivityMonitor {
+        static SHARED: OnceLock<ConnectivityMonitor> = OnceLock::new();
+        SHARED.get_or_init(|| ConnectivityMonitor {
+            consecutive_failures: AtomicU32::new(0),
+            last_success_at: AtomicU64::new(now_secs()),
+        })
+    }
+
+    /// Record the outcome of a network dispatch. Only transport-level
+    /// errors count towards the offline heuristic; HTTP errors and blocked
+    /// requests prove connectivity works.
+    pub fn record(&self, result: &Result<(), &NetworkError>) {
+        match result {
+            Ok(()) => {
+                self.consecutive_failures.store(0, Ordering::Relaxed);
+                self.last_success_at.store(now_secs(), Ordering::Relaxed);
+            }
+            Err(
+                NetworkError::DnsFailure(_)
+                | NetworkError::ConnectionFailed(_)
+                | NetworkError::Timeout,
+            ) => {
+                self.consecutive_failures.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(_) => {
+                self.consecutive_failures.store(0, Ordering::Relaxed);
+            }
+        }
+    }
+
+    pub fn state(&self) -> ConnectivityState {
+        if self.consecutive_failures.load(Ordering::Relaxed) >= OFFLINE_THRESHOLD {
+            ConnectivityState::Offline
+        } else {
+            ConnectivityState::Online
+        }
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.state() == ConnectivityState::Offline
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
@@ -0,0 +1,101 @@
+//! Streaming response body decompression.
+//!
+//! Transports feed raw network chunks into a [`StreamingDecoder`] and drain
+//! decoded bytes as they become available, so compressed documents are never
+//! held in memory twice. Supported codings: gzip (including multi-member),
+//! brotli, and zstd.
+
+use std::io::Write;
+
+use super::request::Headers;
+use super::NetworkError;
+
+/// `Accept-Encoding` value advertised on every request.
+pub const ACCEPT_ENCODING: &str = "gzip, br, zstd";
+
+/// Incremental decoder for one response body.
+pub enum StreamingDecoder {
+    Identity(Vec<u8>),
+    Gzip(flate2::write::MultiGzDecoder<Vec<u8>>),
+    Brotli(Box<brotli::DecompressorWriter<Vec<u8>>>),
+    Zstd(zstd::stream::write::Decoder<'static, Vec<u8>>),
+}
+
+impl StreamingDecoder {
+    /// Pick a decoder from the response's `Content-Encoding`. Unknown or
+    /// stacked codings fall back to identity so the body is at least passed
+    /// through rather than dropped.
+    pub fn for_response(headers: &Headers) -> Self {
+        match headers
+            .get("content-encoding")
+            .map(|v| v.trim().to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("gzip") | Some("x-gzip") => {
+                Self::Gzip(flate2::write::MultiGzDecoder::new(Vec::new()))
+            }
+            Some("br") => Self::Brotli(Box::new(brotli::DecompressorWriter::new(Vec::new(), 4096))),
+            Some("zstd") => match zstd::stream::write::Decoder::new(Vec::new()) {
+                Ok(decoder) => Self::Zstd(decoder),
+                Err(_) => Self::Identity(Vec::new()),
+            },
+            _ => Self::Identity(Vec::new()),
+        }
+    }
+
+    /// Whether this decoder actually transforms the body.
+    pub fn is_identity(&self) -> bool {
+        matches!(self, Self::Identity(_))
+    }
+
+    /// Feed one network chunk.
+    pub fn push(&mut self, chunk: &[u8]) -> Result<(), NetworkError> {
+        let result = match self {
+            Self::Identity(out) => {
+                out.extend_from_slice(chunk);
+                Ok(())
+            }
+            Self::Gzip(decoder) => decoder.write_all(chunk),
+            Self::Brotli(decoder) => decoder.write_all(chunk),
+            Self::Zstd(decoder) => decoder.write_all(chunk),
+        };
+        result.map_err(|e| NetworkError::Protocol(format!("decompression failed: {e}")))
+    }
+
+    /// Take whatever has been decoded since the last drain, leaving the
+    /// decoder ready for more input.
+    pub fn drain(&mut self) -> Vec<u8> {
+        match self {
+            Self::Identity(out) => std::mem::take(out),
+            Self::Gzip(decoder) => std::mem::take(decoder.get_mut()),
+            Self::Brotli(decoder) => std::mem::take(decoder.get_mut()),
+            Self::Zstd(decoder) => std::mem::take(decoder.get_mut()),
+        }
+    }
+
+    /// Flush trailing state and return the final decoded bytes.
+    pub fn finish(self) -> Result<Vec<u8>, NetworkError> {
+        match self {
+            Self::Identity(out) => Ok(out),
+            Self::Gzip(decoder) => decoder
+                .finish()
+                .map_err(|e| NetworkError::Protocol(format!("gzip tail: {e}"))),
+            Self::Brotli(decoder) => decoder
+                .into_inner()
+                .map_err(|_| NetworkError::Protocol("brotli stream truncated".into())),
+            Self::Zstd(mut decoder) => {
+                decoder
+                    .flush()
+                    .map_err(|e| NetworkError::Protocol(format!("zstd tail: {e}")))?;
+                Ok(std::mem::take(decoder.get_mut()))
+            }
+        }
+    }
+}
+
+/// Strip the coding headers once a body has been decoded, so downstream
+/// consumers (cache, renderer) see the representation they actually hold.
+pub fn mark_decoded(headers: &mut Headers) {
+    headers.remove("content-encoding");
+    headers.remove("content-length");
+}
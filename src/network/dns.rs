@@ -0,0 +1,149 @@
+//! Cached DNS resolution.
+//!
+//! Connection setup was paying a full resolver round trip on every request.
+//! [`DnsCache`] memoises lookups per hostname with a TTL, caches NXDOMAIN
+//! negatively (so hosts that don't exist fail fast), and serves recently
+//! expired entries stale while a background task revalidates them.
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+use tokio::sync::Mutex;
+
+use super::NetworkError;
+
+/// Lifetime of a positive entry. The OS resolver hides real record TTLs
+/// from us, so a conservative fixed value is used.
+const POSITIVE_TTL: Duration = Duration::from_secs(60);
+/// Lifetime of a negative (NXDOMAIN) entry.
+const NEGATIVE_TTL: Duration = Duration::from_secs(15);
+/// How long past expiry an entry may still be served stale while a refresh
+/// runs in the background.
+const STALE_WINDOW: Duration = Duration::from_secs(30);
+
+#[derive(Clone)]
+enum CachedResult {
+    Resolved(Vec<SocketAddr>),
+    NxDomain,
+}
+
+#[derive(Clone)]
+struct CacheSlot {
+    result: CachedResult,
+    expires_at: Instant,
+    refreshing: bool,
+}
+
+/// Async-safe hostname → address cache. Keys ignore the port; resolved
+/// addresses are re-targeted to the requested port on the way out.
+pub struct DnsCache {
+    entries: Mutex<HashMap<String, CacheSlot>>,
+}
+
+impl DnsCache {
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Process-wide shared cache used by all transports.
+    pub fn shared() -> &'static Arc<DnsCache> {
+        static SHARED: OnceLock<Arc<DnsCache>> = OnceLock::new();
+        SHARED.get_or_init(|| Arc::new(DnsCache::new()))
+    }
+
+    /// Resolve `host`, returning addresses carrying `port`.
+    pub async fn resolve(
+        self: &Arc<Self>,
+        host: &str,
+        port: u16,
+    ) -> Result<Vec<SocketAddr>, NetworkError> {
+        // Literal addresses bypass the cache entirely.
+        if let Ok(addr) = host.parse::<std::net::IpAddr>() {
+            return Ok(vec![SocketAddr::new(addr, port)]);
+        }
+
+        let now = Instant::now();
+        {
+            let mut entries = self.entries.lock().await;
+            if let Some(slot) = entries.get_mut(host) {
+                if now < slot.expires_at {
+                    return slot_result(&slot.result, host, port);
+                }
+                if now < slot.expires_at + STALE_WINDOW {
+                    // Serve stale and refresh off the request path.
+                    if !slot.refreshing {
+                        slot.refreshing = true;
+                        let cache = Arc::clone(self);
+                        let host = host.to_owned();
+                        tokio::spawn(async move {
+                            let _ = cache.refresh(&host).await;
+                        });
+                    }
+                    return slot_result(&slot.result, host, port);
+                }
+                entries.remove(host);
+            }
+        }
+
+        self.refresh(host).await?;
+        let entries = self.entries.lock().await;
+        match entries.get(host) {
+            Some(slot) => slot_result(&slot.result, host, port),
+            None => Err(NetworkError::DnsFailure(host.to_owned())),
+        }
+    }
+
+    /// Drop every cached entry (used by devtools and tests of network
+    /// partitioning).
+    pub async fn clear(&self) {
+        self.entries.lock().await.clear();
+    }
+
+    async fn refresh(&self, host: &str) -> Result<(), NetworkError> {
+        // Resolve with a throwaway port; entries are stored portless.
+        let (result, ttl) = match tokio::net::lookup_host((host, 0)).await {
+            Ok(addrs) => {
+                let addrs: Vec<SocketAddr> = addrs.collect();
+                if addrs.is_empty() {
+                    (CachedResult::NxDomain, NEGATIVE_TTL)
+                } else {
+                    (CachedResult::Resolved(addrs), POSITIVE_TTL)
+                }
+            }
+            Err(_) => (CachedResult::NxDomain, NEGATIVE_TTL),
+        };
+        self.entries.lock().await.insert(
+            host.to_owned(),
+            CacheSlot {
+                result,
+                expires_at: Instant::now() + ttl,
+                refreshing: false,
+            },
+        );
+        Ok(())
+    }
+}
+
+impl Default for DnsCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn slot_result(
+    result: &CachedResult,
+    host: &str,
+    port: u16,
+) -> Result<Vec<SocketAddr>, NetworkError> {
+    match result {
+        CachedResult::Resolved(addrs) => Ok(addrs
+            .iter()
+            .map(|addr| SocketAddr::new(addr.ip(), port))
+            .collect()),
+        CachedResult::NxDomain => Err(NetworkError::DnsFailure(host.to_owned())),
+    }
+}
@@ -0,0 +1,266 @@
+//! Download manager.
+//!
+//! Downloads are fetched in fixed-size segments using `Range` requests and
+//! appended to the target file as they arrive, which gives pause/resume for
+//! free: pausing simply stops after the current segment, and resuming
+//! continues from the bytes already on disk. Progress and state changes are
+//! emitted as [`DownloadEvent`]s for the UI's downloads panel.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::{mpsc, Notify};
+
+use super::request::Request;
+use super::{NetworkError, NetworkStack};
+
+/// Bytes requested per `Range` segment.
+const SEGMENT_SIZE: u64 = 4 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DownloadId(u64);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DownloadState {
+    Running,
+    Paused,
+    Completed,
+    Cancelled,
+    Failed(String),
+}
+
+/// Events streamed to the UI.
+#[derive(Debug, Clone)]
+pub enum DownloadEvent {
+    Started {
+        id: DownloadId,
+        url: String,
+        target: PathBuf,
+        total_bytes: Option<u64>,
+    },
+    Progress {
+        id: DownloadId,
+        received_bytes: u64,
+        total_bytes: Option<u64>,
+    },
+    StateChanged {
+        id: DownloadId,
+        state: DownloadState,
+    },
+}
+
+struct DownloadControl {
+    paused: AtomicBool,
+    cancelled: AtomicBool,
+    resume: Notify,
+}
+
+struct DownloadRecord {
+    id: DownloadId,
+    control: Arc<DownloadControl>,
+}
+
+/// Owns all active downloads and their control handles.
+pub struct DownloadManager {
+    stack: Arc<NetworkStack>,
+    events: mpsc::UnboundedSender<DownloadEvent>,
+    downloads: Mutex<Vec<DownloadRecord>>,
+    next_id: Mutex<u64>,
+}
+
+impl DownloadManager {
+    /// Create a manager; the returned receiver carries events for the UI.
+    pub fn new(stack: Arc<NetworkStack>) -> (Arc<Self>, mpsc::UnboundedReceiver<DownloadEvent>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        (
+            Arc::new(Self {
+                stack,
+                events: tx,
+                downloads: Mutex::new(Vec::new()),
+                next_id: Mutex::new(1),
+            }),
+            rx,
+        )
+    }
+
+    /// Begin downloading `url` to `target`, resuming from any bytes already
+    /// present there.
+    pub fn start(self: &Arc<Self>, url: String, target: PathBuf) -> DownloadId {
+        let id = {
+            let mut next = self.next_id.lock().unwrap();
+            let id = DownloadId(*next);
+            *next += 1;
+            id
+        };
+        let control = Arc::new(DownloadControl {
+            paused: AtomicBool::new(false),
+            cancelled: AtomicBool::new(false),
+            resume: Notify::new(),
+        });
+        self.downloads.lock().unwrap().push(DownloadRecord {
+            id,
+            control: Arc::clone(&control),
+        });
+
+        let manager = Arc::clone(self);
+        tokio::spawn(async move {
+            let result = manager.run(id, &url, &target, &control).await;
+            let state = match result {
+                Ok(true) => DownloadState::Completed,
+                Ok(false) => DownloadState::Cancelled,
+                Err(err) => DownloadState::Failed(err.to_string()),
+            };
+            manager.emit(DownloadEvent::StateChanged { id, state });
+        });
+        id
+    }
+
+    /// Pause after the in-flight segment completes.
+    pub fn pause(&self, id: DownloadId) {
+        if let Some(control) = self.control_for(id) {
+            control.paused.store(true, Ordering::SeqCst);
+            self.emit(DownloadEvent::StateChanged {
+                id,
+                state: DownloadState::Paused,
+            });
+        }
+    }
+
+    pub fn resume(&self, id: DownloadId) {
+        if let Some(control) = self.control_for(id) {
+            control.paused.store(false, Ordering::SeqCst);
+            control.resume.notify_one();
+            self.emit(DownloadEvent::StateChanged {
+                id,
+                state: DownloadState::Running,
+            });
+        }
+    }
+
+    pub fn cancel(&self, id: DownloadId) {
+        if let Some(control) = self.control_for(id) {
+            control.cancelled.store(true, Ordering::SeqCst);
+            control.resume.notify_one();
+        }
+    }
+
+    async fn run(
+        &self,
+        id: DownloadId,
+        url: &str,
+        target: &PathBuf,
+        control: &DownloadControl,
+    ) -> Result<bool, NetworkError> {
+        let mut received: u64 = std::fs::metadata(target).map(|m| m.len()).unwrap_or(0);
+
+        // Probe the resource for its size and Range support.
+        let head = self
+            .stack
+            .fetch(Request::with_method(url, super::Method::Head))
+            .await?;
+        let total = head
+            .headers
+            .get("content-length")
+            .and_then(|v| v.parse::<u64>().ok());
+        let supports_ranges = head
+            .headers
+            .get("accept-ranges")
+            .map_or(false, |v| v.eq_ignore_ascii_case("bytes"));
+        if received > 0 && !supports_ranges {
+            // Cannot resume; start over.
+            received = 0;
+            let _ = std::fs::remove_file(target);
+        }
+
+        self.emit(DownloadEvent::Started {
+            id,
+            url: url.to_owned(),
+            target: target.clone(),
+            total_bytes: total,
+        });
+
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(target)?;
+
+        loop {
+            if control.cancelled.load(Ordering::SeqCst) {
+                return Ok(false);
+            }
+            while control.paused.load(Ordering::SeqCst) {
+                control.resume.notified().await;
+                if control.cancelled.load(Ordering::SeqCst) {
+                    return Ok(false);
+                }
+            }
+            if let Some(total) = total {
+                if received >= total {
+                    break;
+                }
+            }
+
+            let mut request = Request::get(url);
+            if supports_ranges {
+                let end = received + SEGMENT_SIZE - 1;
+                request
+                    .headers
+                    .set("range", &format!("bytes={received}-{end}"));
+            }
+            let response = self.stack.fetch(request).await?;
+            match response.status {
+                206 => {}
+                200 if received == 0 => {}
+                200 => {
+                    return Err(NetworkError::Protocol(
+                        "server ignored Range during resume".into(),
+                    ))
+                }
+                416 => break, // requested past the end: already complete
+                status => {
+                    return Err(NetworkError::Protocol(format!(
+                        "download failed with status {status}"
+                    )))
+                }
+            }
+
+            use std::io::Write;
+            file.write_all(&response.body)?;
+            received += response.body.len() as u64;
+            self.emit(DownloadEvent::Progress {
+                id,
+                received_bytes: received,
+                total_bytes: total,
+            });
+
+            // A whole-resource 200 answer means there is nothing left.
+            if response.status == 200 || response.body.is_empty() {
+                break;
+            }
+        }
+
+        // Verify we got what the server promised.
+        if let Some(total) = total {
+            if received != total {
+                return Err(NetworkError::Protocol(format!(
+                    "download truncated: got {received} of {total} bytes"
+                )));
+            }
+        }
+        Ok(true)
+    }
+
+    fn control_for(&self, id: DownloadId) -> Option<Arc<DownloadControl>> {
+        self.downloads
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|d| d.id == id)
+            .map(|d| Arc::clone(&d.control))
+    }
+
+    fn emit(&self, event: DownloadEvent) {
+        let _ = self.events.send(event);
+    }
+}
@@ -0,0 +1,178 @@
+//! Form submission bodies.
+//!
+//! Builders for the two encodings HTML forms produce:
+//! `application/x-www-form-urlencoded` and `multipart/form-data` (with file
+//! parts). Form submissions with `method=POST` go through these and then
+//! through the normal [`super::NetworkStack`] path, not a side channel.
+
+use super::request::{Method, Request};
+
+/// Percent-encode a string per the `application/x-www-form-urlencoded`
+/// serializer (space becomes `+`).
+fn form_urlencode(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for byte in input.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'*' | b'-' | b'.' | b'_' => {
+                out.push(byte as char)
+            }
+            b' ' => out.push('+'),
+            other => out.push_str(&format!("%{other:02X}")),
+        }
+    }
+    out
+}
+
+/// Builder for `application/x-www-form-urlencoded` bodies.
+#[derive(Debug, Clone, Default)]
+pub struct UrlEncodedForm {
+    fields: Vec<(String, String)>,
+}
+
+impl UrlEncodedForm {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn field(mut self, name: &str, value: &str) -> Self {
+        self.fields.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Serialize to the wire format.
+    pub fn encode(&self) -> String {
+        self.fields
+            .iter()
+            .map(|(name, value)| format!("{}={}", form_urlencode(name), form_urlencode(value)))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+
+    /// Build a POST request to `url` carrying this form.
+    pub fn into_request(self, url: &str) -> Request {
+        let mut request = Request::with_method(url, Method::Post);
+        request
+            .headers
+            .set("content-type", "application/x-www-form-urlencoded");
+        request.body = Some(self.encode().into_bytes());
+        request
+    }
+}
+
+/// One part of a multipart form.
+#[derive(Debug, Clone)]
+enum Part {
+    Text {
+        name: String,
+        value: String,
+    },
+    File {
+        name: String,
+        filename: String,
+        content_type: String,
+        data: Vec<u8>,
+    },
+}
+
+/// Builder for `multipart/form-data` bodies.
+#[derive(Debug, Clone)]
+pub struct MultipartForm {
+    boundary: String,
+    parts: Vec<Part>,
+}
+
+impl MultipartForm {
+    pub fn new() -> Self {
+        // Random boundary with a browser-style prefix; the chance of a
+        // collision with body content is negligible.
+        let boundary = format!("----BinixFormBoundary{}", hex::encode(rand::random::<[u8; 12]>()));
+        Self {
+            boundary,
+            parts: Vec::new(),
+        }
+    }
+
+    pub fn text(mut self, name: &str, value: &str) -> Self {
+        self.parts.push(Part::Text {
+            name: name.to_owned(),
+            value: value.to_owned(),
+        });
+        self
+    }
+
+    pub fn file(mut self, name: &str, filename: &str, content_type: &str, data: Vec<u8>) -> Self {
+        self.parts.push(Part::File {
+            name: name.to_owned(),
+            filename: filename.to_owned(),
+            content_type: content_type.to_owned(),
+            data,
+        });
+        self
+    }
+
+    /// Serialize the body.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut body = Vec::new();
+        for part in &self.parts {
+            body.extend_from_slice(format!("--{}\r\n", self.boundary).as_bytes());
+            match part {
+                Part::Text { name, value } => {
+                    body.extend_from_slice(
+                        format!(
+                            "content-disposition: form-data; name=\"{}\"\r\n\r\n",
+                            escape_quotes(name)
+                        )
+                        .as_bytes(),
+                    );
+                    body.extend_from_slice(value.as_bytes());
+                }
+                Part::File {
+                    name,
+                    filename,
+                    content_type,
+                    data,
+                } => {
+                    body.extend_from_slice(
+                        format!(
+                            "content-disposition: form-data; name=\"{}\"; filename=\"{}\"\r\n\
+                             content-type: {}\r\n\r\n",
+                            escape_quotes(name),
+                            escape_quotes(filename),
+                            content_type
+                        )
+                        .as_bytes(),
+                    );
+                    body.extend_from_slice(data);
+                }
+            }
+            body.extend_from_slice(b"\r\n");
+        }
+        body.extend_from_slice(format!("--{}--\r\n", self.boundary).as_bytes());
+        body
+    }
+
+    /// Build a POST request to `url` carrying this form.
+    pub fn into_request(self, url: &str) -> Request {
+        let mut request = Request::with_method(url, Method::Post);
+        request.headers.set(
+            "content-type",
+            &format!("multipart/form-data; boundary={}", self.boundary),
+        );
+        request.body = Some(self.encode());
+        request
+    }
+}
+
+impl Default for MultipartForm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Per WHATWG, quotes and newlines in names/filenames are percent-escaped.
+fn escape_quotes(input: &str) -> String {
+    input
+        .replace('"', "%22")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
@@ -0,0 +1,261 @@
+//! HAR 1.2 session capture.
+//!
+//! When a [`HarRecorder`] is installed on the [`super::NetworkClient`],
+//! every request it carries is logged as a HAR entry — metadata, timings,
+//! and optionally response bodies. Devtools' network inspector exports the
+//! recorder's contents; CLI tooling can do the same for automated runs.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use super::body::{self, BodyStream, ResponseHead};
+use super::client::HttpVersion;
+use super::request::Request;
+
+/// The full archive, ready for serialization.
+#[derive(Debug, Serialize)]
+pub struct Har {
+    pub log: HarLog,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HarLog {
+    pub version: &'static str,
+    pub creator: HarCreator,
+    pub entries: Vec<HarEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HarCreator {
+    pub name: &'static str,
+    pub version: &'static str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarEntry {
+    pub started_date_time: String,
+    /// Total elapsed time in milliseconds.
+    pub time: f64,
+    pub request: HarRequest,
+    pub response: HarResponse,
+    pub timings: HarTimings,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarRequest {
+    pub method: String,
+    pub url: String,
+    pub http_version: String,
+    pub headers: Vec<HarHeader>,
+    pub headers_size: i64,
+    pub body_size: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarResponse {
+    pub status: u16,
+    pub status_text: String,
+    pub http_version: String,
+    pub headers: Vec<HarHeader>,
+    pub content: HarContent,
+    pub redirect_url: String,
+    pub headers_size: i64,
+    pub body_size: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HarHeader {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarContent {
+    pub size: i64,
+    pub mime_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+/// Phase timings in milliseconds; `-1.0` marks a phase we don't measure.
+#[derive(Debug, Clone, Serialize)]
+pub struct HarTimings {
+    pub send: f64,
+    pub wait: f64,
+    pub receive: f64,
+}
+
+/// Collects entries for the current capture session.
+pub struct HarRecorder {
+    capture_bodies: bool,
+    entries: Mutex<Vec<HarEntry>>,
+}
+
+impl HarRecorder {
+    pub fn new(capture_bodies: bool) -> Arc<Self> {
+        Arc::new(Self {
+            capture_bodies,
+            entries: Mutex::new(Vec::new()),
+        })
+    }
+
+    pub fn record(&self, entry: HarEntry) {
+        self.entries.lock().unwrap().push(entry);
+    }
+
+    /// Snapshot the session as a HAR document.
+    pub fn export(&self) -> Har {
+        Har {
+            log: HarLog {
+                version: "1.2",
+                creator: HarCreator {
+                    name: "Binix",
+                    version: env!("CARGO_PKG_VERSION"),
+                },
+                entries: self.entries.lock().unwrap().clone(),
+            },
+        }
+    }
+
+    /// The exported archive as JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.export()).unwrap_or_else(|_| "{}".into())
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+/// Wall + monotonic clock pair taken when a request is dispatched.
+pub(crate) struct EntryTimer {
+    wall: SystemTime,
+    mono: Instant,
+}
+
+impl EntryTimer {
+    pub(crate) fn start() -> Self {
+        Self {
+            wall: SystemTime::now(),
+            mono: Instant::now(),
+        }
+    }
+}
+
+/// Tee `body` so the entry is recorded once the response completes; the
+/// caller gets an equivalent stream back.
+pub(crate) fn observe(
+    recorder: Arc<HarRecorder>,
+    request: &Request,
+    timer: EntryTimer,
+    head: &ResponseHead,
+    version: HttpVersion,
+    mut upstream: BodyStream,
+) -> BodyStream {
+    let wait = timer.mono.elapsed();
+    let har_request = HarRequest {
+        method: request.method.as_str().to_owned(),
+        url: request.url.clone(),
+        http_version: version.as_str().to_owned(),
+        headers: headers_of(request.headers.iter()),
+        headers_size: -1,
+        body_size: request.body.as_ref().map_or(-1, |b| b.len() as i64),
+    };
+    let mime_type = head
+        .headers
+        .get("content-type")
+        .unwrap_or_default()
+        .to_owned();
+    let redirect_url = head.headers.get("location").unwrap_or_default().to_owned();
+    let response_headers = headers_of(head.headers.iter());
+    let status = head.status;
+    let capture_bodies = recorder.capture_bodies;
+
+    let (sink, downstream) = body::channel();
+    tokio::spawn(async move {
+        let mut size: i64 = 0;
+        let mut captured = capture_bodies.then(Vec::new);
+        while let Some(chunk) = upstream.next_chunk().await {
+            match chunk {
+                Ok(chunk) => {
+                    size += chunk.len() as i64;
+                    if let Some(buffer) = &mut captured {
+                        buffer.extend_from_slice(&chunk);
+                    }
+                    if sink.send(chunk).await.is_err() {
+                        // Consumer gone; keep draining so the entry still
+                        // reflects the full transfer.
+                    }
+                }
+                Err(err) => {
+                    sink.fail(err).await;
+                    break;
+                }
+            }
+        }
+        let receive = timer.mono.elapsed().saturating_sub(wait);
+        recorder.record(HarEntry {
+            started_date_time: iso8601(timer.wall),
+            time: timer.mono.elapsed().as_secs_f64() * 1000.0,
+            request: har_request,
+            response: HarResponse {
+                status,
+                status_text: String::new(),
+                http_version: version.as_str().to_owned(),
+                headers: response_headers,
+                content: HarContent {
+                    size,
+                    mime_type,
+                    text: captured.map(|b| String::from_utf8_lossy(&b).into_owned()),
+                },
+                redirect_url,
+                headers_size: -1,
+                body_size: size,
+            },
+            timings: HarTimings {
+                send: -1.0,
+                wait: millis(wait),
+                receive: millis(receive),
+            },
+        });
+    });
+    downstream
+}
+
+fn headers_of<'a>(iter: impl Iterator<Item = (&'a str, &'a str)>) -> Vec<HarHeader> {
+    iter.map(|(name, value)| HarHeader {
+        name: name.to_owned(),
+        value: value.to_owned(),
+    })
+    .collect()
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// `SystemTime` as `YYYY-MM-DDThh:mm:ss.mmmZ`, as the HAR spec requires.
+fn iso8601(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let millis = since_epoch.subsec_millis();
+    let total = since_epoch.as_secs();
+    let (second, minute, hour) = (total % 60, (total / 60) % 60, (total / 3600) % 24);
+    // Civil-from-days (Gregorian), days since 1970-01-01.
+    let days = (total / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let doe = days.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let year = yoe + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = if month <= 2 { year + 1 } else { year };
+    format!("{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}.{millis:03}Z")
+}
@@ -0,0 +1,69 @@
+//! Speculative loading hints (`<link rel="preconnect">` and friends).
+//!
+//! The renderer reports hints as it parses; the network stack acts on them
+//! off the critical path: DNS warm-up, ahead-of-time QUIC handshakes, or a
+//! full low-priority fetch into the cache for `preload`.
+
+use std::sync::Arc;
+
+use super::scheduler::ResourcePriority;
+use super::{http3, NetworkStack, Request};
+
+/// What a hint asks us to warm up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResourceHint {
+    /// Resolve the host early (`rel="dns-prefetch"`).
+    DnsPrefetch { url: String },
+    /// Resolve and complete the transport handshake (`rel="preconnect"`).
+    Preconnect { url: String },
+    /// Fetch the resource into the cache (`rel="preload"`).
+    Preload { url: String },
+}
+
+impl ResourceHint {
+    /// Map a `<link rel=… href=…>` pair to a hint, if the rel is one we
+    /// act on. `href` must already be resolved against the document URL.
+    pub fn from_link(rel: &str, href: &str) -> Option<Self> {
+        let url = href.to_owned();
+        match rel.to_ascii_lowercase().as_str() {
+            "dns-prefetch" => Some(Self::DnsPrefetch { url }),
+            "preconnect" => Some(Self::Preconnect { url }),
+            "preload" => Some(Self::Preload { url }),
+            _ => None,
+        }
+    }
+}
+
+impl NetworkStack {
+    /// Act on a hint discovered during parse. All work is fire-and-forget
+    /// and must never delay the document load.
+    pub fn apply_hint(self: &Arc<Self>, hint: ResourceHint) {
+        match hint {
+            ResourceHint::DnsPrefetch { url } => {
+                if let Ok(origin) = http3::origin_of(&url) {
+                    tokio::spawn(async move {
+                        if let Ok((host, port)) = http3::split_host_port(&origin) {
+                            let _ = super::dns::DnsCache::shared().resolve(&host, port).await;
+                        }
+                    });
+                }
+            }
+            ResourceHint::Preconnect { url } => {
+                if let Ok(origin) = http3::origin_of(&url) {
+                    let stack = Arc::clone(self);
+                    tokio::spawn(async move {
+                        stack.client.preconnect(&origin).await;
+                    });
+                }
+            }
+            ResourceHint::Preload { url } => {
+                let stack = Arc::clone(self);
+                tokio::spawn(async move {
+                    let _ = stack
+                        .fetch_prioritized(Request::get(url), ResourcePriority::VeryLow)
+                        .await;
+                });
+            }
+        }
+    }
+}
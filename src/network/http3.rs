@@ -0,0 +1,297 @@
+//! QUIC/HTTP-3 transport client.
+//!
+//! The engine is HTTP/3-first: connections are established with quinn and
+//! requests are multiplexed over h3 streams. One `Http3Client` is shared by
+//! the whole [`super::NetworkStack`]; it pools QUIC connections per origin.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use bytes::{Buf, Bytes};
+use tokio::sync::Mutex;
+
+use super::request::{Headers, Request};
+use super::response::Response;
+use super::NetworkError;
+
+type SendRequest = h3::client::SendRequest<h3_quinn::OpenStreams, Bytes>;
+
+/// Shared HTTP/3 client with a per-origin connection pool.
+pub struct Http3Client {
+    endpoint: quinn::Endpoint,
+    connections: Mutex<HashMap<String, SendRequest>>,
+    stats: std::sync::Arc<super::stats::ConnectionPoolStats>,
+}
+
+impl Http3Client {
+    pub fn new() -> Self {
+        Self::with_stats(std::sync::Arc::new(super::stats::ConnectionPoolStats::new()))
+    }
+
+    /// Build a client reporting connection lifecycle into `stats`.
+    pub fn with_stats(stats: std::sync::Arc<super::stats::ConnectionPoolStats>) -> Self {
+        let endpoint = Self::build_endpoint().expect("failed to create QUIC endpoint");
+        Self {
+            endpoint,
+            connections: Mutex::new(HashMap::new()),
+            stats,
+        }
+    }
+
+    fn build_endpoint() -> Result<quinn::Endpoint, NetworkError> {
+        let mut roots = rustls::RootCertStore::empty();
+        roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+        let mut crypto = rustls::ClientConfig::builder()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        crypto.alpn_protocols = vec![b"h3".to_vec()];
+        // Remember session tickets per origin so reconnects can attempt
+        // 0-RTT. The cache is keyed by server name inside rustls.
+        crypto.resumption = rustls::client::Resumption::store(Arc::new(
+            rustls::client::ClientSessionMemoryCache::new(256),
+        ));
+        crypto.enable_early_data = true;
+
+        let client_config = quinn::ClientConfig::new(Arc::new(
+            quinn::crypto::rustls::QuicClientConfig::try_from(crypto)
+                .map_err(|e| NetworkError::Tls(e.to_string()))?,
+        ));
+        let mut endpoint = quinn::Endpoint::client("[::]:0".parse().unwrap())?;
+        endpoint.set_default_client_config(client_config);
+        Ok(endpoint)
+    }
+
+    /// Issue `request` over an HTTP/3 stream, buffering the whole body.
+    pub async fn send(&self, request: &Request) -> Result<Response, NetworkError> {
+        let (head, body) = self.send_streaming(request).await?;
+        super::body::collect_response(head, body).await
+    }
+
+    /// Issue `request`, returning the response head as soon as it arrives
+    /// and the body as a chunk stream. Reuses a pooled connection to the
+    /// origin when one exists.
+    pub async fn send_streaming(
+        &self,
+        request: &Request,
+    ) -> Result<(super::body::ResponseHead, super::body::BodyStream), NetworkError> {
+        let origin = origin_of(&request.url)?;
+        // Connections are pooled per (top-level site, origin) so a reused
+        // connection can never be observed across sites.
+        let pool_key = super::partition::pool_key(request.partition.as_deref(), &origin);
+        // 0-RTT replays are only safe for idempotent requests.
+        let allow_early_data = request.method.is_idempotent();
+        let send_request = self
+            .connection_for(&origin, &pool_key, allow_early_data)
+            .await?;
+        self.stats.record_request(&origin);
+        match self.send_on(send_request, request).await {
+            Ok(parts) => Ok(parts),
+            Err(err) => {
+                // Drop the pooled connection on stream errors; the next
+                // request will redial.
+                self.connections.lock().await.remove(&pool_key);
+                self.stats.record_close(&origin);
+                Err(err)
+            }
+        }
+    }
+
+    /// Establish (or reuse) the connection to `origin` without sending a
+    /// request, so the handshake cost is paid before it matters.
+    pub async fn preconnect(&self, origin: &str) -> Result<(), NetworkError> {
+        self.connection_for(origin, origin, false).await.map(|_| ())
+    }
+
+    /// Rebind the endpoint to a fresh UDP socket after a network path
+    /// change (Wi-Fi → cellular). Live QUIC connections migrate to the new
+    /// path instead of being torn down.
+    pub fn migrate(&self) -> Result<(), NetworkError> {
+        let socket = std::net::UdpSocket::bind("[::]:0")?;
+        self.endpoint
+            .rebind(socket)
+            .map_err(|e| NetworkError::ConnectionFailed(format!("migration rebind: {e}")))
+    }
+
+    async fn connection_for(
+        &self,
+        origin: &str,
+        pool_key: &str,
+        allow_early_data: bool,
+    ) -> Result<SendRequest, NetworkError> {
+        let mut pool = self.connections.lock().await;
+        if let Some(send_request) = pool.get(pool_key) {
+            return Ok(send_request.clone());
+        }
+        let send_request = self.connect(origin, allow_early_data).await?;
+        pool.insert(pool_key.to_owned(), send_request.clone());
+        Ok(send_request)
+    }
+
+    async fn connect(
+        &self,
+        origin: &str,
+        allow_early_data: bool,
+    ) -> Result<SendRequest, NetworkError> {
+        let (host, port) = split_host_port(origin)?;
+        let addr = super::dns::DnsCache::shared()
+            .resolve(&host, port)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| NetworkError::DnsFailure(host.clone()))?;
+        let connecting = self
+            .endpoint
+            .connect(addr, &host)
+            .map_err(|e| NetworkError::ConnectionFailed(e.to_string()))?;
+        // With a stored session ticket and permission from the caller, send
+        // the request in the first flight; otherwise wait out the full
+        // handshake. `into_0rtt` hands back the `Connecting` when no ticket
+        // is available.
+        let connection = if allow_early_data {
+            match connecting.into_0rtt() {
+                Ok((connection, _accepted)) => connection,
+                Err(connecting) => connecting
+                    .await
+                    .map_err(|e| NetworkError::ConnectionFailed(e.to_string()))?,
+            }
+        } else {
+            connecting
+                .await
+                .map_err(|e| NetworkError::ConnectionFailed(e.to_string()))?
+        };
+        self.stats
+            .record_open(origin, super::client::HttpVersion::H3);
+        self.stats.record_rtt(origin, connection.rtt());
+
+        let (mut driver, send_request) =
+            h3::client::new(h3_quinn::Connection::new(connection))
+                .await
+                .map_err(|e| NetworkError::Protocol(e.to_string()))?;
+        // The driver owns the connection-level state machine; run it until
+        // the connection is closed.
+        tokio::spawn(async move {
+            let _ = std::future::poll_fn(|cx| driver.poll_close(cx)).await;
+        });
+        Ok(send_request)
+    }
+
+    async fn send_on(
+        &self,
+        mut send_request: SendRequest,
+        request: &Request,
+    ) -> Result<(super::body::ResponseHead, super::body::BodyStream), NetworkError> {
+        let mut builder = http::Request::builder()
+            .method(request.method.as_str())
+            .uri(&request.url);
+        for (name, value) in request.headers.iter() {
+            builder = builder.header(name, value);
+        }
+        let h3_request = builder
+            .body(())
+            .map_err(|e| NetworkError::Protocol(e.to_string()))?;
+
+        let mut stream = send_request
+            .send_request(h3_request)
+            .await
+            .map_err(|e| NetworkError::Protocol(e.to_string()))?;
+        if let Some(body) = &request.body {
+            stream
+                .send_data(Bytes::copy_from_slice(body))
+                .await
+                .map_err(|e| NetworkError::Protocol(e.to_string()))?;
+        }
+        stream
+            .finish()
+            .await
+            .map_err(|e| NetworkError::Protocol(e.to_string()))?;
+
+        let h3_response = stream
+            .recv_response()
+            .await
+            .map_err(|e| NetworkError::Protocol(e.to_string()))?;
+        let mut headers = Headers::new();
+        for (name, value) in h3_response.headers() {
+            headers.append(name.as_str(), value.to_str().unwrap_or_default());
+        }
+
+        let mut decoder = super::decompress::StreamingDecoder::for_response(&headers);
+        super::decompress::mark_decoded(&mut headers);
+        let head = super::body::ResponseHead {
+            url: request.url.clone(),
+            status: h3_response.status().as_u16(),
+            headers,
+        };
+
+        let (sink, body) = super::body::channel();
+        tokio::spawn(async move {
+            loop {
+                match stream.recv_data().await {
+                    Ok(Some(mut chunk)) => {
+                        let raw = chunk.copy_to_bytes(chunk.remaining());
+                        if let Err(err) = decoder.push(raw.as_ref()) {
+                            sink.fail(err).await;
+                            return;
+                        }
+                        let decoded = decoder.drain();
+                        if !decoded.is_empty() && sink.send(decoded.into()).await.is_err() {
+                            return;
+                        }
+                    }
+                    Ok(None) => {
+                        match decoder.finish() {
+                            Ok(tail) if !tail.is_empty() => {
+                                let _ = sink.send(tail.into()).await;
+                            }
+                            Ok(_) => {}
+                            Err(err) => sink.fail(err).await,
+                        }
+                        return;
+                    }
+                    Err(err) => {
+                        sink.fail(NetworkError::Protocol(err.to_string())).await;
+                        return;
+                    }
+                }
+            }
+        });
+
+        Ok((head, body))
+    }
+}
+
+impl Default for Http3Client {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Scheme + host + port of `url`, e.g. `https://example.com:443`.
+pub(crate) fn origin_of(url: &str) -> Result<String, NetworkError> {
+    let rest = url
+        .strip_prefix("https://")
+        .ok_or_else(|| NetworkError::InvalidUrl(url.to_owned()))?;
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or(rest);
+    if authority.is_empty() {
+        return Err(NetworkError::InvalidUrl(url.to_owned()));
+    }
+    Ok(format!("https://{authority}"))
+}
+
+pub(crate) fn split_host_port(origin: &str) -> Result<(String, u16), NetworkError> {
+    let authority = origin
+        .strip_prefix("https://")
+        .or_else(|| origin.strip_prefix("http://"))
+        .unwrap_or(origin);
+    match authority.rsplit_once(':') {
+        Some((host, port)) if !host.contains(']') || host.ends_with(']') => {
+            let port = port
+                .parse()
+                .map_err(|_| NetworkError::InvalidUrl(origin.to_owned()))?;
+            Ok((host.trim_matches(['[', ']']).to_owned(), port))
+        }
+        _ => {
+            let default = if origin.starts_with("http://") { 80 } else { 443 };
+            Ok((authority.to_owned(), default))
+        }
+    }
+}
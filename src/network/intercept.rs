@@ -0,0 +1,159 @@
+//! Fetch interception (service-worker style).
+//!
+//! Handlers registered against a [`UrlPattern`] see every matching request
+//! before network dispatch and can let it pass, rewrite it, synthesize a
+//! response, or block it outright. Content blockers, offline fallbacks and
+//! the future Service Worker implementation all sit on this hook.
+
+use std::sync::{Arc, RwLock};
+
+use super::request::{Headers, Request};
+use super::response::Response;
+
+/// A simple URL pattern: optional scheme, host with leading-`*` wildcard,
+/// and path prefix. `*://*.example.com/api` matches any scheme, any
+/// subdomain of example.com, and paths under `/api`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UrlPattern {
+    scheme: Option<String>,
+    host_suffix: String,
+    exact_host: bool,
+    path_prefix: String,
+}
+
+impl UrlPattern {
+    /// Parse a `scheme://host/path` pattern. Returns `None` if the pattern
+    /// has no host part.
+    pub fn parse(pattern: &str) -> Option<Self> {
+        let (scheme, rest) = match pattern.split_once("://") {
+            Some(("*", rest)) => (None, rest),
+            Some((scheme, rest)) => (Some(scheme.to_ascii_lowercase()), rest),
+            None => (None, pattern),
+        };
+        let (host, path) = match rest.find('/') {
+            Some(idx) => (&rest[..idx], &rest[idx..]),
+            None => (rest, "/"),
+        };
+        if host.is_empty() {
+            return None;
+        }
+        let (exact_host, host_suffix) = match host.strip_prefix("*.") {
+            Some(suffix) => (false, suffix.to_ascii_lowercase()),
+            None if host == "*" => (false, String::new()),
+            None => (true, host.to_ascii_lowercase()),
+        };
+        Some(Self {
+            scheme,
+            host_suffix,
+            exact_host,
+            path_prefix: path.to_owned(),
+        })
+    }
+
+    pub fn matches(&self, url: &str) -> bool {
+        let (scheme, rest) = match url.split_once("://") {
+            Some(parts) => parts,
+            None => return false,
+        };
+        if let Some(want) = &self.scheme {
+            if !scheme.eq_ignore_ascii_case(want) {
+                return false;
+            }
+        }
+        let (authority, path) = match rest.find('/') {
+            Some(idx) => (&rest[..idx], &rest[idx..]),
+            None => (rest, "/"),
+        };
+        let host = authority
+            .rsplit_once(':')
+            .map_or(authority, |(h, _)| h)
+            .to_ascii_lowercase();
+
+        let host_ok = if self.host_suffix.is_empty() {
+            true
+        } else if self.exact_host {
+            host == self.host_suffix
+        } else {
+            host == self.host_suffix
+                || (host.ends_with(&self.host_suffix)
+                    && host.as_bytes()[host.len() - self.host_suffix.len() - 1] == b'.')
+        };
+        host_ok && path.starts_with(&self.path_prefix)
+    }
+}
+
+/// What an interceptor decided about a request.
+pub enum InterceptDecision {
+    /// Let the request proceed unchanged.
+    Continue,
+    /// Proceed with a rewritten request.
+    ContinueWith(Request),
+    /// Answer with a synthesized response; the network is never touched.
+    Respond(Response),
+    /// Cancel the request. The given reason surfaces in the error page and
+    /// devtools.
+    Block(String),
+}
+
+/// A fetch interception handler.
+pub trait FetchInterceptor: Send + Sync {
+    fn intercept(&self, request: &Request) -> InterceptDecision;
+}
+
+struct Registration {
+    pattern: UrlPattern,
+    handler: Arc<dyn FetchInterceptor>,
+}
+
+/// Ordered set of interceptors consulted before dispatch. Handlers run in
+/// registration order; the first non-`Continue` decision wins.
+#[derive(Default)]
+pub struct InterceptorRegistry {
+    registrations: RwLock<Vec<Registration>>,
+}
+
+impl InterceptorRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&self, pattern: UrlPattern, handler: Arc<dyn FetchInterceptor>) {
+        self.registrations
+            .write()
+            .unwrap()
+            .push(Registration { pattern, handler });
+    }
+
+    /// Run `request` through the matching handlers, threading rewrites.
+    pub fn run(&self, request: &Request) -> InterceptDecision {
+        let registrations = self.registrations.read().unwrap();
+        let mut current: Option<Request> = None;
+        for registration in registrations.iter() {
+            let effective = current.as_ref().unwrap_or(request);
+            if !registration.pattern.matches(&effective.url) {
+                continue;
+            }
+            match registration.handler.intercept(effective) {
+                InterceptDecision::Continue => {}
+                InterceptDecision::ContinueWith(rewritten) => current = Some(rewritten),
+                terminal => return terminal,
+            }
+        }
+        match current {
+            Some(rewritten) => InterceptDecision::ContinueWith(rewritten),
+            None => InterceptDecision::Continue,
+        }
+    }
+}
+
+/// Convenience for synthesizing an OK response from an interceptor.
+pub fn synthetic_response(url: &str, content_type: &str, body: Vec<u8>) -> Response {
+    let mut headers = Headers::new();
+    headers.set("content-type", content_type);
+    Response {
+        url: url.to_owned(),
+        status: 200,
+        headers,
+        body,
+    }
+}
@@ -0,0 +1,414 @@
+//! The network stack: everything between a URL and the bytes handed to the
+//! renderer.
+//!
+//! [`NetworkStack`] is the single entry point the engine uses to load
+//! resources. It owns the transport client and the HTTP cache and decides,
+//! per request, whether to serve from cache, revalidate, or go to the
+//! network.
+
+pub mod auth;
+pub mod body;
+pub mod cache;
+pub mod client;
+pub mod connectivity;
+pub mod decompress;
+pub mod dns;
+pub mod downloads;
+pub mod form;
+pub mod har;
+pub mod hints;
+pub mod http3;
+pub mod intercept;
+pub mod partition;
+pub mod protocol;
+pub mod proxy;
+pub mod referrer;
+pub mod request;
+pub mod response;
+pub mod scheduler;
+pub mod sniff;
+pub mod stats;
+pub mod throttle;
+pub mod tls;
+pub mod websocket;
+
+use std::io;
+use std::path::PathBuf;
+
+pub use body::{BodyStream, ResponseHead};
+pub use cache::{CacheEntryInfo, CacheLookup, HttpCache, HttpCacheConfig};
+pub use client::{HttpVersion, NetworkClient};
+pub use request::{Headers, Method, Request, RequestPolicy};
+pub use response::Response;
+pub use scheduler::{ResourcePriority, ResourceScheduler, ScheduleId};
+
+use std::sync::Arc;
+
+/// Errors produced by the network stack.
+#[derive(Debug, thiserror::Error)]
+pub enum NetworkError {
+    #[error("invalid URL: {0}")]
+    InvalidUrl(String),
+    #[error("DNS resolution failed for {0}")]
+    DnsFailure(String),
+    #[error("connection failed: {0}")]
+    ConnectionFailed(String),
+    #[error("TLS error: {0}")]
+    Tls(String),
+    #[error("certificate validation failed: {0}")]
+    Certificate(tls::TlsFailure),
+    #[error("request timed out")]
+    Timeout,
+    #[error("protocol error: {0}")]
+    Protocol(String),
+    #[error("request blocked: {0}")]
+    Blocked(String),
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// Top-level resource loader shared by all tabs.
+pub struct NetworkStack {
+    client: NetworkClient,
+    cache: Arc<HttpCache>,
+    scheduler: Arc<ResourceScheduler>,
+    security: Arc<crate::security::SecurityManager>,
+    interceptors: intercept::InterceptorRegistry,
+    auth: auth::AuthManager,
+    protocols: protocol::ProtocolRegistry,
+}
+
+impl NetworkStack {
+    /// Create a stack with the default on-disk cache location under the
+    /// profile directory.
+    pub fn new(
+        profile_dir: PathBuf,
+        security: Arc<crate::security::SecurityManager>,
+    ) -> Result<Self, NetworkError> {
+        let cache = HttpCache::new(HttpCacheConfig {
+            disk_root: profile_dir.join("http-cache"),
+            ..HttpCacheConfig::default()
+        })?;
+        Ok(Self {
+            client: NetworkClient::with_pins(security.pins()),
+            cache: Arc::new(cache),
+            scheduler: ResourceScheduler::new(),
+            security,
+            interceptors: intercept::InterceptorRegistry::new(),
+            auth: auth::AuthManager::new(),
+            protocols: protocol::ProtocolRegistry::new(),
+        })
+    }
+
+    /// Load a resource under the priority scheduler. Cache hits bypass the
+    /// queue entirely; only actual network dispatch consumes a slot.
+    pub async fn fetch_prioritized(
+        &self,
+        mut request: Request,
+        priority: ResourcePriority,
+    ) -> Result<Response, NetworkError> {
+        if !self.security.network_partitioning() {
+            request.partition = None;
+        }
+        if request.method == Method::Get {
+            if let CacheLookup::Fresh(response) = self.cache.lookup(&request).await {
+                return Ok(response);
+            }
+        }
+        let host = http3::split_host_port(&http3::origin_of(&request.url)?)?.0;
+        let (_id, _permit) = self.scheduler.acquire(&host, priority).await;
+        self.fetch(request).await
+    }
+
+    /// The priority scheduler, so the renderer can reprioritise queued
+    /// requests it discovers to be render-blocking.
+    pub fn scheduler(&self) -> &Arc<ResourceScheduler> {
+        &self.scheduler
+    }
+
+    /// Load a resource, consulting the HTTP cache first.
+    ///
+    /// Fresh cache hits are returned without touching the network. Stale
+    /// entries with validators are revalidated with a conditional request;
+    /// a `304 Not Modified` answer refreshes the stored entry and serves it.
+    pub async fn fetch(&self, mut request: Request) -> Result<Response, NetworkError> {
+        if !self.security.network_partitioning() {
+            request.partition = None;
+        }
+        if let Some(handler) = self.protocols.handler_for(&request.url) {
+            return handler.handle(&request);
+        }
+        request.url = self.security.hsts().upgrade(&request.url);
+        match self.interceptors.run(&request) {
+            intercept::InterceptDecision::Continue => {}
+            intercept::InterceptDecision::ContinueWith(rewritten) => request = rewritten,
+            intercept::InterceptDecision::Respond(response) => return Ok(response),
+            intercept::InterceptDecision::Block(reason) => {
+                return Err(NetworkError::Blocked(reason))
+            }
+        }
+        if !request.headers.contains("accept-encoding") {
+            request
+                .headers
+                .set("accept-encoding", decompress::ACCEPT_ENCODING);
+        }
+        if request.method == Method::Get {
+            match self.cache.lookup(&request).await {
+                CacheLookup::Fresh(response) => return Ok(response),
+                CacheLookup::NeedsRevalidation { conditional_headers, stored } => {
+                    for (name, value) in conditional_headers.iter() {
+                        request.headers.set(name, value);
+                    }
+                    let response = self.dispatch(&request).await?;
+                    if response.status == 304 {
+                        let refreshed = self.cache.refresh(&request, &response, stored).await?;
+                        return Ok(refreshed);
+                    }
+                    self.cache.store(&request, &response).await?;
+                    return Ok(response);
+                }
+                CacheLookup::Miss => {}
+            }
+        }
+
+        let mut response = self.dispatch(&request).await?;
+
+        // Answer an authentication challenge once, with stored or prompted
+        // credentials.
+        if let Some((challenge, header)) = auth::challenge_from(&response) {
+            let origin = http3::origin_of(&request.url)?;
+            if let Some(credentials) = self.auth.credentials_for(&origin, &challenge) {
+                let value = self.auth.authorization(
+                    &challenge,
+                    &credentials,
+                    request.method,
+                    &auth::request_uri(&request),
+                );
+                request.headers.set(header, &value);
+                response = self.dispatch(&request).await?;
+                if matches!(response.status, 401 | 407) {
+                    // Rejected: don't keep bad credentials around.
+                    self.auth.forget(&origin, &challenge.realm);
+                }
+            }
+        }
+
+        if request.method == Method::Get {
+            self.cache.store(&request, &response).await?;
+        }
+        Ok(response)
+    }
+
+    /// Send on the wire under the request's [`request::RequestPolicy`]:
+    /// read timeout, then jittered-backoff retries for transport failures.
+    /// Outcomes feed connectivity detection and the policy stores.
+    async fn dispatch(&self, request: &Request) -> Result<Response, NetworkError> {
+        let mut attempt = 0;
+        loop {
+            let outcome = match tokio::time::timeout(
+                request.policy.read_timeout,
+                self.client.send(request),
+            )
+            .await
+            {
+                Ok(outcome) => outcome,
+                Err(_) => Err(NetworkError::Timeout),
+            };
+            match outcome {
+                Ok(response) => {
+                    connectivity::ConnectivityMonitor::shared().record(&Ok(()));
+                    self.observe_response(request, &response);
+                    return Ok(response);
+                }
+                Err(err) => {
+                    connectivity::ConnectivityMonitor::shared().record(&Err(&err));
+                    let transient = matches!(
+                        err,
+                        NetworkError::Timeout | NetworkError::ConnectionFailed(_)
+                    );
+                    if transient && request.policy.may_retry(attempt, request.method) {
+                        attempt += 1;
+                        tokio::time::sleep(request.policy.backoff_delay(attempt)).await;
+                        continue;
+                    }
+                    return Err(err);
+                }
+            }
+        }
+    }
+
+    /// Load a resource as a chunk stream, for progressive consumers (the
+    /// streaming HTML parser, media, downloads).
+    ///
+    /// Fresh cache hits stream straight from the cache. Network responses
+    /// are teed: chunks flow to the caller immediately while a copy is
+    /// accumulated and stored once the body completes.
+    pub async fn fetch_streaming(
+        &self,
+        mut request: Request,
+    ) -> Result<(ResponseHead, BodyStream), NetworkError> {
+        if !self.security.network_partitioning() {
+            request.partition = None;
+        }
+        if let Some(handler) = self.protocols.handler_for(&request.url) {
+            let Response {
+                url,
+                status,
+                headers,
+                body: bytes,
+            } = handler.handle(&request)?;
+            let head = ResponseHead {
+                url,
+                status,
+                headers,
+            };
+            return Ok((head, body::single(bytes)));
+        }
+        request.url = self.security.hsts().upgrade(&request.url);
+        match self.interceptors.run(&request) {
+            intercept::InterceptDecision::Continue => {}
+            intercept::InterceptDecision::ContinueWith(rewritten) => request = rewritten,
+            intercept::InterceptDecision::Respond(response) => {
+                let Response {
+                    url,
+                    status,
+                    headers,
+                    body: bytes,
+                } = response;
+                let head = ResponseHead {
+                    url,
+                    status,
+                    headers,
+                };
+                return Ok((head, body::single(bytes)));
+            }
+            intercept::InterceptDecision::Block(reason) => {
+                return Err(NetworkError::Blocked(reason))
+            }
+        }
+        if !request.headers.contains("accept-encoding") {
+            request
+                .headers
+                .set("accept-encoding", decompress::ACCEPT_ENCODING);
+        }
+        if request.method == Method::Get {
+            if let CacheLookup::Fresh(response) = self.cache.lookup(&request).await {
+                let Response {
+                    url,
+                    status,
+                    headers,
+                    body: bytes,
+                } = response;
+                let head = ResponseHead {
+                    url,
+                    status,
+                    headers,
+                };
+                return Ok((head, body::single(bytes)));
+            }
+        }
+
+        let (head, mut upstream) = match self.client.send_streaming(&request).await {
+            Ok(parts) => {
+                connectivity::ConnectivityMonitor::shared().record(&Ok(()));
+                parts
+            }
+            Err(err) => {
+                connectivity::ConnectivityMonitor::shared().record(&Err(&err));
+                return Err(err);
+            }
+        };
+        self.security.hsts().observe(
+            &http3::split_host_port(&http3::origin_of(&request.url)?)?.0,
+            &head.headers,
+        );
+
+        let (sink, downstream) = body::channel();
+        let cache_head = head.clone();
+        let cache = Arc::clone(&self.cache);
+        let cache_partition = request.partition.clone();
+        tokio::spawn(async move {
+            let mut copy = Vec::new();
+            while let Some(chunk) = upstream.next_chunk().await {
+                match chunk {
+                    Ok(chunk) => {
+                        copy.extend_from_slice(&chunk);
+                        if sink.send(chunk).await.is_err() {
+                            // Consumer went away; finish pulling so the
+                            // entry can still be cached.
+                        }
+                    }
+                    Err(err) => {
+                        sink.fail(err).await;
+                        return;
+                    }
+                }
+            }
+            let response = Response {
+                url: cache_head.url.clone(),
+                status: cache_head.status,
+                headers: cache_head.headers,
+                body: copy,
+            };
+            let mut request = Request::get(cache_head.url);
+            request.partition = cache_partition;
+            let _ = cache.store(&request, &response).await;
+        });
+        Ok((head, downstream))
+    }
+
+    /// Feed security-relevant response headers (HSTS, …) into the policy
+    /// stores. Only secure transports count, per RFC 6797.
+    fn observe_response(&self, request: &Request, response: &Response) {
+        if let Ok(origin) = http3::origin_of(&request.url) {
+            if let Ok((host, _)) = http3::split_host_port(&origin) {
+                self.security.hsts().observe(&host, &response.headers);
+            }
+        }
+    }
+
+    /// The HTTP cache, for inspection (about:cache, devtools) and clearing.
+    pub fn cache(&self) -> &HttpCache {
+        &self.cache
+    }
+
+    /// Connection pool metrics, for devtools and `about:network`.
+    pub fn pool_stats(&self) -> &Arc<stats::ConnectionPoolStats> {
+        self.client.pool_stats()
+    }
+
+    /// The fetch interception registry (content blockers, offline
+    /// fallbacks, future service workers).
+    pub fn interceptors(&self) -> &intercept::InterceptorRegistry {
+        &self.interceptors
+    }
+
+    /// HTTP authentication state; the UI installs its credential prompt
+    /// here.
+    pub fn auth(&self) -> &auth::AuthManager {
+        &self.auth
+    }
+
+    /// The custom scheme handler table (`about:`, `binix:`, embedder
+    /// schemes).
+    pub fn protocols(&self) -> &protocol::ProtocolRegistry {
+        &self.protocols
+    }
+
+    /// Throttle control, for devtools' network conditions panel.
+    pub fn throttler(&self) -> &Arc<throttle::Throttler> {
+        self.client.throttler()
+    }
+
+    /// Begin recording traffic as a HAR session; the handle exports it.
+    pub fn start_har_capture(&self, capture_bodies: bool) -> Arc<har::HarRecorder> {
+        let recorder = har::HarRecorder::new(capture_bodies);
+        self.client.set_har_recorder(Some(Arc::clone(&recorder)));
+        recorder
+    }
+
+    /// Stop recording. Entries already captured stay on the handle.
+    pub fn stop_har_capture(&self) {
+        self.client.set_har_recorder(None);
+    }
+}
@@ -0,0 +1,74 @@
+//! Network state partitioning.
+//!
+//! Shared network state — the HTTP cache, connection pools — is a classic
+//! cross-site tracking channel: a third party embedded on two sites can
+//! tell the same user apart by timing cache hits or reusing a connection.
+//! Partitioning keys that state by the *top-level site* the request was
+//! made for, so state accumulated under one site is invisible under
+//! another. The switch lives on [`crate::security::SecurityManager`]; when
+//! disabled (debugging only), requests fall back to a single shared
+//! partition.
+
+/// Multi-label public suffixes we special-case; everything else is treated
+/// as a single-label suffix. A full public-suffix list integration can
+/// replace this without changing callers.
+const TWO_LABEL_SUFFIXES: &[&str] = &[
+    "co.uk", "org.uk", "ac.uk", "gov.uk", "com.au", "net.au", "org.au", "co.jp", "ne.jp",
+    "or.jp", "com.br", "com.cn", "com.mx", "co.in", "co.kr", "co.nz", "com.tw",
+];
+
+/// The top-level site of `url`: scheme plus registrable domain, e.g.
+/// `https://example.co.uk` for `https://a.b.example.co.uk/page`. `None`
+/// for URLs without a host.
+pub fn top_level_site(url: &str) -> Option<String> {
+    let (scheme, rest) = url.split_once("://")?;
+    let host = rest.split(['/', '?', '#']).next()?;
+    let host = host.rsplit_once('@').map_or(host, |(_, h)| h);
+    let host = host.split(':').next()?;
+    if host.is_empty() {
+        return None;
+    }
+    // IP addresses are their own site.
+    if host.parse::<std::net::IpAddr>().is_ok() || host.starts_with('[') {
+        return Some(format!("{scheme}://{host}"));
+    }
+    let labels: Vec<&str> = host.split('.').collect();
+    let suffix_labels = if labels.len() >= 3
+        && TWO_LABEL_SUFFIXES.contains(&labels[labels.len() - 2..].join(".").as_str())
+    {
+        3
+    } else {
+        2
+    };
+    let registrable = if labels.len() <= suffix_labels {
+        host.to_owned()
+    } else {
+        labels[labels.len() - suffix_labels..].join(".")
+    };
+    Some(format!("{scheme}://{registrable}"))
+}
+
+/// Separator between partition and resource in composite keys. `\u{1}`
+/// cannot appear in a URL or a site, so keys never collide.
+const SEPARATOR: char = '\u{1}';
+
+/// Composite key for per-resource state (cache entries).
+pub(crate) fn state_key(partition: Option<&str>, url: &str) -> String {
+    match partition {
+        Some(site) => format!("{site}{SEPARATOR}{url}"),
+        None => url.to_owned(),
+    }
+}
+
+/// Composite key for per-origin state (connection pools).
+pub(crate) fn pool_key(partition: Option<&str>, origin: &str) -> String {
+    state_key(partition, origin)
+}
+
+/// Decompose a composite key into `(partition, resource)`.
+pub(crate) fn split_key(key: &str) -> (Option<&str>, &str) {
+    match key.split_once(SEPARATOR) {
+        Some((site, resource)) => (Some(site), resource),
+        None => (None, key),
+    }
+}
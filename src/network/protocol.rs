@@ -0,0 +1,127 @@
+//! Custom protocol handlers.
+//!
+//! Everything the engine loads goes through [`super::NetworkStack::fetch`],
+//! including URLs that never touch the network: `about:` pages, the
+//! `binix:` internal scheme, embedder schemes like `ipfs:`. A
+//! [`ProtocolHandler`] registered for a scheme synthesizes the [`Response`]
+//! for such URLs, so callers see one uniform loading path.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use super::request::{Headers, Request};
+use super::response::Response;
+use super::NetworkError;
+
+/// Synthesizes responses for a non-HTTP scheme.
+pub trait ProtocolHandler: Send + Sync {
+    fn handle(&self, request: &Request) -> Result<Response, NetworkError>;
+}
+
+/// Scheme → handler table owned by the [`super::NetworkStack`].
+pub struct ProtocolRegistry {
+    handlers: Mutex<HashMap<String, Arc<dyn ProtocolHandler>>>,
+}
+
+impl ProtocolRegistry {
+    pub fn new() -> Self {
+        let registry = Self {
+            handlers: Mutex::new(HashMap::new()),
+        };
+        registry.register("about", Arc::new(AboutHandler));
+        registry
+    }
+
+    /// Register `handler` for `scheme` (without the trailing colon),
+    /// replacing any previous handler. `http` and `https` cannot be
+    /// overridden.
+    pub fn register(&self, scheme: &str, handler: Arc<dyn ProtocolHandler>) {
+        let scheme = scheme.to_ascii_lowercase();
+        if scheme == "http" || scheme == "https" {
+            return;
+        }
+        self.handlers.lock().unwrap().insert(scheme, handler);
+    }
+
+    pub fn unregister(&self, scheme: &str) {
+        self.handlers
+            .lock()
+            .unwrap()
+            .remove(&scheme.to_ascii_lowercase());
+    }
+
+    /// The handler responsible for `url`'s scheme, if one is registered.
+    pub fn handler_for(&self, url: &str) -> Option<Arc<dyn ProtocolHandler>> {
+        let scheme = scheme_of(url)?;
+        self.handlers
+            .lock()
+            .unwrap()
+            .get(&scheme.to_ascii_lowercase())
+            .cloned()
+    }
+}
+
+impl Default for ProtocolRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The scheme of `url`, if it has one.
+pub fn scheme_of(url: &str) -> Option<&str> {
+    let (scheme, _) = url.split_once(':')?;
+    if scheme.is_empty()
+        || !scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.')
+    {
+        return None;
+    }
+    Some(scheme)
+}
+
+/// Build a synthesized response for a handler to return.
+pub fn synthesize(url: &str, status: u16, content_type: &str, body: Vec<u8>) -> Response {
+    let mut headers = Headers::new();
+    headers.set("content-type", content_type);
+    Response {
+        url: url.to_owned(),
+        status,
+        headers,
+        body,
+    }
+}
+
+/// Built-in `about:` pages.
+struct AboutHandler;
+
+impl ProtocolHandler for AboutHandler {
+    fn handle(&self, request: &Request) -> Result<Response, NetworkError> {
+        let page = request.url.strip_prefix("about:").unwrap_or_default();
+        match page {
+            "blank" | "" => Ok(synthesize(
+                &request.url,
+                200,
+                "text/html",
+                b"<!DOCTYPE html><html><head></head><body></body></html>".to_vec(),
+            )),
+            "version" => Ok(synthesize(
+                &request.url,
+                200,
+                "text/html",
+                format!(
+                    "<!DOCTYPE html><html><body><h1>Binix {}</h1></body></html>",
+                    env!("CARGO_PKG_VERSION")
+                )
+                .into_bytes(),
+            )),
+            _ => Ok(synthesize(
+                &request.url,
+                404,
+                "text/html",
+                format!("<!DOCTYPE html><html><body><p>Unknown page: about:{page}</p></body></html>")
+                    .into_bytes(),
+            )),
+        }
+    }
+}
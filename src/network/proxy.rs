@@ -0,0 +1,263 @@
+//! Proxy support: explicit HTTP(S) proxies via CONNECT tunnels, SOCKS5
+//! (RFC 1928/1929), and PAC scripts evaluated with the JS engine.
+//!
+//! UDP-based HTTP/3 cannot traverse these proxies, so the client drops to
+//! the TCP path whenever a proxy applies to a request (see
+//! [`super::client::NetworkClient`]).
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+use crate::js_engine::{JavaScriptEngine, JsRuntime};
+
+use super::NetworkError;
+
+/// A single proxy endpoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProxyServer {
+    /// HTTP proxy reached with a CONNECT tunnel.
+    Http { host: String, port: u16 },
+    /// SOCKS5 proxy, optionally with username/password auth.
+    Socks5 {
+        host: String,
+        port: u16,
+        auth: Option<(String, String)>,
+    },
+}
+
+/// How proxies are chosen for a request.
+#[derive(Debug, Clone, Default)]
+pub enum ProxyConfig {
+    /// No proxying.
+    #[default]
+    Direct,
+    /// Fixed proxy for all requests.
+    Fixed(ProxyServer),
+    /// Proxy auto-config script source; evaluated per request.
+    Pac(String),
+}
+
+/// Full proxy settings including the bypass list. Bypass patterns match a
+/// whole host (`example.com`) or a domain suffix (`.internal`).
+#[derive(Debug, Clone, Default)]
+pub struct ProxySettings {
+    pub config: ProxyConfig,
+    pub bypass: Vec<String>,
+}
+
+impl ProxySettings {
+    pub fn is_direct(&self) -> bool {
+        matches!(self.config, ProxyConfig::Direct)
+    }
+
+    pub fn should_bypass(&self, host: &str) -> bool {
+        self.bypass.iter().any(|pattern| {
+            if let Some(suffix) = pattern.strip_prefix('.') {
+                host.ends_with(suffix)
+            } else {
+                host.eq_ignore_ascii_case(pattern)
+            }
+        })
+    }
+
+    /// Resolve the proxy to use for `url`/`host`, evaluating PAC if
+    /// configured. `None` means connect directly.
+    pub fn proxy_for(&self, url: &str, host: &str) -> Result<Option<ProxyServer>, NetworkError> {
+        if self.should_bypass(host) {
+            return Ok(None);
+        }
+        match &self.config {
+            ProxyConfig::Direct => Ok(None),
+            ProxyConfig::Fixed(server) => Ok(Some(server.clone())),
+            ProxyConfig::Pac(script) => evaluate_pac(script, url, host),
+        }
+    }
+}
+
+/// Open a TCP connection to `host:port`, tunnelling through `proxy` if one
+/// is given.
+pub async fn connect(
+    proxy: Option<&ProxyServer>,
+    host: &str,
+    port: u16,
+) -> Result<TcpStream, NetworkError> {
+    match proxy {
+        None => {
+            let addrs = super::dns::DnsCache::shared().resolve(host, port).await?;
+            TcpStream::connect(addrs.as_slice())
+                .await
+                .map_err(|e| NetworkError::ConnectionFailed(e.to_string()))
+        }
+        Some(ProxyServer::Http { host: ph, port: pp }) => {
+            connect_http_tunnel(ph, *pp, host, port).await
+        }
+        Some(ProxyServer::Socks5 {
+            host: ph,
+            port: pp,
+            auth,
+        }) => connect_socks5(ph, *pp, auth.as_ref(), host, port).await,
+    }
+}
+
+async fn connect_http_tunnel(
+    proxy_host: &str,
+    proxy_port: u16,
+    host: &str,
+    port: u16,
+) -> Result<TcpStream, NetworkError> {
+    let mut stream = TcpStream::connect((proxy_host, proxy_port))
+        .await
+        .map_err(|e| NetworkError::ConnectionFailed(format!("proxy: {e}")))?;
+    let connect = format!(
+        "CONNECT {host}:{port} HTTP/1.1\r\nhost: {host}:{port}\r\nproxy-connection: keep-alive\r\n\r\n"
+    );
+    stream.write_all(connect.as_bytes()).await?;
+
+    // Read the proxy's response head only; tunnel bytes follow immediately.
+    let mut head = Vec::new();
+    let mut byte = [0u8; 1];
+    loop {
+        stream.read_exact(&mut byte).await?;
+        head.push(byte[0]);
+        if head.ends_with(b"\r\n\r\n") {
+            break;
+        }
+        if head.len() > 16 * 1024 {
+            return Err(NetworkError::Protocol("oversized CONNECT response".into()));
+        }
+    }
+    let status_line = String::from_utf8_lossy(&head);
+    let status_line = status_line.lines().next().unwrap_or_default();
+    let ok = status_line
+        .split_whitespace()
+        .nth(1)
+        .map_or(false, |code| code.starts_with('2'));
+    if !ok {
+        return Err(NetworkError::ConnectionFailed(format!(
+            "proxy refused CONNECT: {status_line}"
+        )));
+    }
+    Ok(stream)
+}
+
+async fn connect_socks5(
+    proxy_host: &str,
+    proxy_port: u16,
+    auth: Option<&(String, String)>,
+    host: &str,
+    port: u16,
+) -> Result<TcpStream, NetworkError> {
+    let mut stream = TcpStream::connect((proxy_host, proxy_port))
+        .await
+        .map_err(|e| NetworkError::ConnectionFailed(format!("SOCKS5 proxy: {e}")))?;
+
+    // Greeting: offer no-auth, plus username/password when configured.
+    let methods: &[u8] = if auth.is_some() { &[0x00, 0x02] } else { &[0x00] };
+    let mut greeting = vec![0x05, methods.len() as u8];
+    greeting.extend_from_slice(methods);
+    stream.write_all(&greeting).await?;
+
+    let mut choice = [0u8; 2];
+    stream.read_exact(&mut choice).await?;
+    match choice {
+        [0x05, 0x00] => {}
+        [0x05, 0x02] => {
+            let (user, pass) = auth.ok_or_else(|| {
+                NetworkError::ConnectionFailed("SOCKS5 proxy requires credentials".into())
+            })?;
+            let mut msg = vec![0x01, user.len() as u8];
+            msg.extend_from_slice(user.as_bytes());
+            msg.push(pass.len() as u8);
+            msg.extend_from_slice(pass.as_bytes());
+            stream.write_all(&msg).await?;
+            let mut reply = [0u8; 2];
+            stream.read_exact(&mut reply).await?;
+            if reply[1] != 0x00 {
+                return Err(NetworkError::ConnectionFailed(
+                    "SOCKS5 authentication rejected".into(),
+                ));
+            }
+        }
+        _ => {
+            return Err(NetworkError::ConnectionFailed(
+                "SOCKS5 method negotiation failed".into(),
+            ))
+        }
+    }
+
+    // CONNECT with a domain address; the proxy does the resolving.
+    let mut request = vec![0x05, 0x01, 0x00, 0x03, host.len() as u8];
+    request.extend_from_slice(host.as_bytes());
+    request.extend_from_slice(&port.to_be_bytes());
+    stream.write_all(&request).await?;
+
+    let mut reply_head = [0u8; 4];
+    stream.read_exact(&mut reply_head).await?;
+    if reply_head[1] != 0x00 {
+        return Err(NetworkError::ConnectionFailed(format!(
+            "SOCKS5 connect failed: code {:#x}",
+            reply_head[1]
+        )));
+    }
+    // Consume the bound address, which varies with its type byte.
+    let bound_len = match reply_head[3] {
+        0x01 => 4,
+        0x04 => 16,
+        0x03 => {
+            let mut len = [0u8; 1];
+            stream.read_exact(&mut len).await?;
+            len[0] as usize
+        }
+        other => {
+            return Err(NetworkError::Protocol(format!(
+                "SOCKS5 bad address type {other:#x}"
+            )))
+        }
+    };
+    let mut skip = vec![0u8; bound_len + 2];
+    stream.read_exact(&mut skip).await?;
+    Ok(stream)
+}
+
+/// Run `FindProxyForURL` from a PAC script and parse the first usable
+/// directive out of its `"PROXY h:p; SOCKS5 h:p; DIRECT"` result.
+fn evaluate_pac(script: &str, url: &str, host: &str) -> Result<Option<ProxyServer>, NetworkError> {
+    let mut runtime = JsRuntime::new();
+    let call = format!(
+        "{script}\nFindProxyForURL({url:?}, {host:?})",
+    );
+    let result = runtime
+        .execute(&call)
+        .map_err(|e| NetworkError::Protocol(format!("PAC evaluation: {e}")))?;
+
+    for directive in result.trim_matches('"').split(';') {
+        let directive = directive.trim();
+        let mut parts = directive.split_whitespace();
+        match parts.next().map(|k| k.to_ascii_uppercase()).as_deref() {
+            Some("DIRECT") => return Ok(None),
+            Some("PROXY") | Some("HTTP") => {
+                if let Some((host, port)) = parts.next().and_then(|hp| hp.rsplit_once(':')) {
+                    if let Ok(port) = port.parse() {
+                        return Ok(Some(ProxyServer::Http {
+                            host: host.to_owned(),
+                            port,
+                        }));
+                    }
+                }
+            }
+            Some("SOCKS5") | Some("SOCKS") => {
+                if let Some((host, port)) = parts.next().and_then(|hp| hp.rsplit_once(':')) {
+                    if let Ok(port) = port.parse() {
+                        return Ok(Some(ProxyServer::Socks5 {
+                            host: host.to_owned(),
+                            port,
+                            auth: None,
+                        }));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(None)
+}
@@ -0,0 +1,105 @@
+//! Referrer computation (Referrer-Policy spec).
+//!
+//! The referrer a request carries is derived from the document URL, the
+//! page's policy (header or `<meta name=referrer>`), and any per-link
+//! `referrerpolicy` attribute. The default is
+//! `strict-origin-when-cross-origin`, matching current browsers.
+
+use super::request::Request;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReferrerPolicy {
+    NoReferrer,
+    NoReferrerWhenDowngrade,
+    Origin,
+    OriginWhenCrossOrigin,
+    SameOrigin,
+    StrictOrigin,
+    #[default]
+    StrictOriginWhenCrossOrigin,
+    UnsafeUrl,
+}
+
+impl ReferrerPolicy {
+    /// Parse a policy token (header value, meta content, or attribute).
+    /// Unknown tokens yield `None` so callers fall back to the next source.
+    pub fn parse(token: &str) -> Option<Self> {
+        Some(match token.trim().to_ascii_lowercase().as_str() {
+            "no-referrer" => Self::NoReferrer,
+            "no-referrer-when-downgrade" => Self::NoReferrerWhenDowngrade,
+            "origin" => Self::Origin,
+            "origin-when-cross-origin" => Self::OriginWhenCrossOrigin,
+            "same-origin" => Self::SameOrigin,
+            "strict-origin" => Self::StrictOrigin,
+            "strict-origin-when-cross-origin" => Self::StrictOriginWhenCrossOrigin,
+            "unsafe-url" => Self::UnsafeUrl,
+            _ => return None,
+        })
+    }
+
+    /// The last valid token wins when a header lists several.
+    pub fn parse_header(value: &str) -> Option<Self> {
+        value.split(',').rev().find_map(Self::parse)
+    }
+}
+
+/// `scheme://host[:port]` of a URL, or `None` for non-hierarchical URLs.
+fn origin_of(url: &str) -> Option<String> {
+    let (scheme, rest) = url.split_once("://")?;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    if authority.is_empty() {
+        return None;
+    }
+    Some(format!("{scheme}://{authority}"))
+}
+
+/// URL stripped for use as a referrer: no fragment, no credentials.
+fn strip_for_referrer(url: &str) -> String {
+    url.split('#').next().unwrap_or(url).to_owned()
+}
+
+fn is_https(url: &str) -> bool {
+    url.starts_with("https://")
+}
+
+/// Compute the `Referer` value for a request from `document_url` to
+/// `target_url` under `policy`. `None` means no header is sent.
+pub fn compute(policy: ReferrerPolicy, document_url: &str, target_url: &str) -> Option<String> {
+    let source_origin = origin_of(document_url)?;
+    let target_origin = origin_of(target_url);
+    let same_origin = target_origin.as_deref() == Some(source_origin.as_str());
+    let downgrade = is_https(document_url) && !is_https(target_url);
+
+    let full = strip_for_referrer(document_url);
+    let origin_only = format!("{source_origin}/");
+
+    match policy {
+        ReferrerPolicy::NoReferrer => None,
+        ReferrerPolicy::UnsafeUrl => Some(full),
+        ReferrerPolicy::NoReferrerWhenDowngrade => (!downgrade).then_some(full),
+        ReferrerPolicy::Origin => Some(origin_only),
+        ReferrerPolicy::SameOrigin => same_origin.then_some(full),
+        ReferrerPolicy::StrictOrigin => (!downgrade).then_some(origin_only),
+        ReferrerPolicy::OriginWhenCrossOrigin => {
+            Some(if same_origin { full } else { origin_only })
+        }
+        ReferrerPolicy::StrictOriginWhenCrossOrigin => {
+            if same_origin {
+                Some(full)
+            } else if downgrade {
+                None
+            } else {
+                Some(origin_only)
+            }
+        }
+    }
+}
+
+/// Set (or clear) the `Referer` header on `request` for a fetch initiated
+/// by the document at `document_url`.
+pub fn apply(request: &mut Request, policy: ReferrerPolicy, document_url: &str) {
+    match compute(policy, document_url, &request.url) {
+        Some(value) => request.headers.set("referer", &value),
+        None => request.headers.remove("referer"),
+    }
+}
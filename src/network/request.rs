@@ -0,0 +1,232 @@
+//! Request types shared across the network stack.
+
+use std::fmt;
+use std::time::Duration;
+
+/// HTTP request methods the engine issues.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Method {
+    Get,
+    Head,
+    Post,
+    Put,
+    Delete,
+    Options,
+    Patch,
+    Connect,
+}
+
+impl Method {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Method::Get => "GET",
+            Method::Head => "HEAD",
+            Method::Post => "POST",
+            Method::Put => "PUT",
+            Method::Delete => "DELETE",
+            Method::Options => "OPTIONS",
+            Method::Patch => "PATCH",
+            Method::Connect => "CONNECT",
+        }
+    }
+
+    /// Whether the method is safe to retry or answer from cache.
+    pub fn is_idempotent(&self) -> bool {
+        matches!(
+            self,
+            Method::Get | Method::Head | Method::Put | Method::Delete | Method::Options
+        )
+    }
+}
+
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// An ordered, case-insensitive multimap of HTTP header fields.
+///
+/// Header names are stored as sent; lookups compare ASCII
+/// case-insensitively, as required by RFC 9110.
+#[derive(Debug, Clone, Default)]
+pub struct Headers {
+    entries: Vec<(String, String)>,
+}
+
+impl Headers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// First value for `name`, if any.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// All values for `name`, in insertion order.
+    pub fn get_all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> {
+        self.entries
+            .iter()
+            .filter(move |(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Replace any existing values for `name` with `value`.
+    pub fn set(&mut self, name: &str, value: &str) {
+        self.remove(name);
+        self.entries.push((name.to_owned(), value.to_owned()));
+    }
+
+    /// Add a value without removing existing ones.
+    pub fn append(&mut self, name: &str, value: &str) {
+        self.entries.push((name.to_owned(), value.to_owned()));
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.entries.retain(|(n, _)| !n.eq_ignore_ascii_case(name));
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries.iter().map(|(n, v)| (n.as_str(), v.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Timeout and retry behaviour for a class of requests.
+///
+/// Retries only apply to transport-level failures (connect, timeout); HTTP
+/// error statuses are never retried here. Non-idempotent methods are not
+/// retried unless the policy explicitly opts in, since the server may have
+/// seen the first attempt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestPolicy {
+    pub connect_timeout: Duration,
+    /// Budget for receiving the response head after the request is sent.
+    pub read_timeout: Duration,
+    /// Additional attempts after the first failure.
+    pub max_retries: u32,
+    /// Base delay for exponential backoff; each attempt doubles it, with
+    /// up to 50% random jitter added.
+    pub backoff_base: Duration,
+    /// Allow retrying POST and other non-idempotent methods.
+    pub retry_non_idempotent: bool,
+}
+
+impl Default for RequestPolicy {
+    fn default() -> Self {
+        Self::subresource()
+    }
+}
+
+impl RequestPolicy {
+    /// Top-level navigations: patient, with a couple of retries.
+    pub fn navigation() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(10),
+            read_timeout: Duration::from_secs(30),
+            max_retries: 2,
+            backoff_base: Duration::from_millis(250),
+            retry_non_idempotent: false,
+        }
+    }
+
+    /// Subresources (images, scripts, stylesheets).
+    pub fn subresource() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(10),
+            read_timeout: Duration::from_secs(20),
+            max_retries: 1,
+            backoff_base: Duration::from_millis(200),
+            retry_non_idempotent: false,
+        }
+    }
+
+    /// Background work (prefetch, telemetry): fail fast, never retry.
+    pub fn background() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(5),
+            read_timeout: Duration::from_secs(10),
+            max_retries: 0,
+            backoff_base: Duration::from_millis(0),
+            retry_non_idempotent: false,
+        }
+    }
+
+    /// Whether attempt `n` (0-based) may be retried for `method`.
+    pub fn may_retry(&self, attempt: u32, method: Method) -> bool {
+        attempt < self.max_retries && (method.is_idempotent() || self.retry_non_idempotent)
+    }
+
+    /// Jittered exponential backoff delay before retry `attempt` (1-based).
+    pub fn backoff_delay(&self, attempt: u32) -> Duration {
+        let base = self.backoff_base.as_millis() as u64;
+        let exp = base.saturating_mul(1u64 << attempt.min(8));
+        let jitter = (rand::random::<u64>() % (exp / 2 + 1)).min(exp);
+        Duration::from_millis(exp + jitter)
+    }
+}
+
+/// A resource request as it moves through the network stack.
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub url: String,
+    pub method: Method,
+    pub headers: Headers,
+    pub body: Option<Vec<u8>>,
+    pub policy: RequestPolicy,
+    /// Top-level site the request is made on behalf of, for network state
+    /// partitioning (see [`super::partition`]). `None` means unpartitioned.
+    pub partition: Option<String>,
+}
+
+impl Request {
+    /// A plain GET for `url` with no extra headers.
+    pub fn get(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            method: Method::Get,
+            headers: Headers::new(),
+            body: None,
+            policy: RequestPolicy::default(),
+            partition: None,
+        }
+    }
+
+    pub fn with_method(url: impl Into<String>, method: Method) -> Self {
+        Self {
+            url: url.into(),
+            method,
+            headers: Headers::new(),
+            body: None,
+            policy: RequestPolicy::default(),
+            partition: None,
+        }
+    }
+
+    pub fn with_policy(mut self, policy: RequestPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Partition the request under the top-level site of `document_url`.
+    /// Callers pass the URL of the document initiating the load.
+    pub fn partitioned_for(mut self, document_url: &str) -> Self {
+        self.partition = super::partition::top_level_site(document_url);
+        self
+    }
+}
@@ -0,0 +1,35 @@
+//! Response types shared across the network stack.
+
+use super::request::Headers;
+
+/// A complete HTTP response.
+#[derive(Debug, Clone)]
+pub struct Response {
+    /// Final URL after any redirects.
+    pub url: String,
+    pub status: u16,
+    pub headers: Headers,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    pub fn is_redirect(&self) -> bool {
+        matches!(self.status, 301 | 302 | 303 | 307 | 308)
+    }
+
+    /// The `Content-Type` value, without parameters.
+    pub fn content_type(&self) -> Option<&str> {
+        self.headers
+            .get("content-type")
+            .map(|v| v.split(';').next().unwrap_or(v).trim())
+    }
+
+    /// Body decoded as UTF-8, lossily.
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
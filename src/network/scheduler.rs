@@ -0,0 +1,180 @@
+//! Resource prioritisation for page loads.
+//!
+//! Render-blocking resources (the document, CSS, fonts) must not sit behind
+//! a wall of image fetches. [`ResourceScheduler`] hands out dispatch slots
+//! ordered by [`ResourcePriority`], limits concurrency per host and
+//! globally, and lets the renderer bump a queued request when it discovers
+//! late that it is render-blocking.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::oneshot;
+
+/// Scheduling classes, highest first. The renderer assigns them from the
+/// resource's role in the document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ResourcePriority {
+    /// The main document and render-blocking CSS.
+    VeryHigh,
+    /// Fonts and synchronous scripts.
+    High,
+    /// Async scripts, fetch/XHR.
+    Medium,
+    /// In-viewport images.
+    Low,
+    /// Off-screen images, prefetches, speculative loads.
+    VeryLow,
+}
+
+/// Identifies a queued request so it can be reprioritised.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ScheduleId(u64);
+
+struct Waiter {
+    id: ScheduleId,
+    host: String,
+    priority: ResourcePriority,
+    /// Enqueue order, used as the tie-breaker within a priority class.
+    sequence: u64,
+    slot: oneshot::Sender<()>,
+}
+
+struct SchedulerState {
+    queue: Vec<Waiter>,
+    running_per_host: HashMap<String, usize>,
+    running_total: usize,
+    next_id: u64,
+}
+
+/// Limits matching common browser behaviour.
+const MAX_PER_HOST: usize = 6;
+const MAX_TOTAL: usize = 24;
+
+/// Priority-ordered dispatch gate in front of the transport.
+pub struct ResourceScheduler {
+    state: Mutex<SchedulerState>,
+}
+
+/// A held dispatch slot; dropping it releases the slot and dispatches the
+/// next eligible waiter.
+pub struct Permit {
+    scheduler: Arc<ResourceScheduler>,
+    host: String,
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        self.scheduler.release(&self.host);
+    }
+}
+
+impl ResourceScheduler {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            state: Mutex::new(SchedulerState {
+                queue: Vec::new(),
+                running_per_host: HashMap::new(),
+                running_total: 0,
+                next_id: 1,
+            }),
+        })
+    }
+
+    /// Wait for a dispatch slot for `host` at `priority`. The returned id
+    /// can be used with [`reprioritize`](Self::reprioritize) while waiting.
+    pub async fn acquire(
+        self: &Arc<Self>,
+        host: &str,
+        priority: ResourcePriority,
+    ) -> (ScheduleId, Permit) {
+        let (tx, rx) = oneshot::channel();
+        let id = {
+            let mut state = self.state.lock().unwrap();
+            let id = ScheduleId(state.next_id);
+            state.next_id += 1;
+            if Self::can_run(&state, host) {
+                state.running_total += 1;
+                *state.running_per_host.entry(host.to_owned()).or_insert(0) += 1;
+                let _ = tx.send(());
+            } else {
+                let sequence = state.next_id;
+                state.queue.push(Waiter {
+                    id,
+                    host: host.to_owned(),
+                    priority,
+                    sequence,
+                    slot: tx,
+                });
+            }
+            id
+        };
+        // The sender is dropped only if the scheduler itself goes away.
+        let _ = rx.await;
+        (
+            id,
+            Permit {
+                scheduler: Arc::clone(self),
+                host: host.to_owned(),
+            },
+        )
+    }
+
+    /// Raise (or lower) the priority of a queued request. No-op once the
+    /// request has been dispatched.
+    pub fn reprioritize(&self, id: ScheduleId, priority: ResourcePriority) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(waiter) = state.queue.iter_mut().find(|w| w.id == id) {
+            waiter.priority = priority;
+        }
+    }
+
+    /// Number of queued (not yet dispatched) requests, for devtools.
+    pub fn queued(&self) -> usize {
+        self.state.lock().unwrap().queue.len()
+    }
+
+    fn can_run(state: &SchedulerState, host: &str) -> bool {
+        state.running_total < MAX_TOTAL
+            && state.running_per_host.get(host).copied().unwrap_or(0) < MAX_PER_HOST
+    }
+
+    fn release(&self, host: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.running_total = state.running_total.saturating_sub(1);
+        if let Some(count) = state.running_per_host.get_mut(host) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                state.running_per_host.remove(host);
+            }
+        }
+
+        // Dispatch the best eligible waiter: highest priority class, then
+        // FIFO within it, skipping hosts at their limit.
+        let mut best: Option<usize> = None;
+        for (idx, waiter) in state.queue.iter().enumerate() {
+            if !Self::can_run(&state, &waiter.host) {
+                continue;
+            }
+            let better = match best {
+                None => true,
+                Some(current) => {
+                    let current = &state.queue[current];
+                    (waiter.priority, waiter.sequence) < (current.priority, current.sequence)
+                }
+            };
+            if better {
+                best = Some(idx);
+            }
+        }
+        if let Some(idx) = best {
+            let waiter = state.queue.remove(idx);
+            state.running_total += 1;
+            *state
+                .running_per_host
+                .entry(waiter.host.clone())
+                .or_insert(0) += 1;
+            let _ = waiter.slot.send(());
+        }
+    }
+}
@@ -0,0 +1,86 @@
+//! Connection pool introspection.
+//!
+//! Transports report connection lifecycle events into a shared
+//! [`ConnectionPoolStats`]; devtools and `about:network` render snapshots
+//! of it.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::client::HttpVersion;
+
+/// Live state of one pooled connection.
+#[derive(Debug, Clone)]
+pub struct ConnectionInfo {
+    pub origin: String,
+    pub protocol: HttpVersion,
+    pub opened_at: Instant,
+    pub last_used: Instant,
+    pub requests_served: u64,
+    /// Smoothed RTT estimate, where the transport exposes one (QUIC does).
+    pub rtt: Option<Duration>,
+    pub open: bool,
+}
+
+impl ConnectionInfo {
+    /// How long the connection has sat unused.
+    pub fn idle_for(&self) -> Duration {
+        self.last_used.elapsed()
+    }
+}
+
+/// Registry of per-origin connection state, shared by all transports.
+#[derive(Default)]
+pub struct ConnectionPoolStats {
+    connections: Mutex<HashMap<String, ConnectionInfo>>,
+}
+
+impl ConnectionPoolStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_open(&self, origin: &str, protocol: HttpVersion) {
+        let now = Instant::now();
+        self.connections.lock().unwrap().insert(
+            origin.to_owned(),
+            ConnectionInfo {
+                origin: origin.to_owned(),
+                protocol,
+                opened_at: now,
+                last_used: now,
+                requests_served: 0,
+                rtt: None,
+                open: true,
+            },
+        );
+    }
+
+    pub fn record_request(&self, origin: &str) {
+        if let Some(info) = self.connections.lock().unwrap().get_mut(origin) {
+            info.requests_served += 1;
+            info.last_used = Instant::now();
+        }
+    }
+
+    pub fn record_rtt(&self, origin: &str, rtt: Duration) {
+        if let Some(info) = self.connections.lock().unwrap().get_mut(origin) {
+            info.rtt = Some(rtt);
+        }
+    }
+
+    pub fn record_close(&self, origin: &str) {
+        if let Some(info) = self.connections.lock().unwrap().get_mut(origin) {
+            info.open = false;
+        }
+    }
+
+    /// Snapshot of every known connection, open ones first.
+    pub fn snapshot(&self) -> Vec<ConnectionInfo> {
+        let mut infos: Vec<ConnectionInfo> =
+            self.connections.lock().unwrap().values().cloned().collect();
+        infos.sort_by_key(|info| (!info.open, info.origin.clone()));
+        infos
+    }
+}
@@ -0,0 +1,139 @@
+//! Network throttling for testing.
+//!
+//! Devtools can select a [`ThrottleProfile`]; the client then injects
+//! round-trip latency before dispatch and paces body chunks to the
+//! profile's bandwidth, approximating how the page behaves on a slow
+//! connection. The default profile is a no-op.
+
+use std::sync::RwLock;
+use std::time::Duration;
+
+use super::body::{self, BodyStream};
+
+/// A simulated connection class.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThrottleProfile {
+    pub name: &'static str,
+    /// Extra latency added before every request is dispatched.
+    pub latency: Duration,
+    /// Download bandwidth cap in bytes per second; `None` = unlimited.
+    pub download_bps: Option<u64>,
+    /// Simulate total connectivity loss.
+    pub offline: bool,
+}
+
+impl ThrottleProfile {
+    pub fn none() -> Self {
+        Self {
+            name: "No throttling",
+            latency: Duration::ZERO,
+            download_bps: None,
+            offline: false,
+        }
+    }
+
+    /// Roughly devtools' "Slow 3G": 400ms RTT, 50 kB/s down.
+    pub fn slow_3g() -> Self {
+        Self {
+            name: "Slow 3G",
+            latency: Duration::from_millis(400),
+            download_bps: Some(50 * 1024),
+            offline: false,
+        }
+    }
+
+    /// Roughly devtools' "Fast 3G": 150ms RTT, 180 kB/s down.
+    pub fn fast_3g() -> Self {
+        Self {
+            name: "Fast 3G",
+            latency: Duration::from_millis(150),
+            download_bps: Some(180 * 1024),
+            offline: false,
+        }
+    }
+
+    pub fn offline() -> Self {
+        Self {
+            name: "Offline",
+            latency: Duration::ZERO,
+            download_bps: None,
+            offline: true,
+        }
+    }
+
+    pub fn is_noop(&self) -> bool {
+        self.latency.is_zero() && self.download_bps.is_none() && !self.offline
+    }
+}
+
+/// Shared throttle state consulted by the client on every request.
+pub struct Throttler {
+    profile: RwLock<ThrottleProfile>,
+}
+
+impl Throttler {
+    pub fn new() -> Self {
+        Self {
+            profile: RwLock::new(ThrottleProfile::none()),
+        }
+    }
+
+    pub fn set_profile(&self, profile: ThrottleProfile) {
+        *self.profile.write().unwrap() = profile;
+    }
+
+    pub fn profile(&self) -> ThrottleProfile {
+        self.profile.read().unwrap().clone()
+    }
+
+    /// Whether requests should fail as if the network were unplugged.
+    pub fn simulated_offline(&self) -> bool {
+        self.profile.read().unwrap().offline
+    }
+
+    /// Latency to inject before dispatching a request.
+    pub async fn delay_request(&self) {
+        let latency = self.profile.read().unwrap().latency;
+        if !latency.is_zero() {
+            tokio::time::sleep(latency).await;
+        }
+    }
+
+    /// Wrap `upstream` so chunks are delivered no faster than the profile's
+    /// download bandwidth.
+    pub fn pace(&self, upstream: BodyStream) -> BodyStream {
+        let Some(bps) = self.profile.read().unwrap().download_bps else {
+            return upstream;
+        };
+        pace_stream(upstream, bps)
+    }
+}
+
+impl Default for Throttler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn pace_stream(mut upstream: BodyStream, bps: u64) -> BodyStream {
+    let (sink, downstream) = body::channel();
+    tokio::spawn(async move {
+        while let Some(chunk) = upstream.next_chunk().await {
+            match chunk {
+                Ok(chunk) => {
+                    // Sleep for the time this chunk "should" have taken.
+                    let secs = chunk.len() as f64 / bps as f64;
+                    tokio::time::sleep(Duration::from_secs_f64(secs)).await;
+                    if sink.send(chunk).await.is_err() {
+                        return;
+                    }
+                }
+                Err(err) => {
+                    sink.fail(err).await;
+                    return;
+                }
+            }
+        }
+    });
+    downstream
+}
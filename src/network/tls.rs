@@ -0,0 +1,84 @@
+//! Structured TLS failure reporting.
+//!
+//! Raw rustls error strings are useless to users. Certificate validation
+//! failures are classified into [`TlsCertificateError`] and carry a
+//! [`CertificateSummary`] so the UI can show a meaningful interstitial
+//! instead of an opaque message.
+
+use std::fmt;
+
+/// Why certificate validation failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TlsCertificateError {
+    Expired,
+    NotYetValid,
+    HostnameMismatch,
+    UntrustedIssuer,
+    SelfSigned,
+    Revoked,
+    /// Anything rustls reports that we don't classify.
+    Other(String),
+}
+
+impl fmt::Display for TlsCertificateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Expired => f.write_str("the certificate has expired"),
+            Self::NotYetValid => f.write_str("the certificate is not yet valid"),
+            Self::HostnameMismatch => {
+                f.write_str("the certificate does not match the site's hostname")
+            }
+            Self::UntrustedIssuer => f.write_str("the certificate issuer is not trusted"),
+            Self::SelfSigned => f.write_str("the certificate is self-signed"),
+            Self::Revoked => f.write_str("the certificate has been revoked"),
+            Self::Other(detail) => write!(f, "certificate validation failed: {detail}"),
+        }
+    }
+}
+
+/// Human-readable details of the certificate that failed validation, for
+/// the interstitial's "advanced" section.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CertificateSummary {
+    pub subject: String,
+    pub issuer: String,
+    pub not_before: String,
+    pub not_after: String,
+    /// Hex-encoded SHA-256 of the SubjectPublicKeyInfo.
+    pub spki_sha256: String,
+}
+
+/// A classified TLS validation failure for one host.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TlsFailure {
+    pub host: String,
+    pub error: TlsCertificateError,
+    pub certificate: Option<CertificateSummary>,
+}
+
+impl fmt::Display for TlsFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.host, self.error)
+    }
+}
+
+/// Map a rustls error for `host` into a classified failure where possible.
+pub fn classify(host: &str, error: &rustls::Error) -> Option<TlsFailure> {
+    use rustls::CertificateError::*;
+    let cert_error = match error {
+        rustls::Error::InvalidCertificate(reason) => match reason {
+            Expired => TlsCertificateError::Expired,
+            NotValidYet => TlsCertificateError::NotYetValid,
+            NotValidForName => TlsCertificateError::HostnameMismatch,
+            UnknownIssuer => TlsCertificateError::UntrustedIssuer,
+            Revoked => TlsCertificateError::Revoked,
+            other => TlsCertificateError::Other(format!("{other:?}")),
+        },
+        _ => return None,
+    };
+    Some(TlsFailure {
+        host: host.to_owned(),
+        error: cert_error,
+        certificate: None,
+    })
+}
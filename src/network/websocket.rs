@@ -0,0 +1,345 @@
+//! RFC 6455 WebSocket client.
+//!
+//! The handshake runs over the same TLS/TCP path as HTTP/1.1; frames are
+//! encoded and decoded here directly. Client frames are always masked, as
+//! the RFC requires. Connections are exposed as a small async API
+//! ([`WebSocketConnection`]) that the `WebSocket` JS binding wraps.
+
+use std::sync::Arc;
+
+use base64::Engine as _;
+use sha1::{Digest, Sha1};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+
+use super::http3::split_host_port;
+use super::NetworkError;
+
+/// GUID from RFC 6455 §1.3 used to derive `Sec-WebSocket-Accept`.
+const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// A message delivered to or sent by the application.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WsMessage {
+    Text(String),
+    Binary(Vec<u8>),
+    Ping(Vec<u8>),
+    Pong(Vec<u8>),
+    Close { code: Option<u16>, reason: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Opcode {
+    Continuation = 0x0,
+    Text = 0x1,
+    Binary = 0x2,
+    Close = 0x8,
+    Ping = 0x9,
+    Pong = 0xA,
+}
+
+impl Opcode {
+    fn from_u8(v: u8) -> Result<Self, NetworkError> {
+        Ok(match v {
+            0x0 => Opcode::Continuation,
+            0x1 => Opcode::Text,
+            0x2 => Opcode::Binary,
+            0x8 => Opcode::Close,
+            0x9 => Opcode::Ping,
+            0xA => Opcode::Pong,
+            other => {
+                return Err(NetworkError::Protocol(format!(
+                    "reserved WebSocket opcode {other:#x}"
+                )))
+            }
+        })
+    }
+
+    fn is_control(self) -> bool {
+        matches!(self, Opcode::Close | Opcode::Ping | Opcode::Pong)
+    }
+}
+
+enum Transport {
+    Tls(tokio_rustls::client::TlsStream<TcpStream>),
+    Plain(TcpStream),
+}
+
+impl Transport {
+    fn split_io(
+        self,
+    ) -> (
+        Box<dyn AsyncRead + Unpin + Send>,
+        Box<dyn AsyncWrite + Unpin + Send>,
+    ) {
+        match self {
+            Transport::Tls(stream) => {
+                let (r, w) = tokio::io::split(stream);
+                (Box::new(r), Box::new(w))
+            }
+            Transport::Plain(stream) => {
+                let (r, w) = stream.into_split();
+                (Box::new(r), Box::new(w))
+            }
+        }
+    }
+}
+
+/// An open WebSocket connection.
+pub struct WebSocketConnection {
+    reader: BufReader<Box<dyn AsyncRead + Unpin + Send>>,
+    writer: Box<dyn AsyncWrite + Unpin + Send>,
+    /// Accumulates fragmented data frames until a FIN frame arrives.
+    fragments: Option<(Opcode, Vec<u8>)>,
+    closed: bool,
+}
+
+impl WebSocketConnection {
+    /// Open a connection to a `ws://` or `wss://` URL, performing the
+    /// opening handshake with the given subprotocols (if any).
+    pub async fn connect(url: &str, protocols: &[&str]) -> Result<Self, NetworkError> {
+        let (secure, rest) = if let Some(rest) = url.strip_prefix("wss://") {
+            (true, rest)
+        } else if let Some(rest) = url.strip_prefix("ws://") {
+            (false, rest)
+        } else {
+            return Err(NetworkError::InvalidUrl(url.to_owned()));
+        };
+        let (authority, path) = match rest.find('/') {
+            Some(idx) => (&rest[..idx], &rest[idx..]),
+            None => (rest, "/"),
+        };
+        let scheme = if secure { "https://" } else { "http://" };
+        let (host, port) = split_host_port(&format!("{scheme}{authority}"))?;
+
+        let tcp = TcpStream::connect((host.as_str(), port))
+            .await
+            .map_err(|e| NetworkError::ConnectionFailed(e.to_string()))?;
+        let transport = if secure {
+            let mut roots = rustls::RootCertStore::empty();
+            roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+            let config = rustls::ClientConfig::builder()
+                .with_root_certificates(roots)
+                .with_no_client_auth();
+            let server_name = rustls::pki_types::ServerName::try_from(host.clone())
+                .map_err(|_| NetworkError::InvalidUrl(url.to_owned()))?;
+            let connector = tokio_rustls::TlsConnector::from(Arc::new(config));
+            Transport::Tls(
+                connector
+                    .connect(server_name, tcp)
+                    .await
+                    .map_err(|e| NetworkError::Tls(e.to_string()))?,
+            )
+        } else {
+            Transport::Plain(tcp)
+        };
+
+        let key = base64::engine::general_purpose::STANDARD.encode(rand::random::<[u8; 16]>());
+        let mut handshake = format!(
+            "GET {path} HTTP/1.1\r\n\
+             host: {host}\r\n\
+             upgrade: websocket\r\n\
+             connection: Upgrade\r\n\
+             sec-websocket-key: {key}\r\n\
+             sec-websocket-version: 13\r\n"
+        );
+        if !protocols.is_empty() {
+            handshake.push_str(&format!("sec-websocket-protocol: {}\r\n", protocols.join(", ")));
+        }
+        handshake.push_str("\r\n");
+
+        let (reader, mut writer) = transport.split_io();
+        let mut reader = BufReader::new(reader);
+        writer.write_all(handshake.as_bytes()).await?;
+
+        Self::verify_handshake(&mut reader, &key).await?;
+        Ok(Self {
+            reader,
+            writer,
+            fragments: None,
+            closed: false,
+        })
+    }
+
+    async fn verify_handshake(
+        reader: &mut BufReader<Box<dyn AsyncRead + Unpin + Send>>,
+        key: &str,
+    ) -> Result<(), NetworkError> {
+        let mut head = Vec::new();
+        // Read byte-wise until the blank line; handshake responses are tiny.
+        let mut last4 = [0u8; 4];
+        loop {
+            let byte = reader.read_u8().await?;
+            head.push(byte);
+            last4.rotate_left(1);
+            last4[3] = byte;
+            if last4 == *b"\r\n\r\n" {
+                break;
+            }
+            if head.len() > 16 * 1024 {
+                return Err(NetworkError::Protocol("oversized handshake response".into()));
+            }
+        }
+        let head = String::from_utf8_lossy(&head);
+        let mut lines = head.lines();
+        let status = lines.next().unwrap_or_default();
+        if !status.contains(" 101 ") {
+            return Err(NetworkError::Protocol(format!(
+                "WebSocket upgrade refused: {status}"
+            )));
+        }
+
+        let expected = base64::engine::general_purpose::STANDARD
+            .encode(Sha1::digest(format!("{key}{WEBSOCKET_GUID}").as_bytes()));
+        let accept = lines
+            .filter_map(|l| l.split_once(':'))
+            .find(|(n, _)| n.eq_ignore_ascii_case("sec-websocket-accept"))
+            .map(|(_, v)| v.trim());
+        if accept != Some(expected.as_str()) {
+            return Err(NetworkError::Protocol(
+                "Sec-WebSocket-Accept mismatch".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Send a message, masking it as required for client frames.
+    pub async fn send(&mut self, message: WsMessage) -> Result<(), NetworkError> {
+        let (opcode, payload) = match message {
+            WsMessage::Text(s) => (Opcode::Text, s.into_bytes()),
+            WsMessage::Binary(b) => (Opcode::Binary, b),
+            WsMessage::Ping(b) => (Opcode::Ping, b),
+            WsMessage::Pong(b) => (Opcode::Pong, b),
+            WsMessage::Close { code, reason } => {
+                let mut payload = Vec::new();
+                if let Some(code) = code {
+                    payload.extend_from_slice(&code.to_be_bytes());
+                    payload.extend_from_slice(reason.as_bytes());
+                }
+                self.closed = true;
+                (Opcode::Close, payload)
+            }
+        };
+        self.write_frame(opcode, &payload).await
+    }
+
+    /// Receive the next complete message, transparently replying to pings
+    /// and reassembling fragmented frames.
+    pub async fn recv(&mut self) -> Result<WsMessage, NetworkError> {
+        loop {
+            let (fin, opcode, payload) = self.read_frame().await?;
+            if opcode.is_control() {
+                if !fin {
+                    return Err(NetworkError::Protocol("fragmented control frame".into()));
+                }
+                match opcode {
+                    Opcode::Ping => {
+                        self.write_frame(Opcode::Pong, &payload).await?;
+                        return Ok(WsMessage::Ping(payload));
+                    }
+                    Opcode::Pong => return Ok(WsMessage::Pong(payload)),
+                    Opcode::Close => {
+                        let code = (payload.len() >= 2)
+                            .then(|| u16::from_be_bytes([payload[0], payload[1]]));
+                        let reason =
+                            String::from_utf8_lossy(payload.get(2..).unwrap_or(&[])).into_owned();
+                        if !self.closed {
+                            self.closed = true;
+                            let _ = self.write_frame(Opcode::Close, &payload).await;
+                        }
+                        return Ok(WsMessage::Close { code, reason });
+                    }
+                    _ => unreachable!(),
+                }
+            }
+
+            match (&mut self.fragments, opcode, fin) {
+                (None, Opcode::Continuation, _) => {
+                    return Err(NetworkError::Protocol(
+                        "continuation frame without a start".into(),
+                    ))
+                }
+                (None, opcode, true) => return Ok(Self::finish(opcode, payload)?),
+                (None, opcode, false) => self.fragments = Some((opcode, payload)),
+                (Some((_, buffer)), Opcode::Continuation, false) => {
+                    buffer.extend_from_slice(&payload);
+                }
+                (Some(_), Opcode::Continuation, true) => {
+                    let (opcode, mut buffer) = self.fragments.take().unwrap();
+                    buffer.extend_from_slice(&payload);
+                    return Ok(Self::finish(opcode, buffer)?);
+                }
+                (Some(_), _, _) => {
+                    return Err(NetworkError::Protocol(
+                        "new data frame during fragmented message".into(),
+                    ))
+                }
+            }
+        }
+    }
+
+    fn finish(opcode: Opcode, payload: Vec<u8>) -> Result<WsMessage, NetworkError> {
+        Ok(match opcode {
+            Opcode::Text => WsMessage::Text(
+                String::from_utf8(payload)
+                    .map_err(|_| NetworkError::Protocol("invalid UTF-8 in text frame".into()))?,
+            ),
+            Opcode::Binary => WsMessage::Binary(payload),
+            _ => unreachable!("control frames handled by caller"),
+        })
+    }
+
+    async fn write_frame(&mut self, opcode: Opcode, payload: &[u8]) -> Result<(), NetworkError> {
+        let mut frame = Vec::with_capacity(payload.len() + 14);
+        frame.push(0x80 | opcode as u8);
+        const MASKED: u8 = 0x80;
+        match payload.len() {
+            len @ 0..=125 => frame.push(MASKED | len as u8),
+            len @ 126..=65535 => {
+                frame.push(MASKED | 126);
+                frame.extend_from_slice(&(len as u16).to_be_bytes());
+            }
+            len => {
+                frame.push(MASKED | 127);
+                frame.extend_from_slice(&(len as u64).to_be_bytes());
+            }
+        }
+        let mask: [u8; 4] = rand::random();
+        frame.extend_from_slice(&mask);
+        frame.extend(
+            payload
+                .iter()
+                .enumerate()
+                .map(|(i, byte)| byte ^ mask[i % 4]),
+        );
+        self.writer.write_all(&frame).await?;
+        self.writer.flush().await?;
+        Ok(())
+    }
+
+    async fn read_frame(&mut self) -> Result<(bool, Opcode, Vec<u8>), NetworkError> {
+        let first = self.reader.read_u8().await?;
+        let fin = first & 0x80 != 0;
+        if first & 0x70 != 0 {
+            return Err(NetworkError::Protocol("unexpected RSV bits".into()));
+        }
+        let opcode = Opcode::from_u8(first & 0x0F)?;
+
+        let second = self.reader.read_u8().await?;
+        if second & 0x80 != 0 {
+            return Err(NetworkError::Protocol("masked frame from server".into()));
+        }
+        let len = match second & 0x7F {
+            126 => self.reader.read_u16().await? as u64,
+            127 => self.reader.read_u64().await?,
+            len => len as u64,
+        };
+        if opcode.is_control() && len > 125 {
+            return Err(NetworkError::Protocol("oversized control frame".into()));
+        }
+
+        let mut payload = vec![0u8; len as usize];
+        self.reader.read_exact(&mut payload).await?;
+        Ok((fin, opcode, payload))
+    }
+}
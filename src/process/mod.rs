@@ -0,0 +1,17 @@
+//! Process model.
+//!
+//! Binix follows the usual split: one browser process, sandboxed renderer
+//! processes, and a single network process that owns sockets, cookies and
+//! the HTTP cache. Renderers never touch the network directly; they broker
+//! every fetch through [`network_broker`].
+
+pub mod network_broker;
+
+/// The kinds of processes the browser spawns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessKind {
+    Browser,
+    Renderer,
+    Network,
+    Gpu,
+}
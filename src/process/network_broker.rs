@@ -0,0 +1,235 @@
+//! Brokered resource loading between renderer and network processes.
+//!
+//! The network process runs a [`NetworkProcessHost`] around the one real
+//! [`NetworkStack`]; renderers hold a [`RemoteNetworkStack`] that satisfies
+//! fetches by exchanging serialized messages over an [`IpcTransport`]. The
+//! transport is pluggable: production uses a socketpair per renderer, tests
+//! and the single-process `--no-sandbox` mode use an in-process channel.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, oneshot};
+
+use crate::network::{Headers, Method, NetworkError, NetworkStack, Request, Response};
+
+/// A fetch crossing the renderer → network process boundary.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IpcRequest {
+    pub id: u64,
+    pub url: String,
+    pub method: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<Vec<u8>>,
+}
+
+/// The answer travelling back.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IpcResponse {
+    pub id: u64,
+    pub result: Result<IpcResponseBody, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IpcResponseBody {
+    pub url: String,
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+/// Bidirectional message transport between two processes. Implementations
+/// must preserve message boundaries and ordering.
+pub trait IpcTransport: Send + Sync + 'static {
+    fn send(&self, message: Vec<u8>);
+    /// Receive the next message, or `None` once the peer is gone.
+    fn try_recv(&self) -> Option<Vec<u8>>;
+}
+
+/// In-process transport used by tests and single-process mode.
+pub struct ChannelTransport {
+    tx: mpsc::UnboundedSender<Vec<u8>>,
+    rx: Mutex<mpsc::UnboundedReceiver<Vec<u8>>>,
+}
+
+impl ChannelTransport {
+    /// A connected pair of endpoints.
+    pub fn pair() -> (Self, Self) {
+        let (a_tx, a_rx) = mpsc::unbounded_channel();
+        let (b_tx, b_rx) = mpsc::unbounded_channel();
+        (
+            Self {
+                tx: a_tx,
+                rx: Mutex::new(b_rx),
+            },
+            Self {
+                tx: b_tx,
+                rx: Mutex::new(a_rx),
+            },
+        )
+    }
+}
+
+impl IpcTransport for ChannelTransport {
+    fn send(&self, message: Vec<u8>) {
+        let _ = self.tx.send(message);
+    }
+
+    fn try_recv(&self) -> Option<Vec<u8>> {
+        self.rx.lock().unwrap().try_recv().ok()
+    }
+}
+
+/// Network-process side: owns the real stack and answers broker requests.
+pub struct NetworkProcessHost {
+    stack: Arc<NetworkStack>,
+    transport: Arc<dyn IpcTransport>,
+}
+
+impl NetworkProcessHost {
+    pub fn new(stack: Arc<NetworkStack>, transport: Arc<dyn IpcTransport>) -> Self {
+        Self { stack, transport }
+    }
+
+    /// Service loop: decode requests, dispatch them on the stack, send the
+    /// answers back. Runs until the transport closes.
+    pub async fn run(self) {
+        loop {
+            let Some(raw) = self.transport.try_recv() else {
+                tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+                continue;
+            };
+            let Ok(ipc_request) = bincode::deserialize::<IpcRequest>(&raw) else {
+                continue;
+            };
+            let stack = Arc::clone(&self.stack);
+            let transport = Arc::clone(&self.transport);
+            tokio::spawn(async move {
+                let id = ipc_request.id;
+                let result = stack
+                    .fetch(decode_request(ipc_request))
+                    .await
+                    .map(encode_response)
+                    .map_err(|e| e.to_string());
+                if let Ok(message) = bincode::serialize(&IpcResponse { id, result }) {
+                    transport.send(message);
+                }
+            });
+        }
+    }
+}
+
+/// Renderer-side proxy with the same fetch surface as [`NetworkStack`].
+pub struct RemoteNetworkStack {
+    transport: Arc<dyn IpcTransport>,
+    next_id: AtomicU64,
+    pending: Arc<Mutex<HashMap<u64, oneshot::Sender<IpcResponse>>>>,
+}
+
+impl RemoteNetworkStack {
+    pub fn new(transport: Arc<dyn IpcTransport>) -> Arc<Self> {
+        let stack = Arc::new(Self {
+            transport,
+            next_id: AtomicU64::new(1),
+            pending: Arc::new(Mutex::new(HashMap::new())),
+        });
+        stack.spawn_reader();
+        stack
+    }
+
+    /// Fetch a resource through the network process.
+    pub async fn fetch(&self, request: Request) -> Result<Response, NetworkError> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().unwrap().insert(id, tx);
+
+        let message = bincode::serialize(&encode_request(id, &request))
+            .map_err(|e| NetworkError::Protocol(format!("IPC encode: {e}")))?;
+        self.transport.send(message);
+
+        let response = rx
+            .await
+            .map_err(|_| NetworkError::ConnectionFailed("network process exited".into()))?;
+        match response.result {
+            Ok(body) => Ok(decode_response(body)),
+            Err(message) => Err(NetworkError::Protocol(message)),
+        }
+    }
+
+    fn spawn_reader(self: &Arc<Self>) {
+        let this = Arc::clone(self);
+        tokio::spawn(async move {
+            loop {
+                let Some(raw) = this.transport.try_recv() else {
+                    tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+                    continue;
+                };
+                if let Ok(response) = bincode::deserialize::<IpcResponse>(&raw) {
+                    if let Some(waiter) = this.pending.lock().unwrap().remove(&response.id) {
+                        let _ = waiter.send(response);
+                    }
+                }
+            }
+        });
+    }
+}
+
+fn encode_request(id: u64, request: &Request) -> IpcRequest {
+    IpcRequest {
+        id,
+        url: request.url.clone(),
+        method: request.method.as_str().to_owned(),
+        headers: request
+            .headers
+            .iter()
+            .map(|(n, v)| (n.to_owned(), v.to_owned()))
+            .collect(),
+        body: request.body.clone(),
+    }
+}
+
+fn decode_request(ipc: IpcRequest) -> Request {
+    let method = match ipc.method.as_str() {
+        "HEAD" => Method::Head,
+        "POST" => Method::Post,
+        "PUT" => Method::Put,
+        "DELETE" => Method::Delete,
+        "OPTIONS" => Method::Options,
+        "PATCH" => Method::Patch,
+        _ => Method::Get,
+    };
+    let mut request = Request::with_method(ipc.url, method);
+    for (name, value) in &ipc.headers {
+        request.headers.append(name, value);
+    }
+    request.body = ipc.body;
+    request
+}
+
+fn encode_response(response: Response) -> IpcResponseBody {
+    IpcResponseBody {
+        url: response.url,
+        status: response.status,
+        headers: response
+            .headers
+            .iter()
+            .map(|(n, v)| (n.to_owned(), v.to_owned()))
+            .collect(),
+        body: response.body,
+    }
+}
+
+fn decode_response(body: IpcResponseBody) -> Response {
+    let mut headers = Headers::new();
+    for (name, value) in &body.headers {
+        headers.append(name, value);
+    }
+    Response {
+        url: body.url,
+        status: body.status,
+        headers,
+        body: body.body,
+    }
+}
@@ -0,0 +1,472 @@
+//! CSS animations: `@keyframes` timelines driving per-frame style
+//! overrides.
+//!
+//! The [`AnimationTimeline`] owns the page's `@keyframes` rules and the
+//! animations currently running on elements. Each frame, [`tick`] samples
+//! every animation at its eased progress and returns declarations that
+//! the style engine layers over the cascade result for the frame.
+//! Numeric values with matching units interpolate; everything else flips
+//! discretely at the midpoint. Devtools can pause and resume individual
+//! animations through the play-state methods.
+//!
+//! [`tick`]: AnimationTimeline::tick
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use super::css::{self, Declaration};
+use super::dom::NodeId;
+use super::style::ComputedStyle;
+
+/// One stop of a keyframes rule.
+#[derive(Debug, Clone)]
+pub struct Keyframe {
+    /// Progress offset in `[0, 1]`.
+    pub offset: f32,
+    pub declarations: Vec<Declaration>,
+}
+
+/// A parsed `@keyframes` rule, stops sorted by offset.
+#[derive(Debug, Clone)]
+pub struct KeyframesRule {
+    pub name: String,
+    pub keyframes: Vec<Keyframe>,
+}
+
+impl KeyframesRule {
+    fn from_blocks(name: String, blocks: Vec<(String, Vec<Declaration>)>) -> Self {
+        let mut keyframes = Vec::new();
+        for (selector, declarations) in blocks {
+            // A stop list like `0%, 50%` repeats the block at each offset.
+            for part in selector.split(',') {
+                let offset = match part.trim() {
+                    "from" => Some(0.0),
+                    "to" => Some(1.0),
+                    percent => percent
+                        .strip_suffix('%')
+                        .and_then(|n| n.trim().parse::<f32>().ok())
+                        .map(|n| n / 100.0),
+                };
+                if let Some(offset) = offset.filter(|o| (0.0..=1.0).contains(o)) {
+                    keyframes.push(Keyframe {
+                        offset,
+                        declarations: declarations.clone(),
+                    });
+                }
+            }
+        }
+        keyframes.sort_by(|a, b| a.offset.total_cmp(&b.offset));
+        Self { name, keyframes }
+    }
+
+    /// The declarations at progress `t`, interpolating between the
+    /// surrounding keyframes.
+    fn sample(&self, t: f32) -> Vec<Declaration> {
+        let Some(first) = self.keyframes.first() else {
+            return Vec::new();
+        };
+        let prev = self
+            .keyframes
+            .iter()
+            .rev()
+            .find(|k| k.offset <= t)
+            .unwrap_or(first);
+        let next = self
+            .keyframes
+            .iter()
+            .find(|k| k.offset >= t)
+            .unwrap_or_else(|| self.keyframes.last().unwrap_or(first));
+        let span = next.offset - prev.offset;
+        let local = if span <= f32::EPSILON {
+            0.0
+        } else {
+            (t - prev.offset) / span
+        };
+        let mut out: Vec<Declaration> = Vec::new();
+        for declaration in next.declarations.iter().chain(&prev.declarations) {
+            if out.iter().any(|d| d.name == declaration.name) {
+                continue;
+            }
+            let from = prev
+                .declarations
+                .iter()
+                .find(|d| d.name == declaration.name)
+                .map_or(declaration.value.as_str(), |d| d.value.as_str());
+            let to = next
+                .declarations
+                .iter()
+                .find(|d| d.name == declaration.name)
+                .map_or(from, |d| d.value.as_str());
+            out.push(Declaration {
+                name: declaration.name.clone(),
+                value: interpolate(from, to, local),
+                important: false,
+            });
+        }
+        out
+    }
+}
+
+/// `animation-direction` values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Direction {
+    #[default]
+    Normal,
+    Reverse,
+    Alternate,
+    AlternateReverse,
+}
+
+/// `animation-fill-mode` values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FillMode {
+    #[default]
+    None,
+    Forwards,
+    Backwards,
+    Both,
+}
+
+/// `animation-timing-function` values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimingFunction {
+    Linear,
+    Ease,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    CubicBezier(f32, f32, f32, f32),
+}
+
+impl TimingFunction {
+    /// Map linear progress through the easing curve.
+    pub fn evaluate(&self, t: f32) -> f32 {
+        let (x1, y1, x2, y2) = match *self {
+            Self::Linear => return t,
+            Self::Ease => (0.25, 0.1, 0.25, 1.0),
+            Self::EaseIn => (0.42, 0.0, 1.0, 1.0),
+            Self::EaseOut => (0.0, 0.0, 0.58, 1.0),
+            Self::EaseInOut => (0.42, 0.0, 0.58, 1.0),
+            Self::CubicBezier(x1, y1, x2, y2) => (x1, y1, x2, y2),
+        };
+        // Solve the curve's parameter for x = t by bisection, then read y.
+        let bezier = |p1: f32, p2: f32, s: f32| {
+            3.0 * p1 * s * (1.0 - s) * (1.0 - s) + 3.0 * p2 * s * s * (1.0 - s) + s * s * s
+        };
+        let (mut lo, mut hi) = (0.0f32, 1.0f32);
+        for _ in 0..16 {
+            let mid = (lo + hi) / 2.0;
+            if bezier(x1, x2, mid) < t {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        bezier(y1, y2, (lo + hi) / 2.0)
+    }
+}
+
+/// The `animation-*` longhands of one animation on an element.
+#[derive(Debug, Clone)]
+pub struct AnimationSpec {
+    pub name: String,
+    pub duration: Duration,
+    pub delay: Duration,
+    /// `f32::INFINITY` for `infinite`.
+    pub iterations: f32,
+    pub direction: Direction,
+    pub timing: TimingFunction,
+    pub fill: FillMode,
+}
+
+impl AnimationSpec {
+    /// Read the `animation-*` longhands of a computed style. `None` when
+    /// no animation applies (`animation-name: none` or zero duration).
+    pub fn from_style(style: &ComputedStyle) -> Option<Self> {
+        let name = style.get("animation-name")?;
+        if name == "none" {
+            return None;
+        }
+        let duration = parse_time(style.get("animation-duration")?)?;
+        if duration.is_zero() {
+            return None;
+        }
+        let delay = style
+            .get("animation-delay")
+            .and_then(|v| parse_time(v))
+            .unwrap_or_default();
+        let iterations = match style.get("animation-iteration-count").map(String::as_str) {
+            Some("infinite") => f32::INFINITY,
+            Some(count) => count.parse().unwrap_or(1.0),
+            None => 1.0,
+        };
+        let direction = match style.get("animation-direction").map(String::as_str) {
+            Some("reverse") => Direction::Reverse,
+            Some("alternate") => Direction::Alternate,
+            Some("alternate-reverse") => Direction::AlternateReverse,
+            _ => Direction::Normal,
+        };
+        let timing = match style.get("animation-timing-function").map(String::as_str) {
+            Some("linear") => TimingFunction::Linear,
+            Some("ease-in") => TimingFunction::EaseIn,
+            Some("ease-out") => TimingFunction::EaseOut,
+            Some("ease-in-out") => TimingFunction::EaseInOut,
+            Some(value) => parse_bezier(value).unwrap_or(TimingFunction::Ease),
+            None => TimingFunction::Ease,
+        };
+        let fill = match style.get("animation-fill-mode").map(String::as_str) {
+            Some("forwards") => FillMode::Forwards,
+            Some("backwards") => FillMode::Backwards,
+            Some("both") => FillMode::Both,
+            _ => FillMode::None,
+        };
+        Some(Self {
+            name: name.clone(),
+            duration,
+            delay,
+            iterations,
+            direction,
+            timing,
+            fill,
+        })
+    }
+}
+
+struct RunningAnimation {
+    node: NodeId,
+    spec: AnimationSpec,
+    start: Instant,
+    /// When paused (devtools or `animation-play-state`), the pause time;
+    /// resuming shifts `start` so progress continues where it stopped.
+    paused_at: Option<Instant>,
+}
+
+/// The animations of one page and the clock that drives them.
+#[derive(Default)]
+pub struct AnimationTimeline {
+    rules: HashMap<String, KeyframesRule>,
+    running: Vec<RunningAnimation>,
+}
+
+impl AnimationTimeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Collect the `@keyframes` rules of a stylesheet source.
+    pub fn add_stylesheet_source(&mut self, source: &str) {
+        for (name, blocks) in css::collect_keyframes(source) {
+            let rule = KeyframesRule::from_blocks(name.clone(), blocks);
+            self.rules.insert(name, rule);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.rules.clear();
+        self.running.clear();
+    }
+
+    /// Start or stop animations on `node` to match its computed style.
+    /// A running animation of the same name keeps its clock; a paused
+    /// `animation-play-state` pauses it.
+    pub fn sync(&mut self, node: NodeId, style: &ComputedStyle, now: Instant) {
+        let spec = AnimationSpec::from_style(style);
+        let existing = self
+            .running
+            .iter_mut()
+            .find(|animation| animation.node == node);
+        match (existing, spec) {
+            (Some(animation), Some(spec)) if animation.spec.name == spec.name => {
+                animation.spec = spec;
+            }
+            (Some(animation), Some(spec)) => {
+                animation.spec = spec;
+                animation.start = now;
+                animation.paused_at = None;
+            }
+            (Some(_), None) => self.running.retain(|animation| animation.node != node),
+            (None, Some(spec)) => self.running.push(RunningAnimation {
+                node,
+                spec,
+                start: now,
+                paused_at: None,
+            }),
+            (None, None) => {}
+        }
+        if style.get("animation-play-state").map(String::as_str) == Some("paused") {
+            self.pause(node, now);
+        }
+    }
+
+    /// Sample every animation at `now`: declarations to layer over each
+    /// animated element's cascade result this frame. Finished animations
+    /// without a forwards fill are dropped.
+    pub fn tick(&mut self, now: Instant) -> HashMap<NodeId, Vec<Declaration>> {
+        let mut overrides = HashMap::new();
+        let rules = &self.rules;
+        self.running.retain(|animation| {
+            let Some(rule) = rules.get(&animation.spec.name) else {
+                return false;
+            };
+            let clock = animation.paused_at.unwrap_or(now);
+            let elapsed = clock.saturating_duration_since(animation.start);
+            match progress_of(&animation.spec, elapsed) {
+                Progress::Pending => {
+                    if matches!(animation.spec.fill, FillMode::Backwards | FillMode::Both) {
+                        let t = animation.spec.timing.evaluate(0.0);
+                        overrides.insert(animation.node, rule.sample(t));
+                    }
+                    true
+                }
+                Progress::At(t) => {
+                    let eased = animation.spec.timing.evaluate(t);
+                    overrides.insert(animation.node, rule.sample(eased));
+                    true
+                }
+                Progress::Finished(t) => {
+                    if matches!(animation.spec.fill, FillMode::Forwards | FillMode::Both) {
+                        let eased = animation.spec.timing.evaluate(t);
+                        overrides.insert(animation.node, rule.sample(eased));
+                        true
+                    } else {
+                        false
+                    }
+                }
+            }
+        });
+        overrides
+    }
+
+    /// Whether a frame tick is needed (any unpaused animation running).
+    pub fn needs_frame(&self) -> bool {
+        self.running
+            .iter()
+            .any(|animation| animation.paused_at.is_none())
+    }
+
+    /// Pause the animation on `node` (devtools play-state control).
+    pub fn pause(&mut self, node: NodeId, now: Instant) {
+        for animation in &mut self.running {
+            if animation.node == node && animation.paused_at.is_none() {
+                animation.paused_at = Some(now);
+            }
+        }
+    }
+
+    /// Resume a paused animation; its clock continues where it stopped.
+    pub fn resume(&mut self, node: NodeId, now: Instant) {
+        for animation in &mut self.running {
+            if animation.node == node {
+                if let Some(paused_at) = animation.paused_at.take() {
+                    animation.start += now.saturating_duration_since(paused_at);
+                }
+            }
+        }
+    }
+
+    /// Running animations for the devtools animation panel:
+    /// (element, name, paused).
+    pub fn inspect(&self) -> Vec<(NodeId, &str, bool)> {
+        self.running
+            .iter()
+            .map(|animation| {
+                (
+                    animation.node,
+                    animation.spec.name.as_str(),
+                    animation.paused_at.is_some(),
+                )
+            })
+            .collect()
+    }
+}
+
+enum Progress {
+    /// Still in the delay.
+    Pending,
+    At(f32),
+    /// Past the last iteration; carries the final directed progress.
+    Finished(f32),
+}
+
+/// The directed progress of `spec` after `elapsed`, applying delay,
+/// iteration count, and direction.
+fn progress_of(spec: &AnimationSpec, elapsed: Duration) -> Progress {
+    let Some(active) = elapsed.checked_sub(spec.delay) else {
+        return Progress::Pending;
+    };
+    let total = active.as_secs_f32() / spec.duration.as_secs_f32();
+    let finished = total >= spec.iterations;
+    let iteration = if finished {
+        (spec.iterations.ceil() - 1.0).max(0.0)
+    } else {
+        total.floor()
+    };
+    let fraction = if finished {
+        (spec.iterations - iteration).clamp(0.0, 1.0)
+    } else {
+        total - iteration
+    };
+    let reversed = match spec.direction {
+        Direction::Normal => false,
+        Direction::Reverse => true,
+        Direction::Alternate => iteration as u64 % 2 == 1,
+        Direction::AlternateReverse => iteration as u64 % 2 == 0,
+    };
+    let directed = if reversed { 1.0 - fraction } else { fraction };
+    if finished {
+        Progress::Finished(directed)
+    } else {
+        Progress::At(directed)
+    }
+}
+
+/// Interpolate two declaration values: numeric values with the same unit
+/// lerp; anything else flips discretely at the midpoint.
+fn interpolate(from: &str, to: &str, t: f32) -> String {
+    if let (Some((a, unit_a)), Some((b, unit_b))) = (split_number(from), split_number(to)) {
+        if unit_a == unit_b {
+            let value = a + (b - a) * t;
+            return format!("{value}{unit_a}");
+        }
+    }
+    if t < 0.5 {
+        from.to_owned()
+    } else {
+        to.to_owned()
+    }
+}
+
+/// Split a value into its leading number and unit suffix.
+fn split_number(value: &str) -> Option<(f32, &str)> {
+    let end = value
+        .char_indices()
+        .find(|&(i, c)| !(c.is_ascii_digit() || c == '.' || c == '-' || c == '+' && i == 0))
+        .map_or(value.len(), |(i, _)| i);
+    let number = value[..end].parse().ok()?;
+    Some((number, &value[end..]))
+}
+
+/// Parse `1.5s` / `300ms`.
+fn parse_time(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Some(ms) = value.strip_suffix("ms") {
+        return Some(Duration::from_secs_f64(ms.trim().parse::<f64>().ok()? / 1000.0));
+    }
+    let secs = value.strip_suffix('s')?.trim().parse::<f64>().ok()?;
+    Some(Duration::from_secs_f64(secs))
+}
+
+/// Parse `cubic-bezier(x1, y1, x2, y2)`.
+fn parse_bezier(value: &str) -> Option<TimingFunction> {
+    let inner = value
+        .strip_prefix("cubic-bezier(")?
+        .strip_suffix(')')?
+        .split(',')
+        .map(|n| n.trim().parse::<f32>())
+        .collect::<Result<Vec<_>, _>>()
+        .ok()?;
+    let [x1, y1, x2, y2] = inner.as_slice() else {
+        return None;
+    };
+    Some(TimingFunction::CubicBezier(*x1, *y1, *x2, *y2))
+}
@@ -0,0 +1,322 @@
+//! CSS backgrounds: `background-image` and its sizing, positioning and
+//! tiling properties.
+//!
+//! The module parses the longhands into a [`BackgroundLayer`], and
+//! [`place`] turns a layer plus the image's intrinsic size into the tile
+//! rectangle and repeat flags the painter needs — `cover`/`contain`
+//! scaling and percentage positioning resolve here so paint code only
+//! draws. Image bytes come through [`ImageCache`], which deduplicates
+//! fetches per document; decoding is the rasterizer's business.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::network::{NetworkStack, Request, ResourcePriority};
+
+use super::gradient::{self, Gradient};
+use super::layout::Rect;
+use super::style::ComputedStyle;
+use super::values::{parse_css_size, LengthContext};
+
+/// One parsed background layer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackgroundLayer {
+    pub image: BackgroundImage,
+    pub size: BackgroundSize,
+    pub position: BackgroundPosition,
+    pub repeat: BackgroundRepeat,
+}
+
+/// The image of a layer: a fetched resource or a generated gradient.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BackgroundImage {
+    /// The `url()` of `background-image`, unresolved.
+    Url(String),
+    /// A gradient, sampled by the painter at paint time — a GPU shader
+    /// or [`Gradient::sample`] in the software fallback.
+    Gradient(Gradient),
+}
+
+/// `background-size`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum BackgroundSize {
+    /// The image's intrinsic size.
+    #[default]
+    Auto,
+    /// Scale to cover the box, cropping one axis.
+    Cover,
+    /// Scale to fit inside the box, letterboxing one axis.
+    Contain,
+    /// Explicit width and height as written; `auto` keeps the aspect
+    /// ratio from the other axis.
+    Explicit(String, String),
+}
+
+/// `background-position`, one component per axis. Percentages align the
+/// image's p% point with the box's p% point, per spec.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BackgroundPosition {
+    pub x: PositionComponent,
+    pub y: PositionComponent,
+}
+
+impl Default for BackgroundPosition {
+    fn default() -> Self {
+        Self {
+            x: PositionComponent::Percent(0.0),
+            y: PositionComponent::Percent(0.0),
+        }
+    }
+}
+
+/// One axis of a background position.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PositionComponent {
+    /// Keywords normalize to percentages: left/top = 0, center = 50,
+    /// right/bottom = 100.
+    Percent(f32),
+    Px(f32),
+}
+
+/// `background-repeat`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BackgroundRepeat {
+    #[default]
+    Repeat,
+    RepeatX,
+    RepeatY,
+    NoRepeat,
+}
+
+/// The painter's instructions for one layer: the rect of the first tile
+/// and whether to step it across each axis (at the tile's stride) until
+/// the box is covered.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BackgroundPlacement {
+    pub tile: Rect,
+    pub repeat_x: bool,
+    pub repeat_y: bool,
+}
+
+/// The background layer of a computed style, if it declares an image.
+/// Reads the longhands, falling back to a `url()` in the `background`
+/// shorthand.
+pub fn background_of(style: &ComputedStyle) -> Option<BackgroundLayer> {
+    let image = style
+        .get("background-image")
+        .and_then(|value| parse_image(value))
+        .or_else(|| style.get("background").and_then(|value| parse_image(value)))?;
+    Some(BackgroundLayer {
+        image,
+        size: parse_size(style.get("background-size").map(String::as_str)),
+        position: parse_position(style.get("background-position").map(String::as_str)),
+        repeat: parse_repeat(style.get("background-repeat").map(String::as_str)),
+    })
+}
+
+/// An image value: a gradient function or a `url()`.
+fn parse_image(value: &str) -> Option<BackgroundImage> {
+    if let Some(parsed) = gradient::parse_gradient(value) {
+        return Some(BackgroundImage::Gradient(parsed));
+    }
+    extract_url(value).map(BackgroundImage::Url)
+}
+
+/// The URL inside a `url(...)` token, quotes stripped. `none` and
+/// unsupported image functions yield `None`.
+pub fn extract_url(value: &str) -> Option<String> {
+    let start = value.find("url(")?;
+    let rest = &value[start + 4..];
+    let close = rest.find(')')?;
+    let url = rest[..close].trim().trim_matches(['"', '\'']);
+    if url.is_empty() {
+        return None;
+    }
+    Some(url.to_owned())
+}
+
+fn parse_size(value: Option<&str>) -> BackgroundSize {
+    let Some(value) = value.map(str::trim) else {
+        return BackgroundSize::Auto;
+    };
+    match value {
+        "cover" => BackgroundSize::Cover,
+        "contain" => BackgroundSize::Contain,
+        "auto" | "" => BackgroundSize::Auto,
+        _ => {
+            let mut parts = value.split_whitespace();
+            let width = parts.next().unwrap_or("auto").to_owned();
+            let height = parts.next().unwrap_or("auto").to_owned();
+            BackgroundSize::Explicit(width, height)
+        }
+    }
+}
+
+fn parse_position(value: Option<&str>) -> BackgroundPosition {
+    let Some(value) = value else {
+        return BackgroundPosition::default();
+    };
+    let parts: Vec<&str> = value.split_whitespace().collect();
+    let component = |part: &str| -> PositionComponent {
+        match part {
+            "left" | "top" => PositionComponent::Percent(0.0),
+            "right" | "bottom" => PositionComponent::Percent(100.0),
+            "center" => PositionComponent::Percent(50.0),
+            other => match other.strip_suffix('%') {
+                Some(percent) => {
+                    PositionComponent::Percent(percent.trim().parse().unwrap_or(0.0))
+                }
+                None => PositionComponent::Px(
+                    other.strip_suffix("px").unwrap_or(other).parse().unwrap_or(0.0),
+                ),
+            },
+        }
+    };
+    // Keyword order is flexible (`top left`); put the vertical keyword on
+    // the y axis wherever it appears. A single component centers the
+    // other axis.
+    let (first, second) = match parts.as_slice() {
+        [a, b] if matches!(*a, "top" | "bottom") || matches!(*b, "left" | "right") => (b, a),
+        [a, b] => (a, b),
+        [a] => {
+            return BackgroundPosition {
+                x: component(a),
+                y: PositionComponent::Percent(50.0),
+            }
+        }
+        _ => return BackgroundPosition::default(),
+    };
+    BackgroundPosition {
+        x: component(first),
+        y: component(second),
+    }
+}
+
+fn parse_repeat(value: Option<&str>) -> BackgroundRepeat {
+    match value.map(str::trim) {
+        Some("no-repeat") => BackgroundRepeat::NoRepeat,
+        Some("repeat-x") => BackgroundRepeat::RepeatX,
+        Some("repeat-y") => BackgroundRepeat::RepeatY,
+        _ => BackgroundRepeat::Repeat,
+    }
+}
+
+/// Resolve a layer against the box it paints into: the first tile's rect
+/// after `background-size` scaling and `background-position` offsetting,
+/// plus the repeat flags. `intrinsic` is the decoded image's pixel size;
+/// gradients have none, so the painter passes the box's own size.
+pub fn place(
+    layer: &BackgroundLayer,
+    painting: Rect,
+    intrinsic: (f32, f32),
+    ctx: &LengthContext,
+) -> BackgroundPlacement {
+    let (image_width, image_height) = intrinsic;
+    let ratio = if image_height > 0.0 {
+        image_width / image_height
+    } else {
+        1.0
+    };
+    let (width, height) = match &layer.size {
+        BackgroundSize::Auto => (image_width, image_height),
+        BackgroundSize::Cover | BackgroundSize::Contain => {
+            let scale_x = painting.width / image_width.max(1.0);
+            let scale_y = painting.height / image_height.max(1.0);
+            let scale = if layer.size == BackgroundSize::Cover {
+                scale_x.max(scale_y)
+            } else {
+                scale_x.min(scale_y)
+            };
+            (image_width * scale, image_height * scale)
+        }
+        BackgroundSize::Explicit(width, height) => {
+            let resolve = |value: &str, basis: f32| -> Option<f32> {
+                parse_css_size(value)?.resolve(&LengthContext {
+                    containing_block: basis,
+                    ..*ctx
+                })
+            };
+            match (
+                resolve(width, painting.width),
+                resolve(height, painting.height),
+            ) {
+                (Some(w), Some(h)) => (w, h),
+                // One auto axis keeps the image's aspect ratio.
+                (Some(w), None) => (w, w / ratio),
+                (None, Some(h)) => (h * ratio, h),
+                (None, None) => (image_width, image_height),
+            }
+        }
+    };
+    let offset = |component: PositionComponent, box_extent: f32, tile_extent: f32| -> f32 {
+        match component {
+            // p% aligns the image's p% point with the box's: the free
+            // space scaled by the fraction.
+            PositionComponent::Percent(p) => (box_extent - tile_extent) * p / 100.0,
+            PositionComponent::Px(px) => px,
+        }
+    };
+    BackgroundPlacement {
+        tile: Rect {
+            x: painting.x + offset(layer.position.x, painting.width, width),
+            y: painting.y + offset(layer.position.y, painting.height, height),
+            width,
+            height,
+        },
+        repeat_x: matches!(
+            layer.repeat,
+            BackgroundRepeat::Repeat | BackgroundRepeat::RepeatX
+        ),
+        repeat_y: matches!(
+            layer.repeat,
+            BackgroundRepeat::Repeat | BackgroundRepeat::RepeatY
+        ),
+    }
+}
+
+/// Fetches and caches background image bytes, one entry per resolved
+/// URL. Decoding stays in the rasterizer; the cache only guarantees each
+/// image is fetched once per document however many boxes reference it.
+pub struct ImageCache {
+    stack: Arc<NetworkStack>,
+    images: Mutex<HashMap<String, Arc<Vec<u8>>>>,
+}
+
+impl ImageCache {
+    pub fn new(stack: Arc<NetworkStack>) -> Self {
+        Self {
+            stack,
+            images: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The image's bytes, fetching on first use. `None` when the fetch
+    /// fails; failures are not cached, so a retry is possible.
+    pub async fn get(&self, url: &str) -> Option<Arc<Vec<u8>>> {
+        if let Some(bytes) = self.cached(url) {
+            return Some(bytes);
+        }
+        let response = self
+            .stack
+            .fetch_prioritized(Request::get(url.to_owned()), ResourcePriority::Low)
+            .await
+            .ok()?;
+        let bytes = Arc::new(response.body);
+        self.images
+            .lock()
+            .unwrap()
+            .insert(url.to_owned(), Arc::clone(&bytes));
+        Some(bytes)
+    }
+
+    /// The cached bytes, without fetching. Paint code uses this: a miss
+    /// paints nothing this frame and the fetch fills the cache for the
+    /// next.
+    pub fn cached(&self, url: &str) -> Option<Arc<Vec<u8>>> {
+        self.images.lock().unwrap().get(url).cloned()
+    }
+
+    pub fn clear(&self) {
+        self.images.lock().unwrap().clear();
+    }
+}
@@ -0,0 +1,185 @@
+//! CSS borders: per-side widths, styles and colors.
+//!
+//! Resolves the border longhands and shorthands of a computed style into
+//! a [`Border`] the painter can stroke directly. Sides are independent —
+//! `border-left: 2px dashed red` and `border-top-color: blue` compose —
+//! and an unset `border-*-color` falls back to the element's `color`,
+//! per spec.
+
+use super::color::{self, Color};
+use super::style::ComputedStyle;
+use super::values::{parse_css_size, LengthContext};
+
+/// How a border side is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BorderStyle {
+    #[default]
+    None,
+    Solid,
+    Dashed,
+    Dotted,
+}
+
+impl BorderStyle {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "none" | "hidden" => Some(BorderStyle::None),
+            "solid" => Some(BorderStyle::Solid),
+            "dashed" => Some(BorderStyle::Dashed),
+            "dotted" => Some(BorderStyle::Dotted),
+            // Unsupported decorative styles draw as solid rather than
+            // dropping the border.
+            "double" | "groove" | "ridge" | "inset" | "outset" => Some(BorderStyle::Solid),
+            _ => None,
+        }
+    }
+}
+
+/// One resolved border side.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct BorderSide {
+    /// Used width in px: zero when the style is `none`.
+    pub width: f32,
+    pub style: BorderStyle,
+    pub color: Color,
+}
+
+impl BorderSide {
+    /// Whether the side paints anything.
+    pub fn is_visible(&self) -> bool {
+        self.style != BorderStyle::None && self.width > 0.0 && self.color.a > 0.0
+    }
+}
+
+/// All four sides of an element's border.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Border {
+    pub top: BorderSide,
+    pub right: BorderSide,
+    pub bottom: BorderSide,
+    pub left: BorderSide,
+}
+
+/// `medium`, the initial border width.
+const MEDIUM_WIDTH: f32 = 3.0;
+
+/// Resolve the border of a computed style. `current_color` is the
+/// element's computed `color`, the fallback for unset border colors.
+pub fn border_of(style: &ComputedStyle, ctx: &LengthContext, current_color: Color) -> Border {
+    let shorthand = style.get("border").map(|value| parse_side_shorthand(value, ctx));
+    let side = |name: &str, index: usize| -> BorderSide {
+        // Most specific wins: side longhands, then the side shorthand
+        // (`border-top`), then the four-value longhands
+        // (`border-width/style/color`), then `border`.
+        let side_shorthand = style
+            .get(&format!("border-{name}"))
+            .map(|value| parse_side_shorthand(value, ctx));
+        let border_style = style
+            .get(&format!("border-{name}-style"))
+            .and_then(|value| BorderStyle::parse(value.trim()))
+            .or_else(|| {
+                expanded_value(style, "border-style", index)
+                    .and_then(|v| BorderStyle::parse(&v))
+            })
+            .or_else(|| side_shorthand.as_ref().and_then(|s| s.style))
+            .or_else(|| shorthand.as_ref().and_then(|s| s.style))
+            .unwrap_or_default();
+        let width = style
+            .get(&format!("border-{name}-width"))
+            .and_then(|value| parse_width(value, ctx))
+            .or_else(|| {
+                expanded_value(style, "border-width", index)
+                    .and_then(|v| parse_width(&v, ctx))
+            })
+            .or_else(|| side_shorthand.as_ref().and_then(|s| s.width))
+            .or_else(|| shorthand.as_ref().and_then(|s| s.width))
+            .unwrap_or(MEDIUM_WIDTH);
+        let side_color = style
+            .get(&format!("border-{name}-color"))
+            .and_then(|value| color::resolve_color(value, current_color))
+            .or_else(|| {
+                expanded_value(style, "border-color", index)
+                    .and_then(|v| color::resolve_color(&v, current_color))
+            })
+            .or_else(|| side_shorthand.as_ref().and_then(|s| s.color))
+            .or_else(|| shorthand.as_ref().and_then(|s| s.color))
+            .unwrap_or(current_color);
+        BorderSide {
+            // A `none` style suppresses the width, per the used-value
+            // rules.
+            width: if border_style == BorderStyle::None {
+                0.0
+            } else {
+                width
+            },
+            style: border_style,
+            color: side_color,
+        }
+    };
+    Border {
+        top: side("top", 0),
+        right: side("right", 1),
+        bottom: side("bottom", 2),
+        left: side("left", 3),
+    }
+}
+
+/// One side's value from a 1–4 value longhand (`border-width: 1px 2px`),
+/// with the usual top/right/bottom/left expansion.
+fn expanded_value(style: &ComputedStyle, property: &str, index: usize) -> Option<String> {
+    let parts: Vec<&str> = style.get(property)?.split_whitespace().collect();
+    let part = match parts.len() {
+        1 => parts.first(),
+        2 => parts.get(index % 2),
+        3 => parts.get(if index == 3 { 1 } else { index }),
+        4 => parts.get(index),
+        _ => None,
+    }?;
+    Some((*part).to_owned())
+}
+
+/// The pieces a `border`/`border-top` shorthand declares.
+struct ParsedSide {
+    width: Option<f32>,
+    style: Option<BorderStyle>,
+    color: Option<Color>,
+}
+
+/// Parse `<width> <style> <color>` in any order, each part optional.
+fn parse_side_shorthand(value: &str, ctx: &LengthContext) -> ParsedSide {
+    let mut parsed = ParsedSide {
+        width: None,
+        style: None,
+        color: None,
+    };
+    for token in value.split_whitespace() {
+        if parsed.style.is_none() {
+            if let Some(border_style) = BorderStyle::parse(token) {
+                parsed.style = Some(border_style);
+                continue;
+            }
+        }
+        if parsed.width.is_none() {
+            if let Some(width) = parse_width(token, ctx) {
+                parsed.width = Some(width);
+                continue;
+            }
+        }
+        if parsed.color.is_none() {
+            if let Some(parsed_color) = color::parse_color(token) {
+                parsed.color = Some(parsed_color);
+            }
+        }
+    }
+    parsed
+}
+
+/// A border width: a length or one of the named widths.
+fn parse_width(value: &str, ctx: &LengthContext) -> Option<f32> {
+    match value.trim() {
+        "thin" => Some(1.0),
+        "medium" => Some(MEDIUM_WIDTH),
+        "thick" => Some(5.0),
+        other => parse_css_size(other)?.resolve(ctx),
+    }
+}
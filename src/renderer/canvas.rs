@@ -0,0 +1,359 @@
+//! Canvas 2D: a recording implementation of the `CanvasRenderingContext2D`
+//! drawing model.
+//!
+//! [`Canvas2D`] keeps the context state machine (styles, the current path,
+//! the transform with its save/restore stack) and records every paint call
+//! as a [`DrawCommand`]. The compositor drains the command list each frame
+//! and rasterizes it into the canvas's layer; script-side method plumbing
+//! lives in [`crate::js_engine::canvas`].
+
+use super::color::{self, Color};
+use super::svg::PathCommand;
+use super::transform::Transform2D;
+
+/// One recorded paint operation, in canvas coordinates with the transform
+/// that was current when it was issued.
+#[derive(Debug, Clone)]
+pub enum DrawCommand {
+    FillPath {
+        path: Vec<PathCommand>,
+        color: Color,
+        transform: Transform2D,
+    },
+    StrokePath {
+        path: Vec<PathCommand>,
+        color: Color,
+        width: f32,
+        transform: Transform2D,
+    },
+    /// Reset a rectangle to transparent black.
+    ClearRect {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        transform: Transform2D,
+    },
+    FillText {
+        text: String,
+        x: f32,
+        y: f32,
+        font: String,
+        color: Color,
+        transform: Transform2D,
+    },
+    /// Draw a decoded image (looked up by URL) into a destination rect.
+    DrawImage {
+        url: String,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        transform: Transform2D,
+    },
+}
+
+/// The state `save()`/`restore()` stacks, per spec.
+#[derive(Debug, Clone)]
+struct DrawState {
+    fill: Color,
+    stroke: Color,
+    line_width: f32,
+    font: String,
+    global_alpha: f32,
+    transform: Transform2D,
+}
+
+impl Default for DrawState {
+    fn default() -> Self {
+        Self {
+            fill: Color::BLACK,
+            stroke: Color::BLACK,
+            line_width: 1.0,
+            font: "10px sans-serif".to_owned(),
+            global_alpha: 1.0,
+            transform: Transform2D::IDENTITY,
+        }
+    }
+}
+
+/// A 2D canvas drawing context.
+#[derive(Debug, Default)]
+pub struct Canvas2D {
+    pub width: f32,
+    pub height: f32,
+    state: DrawState,
+    saved: Vec<DrawState>,
+    path: Vec<PathCommand>,
+    commands: Vec<DrawCommand>,
+}
+
+impl Canvas2D {
+    pub fn new(width: f32, height: f32) -> Self {
+        Self {
+            width,
+            height,
+            ..Self::default()
+        }
+    }
+
+    /// Take the commands recorded since the last drain, for rasterization.
+    pub fn take_commands(&mut self) -> Vec<DrawCommand> {
+        std::mem::take(&mut self.commands)
+    }
+
+    // State.
+
+    /// Set `fillStyle`; unparseable values are ignored, per spec.
+    pub fn set_fill_style(&mut self, value: &str) {
+        if let Some(color) = color::parse_color(value) {
+            self.state.fill = color;
+        }
+    }
+
+    pub fn set_stroke_style(&mut self, value: &str) {
+        if let Some(color) = color::parse_color(value) {
+            self.state.stroke = color;
+        }
+    }
+
+    pub fn set_line_width(&mut self, width: f32) {
+        if width.is_finite() && width > 0.0 {
+            self.state.line_width = width;
+        }
+    }
+
+    pub fn set_font(&mut self, font: &str) {
+        self.state.font = font.to_owned();
+    }
+
+    pub fn set_global_alpha(&mut self, alpha: f32) {
+        if (0.0..=1.0).contains(&alpha) {
+            self.state.global_alpha = alpha;
+        }
+    }
+
+    pub fn save(&mut self) {
+        self.saved.push(self.state.clone());
+    }
+
+    /// Restore the last saved state; a restore with nothing saved is a
+    /// no-op, per spec.
+    pub fn restore(&mut self) {
+        if let Some(state) = self.saved.pop() {
+            self.state = state;
+        }
+    }
+
+    // Transforms. Each multiplies onto the current matrix.
+
+    pub fn translate(&mut self, x: f32, y: f32) {
+        self.transform(Transform2D::translate(x, y));
+    }
+
+    pub fn scale(&mut self, x: f32, y: f32) {
+        self.transform(Transform2D::scale(x, y));
+    }
+
+    pub fn rotate(&mut self, radians: f32) {
+        self.transform(Transform2D::rotate(radians));
+    }
+
+    pub fn transform(&mut self, matrix: Transform2D) {
+        self.state.transform = self.state.transform.then(&matrix);
+    }
+
+    pub fn set_transform(&mut self, matrix: Transform2D) {
+        self.state.transform = matrix;
+    }
+
+    pub fn reset_transform(&mut self) {
+        self.state.transform = Transform2D::IDENTITY;
+    }
+
+    // Path building.
+
+    pub fn begin_path(&mut self) {
+        self.path.clear();
+    }
+
+    pub fn close_path(&mut self) {
+        self.path.push(PathCommand::Close);
+    }
+
+    pub fn move_to(&mut self, x: f32, y: f32) {
+        self.path.push(PathCommand::MoveTo(x, y));
+    }
+
+    pub fn line_to(&mut self, x: f32, y: f32) {
+        self.path.push(PathCommand::LineTo(x, y));
+    }
+
+    pub fn bezier_curve_to(&mut self, c1x: f32, c1y: f32, c2x: f32, c2y: f32, x: f32, y: f32) {
+        self.path.push(PathCommand::CubicTo(c1x, c1y, c2x, c2y, x, y));
+    }
+
+    pub fn quadratic_curve_to(&mut self, cx: f32, cy: f32, x: f32, y: f32) {
+        self.path.push(PathCommand::QuadTo(cx, cy, x, y));
+    }
+
+    pub fn rect(&mut self, x: f32, y: f32, width: f32, height: f32) {
+        self.path.extend([
+            PathCommand::MoveTo(x, y),
+            PathCommand::LineTo(x + width, y),
+            PathCommand::LineTo(x + width, y + height),
+            PathCommand::LineTo(x, y + height),
+            PathCommand::Close,
+        ]);
+    }
+
+    /// Append a circular arc, approximated by cubic Béziers (one per
+    /// quarter turn).
+    pub fn arc(&mut self, cx: f32, cy: f32, radius: f32, start: f32, end: f32, anticlockwise: bool) {
+        if radius <= 0.0 {
+            return;
+        }
+        let mut sweep = end - start;
+        if anticlockwise {
+            if sweep > 0.0 {
+                sweep -= std::f32::consts::TAU;
+            }
+        } else if sweep < 0.0 {
+            sweep += std::f32::consts::TAU;
+        }
+        sweep = sweep.clamp(-std::f32::consts::TAU, std::f32::consts::TAU);
+        let segments = (sweep.abs() / std::f32::consts::FRAC_PI_2).ceil().max(1.0) as usize;
+        let step = sweep / segments as f32;
+        let point = |angle: f32| (cx + radius * angle.cos(), cy + radius * angle.sin());
+        let (x0, y0) = point(start);
+        match self.path.last() {
+            // Per spec the arc start connects to the current point.
+            Some(_) => self.path.push(PathCommand::LineTo(x0, y0)),
+            None => self.path.push(PathCommand::MoveTo(x0, y0)),
+        }
+        let mut angle = start;
+        for _ in 0..segments {
+            let next = angle + step;
+            // Control distance for a cubic circular-arc segment.
+            let k = 4.0 / 3.0 * (step / 4.0).tan() * radius;
+            let (sx, sy) = point(angle);
+            let (ex, ey) = point(next);
+            let (tsx, tsy) = (-angle.sin(), angle.cos());
+            let (tex, tey) = (-next.sin(), next.cos());
+            self.path.push(PathCommand::CubicTo(
+                sx + k * tsx,
+                sy + k * tsy,
+                ex - k * tex,
+                ey - k * tey,
+                ex,
+                ey,
+            ));
+            angle = next;
+        }
+    }
+
+    // Painting.
+
+    pub fn fill(&mut self) {
+        if self.path.is_empty() {
+            return;
+        }
+        self.commands.push(DrawCommand::FillPath {
+            path: self.path.clone(),
+            color: self.paint(self.state.fill),
+            transform: self.state.transform,
+        });
+    }
+
+    pub fn stroke(&mut self) {
+        if self.path.is_empty() {
+            return;
+        }
+        self.commands.push(DrawCommand::StrokePath {
+            path: self.path.clone(),
+            color: self.paint(self.state.stroke),
+            width: self.state.line_width,
+            transform: self.state.transform,
+        });
+    }
+
+    pub fn fill_rect(&mut self, x: f32, y: f32, width: f32, height: f32) {
+        self.commands.push(DrawCommand::FillPath {
+            path: rect_path(x, y, width, height),
+            color: self.paint(self.state.fill),
+            transform: self.state.transform,
+        });
+    }
+
+    pub fn stroke_rect(&mut self, x: f32, y: f32, width: f32, height: f32) {
+        self.commands.push(DrawCommand::StrokePath {
+            path: rect_path(x, y, width, height),
+            color: self.paint(self.state.stroke),
+            width: self.state.line_width,
+            transform: self.state.transform,
+        });
+    }
+
+    pub fn clear_rect(&mut self, x: f32, y: f32, width: f32, height: f32) {
+        self.commands.push(DrawCommand::ClearRect {
+            x,
+            y,
+            width,
+            height,
+            transform: self.state.transform,
+        });
+    }
+
+    pub fn fill_text(&mut self, text: &str, x: f32, y: f32) {
+        self.commands.push(DrawCommand::FillText {
+            text: text.to_owned(),
+            x,
+            y,
+            font: self.state.font.clone(),
+            color: self.paint(self.state.fill),
+            transform: self.state.transform,
+        });
+    }
+
+    pub fn draw_image(&mut self, url: &str, x: f32, y: f32, width: f32, height: f32) {
+        self.commands.push(DrawCommand::DrawImage {
+            url: url.to_owned(),
+            x,
+            y,
+            width,
+            height,
+            transform: self.state.transform,
+        });
+    }
+
+    /// The scriptable style state, for reflecting `restore()` back into
+    /// JS properties: (fillStyle, strokeStyle, lineWidth, font,
+    /// globalAlpha).
+    pub fn style_snapshot(&self) -> (String, String, f32, String, f32) {
+        (
+            self.state.fill.to_css_string(),
+            self.state.stroke.to_css_string(),
+            self.state.line_width,
+            self.state.font.clone(),
+            self.state.global_alpha,
+        )
+    }
+
+    /// A paint color with `globalAlpha` applied.
+    fn paint(&self, color: Color) -> Color {
+        Color {
+            a: color.a * self.state.global_alpha,
+            ..color
+        }
+    }
+}
+
+fn rect_path(x: f32, y: f32, width: f32, height: f32) -> Vec<PathCommand> {
+    vec![
+        PathCommand::MoveTo(x, y),
+        PathCommand::LineTo(x + width, y),
+        PathCommand::LineTo(x + width, y + height),
+        PathCommand::LineTo(x, y + height),
+        PathCommand::Close,
+    ]
+}
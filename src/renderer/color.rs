@@ -0,0 +1,399 @@
+//! CSS color values.
+//!
+//! Colors are stored as 8-bit sRGB with a separate float alpha, which is
+//! what the paint code wants. The parser covers hex in all four lengths,
+//! `rgb()`/`rgba()`, `hsl()`/`hsla()`, `hwb()` — in both the legacy
+//! comma and the modern space-separated syntax — and the complete CSS
+//! named-color table. `currentColor` needs the element's computed
+//! `color` and goes through [`resolve_color`].
+
+/// An sRGB color with alpha.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: f32,
+}
+
+impl Color {
+    pub const BLACK: Color = Color::rgb(0, 0, 0);
+    pub const WHITE: Color = Color::rgb(255, 255, 255);
+    pub const TRANSPARENT: Color = Color {
+        r: 0,
+        g: 0,
+        b: 0,
+        a: 0.0,
+    };
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b, a: 1.0 }
+    }
+
+    /// Serialize for script: `#rrggbb` when opaque, `rgba()` otherwise,
+    /// matching how canvas and getComputedStyle report colors.
+    pub fn to_css_string(&self) -> String {
+        if (self.a - 1.0).abs() < f32::EPSILON {
+            format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+        } else {
+            format!("rgba({}, {}, {}, {})", self.r, self.g, self.b, self.a)
+        }
+    }
+}
+
+/// The complete CSS named-color table, sorted for binary search.
+const NAMED_COLORS: &[(&str, Color)] = &[
+    ("aliceblue", Color::rgb(240, 248, 255)),
+    ("antiquewhite", Color::rgb(250, 235, 215)),
+    ("aqua", Color::rgb(0, 255, 255)),
+    ("aquamarine", Color::rgb(127, 255, 212)),
+    ("azure", Color::rgb(240, 255, 255)),
+    ("beige", Color::rgb(245, 245, 220)),
+    ("bisque", Color::rgb(255, 228, 196)),
+    ("black", Color::BLACK),
+    ("blanchedalmond", Color::rgb(255, 235, 205)),
+    ("blue", Color::rgb(0, 0, 255)),
+    ("blueviolet", Color::rgb(138, 43, 226)),
+    ("brown", Color::rgb(165, 42, 42)),
+    ("burlywood", Color::rgb(222, 184, 135)),
+    ("cadetblue", Color::rgb(95, 158, 160)),
+    ("chartreuse", Color::rgb(127, 255, 0)),
+    ("chocolate", Color::rgb(210, 105, 30)),
+    ("coral", Color::rgb(255, 127, 80)),
+    ("cornflowerblue", Color::rgb(100, 149, 237)),
+    ("cornsilk", Color::rgb(255, 248, 220)),
+    ("crimson", Color::rgb(220, 20, 60)),
+    ("cyan", Color::rgb(0, 255, 255)),
+    ("darkblue", Color::rgb(0, 0, 139)),
+    ("darkcyan", Color::rgb(0, 139, 139)),
+    ("darkgoldenrod", Color::rgb(184, 134, 11)),
+    ("darkgray", Color::rgb(169, 169, 169)),
+    ("darkgreen", Color::rgb(0, 100, 0)),
+    ("darkgrey", Color::rgb(169, 169, 169)),
+    ("darkkhaki", Color::rgb(189, 183, 107)),
+    ("darkmagenta", Color::rgb(139, 0, 139)),
+    ("darkolivegreen", Color::rgb(85, 107, 47)),
+    ("darkorange", Color::rgb(255, 140, 0)),
+    ("darkorchid", Color::rgb(153, 50, 204)),
+    ("darkred", Color::rgb(139, 0, 0)),
+    ("darksalmon", Color::rgb(233, 150, 122)),
+    ("darkseagreen", Color::rgb(143, 188, 143)),
+    ("darkslateblue", Color::rgb(72, 61, 139)),
+    ("darkslategray", Color::rgb(47, 79, 79)),
+    ("darkslategrey", Color::rgb(47, 79, 79)),
+    ("darkturquoise", Color::rgb(0, 206, 209)),
+    ("darkviolet", Color::rgb(148, 0, 211)),
+    ("deeppink", Color::rgb(255, 20, 147)),
+    ("deepskyblue", Color::rgb(0, 191, 255)),
+    ("dimgray", Color::rgb(105, 105, 105)),
+    ("dimgrey", Color::rgb(105, 105, 105)),
+    ("dodgerblue", Color::rgb(30, 144, 255)),
+    ("firebrick", Color::rgb(178, 34, 34)),
+    ("floralwhite", Color::rgb(255, 250, 240)),
+    ("forestgreen", Color::rgb(34, 139, 34)),
+    ("fuchsia", Color::rgb(255, 0, 255)),
+    ("gainsboro", Color::rgb(220, 220, 220)),
+    ("ghostwhite", Color::rgb(248, 248, 255)),
+    ("gold", Color::rgb(255, 215, 0)),
+    ("goldenrod", Color::rgb(218, 165, 32)),
+    ("gray", Color::rgb(128, 128, 128)),
+    ("green", Color::rgb(0, 128, 0)),
+    ("greenyellow", Color::rgb(173, 255, 47)),
+    ("grey", Color::rgb(128, 128, 128)),
+    ("honeydew", Color::rgb(240, 255, 240)),
+    ("hotpink", Color::rgb(255, 105, 180)),
+    ("indianred", Color::rgb(205, 92, 92)),
+    ("indigo", Color::rgb(75, 0, 130)),
+    ("ivory", Color::rgb(255, 255, 240)),
+    ("khaki", Color::rgb(240, 230, 140)),
+    ("lavender", Color::rgb(230, 230, 250)),
+    ("lavenderblush", Color::rgb(255, 240, 245)),
+    ("lawngreen", Color::rgb(124, 252, 0)),
+    ("lemonchiffon", Color::rgb(255, 250, 205)),
+    ("lightblue", Color::rgb(173, 216, 230)),
+    ("lightcoral", Color::rgb(240, 128, 128)),
+    ("lightcyan", Color::rgb(224, 255, 255)),
+    ("lightgoldenrodyellow", Color::rgb(250, 250, 210)),
+    ("lightgray", Color::rgb(211, 211, 211)),
+    ("lightgreen", Color::rgb(144, 238, 144)),
+    ("lightgrey", Color::rgb(211, 211, 211)),
+    ("lightpink", Color::rgb(255, 182, 193)),
+    ("lightsalmon", Color::rgb(255, 160, 122)),
+    ("lightseagreen", Color::rgb(32, 178, 170)),
+    ("lightskyblue", Color::rgb(135, 206, 250)),
+    ("lightslategray", Color::rgb(119, 136, 153)),
+    ("lightslategrey", Color::rgb(119, 136, 153)),
+    ("lightsteelblue", Color::rgb(176, 196, 222)),
+    ("lightyellow", Color::rgb(255, 255, 224)),
+    ("lime", Color::rgb(0, 255, 0)),
+    ("limegreen", Color::rgb(50, 205, 50)),
+    ("linen", Color::rgb(250, 240, 230)),
+    ("magenta", Color::rgb(255, 0, 255)),
+    ("maroon", Color::rgb(128, 0, 0)),
+    ("mediumaquamarine", Color::rgb(102, 205, 170)),
+    ("mediumblue", Color::rgb(0, 0, 205)),
+    ("mediumorchid", Color::rgb(186, 85, 211)),
+    ("mediumpurple", Color::rgb(147, 112, 219)),
+    ("mediumseagreen", Color::rgb(60, 179, 113)),
+    ("mediumslateblue", Color::rgb(123, 104, 238)),
+    ("mediumspringgreen", Color::rgb(0, 250, 154)),
+    ("mediumturquoise", Color::rgb(72, 209, 204)),
+    ("mediumvioletred", Color::rgb(199, 21, 133)),
+    ("midnightblue", Color::rgb(25, 25, 112)),
+    ("mintcream", Color::rgb(245, 255, 250)),
+    ("mistyrose", Color::rgb(255, 228, 225)),
+    ("moccasin", Color::rgb(255, 228, 181)),
+    ("navajowhite", Color::rgb(255, 222, 173)),
+    ("navy", Color::rgb(0, 0, 128)),
+    ("oldlace", Color::rgb(253, 245, 230)),
+    ("olive", Color::rgb(128, 128, 0)),
+    ("olivedrab", Color::rgb(107, 142, 35)),
+    ("orange", Color::rgb(255, 165, 0)),
+    ("orangered", Color::rgb(255, 69, 0)),
+    ("orchid", Color::rgb(218, 112, 214)),
+    ("palegoldenrod", Color::rgb(238, 232, 170)),
+    ("palegreen", Color::rgb(152, 251, 152)),
+    ("paleturquoise", Color::rgb(175, 238, 238)),
+    ("palevioletred", Color::rgb(219, 112, 147)),
+    ("papayawhip", Color::rgb(255, 239, 213)),
+    ("peachpuff", Color::rgb(255, 218, 185)),
+    ("peru", Color::rgb(205, 133, 63)),
+    ("pink", Color::rgb(255, 192, 203)),
+    ("plum", Color::rgb(221, 160, 221)),
+    ("powderblue", Color::rgb(176, 224, 230)),
+    ("purple", Color::rgb(128, 0, 128)),
+    ("rebeccapurple", Color::rgb(102, 51, 153)),
+    ("red", Color::rgb(255, 0, 0)),
+    ("rosybrown", Color::rgb(188, 143, 143)),
+    ("royalblue", Color::rgb(65, 105, 225)),
+    ("saddlebrown", Color::rgb(139, 69, 19)),
+    ("salmon", Color::rgb(250, 128, 114)),
+    ("sandybrown", Color::rgb(244, 164, 96)),
+    ("seagreen", Color::rgb(46, 139, 87)),
+    ("seashell", Color::rgb(255, 245, 238)),
+    ("sienna", Color::rgb(160, 82, 45)),
+    ("silver", Color::rgb(192, 192, 192)),
+    ("skyblue", Color::rgb(135, 206, 235)),
+    ("slateblue", Color::rgb(106, 90, 205)),
+    ("slategray", Color::rgb(112, 128, 144)),
+    ("slategrey", Color::rgb(112, 128, 144)),
+    ("snow", Color::rgb(255, 250, 250)),
+    ("springgreen", Color::rgb(0, 255, 127)),
+    ("steelblue", Color::rgb(70, 130, 180)),
+    ("tan", Color::rgb(210, 180, 140)),
+    ("teal", Color::rgb(0, 128, 128)),
+    ("thistle", Color::rgb(216, 191, 216)),
+    ("tomato", Color::rgb(255, 99, 71)),
+    ("turquoise", Color::rgb(64, 224, 208)),
+    ("violet", Color::rgb(238, 130, 238)),
+    ("wheat", Color::rgb(245, 222, 179)),
+    ("white", Color::WHITE),
+    ("whitesmoke", Color::rgb(245, 245, 245)),
+    ("yellow", Color::rgb(255, 255, 0)),
+    ("yellowgreen", Color::rgb(154, 205, 50)),
+];
+
+/// Parse a CSS color value. `None` for anything unrecognized.
+pub fn parse_color(value: &str) -> Option<Color> {
+    let value = value.trim();
+    if let Some(hex) = value.strip_prefix('#') {
+        return parse_hex(hex);
+    }
+    let lower = value.to_ascii_lowercase();
+    if lower == "transparent" {
+        return Some(Color::TRANSPARENT);
+    }
+    if let Some(args) = lower
+        .strip_prefix("rgba(")
+        .or_else(|| lower.strip_prefix("rgb("))
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        return parse_rgb_args(args);
+    }
+    if let Some(args) = lower
+        .strip_prefix("hsla(")
+        .or_else(|| lower.strip_prefix("hsl("))
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        return parse_hsl_args(args);
+    }
+    if let Some(args) = lower
+        .strip_prefix("hwb(")
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        return parse_hwb_args(args);
+    }
+    NAMED_COLORS
+        .binary_search_by_key(&lower.as_str(), |&(name, _)| name)
+        .ok()
+        .map(|index| NAMED_COLORS[index].1)
+}
+
+/// Parse a CSS color, resolving `currentColor` against `current` (the
+/// element's computed `color`, per spec).
+pub fn resolve_color(value: &str, current: Color) -> Option<Color> {
+    if value.trim().eq_ignore_ascii_case("currentcolor") {
+        return Some(current);
+    }
+    parse_color(value)
+}
+
+fn parse_hex(hex: &str) -> Option<Color> {
+    let nibble = |i: usize| u8::from_str_radix(hex.get(i..i + 1)?, 16).ok();
+    let byte = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    match hex.len() {
+        // #rgb and #rgba expand each digit: f → ff.
+        3 | 4 => {
+            let (r, g, b) = (nibble(0)?, nibble(1)?, nibble(2)?);
+            let a = if hex.len() == 4 { nibble(3)? * 17 } else { 255 };
+            Some(Color {
+                r: r * 17,
+                g: g * 17,
+                b: b * 17,
+                a: f32::from(a) / 255.0,
+            })
+        }
+        6 | 8 => {
+            let (r, g, b) = (byte(0)?, byte(2)?, byte(4)?);
+            let a = if hex.len() == 8 { byte(6)? } else { 255 };
+            Some(Color {
+                r,
+                g,
+                b,
+                a: f32::from(a) / 255.0,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// The inside of `rgb()`/`rgba()`: comma or space separated components,
+/// numbers or percentages, optional alpha.
+fn parse_rgb_args(args: &str) -> Option<Color> {
+    let parts = split_components(args);
+    if parts.len() != 3 && parts.len() != 4 {
+        return None;
+    }
+    let channel = |part: &str| -> Option<u8> {
+        let value = match part.strip_suffix('%') {
+            Some(percent) => percent.trim().parse::<f32>().ok()? * 255.0 / 100.0,
+            None => part.parse::<f32>().ok()?,
+        };
+        Some(value.clamp(0.0, 255.0).round() as u8)
+    };
+    let alpha = match parts.get(3) {
+        Some(part) => match part.strip_suffix('%') {
+            Some(percent) => percent.trim().parse::<f32>().ok()? / 100.0,
+            None => part.parse::<f32>().ok()?,
+        },
+        None => 1.0,
+    };
+    Some(Color {
+        r: channel(parts[0])?,
+        g: channel(parts[1])?,
+        b: channel(parts[2])?,
+        a: alpha.clamp(0.0, 1.0),
+    })
+}
+
+/// The inside of `hsl()`/`hsla()`: hue, saturation and lightness
+/// percentages (bare numbers accepted per the modern syntax), optional
+/// alpha.
+fn parse_hsl_args(args: &str) -> Option<Color> {
+    let parts = split_components(args);
+    if parts.len() != 3 && parts.len() != 4 {
+        return None;
+    }
+    let hue = parse_hue(parts[0])?;
+    let saturation = parse_percentage(parts[1])?;
+    let lightness = parse_percentage(parts[2])?;
+    let alpha = parts.get(3).map_or(Some(1.0), |p| parse_percentage(p))?;
+    let (r, g, b) = hsl_to_rgb(hue, saturation, lightness);
+    Some(Color { r, g, b, a: alpha })
+}
+
+/// The inside of `hwb()`: hue, whiteness and blackness percentages,
+/// optional alpha. Whiteness and blackness summing past 100% normalize
+/// to gray, per spec.
+fn parse_hwb_args(args: &str) -> Option<Color> {
+    let parts = split_components(args);
+    if parts.len() != 3 && parts.len() != 4 {
+        return None;
+    }
+    let hue = parse_hue(parts[0])?;
+    let mut white = parse_percentage(parts[1])?;
+    let mut black = parse_percentage(parts[2])?;
+    let alpha = parts.get(3).map_or(Some(1.0), |p| parse_percentage(p))?;
+    if white + black > 1.0 {
+        let sum = white + black;
+        white /= sum;
+        black /= sum;
+    }
+    // hwb mixes the pure hue with white and black.
+    let (r, g, b) = hsl_to_rgb(hue, 1.0, 0.5);
+    let mix = |channel: u8| -> u8 {
+        let value = f32::from(channel) / 255.0 * (1.0 - white - black) + white;
+        (value * 255.0).round() as u8
+    };
+    Some(Color {
+        r: mix(r),
+        g: mix(g),
+        b: mix(b),
+        a: alpha,
+    })
+}
+
+/// Split function arguments on the legacy comma or modern space/slash
+/// separators.
+fn split_components(args: &str) -> Vec<&str> {
+    args.split([',', '/', ' '])
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+/// A hue component in degrees, accepting the angle units.
+fn parse_hue(part: &str) -> Option<f32> {
+    let (number, scale) = if let Some(n) = part.strip_suffix("deg") {
+        (n, 1.0)
+    } else if let Some(n) = part.strip_suffix("grad") {
+        (n, 360.0 / 400.0)
+    } else if let Some(n) = part.strip_suffix("rad") {
+        (n, 180.0 / std::f32::consts::PI)
+    } else if let Some(n) = part.strip_suffix("turn") {
+        (n, 360.0)
+    } else {
+        (part, 1.0)
+    };
+    Some(number.trim().parse::<f32>().ok()? * scale)
+}
+
+/// A percentage component as 0..=1; a bare number is taken as already
+/// normalized.
+fn parse_percentage(part: &str) -> Option<f32> {
+    let value = match part.strip_suffix('%') {
+        Some(percent) => percent.trim().parse::<f32>().ok()? / 100.0,
+        None => part.parse::<f32>().ok()?,
+    };
+    Some(value.clamp(0.0, 1.0))
+}
+
+/// hsl → sRGB, the CSS Color 4 algorithm. `saturation` and `lightness`
+/// are 0..=1, `hue` in degrees (any range).
+fn hsl_to_rgb(hue: f32, saturation: f32, lightness: f32) -> (u8, u8, u8) {
+    let hue = hue.rem_euclid(360.0);
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let h = hue / 60.0;
+    let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = lightness - chroma / 2.0;
+    let to_byte = |v: f32| ((v + m).clamp(0.0, 1.0) * 255.0).round() as u8;
+    (to_byte(r), to_byte(g), to_byte(b))
+}
@@ -0,0 +1,1209 @@
+//! CSS: stylesheet parsing and selector matching.
+//!
+//! Selector matching is right-to-left: the rightmost compound is tested
+//! against the candidate element, then each combinator walks the DOM —
+//! ancestors for descendant/child, preceding siblings for `+`/`~` — to
+//! find an element matching the next compound. This is the one matching
+//! implementation in the engine; [`crate::ui::tab`] uses it too rather
+//! than keeping its own.
+
+use super::dom::{Document, NodeId};
+use super::media::{MediaEnvironment, MediaQueryList};
+
+/// A parsed stylesheet: rules in source order.
+#[derive(Debug, Clone, Default)]
+pub struct Stylesheet {
+    pub rules: Vec<StyleRule>,
+}
+
+/// One `selector-list { declarations }` rule.
+#[derive(Debug, Clone)]
+pub struct StyleRule {
+    pub selectors: Vec<Selector>,
+    pub declarations: Vec<Declaration>,
+    /// Media query lists of every enclosing `@media` block, outermost
+    /// first. All must match for the rule to apply; empty = unconditional.
+    pub conditions: Vec<MediaQueryList>,
+}
+
+impl StyleRule {
+    /// Whether the rule is active under `env`, honouring enclosing
+    /// `@media` blocks.
+    pub fn applies(&self, env: &MediaEnvironment) -> bool {
+        self.conditions.iter().all(|list| list.evaluate(env))
+    }
+}
+
+/// A single `name: value` declaration.
+#[derive(Debug, Clone)]
+pub struct Declaration {
+    pub name: String,
+    pub value: String,
+    /// Declared with `!important`.
+    pub important: bool,
+}
+
+/// How two compound selectors relate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Combinator {
+    /// Whitespace: any ancestor.
+    Descendant,
+    /// `>`: parent.
+    Child,
+    /// `+`: immediately preceding element sibling.
+    NextSibling,
+    /// `~`: any preceding element sibling.
+    SubsequentSibling,
+}
+
+/// A full complex selector: the rightmost compound plus the chain of
+/// (combinator, compound) pairs leading left from it.
+#[derive(Debug, Clone)]
+pub struct Selector {
+    pub key: CompoundSelector,
+    /// Leftward chain, nearest first: `div > p span` parses with `key` =
+    /// `span`, then `(Descendant, p)`, `(Child, div)`.
+    pub ancestors: Vec<(Combinator, CompoundSelector)>,
+}
+
+/// A pseudo-element a selector can target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PseudoElement {
+    Before,
+    After,
+}
+
+/// Pseudo-classes: dynamic ones matched against [`InteractionState`],
+/// structural ones against tree position, and the functional logical
+/// combinators over nested selector lists.
+#[derive(Debug, Clone)]
+pub enum PseudoClass {
+    Hover,
+    Focus,
+    Active,
+    FirstChild,
+    LastChild,
+    NthChild(AnB),
+    NthLastChild(AnB),
+    /// `:not(list)`: matches when no argument selector does.
+    Not(Vec<Selector>),
+    /// `:is(list)`: matches when any argument selector does.
+    Is(Vec<Selector>),
+    /// `:where(list)`: `:is` with zero specificity.
+    Where(Vec<Selector>),
+}
+
+impl PseudoClass {
+    fn matches(&self, document: &Document, node: NodeId, state: &InteractionState) -> bool {
+        match self {
+            PseudoClass::Hover => state.hovered.contains(&node),
+            PseudoClass::Focus => state.focused == Some(node),
+            PseudoClass::Active => state.active.contains(&node),
+            PseudoClass::FirstChild => document.previous_element_sibling(node).is_none(),
+            PseudoClass::LastChild => document.next_element_sibling(node).is_none(),
+            PseudoClass::NthChild(anb) => anb.matches(element_index(document, node)),
+            PseudoClass::NthLastChild(anb) => {
+                anb.matches(element_index_from_end(document, node))
+            }
+            PseudoClass::Not(list) => {
+                !list.iter().any(|selector| selector.matches(document, node, state))
+            }
+            PseudoClass::Is(list) | PseudoClass::Where(list) => {
+                list.iter().any(|selector| selector.matches(document, node, state))
+            }
+        }
+    }
+
+    /// Whether matching can change with [`InteractionState`], directly or
+    /// through a nested selector list.
+    pub fn is_dynamic(&self) -> bool {
+        match self {
+            PseudoClass::Hover | PseudoClass::Focus | PseudoClass::Active => true,
+            PseudoClass::FirstChild
+            | PseudoClass::LastChild
+            | PseudoClass::NthChild(_)
+            | PseudoClass::NthLastChild(_) => false,
+            PseudoClass::Not(list) | PseudoClass::Is(list) | PseudoClass::Where(list) => list
+                .iter()
+                .flat_map(|selector| {
+                    std::iter::once(&selector.key)
+                        .chain(selector.ancestors.iter().map(|(_, c)| c))
+                })
+                .any(|compound| compound.pseudo_classes.iter().any(PseudoClass::is_dynamic)),
+        }
+    }
+}
+
+/// The `an+b` microsyntax of `:nth-child()` and friends: matches the
+/// 1-based indices `a*n + b` for non-negative integer `n`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnB {
+    pub a: i64,
+    pub b: i64,
+}
+
+impl AnB {
+    /// Parse `odd`, `even`, an integer, or the full form (`2n+1`, `-n+3`,
+    /// `n`, `3n`).
+    pub fn parse(input: &str) -> Option<Self> {
+        let input: String = input.chars().filter(|c| !c.is_whitespace()).collect();
+        let input = input.to_ascii_lowercase();
+        match input.as_str() {
+            "odd" => return Some(Self { a: 2, b: 1 }),
+            "even" => return Some(Self { a: 2, b: 0 }),
+            _ => {}
+        }
+        let Some(n) = input.find('n') else {
+            return Some(Self {
+                a: 0,
+                b: input.parse().ok()?,
+            });
+        };
+        let a = match &input[..n] {
+            "" | "+" => 1,
+            "-" => -1,
+            coefficient => coefficient.parse().ok()?,
+        };
+        let b = match &input[n + 1..] {
+            "" => 0,
+            offset => {
+                if !offset.starts_with(['+', '-']) {
+                    return None;
+                }
+                offset.parse().ok()?
+            }
+        };
+        Some(Self { a, b })
+    }
+
+    /// Whether 1-based `index` is in the set.
+    pub fn matches(&self, index: i64) -> bool {
+        if self.a == 0 {
+            return index == self.b;
+        }
+        let distance = index - self.b;
+        distance % self.a == 0 && distance / self.a >= 0
+    }
+}
+
+/// 1-based position of `node` among its element siblings.
+fn element_index(document: &Document, node: NodeId) -> i64 {
+    let mut index = 1;
+    let mut sibling = document.previous_element_sibling(node);
+    while let Some(previous) = sibling {
+        index += 1;
+        sibling = document.previous_element_sibling(previous);
+    }
+    index
+}
+
+/// 1-based position of `node` among its element siblings, counted from
+/// the end.
+fn element_index_from_end(document: &Document, node: NodeId) -> i64 {
+    let mut index = 1;
+    let mut sibling = document.next_element_sibling(node);
+    while let Some(next) = sibling {
+        index += 1;
+        sibling = document.next_element_sibling(next);
+    }
+    index
+}
+
+/// Which elements are currently interacted with. Owned by the UI (one per
+/// tab) and consulted during matching; changing it invalidates the styles
+/// of elements that rules with dynamic pseudo-classes touch.
+#[derive(Debug, Clone, Default)]
+pub struct InteractionState {
+    /// The element under the pointer and its ancestor chain — hovering a
+    /// child hovers every ancestor, per spec.
+    pub hovered: std::collections::HashSet<NodeId>,
+    pub focused: Option<NodeId>,
+    /// Elements being activated (pointer down), plus their ancestors.
+    pub active: std::collections::HashSet<NodeId>,
+}
+
+/// How an attribute selector compares against the attribute value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttrOp {
+    /// `=`
+    Equals,
+    /// `~=`: one of a whitespace-separated list.
+    Includes,
+    /// `|=`: exactly, or followed by a hyphen.
+    DashMatch,
+    /// `^=`
+    Prefix,
+    /// `$=`
+    Suffix,
+    /// `*=`
+    Substring,
+}
+
+/// One `[attr]` / `[attr=value]` selector.
+#[derive(Debug, Clone)]
+pub struct AttributeSelector {
+    /// Lowercased attribute name.
+    pub name: String,
+    /// Comparison and expected value; `None` tests presence only.
+    pub op: Option<(AttrOp, String)>,
+    /// The `i` flag: compare values case-insensitively.
+    pub case_insensitive: bool,
+}
+
+impl AttributeSelector {
+    /// Parse the inside of the brackets, e.g. `href^="https" i`.
+    fn parse(inner: &str) -> Option<Self> {
+        let inner = inner.trim();
+        let op_start = inner.find(['~', '|', '^', '$', '*', '=']);
+        let Some(start) = op_start else {
+            if inner.is_empty() {
+                return None;
+            }
+            return Some(Self {
+                name: inner.to_ascii_lowercase(),
+                op: None,
+                case_insensitive: false,
+            });
+        };
+        let name = inner[..start].trim().to_ascii_lowercase();
+        if name.is_empty() {
+            return None;
+        }
+        let (op, value_start) = match inner.as_bytes()[start] {
+            b'=' => (AttrOp::Equals, start + 1),
+            marker => {
+                if inner.as_bytes().get(start + 1) != Some(&b'=') {
+                    return None;
+                }
+                let op = match marker {
+                    b'~' => AttrOp::Includes,
+                    b'|' => AttrOp::DashMatch,
+                    b'^' => AttrOp::Prefix,
+                    b'$' => AttrOp::Suffix,
+                    b'*' => AttrOp::Substring,
+                    _ => return None,
+                };
+                (op, start + 2)
+            }
+        };
+        let mut value = inner[value_start..].trim();
+        let mut case_insensitive = false;
+        if let Some(stripped) = value
+            .strip_suffix(" i")
+            .or_else(|| value.strip_suffix(" I"))
+        {
+            case_insensitive = true;
+            value = stripped.trim_end();
+        } else if let Some(stripped) = value
+            .strip_suffix(" s")
+            .or_else(|| value.strip_suffix(" S"))
+        {
+            value = stripped.trim_end();
+        }
+        let value = value.trim_matches(['"', '\'']);
+        Some(Self {
+            name,
+            op: Some((op, value.to_owned())),
+            case_insensitive,
+        })
+    }
+
+    fn matches(&self, element: &super::dom::ElementData) -> bool {
+        let Some(actual) = element.attr(&self.name) else {
+            return false;
+        };
+        let Some((op, expected)) = &self.op else {
+            return true;
+        };
+        let (actual, expected) = if self.case_insensitive {
+            (actual.to_ascii_lowercase(), expected.to_ascii_lowercase())
+        } else {
+            (actual.to_owned(), expected.clone())
+        };
+        match op {
+            AttrOp::Equals => actual == expected,
+            AttrOp::Includes => {
+                !expected.is_empty() && actual.split_whitespace().any(|w| w == expected)
+            }
+            AttrOp::DashMatch => {
+                actual == expected
+                    || (actual.starts_with(&expected)
+                        && actual.as_bytes().get(expected.len()) == Some(&b'-'))
+            }
+            // An empty expected value never matches for the substring
+            // operators, per spec.
+            AttrOp::Prefix => !expected.is_empty() && actual.starts_with(&expected),
+            AttrOp::Suffix => !expected.is_empty() && actual.ends_with(&expected),
+            AttrOp::Substring => !expected.is_empty() && actual.contains(&expected),
+        }
+    }
+}
+
+/// Simple selectors that all apply to one element.
+#[derive(Debug, Clone, Default)]
+pub struct CompoundSelector {
+    /// Lowercased tag name; `None` matches any (`*` or omitted).
+    pub tag: Option<String>,
+    pub id: Option<String>,
+    pub classes: Vec<String>,
+    pub attributes: Vec<AttributeSelector>,
+    pub pseudo_classes: Vec<PseudoClass>,
+    /// Pseudo-element, only meaningful on the rightmost compound.
+    pub pseudo_element: Option<PseudoElement>,
+}
+
+impl Selector {
+    /// Parse one complex selector, e.g. `nav > ul li.item`. Returns `None`
+    /// for syntax this engine does not support yet, so the containing rule
+    /// can be dropped rather than mis-applied.
+    pub fn parse(input: &str) -> Option<Self> {
+        let tokens = tokenize_selector(input)?;
+        let mut compounds = Vec::new();
+        let mut pending_combinator = None;
+        for token in tokens {
+            match token {
+                SelectorToken::Compound(text) => {
+                    let compound = CompoundSelector::parse(&text)?;
+                    compounds.push((pending_combinator.take(), compound));
+                }
+                SelectorToken::Combinator(combinator) => {
+                    if compounds.is_empty() || pending_combinator.is_some() {
+                        return None;
+                    }
+                    pending_combinator = Some(combinator);
+                }
+            }
+        }
+        if pending_combinator.is_some() {
+            return None;
+        }
+        let (mut right_combinator, key) = compounds.pop()?;
+        // Walking right to left, each ancestor entry carries the
+        // combinator that linked it to the compound on its *right*.
+        let mut ancestors = Vec::new();
+        for (combinator, compound) in compounds.into_iter().rev() {
+            ancestors.push((
+                right_combinator.unwrap_or(Combinator::Descendant),
+                compound,
+            ));
+            right_combinator = combinator;
+        }
+        Some(Self { key, ancestors })
+    }
+
+    /// The pseudo-element this selector styles, if any.
+    pub fn pseudo_element(&self) -> Option<PseudoElement> {
+        self.key.pseudo_element
+    }
+
+    /// Whether this selector matches `node` in `document`, honouring the
+    /// full combinator chain. Selectors targeting a pseudo-element never
+    /// match a real element; see [`Selector::matches_pseudo`].
+    pub fn matches(&self, document: &Document, node: NodeId, state: &InteractionState) -> bool {
+        self.matches_pseudo(document, node, None, state)
+    }
+
+    /// Like [`Selector::matches`], but for the given pseudo-element of
+    /// `node` (the originating element).
+    pub fn matches_pseudo(
+        &self,
+        document: &Document,
+        node: NodeId,
+        pseudo: Option<PseudoElement>,
+        state: &InteractionState,
+    ) -> bool {
+        if self.key.pseudo_element != pseudo {
+            return false;
+        }
+        if !self.key.matches(document, node, state) {
+            return false;
+        }
+        let mut current = node;
+        for (combinator, compound) in &self.ancestors {
+            match combinator {
+                Combinator::Descendant => {
+                    let mut ancestor = tree_parent(document, current);
+                    loop {
+                        match ancestor {
+                            Some(candidate) => {
+                                if compound.matches(document, candidate, state) {
+                                    current = candidate;
+                                    break;
+                                }
+                                ancestor = tree_parent(document, candidate);
+                            }
+                            None => return false,
+                        }
+                    }
+                }
+                Combinator::Child => match tree_parent(document, current) {
+                    Some(parent) if compound.matches(document, parent, state) => current = parent,
+                    _ => return false,
+                },
+                Combinator::NextSibling => match document.previous_element_sibling(current) {
+                    Some(sibling) if compound.matches(document, sibling, state) => {
+                        current = sibling
+                    }
+                    _ => return false,
+                },
+                Combinator::SubsequentSibling => {
+                    let mut sibling = document.previous_element_sibling(current);
+                    loop {
+                        match sibling {
+                            Some(candidate) => {
+                                if compound.matches(document, candidate, state) {
+                                    current = candidate;
+                                    break;
+                                }
+                                sibling = document.previous_element_sibling(candidate);
+                            }
+                            None => return false,
+                        }
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    /// Specificity as (id, class, type) counts packed for comparison.
+    pub fn specificity(&self) -> (u32, u32, u32) {
+        std::iter::once(&self.key)
+            .chain(self.ancestors.iter().map(|(_, c)| c))
+            .fold((0, 0, 0), |acc, compound| {
+                add_specificity(acc, compound.specificity())
+            })
+    }
+}
+
+fn add_specificity(a: (u32, u32, u32), b: (u32, u32, u32)) -> (u32, u32, u32) {
+    (a.0 + b.0, a.1 + b.1, a.2 + b.2)
+}
+
+impl CompoundSelector {
+    /// This compound's (id, class, type) specificity contribution.
+    /// Attribute selectors and pseudo-classes count in the class bucket;
+    /// `:not()` and `:is()` contribute their most specific argument,
+    /// `:where()` contributes nothing, per Selectors 4.
+    fn specificity(&self) -> (u32, u32, u32) {
+        let mut id = 0;
+        let mut class = 0;
+        let mut ty = 0;
+        if self.id.is_some() {
+            id += 1;
+        }
+        class += self.classes.len() as u32;
+        class += self.attributes.len() as u32;
+        if self.tag.is_some() {
+            ty += 1;
+        }
+        if self.pseudo_element.is_some() {
+            ty += 1;
+        }
+        let mut total = (id, class, ty);
+        for pseudo_class in &self.pseudo_classes {
+            let contribution = match pseudo_class {
+                PseudoClass::Where(_) => (0, 0, 0),
+                PseudoClass::Not(list) | PseudoClass::Is(list) => list
+                    .iter()
+                    .map(Selector::specificity)
+                    .max()
+                    .unwrap_or((0, 0, 0)),
+                _ => (0, 1, 0),
+            };
+            total = add_specificity(total, contribution);
+        }
+        total
+    }
+
+    /// Parse one compound selector, e.g. `div#main.item.active`.
+    pub fn parse(input: &str) -> Option<Self> {
+        let mut compound = Self::default();
+        let mut rest = input;
+        // Leading tag or universal selector.
+        if let Some(stripped) = rest.strip_prefix('*') {
+            rest = stripped;
+        } else if rest
+            .chars()
+            .next()
+            .map_or(false, |c| c.is_ascii_alphanumeric())
+        {
+            let end = rest
+                .find(['#', '.', ':', '['])
+                .unwrap_or(rest.len());
+            compound.tag = Some(rest[..end].to_ascii_lowercase());
+            rest = &rest[end..];
+        }
+        while !rest.is_empty() {
+            if let Some(tail) = rest.strip_prefix('[') {
+                let close = attribute_end(tail)?;
+                compound
+                    .attributes
+                    .push(AttributeSelector::parse(&tail[..close])?);
+                rest = &tail[close + 1..];
+                continue;
+            }
+            // Pseudo-elements and pseudo-classes; the legacy one-colon
+            // pseudo-element spelling is accepted for compatibility.
+            if let Some(tail) = rest.strip_prefix("::").or_else(|| rest.strip_prefix(':')) {
+                let end = tail.find(['#', '.', ':', '[', '(']).unwrap_or(tail.len());
+                let name = &tail[..end];
+                if tail[end..].starts_with('(') {
+                    let args_start = end + 1;
+                    let close = args_start + paren_end(&tail[args_start..])?;
+                    let args = &tail[args_start..close];
+                    let pseudo_class = match name {
+                        "nth-child" => PseudoClass::NthChild(AnB::parse(args)?),
+                        "nth-last-child" => PseudoClass::NthLastChild(AnB::parse(args)?),
+                        // `:not()` is unforgiving: an unparseable argument
+                        // invalidates the whole selector.
+                        "not" => PseudoClass::Not(
+                            split_selector_list(args)
+                                .into_iter()
+                                .map(|part| Selector::parse(part.trim()))
+                                .collect::<Option<Vec<_>>>()?,
+                        ),
+                        // `:is()`/`:where()` are forgiving: unparseable
+                        // arguments are dropped, the rest still match.
+                        "is" => PseudoClass::Is(parse_forgiving_list(args)),
+                        "where" => PseudoClass::Where(parse_forgiving_list(args)),
+                        _ => return None,
+                    };
+                    compound.pseudo_classes.push(pseudo_class);
+                    rest = &tail[close + 1..];
+                    continue;
+                }
+                match name {
+                    "before" => compound.pseudo_element = Some(PseudoElement::Before),
+                    "after" => compound.pseudo_element = Some(PseudoElement::After),
+                    "hover" => compound.pseudo_classes.push(PseudoClass::Hover),
+                    "focus" => compound.pseudo_classes.push(PseudoClass::Focus),
+                    "active" => compound.pseudo_classes.push(PseudoClass::Active),
+                    "first-child" => compound.pseudo_classes.push(PseudoClass::FirstChild),
+                    "last-child" => compound.pseudo_classes.push(PseudoClass::LastChild),
+                    // Other pseudo-classes are not supported yet.
+                    _ => return None,
+                }
+                rest = &tail[end..];
+                continue;
+            }
+            let (marker, tail) = rest.split_at(1);
+            let end = tail.find(['#', '.', ':', '[']).unwrap_or(tail.len());
+            let (name, remaining) = tail.split_at(end);
+            if name.is_empty() {
+                return None;
+            }
+            match marker {
+                "#" => compound.id = Some(name.to_owned()),
+                "." => compound.classes.push(name.to_owned()),
+                _ => return None,
+            }
+            rest = remaining;
+        }
+        if compound.tag.is_none()
+            && compound.id.is_none()
+            && compound.classes.is_empty()
+            && compound.attributes.is_empty()
+            && compound.pseudo_classes.is_empty()
+            && compound.pseudo_element.is_none()
+        {
+            return None;
+        }
+        Some(compound)
+    }
+
+    pub fn matches(&self, document: &Document, node: NodeId, state: &InteractionState) -> bool {
+        let Some(element) = document.element(node) else {
+            return false;
+        };
+        if let Some(tag) = &self.tag {
+            if element.tag_name != *tag {
+                return false;
+            }
+        }
+        if let Some(id) = &self.id {
+            if element.id() != Some(id.as_str()) {
+                return false;
+            }
+        }
+        if !self.classes.iter().all(|class| element.has_class(class)) {
+            return false;
+        }
+        if !self.attributes.iter().all(|attr| attr.matches(element)) {
+            return false;
+        }
+        self.pseudo_classes
+            .iter()
+            .all(|pseudo_class| pseudo_class.matches(document, node, state))
+    }
+}
+
+/// Offset of the `)` closing the parenthesis `input` starts inside,
+/// tolerating nested parentheses (`:not(:is(a, b))`).
+fn paren_end(input: &str) -> Option<usize> {
+    let mut depth = 1usize;
+    for (index, ch) in input.char_indices() {
+        match ch {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(index);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Split a selector list on top-level commas, leaving commas inside
+/// brackets, parentheses, and quotes alone.
+fn split_selector_list(input: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut depth = 0usize;
+    let mut quote = None;
+    for (index, ch) in input.char_indices() {
+        match quote {
+            Some(q) => {
+                if ch == q {
+                    quote = None;
+                }
+            }
+            None => match ch {
+                '"' | '\'' => quote = Some(ch),
+                '[' | '(' => depth += 1,
+                ']' | ')' => depth = depth.saturating_sub(1),
+                ',' if depth == 0 => {
+                    parts.push(&input[start..index]);
+                    start = index + 1;
+                }
+                _ => {}
+            },
+        }
+    }
+    parts.push(&input[start..]);
+    parts
+}
+
+/// Parse a comma-separated selector list forgivingly, for callers outside
+/// the cascade (the `querySelector` bindings): unparseable members are
+/// dropped rather than invalidating the whole list.
+pub fn parse_selector_list(input: &str) -> Vec<Selector> {
+    parse_forgiving_list(input)
+}
+
+/// Parse a forgiving selector list (`:is()`/`:where()` arguments):
+/// unparseable selectors are dropped rather than invalidating the rest.
+fn parse_forgiving_list(input: &str) -> Vec<Selector> {
+    split_selector_list(input)
+        .into_iter()
+        .filter_map(|part| Selector::parse(part.trim()))
+        .collect()
+}
+
+/// Offset of the `]` closing an attribute selector, skipping quoted
+/// values (which may contain `]`).
+fn attribute_end(input: &str) -> Option<usize> {
+    let mut quote = None;
+    for (index, ch) in input.char_indices() {
+        match quote {
+            Some(q) => {
+                if ch == q {
+                    quote = None;
+                }
+            }
+            None => match ch {
+                '"' | '\'' => quote = Some(ch),
+                ']' => return Some(index),
+                _ => {}
+            },
+        }
+    }
+    None
+}
+
+/// Parent for selector matching. Combinators never cross a shadow
+/// boundary, so the walk stops at the tree's shadow root.
+fn tree_parent(document: &Document, node: NodeId) -> Option<NodeId> {
+    document
+        .parent(node)
+        .filter(|&parent| !document.is_shadow_root(parent))
+}
+
+enum SelectorToken {
+    Compound(String),
+    Combinator(Combinator),
+}
+
+/// Split a complex selector into compounds and combinators, treating bare
+/// whitespace as the descendant combinator. Whitespace inside attribute
+/// brackets does not split, so `[title="a b"]` stays one compound.
+fn tokenize_selector(input: &str) -> Option<Vec<SelectorToken>> {
+    let mut tokens = Vec::new();
+    for part in split_selector(input)? {
+        match part.as_str() {
+            ">" => tokens.push(SelectorToken::Combinator(Combinator::Child)),
+            "+" => tokens.push(SelectorToken::Combinator(Combinator::NextSibling)),
+            "~" => tokens.push(SelectorToken::Combinator(Combinator::SubsequentSibling)),
+            _ => {
+                if matches!(tokens.last(), Some(SelectorToken::Compound(_))) {
+                    tokens.push(SelectorToken::Combinator(Combinator::Descendant));
+                }
+                tokens.push(SelectorToken::Compound(part));
+            }
+        }
+    }
+    if tokens.is_empty() {
+        return None;
+    }
+    Some(tokens)
+}
+
+/// Whitespace-split `input`, keeping bracketed and parenthesized (and
+/// quoted-within) runs intact. `None` on unbalanced brackets or quotes.
+fn split_selector(input: &str) -> Option<Vec<String>> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+    let mut quote = None;
+    for ch in input.chars() {
+        if let Some(q) = quote {
+            current.push(ch);
+            if ch == q {
+                quote = None;
+            }
+            continue;
+        }
+        match ch {
+            '"' | '\'' if depth > 0 => {
+                quote = Some(ch);
+                current.push(ch);
+            }
+            '[' | '(' => {
+                depth += 1;
+                current.push(ch);
+            }
+            ']' | ')' => {
+                depth = depth.checked_sub(1)?;
+                current.push(ch);
+            }
+            c if c.is_whitespace() && depth == 0 => {
+                if !current.is_empty() {
+                    parts.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if quote.is_some() || depth != 0 {
+        return None;
+    }
+    if !current.is_empty() {
+        parts.push(current);
+    }
+    Some(parts)
+}
+
+/// Parse a stylesheet's rules; unsupported selectors drop the selector,
+/// and rules left with no selectors are skipped.
+pub fn parse_stylesheet(source: &str) -> Stylesheet {
+    let source = strip_comments(source);
+    let mut rules = Vec::new();
+    parse_rules(&source, &[], &mut rules);
+    Stylesheet { rules }
+}
+
+/// Parse rules from `input`, tagging each with the enclosing `@media`
+/// conditions; recurses into nested `@media` blocks.
+fn parse_rules(input: &str, conditions: &[MediaQueryList], rules: &mut Vec<StyleRule>) {
+    let mut rest = input;
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            return;
+        }
+        if rest.starts_with('@') {
+            let brace = rest.find('{');
+            let semicolon = rest.find(';');
+            match (brace, semicolon) {
+                (Some(brace), semicolon) if semicolon.map_or(true, |s| brace < s) => {
+                    let prelude = rest[..brace].trim();
+                    let (block, after) = take_block(&rest[brace..]);
+                    if let Some(query) = prelude.strip_prefix("@media") {
+                        let mut nested = conditions.to_vec();
+                        nested.push(MediaQueryList::parse(query.trim()));
+                        parse_rules(block, &nested, rules);
+                    }
+                    // Other block at-rules are handled by dedicated passes
+                    // or skipped.
+                    rest = after;
+                }
+                (_, Some(semicolon)) => rest = &rest[semicolon + 1..],
+                (None, None) => return,
+            }
+            continue;
+        }
+        let Some(open) = rest.find('{') else { return };
+        let selector_text = rest[..open].trim();
+        let (body, after) = take_block(&rest[open..]);
+        rest = after;
+        let selectors: Vec<Selector> = split_selector_list(selector_text)
+            .into_iter()
+            .filter_map(|s| Selector::parse(s.trim()))
+            .collect();
+        if selectors.is_empty() {
+            continue;
+        }
+        rules.push(StyleRule {
+            selectors,
+            declarations: parse_declarations(body),
+            conditions: conditions.to_vec(),
+        });
+    }
+}
+
+/// `input` starts at `{`; returns the block's inside and the text after
+/// the matching close brace.
+fn take_block(input: &str) -> (&str, &str) {
+    let mut depth = 0usize;
+    for (index, ch) in input.char_indices() {
+        match ch {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return (&input[1..index], &input[index + 1..]);
+                }
+            }
+            _ => {}
+        }
+    }
+    (&input[1..], "")
+}
+
+/// Collect the declaration blocks of every `@font-face` rule in `source`,
+/// including those nested in `@media` blocks. Interpretation is up to
+/// [`super::fonts`].
+pub fn collect_font_faces(source: &str) -> Vec<Vec<Declaration>> {
+    let source = strip_comments(source);
+    let mut faces = Vec::new();
+    collect_font_faces_inner(&source, &mut faces);
+    faces
+}
+
+fn collect_font_faces_inner(input: &str, faces: &mut Vec<Vec<Declaration>>) {
+    let mut rest = input;
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            return;
+        }
+        let Some(open) = rest.find(['{', ';']) else {
+            return;
+        };
+        if rest.as_bytes()[open] == b';' {
+            rest = &rest[open + 1..];
+            continue;
+        }
+        let prelude = rest[..open].trim();
+        let (block, after) = take_block(&rest[open..]);
+        if prelude == "@font-face" {
+            faces.push(parse_declarations(block));
+        } else if prelude.starts_with("@media") {
+            collect_font_faces_inner(block, faces);
+        }
+        rest = after;
+    }
+}
+
+/// Collect every `@keyframes` rule in `source`, including those nested
+/// in `@media` blocks: the animation name and its keyframe blocks as
+/// (selector text, declarations) pairs. Offset parsing and interpolation
+/// are up to [`super::animation`].
+pub fn collect_keyframes(source: &str) -> Vec<(String, Vec<(String, Vec<Declaration>)>)> {
+    let source = strip_comments(source);
+    let mut rules = Vec::new();
+    collect_keyframes_inner(&source, &mut rules);
+    rules
+}
+
+fn collect_keyframes_inner(input: &str, rules: &mut Vec<(String, Vec<(String, Vec<Declaration>)>)>) {
+    let mut rest = input;
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            return;
+        }
+        let Some(open) = rest.find(['{', ';']) else {
+            return;
+        };
+        if rest.as_bytes()[open] == b';' {
+            rest = &rest[open + 1..];
+            continue;
+        }
+        let prelude = rest[..open].trim();
+        let (block, after) = take_block(&rest[open..]);
+        if let Some(name) = prelude.strip_prefix("@keyframes") {
+            let mut frames = Vec::new();
+            let mut body = block;
+            while let Some(frame_open) = body.find('{') {
+                let selector = body[..frame_open].trim().to_owned();
+                let (declarations, remaining) = take_block(&body[frame_open..]);
+                frames.push((selector, parse_declarations(declarations)));
+                body = remaining;
+            }
+            rules.push((name.trim().to_owned(), frames));
+        } else if prelude.starts_with("@media") {
+            collect_keyframes_inner(block, rules);
+        }
+        rest = after;
+    }
+}
+
+/// One `@import` statement found in a stylesheet.
+#[derive(Debug, Clone)]
+pub struct ImportRule {
+    pub href: String,
+    /// Media conditions after the URL, if any.
+    pub media: Option<MediaQueryList>,
+}
+
+/// Collect the `@import` statements of a stylesheet. Only imports before
+/// the first style rule are valid, per spec.
+pub fn collect_imports(source: &str) -> Vec<ImportRule> {
+    let source = strip_comments(source);
+    let mut imports = Vec::new();
+    let mut rest = source.as_str();
+    loop {
+        rest = rest.trim_start();
+        let Some(statement) = rest.strip_prefix("@import") else {
+            // @charset / @layer statements may legally precede imports;
+            // skip other semicolon-terminated at-rules, stop at anything
+            // else.
+            if rest.starts_with('@') {
+                match rest.find(';') {
+                    Some(end) if rest.find('{').map_or(true, |b| end < b) => {
+                        rest = &rest[end + 1..];
+                        continue;
+                    }
+                    _ => return imports,
+                }
+            }
+            return imports;
+        };
+        let Some(end) = statement.find(';') else {
+            return imports;
+        };
+        if let Some(import) = parse_import(statement[..end].trim()) {
+            imports.push(import);
+        }
+        rest = &statement[end + 1..];
+    }
+}
+
+/// Parse the prelude of one `@import`: `url(...)` or a quoted string,
+/// optionally followed by a media query list.
+fn parse_import(prelude: &str) -> Option<ImportRule> {
+    let (href, rest) = if let Some(after) = prelude.strip_prefix("url(") {
+        let close = after.find(')')?;
+        (after[..close].trim_matches(['"', '\'']), &after[close + 1..])
+    } else if prelude.starts_with('"') || prelude.starts_with('\'') {
+        let quote = prelude.chars().next()?;
+        let close = prelude[1..].find(quote)? + 1;
+        (&prelude[1..close], &prelude[close + 1..])
+    } else {
+        return None;
+    };
+    if href.is_empty() {
+        return None;
+    }
+    let media = match rest.trim() {
+        "" => None,
+        conditions => Some(MediaQueryList::parse(conditions)),
+    };
+    Some(ImportRule {
+        href: href.to_owned(),
+        media,
+    })
+}
+
+/// Parse the inside of a declaration block.
+pub fn parse_declarations(body: &str) -> Vec<Declaration> {
+    body.split(';')
+        .filter_map(|declaration| {
+            let (name, value) = declaration.split_once(':')?;
+            let name = name.trim().to_ascii_lowercase();
+            let mut value = value.trim();
+            let mut important = false;
+            if let Some(stripped) = value.strip_suffix("!important") {
+                important = true;
+                value = stripped.trim_end();
+            }
+            if name.is_empty() || value.is_empty() {
+                return None;
+            }
+            Some(Declaration {
+                name,
+                value: value.to_owned(),
+                important,
+            })
+        })
+        .collect()
+}
+
+fn strip_comments(source: &str) -> String {
+    let mut out = String::with_capacity(source.len());
+    let mut rest = source;
+    while let Some(start) = rest.find("/*") {
+        out.push_str(&rest[..start]);
+        match rest[start + 2..].find("*/") {
+            Some(end) => rest = &rest[start + 2 + end + 2..],
+            None => return out,
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// FNV-1a hash of an identifier, for the ancestor filter. Tag names, ids
+/// and class names share one hash space; collisions only cost a wasted
+/// full match, never a missed one.
+pub(crate) fn identifier_hash(name: &str) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in name.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
+
+/// A 256-bit bloom filter over the identifiers (tag, id, classes) of an
+/// element's ancestor chain, as Blink and WebKit keep during their style
+/// walk. A selector whose ancestor compounds name an identifier not in
+/// the filter cannot match, which rejects most rules without touching the
+/// tree.
+#[derive(Debug, Clone, Copy)]
+pub struct AncestorFilter {
+    bits: [u64; 4],
+}
+
+impl AncestorFilter {
+    /// Build the filter for `node`'s ancestors, following the same parent
+    /// relation combinators walk (stopping at shadow roots).
+    pub fn for_element(document: &Document, node: NodeId) -> Self {
+        let mut filter = Self { bits: [0; 4] };
+        let mut current = tree_parent(document, node);
+        while let Some(ancestor) = current {
+            if let Some(element) = document.element(ancestor) {
+                filter.insert(identifier_hash(&element.tag_name));
+                if let Some(id) = element.id() {
+                    filter.insert(identifier_hash(id));
+                }
+                for class in element.classes() {
+                    filter.insert(identifier_hash(class));
+                }
+            }
+            current = tree_parent(document, ancestor);
+        }
+        filter
+    }
+
+    /// Set two probe bits derived from opposite halves of the hash.
+    fn insert(&mut self, hash: u64) {
+        let (a, b) = (hash as u32 as u64 % 256, (hash >> 32) % 256);
+        self.bits[(a / 64) as usize] |= 1 << (a % 64);
+        self.bits[(b / 64) as usize] |= 1 << (b % 64);
+    }
+
+    fn may_contain(&self, hash: u64) -> bool {
+        let (a, b) = (hash as u32 as u64 % 256, (hash >> 32) % 256);
+        self.bits[(a / 64) as usize] & (1 << (a % 64)) != 0
+            && self.bits[(b / 64) as usize] & (1 << (b % 64)) != 0
+    }
+}
+
+impl Selector {
+    /// Whether the ancestor filter proves this selector cannot match.
+    /// Only tag/id/class parts of ancestor compounds are consulted;
+    /// attribute and pseudo-class parts fall through to the full walk.
+    pub fn fast_reject(&self, filter: &AncestorFilter) -> bool {
+        for (_, compound) in &self.ancestors {
+            if let Some(tag) = &compound.tag {
+                if !filter.may_contain(identifier_hash(tag)) {
+                    return true;
+                }
+            }
+            if let Some(id) = &compound.id {
+                if !filter.may_contain(identifier_hash(id)) {
+                    return true;
+                }
+            }
+            for class in &compound.classes {
+                if !filter.may_contain(identifier_hash(class)) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+}
+
+/// Rule positions bucketed by each selector's rightmost compound: an
+/// element only needs the rules keyed by its own id, classes and tag
+/// (plus the universal bucket), instead of every rule in the sheet.
+#[derive(Debug, Clone, Default)]
+pub struct RuleIndex {
+    by_id: std::collections::HashMap<String, Vec<usize>>,
+    by_class: std::collections::HashMap<String, Vec<usize>>,
+    by_tag: std::collections::HashMap<String, Vec<usize>>,
+    /// Rules whose key compound has no id, class or tag.
+    universal: Vec<usize>,
+}
+
+impl RuleIndex {
+    pub fn build(sheet: &Stylesheet) -> Self {
+        let mut index = Self::default();
+        for (position, rule) in sheet.rules.iter().enumerate() {
+            for selector in &rule.selectors {
+                let key = &selector.key;
+                let bucket = if let Some(id) = &key.id {
+                    index.by_id.entry(id.clone()).or_default()
+                } else if let Some(class) = key.classes.first() {
+                    index.by_class.entry(class.clone()).or_default()
+                } else if let Some(tag) = &key.tag {
+                    index.by_tag.entry(tag.clone()).or_default()
+                } else {
+                    &mut index.universal
+                };
+                if bucket.last() != Some(&position) {
+                    bucket.push(position);
+                }
+            }
+        }
+        index
+    }
+
+    /// Positions of the rules that could match `element`, in source
+    /// order. A rule reachable through several buckets appears once.
+    pub fn candidates(&self, element: &super::dom::ElementData) -> Vec<usize> {
+        let mut out = self.universal.clone();
+        if let Some(id) = element.id() {
+            if let Some(bucket) = self.by_id.get(id) {
+                out.extend_from_slice(bucket);
+            }
+        }
+        for class in element.classes() {
+            if let Some(bucket) = self.by_class.get(class) {
+                out.extend_from_slice(bucket);
+            }
+        }
+        if let Some(bucket) = self.by_tag.get(&element.tag_name) {
+            out.extend_from_slice(bucket);
+        }
+        out.sort_unstable();
+        out.dedup();
+        out
+    }
+}
@@ -0,0 +1,176 @@
+//! Dirty tracking: re-lay-out only what changed, repaint only where.
+//!
+//! Style recalculation and DOM mutation report what they touched through
+//! [`DirtyTracker::mark`]; [`DirtyTracker::flush`] then updates an
+//! existing layout tree with the least work that is still correct.
+//! Paint-only changes skip layout entirely; layout changes first try
+//! [`super::layout::relayout_subtree`], and only when a subtree's size
+//! actually changes does the whole page re-lay-out. Keeping this path
+//! cheap is what holds interactive updates inside the 1500 ms page-load
+//! budget on style-churn-heavy pages.
+
+use std::collections::{HashMap, HashSet};
+
+use super::dom::{Document, NodeId};
+use super::frame::FrameTree;
+use super::layout::{self, LayoutBox, Rect, TextMeasurer};
+use super::media::MediaEnvironment;
+use super::style::StyleEngine;
+
+/// How a mutation can affect layout, from cheapest to most invasive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutChange {
+    /// Only paint output changes (color, visibility): repaint the node's
+    /// rect, keep its geometry.
+    PaintOnly,
+    /// The node's own box may change (font, width, padding): re-lay-out
+    /// its subtree.
+    SelfLayout,
+    /// Children were added, removed or reordered beneath the node.
+    Subtree,
+}
+
+/// Accumulates dirty bits between frames.
+#[derive(Debug, Default)]
+pub struct DirtyTracker {
+    paint: HashSet<NodeId>,
+    layout: HashSet<NodeId>,
+}
+
+impl DirtyTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn mark(&mut self, node: NodeId, change: LayoutChange) {
+        match change {
+            LayoutChange::PaintOnly => {
+                self.paint.insert(node);
+            }
+            LayoutChange::SelfLayout | LayoutChange::Subtree => {
+                self.layout.insert(node);
+            }
+        }
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.paint.is_empty() && self.layout.is_empty()
+    }
+
+    pub fn needs_layout(&self) -> bool {
+        !self.layout.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.paint.clear();
+        self.layout.clear();
+    }
+
+    /// Apply the accumulated dirt to `laid`, re-laying-out as little as
+    /// possible, and return the rects the compositor must repaint. The
+    /// tracker is clean afterwards.
+    #[allow(clippy::too_many_arguments)]
+    pub fn flush(
+        &mut self,
+        laid: &mut LayoutBox,
+        document: &Document,
+        styles: &StyleEngine,
+        env: &MediaEnvironment,
+        measurer: &dyn TextMeasurer,
+        frames: &FrameTree,
+    ) -> Vec<Rect> {
+        if self.is_clean() {
+            return Vec::new();
+        }
+        let mut rects = Vec::new();
+        for &node in &self.paint {
+            if let Some(rect) = layout::box_rect(laid, node) {
+                rects.push(rect);
+            }
+        }
+        let mut full = false;
+        for &node in &self.layout {
+            match layout::relayout_subtree(laid, node, document, styles, env, measurer, frames) {
+                Some((old, new)) => {
+                    rects.push(old);
+                    rects.push(new);
+                }
+                None => {
+                    full = true;
+                    break;
+                }
+            }
+        }
+        if full {
+            let rebuilt =
+                layout::layout_document_with_frames(document, styles, env, measurer, frames);
+            let previous = std::mem::replace(laid, rebuilt);
+            rects = diff_rects(&previous, laid);
+        }
+        self.clear();
+        coalesce(rects)
+    }
+}
+
+/// The rects that differ between two layouts of the same document: every
+/// box whose geometry changed contributes its old and new rect, and boxes
+/// that appeared or disappeared contribute theirs.
+fn diff_rects(old: &LayoutBox, new: &LayoutBox) -> Vec<Rect> {
+    let mut old_rects: HashMap<NodeId, Rect> = HashMap::new();
+    collect_rects(old, &mut old_rects);
+    let mut rects = Vec::new();
+    diff_walk(new, &mut old_rects, &mut rects);
+    // Whatever is left in the map no longer has a box.
+    rects.extend(old_rects.into_values());
+    rects
+}
+
+fn collect_rects(laid: &LayoutBox, out: &mut HashMap<NodeId, Rect>) {
+    if let Some(node) = laid.node {
+        out.insert(node, laid.rect);
+    }
+    for child in &laid.children {
+        collect_rects(child, out);
+    }
+}
+
+fn diff_walk(laid: &LayoutBox, old: &mut HashMap<NodeId, Rect>, out: &mut Vec<Rect>) {
+    if let Some(node) = laid.node {
+        match old.remove(&node) {
+            Some(previous) if previous == laid.rect => {}
+            Some(previous) => {
+                out.push(previous);
+                out.push(laid.rect);
+            }
+            None => out.push(laid.rect),
+        }
+    }
+    for child in &laid.children {
+        diff_walk(child, old, out);
+    }
+}
+
+/// Past this count, repainting one bounding rect beats tracking many.
+const MAX_DIRTY_RECTS: usize = 32;
+
+/// Drop empty rects and collapse an excessive list into its bounding box.
+fn coalesce(rects: Vec<Rect>) -> Vec<Rect> {
+    let mut rects: Vec<Rect> = rects
+        .into_iter()
+        .filter(|rect| rect.width > 0.0 && rect.height > 0.0)
+        .collect();
+    if rects.len() > MAX_DIRTY_RECTS {
+        let union = rects.iter().skip(1).fold(rects[0], |a, b| {
+            let x = a.x.min(b.x);
+            let y = a.y.min(b.y);
+            Rect {
+                x,
+                y,
+                width: (a.x + a.width).max(b.x + b.width) - x,
+                height: (a.y + a.height).max(b.y + b.height) - y,
+            }
+        });
+        rects = vec![union];
+    }
+    rects
+}
@@ -0,0 +1,388 @@
+//! DOM tree, arena-allocated.
+//!
+//! Nodes live in a flat `Vec` inside [`Document`] and refer to each other
+//! by [`NodeId`]. That keeps the tree `Send`, makes traversal cheap, and
+//! sidesteps ownership cycles entirely.
+
+use std::fmt;
+
+/// Index of a node in its document's arena.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(pub usize);
+
+/// What a node is.
+#[derive(Debug, Clone)]
+pub enum NodeData {
+    Document,
+    Element(ElementData),
+    Text(String),
+    Comment(String),
+    /// Root of a shadow tree attached to its parent (the host element).
+    /// Never in the host's child list; see [`Document::attach_shadow`].
+    ShadowRoot(ShadowRootMode),
+}
+
+/// The `mode` a shadow root was attached with. Closed roots are not
+/// reachable from the host through script (`host.shadowRoot` is null).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShadowRootMode {
+    Open,
+    Closed,
+}
+
+/// The document's rendering mode, decided by its DOCTYPE. Layout
+/// consults it for the handful of legacy behaviors that differ; see the
+/// quirks checks in [`super::layout`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QuirksMode {
+    /// A modern DOCTYPE (`<!doctype html>`), or a script-created
+    /// document: standards rendering.
+    #[default]
+    NoQuirks,
+    /// A transitional/frameset DOCTYPE with a system identifier: standards
+    /// rendering except for a few table-sizing legacies.
+    LimitedQuirks,
+    /// Missing or ancient DOCTYPE: legacy box sizing and line heights.
+    Quirks,
+}
+
+/// Tag name and attributes of an element node.
+#[derive(Debug, Clone)]
+pub struct ElementData {
+    /// Lowercased tag name.
+    pub tag_name: String,
+    /// Attributes in source order; names lowercased.
+    pub attributes: Vec<(String, String)>,
+}
+
+impl ElementData {
+    pub fn new(tag_name: &str) -> Self {
+        Self {
+            tag_name: tag_name.to_ascii_lowercase(),
+            attributes: Vec::new(),
+        }
+    }
+
+    /// Value of attribute `name`, if present.
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn set_attr(&mut self, name: &str, value: &str) {
+        let name = name.to_ascii_lowercase();
+        match self.attributes.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, v)) => *v = value.to_owned(),
+            None => self.attributes.push((name, value.to_owned())),
+        }
+    }
+
+    pub fn id(&self) -> Option<&str> {
+        self.attr("id")
+    }
+
+    /// The element's classes, split on whitespace.
+    pub fn classes(&self) -> impl Iterator<Item = &str> {
+        self.attr("class").unwrap_or_default().split_whitespace()
+    }
+
+    pub fn has_class(&self, class: &str) -> bool {
+        self.classes().any(|c| c == class)
+    }
+}
+
+/// One node in the arena.
+#[derive(Debug, Clone)]
+pub struct Node {
+    pub parent: Option<NodeId>,
+    pub children: Vec<NodeId>,
+    pub data: NodeData,
+}
+
+/// An entire page's DOM.
+#[derive(Debug, Clone)]
+pub struct Document {
+    nodes: Vec<Node>,
+    /// Host element → shadow root node.
+    shadow_roots: std::collections::HashMap<NodeId, NodeId>,
+    quirks_mode: QuirksMode,
+}
+
+impl Document {
+    /// An empty document containing only the document node.
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![Node {
+                parent: None,
+                children: Vec::new(),
+                data: NodeData::Document,
+            }],
+            shadow_roots: std::collections::HashMap::new(),
+            quirks_mode: QuirksMode::default(),
+        }
+    }
+
+    pub fn quirks_mode(&self) -> QuirksMode {
+        self.quirks_mode
+    }
+
+    /// Set by the parser from the DOCTYPE; fixed for the document's life.
+    pub fn set_quirks_mode(&mut self, mode: QuirksMode) {
+        self.quirks_mode = mode;
+    }
+
+    pub fn root(&self) -> NodeId {
+        NodeId(0)
+    }
+
+    pub fn node(&self, id: NodeId) -> &Node {
+        &self.nodes[id.0]
+    }
+
+    pub fn node_mut(&mut self, id: NodeId) -> &mut Node {
+        &mut self.nodes[id.0]
+    }
+
+    /// Append a new node under `parent` and return its id.
+    pub fn append(&mut self, parent: NodeId, data: NodeData) -> NodeId {
+        let id = NodeId(self.nodes.len());
+        self.nodes.push(Node {
+            parent: Some(parent),
+            children: Vec::new(),
+            data,
+        });
+        self.nodes[parent.0].children.push(id);
+        id
+    }
+
+    /// Create a node with no parent (scripted `createElement`); attach
+    /// it with [`Document::attach`]. Arena slots are never reclaimed, so
+    /// a node that stays detached just goes unused.
+    pub fn create(&mut self, data: NodeData) -> NodeId {
+        let id = NodeId(self.nodes.len());
+        self.nodes.push(Node {
+            parent: None,
+            children: Vec::new(),
+            data,
+        });
+        id
+    }
+
+    /// Append `child` under `parent`, detaching it from any current
+    /// parent first (`appendChild` moves, per spec).
+    pub fn attach(&mut self, parent: NodeId, child: NodeId) {
+        self.detach(child);
+        self.nodes[child.0].parent = Some(parent);
+        self.nodes[parent.0].children.push(child);
+    }
+
+    /// Remove `child` from its parent's child list. The subtree stays
+    /// intact and can be re-attached.
+    pub fn detach(&mut self, child: NodeId) {
+        if let Some(parent) = self.nodes[child.0].parent.take() {
+            self.nodes[parent.0].children.retain(|&c| c != child);
+        }
+    }
+
+    /// The element data of `id`, if it is an element.
+    pub fn element(&self, id: NodeId) -> Option<&ElementData> {
+        match &self.node(id).data {
+            NodeData::Element(element) => Some(element),
+            _ => None,
+        }
+    }
+
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.node(id).parent
+    }
+
+    /// The nearest preceding sibling of `id` that is an element.
+    pub fn previous_element_sibling(&self, id: NodeId) -> Option<NodeId> {
+        let parent = self.parent(id)?;
+        let siblings = &self.node(parent).children;
+        let position = siblings.iter().position(|&c| c == id)?;
+        siblings[..position]
+            .iter()
+            .rev()
+            .copied()
+            .find(|&sibling| self.element(sibling).is_some())
+    }
+
+    /// The nearest following sibling of `id` that is an element.
+    pub fn next_element_sibling(&self, id: NodeId) -> Option<NodeId> {
+        let parent = self.parent(id)?;
+        let siblings = &self.node(parent).children;
+        let position = siblings.iter().position(|&c| c == id)?;
+        siblings[position + 1..]
+            .iter()
+            .copied()
+            .find(|&sibling| self.element(sibling).is_some())
+    }
+
+    /// All node ids in document (pre-)order.
+    pub fn descendants(&self, id: NodeId) -> Vec<NodeId> {
+        let mut out = Vec::new();
+        let mut stack = vec![id];
+        while let Some(current) = stack.pop() {
+            out.push(current);
+            for &child in self.node(current).children.iter().rev() {
+                stack.push(child);
+            }
+        }
+        out
+    }
+
+    /// Attach a shadow root to `host` (the `attachShadow` primitive, also
+    /// used for declarative shadow DOM). The root's parent is the host
+    /// but it is not a light-tree child, so [`Document::descendants`]
+    /// never enters it. Re-attaching returns the existing root.
+    pub fn attach_shadow(&mut self, host: NodeId, mode: ShadowRootMode) -> NodeId {
+        if let Some(&root) = self.shadow_roots.get(&host) {
+            return root;
+        }
+        let id = NodeId(self.nodes.len());
+        self.nodes.push(Node {
+            parent: Some(host),
+            children: Vec::new(),
+            data: NodeData::ShadowRoot(mode),
+        });
+        self.shadow_roots.insert(host, id);
+        id
+    }
+
+    /// The shadow root attached to `host`, regardless of mode. Script
+    /// visibility of closed roots is the bindings' concern.
+    pub fn shadow_root(&self, host: NodeId) -> Option<NodeId> {
+        self.shadow_roots.get(&host).copied()
+    }
+
+    pub fn is_shadow_root(&self, id: NodeId) -> bool {
+        matches!(self.node(id).data, NodeData::ShadowRoot(_))
+    }
+
+    /// Every (host, shadow root) pair, in host order.
+    pub fn shadow_hosts(&self) -> Vec<(NodeId, NodeId)> {
+        let mut hosts: Vec<_> = self
+            .shadow_roots
+            .iter()
+            .map(|(&host, &root)| (host, root))
+            .collect();
+        hosts.sort();
+        hosts
+    }
+
+    /// The shadow root whose tree `id` lives in, if any.
+    pub fn containing_shadow_root(&self, id: NodeId) -> Option<NodeId> {
+        let mut current = Some(id);
+        while let Some(candidate) = current {
+            if self.is_shadow_root(candidate) {
+                return Some(candidate);
+            }
+            current = self.parent(candidate);
+        }
+        None
+    }
+
+    /// Children in the flattened (composed) tree: a shadow host renders
+    /// its shadow root's children instead of its light children. `<slot>`
+    /// projection is not implemented; light children of a host simply do
+    /// not render.
+    pub fn composed_children(&self, id: NodeId) -> &[NodeId] {
+        match self.shadow_roots.get(&id) {
+            Some(&root) => &self.node(root).children,
+            None => &self.node(id).children,
+        }
+    }
+
+    /// All node ids under `id` in the flattened tree, pre-order.
+    pub fn composed_descendants(&self, id: NodeId) -> Vec<NodeId> {
+        let mut out = Vec::new();
+        let mut stack = vec![id];
+        while let Some(current) = stack.pop() {
+            out.push(current);
+            for &child in self.composed_children(current).iter().rev() {
+                stack.push(child);
+            }
+        }
+        out
+    }
+
+    /// Concatenated text content under `id`.
+    pub fn text_content(&self, id: NodeId) -> String {
+        let mut out = String::new();
+        for descendant in self.descendants(id) {
+            if let NodeData::Text(text) = &self.node(descendant).data {
+                out.push_str(text);
+            }
+        }
+        out
+    }
+
+    /// The markup of `id`'s children (the `innerHTML` view). A plain
+    /// re-serialisation: attributes in stored order, no entity escaping
+    /// beyond the structural characters.
+    pub fn inner_html(&self, id: NodeId) -> String {
+        let mut out = String::new();
+        for &child in &self.node(id).children {
+            self.write_markup(child, &mut out);
+        }
+        out
+    }
+
+    fn write_markup(&self, id: NodeId, out: &mut String) {
+        match &self.node(id).data {
+            NodeData::Text(text) => out.push_str(text),
+            NodeData::Comment(text) => {
+                out.push_str("<!--");
+                out.push_str(text);
+                out.push_str("-->");
+            }
+            NodeData::Element(element) => {
+                out.push('<');
+                out.push_str(&element.tag_name);
+                for (name, value) in &element.attributes {
+                    out.push(' ');
+                    out.push_str(name);
+                    out.push_str("=\"");
+                    out.push_str(&value.replace('"', "&quot;"));
+                    out.push('"');
+                }
+                out.push('>');
+                for &child in &self.node(id).children {
+                    self.write_markup(child, out);
+                }
+                out.push_str("</");
+                out.push_str(&element.tag_name);
+                out.push('>');
+            }
+            NodeData::Document | NodeData::ShadowRoot(_) => {
+                for &child in &self.node(id).children {
+                    self.write_markup(child, out);
+                }
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        // The document node always exists.
+        false
+    }
+}
+
+impl Default for Document {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for NodeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
@@ -0,0 +1,307 @@
+//! Web fonts: `@font-face` interpretation, loading, and selection.
+//!
+//! Parsed `@font-face` blocks become [`FontFaceRule`]s; the loader fetches
+//! their files through the network stack and registers the bytes with the
+//! [`FontRegistry`], which the text rasterizer consults per glyph. When a
+//! face declares a `unicode-range`, characters outside it fall through to
+//! the next matching face, then to the default font.
+
+use std::sync::Arc;
+
+use crate::network::{NetworkStack, Request, ResourcePriority};
+
+use super::css::Declaration;
+use super::layout::{FontMetrics, HeuristicMeasurer, TextMeasurer, TextStyle};
+use super::loader::resolve_url;
+
+/// One source in an `@font-face` `src` list, in preference order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FontSource {
+    Url {
+        url: String,
+        /// `format(...)` hint, lowercased.
+        format: Option<String>,
+    },
+    Local(String),
+}
+
+/// A parsed `@font-face` rule.
+#[derive(Debug, Clone)]
+pub struct FontFaceRule {
+    /// Family name, lowercased for matching.
+    pub family: String,
+    pub sources: Vec<FontSource>,
+    pub weight: u16,
+    pub italic: bool,
+    /// Inclusive codepoint ranges this face covers; empty means all.
+    pub unicode_ranges: Vec<(u32, u32)>,
+}
+
+impl FontFaceRule {
+    /// Interpret the declarations of one `@font-face` block. `None` when
+    /// the required `font-family` or `src` descriptors are missing.
+    pub fn from_declarations(declarations: &[Declaration]) -> Option<Self> {
+        let mut family = None;
+        let mut sources = Vec::new();
+        let mut weight = 400;
+        let mut italic = false;
+        let mut unicode_ranges = Vec::new();
+
+        for declaration in declarations {
+            match declaration.name.as_str() {
+                "font-family" => {
+                    family = Some(
+                        declaration
+                            .value
+                            .trim_matches(['"', '\''])
+                            .to_ascii_lowercase(),
+                    );
+                }
+                "src" => sources = parse_src(&declaration.value),
+                "font-weight" => {
+                    weight = match declaration.value.as_str() {
+                        "normal" => 400,
+                        "bold" => 700,
+                        value => value.parse().unwrap_or(400),
+                    };
+                }
+                "font-style" => italic = declaration.value.starts_with("italic"),
+                "unicode-range" => unicode_ranges = parse_unicode_ranges(&declaration.value),
+                _ => {}
+            }
+        }
+
+        let family = family?;
+        if sources.is_empty() {
+            return None;
+        }
+        Some(Self {
+            family,
+            sources,
+            weight,
+            italic,
+            unicode_ranges,
+        })
+    }
+
+    /// Whether this face supplies a glyph for `ch` per its unicode-range.
+    pub fn covers(&self, ch: char) -> bool {
+        if self.unicode_ranges.is_empty() {
+            return true;
+        }
+        let code = ch as u32;
+        self.unicode_ranges
+            .iter()
+            .any(|&(start, end)| (start..=end).contains(&code))
+    }
+}
+
+/// A registered face with its fetched font file.
+pub struct LoadedFont {
+    pub rule: FontFaceRule,
+    pub data: Vec<u8>,
+}
+
+/// The faces available to the text rasterizer.
+#[derive(Default)]
+pub struct FontRegistry {
+    faces: Vec<LoadedFont>,
+}
+
+impl FontRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, rule: FontFaceRule, data: Vec<u8>) {
+        self.faces.push(LoadedFont { rule, data });
+    }
+
+    /// Pick the face to rasterize `ch` in `family`: faces of the family
+    /// covering the character, nearest declared weight first. `None` sends
+    /// the rasterizer to its built-in default font.
+    pub fn select(&self, family: &str, ch: char, weight: u16, italic: bool) -> Option<&LoadedFont> {
+        let family = family.trim_matches(['"', '\'']).to_ascii_lowercase();
+        self.faces
+            .iter()
+            .filter(|font| font.rule.family == family && font.rule.covers(ch))
+            .min_by_key(|font| {
+                let weight_distance = font.rule.weight.abs_diff(weight);
+                let style_penalty = if font.rule.italic == italic { 0 } else { 1000 };
+                u32::from(weight_distance) + style_penalty
+            })
+    }
+
+    pub fn len(&self) -> usize {
+        self.faces.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.faces.is_empty()
+    }
+}
+
+/// Measures text against the glyph tables of registered faces, so
+/// wrapping and line heights come from actual advances rather than an
+/// average-width guess. Falls back to [`HeuristicMeasurer`] for families
+/// with no registered face.
+pub struct FontMeasurer<'a> {
+    registry: &'a FontRegistry,
+    fallback: HeuristicMeasurer,
+}
+
+impl<'a> FontMeasurer<'a> {
+    pub fn new(registry: &'a FontRegistry) -> Self {
+        Self {
+            registry,
+            fallback: HeuristicMeasurer,
+        }
+    }
+
+    /// The parsed face a run in `style` shapes with. Face selection is
+    /// per run, keyed on its first character; per-cluster fallback is
+    /// left to the rasterizer.
+    fn face_for(&self, text: &str, style: &TextStyle) -> Option<ttf_parser::Face<'a>> {
+        let ch = text.chars().next().unwrap_or('a');
+        let font = self
+            .registry
+            .select(&style.family, ch, style.weight, style.italic)?;
+        ttf_parser::Face::parse(&font.data, 0).ok()
+    }
+}
+
+impl TextMeasurer for FontMeasurer<'_> {
+    fn width(&self, text: &str, style: &TextStyle) -> f32 {
+        let Some(face) = self.face_for(text, style) else {
+            return self.fallback.width(text, style);
+        };
+        let scale = style.size / f32::from(face.units_per_em());
+        text.chars()
+            .map(|ch| {
+                face.glyph_index(ch)
+                    .and_then(|glyph| face.glyph_hor_advance(glyph))
+                    .map_or(style.size * 0.5, |advance| f32::from(advance) * scale)
+            })
+            .sum()
+    }
+
+    fn metrics(&self, style: &TextStyle) -> FontMetrics {
+        let Some(face) = self.face_for("x", style) else {
+            return self.fallback.metrics(style);
+        };
+        let scale = style.size / f32::from(face.units_per_em());
+        FontMetrics {
+            ascent: f32::from(face.ascender()) * scale,
+            // Descender is negative in font units.
+            descent: f32::from(-face.descender()) * scale,
+        }
+    }
+}
+
+/// Formats the rasterizer can decode.
+const SUPPORTED_FORMATS: &[&str] = &["woff2", "woff", "truetype", "opentype"];
+
+/// Fetches `@font-face` sources and fills a [`FontRegistry`].
+pub struct FontLoader {
+    stack: Arc<NetworkStack>,
+}
+
+impl FontLoader {
+    pub fn new(stack: Arc<NetworkStack>) -> Self {
+        Self { stack }
+    }
+
+    /// Load every `@font-face` in `stylesheet_source` into `registry`,
+    /// trying each rule's sources in order until one fetches. Local
+    /// sources are skipped (no system font lookup yet); faces whose
+    /// sources all fail are dropped and text falls back to the default
+    /// font.
+    pub async fn load_faces(
+        &self,
+        stylesheet_source: &str,
+        base_url: &str,
+        registry: &mut FontRegistry,
+    ) {
+        for block in super::css::collect_font_faces(stylesheet_source) {
+            let Some(rule) = FontFaceRule::from_declarations(&block) else {
+                continue;
+            };
+            for source in &rule.sources {
+                let FontSource::Url { url, format } = source else {
+                    continue;
+                };
+                if let Some(format) = format {
+                    if !SUPPORTED_FORMATS.contains(&format.as_str()) {
+                        continue;
+                    }
+                }
+                let url = resolve_url(base_url, url);
+                let request = Request::get(url);
+                if let Ok(response) = self
+                    .stack
+                    .fetch_prioritized(request, ResourcePriority::High)
+                    .await
+                {
+                    if response.is_success() {
+                        registry.register(rule.clone(), response.body);
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Parse a `src` list: comma-separated `url(...) format(...)` or
+/// `local(...)` entries, in preference order.
+fn parse_src(value: &str) -> Vec<FontSource> {
+    value
+        .split(',')
+        .filter_map(|entry| {
+            let entry = entry.trim();
+            if let Some(after) = entry.strip_prefix("url(") {
+                let close = after.find(')')?;
+                let url = after[..close].trim_matches(['"', '\'']).to_owned();
+                let format = after[close + 1..]
+                    .trim()
+                    .strip_prefix("format(")
+                    .and_then(|f| f.find(')').map(|end| &f[..end]))
+                    .map(|f| f.trim_matches(['"', '\'']).to_ascii_lowercase());
+                Some(FontSource::Url { url, format })
+            } else if let Some(after) = entry.strip_prefix("local(") {
+                let close = after.find(')')?;
+                Some(FontSource::Local(
+                    after[..close].trim_matches(['"', '\'']).to_owned(),
+                ))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Parse `unicode-range`: `U+26`, `U+0-7F`, `U+4??` wildcard forms,
+/// comma-separated.
+fn parse_unicode_ranges(value: &str) -> Vec<(u32, u32)> {
+    value
+        .split(',')
+        .filter_map(|range| {
+            let range = range.trim();
+            let hex = range
+                .strip_prefix("U+")
+                .or_else(|| range.strip_prefix("u+"))?;
+            if let Some((start, end)) = hex.split_once('-') {
+                let start = u32::from_str_radix(start, 16).ok()?;
+                let end = u32::from_str_radix(end, 16).ok()?;
+                return Some((start, end));
+            }
+            if hex.contains('?') {
+                let start = u32::from_str_radix(&hex.replace('?', "0"), 16).ok()?;
+                let end = u32::from_str_radix(&hex.replace('?', "F"), 16).ok()?;
+                return Some((start, end));
+            }
+            let code = u32::from_str_radix(hex, 16).ok()?;
+            Some((code, code))
+        })
+        .collect()
+}
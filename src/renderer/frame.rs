@@ -0,0 +1,218 @@
+//! Nested browsing contexts: the documents loaded into `<iframe>`s.
+//!
+//! Each frame gets its own [`Document`] and [`StyleEngine`] — styles never
+//! leak across a frame boundary — and may nest frames of its own, forming
+//! a tree. [`FrameLoader`] fetches the frame documents through the network
+//! stack and enforces the embedding rules (`X-Frame-Options`, the
+//! `sandbox` attribute); layout then places each frame document inside its
+//! `<iframe>` box, clipped to it.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use crate::network::{NetworkStack, Request, ResourcePriority};
+
+use super::dom::{Document, NodeId};
+use super::html;
+use super::loader::resolve_url;
+use super::style::{self, StyleEngine};
+
+/// Restrictions from an `<iframe sandbox>` attribute. An empty attribute
+/// applies every restriction; each `allow-*` token lifts one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SandboxFlags {
+    pub allow_scripts: bool,
+    pub allow_same_origin: bool,
+    pub allow_forms: bool,
+    pub allow_popups: bool,
+    pub allow_top_navigation: bool,
+}
+
+impl SandboxFlags {
+    /// Parse a `sandbox` attribute value. Unknown tokens are ignored, per
+    /// spec.
+    pub fn parse(attr: &str) -> Self {
+        let mut flags = Self::default();
+        for token in attr.split_whitespace() {
+            match token.to_ascii_lowercase().as_str() {
+                "allow-scripts" => flags.allow_scripts = true,
+                "allow-same-origin" => flags.allow_same_origin = true,
+                "allow-forms" => flags.allow_forms = true,
+                "allow-popups" => flags.allow_popups = true,
+                "allow-top-navigation" => flags.allow_top_navigation = true,
+                _ => {}
+            }
+        }
+        flags
+    }
+}
+
+/// Whether a document fetched from `frame_url` may be embedded by the
+/// document at `embedder_url`, per the response's `X-Frame-Options`
+/// header. Absent, obsolete (`ALLOW-FROM`) and invalid values do not
+/// block, matching current browser behavior.
+pub fn frame_allowed(
+    x_frame_options: Option<&str>,
+    frame_url: &str,
+    embedder_url: &str,
+) -> bool {
+    match x_frame_options
+        .map(|value| value.trim().to_ascii_lowercase())
+        .as_deref()
+    {
+        Some("deny") => false,
+        Some("sameorigin") => origin_of(frame_url) == origin_of(embedder_url),
+        _ => true,
+    }
+}
+
+/// `scheme://authority` of `url`, lowercased.
+fn origin_of(url: &str) -> String {
+    let (scheme, rest) = match url.split_once("://") {
+        Some(parts) => parts,
+        None => return url.to_ascii_lowercase(),
+    };
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or(rest);
+    format!("{}://{}", scheme.to_ascii_lowercase(), authority.to_ascii_lowercase())
+}
+
+/// One nested browsing context: the document loaded into an `<iframe>`,
+/// with its own stylesheets and whatever frames it nests in turn.
+pub struct FrameContext {
+    pub url: String,
+    pub document: Document,
+    pub styles: StyleEngine,
+    /// `None` when the iframe has no `sandbox` attribute.
+    pub sandbox: Option<SandboxFlags>,
+    pub frames: FrameTree,
+}
+
+impl FrameContext {
+    /// Parse `source` as the frame's document and collect its `<style>`
+    /// elements, mirroring top-level document setup.
+    pub fn new(url: &str, source: &str, sandbox: Option<SandboxFlags>) -> Self {
+        let document = html::parse(source);
+        let mut styles = StyleEngine::new();
+        style::collect_styles(&document, &mut styles);
+        Self {
+            url: url.to_owned(),
+            document,
+            styles,
+            sandbox,
+            frames: FrameTree::default(),
+        }
+    }
+
+    /// Whether script may run in this frame: sandboxed frames need
+    /// `allow-scripts`.
+    pub fn scripts_allowed(&self) -> bool {
+        self.sandbox.map_or(true, |flags| flags.allow_scripts)
+    }
+}
+
+/// The frames nested in one document, keyed by their `<iframe>` element.
+#[derive(Default)]
+pub struct FrameTree {
+    frames: HashMap<NodeId, FrameContext>,
+}
+
+impl FrameTree {
+    pub fn insert(&mut self, element: NodeId, frame: FrameContext) {
+        self.frames.insert(element, frame);
+    }
+
+    /// The frame loaded into the `<iframe>` at `element`, if any.
+    pub fn get(&self, element: NodeId) -> Option<&FrameContext> {
+        self.frames.get(&element)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (NodeId, &FrameContext)> {
+        self.frames.iter().map(|(&element, frame)| (element, frame))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+}
+
+/// Depth past which nested frames are not loaded; a page embedding itself
+/// would otherwise recurse forever.
+const MAX_FRAME_DEPTH: usize = 8;
+
+/// Fetches and builds the nested contexts for a document's iframes.
+pub struct FrameLoader {
+    stack: Arc<NetworkStack>,
+}
+
+impl FrameLoader {
+    pub fn new(stack: Arc<NetworkStack>) -> Self {
+        Self { stack }
+    }
+
+    /// Load every `<iframe>` under `document` into a [`FrameTree`],
+    /// recursively. Frames without a usable `src`, blocked by
+    /// `X-Frame-Options`, or nested past the depth guard are omitted and
+    /// render as empty boxes.
+    pub async fn load_frames(&self, document: &Document, base_url: &str) -> FrameTree {
+        self.load_frames_inner(document, base_url, 0).await
+    }
+
+    fn load_frames_inner<'a>(
+        &'a self,
+        document: &'a Document,
+        base_url: &'a str,
+        depth: usize,
+    ) -> Pin<Box<dyn Future<Output = FrameTree> + Send + 'a>> {
+        Box::pin(async move {
+            let mut tree = FrameTree::default();
+            if depth >= MAX_FRAME_DEPTH {
+                return tree;
+            }
+            for node in document.composed_descendants(document.root()) {
+                let Some(element) = document.element(node) else {
+                    continue;
+                };
+                if element.tag_name != "iframe" {
+                    continue;
+                }
+                let Some(src) = element.attr("src").filter(|s| !s.is_empty()) else {
+                    continue;
+                };
+                if src.starts_with("about:") {
+                    continue;
+                }
+                let url = resolve_url(base_url, src);
+                let sandbox = element.attr("sandbox").map(SandboxFlags::parse);
+                let request = Request::get(url);
+                let Ok(response) = self
+                    .stack
+                    .fetch_prioritized(request, ResourcePriority::High)
+                    .await
+                else {
+                    continue;
+                };
+                if !frame_allowed(
+                    response.headers.get("x-frame-options"),
+                    &response.url,
+                    base_url,
+                ) {
+                    continue;
+                }
+                let source = String::from_utf8_lossy(&response.body).into_owned();
+                let mut frame = FrameContext::new(&response.url, &source, sandbox);
+                let nested = self
+                    .load_frames_inner(&frame.document, &frame.url, depth + 1)
+                    .await;
+                frame.frames = nested;
+                tree.insert(node, frame);
+            }
+            tree
+        })
+    }
+}
@@ -0,0 +1,291 @@
+//! Generated content: `::before` and `::after` boxes.
+//!
+//! When a matched rule gives a pseudo-element a `content` value, layout
+//! inserts an anonymous box as the originating element's first
+//! (`::before`) or last (`::after`) child. This module parses `content`
+//! values — strings, `attr()`, `counter()` — and realizes them to the text
+//! those boxes render.
+
+use std::collections::HashMap;
+
+use super::css::PseudoElement;
+use super::dom::{Document, NodeId};
+use super::media::MediaEnvironment;
+use super::style::{ComputedStyle, StyleEngine};
+
+/// One component of a `content` value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContentItem {
+    Text(String),
+    /// `attr(name)`: the originating element's attribute value.
+    Attr(String),
+    /// `counter(name, style)`: the counter's current value formatted in
+    /// the given counter style (`decimal` when omitted).
+    Counter(String, String),
+}
+
+/// An anonymous box generated for a pseudo-element, ready for layout.
+#[derive(Debug, Clone)]
+pub struct GeneratedBox {
+    pub pseudo: PseudoElement,
+    /// Realized text content.
+    pub text: String,
+    pub style: ComputedStyle,
+}
+
+/// The generated boxes of `node`, `::before` first. Layout places them as
+/// anonymous first/last children of the element's box.
+pub fn generated_boxes(
+    engine: &StyleEngine,
+    document: &Document,
+    node: NodeId,
+    env: &MediaEnvironment,
+    counters: &HashMap<String, i64>,
+) -> Vec<GeneratedBox> {
+    [PseudoElement::Before, PseudoElement::After]
+        .into_iter()
+        .filter_map(|pseudo| {
+            let style = engine.pseudo_style(document, node, pseudo, env)?;
+            let items = parse_content(style.get("content")?);
+            let text = realize_content(&items, document, node, counters);
+            Some(GeneratedBox {
+                pseudo,
+                text,
+                style,
+            })
+        })
+        .collect()
+}
+
+/// Parse a `content` value into its components.
+pub fn parse_content(value: &str) -> Vec<ContentItem> {
+    let mut items = Vec::new();
+    let mut rest = value.trim();
+    while !rest.is_empty() {
+        if let Some(quote) = rest.chars().next().filter(|&c| c == '"' || c == '\'') {
+            let Some(close) = rest[1..].find(quote) else {
+                break;
+            };
+            items.push(ContentItem::Text(rest[1..close + 1].to_owned()));
+            rest = rest[close + 2..].trim_start();
+        } else if let Some(after) = rest.strip_prefix("attr(") {
+            let Some(close) = after.find(')') else { break };
+            items.push(ContentItem::Attr(after[..close].trim().to_ascii_lowercase()));
+            rest = after[close + 1..].trim_start();
+        } else if let Some(after) = rest.strip_prefix("counter(") {
+            let Some(close) = after.find(')') else { break };
+            let mut args = after[..close].split(',').map(str::trim);
+            let name = args.next().unwrap_or_default();
+            let style = args.next().unwrap_or("decimal");
+            items.push(ContentItem::Counter(name.to_owned(), style.to_owned()));
+            rest = after[close + 1..].trim_start();
+        } else {
+            // Unknown token: skip to the next whitespace.
+            match rest.find(char::is_whitespace) {
+                Some(end) => rest = rest[end..].trim_start(),
+                None => break,
+            }
+        }
+    }
+    items
+}
+
+/// Counter values in scope at each element, computed in one pass over the
+/// composed tree.
+///
+/// Scopes nest with the tree: `counter-reset` instantiates a counter in
+/// the scope its element lives in (so it is visible to the element, its
+/// descendants and its following siblings), shadowing any same-named
+/// counter from an outer scope; `counter-increment` updates the nearest
+/// counter in scope, instantiating one at 0 when none exists. List
+/// elements carry the implicit `list-item` counter: `<ol>`/`<ul>` reset
+/// it (honoring the `start` attribute), `<li>` increments it (honoring
+/// the `value` attribute).
+pub fn compute_counters(
+    document: &Document,
+    styles: &HashMap<NodeId, ComputedStyle>,
+) -> HashMap<NodeId, HashMap<String, i64>> {
+    let mut out = HashMap::new();
+    let mut scopes: Vec<HashMap<String, i64>> = vec![HashMap::new()];
+    walk_counters(document, styles, document.root(), &mut scopes, &mut out);
+    out
+}
+
+fn walk_counters(
+    document: &Document,
+    styles: &HashMap<NodeId, ComputedStyle>,
+    node: NodeId,
+    scopes: &mut Vec<HashMap<String, i64>>,
+    out: &mut HashMap<NodeId, HashMap<String, i64>>,
+) {
+    if let Some(element) = document.element(node) {
+        let style = styles.get(&node);
+        let property = |name: &str| style.and_then(|s| s.get(name)).map(String::as_str);
+
+        for (name, value) in parse_counter_actions(property("counter-reset"), 0) {
+            scopes.last_mut().unwrap().insert(name, value);
+        }
+        match element.tag_name.as_str() {
+            "ol" | "ul" => {
+                let start = element
+                    .attr("start")
+                    .and_then(|v| v.trim().parse::<i64>().ok())
+                    .unwrap_or(1);
+                scopes.last_mut().unwrap().insert("list-item".to_owned(), start - 1);
+            }
+            "li" => match element.attr("value").and_then(|v| v.trim().parse::<i64>().ok()) {
+                Some(value) => set_counter(scopes, "list-item", value),
+                None => increment_counter(scopes, "list-item", 1),
+            },
+            _ => {}
+        }
+        for (name, delta) in parse_counter_actions(property("counter-increment"), 1) {
+            increment_counter(scopes, &name, delta);
+        }
+
+        // Snapshot the flattened scope chain, inner counters shadowing
+        // outer ones; generated content reads counters from this.
+        let mut snapshot = HashMap::new();
+        for scope in scopes.iter() {
+            snapshot.extend(scope.iter().map(|(k, v)| (k.clone(), *v)));
+        }
+        out.insert(node, snapshot);
+    }
+    scopes.push(HashMap::new());
+    for child in document.composed_children(node).to_vec() {
+        walk_counters(document, styles, child, scopes, out);
+    }
+    scopes.pop();
+}
+
+/// Parse `counter-reset`/`counter-increment`: pairs of a counter name and
+/// an optional integer, `default` when the integer is omitted. `none`
+/// yields nothing.
+fn parse_counter_actions(value: Option<&str>, default: i64) -> Vec<(String, i64)> {
+    let Some(value) = value.filter(|v| *v != "none") else {
+        return Vec::new();
+    };
+    let mut actions: Vec<(String, i64)> = Vec::new();
+    for token in value.split_whitespace() {
+        match token.parse::<i64>() {
+            Ok(n) => {
+                if let Some(last) = actions.last_mut() {
+                    last.1 = n;
+                }
+            }
+            Err(_) => actions.push((token.to_owned(), default)),
+        }
+    }
+    actions
+}
+
+/// Add `delta` to the nearest `name` counter in scope, instantiating one
+/// in the innermost scope when none exists.
+fn increment_counter(scopes: &mut [HashMap<String, i64>], name: &str, delta: i64) {
+    for scope in scopes.iter_mut().rev() {
+        if let Some(value) = scope.get_mut(name) {
+            *value += delta;
+            return;
+        }
+    }
+    scopes.last_mut().unwrap().insert(name.to_owned(), delta);
+}
+
+/// Set the nearest `name` counter in scope to `value`.
+fn set_counter(scopes: &mut [HashMap<String, i64>], name: &str, value: i64) {
+    for scope in scopes.iter_mut().rev() {
+        if let Some(slot) = scope.get_mut(name) {
+            *slot = value;
+            return;
+        }
+    }
+    scopes.last_mut().unwrap().insert(name.to_owned(), value);
+}
+
+/// The text of a list item's `::marker` for `list-style-type`, or `None`
+/// for `none`. Numeric styles include the conventional trailing dot.
+pub fn marker_text(style_type: &str, value: i64) -> Option<String> {
+    match style_type {
+        "none" => None,
+        "disc" => Some("\u{2022}".to_owned()),
+        "circle" => Some("\u{25e6}".to_owned()),
+        "square" => Some("\u{25aa}".to_owned()),
+        _ => Some(format!("{}.", format_counter(value, style_type))),
+    }
+}
+
+/// Format a counter value in a counter style. Unknown styles fall back to
+/// decimal, per the spec's fallback chain.
+pub fn format_counter(value: i64, style: &str) -> String {
+    match style {
+        "lower-alpha" | "lower-latin" if value >= 1 => alphabetic(value, b'a'),
+        "upper-alpha" | "upper-latin" if value >= 1 => alphabetic(value, b'A'),
+        "lower-roman" if (1..=3999).contains(&value) => roman(value).to_lowercase(),
+        "upper-roman" if (1..=3999).contains(&value) => roman(value),
+        _ => value.to_string(),
+    }
+}
+
+/// Bijective base-26 numbering: a..z, aa..az, ba..
+fn alphabetic(mut value: i64, base: u8) -> String {
+    let mut out = Vec::new();
+    while value > 0 {
+        value -= 1;
+        out.push(base + (value % 26) as u8);
+        value /= 26;
+    }
+    out.reverse();
+    String::from_utf8(out).unwrap_or_default()
+}
+
+fn roman(mut value: i64) -> String {
+    const NUMERALS: &[(i64, &str)] = &[
+        (1000, "M"),
+        (900, "CM"),
+        (500, "D"),
+        (400, "CD"),
+        (100, "C"),
+        (90, "XC"),
+        (50, "L"),
+        (40, "XL"),
+        (10, "X"),
+        (9, "IX"),
+        (5, "V"),
+        (4, "IV"),
+        (1, "I"),
+    ];
+    let mut out = String::new();
+    for &(n, numeral) in NUMERALS {
+        while value >= n {
+            out.push_str(numeral);
+            value -= n;
+        }
+    }
+    out
+}
+
+/// Realize parsed content items against the originating element.
+pub fn realize_content(
+    items: &[ContentItem],
+    document: &Document,
+    node: NodeId,
+    counters: &HashMap<String, i64>,
+) -> String {
+    let mut out = String::new();
+    for item in items {
+        match item {
+            ContentItem::Text(text) => out.push_str(text),
+            ContentItem::Attr(name) => {
+                if let Some(value) = document.element(node).and_then(|e| e.attr(name)) {
+                    out.push_str(value);
+                }
+            }
+            ContentItem::Counter(name, style) => {
+                // An unset counter reads as 0, per CSS counter rules.
+                let value = counters.get(name).copied().unwrap_or(0);
+                out.push_str(&format_counter(value, style));
+            }
+        }
+    }
+    out
+}
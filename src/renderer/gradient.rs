@@ -0,0 +1,327 @@
+//! CSS gradients: `linear-gradient()` and `radial-gradient()` values.
+//!
+//! A gradient parses once into its angle/shape and color stops; the
+//! painter samples it per pixel through [`Gradient::sample`], which does
+//! the spec's gradient-line geometry, so a GPU shader and the software
+//! fallback share one definition of what the gradient looks like.
+
+use super::color::{self, Color};
+
+/// One color stop. `position` is a 0..=1 fraction along the gradient
+/// line; stops without one are spaced evenly between their neighbours at
+/// resolve time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorStop {
+    pub color: Color,
+    pub position: Option<f32>,
+}
+
+/// A parsed gradient.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Gradient {
+    Linear {
+        /// CSS angle in degrees: 0 points up, 90 to the right.
+        angle: f32,
+        stops: Vec<ColorStop>,
+    },
+    Radial {
+        /// Ellipse by default; a circle uses one radius for both axes.
+        circle: bool,
+        /// Center as 0..=1 fractions of the box.
+        center: (f32, f32),
+        stops: Vec<ColorStop>,
+    },
+}
+
+/// Parse a gradient value. `None` for anything that is not a supported
+/// gradient function (callers fall back to `url()` handling).
+pub fn parse_gradient(value: &str) -> Option<Gradient> {
+    let value = value.trim();
+    if let Some(args) = strip_function(value, "linear-gradient") {
+        return parse_linear(args);
+    }
+    if let Some(args) = strip_function(value, "radial-gradient") {
+        return parse_radial(args);
+    }
+    None
+}
+
+fn strip_function<'a>(value: &'a str, name: &str) -> Option<&'a str> {
+    value
+        .strip_prefix(name)
+        .and_then(|rest| rest.trim_start().strip_prefix('('))
+        .and_then(|rest| rest.strip_suffix(')'))
+}
+
+fn parse_linear(args: &str) -> Option<Gradient> {
+    let mut parts = split_arguments(args).into_iter().peekable();
+    // Leading direction: an angle or a `to <side>` keyword pair; absent
+    // means `to bottom`.
+    let mut angle = 180.0;
+    if let Some(first) = parts.peek() {
+        let first = first.trim();
+        if let Some(parsed) = parse_angle(first) {
+            angle = parsed;
+            parts.next();
+        } else if let Some(sides) = first.strip_prefix("to ") {
+            angle = side_angle(sides)?;
+            parts.next();
+        }
+    }
+    let stops = parse_stops(parts)?;
+    Some(Gradient::Linear { angle, stops })
+}
+
+fn parse_radial(args: &str) -> Option<Gradient> {
+    let mut parts = split_arguments(args).into_iter().peekable();
+    let mut circle = false;
+    let mut center = (0.5, 0.5);
+    if let Some(first) = parts.peek() {
+        let first = first.trim();
+        // A shape/position prelude never parses as a color, so peeking is
+        // safe: `radial-gradient(red, blue)` keeps its first stop.
+        let is_prelude = first.starts_with("circle")
+            || first.starts_with("ellipse")
+            || first.starts_with("at ")
+            || first.starts_with("closest-")
+            || first.starts_with("farthest-");
+        if is_prelude {
+            let prelude = parts.next().unwrap();
+            let mut tokens = prelude.split_whitespace().peekable();
+            while let Some(token) = tokens.next() {
+                match token {
+                    "circle" => circle = true,
+                    "ellipse" => circle = false,
+                    // Only the farthest-corner default geometry is
+                    // implemented; the keywords are accepted and ignored.
+                    "closest-side" | "closest-corner" | "farthest-side"
+                    | "farthest-corner" => {}
+                    "at" => {
+                        center.0 = tokens.next().map_or(0.5, position_fraction);
+                        center.1 = tokens.next().map_or(center.0, position_fraction);
+                    }
+                    _ => return None,
+                }
+            }
+        }
+    }
+    let stops = parse_stops(parts)?;
+    Some(Gradient::Radial {
+        circle,
+        center,
+        stops,
+    })
+}
+
+/// A position component as a 0..=1 fraction.
+fn position_fraction(token: &str) -> f32 {
+    match token {
+        "left" | "top" => 0.0,
+        "center" => 0.5,
+        "right" | "bottom" => 1.0,
+        other => other
+            .strip_suffix('%')
+            .and_then(|p| p.parse::<f32>().ok())
+            .map_or(0.5, |p| p / 100.0),
+    }
+}
+
+/// Parse the remaining arguments as color stops: a color, optionally
+/// followed by a percentage position.
+fn parse_stops(parts: impl Iterator<Item = String>) -> Option<Vec<ColorStop>> {
+    let mut stops = Vec::new();
+    for part in parts {
+        let part = part.trim();
+        let (color_text, position) = match part.rsplit_once(char::is_whitespace) {
+            Some((head, tail)) if tail.ends_with('%') => {
+                let position = tail.strip_suffix('%')?.parse::<f32>().ok()? / 100.0;
+                (head.trim(), Some(position))
+            }
+            _ => (part, None),
+        };
+        stops.push(ColorStop {
+            color: color::parse_color(color_text)?,
+            position,
+        });
+    }
+    if stops.len() < 2 {
+        return None;
+    }
+    Some(stops)
+}
+
+/// `to <side>` directions as angles.
+fn side_angle(sides: &str) -> Option<f32> {
+    match sides.trim() {
+        "top" => Some(0.0),
+        "right" => Some(90.0),
+        "bottom" => Some(180.0),
+        "left" => Some(270.0),
+        "top right" | "right top" => Some(45.0),
+        "bottom right" | "right bottom" => Some(135.0),
+        "bottom left" | "left bottom" => Some(225.0),
+        "top left" | "left top" => Some(315.0),
+        _ => None,
+    }
+}
+
+fn parse_angle(token: &str) -> Option<f32> {
+    if let Some(n) = token.strip_suffix("deg") {
+        return n.trim().parse().ok();
+    }
+    if let Some(n) = token.strip_suffix("turn") {
+        return n.trim().parse::<f32>().ok().map(|t| t * 360.0);
+    }
+    if let Some(n) = token.strip_suffix("rad") {
+        return n.trim().parse::<f32>().ok().map(f32::to_degrees);
+    }
+    None
+}
+
+/// Split function arguments on top-level commas (colors contain commas
+/// of their own).
+fn split_arguments(input: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+    for ch in input.chars() {
+        match ch {
+            '(' => {
+                depth += 1;
+                current.push(ch);
+            }
+            ')' => {
+                depth = depth.saturating_sub(1);
+                current.push(ch);
+            }
+            ',' if depth == 0 => parts.push(std::mem::take(&mut current)),
+            _ => current.push(ch),
+        }
+    }
+    if !current.trim().is_empty() {
+        parts.push(current);
+    }
+    parts
+}
+
+impl Gradient {
+    /// The color at pixel (`x`, `y`) of a `width`×`height` box. Linear
+    /// gradients project the point onto the gradient line sized per the
+    /// spec (`|w·sin θ| + |h·cos θ|`); radial gradients measure from the
+    /// center against the farthest-corner radius.
+    pub fn sample(&self, x: f32, y: f32, width: f32, height: f32) -> Color {
+        let t = match self {
+            Gradient::Linear { angle, .. } => {
+                let theta = angle.to_radians();
+                let (sin, cos) = theta.sin_cos();
+                let line_length = (width * sin).abs() + (height * cos).abs();
+                if line_length <= 0.0 {
+                    return self.stops().last().map_or(Color::TRANSPARENT, |s| s.color);
+                }
+                // Gradient direction: angle measured clockwise from "up".
+                let dx = x - width / 2.0;
+                let dy = y - height / 2.0;
+                (dx * sin - dy * cos) / line_length + 0.5
+            }
+            Gradient::Radial { circle, center, .. } => {
+                let (cx, cy) = (center.0 * width, center.1 * height);
+                // Farthest corner from the center.
+                let far_x = cx.max(width - cx);
+                let far_y = cy.max(height - cy);
+                let (dx, dy) = (x - cx, y - cy);
+                if *circle {
+                    let radius = (far_x * far_x + far_y * far_y).sqrt();
+                    if radius <= 0.0 {
+                        return self.stops().last().map_or(Color::TRANSPARENT, |s| s.color);
+                    }
+                    (dx * dx + dy * dy).sqrt() / radius
+                } else {
+                    // Ellipse through the farthest corner.
+                    let (rx, ry) = (far_x.max(1e-6), far_y.max(1e-6));
+                    ((dx / rx).powi(2) + (dy / ry).powi(2)).sqrt() / std::f32::consts::SQRT_2
+                }
+            }
+        };
+        sample_stops(&self.resolved_stops(), t.clamp(0.0, 1.0))
+    }
+
+    fn stops(&self) -> &[ColorStop] {
+        match self {
+            Gradient::Linear { stops, .. } | Gradient::Radial { stops, .. } => stops,
+        }
+    }
+
+    /// Stops with every position filled in: the first defaults to 0, the
+    /// last to 1, unplaced runs spread evenly between their neighbours,
+    /// and positions are monotonically clamped, per spec.
+    pub fn resolved_stops(&self) -> Vec<(f32, Color)> {
+        let stops = self.stops();
+        let mut positions: Vec<Option<f32>> = stops.iter().map(|s| s.position).collect();
+        if let Some(first) = positions.first_mut() {
+            first.get_or_insert(0.0);
+        }
+        if let Some(last) = positions.last_mut() {
+            last.get_or_insert(1.0);
+        }
+        let mut index = 0;
+        while index < positions.len() {
+            if positions[index].is_some() {
+                index += 1;
+                continue;
+            }
+            // A run of unplaced stops: interpolate between the placed
+            // neighbours.
+            let start = index - 1;
+            let mut end = index;
+            while positions[end].is_none() {
+                end += 1;
+            }
+            let from = positions[start].unwrap();
+            let to = positions[end].unwrap();
+            let span = (end - start) as f32;
+            for (offset, position) in positions[start + 1..end].iter_mut().enumerate() {
+                *position = Some(from + (to - from) * (offset + 1) as f32 / span);
+            }
+            index = end;
+        }
+        let mut floor = 0.0f32;
+        positions
+            .into_iter()
+            .zip(stops)
+            .map(|(position, stop)| {
+                floor = floor.max(position.unwrap_or(0.0));
+                (floor, stop.color)
+            })
+            .collect()
+    }
+}
+
+/// Interpolate resolved stops at `t`.
+fn sample_stops(stops: &[(f32, Color)], t: f32) -> Color {
+    let Some(&(first_position, first_color)) = stops.first() else {
+        return Color::TRANSPARENT;
+    };
+    if t <= first_position {
+        return first_color;
+    }
+    for pair in stops.windows(2) {
+        let (from_t, from) = pair[0];
+        let (to_t, to) = pair[1];
+        if t <= to_t {
+            if to_t - from_t <= f32::EPSILON {
+                return to;
+            }
+            let f = (t - from_t) / (to_t - from_t);
+            let lerp = |a: u8, b: u8| -> u8 {
+                (f32::from(a) + (f32::from(b) - f32::from(a)) * f).round() as u8
+            };
+            return Color {
+                r: lerp(from.r, to.r),
+                g: lerp(from.g, to.g),
+                b: lerp(from.b, to.b),
+                a: from.a + (to.a - from.a) * f,
+            };
+        }
+    }
+    stops.last().map_or(Color::TRANSPARENT, |&(_, c)| c)
+}
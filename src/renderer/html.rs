@@ -0,0 +1,408 @@
+//! HTML parsing: tag soup in, [`Document`] out.
+//!
+//! This is a pragmatic parser, not a full HTML5 tree builder: it handles
+//! tags, attributes (quoted and bare), comments, void elements, raw-text
+//! elements (`<script>`, `<style>`) and mis-nested close tags by popping to
+//! the nearest matching ancestor. That covers real-world markup well
+//! enough for the engine's current needs.
+
+use super::dom::{Document, ElementData, NodeData, NodeId, QuirksMode, ShadowRootMode};
+
+/// Elements that never have children or a close tag.
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source",
+    "track", "wbr",
+];
+
+/// Elements whose content is raw text until the matching close tag.
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style", "title", "textarea"];
+
+/// Parse `html` into a DOM tree.
+pub fn parse(html: &str) -> Document {
+    let mut parser = StreamingParser::new();
+    parser.feed(html);
+    parser.finish()
+}
+
+/// Incremental parser: feed it network chunks as they arrive and the tree
+/// grows after each feed, so above-the-fold content can be styled and laid
+/// out before the document finishes downloading.
+///
+/// After each [`StreamingParser::feed`] the parser has consumed up to the
+/// last *safe point*: a construct that may continue in the next chunk (an
+/// unterminated tag, comment, raw-text element or text run) stays buffered
+/// rather than being committed in a form a later chunk would contradict.
+pub struct StreamingParser {
+    document: Document,
+    open: Vec<NodeId>,
+    buffer: String,
+    pos: usize,
+    /// An open raw-text element still waiting for its close tag.
+    raw_text: Option<(NodeId, String)>,
+    saw_doctype: bool,
+}
+
+impl StreamingParser {
+    pub fn new() -> Self {
+        let document = Document::new();
+        let root = document.root();
+        Self {
+            document,
+            open: vec![root],
+            buffer: String::new(),
+            pos: 0,
+            raw_text: None,
+            saw_doctype: false,
+        }
+    }
+
+    /// The tree built so far. Incomplete trailing constructs are simply
+    /// not in it yet.
+    pub fn document(&self) -> &Document {
+        &self.document
+    }
+
+    /// Append a chunk of markup and parse as far as possible.
+    pub fn feed(&mut self, chunk: &str) {
+        self.buffer.push_str(chunk);
+        self.drive(false);
+    }
+
+    /// End of input: flush held-back constructs with the whole-document
+    /// fallbacks and return the finished tree.
+    pub fn finish(mut self) -> Document {
+        self.drive(true);
+        if !self.saw_doctype {
+            // No DOCTYPE at all: full quirks, per spec.
+            self.document.set_quirks_mode(QuirksMode::Quirks);
+        }
+        attach_declarative_shadows(&mut self.document);
+        self.document
+    }
+
+    fn drive(&mut self, eof: bool) {
+        // A raw-text element left open by the previous chunk consumes
+        // input until its close tag shows up.
+        if let Some((id, tag)) = self.raw_text.clone() {
+            if !self.consume_raw_text(id, &tag, eof) {
+                return;
+            }
+        }
+        while self.pos < self.buffer.len() {
+            let html = self.buffer.as_str();
+            let pos = self.pos;
+            if html.as_bytes()[pos] == b'<' {
+                if html[pos..].starts_with("<!--") {
+                    let end = html[pos + 4..].find("-->").map(|i| pos + 4 + i);
+                    if end.is_none() && !eof {
+                        return;
+                    }
+                    let content = html[pos + 4..end.unwrap_or(html.len())].to_owned();
+                    let parent = *self.open.last().unwrap();
+                    self.document.append(parent, NodeData::Comment(content));
+                    self.pos = end.map_or(self.buffer.len(), |e| e + 3);
+                    continue;
+                }
+                if html[pos..].starts_with("<!") {
+                    // Doctype (which sets the rendering mode) or bogus
+                    // markup declaration; either way consume to '>'.
+                    let Some(end) = html[pos..].find('>') else {
+                        if !eof {
+                            return;
+                        }
+                        self.pos = self.buffer.len();
+                        continue;
+                    };
+                    let declaration = &html[pos + 2..pos + end];
+                    if declaration.len() >= 7
+                        && declaration[..7].eq_ignore_ascii_case("doctype")
+                        && !self.saw_doctype
+                    {
+                        self.saw_doctype = true;
+                        self.document.set_quirks_mode(doctype_mode(&declaration[7..]));
+                    }
+                    self.pos = pos + end + 1;
+                    continue;
+                }
+                if html[pos..].starts_with("</") {
+                    let Some(end) = html[pos..].find('>').map(|i| pos + i) else {
+                        if !eof {
+                            return;
+                        }
+                        self.pos = self.buffer.len();
+                        continue;
+                    };
+                    let name = html[pos + 2..end].trim().to_ascii_lowercase();
+                    close_element(&self.document, &mut self.open, &name);
+                    self.pos = end + 1;
+                    continue;
+                }
+                if let Some((element, self_closing, next)) = parse_tag(html, pos) {
+                    // `parse_tag` consumes to the buffer end when the tag
+                    // is unterminated; mid-stream that means "wait".
+                    if next == self.buffer.len() && !eof && !self.buffer.ends_with('>') {
+                        return;
+                    }
+                    let tag = element.tag_name.clone();
+                    let parent = *self.open.last().unwrap();
+                    let id = self.document.append(parent, NodeData::Element(element));
+                    self.pos = next;
+                    if self_closing || VOID_ELEMENTS.contains(&tag.as_str()) {
+                        continue;
+                    }
+                    if RAW_TEXT_ELEMENTS.contains(&tag.as_str()) {
+                        if !self.consume_raw_text(id, &tag, eof) {
+                            self.raw_text = Some((id, tag));
+                            return;
+                        }
+                        continue;
+                    }
+                    self.open.push(id);
+                    continue;
+                }
+            }
+            // Text run up to the next tag. Without a following '<' the run
+            // may continue in the next chunk; hold it back until then.
+            let end = html[pos + 1..].find('<').map(|i| pos + 1 + i);
+            if end.is_none() && !eof {
+                return;
+            }
+            let end = end.unwrap_or(html.len());
+            let text = &html[pos..end];
+            if !text.trim().is_empty() {
+                let decoded = decode_entities(text);
+                let parent = *self.open.last().unwrap();
+                self.document.append(parent, NodeData::Text(decoded));
+            }
+            self.pos = end;
+        }
+    }
+
+    /// Consume raw text for `id` up to `</tag`. Returns false when the
+    /// close tag has not arrived yet (and `eof` is false).
+    fn consume_raw_text(&mut self, id: NodeId, tag: &str, eof: bool) -> bool {
+        let close = format!("</{tag}");
+        let pos = self.pos;
+        let end = self.buffer[pos..]
+            .to_ascii_lowercase()
+            .find(&close)
+            .map(|i| pos + i);
+        if end.is_none() && !eof {
+            return false;
+        }
+        let end = end.unwrap_or(self.buffer.len());
+        if !eof && !self.buffer[end..].contains('>') {
+            // The close tag itself is split across chunks.
+            return false;
+        }
+        if end > pos {
+            let text = self.buffer[pos..end].to_owned();
+            self.document.append(id, NodeData::Text(text));
+        }
+        self.pos = self.buffer[end..]
+            .find('>')
+            .map_or(self.buffer.len(), |i| end + i + 1);
+        self.raw_text = None;
+        true
+    }
+}
+
+impl Default for StreamingParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Classify a DOCTYPE's text (everything after `<!doctype`) into a
+/// rendering mode: the spec's table condensed to the identifiers that
+/// occur in practice. `<!doctype html>` and anything unrecognised are
+/// standards mode; ancient and transitional identifiers trigger the
+/// legacy modes.
+fn doctype_mode(rest: &str) -> QuirksMode {
+    let lower = rest.trim().to_ascii_lowercase();
+    if lower.is_empty() {
+        return QuirksMode::Quirks;
+    }
+    if lower == "html" {
+        return QuirksMode::NoQuirks;
+    }
+    if !lower.starts_with("html") {
+        return QuirksMode::Quirks;
+    }
+    if lower.contains("xhtml 1.0 transitional") || lower.contains("xhtml 1.0 frameset") {
+        return QuirksMode::LimitedQuirks;
+    }
+    if lower.contains("html 4.01 transitional")
+        || lower.contains("html 4.01 frameset")
+        || lower.contains("html 4.0 transitional")
+        || lower.contains("html 4.0 frameset")
+    {
+        // With a system identifier these are limited quirks; without one,
+        // full quirks.
+        return if lower.contains(".dtd") {
+            QuirksMode::LimitedQuirks
+        } else {
+            QuirksMode::Quirks
+        };
+    }
+    if lower.contains("html 3.2")
+        || lower.contains("html 2.0")
+        || lower.contains("-//w3o//")
+        || lower.contains("-//ietf//dtd html")
+    {
+        return QuirksMode::Quirks;
+    }
+    QuirksMode::NoQuirks
+}
+
+/// Declarative shadow DOM: a `<template shadowrootmode>` becomes its
+/// parent's shadow root — the template's children move into the shadow
+/// tree and the template itself disappears from the light tree.
+fn attach_declarative_shadows(document: &mut Document) {
+    let templates: Vec<(NodeId, NodeId, ShadowRootMode)> = document
+        .descendants(document.root())
+        .into_iter()
+        .filter_map(|node| {
+            let element = document.element(node)?;
+            if element.tag_name != "template" {
+                return None;
+            }
+            let mode = match element.attr("shadowrootmode")? {
+                "open" => ShadowRootMode::Open,
+                "closed" => ShadowRootMode::Closed,
+                _ => return None,
+            };
+            let host = document.parent(node).filter(|&p| document.element(p).is_some())?;
+            Some((node, host, mode))
+        })
+        .collect();
+    for (template, host, mode) in templates {
+        let root = document.attach_shadow(host, mode);
+        let children = std::mem::take(&mut document.node_mut(template).children);
+        for &child in &children {
+            document.node_mut(child).parent = Some(root);
+        }
+        document.node_mut(root).children.extend(children);
+        document
+            .node_mut(host)
+            .children
+            .retain(|&child| child != template);
+    }
+}
+
+/// Pop the open-element stack to close `name`, tolerating mis-nesting: if
+/// no matching ancestor is open, the close tag is ignored.
+fn close_element(document: &Document, open: &mut Vec<NodeId>, name: &str) {
+    let matching = open
+        .iter()
+        .rposition(|&id| document.element(id).map_or(false, |e| e.tag_name == name));
+    if let Some(index) = matching {
+        open.truncate(index.max(1));
+    }
+}
+
+/// Parse an open tag starting at `pos` (which points at `<`). Returns the
+/// element, whether it was self-closing, and the offset past the `>`.
+/// Also used by the preload scanner, which tokenizes ahead of the parser.
+pub(crate) fn parse_tag(html: &str, pos: usize) -> Option<(ElementData, bool, usize)> {
+    let rest = &html[pos + 1..];
+    let name_len = rest
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+        .unwrap_or(rest.len());
+    if name_len == 0 {
+        return None;
+    }
+    let mut element = ElementData::new(&rest[..name_len]);
+    let mut cursor = pos + 1 + name_len;
+
+    loop {
+        let remaining = &html[cursor..];
+        let trimmed = remaining.trim_start();
+        cursor += remaining.len() - trimmed.len();
+        if trimmed.is_empty() {
+            return Some((element, false, html.len()));
+        }
+        if trimmed.starts_with("/>") {
+            return Some((element, true, cursor + 2));
+        }
+        if trimmed.starts_with('>') {
+            return Some((element, false, cursor + 1));
+        }
+        // Attribute name.
+        let name_end = trimmed
+            .find(|c: char| c.is_whitespace() || c == '=' || c == '>' || c == '/')
+            .unwrap_or(trimmed.len());
+        let name = trimmed[..name_end].to_ascii_lowercase();
+        cursor += name_end;
+        let after = html[cursor..].trim_start();
+        if let Some(value_part) = after.strip_prefix('=') {
+            cursor += html[cursor..].len() - after.len() + 1;
+            let value_part = value_part.trim_start();
+            cursor += after[1..].len() - value_part.len();
+            let (value, consumed) = if let Some(inner) = value_part.strip_prefix('"') {
+                let end = inner.find('"').unwrap_or(inner.len());
+                (inner[..end].to_owned(), end + 2)
+            } else if let Some(inner) = value_part.strip_prefix('\'') {
+                let end = inner.find('\'').unwrap_or(inner.len());
+                (inner[..end].to_owned(), end + 2)
+            } else {
+                let end = value_part
+                    .find(|c: char| c.is_whitespace() || c == '>')
+                    .unwrap_or(value_part.len());
+                (value_part[..end].to_owned(), end)
+            };
+            element.set_attr(&name, &decode_entities(&value));
+            cursor += consumed;
+        } else if !name.is_empty() {
+            element.set_attr(&name, "");
+        } else {
+            cursor += 1;
+        }
+    }
+}
+
+/// Decode the named entities that actually occur in practice, plus numeric
+/// references.
+fn decode_entities(input: &str) -> String {
+    if !input.contains('&') {
+        return input.to_owned();
+    }
+    let mut out = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let end = rest.find(';').filter(|&i| i <= 10);
+        match end {
+            Some(end) => {
+                let entity = &rest[1..end];
+                match entity {
+                    "amp" => out.push('&'),
+                    "lt" => out.push('<'),
+                    "gt" => out.push('>'),
+                    "quot" => out.push('"'),
+                    "apos" => out.push('\''),
+                    "nbsp" => out.push('\u{a0}'),
+                    _ => {
+                        let code = entity
+                            .strip_prefix("#x")
+                            .or_else(|| entity.strip_prefix("#X"))
+                            .and_then(|h| u32::from_str_radix(h, 16).ok())
+                            .or_else(|| entity.strip_prefix('#').and_then(|d| d.parse().ok()));
+                        match code.and_then(char::from_u32) {
+                            Some(c) => out.push(c),
+                            None => out.push_str(&rest[..end + 1]),
+                        }
+                    }
+                }
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
@@ -0,0 +1,1494 @@
+//! Layout: block stacking and inline formatting with real line boxes.
+//!
+//! Block-level boxes stack vertically inside their containing block.
+//! Consecutive inline-level children share an inline formatting context:
+//! their text is split into words, measured, and flowed greedily into
+//! [`LineBox`]es that wrap at the containing width. Fragments on a line
+//! share a baseline — each is shifted down so its ascent meets the line's
+//! tallest ascent — rather than every element starting its own line.
+//!
+//! Text measurement goes through [`TextMeasurer`] so the rasterizer can
+//! supply per-glyph advances; [`HeuristicMeasurer`] approximates them
+//! until real font metrics are wired in.
+
+use std::collections::HashMap;
+
+use super::dom::{Document, NodeData, NodeId, QuirksMode};
+use super::frame::FrameTree;
+use super::generated;
+use super::media::MediaEnvironment;
+use super::style::{ComputedStyle, StyleEngine};
+use super::svg;
+use super::transform::{self, Transform2D};
+use super::values::{parse_css_size, Length, LengthContext};
+
+/// A rectangle in page coordinates, origin top-left.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// Vertical font metrics at a given size, both measured down from the
+/// baseline's line top.
+#[derive(Debug, Clone, Copy)]
+pub struct FontMetrics {
+    pub ascent: f32,
+    pub descent: f32,
+}
+
+/// The font properties a text run is measured and rendered with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextStyle {
+    /// First family of the `font-family` list, quotes stripped.
+    pub family: String,
+    /// Font size in px.
+    pub size: f32,
+    pub weight: u16,
+    pub italic: bool,
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        Self {
+            family: "serif".to_owned(),
+            size: 16.0,
+            weight: 400,
+            italic: false,
+        }
+    }
+}
+
+/// Supplies text widths and font metrics to inline layout.
+pub trait TextMeasurer {
+    /// Advance width of `text` in `style`.
+    fn width(&self, text: &str, style: &TextStyle) -> f32;
+
+    fn metrics(&self, style: &TextStyle) -> FontMetrics;
+}
+
+/// Approximate measurements for when no font backend is available: a flat
+/// average advance per character. Wrapping positions are plausible but
+/// not glyph-accurate; [`super::fonts::FontMeasurer`] reads real glyph
+/// advances.
+pub struct HeuristicMeasurer;
+
+impl TextMeasurer for HeuristicMeasurer {
+    fn width(&self, text: &str, style: &TextStyle) -> f32 {
+        text.chars().count() as f32 * style.size * 0.5
+    }
+
+    fn metrics(&self, style: &TextStyle) -> FontMetrics {
+        FontMetrics {
+            ascent: style.size * 0.8,
+            descent: style.size * 0.2,
+        }
+    }
+}
+
+/// Positioning schemes layout distinguishes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Position {
+    #[default]
+    Static,
+    Relative,
+    Absolute,
+    /// Positioned against the viewport; the compositor keeps the box in
+    /// place during scroll.
+    Fixed,
+    Sticky,
+}
+
+/// The inset offsets of a `position: sticky` box. The scroller clamps the
+/// box against these while its containing block is in view; at rest the
+/// box sits at its normal flow position.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StickyOffsets {
+    pub top: Option<f32>,
+    pub right: Option<f32>,
+    pub bottom: Option<f32>,
+    pub left: Option<f32>,
+}
+
+/// How a box treats content that exceeds its bounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Overflow {
+    #[default]
+    Visible,
+    /// Clipped, not user-scrollable.
+    Hidden,
+    /// Clipped, scrollable when the content overflows.
+    Auto,
+    /// Clipped, always a scroll container.
+    Scroll,
+}
+
+/// A laid-out box. Block containers hold child boxes or line boxes,
+/// never both in the same box (inline runs between block siblings get
+/// their own anonymous box).
+#[derive(Debug, Clone, Default)]
+pub struct LayoutBox {
+    /// Generating element; `None` for anonymous boxes.
+    pub node: Option<NodeId>,
+    /// Border box in page coordinates.
+    pub rect: Rect,
+    pub position: Position,
+    /// Set for `position: sticky` boxes.
+    pub sticky: Option<StickyOffsets>,
+    pub overflow: Overflow,
+    /// Page-space layer matrix (origin adjustment already folded in); the
+    /// compositor applies it at paint time, flow positions are untouched.
+    pub transform: Option<Transform2D>,
+    /// The contents were never laid out (`content-visibility`): the box
+    /// keeps its size-contained rect but holds nothing to paint. A scroll
+    /// that brings the box near the viewport dirties it for relayout.
+    pub contents_skipped: bool,
+    /// Extent of the content laid inside, which may exceed `rect` when an
+    /// explicit height is set or children overflow horizontally.
+    pub content_width: f32,
+    pub content_height: f32,
+    pub children: Vec<LayoutBox>,
+    pub lines: Vec<LineBox>,
+}
+
+impl LayoutBox {
+    /// Whether painting of descendants is clipped to this box.
+    pub fn clips_content(&self) -> bool {
+        self.overflow != Overflow::Visible
+    }
+
+    /// The rectangle the painter clips descendants to, if any.
+    pub fn clip_rect(&self) -> Option<Rect> {
+        self.clips_content().then_some(self.rect)
+    }
+
+    /// Whether the box scrolls its own content independently of the page.
+    pub fn is_scroll_container(&self) -> bool {
+        match self.overflow {
+            Overflow::Scroll => true,
+            Overflow::Auto => {
+                self.content_width > self.rect.width || self.content_height > self.rect.height
+            }
+            Overflow::Visible | Overflow::Hidden => false,
+        }
+    }
+
+    /// Maximum scroll offset on each axis.
+    pub fn max_scroll(&self) -> (f32, f32) {
+        if !self.is_scroll_container() {
+            return (0.0, 0.0);
+        }
+        (
+            (self.content_width - self.rect.width).max(0.0),
+            (self.content_height - self.rect.height).max(0.0),
+        )
+    }
+}
+
+/// One line of an inline formatting context.
+#[derive(Debug, Clone)]
+pub struct LineBox {
+    pub rect: Rect,
+    /// Baseline offset from the line's top.
+    pub baseline: f32,
+    pub fragments: Vec<InlineFragment>,
+}
+
+/// A run of text from one node placed on a line.
+#[derive(Debug, Clone)]
+pub struct InlineFragment {
+    /// The text node the run came from.
+    pub node: NodeId,
+    pub text: String,
+    pub rect: Rect,
+    pub style: TextStyle,
+}
+
+/// Lay out the whole document against the viewport in `env`.
+pub fn layout_document(
+    document: &Document,
+    styles: &StyleEngine,
+    env: &MediaEnvironment,
+    measurer: &dyn TextMeasurer,
+) -> LayoutBox {
+    layout_document_with_frames(document, styles, env, measurer, &FrameTree::default())
+}
+
+/// Lay out the whole document, placing each loaded frame document inside
+/// its `<iframe>` box (clipped, with the frame as its viewport).
+pub fn layout_document_with_frames(
+    document: &Document,
+    styles: &StyleEngine,
+    env: &MediaEnvironment,
+    measurer: &dyn TextMeasurer,
+    frames: &FrameTree,
+) -> LayoutBox {
+    layout_with(&LayoutContext::new(document, styles, env, measurer, frames), env)
+}
+
+/// Resolves styles and lays out with the independent per-element work —
+/// selector matching — spread across the rayon pool. Same output as
+/// [`layout_document_with_frames`]; worth it on large documents
+/// (benchmarked in `benches/style_parallel.rs`).
+pub struct LayoutBatcher<'a> {
+    measurer: &'a (dyn TextMeasurer + Sync),
+}
+
+impl<'a> LayoutBatcher<'a> {
+    pub fn new(measurer: &'a (dyn TextMeasurer + Sync)) -> Self {
+        Self { measurer }
+    }
+
+    pub fn layout(
+        &self,
+        document: &Document,
+        styles: &StyleEngine,
+        env: &MediaEnvironment,
+        frames: &FrameTree,
+    ) -> LayoutBox {
+        let resolved = styles.resolve_parallel(document, env);
+        let ctx = LayoutContext::with_styles(document, resolved, env, self.measurer, frames);
+        layout_with(&ctx, env)
+    }
+}
+
+/// Flow the document into boxes against the viewport, with styles already
+/// resolved into `ctx`.
+fn layout_with(ctx: &LayoutContext, env: &MediaEnvironment) -> LayoutBox {
+    let document = ctx.document;
+    let mut root = LayoutBox {
+        node: None,
+        rect: Rect {
+            width: env.width,
+            ..Rect::default()
+        },
+        ..LayoutBox::default()
+    };
+    let mut cursor = 0.0;
+    let mut absolutes = Vec::new();
+    let mut fixed = Vec::new();
+    for child in document.node(document.root()).children.clone() {
+        cursor = ctx.flow_child(child, &mut root, 0.0, cursor, env.width, &mut absolutes, &mut fixed);
+    }
+    root.rect.height = cursor;
+    root.content_width = env.width;
+    root.content_height = cursor;
+    // The initial containing block absorbs absolutes with no positioned
+    // ancestor; fixed boxes attach to the viewport.
+    let initial = root.rect;
+    for item in absolutes {
+        if let Some(placed) = ctx.place_out_of_flow(item, initial, &mut fixed) {
+            root.children.push(placed);
+        }
+    }
+    let viewport = Rect {
+        x: 0.0,
+        y: 0.0,
+        width: env.width,
+        height: env.height,
+    };
+    let mut nested_fixed = Vec::new();
+    while let Some(item) = fixed.pop() {
+        if let Some(placed) = ctx.place_out_of_flow(item, viewport, &mut nested_fixed) {
+            root.children.push(placed);
+        }
+        fixed.append(&mut nested_fixed);
+    }
+    root
+}
+
+/// Re-lay-out just the subtree rooted at `target` inside an existing
+/// layout tree, in place. Succeeds — returning the old and new rects for
+/// repainting — only when the rebuilt box keeps its exact size, so no
+/// sibling or ancestor can be affected. `None` means the cheap path is
+/// unavailable (box not found, size changed, or out-of-flow descendants
+/// escape the subtree) and the caller must do a full layout.
+pub fn relayout_subtree(
+    root: &mut LayoutBox,
+    target: NodeId,
+    document: &Document,
+    styles: &StyleEngine,
+    env: &MediaEnvironment,
+    measurer: &dyn TextMeasurer,
+    frames: &FrameTree,
+) -> Option<(Rect, Rect)> {
+    let ctx = LayoutContext::new(document, styles, env, measurer, frames);
+    match replace_subtree(&ctx, root, target) {
+        SubtreeRelayout::Replaced(old, new) => Some((old, new)),
+        SubtreeRelayout::NotFound | SubtreeRelayout::NeedsFull => None,
+    }
+}
+
+enum SubtreeRelayout {
+    NotFound,
+    /// The box was rebuilt in place; old and new rects for repaint.
+    Replaced(Rect, Rect),
+    /// The rebuilt box changed size (or sheds out-of-flow boxes), which
+    /// would move other content: a full layout is required.
+    NeedsFull,
+}
+
+fn replace_subtree(ctx: &LayoutContext, parent: &mut LayoutBox, target: NodeId) -> SubtreeRelayout {
+    let available = parent.rect.width;
+    for child in &mut parent.children {
+        if child.node == Some(target) {
+            let mut absolutes = Vec::new();
+            let mut fixed = Vec::new();
+            let Some(rebuilt) = ctx.layout_block(
+                target,
+                child.rect.x,
+                child.rect.y,
+                available,
+                &mut absolutes,
+                &mut fixed,
+            ) else {
+                // The subtree no longer produces a box (display changed);
+                // everything after it moves up.
+                return SubtreeRelayout::NeedsFull;
+            };
+            let same_size = (rebuilt.rect.width - child.rect.width).abs() < 0.5
+                && (rebuilt.rect.height - child.rect.height).abs() < 0.5;
+            if !same_size || !absolutes.is_empty() || !fixed.is_empty() {
+                return SubtreeRelayout::NeedsFull;
+            }
+            let old = child.rect;
+            let new = rebuilt.rect;
+            *child = rebuilt;
+            return SubtreeRelayout::Replaced(old, new);
+        }
+        match replace_subtree(ctx, child, target) {
+            SubtreeRelayout::NotFound => continue,
+            found => return found,
+        }
+    }
+    SubtreeRelayout::NotFound
+}
+
+/// An element removed from normal flow, remembered with the static
+/// position it would have flowed to (used when its insets leave an axis
+/// unconstrained).
+struct OutOfFlow {
+    node: NodeId,
+    static_x: f32,
+    static_y: f32,
+}
+
+/// Word-level unit collected from an inline run before line breaking.
+struct InlineWord {
+    node: NodeId,
+    text: String,
+    style: TextStyle,
+}
+
+struct LayoutContext<'a> {
+    document: &'a Document,
+    styles: HashMap<NodeId, ComputedStyle>,
+    measurer: &'a dyn TextMeasurer,
+    media: MediaEnvironment,
+    viewport_width: f32,
+    viewport_height: f32,
+    /// The root element's computed font size, for `rem`.
+    root_font_size: f32,
+    /// Nested frame documents, keyed by their `<iframe>` element.
+    frames: &'a FrameTree,
+    /// Counter values in scope at each element, for list markers and
+    /// generated content.
+    counters: HashMap<NodeId, HashMap<String, i64>>,
+}
+
+impl<'a> LayoutContext<'a> {
+    fn new(
+        document: &'a Document,
+        styles: &StyleEngine,
+        env: &MediaEnvironment,
+        measurer: &'a dyn TextMeasurer,
+        frames: &'a FrameTree,
+    ) -> Self {
+        Self::with_styles(document, styles.resolve(document, env), env, measurer, frames)
+    }
+
+    /// Build a context around an already resolved style map (see
+    /// [`LayoutBatcher`]).
+    fn with_styles(
+        document: &'a Document,
+        styles: HashMap<NodeId, ComputedStyle>,
+        env: &MediaEnvironment,
+        measurer: &'a dyn TextMeasurer,
+        frames: &'a FrameTree,
+    ) -> Self {
+        let counters = generated::compute_counters(document, &styles);
+        let mut ctx = Self {
+            document,
+            styles,
+            measurer,
+            media: *env,
+            viewport_width: env.width,
+            viewport_height: env.height,
+            root_font_size: 16.0,
+            frames,
+            counters,
+        };
+        // `rem` resolves against the root element's computed font size
+        // (the root's own font-size may itself use rem, against the
+        // initial 16px).
+        if let Some(html) = document
+            .node(document.root())
+            .children
+            .iter()
+            .copied()
+            .find(|&node| document.element(node).is_some())
+        {
+            ctx.root_font_size = ctx.font_size_of(html);
+        }
+        ctx
+    }
+
+    /// Flow one block-level child of `parent`: removes absolutely and
+    /// fixed positioned elements from flow (deferring them to their
+    /// containing block), applies relative offsets after placement, and
+    /// records sticky constraints. Returns the updated flow cursor.
+    #[allow(clippy::too_many_arguments)]
+    fn flow_child(
+        &self,
+        child: NodeId,
+        parent: &mut LayoutBox,
+        x: f32,
+        cursor: f32,
+        width: f32,
+        absolutes: &mut Vec<OutOfFlow>,
+        fixed: &mut Vec<OutOfFlow>,
+    ) -> f32 {
+        match self.position_of(child) {
+            Position::Absolute => {
+                absolutes.push(OutOfFlow {
+                    node: child,
+                    static_x: x,
+                    static_y: cursor,
+                });
+                return cursor;
+            }
+            Position::Fixed => {
+                fixed.push(OutOfFlow {
+                    node: child,
+                    static_x: x,
+                    static_y: cursor,
+                });
+                return cursor;
+            }
+            _ => {}
+        }
+        let Some(mut block) = self.layout_block(child, x, cursor, width, absolutes, fixed) else {
+            return cursor;
+        };
+        // Relative offsets move the box after flow placement, so the
+        // cursor advances past where the box would have been.
+        let advance = block.rect.y + block.rect.height;
+        let containing = Rect {
+            x,
+            y: cursor,
+            width,
+            height: 0.0,
+        };
+        match block.position {
+            Position::Relative => {
+                let offsets = self.offsets_of(child, containing);
+                let dx = offsets
+                    .left
+                    .or(offsets.right.map(|right| -right))
+                    .unwrap_or(0.0);
+                let dy = offsets
+                    .top
+                    .or(offsets.bottom.map(|bottom| -bottom))
+                    .unwrap_or(0.0);
+                offset_subtree(&mut block, dx, dy);
+            }
+            Position::Sticky => {
+                block.sticky = Some(self.offsets_of(child, containing));
+            }
+            _ => {}
+        }
+        parent.children.push(block);
+        advance
+    }
+
+    /// Lay out an element taken out of normal flow against `containing`.
+    /// Unconstrained axes fall back to the recorded static position;
+    /// nested fixed descendants bubble out through `fixed`.
+    fn place_out_of_flow(
+        &self,
+        item: OutOfFlow,
+        containing: Rect,
+        fixed: &mut Vec<OutOfFlow>,
+    ) -> Option<LayoutBox> {
+        let offsets = self.offsets_of(item.node, containing);
+        let explicit_width = self
+            .styles
+            .get(&item.node)
+            .and_then(|s| s.get("width"))
+            .is_some();
+        let available = match (offsets.left, offsets.right) {
+            // Both insets with auto width pin both edges.
+            (Some(left), Some(right)) if !explicit_width => {
+                (containing.width - left - right).max(0.0)
+            }
+            _ => containing.width,
+        };
+        let mut absolutes = Vec::new();
+        let mut block = self.layout_block(item.node, 0.0, 0.0, available, &mut absolutes, fixed)?;
+        let target_x = offsets
+            .left
+            .map(|left| containing.x + left)
+            .or(offsets
+                .right
+                .map(|right| containing.x + containing.width - right - block.rect.width))
+            .unwrap_or(item.static_x);
+        let target_y = offsets
+            .top
+            .map(|top| containing.y + top)
+            .or(offsets
+                .bottom
+                .map(|bottom| containing.y + containing.height - bottom - block.rect.height))
+            .unwrap_or(item.static_y);
+        offset_subtree(&mut block, target_x - block.rect.x, target_y - block.rect.y);
+        Some(block)
+    }
+
+    /// Lay out the block-level element `node` with its content box at
+    /// (`x`, `y`), `available` wide. `None` for `display: none` subtrees
+    /// and non-element nodes (inline content is handled by the caller's
+    /// inline pass).
+    fn layout_block(
+        &self,
+        node: NodeId,
+        x: f32,
+        y: f32,
+        available: f32,
+        parent_absolutes: &mut Vec<OutOfFlow>,
+        fixed: &mut Vec<OutOfFlow>,
+    ) -> Option<LayoutBox> {
+        self.document.element(node)?;
+        let style = self.styles.get(&node);
+        match self.display_of(node) {
+            Display::None => return None,
+            // Replaced boxes are atomic whatever their display: sized by
+            // attributes or CSS, never by their internal content.
+            _ if self.document.element(node).map_or(false, |e| e.tag_name == "iframe") => {
+                return Some(self.layout_iframe(node, x, y, available));
+            }
+            _ if self.document.element(node).map_or(false, |e| e.tag_name == "svg") => {
+                return Some(self.layout_svg(node, x, y, available));
+            }
+            _ if self.document.element(node).map_or(false, |e| e.tag_name == "canvas") => {
+                return Some(self.layout_canvas(node, x, y, available));
+            }
+            Display::Inline => return None,
+            Display::Block | Display::ListItem => {}
+        }
+        // Content-box sizing: padding sits outside the declared width and
+        // insets the content from the border box. Quirks mode sizes
+        // declared widths border-box instead — padding comes out of the
+        // written width, the legacy behavior pre-standards pages expect.
+        let (pt, pr, pb, pl) = self.padding_of(node, available);
+        let explicit_width = style
+            .and_then(|s| s.get("width"))
+            .and_then(|value| parse_css_size(value))
+            .and_then(|size| size.resolve(&self.length_context(node, available)));
+        let mut width = match explicit_width {
+            Some(w) if self.quirks() => (w - pl - pr).max(0.0),
+            Some(w) => w,
+            None => (available - pl - pr).max(0.0),
+        };
+        if let Some(max) = style
+            .and_then(|s| s.get("max-width"))
+            .and_then(|value| parse_css_size(value))
+            .and_then(|size| size.resolve(&self.length_context(node, available)))
+        {
+            width = width.min(max);
+        }
+        let content_x = x + pl;
+
+        let containment = self.containment_of(node);
+        let skip_contents = match self.content_visibility_of(node) {
+            ContentVisibility::Hidden => true,
+            // `auto`: subtrees that start well below the viewport skip
+            // layout entirely; the slack viewport keeps normal scrolling
+            // ahead of the skip boundary.
+            ContentVisibility::Auto => y > self.viewport_height * 2.0,
+            ContentVisibility::Visible => false,
+        };
+        // Skipped contents imply full containment: the box cannot be
+        // sized by, leak boxes from, or paint children it never laid out.
+        let contain_size = containment.size || skip_contents;
+        let contain_layout = containment.layout || skip_contents;
+        let contain_paint = containment.paint || skip_contents;
+
+        let mut laid = LayoutBox {
+            node: Some(node),
+            rect: Rect {
+                x,
+                y,
+                width: width + pl + pr,
+                height: 0.0,
+            },
+            ..LayoutBox::default()
+        };
+
+        let align = self.text_align_of(node);
+        let mut cursor = y + pt;
+        let mut inline_run: Vec<NodeId> = Vec::new();
+        let mut absolutes = Vec::new();
+        if !skip_contents {
+            for child in self.document.composed_children(node).to_vec() {
+                if self.is_inline_level(child) {
+                    inline_run.push(child);
+                    continue;
+                }
+                cursor = self.flush_inline(&mut inline_run, &mut laid, content_x, cursor, width, align);
+                cursor = self.flow_child(child, &mut laid, content_x, cursor, width, &mut absolutes, fixed);
+            }
+            cursor = self.flush_inline(&mut inline_run, &mut laid, content_x, cursor, width, align);
+            if self.display_of(node) == Display::ListItem {
+                self.place_marker(node, &mut laid);
+            }
+        }
+
+        laid.content_height = cursor - y + pb;
+        laid.content_width = content_extent(&laid, content_x).max(width) + pl + pr;
+        // An explicit height bounds the box; the content keeps its flowed
+        // extent and `overflow` decides what happens to the excess.
+        let height = style
+            .and_then(|s| s.get("height"))
+            .and_then(|value| parse_css_size(value))
+            .and_then(|size| size.resolve(&self.length_context(node, self.viewport_height)));
+        laid.rect.height = match (height, contain_size) {
+            // Same border-box legacy as widths.
+            (Some(h), _) if self.quirks() => h.max(pt + pb),
+            (Some(h), _) => h + pt + pb,
+            // Size containment: sized as if empty, with
+            // `contain-intrinsic-size` as the placeholder extent so
+            // skipped subtrees keep the page's scroll height stable.
+            (None, true) => self.intrinsic_block_size(node).unwrap_or(0.0) + pt + pb,
+            (None, false) => laid.content_height,
+        };
+        laid.overflow = match self
+            .styles
+            .get(&node)
+            .and_then(|s| s.get("overflow"))
+            .map(String::as_str)
+        {
+            Some("hidden") => Overflow::Hidden,
+            Some("auto") => Overflow::Auto,
+            Some("scroll") => Overflow::Scroll,
+            // Paint containment clips to the box even with `overflow`
+            // untouched.
+            _ if contain_paint => Overflow::Hidden,
+            _ => Overflow::Visible,
+        };
+        laid.contents_skipped = skip_contents;
+        laid.position = self.position_of(node);
+        laid.transform = self.transform_of(node, laid.rect);
+        if laid.position == Position::Static && !contain_layout {
+            // Not a containing block for absolutes; they keep climbing.
+            parent_absolutes.append(&mut absolutes);
+        } else {
+            // Positioned, or layout containment: out-of-flow descendants
+            // stay inside this box.
+            let containing = laid.rect;
+            for item in absolutes {
+                if let Some(placed) = self.place_out_of_flow(item, containing, fixed) {
+                    laid.children.push(placed);
+                }
+            }
+        }
+        Some(laid)
+    }
+
+    /// Lay out an `<iframe>`: a fixed-size box — CSS size, else the
+    /// `width`/`height` attributes, else the 300×150 default — whose
+    /// frame document, when one was loaded, is laid out with the frame
+    /// rect as its viewport and clipped to it. Unloaded frames (blocked,
+    /// failed, no `src`) keep their box and render empty.
+    fn layout_iframe(&self, node: NodeId, x: f32, y: f32, available: f32) -> LayoutBox {
+        let style = self.styles.get(&node);
+        let attr_size = |name: &str| -> Option<f32> {
+            self.document.element(node)?.attr(name)?.trim().parse().ok()
+        };
+        let width = style
+            .and_then(|s| s.get("width"))
+            .and_then(|value| parse_css_size(value))
+            .and_then(|size| size.resolve(&self.length_context(node, available)))
+            .or_else(|| attr_size("width"))
+            .unwrap_or(300.0);
+        let height = style
+            .and_then(|s| s.get("height"))
+            .and_then(|value| parse_css_size(value))
+            .and_then(|size| size.resolve(&self.length_context(node, self.viewport_height)))
+            .or_else(|| attr_size("height"))
+            .unwrap_or(150.0);
+        let rect = Rect {
+            x,
+            y,
+            width,
+            height,
+        };
+        let mut laid = LayoutBox {
+            node: Some(node),
+            rect,
+            // Frame content never paints outside the frame.
+            overflow: Overflow::Hidden,
+            content_width: width,
+            content_height: height,
+            position: self.position_of(node),
+            transform: self.transform_of(node, rect),
+            ..LayoutBox::default()
+        };
+        if let Some(frame) = self.frames.get(node) {
+            let env = MediaEnvironment {
+                width,
+                height,
+                ..self.media
+            };
+            let mut inner = layout_document_with_frames(
+                &frame.document,
+                &frame.styles,
+                &env,
+                self.measurer,
+                &frame.frames,
+            );
+            offset_subtree(&mut inner, x, y);
+            laid.children.push(inner);
+        }
+        laid
+    }
+
+    /// Lay out an inline `<svg>`: an atomic box at the element's
+    /// intrinsic size unless CSS overrides it. The SVG content itself is
+    /// flattened at paint time by [`super::svg::build_scene`] into the
+    /// box's layer; its children never enter block or inline layout.
+    fn layout_svg(&self, node: NodeId, x: f32, y: f32, available: f32) -> LayoutBox {
+        let style = self.styles.get(&node);
+        let (intrinsic_width, intrinsic_height) = self
+            .document
+            .element(node)
+            .map(svg::intrinsic_size)
+            .unwrap_or((300.0, 150.0));
+        let width = style
+            .and_then(|s| s.get("width"))
+            .and_then(|value| parse_css_size(value))
+            .and_then(|size| size.resolve(&self.length_context(node, available)))
+            .unwrap_or(intrinsic_width);
+        let height = style
+            .and_then(|s| s.get("height"))
+            .and_then(|value| parse_css_size(value))
+            .and_then(|size| size.resolve(&self.length_context(node, self.viewport_height)))
+            .unwrap_or(intrinsic_height);
+        let rect = Rect {
+            x,
+            y,
+            width,
+            height,
+        };
+        LayoutBox {
+            node: Some(node),
+            rect,
+            overflow: Overflow::Hidden,
+            content_width: width,
+            content_height: height,
+            position: self.position_of(node),
+            transform: self.transform_of(node, rect),
+            ..LayoutBox::default()
+        }
+    }
+
+    /// Lay out a `<canvas>`: an atomic box at its attribute size (the
+    /// 300×150 default when absent) unless CSS overrides it. Its recorded
+    /// draw commands are rasterized into the box's layer at paint time.
+    fn layout_canvas(&self, node: NodeId, x: f32, y: f32, available: f32) -> LayoutBox {
+        let style = self.styles.get(&node);
+        let attr_size = |name: &str| -> Option<f32> {
+            self.document.element(node)?.attr(name)?.trim().parse().ok()
+        };
+        let width = style
+            .and_then(|s| s.get("width"))
+            .and_then(|value| parse_css_size(value))
+            .and_then(|size| size.resolve(&self.length_context(node, available)))
+            .or_else(|| attr_size("width"))
+            .unwrap_or(300.0);
+        let height = style
+            .and_then(|s| s.get("height"))
+            .and_then(|value| parse_css_size(value))
+            .and_then(|size| size.resolve(&self.length_context(node, self.viewport_height)))
+            .or_else(|| attr_size("height"))
+            .unwrap_or(150.0);
+        let rect = Rect {
+            x,
+            y,
+            width,
+            height,
+        };
+        LayoutBox {
+            node: Some(node),
+            rect,
+            overflow: Overflow::Hidden,
+            content_width: width,
+            content_height: height,
+            position: self.position_of(node),
+            transform: self.transform_of(node, rect),
+            ..LayoutBox::default()
+        }
+    }
+
+    /// Place a list item's `::marker`: the `list-style-type` glyph or the
+    /// formatted `list-item` counter, set in the list's padding to the
+    /// left of the item's content edge (`list-style-position: outside`)
+    /// and baseline-aligned with the item's first line.
+    fn place_marker(&self, node: NodeId, laid: &mut LayoutBox) {
+        let style_type = self
+            .inherited_property(node, "list-style-type")
+            .unwrap_or_else(|| "disc".to_owned());
+        let value = self
+            .counters
+            .get(&node)
+            .and_then(|counters| counters.get("list-item"))
+            .copied()
+            .unwrap_or(0);
+        let Some(text) = generated::marker_text(&style_type, value) else {
+            return;
+        };
+        let style = self.text_style_of(node);
+        let width = self.measurer.width(&text, &style);
+        let metrics = self.measurer.metrics(&style);
+        let gap = style.size * 0.5;
+        let y = laid
+            .lines
+            .first()
+            .map_or(laid.rect.y, |line| line.rect.y + line.baseline - metrics.ascent);
+        let fragment = InlineFragment {
+            node,
+            text,
+            rect: Rect {
+                x: laid.rect.x - width - gap,
+                y,
+                width,
+                height: metrics.ascent + metrics.descent,
+            },
+            style,
+        };
+        match laid.lines.first_mut() {
+            Some(line) => line.fragments.insert(0, fragment),
+            // No inline content of its own (empty, or block children
+            // only): the marker gets an anonymous box so lines and block
+            // children stay unmixed.
+            None => laid.children.insert(
+                0,
+                LayoutBox {
+                    node: None,
+                    rect: fragment.rect,
+                    lines: vec![LineBox {
+                        rect: fragment.rect,
+                        baseline: metrics.ascent,
+                        fragments: vec![fragment],
+                    }],
+                    ..LayoutBox::default()
+                },
+            ),
+        }
+    }
+
+    /// The page-space layer matrix of `node`'s `transform`, if any:
+    /// the declared matrix conjugated by a translation to its
+    /// `transform-origin`.
+    fn transform_of(&self, node: NodeId, rect: Rect) -> Option<Transform2D> {
+        let style = self.styles.get(&node)?;
+        let ctx = self.length_context(node, rect.width);
+        let matrix = transform::parse_transform_list(
+            style.get("transform")?,
+            rect.width,
+            rect.height,
+            &ctx,
+        )?;
+        let (ox, oy) = transform::parse_transform_origin(
+            style.get("transform-origin").map(String::as_str),
+            rect.width,
+            rect.height,
+            &ctx,
+        );
+        let (ox, oy) = (rect.x + ox, rect.y + oy);
+        Some(
+            Transform2D::translate(ox, oy)
+                .then(&matrix)
+                .then(&Transform2D::translate(-ox, -oy)),
+        )
+    }
+
+    /// Resolved padding of `node` as (top, right, bottom, left), from the
+    /// longhands or the `padding` shorthand with the usual 1–4 value
+    /// expansion. Percentages resolve against the containing block width
+    /// on every side, per spec.
+    fn padding_of(&self, node: NodeId, available: f32) -> (f32, f32, f32, f32) {
+        let ctx = self.length_context(node, available);
+        let style = self.styles.get(&node);
+        let shorthand: Vec<f32> = style
+            .and_then(|s| s.get("padding"))
+            .map(|value| {
+                value
+                    .split_whitespace()
+                    .filter_map(|part| parse_css_size(part).and_then(|size| size.resolve(&ctx)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let from_shorthand = |index: usize| -> Option<f32> {
+            match shorthand.len() {
+                1 => shorthand.first().copied(),
+                // Two values: vertical then horizontal.
+                2 => shorthand.get(index % 2).copied(),
+                // Three values: top, horizontal, bottom; left copies right.
+                3 => shorthand.get(if index == 3 { 1 } else { index }).copied(),
+                4 => shorthand.get(index).copied(),
+                _ => None,
+            }
+        };
+        let side = |name: &str, index: usize| -> f32 {
+            style
+                .and_then(|s| s.get(name))
+                .and_then(|value| parse_css_size(value))
+                .and_then(|size| size.resolve(&ctx))
+                .or_else(|| from_shorthand(index))
+                .unwrap_or(0.0)
+                .max(0.0)
+        };
+        (
+            side("padding-top", 0),
+            side("padding-right", 1),
+            side("padding-bottom", 2),
+            side("padding-left", 3),
+        )
+    }
+
+    /// Whether the document renders in full quirks mode (limited quirks
+    /// changes nothing this layout implements yet).
+    fn quirks(&self) -> bool {
+        self.document.quirks_mode() == QuirksMode::Quirks
+    }
+
+    fn text_align_of(&self, node: NodeId) -> TextAlign {
+        match self
+            .inherited_property(node, "text-align")
+            .as_deref()
+        {
+            Some("center") => TextAlign::Center,
+            Some("right") => TextAlign::Right,
+            Some("justify") => TextAlign::Justify,
+            _ => TextAlign::Left,
+        }
+    }
+
+    /// The containment `node` opts into via `contain`.
+    fn containment_of(&self, node: NodeId) -> Containment {
+        let mut containment = Containment::default();
+        let Some(value) = self.styles.get(&node).and_then(|s| s.get("contain")) else {
+            return containment;
+        };
+        for token in value.split_whitespace() {
+            match token {
+                "strict" => {
+                    containment.size = true;
+                    containment.layout = true;
+                    containment.paint = true;
+                }
+                "content" => {
+                    containment.layout = true;
+                    containment.paint = true;
+                }
+                "size" => containment.size = true,
+                "layout" => containment.layout = true,
+                "paint" => containment.paint = true,
+                // `style` containment scopes counters, which already
+                // compute per element; `none` and unknown tokens add
+                // nothing.
+                _ => {}
+            }
+        }
+        containment
+    }
+
+    fn content_visibility_of(&self, node: NodeId) -> ContentVisibility {
+        match self
+            .styles
+            .get(&node)
+            .and_then(|s| s.get("content-visibility"))
+            .map(String::as_str)
+        {
+            Some("hidden") => ContentVisibility::Hidden,
+            Some("auto") => ContentVisibility::Auto,
+            _ => ContentVisibility::Visible,
+        }
+    }
+
+    /// The placeholder block size from `contain-intrinsic-size`, used by
+    /// size-contained boxes with no explicit height. One value applies to
+    /// both axes, two are width then height; `auto` prefixes (last
+    /// remembered size) fall back to the written length.
+    fn intrinsic_block_size(&self, node: NodeId) -> Option<f32> {
+        let value = self
+            .styles
+            .get(&node)?
+            .get("contain-intrinsic-size")?;
+        let part = value
+            .split_whitespace()
+            .filter(|part| *part != "auto")
+            .last()?;
+        parse_css_size(part)?.resolve(&self.length_context(node, self.viewport_height))
+    }
+
+    fn position_of(&self, node: NodeId) -> Position {
+        match self
+            .styles
+            .get(&node)
+            .and_then(|s| s.get("position"))
+            .map(String::as_str)
+        {
+            Some("relative") => Position::Relative,
+            Some("absolute") => Position::Absolute,
+            Some("fixed") => Position::Fixed,
+            Some("sticky") => Position::Sticky,
+            _ => Position::Static,
+        }
+    }
+
+    /// Resolved `top`/`right`/`bottom`/`left` of `node`. Percentages
+    /// resolve against the containing block: width on the horizontal
+    /// axis, height on the vertical. `auto` stays `None`.
+    fn offsets_of(&self, node: NodeId, containing: Rect) -> StickyOffsets {
+        let resolve = |name: &str, basis: f32| -> Option<f32> {
+            let value = self.styles.get(&node)?.get(name)?;
+            Some(Length::parse(value)?.resolve(&self.length_context(node, basis)))
+        };
+        StickyOffsets {
+            top: resolve("top", containing.height),
+            right: resolve("right", containing.width),
+            bottom: resolve("bottom", containing.height),
+            left: resolve("left", containing.width),
+        }
+    }
+
+    /// Lay out a pending inline run into line boxes. When the box also has
+    /// block children the lines go into an anonymous child box, keeping
+    /// blocks and lines unmixed.
+    #[allow(clippy::too_many_arguments)]
+    fn flush_inline(
+        &self,
+        run: &mut Vec<NodeId>,
+        parent: &mut LayoutBox,
+        x: f32,
+        y: f32,
+        width: f32,
+        align: TextAlign,
+    ) -> f32 {
+        if run.is_empty() {
+            return y;
+        }
+        let mut words = Vec::new();
+        for node in run.drain(..) {
+            self.collect_words(node, &mut words);
+        }
+        // Standards mode gives every line the block's own font as a
+        // minimum height (the strut); quirks mode lets lines collapse to
+        // their content, the legacy `line-height` default.
+        let strut = parent
+            .node
+            .filter(|_| !self.quirks())
+            .map(|block| self.measurer.metrics(&self.text_style_of(block)));
+        let lines = self.break_lines(&words, x, y, width, align, strut);
+        let Some(last) = lines.last() else { return y };
+        let bottom = last.rect.y + last.rect.height;
+        if parent.children.is_empty() && parent.lines.is_empty() {
+            parent.lines = lines;
+        } else {
+            parent.children.push(LayoutBox {
+                node: None,
+                rect: Rect {
+                    x,
+                    y,
+                    width,
+                    height: bottom - y,
+                },
+                children: Vec::new(),
+                lines,
+            });
+        }
+        bottom
+    }
+
+    /// Flatten an inline subtree into measured words, collapsing runs of
+    /// whitespace.
+    fn collect_words(&self, node: NodeId, out: &mut Vec<InlineWord>) {
+        match &self.document.node(node).data {
+            NodeData::Text(text) => {
+                let style = self
+                    .document
+                    .parent(node)
+                    .map_or_else(TextStyle::default, |parent| self.text_style_of(parent));
+                for word in text.split_whitespace() {
+                    out.push(InlineWord {
+                        node,
+                        text: word.to_owned(),
+                        style: style.clone(),
+                    });
+                }
+            }
+            NodeData::Element(_) => {
+                if matches!(self.display_of(node), Display::None) {
+                    return;
+                }
+                for child in self.document.composed_children(node) {
+                    self.collect_words(*child, out);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Greedy line breaking: words fill the line until the next one would
+    /// overflow, then a new line opens. Fragments on each line share the
+    /// line's baseline; `align` shifts or stretches the finished lines
+    /// within `width`, and `strut` (standards mode) is each line's
+    /// minimum extent above and below the baseline.
+    #[allow(clippy::too_many_arguments)]
+    fn break_lines(
+        &self,
+        words: &[InlineWord],
+        x: f32,
+        y: f32,
+        width: f32,
+        align: TextAlign,
+        strut: Option<FontMetrics>,
+    ) -> Vec<LineBox> {
+        let mut lines: Vec<Vec<&InlineWord>> = Vec::new();
+        let mut current: Vec<&InlineWord> = Vec::new();
+        let mut cursor = 0.0;
+        for word in words {
+            let word_width = self.measurer.width(&word.text, &word.style);
+            let space = if current.is_empty() {
+                0.0
+            } else {
+                self.measurer.width(" ", &word.style)
+            };
+            if !current.is_empty() && cursor + space + word_width > width {
+                lines.push(std::mem::take(&mut current));
+                cursor = 0.0;
+            }
+            cursor += if current.is_empty() { 0.0 } else { space } + word_width;
+            current.push(word);
+        }
+        if !current.is_empty() {
+            lines.push(current);
+        }
+
+        let mut boxes = Vec::new();
+        let mut line_y = y;
+        let line_count = lines.len();
+        for (index, line) in lines.into_iter().enumerate() {
+            let mut ascent = strut.map_or(0.0, |m| m.ascent);
+            let mut descent = strut.map_or(0.0, |m| m.descent);
+            for word in &line {
+                let metrics = self.measurer.metrics(&word.style);
+                ascent = ascent.max(metrics.ascent);
+                descent = descent.max(metrics.descent);
+            }
+            let height = ascent + descent;
+
+            let mut fragments: Vec<InlineFragment> = Vec::new();
+            let mut cursor = x;
+            for word in line {
+                let space = self.measurer.width(" ", &word.style);
+                let word_width = self.measurer.width(&word.text, &word.style);
+                let metrics = self.measurer.metrics(&word.style);
+                // Merge into the previous fragment when the run continues
+                // from the same node. Justified lines keep one fragment
+                // per word so the extra space can go into the word gaps.
+                if let Some(last) = fragments
+                    .last_mut()
+                    .filter(|_| align != TextAlign::Justify)
+                    .filter(|f| f.node == word.node && f.style == word.style)
+                {
+                    last.text.push(' ');
+                    last.text.push_str(&word.text);
+                    last.rect.width += space + word_width;
+                    cursor += space + word_width;
+                    continue;
+                }
+                if !fragments.is_empty() {
+                    cursor += space;
+                }
+                fragments.push(InlineFragment {
+                    node: word.node,
+                    text: word.text.clone(),
+                    rect: Rect {
+                        x: cursor,
+                        // Baseline-align: drop the fragment so its ascent
+                        // meets the shared baseline.
+                        y: line_y + (ascent - metrics.ascent),
+                        width: word_width,
+                        height: metrics.ascent + metrics.descent,
+                    },
+                    style: word.style.clone(),
+                });
+                cursor += word_width;
+            }
+
+            let mut rect = Rect {
+                x,
+                y: line_y,
+                width: cursor - x,
+                height,
+            };
+            let slack = (width - rect.width).max(0.0);
+            match align {
+                TextAlign::Left => {}
+                TextAlign::Center | TextAlign::Right => {
+                    let dx = if align == TextAlign::Center { slack / 2.0 } else { slack };
+                    rect.x += dx;
+                    for fragment in &mut fragments {
+                        fragment.rect.x += dx;
+                    }
+                }
+                // Justify stretches every line but the last, spreading the
+                // slack evenly across the word gaps.
+                TextAlign::Justify if index + 1 < line_count && fragments.len() > 1 => {
+                    let step = slack / (fragments.len() - 1) as f32;
+                    for (gap, fragment) in fragments.iter_mut().enumerate() {
+                        fragment.rect.x += step * gap as f32;
+                    }
+                    rect.width = width;
+                }
+                TextAlign::Justify => {}
+            }
+            boxes.push(LineBox {
+                rect,
+                baseline: ascent,
+                fragments,
+            });
+            line_y += height;
+        }
+        boxes
+    }
+
+    /// Whether `node` participates in its parent's inline formatting
+    /// context: non-empty text, or an element computing to
+    /// `display: inline`.
+    fn is_inline_level(&self, node: NodeId) -> bool {
+        match &self.document.node(node).data {
+            NodeData::Text(text) => !text.trim().is_empty(),
+            // Replaced elements take the block path even when display is
+            // inline; see the `layout_*` replaced-box methods.
+            NodeData::Element(element)
+                if matches!(element.tag_name.as_str(), "iframe" | "svg" | "canvas") =>
+            {
+                false
+            }
+            NodeData::Element(_) => matches!(self.display_of(node), Display::Inline),
+            _ => false,
+        }
+    }
+
+    fn display_of(&self, node: NodeId) -> Display {
+        match self
+            .styles
+            .get(&node)
+            .and_then(|s| s.get("display"))
+            .map(String::as_str)
+        {
+            Some("none") => Display::None,
+            Some("block") => Display::Block,
+            Some("list-item") => Display::ListItem,
+            // Unknown display types fall back to inline, the initial
+            // value.
+            _ => Display::Inline,
+        }
+    }
+
+    /// A resolution context for `node`'s own property values.
+    fn length_context(&self, node: NodeId, containing_block: f32) -> LengthContext {
+        LengthContext {
+            font_size: self.font_size_of(node),
+            root_font_size: self.root_font_size,
+            containing_block,
+            viewport_width: self.viewport_width,
+            viewport_height: self.viewport_height,
+        }
+    }
+
+    /// The font properties text under `node` is measured with. Font
+    /// properties inherit, so unset ones are looked up the ancestor chain.
+    fn text_style_of(&self, node: NodeId) -> TextStyle {
+        let family = self
+            .inherited_property(node, "font-family")
+            .and_then(|list| list.split(',').next())
+            .map(|family| family.trim().trim_matches(['"', '\'']).to_owned())
+            .unwrap_or_else(|| TextStyle::default().family);
+        let weight = match self.inherited_property(node, "font-weight").as_deref() {
+            Some("bold") | Some("bolder") => 700,
+            Some("normal") | None => 400,
+            Some(value) => value.parse().unwrap_or(400),
+        };
+        let italic = self
+            .inherited_property(node, "font-style")
+            .map_or(false, |style| {
+                style.starts_with("italic") || style.starts_with("oblique")
+            });
+        TextStyle {
+            family,
+            size: self.font_size_of(node),
+            weight,
+            italic,
+        }
+    }
+
+    /// The nearest declared value of inherited property `name`, walking up
+    /// from `node`.
+    fn inherited_property(&self, node: NodeId, name: &str) -> Option<String> {
+        let mut current = Some(node);
+        while let Some(candidate) = current {
+            if let Some(value) = self.styles.get(&candidate).and_then(|s| s.get(name)) {
+                return Some(value.clone());
+            }
+            current = self.document.parent(candidate);
+        }
+        None
+    }
+
+    /// The element's font size in px, resolving `em` against the parent.
+    fn font_size_of(&self, node: NodeId) -> f32 {
+        let inherited = self
+            .document
+            .parent(node)
+            .filter(|&p| self.document.element(p).is_some())
+            .map_or(16.0, |parent| self.font_size_of(parent));
+        let Some(value) = self.styles.get(&node).and_then(|s| s.get("font-size")) else {
+            return inherited;
+        };
+        Length::parse(value)
+            .map(|length| {
+                // `em` on font-size itself refers to the parent's size.
+                length.resolve(&LengthContext {
+                    font_size: inherited,
+                    root_font_size: self.root_font_size,
+                    viewport_width: self.viewport_width,
+                    viewport_height: self.viewport_height,
+                    ..LengthContext::default()
+                })
+            })
+            .unwrap_or(inherited)
+    }
+}
+
+/// The border-box rect of `node`'s principal box, if it has one.
+pub fn box_rect(laid: &LayoutBox, node: NodeId) -> Option<Rect> {
+    if laid.node == Some(node) {
+        return Some(laid.rect);
+    }
+    laid.children.iter().find_map(|child| box_rect(child, node))
+}
+
+/// The deepest element box under page point (`x`, `y`), mapping the
+/// point through inverse transforms on the way down so links and
+/// controls hit where they are drawn. Later siblings paint on top, so
+/// they are tested first.
+pub fn hit_test(laid: &LayoutBox, x: f32, y: f32) -> Option<NodeId> {
+    let (x, y) = match &laid.transform {
+        Some(matrix) => matrix.inverse()?.apply(x, y),
+        None => (x, y),
+    };
+    let inside = x >= laid.rect.x
+        && x < laid.rect.x + laid.rect.width
+        && y >= laid.rect.y
+        && y < laid.rect.y + laid.rect.height;
+    if laid.clips_content() && !inside {
+        return None;
+    }
+    for child in laid.children.iter().rev() {
+        if let Some(hit) = hit_test(child, x, y) {
+            return Some(hit);
+        }
+    }
+    if inside {
+        laid.node
+    } else {
+        None
+    }
+}
+
+/// Rightmost content edge inside `laid`, relative to its left edge `x`.
+fn content_extent(laid: &LayoutBox, x: f32) -> f32 {
+    let children = laid
+        .children
+        .iter()
+        .map(|child| child.rect.x + child.rect.width - x);
+    let lines = laid.lines.iter().map(|line| line.rect.width);
+    children.chain(lines).fold(0.0, f32::max)
+}
+
+/// Shift a box and everything inside it by (`dx`, `dy`).
+fn offset_subtree(laid: &mut LayoutBox, dx: f32, dy: f32) {
+    if dx == 0.0 && dy == 0.0 {
+        return;
+    }
+    laid.rect.x += dx;
+    laid.rect.y += dy;
+    for child in &mut laid.children {
+        offset_subtree(child, dx, dy);
+    }
+    for line in &mut laid.lines {
+        line.rect.x += dx;
+        line.rect.y += dy;
+        for fragment in &mut line.fragments {
+            fragment.rect.x += dx;
+            fragment.rect.y += dy;
+        }
+    }
+}
+
+/// Horizontal placement of line boxes within their containing width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TextAlign {
+    Left,
+    Center,
+    Right,
+    /// Every line but the last stretches to the full width.
+    Justify,
+}
+
+/// The containments an element opts into with `contain`; `strict` and
+/// `content` expand to combinations of these.
+#[derive(Debug, Clone, Copy, Default)]
+struct Containment {
+    /// Sized as if empty, independent of descendants.
+    size: bool,
+    /// An independent formatting and containing block: out-of-flow
+    /// descendants cannot escape.
+    layout: bool,
+    /// Descendant painting clipped to the box.
+    paint: bool,
+}
+
+/// `content-visibility`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ContentVisibility {
+    Visible,
+    Hidden,
+    /// Skip the contents while the box is far off-screen.
+    Auto,
+}
+
+/// Display types layout distinguishes so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Display {
+    Block,
+    Inline,
+    /// Block-level with a `::marker` box; see `place_marker`.
+    ListItem,
+    None,
+}
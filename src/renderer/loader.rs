@@ -0,0 +1,100 @@
+//! Stylesheet loading: fetch, parse, and flatten `@import` chains.
+//!
+//! A fetched sheet may pull in further sheets via `@import`. The loader
+//! resolves those recursively through the network stack before the cascade
+//! runs, so the style engine only ever sees flat [`Stylesheet`]s. Imported
+//! rules inherit the import's media conditions, and a visited set stops
+//! import cycles.
+
+use std::collections::HashSet;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use crate::network::{NetworkStack, Request, ResourcePriority};
+
+use super::css::{self, Stylesheet};
+
+/// Fetches stylesheets and resolves their imports.
+pub struct StylesheetLoader {
+    stack: Arc<NetworkStack>,
+}
+
+impl StylesheetLoader {
+    pub fn new(stack: Arc<NetworkStack>) -> Self {
+        Self { stack }
+    }
+
+    /// Fetch `url` and return its rules with every reachable `@import`
+    /// inlined. Failed imports are skipped; the importing sheet still
+    /// applies.
+    pub async fn load(&self, url: &str) -> Stylesheet {
+        let mut visited = HashSet::new();
+        self.load_inner(url.to_owned(), &mut visited).await
+    }
+
+    /// Resolve `source` (already fetched from `base_url`), inlining its
+    /// imports.
+    pub async fn resolve(&self, source: &str, base_url: &str) -> Stylesheet {
+        let mut visited = HashSet::new();
+        visited.insert(base_url.to_owned());
+        self.resolve_inner(source, base_url, &mut visited).await
+    }
+
+    fn load_inner<'a>(
+        &'a self,
+        url: String,
+        visited: &'a mut HashSet<String>,
+    ) -> Pin<Box<dyn Future<Output = Stylesheet> + Send + 'a>> {
+        Box::pin(async move {
+            if !visited.insert(url.clone()) {
+                // Import cycle: the sheet is already being inlined higher
+                // up the chain.
+                return Stylesheet::default();
+            }
+            let request = Request::get(url.clone());
+            let Ok(response) = self
+                .stack
+                .fetch_prioritized(request, ResourcePriority::High)
+                .await
+            else {
+                return Stylesheet::default();
+            };
+            let source = String::from_utf8_lossy(&response.body).into_owned();
+            self.resolve_inner(&source, &url, visited).await
+        })
+    }
+
+    fn resolve_inner<'a>(
+        &'a self,
+        source: &'a str,
+        base_url: &'a str,
+        visited: &'a mut HashSet<String>,
+    ) -> Pin<Box<dyn Future<Output = Stylesheet> + Send + 'a>> {
+        Box::pin(async move {
+            let mut flattened = Stylesheet::default();
+            for import in css::collect_imports(source) {
+                let href = resolve_url(base_url, &import.href);
+                let imported = self.load_inner(href, visited).await;
+                for mut rule in imported.rules {
+                    // The import's media conditions gate every rule it
+                    // pulled in, on top of the rule's own.
+                    if let Some(media) = &import.media {
+                        rule.conditions.insert(0, media.clone());
+                    }
+                    flattened.rules.push(rule);
+                }
+            }
+            flattened
+                .rules
+                .extend(css::parse_stylesheet(source).rules);
+            flattened
+        })
+    }
+}
+
+/// Resolve `href` against `base` through the real URL parser in
+/// [`crate::url`]. Kept here because every loader already imports it.
+pub fn resolve_url(base: &str, href: &str) -> String {
+    crate::url::resolve(base, href)
+}
@@ -0,0 +1,233 @@
+//! Media query parsing and evaluation.
+//!
+//! Queries are parsed once with their stylesheet and evaluated against a
+//! [`MediaEnvironment`] every time styles are resolved, so a viewport
+//! resize or theme change picks up the right rules without reparsing.
+
+/// The facts a media query is evaluated against.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MediaEnvironment {
+    /// Viewport size in CSS pixels.
+    pub width: f32,
+    pub height: f32,
+    /// Device pixels per CSS pixel.
+    pub device_pixel_ratio: f32,
+    pub color_scheme: ColorScheme,
+}
+
+impl Default for MediaEnvironment {
+    fn default() -> Self {
+        Self {
+            width: 1280.0,
+            height: 720.0,
+            device_pixel_ratio: 1.0,
+            color_scheme: ColorScheme::Light,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorScheme {
+    Light,
+    Dark,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    Portrait,
+    Landscape,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MediaType {
+    All,
+    Screen,
+    Print,
+}
+
+/// A comma-separated media query list: matches if any query does.
+#[derive(Debug, Clone)]
+pub struct MediaQueryList {
+    queries: Vec<MediaQuery>,
+}
+
+/// One query: a media type and `and`-joined feature conditions, possibly
+/// negated as a whole.
+#[derive(Debug, Clone)]
+struct MediaQuery {
+    negated: bool,
+    media_type: MediaType,
+    conditions: Vec<MediaCondition>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum MediaCondition {
+    MinWidth(f32),
+    MaxWidth(f32),
+    Width(f32),
+    MinHeight(f32),
+    MaxHeight(f32),
+    Height(f32),
+    Orientation(Orientation),
+    PrefersColorScheme(ColorScheme),
+    /// Device pixels per CSS pixel.
+    MinResolution(f32),
+    MaxResolution(f32),
+    Resolution(f32),
+}
+
+impl MediaQueryList {
+    /// Parse the condition text of an `@media` rule, e.g.
+    /// `screen and (min-width: 600px), print`. Queries with features this
+    /// engine does not understand evaluate to false, per spec (`not all`).
+    pub fn parse(input: &str) -> Self {
+        let queries = input
+            .split(',')
+            .map(|q| MediaQuery::parse(q.trim()))
+            .collect();
+        Self { queries }
+    }
+
+    pub fn evaluate(&self, env: &MediaEnvironment) -> bool {
+        self.queries.iter().any(|query| query.evaluate(env))
+    }
+}
+
+impl MediaQuery {
+    /// An unparseable query becomes `not all`, which never matches.
+    fn never() -> Self {
+        Self {
+            negated: true,
+            media_type: MediaType::All,
+            conditions: Vec::new(),
+        }
+    }
+
+    fn parse(input: &str) -> Self {
+        let mut negated = false;
+        let mut media_type = MediaType::All;
+        let mut conditions = Vec::new();
+        let mut rest = input;
+
+        if let Some(stripped) = rest.strip_prefix("not ") {
+            negated = true;
+            rest = stripped.trim_start();
+        } else if let Some(stripped) = rest.strip_prefix("only ") {
+            rest = stripped.trim_start();
+        }
+
+        for (index, part) in rest.split(" and ").enumerate() {
+            let part = part.trim();
+            if part.starts_with('(') {
+                match MediaCondition::parse(part) {
+                    Some(condition) => conditions.push(condition),
+                    None => return Self::never(),
+                }
+            } else if index == 0 {
+                media_type = match part {
+                    "all" | "" => MediaType::All,
+                    "screen" => MediaType::Screen,
+                    "print" => MediaType::Print,
+                    _ => return Self::never(),
+                };
+            } else {
+                return Self::never();
+            }
+        }
+
+        Self {
+            negated,
+            media_type,
+            conditions,
+        }
+    }
+
+    fn evaluate(&self, env: &MediaEnvironment) -> bool {
+        // This engine renders to a screen; print styles only apply when
+        // printing, which evaluates stylesheets with its own environment.
+        let type_matches = matches!(self.media_type, MediaType::All | MediaType::Screen);
+        let conditions_match = self
+            .conditions
+            .iter()
+            .all(|condition| condition.evaluate(env));
+        (type_matches && conditions_match) != self.negated
+    }
+}
+
+impl MediaCondition {
+    fn parse(input: &str) -> Option<Self> {
+        let inner = input.strip_prefix('(')?.strip_suffix(')')?;
+        let (feature, value) = match inner.split_once(':') {
+            Some((f, v)) => (f.trim(), v.trim()),
+            // Boolean feature form, e.g. `(orientation)`: not supported.
+            None => return None,
+        };
+        Some(match feature {
+            "min-width" => Self::MinWidth(parse_length(value)?),
+            "max-width" => Self::MaxWidth(parse_length(value)?),
+            "width" => Self::Width(parse_length(value)?),
+            "min-height" => Self::MinHeight(parse_length(value)?),
+            "max-height" => Self::MaxHeight(parse_length(value)?),
+            "height" => Self::Height(parse_length(value)?),
+            "orientation" => Self::Orientation(match value {
+                "portrait" => Orientation::Portrait,
+                "landscape" => Orientation::Landscape,
+                _ => return None,
+            }),
+            "prefers-color-scheme" => Self::PrefersColorScheme(match value {
+                "light" => ColorScheme::Light,
+                "dark" => ColorScheme::Dark,
+                _ => return None,
+            }),
+            "min-resolution" => Self::MinResolution(parse_resolution(value)?),
+            "max-resolution" => Self::MaxResolution(parse_resolution(value)?),
+            "resolution" => Self::Resolution(parse_resolution(value)?),
+            _ => return None,
+        })
+    }
+
+    fn evaluate(&self, env: &MediaEnvironment) -> bool {
+        match *self {
+            Self::MinWidth(v) => env.width >= v,
+            Self::MaxWidth(v) => env.width <= v,
+            Self::Width(v) => env.width == v,
+            Self::MinHeight(v) => env.height >= v,
+            Self::MaxHeight(v) => env.height <= v,
+            Self::Height(v) => env.height == v,
+            Self::Orientation(o) => {
+                let actual = if env.height > env.width {
+                    Orientation::Portrait
+                } else {
+                    Orientation::Landscape
+                };
+                actual == o
+            }
+            Self::PrefersColorScheme(scheme) => env.color_scheme == scheme,
+            Self::MinResolution(v) => env.device_pixel_ratio >= v,
+            Self::MaxResolution(v) => env.device_pixel_ratio <= v,
+            Self::Resolution(v) => env.device_pixel_ratio == v,
+        }
+    }
+}
+
+/// Lengths in media queries: px (and bare numbers, treated as px).
+fn parse_length(value: &str) -> Option<f32> {
+    value
+        .strip_suffix("px")
+        .unwrap_or(value)
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Resolutions: `dppx`/`x` pass through, `dpi` is converted at 96dpi per
+/// CSS pixel.
+fn parse_resolution(value: &str) -> Option<f32> {
+    if let Some(v) = value.strip_suffix("dppx").or_else(|| value.strip_suffix('x')) {
+        return v.trim().parse().ok();
+    }
+    if let Some(v) = value.strip_suffix("dpi") {
+        return v.trim().parse::<f32>().ok().map(|dpi| dpi / 96.0);
+    }
+    None
+}
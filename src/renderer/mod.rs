@@ -0,0 +1,29 @@
+//! The renderer: DOM construction, CSS, style resolution, and layout.
+//!
+//! A page flows through here as `html → dom → styled tree → layout tree`.
+//! Each stage lives in its own module; [`crate::ui::tab::Tab`] drives them
+//! in order for a navigation.
+
+pub mod animation;
+pub mod background;
+pub mod border;
+pub mod canvas;
+pub mod color;
+pub mod css;
+pub mod dirty;
+pub mod dom;
+pub mod fonts;
+pub mod frame;
+pub mod generated;
+pub mod gradient;
+pub mod html;
+pub mod layout;
+pub mod loader;
+pub mod media;
+pub mod preload;
+pub mod script;
+pub mod shadow;
+pub mod style;
+pub mod svg;
+pub mod transform;
+pub mod values;
@@ -0,0 +1,151 @@
+//! Preload scanner: discover subresources ahead of the main parser.
+//!
+//! When the parser blocks on a synchronous script, the bytes after the
+//! block point are already in hand but unparsed. The scanner runs a
+//! forgiving tag-level pass over them, pulls out the URLs the page will
+//! need (`img`/`script` sources, stylesheet links, explicit preload
+//! hints), and starts their fetches at the priority the real request will
+//! use — one of the biggest practical page-load wins.
+//!
+//! The scanner never mutates the tree and tolerates being wrong: a
+//! speculative fetch for a resource the parser never requests just warms
+//! the cache.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::network::hints::ResourceHint;
+use crate::network::{NetworkStack, Request, ResourcePriority};
+
+use super::dom::ElementData;
+use super::html;
+use super::loader::resolve_url;
+
+/// What kind of resource a discovered URL is, which sets its priority.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiscoveredKind {
+    Stylesheet,
+    Script,
+    Image,
+    /// A `<link rel>` speculative hint (preload/preconnect/dns-prefetch).
+    Hint(ResourceHint),
+}
+
+impl DiscoveredKind {
+    /// The priority the eventual real request will carry.
+    fn priority(&self) -> ResourcePriority {
+        match self {
+            DiscoveredKind::Stylesheet => ResourcePriority::VeryHigh,
+            DiscoveredKind::Script => ResourcePriority::High,
+            DiscoveredKind::Image => ResourcePriority::Low,
+            DiscoveredKind::Hint(_) => ResourcePriority::VeryLow,
+        }
+    }
+}
+
+/// A subresource found ahead of the parser, with its resolved URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscoveredResource {
+    pub url: String,
+    pub kind: DiscoveredKind,
+}
+
+/// Scans unparsed markup for fetchable subresources. One scanner lives
+/// per document load, so rescanning overlapping byte ranges never
+/// double-fetches.
+#[derive(Default)]
+pub struct PreloadScanner {
+    seen: HashSet<String>,
+}
+
+impl PreloadScanner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Scan `unparsed` markup and return the not-yet-seen resources, URLs
+    /// resolved against `base_url`. Tag soup ahead of the parser may be
+    /// truncated mid-tag; the last partial tag is simply skipped.
+    pub fn scan(&mut self, unparsed: &str, base_url: &str) -> Vec<DiscoveredResource> {
+        let mut found = Vec::new();
+        let mut pos = 0;
+        while let Some(open) = unparsed[pos..].find('<').map(|i| pos + i) {
+            if unparsed[open..].starts_with("<!--") {
+                match unparsed[open + 4..].find("-->") {
+                    Some(end) => {
+                        pos = open + 4 + end + 3;
+                        continue;
+                    }
+                    None => break,
+                }
+            }
+            let Some((element, _, next)) = html::parse_tag(unparsed, open) else {
+                pos = open + 1;
+                continue;
+            };
+            pos = next;
+            if let Some(resource) = self.discover(&element, base_url) {
+                found.push(resource);
+            }
+            // Raw-text elements can contain markup-looking content; skip
+            // ahead to their close tag so inline script bodies are not
+            // scanned as tags.
+            if element.tag_name == "script" || element.tag_name == "style" {
+                let close = format!("</{}", element.tag_name);
+                match unparsed[pos..].to_ascii_lowercase().find(&close) {
+                    Some(i) => pos += i,
+                    None => break,
+                }
+            }
+        }
+        found
+    }
+
+    /// Scan and immediately start speculative fetches through `stack`.
+    pub fn scan_and_fetch(&mut self, unparsed: &str, base_url: &str, stack: &Arc<NetworkStack>) {
+        for resource in self.scan(unparsed, base_url) {
+            match resource.kind {
+                // Preconnect/dns-prefetch do transport warm-up rather
+                // than a fetch; route them through the hint machinery.
+                DiscoveredKind::Hint(hint) => stack.apply_hint(hint),
+                kind => {
+                    let stack = Arc::clone(stack);
+                    let priority = kind.priority();
+                    tokio::spawn(async move {
+                        let _ = stack
+                            .fetch_prioritized(Request::get(resource.url), priority)
+                            .await;
+                    });
+                }
+            }
+        }
+    }
+
+    /// The resource `element` will fetch, if any and not already seen.
+    fn discover(&mut self, element: &ElementData, base_url: &str) -> Option<DiscoveredResource> {
+        let (kind, href) = match element.tag_name.as_str() {
+            "img" => (DiscoveredKind::Image, element.attr("src")?),
+            "script" => (DiscoveredKind::Script, element.attr("src")?),
+            "link" => {
+                let rel = element.attr("rel")?.to_ascii_lowercase();
+                let href = element.attr("href")?;
+                if rel == "stylesheet" {
+                    (DiscoveredKind::Stylesheet, href)
+                } else {
+                    let resolved = resolve_url(base_url, href);
+                    let hint = ResourceHint::from_link(&rel, &resolved)?;
+                    (DiscoveredKind::Hint(hint), href)
+                }
+            }
+            _ => return None,
+        };
+        if href.is_empty() || href.starts_with("data:") {
+            return None;
+        }
+        let url = resolve_url(base_url, href);
+        if !self.seen.insert(url.clone()) {
+            return None;
+        }
+        Some(DiscoveredResource { url, kind })
+    }
+}
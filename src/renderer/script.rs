@@ -0,0 +1,76 @@
+//! Finding the scripts a parsed document wants to run.
+//!
+//! [`extract_scripts`] walks the document for `<script>` elements and
+//! classifies them — classic versus module, inline versus external —
+//! without fetching or executing anything. The engine pipeline fetches
+//! external sources (and, for modules, the import graph) and the JS
+//! runtime runs them in the order the spec requires.
+
+use super::dom::{Document, NodeId};
+
+/// How a script executes relative to parsing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptKind {
+    /// A classic script; `defer`/`async` move it out of document order.
+    Classic { defer: bool, r#async: bool },
+    /// `type="module"`: always deferred, imports resolved per graph.
+    Module,
+}
+
+/// Where the script's source is.
+#[derive(Debug, Clone)]
+pub enum ScriptSource {
+    /// Inline text content of the element.
+    Inline(String),
+    /// The `src` attribute, unresolved.
+    External(String),
+}
+
+/// One `<script>` element, classified.
+#[derive(Debug, Clone)]
+pub struct PageScript {
+    pub node: NodeId,
+    pub kind: ScriptKind,
+    pub source: ScriptSource,
+}
+
+/// All runnable `<script>` elements in document order. Unknown `type`
+/// values (data blocks, import maps we don't support yet) are skipped,
+/// per spec.
+pub fn extract_scripts(document: &Document) -> Vec<PageScript> {
+    let mut scripts = Vec::new();
+    for node in document.descendants(document.root()) {
+        let Some(element) = document.element(node) else {
+            continue;
+        };
+        if element.tag_name != "script" {
+            continue;
+        }
+        let kind = match element.attr("type").map(str::trim) {
+            None | Some("") => classic(element),
+            Some(t) if t.eq_ignore_ascii_case("module") => ScriptKind::Module,
+            Some(t) if is_javascript_mime(t) => classic(element),
+            Some(_) => continue,
+        };
+        let source = match element.attr("src") {
+            Some(src) if !src.is_empty() => ScriptSource::External(src.to_owned()),
+            _ => ScriptSource::Inline(document.text_content(node)),
+        };
+        scripts.push(PageScript { node, kind, source });
+    }
+    scripts
+}
+
+fn classic(element: &super::dom::ElementData) -> ScriptKind {
+    ScriptKind::Classic {
+        defer: element.attr("defer").is_some(),
+        r#async: element.attr("async").is_some(),
+    }
+}
+
+fn is_javascript_mime(t: &str) -> bool {
+    matches!(
+        t.to_ascii_lowercase().as_str(),
+        "text/javascript" | "application/javascript" | "application/ecmascript"
+    )
+}
@@ -0,0 +1,718 @@
+//! Style resolution: from matched declarations to computed styles.
+//!
+//! [`StyleEngine`] owns the document's stylesheets and resolves computed
+//! styles with the full cascade: declarations are ranked by cascade level
+//! (user-agent < author < inline, with `!important` inverting the origin
+//! order), then specificity, then source order. Custom properties
+//! (`--name`) inherit down the tree and are substituted into `var()`
+//! references before a value is considered computed.
+
+use std::collections::{HashMap, HashSet};
+
+use super::css::{self, AncestorFilter, Declaration, InteractionState, RuleIndex, Stylesheet};
+use super::dom::{Document, NodeData, NodeId};
+use super::media::MediaEnvironment;
+use super::values::{Length, LengthContext};
+
+/// Resolved property → value map for one element.
+pub type ComputedStyle = HashMap<String, String>;
+
+/// Inherited custom property values for one element.
+pub type CustomProperties = HashMap<String, String>;
+
+/// Where a declaration came from, ranked in ascending cascade precedence.
+/// Important declarations invert the origin order, per CSS Cascading 4.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum CascadeLevel {
+    UserAgentNormal,
+    AuthorNormal,
+    InlineNormal,
+    AuthorImportant,
+    InlineImportant,
+    UserAgentImportant,
+}
+
+impl CascadeLevel {
+    fn user_agent(important: bool) -> Self {
+        if important {
+            Self::UserAgentImportant
+        } else {
+            Self::UserAgentNormal
+        }
+    }
+
+    fn author(important: bool) -> Self {
+        if important {
+            Self::AuthorImportant
+        } else {
+            Self::AuthorNormal
+        }
+    }
+
+    fn inline(important: bool) -> Self {
+        if important {
+            Self::InlineImportant
+        } else {
+            Self::InlineNormal
+        }
+    }
+}
+
+/// Defaults every page starts from. Deliberately small: element display
+/// types, hidden metadata elements, heading/emphasis basics, link color.
+const UA_STYLESHEET: &str = "
+    html, body, div, p, h1, h2, h3, h4, h5, h6, ul, ol, form, header,
+    footer, nav, main, section, article, aside, blockquote, pre, figure,
+    fieldset, table { display: block; }
+    li { display: list-item; }
+    head, style, script, title, meta, link, template { display: none; }
+    body { margin: 8px; font-size: 16px; }
+    h1 { font-size: 2em; font-weight: bold; margin: 0.67em 0; }
+    h2 { font-size: 1.5em; font-weight: bold; margin: 0.83em 0; }
+    h3 { font-size: 1.17em; font-weight: bold; margin: 1em 0; }
+    h4 { font-weight: bold; margin: 1.33em 0; }
+    p { margin: 1em 0; }
+    ul, ol { margin: 1em 0; padding-left: 40px; }
+    ul { list-style-type: disc; }
+    ol { list-style-type: decimal; }
+    ul ul { list-style-type: circle; }
+    ul ul ul { list-style-type: square; }
+    b, strong { font-weight: bold; }
+    i, em { font-style: italic; }
+    a { color: #0000ee; text-decoration: underline; }
+    pre, code { font-family: monospace; }
+    pre { margin: 1em 0; white-space: pre; }
+";
+
+/// Resolves computed styles for a document.
+pub struct StyleEngine {
+    user_agent: Stylesheet,
+    user_agent_index: RuleIndex,
+    stylesheets: Vec<Stylesheet>,
+    /// Rule indices parallel to `stylesheets`, rebuilt on mutation so the
+    /// per-element matching path never pays for index construction.
+    indices: Vec<RuleIndex>,
+    /// Stylesheets scoped to one shadow tree, keyed by its root. They
+    /// apply only inside that tree, and document sheets don't reach in.
+    scoped: HashMap<NodeId, Vec<Stylesheet>>,
+    scoped_indices: HashMap<NodeId, Vec<RuleIndex>>,
+    /// Current `:hover`/`:focus`/`:active` state, maintained by the UI.
+    interaction: InteractionState,
+}
+
+impl StyleEngine {
+    pub fn new() -> Self {
+        let user_agent = css::parse_stylesheet(UA_STYLESHEET);
+        Self {
+            user_agent_index: RuleIndex::build(&user_agent),
+            user_agent,
+            stylesheets: Vec::new(),
+            indices: Vec::new(),
+            scoped: HashMap::new(),
+            scoped_indices: HashMap::new(),
+            interaction: InteractionState::default(),
+        }
+    }
+
+    /// Add an author stylesheet (document `<style>`, fetched sheet).
+    pub fn add_stylesheet(&mut self, sheet: Stylesheet) {
+        self.indices.push(RuleIndex::build(&sheet));
+        self.stylesheets.push(sheet);
+    }
+
+    /// Add a stylesheet scoped to the shadow tree rooted at `root`
+    /// (a `<style>` inside the shadow content).
+    pub fn add_scoped_stylesheet(&mut self, root: NodeId, sheet: Stylesheet) {
+        self.scoped_indices
+            .entry(root)
+            .or_default()
+            .push(RuleIndex::build(&sheet));
+        self.scoped.entry(root).or_default().push(sheet);
+    }
+
+    pub fn clear(&mut self) {
+        self.stylesheets.clear();
+        self.indices.clear();
+        self.scoped.clear();
+        self.scoped_indices.clear();
+    }
+
+    /// The author sheets that apply to `node`'s tree: a shadow tree sees
+    /// only its own scoped sheets, the document tree only the document's.
+    fn author_sheets(&self, document: &Document, node: NodeId) -> &[Stylesheet] {
+        match document.containing_shadow_root(node) {
+            Some(root) => self.scoped.get(&root).map_or(&[], Vec::as_slice),
+            None => &self.stylesheets,
+        }
+    }
+
+    /// The rule indices parallel to [`StyleEngine::author_sheets`].
+    fn author_indices(&self, document: &Document, node: NodeId) -> &[RuleIndex] {
+        match document.containing_shadow_root(node) {
+            Some(root) => self.scoped_indices.get(&root).map_or(&[], Vec::as_slice),
+            None => &self.indices,
+        }
+    }
+
+    pub fn stylesheets(&self) -> &[Stylesheet] {
+        &self.stylesheets
+    }
+
+    pub fn interaction(&self) -> &InteractionState {
+        &self.interaction
+    }
+
+    /// Replace the interaction state. The caller restyles the elements the
+    /// change touches; see [`crate::ui::tab::Tab::set_hovered`].
+    pub fn set_interaction(&mut self, state: InteractionState) {
+        self.interaction = state;
+    }
+
+    /// Whether any stylesheet output can change with the viewport size:
+    /// media-conditioned rules or viewport-relative units in values.
+    /// When false, a window resize only needs relayout, not restyle.
+    pub fn depends_on_viewport(&self) -> bool {
+        self.stylesheets
+            .iter()
+            .chain(self.scoped.values().flatten())
+            .flat_map(|sheet| &sheet.rules)
+            .any(|rule| {
+                !rule.conditions.is_empty()
+                    || rule.declarations.iter().any(|declaration| {
+                        ["vw", "vh", "vmin", "vmax"]
+                            .iter()
+                            .any(|unit| declaration.value.contains(unit))
+                    })
+            })
+    }
+
+    /// Whether any active stylesheet uses a dynamic pseudo-class. When
+    /// none does, interaction changes never need a restyle.
+    pub fn uses_interaction_styles(&self) -> bool {
+        std::iter::once(&self.user_agent)
+            .chain(self.stylesheets.iter())
+            .chain(self.scoped.values().flatten())
+            .flat_map(|sheet| &sheet.rules)
+            .flat_map(|rule| &rule.selectors)
+            .any(|selector| {
+                std::iter::once(&selector.key)
+                    .chain(selector.ancestors.iter().map(|(_, c)| c))
+                    .any(|compound| {
+                        compound
+                            .pseudo_classes
+                            .iter()
+                            .any(|pseudo_class| pseudo_class.is_dynamic())
+                    })
+            })
+    }
+
+    /// Compute the style of every element, in document order. Custom
+    /// properties and the inheritable properties flow from the parent
+    /// element as the walk descends.
+    pub fn resolve(
+        &self,
+        document: &Document,
+        env: &MediaEnvironment,
+    ) -> HashMap<NodeId, ComputedStyle> {
+        self.resolve_inherited(document, env, |node| self.cascade(document, node, env))
+    }
+
+    /// [`StyleEngine::resolve`] with selector matching spread across the
+    /// rayon pool. Matching each element against the rule set is the
+    /// expensive, embarrassingly parallel part; the inheritance fold that
+    /// follows is sequential either way. Worth it on large documents,
+    /// measured in `benches/style_parallel.rs`.
+    pub fn resolve_parallel(
+        &self,
+        document: &Document,
+        env: &MediaEnvironment,
+    ) -> HashMap<NodeId, ComputedStyle> {
+        use rayon::prelude::*;
+
+        let elements: Vec<NodeId> = document
+            .composed_descendants(document.root())
+            .into_iter()
+            .filter(|&node| matches!(document.node(node).data, NodeData::Element(_)))
+            .collect();
+        let mut matched: HashMap<NodeId, Vec<Declaration>> = elements
+            .par_iter()
+            .map(|&node| (node, self.cascade(document, node, env)))
+            .collect();
+        self.resolve_inherited(document, env, |node| matched.remove(&node).unwrap_or_default())
+    }
+
+    /// The sequential half of style resolution: fold custom properties
+    /// and inherited values down the composed tree, taking each element's
+    /// cascade-ordered declarations from `cascade_of`.
+    fn resolve_inherited(
+        &self,
+        document: &Document,
+        env: &MediaEnvironment,
+        mut cascade_of: impl FnMut(NodeId) -> Vec<Declaration>,
+    ) -> HashMap<NodeId, ComputedStyle> {
+        let mut styles: HashMap<NodeId, ComputedStyle> = HashMap::new();
+        let mut custom_by_node: HashMap<NodeId, CustomProperties> = HashMap::new();
+        let mut root_font_size = 16.0;
+        // Subtrees under `content-visibility: hidden` can never paint, so
+        // their style never needs computing (`auto` depends on scroll
+        // position, which only layout knows, so it gets no skip here).
+        let mut skipped: HashSet<NodeId> = HashSet::new();
+
+        for node in document.composed_descendants(document.root()) {
+            if !matches!(document.node(node).data, NodeData::Element(_)) {
+                continue;
+            }
+            if let Some(parent) = document.parent(node) {
+                let parent_hides = styles.get(&parent).map_or(false, |style| {
+                    style
+                        .get("content-visibility")
+                        .map_or(false, |value| value == "hidden")
+                });
+                if parent_hides || skipped.contains(&parent) {
+                    skipped.insert(node);
+                    continue;
+                }
+            }
+            let declarations = cascade_of(node);
+            let custom = custom_for(
+                &declarations,
+                inherited_custom(document, node, &custom_by_node),
+            );
+            let mut style = compute(&declarations, &custom);
+            let parent = parent_style(document, node, &styles);
+            absolutize_font_size(&mut style, parent, root_font_size, env);
+            if let Some(parent) = parent {
+                inherit_into(&mut style, parent);
+            } else {
+                // The root element: its computed font size is what `rem`
+                // means for the rest of the tree.
+                root_font_size = font_size_px(&style).unwrap_or(16.0);
+            }
+            styles.insert(node, style);
+            custom_by_node.insert(node, custom);
+        }
+        styles
+    }
+
+    /// Computed style of a single element, walking its ancestor chain for
+    /// inherited properties and custom properties.
+    pub fn style_for(
+        &self,
+        document: &Document,
+        node: NodeId,
+        env: &MediaEnvironment,
+    ) -> ComputedStyle {
+        let mut chain = vec![node];
+        let mut ancestor = document.parent(node);
+        while let Some(candidate) = ancestor {
+            chain.push(candidate);
+            ancestor = document.parent(candidate);
+        }
+        let mut custom = CustomProperties::new();
+        let mut inherited: Option<ComputedStyle> = None;
+        let mut root_font_size = 16.0;
+        for &element in chain.iter().rev() {
+            if document.element(element).is_none() {
+                continue;
+            }
+            let declarations = self.cascade(document, element, env);
+            custom = custom_for(&declarations, custom);
+            let mut style = compute(&declarations, &custom);
+            absolutize_font_size(&mut style, inherited.as_ref(), root_font_size, env);
+            if let Some(parent) = &inherited {
+                inherit_into(&mut style, parent);
+            } else {
+                root_font_size = font_size_px(&style).unwrap_or(16.0);
+            }
+            inherited = Some(style);
+        }
+        inherited.unwrap_or_default()
+    }
+
+    /// Computed style of a pseudo-element of `node`, or `None` when no
+    /// rule generates it (no matched rules, or `content: none/normal`).
+    pub fn pseudo_style(
+        &self,
+        document: &Document,
+        node: NodeId,
+        pseudo: css::PseudoElement,
+        env: &MediaEnvironment,
+    ) -> Option<ComputedStyle> {
+        let element = document.element(node)?;
+        let mut entries: Vec<(CascadeLevel, (u32, u32, u32), usize, Declaration)> = Vec::new();
+        let mut base_order = 0;
+        let filter = AncestorFilter::for_element(document, node);
+        let sheets = std::iter::once((&self.user_agent, &self.user_agent_index, true)).chain(
+            self.author_sheets(document, node)
+                .iter()
+                .zip(self.author_indices(document, node))
+                .map(|(sheet, index)| (sheet, index, false)),
+        );
+        for (sheet, index, user_agent) in sheets {
+            for position in index.candidates(element) {
+                let rule = &sheet.rules[position];
+                if rule.applies(env) {
+                    let best = rule
+                        .selectors
+                        .iter()
+                        .filter(|s| !s.fast_reject(&filter))
+                        .filter(|s| s.matches_pseudo(document, node, Some(pseudo), &self.interaction))
+                        .map(|s| s.specificity())
+                        .max();
+                    if let Some(specificity) = best {
+                        for declaration in &rule.declarations {
+                            let level = if user_agent {
+                                CascadeLevel::user_agent(declaration.important)
+                            } else {
+                                CascadeLevel::author(declaration.important)
+                            };
+                            entries.push((
+                                level,
+                                specificity,
+                                base_order + position,
+                                declaration.clone(),
+                            ));
+                        }
+                    }
+                }
+            }
+            base_order += sheet.rules.len();
+        }
+        if entries.is_empty() {
+            return None;
+        }
+        entries.sort_by(|a, b| (a.0, a.1, a.2).cmp(&(b.0, b.1, b.2)));
+        let declarations: Vec<Declaration> =
+            entries.into_iter().map(|(_, _, _, d)| d).collect();
+        // Custom properties inherit from the originating element.
+        let custom = self.custom_in_scope(document, node, env);
+        let custom = custom_for(&declarations, custom);
+        let style = compute(&declarations, &custom);
+        match style.get("content").map(String::as_str) {
+            None | Some("none") | Some("normal") => None,
+            Some(_) => Some(style),
+        }
+    }
+
+    /// Custom properties in scope at `node`: its ancestors' sets applied
+    /// root-down, then its own declarations.
+    fn custom_in_scope(
+        &self,
+        document: &Document,
+        node: NodeId,
+        env: &MediaEnvironment,
+    ) -> CustomProperties {
+        let mut chain = vec![node];
+        let mut ancestor = document.parent(node);
+        while let Some(candidate) = ancestor {
+            chain.push(candidate);
+            ancestor = document.parent(candidate);
+        }
+        let mut custom = CustomProperties::new();
+        for &element in chain.iter().rev() {
+            if document.element(element).is_none() {
+                continue;
+            }
+            custom = custom_for(&self.cascade(document, element, env), custom);
+        }
+        custom
+    }
+
+    /// Declarations applying to `node` in ascending cascade order: the
+    /// last write for a property wins.
+    pub fn cascade(
+        &self,
+        document: &Document,
+        node: NodeId,
+        env: &MediaEnvironment,
+    ) -> Vec<Declaration> {
+        let Some(element) = document.element(node) else {
+            return Vec::new();
+        };
+        let mut entries: Vec<(CascadeLevel, (u32, u32, u32), usize, Declaration)> = Vec::new();
+        let mut base_order = 0;
+        // Two pruning layers before the full `matches` walk: the rule index
+        // yields only rules whose rightmost compound could match this
+        // element, and the ancestor filter rejects selectors whose ancestor
+        // compounds name an id/class/tag no ancestor carries.
+        let filter = AncestorFilter::for_element(document, node);
+
+        let sheets = std::iter::once((&self.user_agent, &self.user_agent_index, true)).chain(
+            self.author_sheets(document, node)
+                .iter()
+                .zip(self.author_indices(document, node))
+                .map(|(sheet, index)| (sheet, index, false)),
+        );
+        for (sheet, index, user_agent) in sheets {
+            for position in index.candidates(element) {
+                let rule = &sheet.rules[position];
+                if rule.applies(env) {
+                    let best = rule
+                        .selectors
+                        .iter()
+                        .filter(|s| !s.fast_reject(&filter))
+                        .filter(|s| s.matches(document, node, &self.interaction))
+                        .map(|s| s.specificity())
+                        .max();
+                    if let Some(specificity) = best {
+                        for declaration in &rule.declarations {
+                            let level = if user_agent {
+                                CascadeLevel::user_agent(declaration.important)
+                            } else {
+                                CascadeLevel::author(declaration.important)
+                            };
+                            entries.push((
+                                level,
+                                specificity,
+                                base_order + position,
+                                declaration.clone(),
+                            ));
+                        }
+                    }
+                }
+            }
+            base_order += sheet.rules.len();
+        }
+
+        let inline = element
+            .attr("style")
+            .map(css::parse_declarations)
+            .unwrap_or_default();
+        for declaration in inline {
+            entries.push((
+                CascadeLevel::inline(declaration.important),
+                (0, 0, 0),
+                base_order,
+                declaration,
+            ));
+        }
+
+        entries.sort_by(|a, b| (a.0, a.1, a.2).cmp(&(b.0, b.1, b.2)));
+        entries
+            .into_iter()
+            .map(|(_, _, _, declaration)| declaration)
+            .collect()
+    }
+}
+
+impl Default for StyleEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Collect every `<style>` element in `document` into `engine`: sheets in
+/// the light tree apply document-wide, sheets inside a shadow tree scope
+/// to that tree. External sheets go through
+/// [`crate::renderer::loader::StylesheetLoader`] instead.
+pub fn collect_styles(document: &Document, engine: &mut StyleEngine) {
+    let style_source = |node: NodeId| -> Option<String> {
+        (document.element(node)?.tag_name == "style").then(|| document.text_content(node))
+    };
+    for node in document.descendants(document.root()) {
+        if let Some(source) = style_source(node) {
+            engine.add_stylesheet(css::parse_stylesheet(&source));
+        }
+    }
+    // The document walk never enters shadow roots, so there is no overlap.
+    for (_, root) in document.shadow_hosts() {
+        for node in document.descendants(root) {
+            if let Some(source) = style_source(node) {
+                engine.add_scoped_stylesheet(root, css::parse_stylesheet(&source));
+            }
+        }
+    }
+}
+
+/// Apply `declarations`' custom properties on top of the inherited set.
+fn custom_for(declarations: &[Declaration], mut inherited: CustomProperties) -> CustomProperties {
+    for declaration in declarations {
+        if let Some(name) = declaration.name.strip_prefix("--") {
+            inherited.insert(name.to_owned(), declaration.value.clone());
+        }
+    }
+    inherited
+}
+
+/// Collapse cascade-ordered declarations into a property map, with
+/// `var()` substitution.
+fn compute(declarations: &[Declaration], custom: &CustomProperties) -> ComputedStyle {
+    let mut style = ComputedStyle::new();
+    for declaration in declarations {
+        if declaration.name.starts_with("--") {
+            continue;
+        }
+        if let Some(value) = substitute_vars(&declaration.value, custom) {
+            style.insert(declaration.name.clone(), value);
+        }
+    }
+    style
+}
+
+/// Properties that inherit by default, per their specs. Everything else
+/// starts from its initial value on each element.
+const INHERITED_PROPERTIES: &[&str] = &[
+    "color",
+    "cursor",
+    "direction",
+    "font-family",
+    "font-size",
+    "font-style",
+    "font-variant",
+    "font-weight",
+    "letter-spacing",
+    "line-height",
+    "list-style-type",
+    "quotes",
+    "text-align",
+    "text-indent",
+    "text-transform",
+    "visibility",
+    "white-space",
+    "word-spacing",
+];
+
+/// Fill unset inheritable properties of `style` from the parent's
+/// computed values.
+fn inherit_into(style: &mut ComputedStyle, parent: &ComputedStyle) {
+    for &name in INHERITED_PROPERTIES {
+        if style.contains_key(name) {
+            continue;
+        }
+        if let Some(value) = parent.get(name) {
+            style.insert(name.to_owned(), value.clone());
+        }
+    }
+}
+
+/// The computed style of `node`'s nearest element ancestor.
+fn parent_style<'a>(
+    document: &Document,
+    node: NodeId,
+    styles: &'a HashMap<NodeId, ComputedStyle>,
+) -> Option<&'a ComputedStyle> {
+    let mut ancestor = document.parent(node);
+    while let Some(candidate) = ancestor {
+        if let Some(style) = styles.get(&candidate) {
+            return Some(style);
+        }
+        ancestor = document.parent(candidate);
+    }
+    None
+}
+
+/// Inheritance passes computed values, so `font-size` is absolutized to
+/// pixels before children see it — an inherited `1.2em` would otherwise
+/// compound at every level. `em` and `%` resolve against the parent's
+/// size, `rem` against the root's.
+fn absolutize_font_size(
+    style: &mut ComputedStyle,
+    parent: Option<&ComputedStyle>,
+    root_font_size: f32,
+    env: &MediaEnvironment,
+) {
+    let Some(value) = style.get("font-size") else {
+        return;
+    };
+    let parent_px = parent.and_then(font_size_px).unwrap_or(16.0);
+    let px = Length::parse(value)
+        .map(|length| {
+            length.resolve(&LengthContext {
+                font_size: parent_px,
+                root_font_size,
+                containing_block: parent_px,
+                viewport_width: env.width,
+                viewport_height: env.height,
+            })
+        })
+        .unwrap_or(parent_px);
+    style.insert("font-size".to_owned(), format!("{px}px"));
+}
+
+/// Read an already absolutized `font-size` back as pixels.
+fn font_size_px(style: &ComputedStyle) -> Option<f32> {
+    style
+        .get("font-size")?
+        .strip_suffix("px")?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// The custom property set of `node`'s nearest element ancestor.
+fn inherited_custom(
+    document: &Document,
+    node: NodeId,
+    custom_by_node: &HashMap<NodeId, CustomProperties>,
+) -> CustomProperties {
+    let mut ancestor = document.parent(node);
+    while let Some(candidate) = ancestor {
+        if let Some(custom) = custom_by_node.get(&candidate) {
+            return custom.clone();
+        }
+        ancestor = document.parent(candidate);
+    }
+    CustomProperties::new()
+}
+
+/// Substitution depth guard: a `var()` chain deeper than this is treated
+/// as a cycle and the declaration is dropped.
+const MAX_VAR_DEPTH: usize = 16;
+
+/// Replace every `var(--name, fallback)` in `value` with the property's
+/// value, or the fallback when unset. `None` means the value references an
+/// unset property with no fallback (the declaration is invalid at
+/// computed-value time, per spec).
+pub fn substitute_vars(value: &str, custom: &CustomProperties) -> Option<String> {
+    substitute_vars_inner(value, custom, 0)
+}
+
+fn substitute_vars_inner(
+    value: &str,
+    custom: &CustomProperties,
+    depth: usize,
+) -> Option<String> {
+    if depth > MAX_VAR_DEPTH {
+        return None;
+    }
+    let Some(start) = value.find("var(") else {
+        return Some(value.to_owned());
+    };
+    let after = &value[start + 4..];
+    let close = matching_paren(after)?;
+    let inner = &after[..close];
+    let (name, fallback) = match inner.split_once(',') {
+        Some((name, fallback)) => (name.trim(), Some(fallback.trim())),
+        None => (inner.trim(), None),
+    };
+    let name = name.strip_prefix("--")?;
+    let replacement = match custom.get(name) {
+        Some(value) => value.clone(),
+        None => fallback?.to_owned(),
+    };
+    // The replacement may itself contain var() references.
+    let replacement = substitute_vars_inner(&replacement, custom, depth + 1)?;
+    let mut out = String::with_capacity(value.len());
+    out.push_str(&value[..start]);
+    out.push_str(&replacement);
+    out.push_str(&after[close + 1..]);
+    substitute_vars_inner(&out, custom, depth + 1)
+}
+
+/// Offset of the `)` closing the parenthesis that `input` starts inside.
+fn matching_paren(input: &str) -> Option<usize> {
+    let mut depth = 1;
+    for (index, ch) in input.char_indices() {
+        match ch {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(index);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
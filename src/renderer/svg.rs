@@ -0,0 +1,472 @@
+//! SVG: an `<svg>` subtree flattened into paintable shapes.
+//!
+//! [`build_scene`] walks an inline SVG element (or a parsed `.svg`
+//! document) and produces an [`SvgScene`]: every shape reduced to path
+//! commands in scene coordinates, with its fill and stroke resolved. The
+//! compositor rasterizes scenes into their own layers; this module does no
+//! pixel work itself.
+//!
+//! Coverage is the icon subset: `path`, the basic shapes, `g` grouping,
+//! `transform` attributes, and `viewBox` scaling. Elliptical arcs in path
+//! data are flattened to lines; `text` and gradients are not supported.
+
+use super::color::{self, Color};
+use super::dom::{Document, ElementData, NodeId};
+use super::transform::Transform2D;
+
+/// One path drawing command, in user units.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathCommand {
+    MoveTo(f32, f32),
+    LineTo(f32, f32),
+    /// Cubic Bézier: two control points, then the end point.
+    CubicTo(f32, f32, f32, f32, f32, f32),
+    /// Quadratic Bézier: control point, then the end point.
+    QuadTo(f32, f32, f32, f32),
+    Close,
+}
+
+/// A filled and/or stroked path, ready to paint.
+#[derive(Debug, Clone)]
+pub struct SvgShape {
+    pub path: Vec<PathCommand>,
+    pub fill: Option<Color>,
+    pub stroke: Option<Color>,
+    pub stroke_width: f32,
+    /// Accumulated transform from the scene root down to this shape,
+    /// including the viewBox mapping.
+    pub transform: Transform2D,
+}
+
+/// An SVG subtree flattened for painting.
+#[derive(Debug, Clone, Default)]
+pub struct SvgScene {
+    /// Scene size in CSS pixels.
+    pub width: f32,
+    pub height: f32,
+    pub shapes: Vec<SvgShape>,
+}
+
+/// Paint state inherited down the SVG tree.
+#[derive(Clone, Copy)]
+struct PaintStyle {
+    fill: Option<Color>,
+    stroke: Option<Color>,
+    stroke_width: f32,
+}
+
+impl Default for PaintStyle {
+    fn default() -> Self {
+        // SVG's initial values: black fill, no stroke.
+        Self {
+            fill: Some(Color::BLACK),
+            stroke: None,
+            stroke_width: 1.0,
+        }
+    }
+}
+
+/// The intrinsic size of an `<svg>` element: its `width`/`height`
+/// attributes, else the `viewBox` size, else the 300×150 replaced-element
+/// default.
+pub fn intrinsic_size(element: &ElementData) -> (f32, f32) {
+    let attr = |name: &str| -> Option<f32> {
+        element
+            .attr(name)?
+            .trim()
+            .trim_end_matches("px")
+            .parse()
+            .ok()
+    };
+    if let (Some(width), Some(height)) = (attr("width"), attr("height")) {
+        return (width, height);
+    }
+    if let Some((_, _, width, height)) = view_box(element) {
+        return (width, height);
+    }
+    (300.0, 150.0)
+}
+
+/// Flatten the SVG rooted at `root` into shapes, scaled to fill
+/// `width`×`height`.
+pub fn build_scene(document: &Document, root: NodeId, width: f32, height: f32) -> SvgScene {
+    let mut scene = SvgScene {
+        width,
+        height,
+        shapes: Vec::new(),
+    };
+    let Some(element) = document.element(root) else {
+        return scene;
+    };
+    // The viewBox maps user units onto the scene rectangle.
+    let transform = match view_box(element) {
+        Some((min_x, min_y, box_width, box_height)) if box_width > 0.0 && box_height > 0.0 => {
+            Transform2D::scale(width / box_width, height / box_height)
+                .then(&Transform2D::translate(-min_x, -min_y))
+        }
+        _ => Transform2D::IDENTITY,
+    };
+    let style = PaintStyle::default();
+    for &child in document.composed_children(root) {
+        collect_shapes(document, child, transform, style, &mut scene.shapes);
+    }
+    scene
+}
+
+/// The `viewBox` attribute as (min-x, min-y, width, height).
+fn view_box(element: &ElementData) -> Option<(f32, f32, f32, f32)> {
+    let mut parts = element
+        .attr("viewbox")?
+        .split([' ', ','])
+        .filter(|p| !p.is_empty())
+        .map(|p| p.parse::<f32>().ok());
+    Some((parts.next()??, parts.next()??, parts.next()??, parts.next()??))
+}
+
+fn collect_shapes(
+    document: &Document,
+    node: NodeId,
+    parent_transform: Transform2D,
+    parent_style: PaintStyle,
+    out: &mut Vec<SvgShape>,
+) {
+    let Some(element) = document.element(node) else {
+        return;
+    };
+    let transform = match element.attr("transform") {
+        Some(value) => parent_transform.then(&parse_svg_transform(value)),
+        None => parent_transform,
+    };
+    let style = inherit_paint(element, parent_style);
+    let path = match element.tag_name.as_str() {
+        "g" | "svg" => {
+            for &child in document.composed_children(node) {
+                collect_shapes(document, child, transform, style, out);
+            }
+            return;
+        }
+        "path" => element.attr("d").map(parse_path_data).unwrap_or_default(),
+        "rect" => rect_path(element),
+        "circle" => {
+            let cx = number_attr(element, "cx");
+            let cy = number_attr(element, "cy");
+            let r = number_attr(element, "r");
+            ellipse_path(cx, cy, r, r)
+        }
+        "ellipse" => ellipse_path(
+            number_attr(element, "cx"),
+            number_attr(element, "cy"),
+            number_attr(element, "rx"),
+            number_attr(element, "ry"),
+        ),
+        "line" => vec![
+            PathCommand::MoveTo(number_attr(element, "x1"), number_attr(element, "y1")),
+            PathCommand::LineTo(number_attr(element, "x2"), number_attr(element, "y2")),
+        ],
+        "polyline" => points_path(element, false),
+        "polygon" => points_path(element, true),
+        _ => return,
+    };
+    if path.is_empty() || (style.fill.is_none() && style.stroke.is_none()) {
+        return;
+    }
+    out.push(SvgShape {
+        path,
+        fill: style.fill,
+        stroke: style.stroke,
+        stroke_width: style.stroke_width,
+        transform,
+    });
+}
+
+/// Apply an element's paint presentation attributes over the inherited
+/// state. `fill="none"`/`stroke="none"` switch painting off.
+fn inherit_paint(element: &ElementData, mut style: PaintStyle) -> PaintStyle {
+    if let Some(fill) = element.attr("fill") {
+        style.fill = match fill.trim() {
+            "none" => None,
+            value => color::parse_color(value).or(style.fill),
+        };
+    }
+    if let Some(stroke) = element.attr("stroke") {
+        style.stroke = match stroke.trim() {
+            "none" => None,
+            value => color::parse_color(value).or(style.stroke),
+        };
+    }
+    if let Some(width) = element.attr("stroke-width").and_then(|v| v.trim().parse().ok()) {
+        style.stroke_width = width;
+    }
+    style
+}
+
+fn number_attr(element: &ElementData, name: &str) -> f32 {
+    element
+        .attr(name)
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(0.0)
+}
+
+/// A `<rect>` as a path; `rx`/`ry` corner rounding is ignored.
+fn rect_path(element: &ElementData) -> Vec<PathCommand> {
+    let x = number_attr(element, "x");
+    let y = number_attr(element, "y");
+    let width = number_attr(element, "width");
+    let height = number_attr(element, "height");
+    if width <= 0.0 || height <= 0.0 {
+        return Vec::new();
+    }
+    vec![
+        PathCommand::MoveTo(x, y),
+        PathCommand::LineTo(x + width, y),
+        PathCommand::LineTo(x + width, y + height),
+        PathCommand::LineTo(x, y + height),
+        PathCommand::Close,
+    ]
+}
+
+/// Cubic approximation constant for a quarter circle.
+const KAPPA: f32 = 0.552_284_8;
+
+/// An ellipse as four cubic Béziers.
+fn ellipse_path(cx: f32, cy: f32, rx: f32, ry: f32) -> Vec<PathCommand> {
+    if rx <= 0.0 || ry <= 0.0 {
+        return Vec::new();
+    }
+    let (kx, ky) = (rx * KAPPA, ry * KAPPA);
+    vec![
+        PathCommand::MoveTo(cx + rx, cy),
+        PathCommand::CubicTo(cx + rx, cy + ky, cx + kx, cy + ry, cx, cy + ry),
+        PathCommand::CubicTo(cx - kx, cy + ry, cx - rx, cy + ky, cx - rx, cy),
+        PathCommand::CubicTo(cx - rx, cy - ky, cx - kx, cy - ry, cx, cy - ry),
+        PathCommand::CubicTo(cx + kx, cy - ry, cx + rx, cy - ky, cx + rx, cy),
+        PathCommand::Close,
+    ]
+}
+
+/// A `<polyline>`/`<polygon>` `points` attribute as a path.
+fn points_path(element: &ElementData, close: bool) -> Vec<PathCommand> {
+    let numbers: Vec<f32> = element
+        .attr("points")
+        .unwrap_or_default()
+        .split([' ', ',', '\n', '\t'])
+        .filter(|p| !p.is_empty())
+        .filter_map(|p| p.parse().ok())
+        .collect();
+    let mut path = Vec::new();
+    for pair in numbers.chunks_exact(2) {
+        match path.is_empty() {
+            true => path.push(PathCommand::MoveTo(pair[0], pair[1])),
+            false => path.push(PathCommand::LineTo(pair[0], pair[1])),
+        }
+    }
+    if close && !path.is_empty() {
+        path.push(PathCommand::Close);
+    }
+    path
+}
+
+/// Parse SVG path data (`d` attribute). Handles the absolute and relative
+/// forms of M, L, H, V, C, S, Q, T and Z; elliptical arcs (A) are
+/// flattened to a line to their end point.
+pub fn parse_path_data(d: &str) -> Vec<PathCommand> {
+    let mut commands = Vec::new();
+    let mut numbers: Vec<f32> = Vec::new();
+    let mut chars = d.chars().peekable();
+    // Current point, subpath start, and the previous control point for
+    // the S/T reflected-control shorthands.
+    let (mut x, mut y) = (0.0_f32, 0.0_f32);
+    let (mut start_x, mut start_y) = (0.0_f32, 0.0_f32);
+    let mut last_control: Option<(f32, f32)> = None;
+    let mut command = ' ';
+
+    loop {
+        // Scan one token: a command letter or a number.
+        match chars.peek().copied() {
+            None => break,
+            Some(c) if c.is_ascii_alphabetic() => {
+                chars.next();
+                command = c;
+                numbers.clear();
+                if c == 'Z' || c == 'z' {
+                    commands.push(PathCommand::Close);
+                    x = start_x;
+                    y = start_y;
+                    last_control = None;
+                }
+                continue;
+            }
+            Some(c) if c.is_whitespace() || c == ',' => {
+                chars.next();
+                continue;
+            }
+            Some(_) => {
+                let mut token = String::new();
+                while let Some(&c) = chars.peek() {
+                    let part_of_number = c.is_ascii_digit()
+                        || c == '.'
+                        || c == 'e'
+                        || c == 'E'
+                        || ((c == '-' || c == '+')
+                            && (token.is_empty() || token.ends_with(['e', 'E'])));
+                    if !part_of_number {
+                        break;
+                    }
+                    token.push(c);
+                    chars.next();
+                }
+                match token.parse::<f32>() {
+                    Ok(number) => numbers.push(number),
+                    Err(_) => break,
+                }
+            }
+        }
+
+        let relative = command.is_ascii_lowercase();
+        let (dx, dy) = if relative { (x, y) } else { (0.0, 0.0) };
+        match command.to_ascii_uppercase() {
+            'M' if numbers.len() == 2 => {
+                x = dx + numbers[0];
+                y = dy + numbers[1];
+                start_x = x;
+                start_y = y;
+                commands.push(PathCommand::MoveTo(x, y));
+                numbers.clear();
+                // Extra coordinate pairs after a moveto are implicit
+                // linetos, per spec.
+                command = if relative { 'l' } else { 'L' };
+                last_control = None;
+            }
+            'L' if numbers.len() == 2 => {
+                x = dx + numbers[0];
+                y = dy + numbers[1];
+                commands.push(PathCommand::LineTo(x, y));
+                numbers.clear();
+                last_control = None;
+            }
+            'H' if numbers.len() == 1 => {
+                x = if relative { x + numbers[0] } else { numbers[0] };
+                commands.push(PathCommand::LineTo(x, y));
+                numbers.clear();
+                last_control = None;
+            }
+            'V' if numbers.len() == 1 => {
+                y = if relative { y + numbers[0] } else { numbers[0] };
+                commands.push(PathCommand::LineTo(x, y));
+                numbers.clear();
+                last_control = None;
+            }
+            'C' if numbers.len() == 6 => {
+                let control = (dx + numbers[2], dy + numbers[3]);
+                commands.push(PathCommand::CubicTo(
+                    dx + numbers[0],
+                    dy + numbers[1],
+                    control.0,
+                    control.1,
+                    dx + numbers[4],
+                    dy + numbers[5],
+                ));
+                x = dx + numbers[4];
+                y = dy + numbers[5];
+                last_control = Some(control);
+                numbers.clear();
+            }
+            'S' if numbers.len() == 4 => {
+                let (cx1, cy1) = reflect(last_control, x, y);
+                let control = (dx + numbers[0], dy + numbers[1]);
+                commands.push(PathCommand::CubicTo(
+                    cx1,
+                    cy1,
+                    control.0,
+                    control.1,
+                    dx + numbers[2],
+                    dy + numbers[3],
+                ));
+                x = dx + numbers[2];
+                y = dy + numbers[3];
+                last_control = Some(control);
+                numbers.clear();
+            }
+            'Q' if numbers.len() == 4 => {
+                let control = (dx + numbers[0], dy + numbers[1]);
+                commands.push(PathCommand::QuadTo(
+                    control.0,
+                    control.1,
+                    dx + numbers[2],
+                    dy + numbers[3],
+                ));
+                x = dx + numbers[2];
+                y = dy + numbers[3];
+                last_control = Some(control);
+                numbers.clear();
+            }
+            'T' if numbers.len() == 2 => {
+                let control = reflect(last_control, x, y);
+                x = dx + numbers[0];
+                y = dy + numbers[1];
+                commands.push(PathCommand::QuadTo(control.0, control.1, x, y));
+                last_control = Some(control);
+                numbers.clear();
+            }
+            'A' if numbers.len() == 7 => {
+                // Arc flattening is not implemented; keep the contour
+                // connected with a line to the arc's end point.
+                x = dx + numbers[5];
+                y = dy + numbers[6];
+                commands.push(PathCommand::LineTo(x, y));
+                numbers.clear();
+                last_control = None;
+            }
+            _ => {}
+        }
+    }
+    commands
+}
+
+/// The reflection of `control` about the current point, for the S and T
+/// shorthands; the current point itself when there is nothing to reflect.
+fn reflect(control: Option<(f32, f32)>, x: f32, y: f32) -> (f32, f32) {
+    match control {
+        Some((cx, cy)) => (2.0 * x - cx, 2.0 * y - cy),
+        None => (x, y),
+    }
+}
+
+/// Parse an SVG `transform` attribute: a list of `translate`, `scale`,
+/// `rotate` (with optional center), `skewX`, `skewY` and `matrix`
+/// functions, applied left to right. Unlike CSS transforms the arguments
+/// are unitless user units and degrees.
+pub fn parse_svg_transform(value: &str) -> Transform2D {
+    let mut transform = Transform2D::IDENTITY;
+    let mut rest = value.trim();
+    while let Some(open) = rest.find('(') {
+        let name = rest[..open].trim().to_ascii_lowercase();
+        let Some(close) = rest[open..].find(')') else {
+            break;
+        };
+        let args: Vec<f32> = rest[open + 1..open + close]
+            .split([' ', ','])
+            .filter(|p| !p.is_empty())
+            .filter_map(|p| p.parse().ok())
+            .collect();
+        let step = match (name.as_str(), args.as_slice()) {
+            ("translate", [tx]) => Transform2D::translate(*tx, 0.0),
+            ("translate", [tx, ty]) => Transform2D::translate(*tx, *ty),
+            ("scale", [s]) => Transform2D::scale(*s, *s),
+            ("scale", [sx, sy]) => Transform2D::scale(*sx, *sy),
+            ("rotate", [degrees]) => Transform2D::rotate(degrees.to_radians()),
+            ("rotate", [degrees, cx, cy]) => Transform2D::translate(*cx, *cy)
+                .then(&Transform2D::rotate(degrees.to_radians()))
+                .then(&Transform2D::translate(-cx, -cy)),
+            ("skewx", [degrees]) => Transform2D::skew(degrees.to_radians(), 0.0),
+            ("skewy", [degrees]) => Transform2D::skew(0.0, degrees.to_radians()),
+            ("matrix", [a, b, c, d, e, f]) => Transform2D {
+                m: [*a, *b, *c, *d, *e, *f],
+            },
+            _ => Transform2D::IDENTITY,
+        };
+        transform = transform.then(&step);
+        rest = &rest[open + close + 1..];
+    }
+    transform
+}
@@ -0,0 +1,231 @@
+//! CSS transforms: `transform` / `transform-origin` parsing and the
+//! affine matrices painting and hit testing share.
+//!
+//! Transforms never affect layout — a transformed box flows at its
+//! untransformed position and the compositor applies the matrix as a
+//! layer transform at paint time. Hit testing maps pointer coordinates
+//! through the inverse matrix instead, so links and controls stay
+//! clickable where they are drawn. 3D functions are flattened onto the
+//! plane (`translate3d`/`scale3d` keep their x/y components).
+
+use super::values::{Length, LengthContext};
+
+/// A 2D affine matrix in CSS `matrix(a, b, c, d, e, f)` order:
+/// `x' = a·x + c·y + e`, `y' = b·x + d·y + f`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform2D {
+    pub m: [f32; 6],
+}
+
+impl Transform2D {
+    pub const IDENTITY: Self = Self {
+        m: [1.0, 0.0, 0.0, 1.0, 0.0, 0.0],
+    };
+
+    pub fn translate(x: f32, y: f32) -> Self {
+        Self {
+            m: [1.0, 0.0, 0.0, 1.0, x, y],
+        }
+    }
+
+    pub fn scale(x: f32, y: f32) -> Self {
+        Self {
+            m: [x, 0.0, 0.0, y, 0.0, 0.0],
+        }
+    }
+
+    /// Clockwise rotation by `radians`.
+    pub fn rotate(radians: f32) -> Self {
+        let (sin, cos) = radians.sin_cos();
+        Self {
+            m: [cos, sin, -sin, cos, 0.0, 0.0],
+        }
+    }
+
+    pub fn skew(x_radians: f32, y_radians: f32) -> Self {
+        Self {
+            m: [1.0, y_radians.tan(), x_radians.tan(), 1.0, 0.0, 0.0],
+        }
+    }
+
+    /// `self` applied after `rhs` (matrix product `self × rhs`).
+    pub fn then(&self, rhs: &Self) -> Self {
+        let a = &self.m;
+        let b = &rhs.m;
+        Self {
+            m: [
+                a[0] * b[0] + a[2] * b[1],
+                a[1] * b[0] + a[3] * b[1],
+                a[0] * b[2] + a[2] * b[3],
+                a[1] * b[2] + a[3] * b[3],
+                a[0] * b[4] + a[2] * b[5] + a[4],
+                a[1] * b[4] + a[3] * b[5] + a[5],
+            ],
+        }
+    }
+
+    /// Map a point through the matrix.
+    pub fn apply(&self, x: f32, y: f32) -> (f32, f32) {
+        let m = &self.m;
+        (m[0] * x + m[2] * y + m[4], m[1] * x + m[3] * y + m[5])
+    }
+
+    /// The inverse matrix; `None` when degenerate (e.g. `scale(0)`),
+    /// which makes the layer unhittable.
+    pub fn inverse(&self) -> Option<Self> {
+        let m = &self.m;
+        let det = m[0] * m[3] - m[1] * m[2];
+        if det.abs() < f32::EPSILON {
+            return None;
+        }
+        let inv = 1.0 / det;
+        Some(Self {
+            m: [
+                m[3] * inv,
+                -m[1] * inv,
+                -m[2] * inv,
+                m[0] * inv,
+                (m[2] * m[5] - m[3] * m[4]) * inv,
+                (m[1] * m[4] - m[0] * m[5]) * inv,
+            ],
+        })
+    }
+
+    pub fn is_identity(&self) -> bool {
+        *self == Self::IDENTITY
+    }
+}
+
+/// Parse a `transform` list into one matrix, functions applied left to
+/// right. `None` for `none` or unsupported syntax. Percentages in
+/// `translate` resolve against the border box (`width` × `height`).
+pub fn parse_transform_list(
+    value: &str,
+    width: f32,
+    height: f32,
+    ctx: &LengthContext,
+) -> Option<Transform2D> {
+    let value = value.trim();
+    if value == "none" {
+        return None;
+    }
+    let mut matrix = Transform2D::IDENTITY;
+    let mut rest = value;
+    while !rest.trim().is_empty() {
+        let open = rest.find('(')?;
+        let close = rest.find(')')?;
+        let name = rest[..open].trim().to_ascii_lowercase();
+        let args: Vec<&str> = rest[open + 1..close].split(',').map(str::trim).collect();
+        matrix = matrix.then(&parse_function(&name, &args, width, height, ctx)?);
+        rest = &rest[close + 1..];
+    }
+    if matrix.is_identity() {
+        return None;
+    }
+    Some(matrix)
+}
+
+fn parse_function(
+    name: &str,
+    args: &[&str],
+    width: f32,
+    height: f32,
+    ctx: &LengthContext,
+) -> Option<Transform2D> {
+    let length = |arg: &str, basis: f32| -> Option<f32> {
+        Some(Length::parse(arg)?.resolve(&LengthContext {
+            containing_block: basis,
+            ..*ctx
+        }))
+    };
+    let number = |arg: &str| arg.parse::<f32>().ok();
+    match name {
+        "translate" | "translate3d" => {
+            let x = length(args.first()?, width)?;
+            let y = args.get(1).map_or(Some(0.0), |a| length(a, height))?;
+            Some(Transform2D::translate(x, y))
+        }
+        "translatex" => Some(Transform2D::translate(length(args.first()?, width)?, 0.0)),
+        "translatey" => Some(Transform2D::translate(0.0, length(args.first()?, height)?)),
+        "scale" | "scale3d" => {
+            let x = number(args.first()?)?;
+            let y = args.get(1).map_or(Some(x), |a| number(a))?;
+            Some(Transform2D::scale(x, y))
+        }
+        "scalex" => Some(Transform2D::scale(number(args.first()?)?, 1.0)),
+        "scaley" => Some(Transform2D::scale(1.0, number(args.first()?)?)),
+        // rotate() and rotateZ() are the same rotation in the plane.
+        "rotate" | "rotatez" => Some(Transform2D::rotate(parse_angle(args.first()?)?)),
+        "skew" => {
+            let x = parse_angle(args.first()?)?;
+            let y = args.get(1).map_or(Some(0.0), |a| parse_angle(a))?;
+            Some(Transform2D::skew(x, y))
+        }
+        "skewx" => Some(Transform2D::skew(parse_angle(args.first()?)?, 0.0)),
+        "skewy" => Some(Transform2D::skew(0.0, parse_angle(args.first()?)?)),
+        "matrix" => {
+            if args.len() != 6 {
+                return None;
+            }
+            let mut m = [0.0; 6];
+            for (slot, arg) in m.iter_mut().zip(args) {
+                *slot = number(arg)?;
+            }
+            Some(Transform2D { m })
+        }
+        _ => None,
+    }
+}
+
+/// Parse an angle value into radians.
+fn parse_angle(value: &str) -> Option<f32> {
+    let value = value.trim();
+    if value == "0" {
+        return Some(0.0);
+    }
+    if let Some(deg) = value.strip_suffix("deg") {
+        return Some(deg.trim().parse::<f32>().ok()?.to_radians());
+    }
+    if let Some(grad) = value.strip_suffix("grad") {
+        return Some(grad.trim().parse::<f32>().ok()? * std::f32::consts::PI / 200.0);
+    }
+    if let Some(rad) = value.strip_suffix("rad") {
+        return Some(rad.trim().parse().ok()?);
+    }
+    if let Some(turn) = value.strip_suffix("turn") {
+        return Some(turn.trim().parse::<f32>().ok()? * std::f32::consts::TAU);
+    }
+    None
+}
+
+/// Resolve `transform-origin` against the border box. Accepts keywords
+/// and lengths/percentages; the default is the box center.
+pub fn parse_transform_origin(
+    value: Option<&str>,
+    width: f32,
+    height: f32,
+    ctx: &LengthContext,
+) -> (f32, f32) {
+    let Some(value) = value else {
+        return (width / 2.0, height / 2.0);
+    };
+    let component = |part: Option<&str>, basis: f32| -> f32 {
+        match part {
+            Some("left") | Some("top") => 0.0,
+            Some("center") | None => basis / 2.0,
+            Some("right") | Some("bottom") => basis,
+            Some(length) => Length::parse(length)
+                .map(|l| {
+                    l.resolve(&LengthContext {
+                        containing_block: basis,
+                        ..*ctx
+                    })
+                })
+                .unwrap_or(basis / 2.0),
+        }
+    };
+    let mut parts = value.split_whitespace();
+    let x = component(parts.next(), width);
+    let y = component(parts.next(), height);
+    (x, y)
+}
@@ -0,0 +1,306 @@
+//! CSS values: lengths and `calc()` expressions.
+//!
+//! Lengths keep their unit until layout; [`LengthContext`] carries the
+//! font sizes, containing block, and viewport needed to resolve them to
+//! pixels. `calc()` parses to an expression tree once and is evaluated
+//! against the same context, so `calc(100% - 2 * 20px)` comes out right
+//! for whatever containing block the element ends up in.
+
+/// A length with its unit preserved.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Length {
+    Px(f32),
+    Em(f32),
+    Rem(f32),
+    /// Percent of the containing block dimension.
+    Percent(f32),
+    Vw(f32),
+    Vh(f32),
+    Vmin(f32),
+    Vmax(f32),
+}
+
+/// Everything needed to turn a [`Length`] into pixels.
+#[derive(Debug, Clone, Copy)]
+pub struct LengthContext {
+    /// The element's own font size, for `em`.
+    pub font_size: f32,
+    /// The root element's font size, for `rem`.
+    pub root_font_size: f32,
+    /// The containing block dimension percentages resolve against.
+    pub containing_block: f32,
+    pub viewport_width: f32,
+    pub viewport_height: f32,
+}
+
+impl Default for LengthContext {
+    fn default() -> Self {
+        Self {
+            font_size: 16.0,
+            root_font_size: 16.0,
+            containing_block: 0.0,
+            viewport_width: 1280.0,
+            viewport_height: 720.0,
+        }
+    }
+}
+
+impl Length {
+    pub fn resolve(&self, ctx: &LengthContext) -> f32 {
+        match *self {
+            Length::Px(v) => v,
+            Length::Em(v) => v * ctx.font_size,
+            Length::Rem(v) => v * ctx.root_font_size,
+            Length::Percent(v) => v / 100.0 * ctx.containing_block,
+            Length::Vw(v) => v / 100.0 * ctx.viewport_width,
+            Length::Vh(v) => v / 100.0 * ctx.viewport_height,
+            Length::Vmin(v) => v / 100.0 * ctx.viewport_width.min(ctx.viewport_height),
+            Length::Vmax(v) => v / 100.0 * ctx.viewport_width.max(ctx.viewport_height),
+        }
+    }
+
+    /// Parse a single length token, e.g. `12px`, `1.5em`, `50%`. Bare `0`
+    /// is allowed as zero pixels.
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        if input == "0" {
+            return Some(Length::Px(0.0));
+        }
+        let split = input
+            .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-' || c == '+'))
+            .unwrap_or(input.len());
+        let number: f32 = input[..split].parse().ok()?;
+        match &input[split..] {
+            "px" => Some(Length::Px(number)),
+            "em" => Some(Length::Em(number)),
+            "rem" => Some(Length::Rem(number)),
+            "%" => Some(Length::Percent(number)),
+            "vw" => Some(Length::Vw(number)),
+            "vh" => Some(Length::Vh(number)),
+            "vmin" => Some(Length::Vmin(number)),
+            "vmax" => Some(Length::Vmax(number)),
+            _ => None,
+        }
+    }
+}
+
+/// A sizing value as written: a keyword, a plain length, or a `calc()`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CssSize {
+    Auto,
+    Length(Length),
+    Calc(CalcNode),
+}
+
+impl CssSize {
+    /// Resolved size in pixels; `None` for `auto` and invalid `calc()`.
+    pub fn resolve(&self, ctx: &LengthContext) -> Option<f32> {
+        match self {
+            CssSize::Auto => None,
+            CssSize::Length(length) => Some(length.resolve(ctx)),
+            CssSize::Calc(node) => match node.evaluate(ctx)? {
+                (value, true) => Some(value),
+                // A bare number is not a length.
+                (_, false) => None,
+            },
+        }
+    }
+}
+
+/// Parse a CSS sizing value: `auto`, a length, or `calc(expression)`.
+pub fn parse_css_size(value: &str) -> Option<CssSize> {
+    let value = value.trim();
+    if value.eq_ignore_ascii_case("auto") {
+        return Some(CssSize::Auto);
+    }
+    if let Some(inner) = value
+        .strip_prefix("calc(")
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        return Some(CssSize::Calc(CalcNode::parse(inner)?));
+    }
+    Length::parse(value).map(CssSize::Length)
+}
+
+/// One node of a parsed `calc()` expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CalcNode {
+    Length(Length),
+    Number(f32),
+    Add(Box<CalcNode>, Box<CalcNode>),
+    Subtract(Box<CalcNode>, Box<CalcNode>),
+    Multiply(Box<CalcNode>, Box<CalcNode>),
+    Divide(Box<CalcNode>, Box<CalcNode>),
+}
+
+impl CalcNode {
+    /// Parse the inside of a `calc()`.
+    pub fn parse(input: &str) -> Option<Self> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let node = parser.sum()?;
+        if parser.pos != parser.tokens.len() {
+            return None;
+        }
+        Some(node)
+    }
+
+    /// Evaluate to `(pixels, is_length)`. Unit algebra follows the spec:
+    /// lengths add to lengths, a length times a number is a length, and a
+    /// length times a length is invalid.
+    fn evaluate(&self, ctx: &LengthContext) -> Option<(f32, bool)> {
+        match self {
+            CalcNode::Length(length) => Some((length.resolve(ctx), true)),
+            CalcNode::Number(n) => Some((*n, false)),
+            CalcNode::Add(a, b) | CalcNode::Subtract(a, b) => {
+                let (left, left_len) = a.evaluate(ctx)?;
+                let (right, right_len) = b.evaluate(ctx)?;
+                if left_len != right_len {
+                    return None;
+                }
+                let value = if matches!(self, CalcNode::Add(..)) {
+                    left + right
+                } else {
+                    left - right
+                };
+                Some((value, left_len))
+            }
+            CalcNode::Multiply(a, b) => {
+                let (left, left_len) = a.evaluate(ctx)?;
+                let (right, right_len) = b.evaluate(ctx)?;
+                if left_len && right_len {
+                    return None;
+                }
+                Some((left * right, left_len || right_len))
+            }
+            CalcNode::Divide(a, b) => {
+                let (left, left_len) = a.evaluate(ctx)?;
+                let (right, right_len) = b.evaluate(ctx)?;
+                if right_len || right == 0.0 {
+                    return None;
+                }
+                Some((left / right, left_len))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Value(CalcNode),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Open,
+    Close,
+}
+
+fn tokenize(input: &str) -> Option<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut rest = input.trim();
+    while !rest.is_empty() {
+        let ch = rest.chars().next()?;
+        match ch {
+            '+' => {
+                tokens.push(Token::Plus);
+                rest = rest[1..].trim_start();
+            }
+            '-' if !rest[1..].starts_with(|c: char| c.is_ascii_digit() || c == '.')
+                || matches!(tokens.last(), Some(Token::Value(_)) | Some(Token::Close)) =>
+            {
+                tokens.push(Token::Minus);
+                rest = rest[1..].trim_start();
+            }
+            '*' => {
+                tokens.push(Token::Star);
+                rest = rest[1..].trim_start();
+            }
+            '/' => {
+                tokens.push(Token::Slash);
+                rest = rest[1..].trim_start();
+            }
+            '(' => {
+                tokens.push(Token::Open);
+                rest = rest[1..].trim_start();
+            }
+            ')' => {
+                tokens.push(Token::Close);
+                rest = rest[1..].trim_start();
+            }
+            _ => {
+                let end = rest
+                    .find(|c: char| c.is_whitespace() || "+*/()".contains(c))
+                    .unwrap_or(rest.len());
+                let text = &rest[..end];
+                let node = match Length::parse(text) {
+                    Some(length) => CalcNode::Length(length),
+                    None => CalcNode::Number(text.parse().ok()?),
+                };
+                tokens.push(Token::Value(node));
+                rest = rest[end..].trim_start();
+            }
+        }
+    }
+    Some(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn sum(&mut self) -> Option<CalcNode> {
+        let mut node = self.product()?;
+        loop {
+            match self.tokens.get(self.pos) {
+                Some(Token::Plus) => {
+                    self.pos += 1;
+                    node = CalcNode::Add(Box::new(node), Box::new(self.product()?));
+                }
+                Some(Token::Minus) => {
+                    self.pos += 1;
+                    node = CalcNode::Subtract(Box::new(node), Box::new(self.product()?));
+                }
+                _ => return Some(node),
+            }
+        }
+    }
+
+    fn product(&mut self) -> Option<CalcNode> {
+        let mut node = self.atom()?;
+        loop {
+            match self.tokens.get(self.pos) {
+                Some(Token::Star) => {
+                    self.pos += 1;
+                    node = CalcNode::Multiply(Box::new(node), Box::new(self.atom()?));
+                }
+                Some(Token::Slash) => {
+                    self.pos += 1;
+                    node = CalcNode::Divide(Box::new(node), Box::new(self.atom()?));
+                }
+                _ => return Some(node),
+            }
+        }
+    }
+
+    fn atom(&mut self) -> Option<CalcNode> {
+        match self.tokens.get(self.pos).cloned()? {
+            Token::Value(node) => {
+                self.pos += 1;
+                Some(node)
+            }
+            Token::Open => {
+                self.pos += 1;
+                let node = self.sum()?;
+                if self.tokens.get(self.pos) != Some(&Token::Close) {
+                    return None;
+                }
+                self.pos += 1;
+                Some(node)
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,154 @@
+//! HTTP Strict Transport Security (RFC 6797).
+//!
+//! Hosts that have sent `Strict-Transport-Security` — plus a bundled
+//! preload list — get their plain-http navigations silently upgraded to
+//! https before any network traffic happens. Dynamic entries are persisted
+//! to the profile directory.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::network::Headers;
+
+/// A short bundled preload seed. The full Chromium preload list is far too
+/// large to embed here; these cover the highest-traffic origins and the
+/// list can be replaced at build time.
+const PRELOADED: &[(&str, bool)] = &[
+    // (host, include_subdomains)
+    ("google.com", true),
+    ("youtube.com", true),
+    ("facebook.com", true),
+    ("wikipedia.org", true),
+    ("github.com", true),
+    ("mozilla.org", true),
+    ("twitter.com", true),
+    ("x.com", true),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct HstsEntry {
+    /// Unix seconds after which the entry lapses.
+    expires_at: u64,
+    include_subdomains: bool,
+}
+
+/// Dynamic + preloaded HSTS state.
+pub struct HstsStore {
+    path: PathBuf,
+    dynamic: Mutex<HashMap<String, HstsEntry>>,
+}
+
+impl HstsStore {
+    /// Load persisted entries from `path`, starting empty if absent or
+    /// unreadable.
+    pub fn load(path: PathBuf) -> Self {
+        let dynamic = std::fs::read(&path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            dynamic: Mutex::new(dynamic),
+        }
+    }
+
+    /// Record the `Strict-Transport-Security` header of a response served
+    /// over https from `host`. `max-age=0` removes the host.
+    pub fn observe(&self, host: &str, headers: &Headers) {
+        let Some(value) = headers.get("strict-transport-security") else {
+            return;
+        };
+        let mut max_age: Option<u64> = None;
+        let mut include_subdomains = false;
+        for directive in value.split(';') {
+            let directive = directive.trim();
+            if let Some(age) = directive
+                .strip_prefix("max-age=")
+                .or_else(|| directive.strip_prefix("MAX-AGE="))
+            {
+                max_age = age.trim_matches('"').parse().ok();
+            } else if directive.eq_ignore_ascii_case("includeSubDomains") {
+                include_subdomains = true;
+            }
+        }
+        let Some(max_age) = max_age else { return };
+
+        let mut dynamic = self.dynamic.lock().unwrap();
+        if max_age == 0 {
+            dynamic.remove(host);
+        } else {
+            dynamic.insert(
+                host.to_ascii_lowercase(),
+                HstsEntry {
+                    expires_at: now_secs().saturating_add(max_age),
+                    include_subdomains,
+                },
+            );
+        }
+        self.persist(&dynamic);
+    }
+
+    /// Whether `host` must only be reached over https.
+    pub fn is_secure_host(&self, host: &str) -> bool {
+        let host = host.to_ascii_lowercase();
+        if Self::matches_list(&host, PRELOADED.iter().copied()) {
+            return true;
+        }
+        let mut dynamic = self.dynamic.lock().unwrap();
+        let now = now_secs();
+        dynamic.retain(|_, entry| entry.expires_at > now);
+        Self::matches_list(
+            &host,
+            dynamic
+                .iter()
+                .map(|(h, e)| (h.as_str(), e.include_subdomains)),
+        )
+    }
+
+    /// Upgrade an `http://` URL to `https://` when HSTS applies.
+    pub fn upgrade(&self, url: &str) -> String {
+        let Some(rest) = url.strip_prefix("http://") else {
+            return url.to_owned();
+        };
+        let authority = rest.split(['/', '?', '#']).next().unwrap_or(rest);
+        let host = authority.rsplit_once(':').map_or(authority, |(h, _)| h);
+        if self.is_secure_host(host) {
+            format!("https://{rest}")
+        } else {
+            url.to_owned()
+        }
+    }
+
+    fn matches_list<'a>(host: &str, entries: impl Iterator<Item = (&'a str, bool)>) -> bool {
+        for (entry_host, include_subdomains) in entries {
+            if host == entry_host {
+                return true;
+            }
+            if include_subdomains
+                && host.len() > entry_host.len()
+                && host.ends_with(entry_host)
+                && host.as_bytes()[host.len() - entry_host.len() - 1] == b'.'
+            {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn persist(&self, dynamic: &HashMap<String, HstsEntry>) {
+        if let Ok(json) = serde_json::to_vec_pretty(dynamic) {
+            let _ = std::fs::write(&self.path, json);
+        }
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
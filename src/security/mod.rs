@@ -0,0 +1,83 @@
+//! Security policy for the browser: transport security, certificate
+//! handling, and related state.
+//!
+//! [`SecurityManager`] is the engine-wide owner of security state; the
+//! network stack consults it on every request.
+
+pub mod hsts;
+pub mod pinning;
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+pub use hsts::HstsStore;
+pub use pinning::PinStore;
+
+/// Engine-wide security policy and state.
+pub struct SecurityManager {
+    hsts: HstsStore,
+    pins: Arc<PinStore>,
+    /// Hosts the user has clicked through a certificate interstitial for.
+    /// Session-scoped on purpose: overrides do not survive a restart.
+    tls_overrides: Mutex<HashSet<String>>,
+    /// Whether network state (cache, connection pools) is keyed by
+    /// top-level site. On by default; off is for debugging only.
+    network_partitioning: std::sync::atomic::AtomicBool,
+}
+
+impl SecurityManager {
+    pub fn new(profile_dir: PathBuf) -> Self {
+        Self {
+            hsts: HstsStore::load(profile_dir.join("hsts.json")),
+            pins: Arc::new(PinStore::new()),
+            tls_overrides: Mutex::new(HashSet::new()),
+            network_partitioning: std::sync::atomic::AtomicBool::new(true),
+        }
+    }
+
+    /// Toggle network state partitioning. Disabling it makes cross-site
+    /// cache and connection reuse observable again — debugging only.
+    pub fn set_network_partitioning(&self, enabled: bool) {
+        self.network_partitioning
+            .store(enabled, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    pub fn network_partitioning(&self) -> bool {
+        self.network_partitioning
+            .load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Pin `host` to a SubjectPublicKeyInfo SHA-256. Connections to a
+    /// pinned host fail unless the presented chain contains a pinned key;
+    /// see [`pinning::PinningVerifier`].
+    pub fn pin_certificate(&self, host: &str, spki_hash: &str) {
+        self.pins.add(host, spki_hash);
+    }
+
+    /// The pin store, shared with the TLS layer's verifier.
+    pub fn pins(&self) -> Arc<PinStore> {
+        Arc::clone(&self.pins)
+    }
+
+    pub fn hsts(&self) -> &HstsStore {
+        &self.hsts
+    }
+
+    /// Record a per-site certificate override chosen on an interstitial.
+    pub fn add_tls_override(&self, host: &str) {
+        self.tls_overrides
+            .lock()
+            .unwrap()
+            .insert(host.to_ascii_lowercase());
+    }
+
+    /// Whether certificate errors for `host` have been overridden this
+    /// session.
+    pub fn has_tls_override(&self, host: &str) -> bool {
+        self.tls_overrides
+            .lock()
+            .unwrap()
+            .contains(&host.to_ascii_lowercase())
+    }
+}
@@ -0,0 +1,136 @@
+//! Certificate pinning for enterprise deployments.
+//!
+//! Pins are SHA-256 hashes of the server's SubjectPublicKeyInfo, as in HPKP
+//! and Chromium's static pin list. Enforcement happens inside the rustls
+//! verifier: the chain must both pass normal WebPKI validation *and*
+//! contain a pinned key somewhere in the chain. Violations are reported to
+//! the devtools network inspector.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::client::WebPkiServerVerifier;
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use sha2::{Digest, Sha256};
+
+use crate::devtools::network_inspector;
+
+/// Hex-encoded SHA-256 of a SubjectPublicKeyInfo.
+pub type SpkiHash = String;
+
+/// Pins registered per host.
+#[derive(Default)]
+pub struct PinStore {
+    pins: Mutex<HashMap<String, Vec<SpkiHash>>>,
+}
+
+impl PinStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a pin for `host`. Multiple pins per host are allowed (current +
+    /// backup key, as deployments rotate).
+    pub fn add(&self, host: &str, spki_hash: &str) {
+        self.pins
+            .lock()
+            .unwrap()
+            .entry(host.to_ascii_lowercase())
+            .or_default()
+            .push(spki_hash.to_ascii_lowercase());
+    }
+
+    pub fn pins_for(&self, host: &str) -> Option<Vec<SpkiHash>> {
+        self.pins
+            .lock()
+            .unwrap()
+            .get(&host.to_ascii_lowercase())
+            .cloned()
+    }
+
+    /// Check a presented chain against the pins for `host`. `Ok(())` when
+    /// the host is unpinned or any chain element matches a pin.
+    pub fn check_chain(&self, host: &str, chain: &[CertificateDer<'_>]) -> Result<(), SpkiHash> {
+        let Some(pins) = self.pins_for(host) else {
+            return Ok(());
+        };
+        let mut presented = Vec::new();
+        for cert in chain {
+            if let Some(hash) = spki_sha256(cert) {
+                if pins.contains(&hash) {
+                    return Ok(());
+                }
+                presented.push(hash);
+            }
+        }
+        Err(presented.join(","))
+    }
+}
+
+/// Extract and hash the SPKI from a DER certificate.
+fn spki_sha256(cert: &CertificateDer<'_>) -> Option<SpkiHash> {
+    let (_, parsed) = x509_parser::parse_x509_certificate(cert.as_ref()).ok()?;
+    let spki = parsed.public_key().raw;
+    Some(hex::encode(Sha256::digest(spki)))
+}
+
+/// rustls verifier layering pin enforcement on top of WebPKI validation.
+#[derive(Debug)]
+pub struct PinningVerifier {
+    inner: Arc<WebPkiServerVerifier>,
+    pins: Arc<PinStore>,
+}
+
+impl PinningVerifier {
+    pub fn new(inner: Arc<WebPkiServerVerifier>, pins: Arc<PinStore>) -> Self {
+        Self { inner, pins }
+    }
+}
+
+impl ServerCertVerifier for PinningVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        self.inner
+            .verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now)?;
+
+        let host = server_name.to_str();
+        let mut chain = vec![end_entity.clone()];
+        chain.extend(intermediates.iter().cloned());
+        if let Err(presented) = self.pins.check_chain(&host, &chain) {
+            network_inspector::report_pin_violation(&host, &presented);
+            return Err(rustls::Error::General(format!(
+                "certificate pin validation failed for {host}"
+            )));
+        }
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &rustls::DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &rustls::DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<rustls::SignatureScheme> {
+        self.inner.supported_verify_schemes()
+    }
+}
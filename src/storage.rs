@@ -0,0 +1,209 @@
+//! DOM storage: the per-origin key/value stores behind `localStorage`.
+//!
+//! [`LocalStorage`] keeps one [`StorageArea`] per origin, persisted to
+//! the profile directory the way the HSTS store is — one JSON file per
+//! origin, rewritten on mutation. Session storage has no engine-side
+//! state: it is per-tab memory, owned by the JS binding. Mutations fan
+//! out as [`StorageEvent`]s on a process-wide bus so other same-origin
+//! tabs can fire the DOM `storage` event.
+
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+/// Per-origin quota, counting key and value lengths in bytes. The spec
+/// leaves the limit to the UA; 5 MiB matches everyone else.
+pub const ORIGIN_QUOTA: usize = 5 * 1024 * 1024;
+
+/// Most recent cross-tab events kept for pickup before they rotate out.
+const MAX_EVENTS: usize = 256;
+
+/// A mutation rejected because the origin is at quota.
+#[derive(Debug, thiserror::Error)]
+#[error("storage quota exceeded for origin")]
+pub struct QuotaExceeded;
+
+/// One origin's key/value pairs.
+#[derive(Debug, Default, Clone)]
+pub struct StorageArea {
+    entries: HashMap<String, String>,
+}
+
+impl StorageArea {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries.get(key).map(String::as_str)
+    }
+
+    /// Insert or replace `key`, returning the previous value. Fails
+    /// without mutating when the write would push the area past quota.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<Option<String>, QuotaExceeded> {
+        let existing = self.entries.get(key).map_or(0, |v| key.len() + v.len());
+        let grown = self.used() - existing + key.len() + value.len();
+        if grown > ORIGIN_QUOTA {
+            return Err(QuotaExceeded);
+        }
+        Ok(self.entries.insert(key.to_owned(), value.to_owned()))
+    }
+
+    /// Remove `key`, returning the previous value.
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        self.entries.remove(key)
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The key at `index`, in an unspecified but stable-per-snapshot
+    /// order (the `Storage.key(n)` contract).
+    pub fn key(&self, index: usize) -> Option<&str> {
+        let mut keys: Vec<&str> = self.entries.keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        keys.get(index).copied()
+    }
+
+    /// Bytes of keys and values currently stored.
+    pub fn used(&self) -> usize {
+        self.entries.iter().map(|(k, v)| k.len() + v.len()).sum()
+    }
+}
+
+/// A `storage` event as seen by other same-origin tabs: `None` key means
+/// `clear()`.
+#[derive(Debug, Clone)]
+pub struct StorageEvent {
+    pub origin: String,
+    pub key: Option<String>,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    /// The mutating tab, so it can skip its own events (the spec fires
+    /// `storage` everywhere but the source document).
+    pub source_tab: u64,
+    pub sequence: u64,
+}
+
+/// The `localStorage` backend: per-origin areas persisted to disk.
+pub struct LocalStorage {
+    root: PathBuf,
+    areas: Mutex<HashMap<String, StorageArea>>,
+}
+
+impl LocalStorage {
+    /// Store areas under `root`, one JSON file per origin, created on
+    /// first write.
+    pub fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            areas: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Read `origin`'s area through `read`, loading it from disk on
+    /// first touch.
+    pub fn with_area<R>(&self, origin: &str, read: impl FnOnce(&StorageArea) -> R) -> R {
+        let mut areas = self.areas.lock().unwrap();
+        let area = Self::load_into(&mut areas, &self.root, origin);
+        read(area)
+    }
+
+    /// Mutate `origin`'s area through `write`, persisting afterwards.
+    pub fn with_area_mut<R>(&self, origin: &str, write: impl FnOnce(&mut StorageArea) -> R) -> R {
+        let mut areas = self.areas.lock().unwrap();
+        let area = Self::load_into(&mut areas, &self.root, origin);
+        let result = write(area);
+        let entries = area.entries.clone();
+        let path = self.path_for(origin);
+        if let Ok(json) = serde_json::to_vec_pretty(&entries) {
+            let _ = std::fs::create_dir_all(&self.root);
+            let _ = std::fs::write(path, json);
+        }
+        result
+    }
+
+    fn load_into<'a>(
+        areas: &'a mut HashMap<String, StorageArea>,
+        root: &Path,
+        origin: &str,
+    ) -> &'a mut StorageArea {
+        areas.entry(origin.to_owned()).or_insert_with(|| {
+            let path = root.join(format!("{}.json", sanitize(origin)));
+            let entries = std::fs::read(path)
+                .ok()
+                .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+                .unwrap_or_default();
+            StorageArea { entries }
+        })
+    }
+
+    fn path_for(&self, origin: &str) -> PathBuf {
+        self.root.join(format!("{}.json", sanitize(origin)))
+    }
+}
+
+/// An origin rendered safe as a file name (`https://a.example:8443` →
+/// `https_a.example_8443`).
+fn sanitize(origin: &str) -> String {
+    origin
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>()
+        .trim_matches('_')
+        .to_owned()
+}
+
+struct EventBus {
+    events: VecDeque<StorageEvent>,
+    next_sequence: u64,
+}
+
+static EVENTS: OnceLock<Mutex<EventBus>> = OnceLock::new();
+
+fn bus() -> &'static Mutex<EventBus> {
+    EVENTS.get_or_init(|| {
+        Mutex::new(EventBus {
+            events: VecDeque::new(),
+            next_sequence: 1,
+        })
+    })
+}
+
+/// Publish a mutation for other tabs. Returns the event's sequence
+/// number; a tab passes its last seen sequence to [`events_since`].
+pub fn publish(mut event: StorageEvent) -> u64 {
+    let mut bus = bus().lock().unwrap();
+    event.sequence = bus.next_sequence;
+    bus.next_sequence += 1;
+    if bus.events.len() == MAX_EVENTS {
+        bus.events.pop_front();
+    }
+    let sequence = event.sequence;
+    bus.events.push_back(event);
+    sequence
+}
+
+/// Events for `origin` after `last_seen`, excluding those `tab` itself
+/// published.
+pub fn events_since(origin: &str, tab: u64, last_seen: u64) -> Vec<StorageEvent> {
+    bus()
+        .lock()
+        .unwrap()
+        .events
+        .iter()
+        .filter(|e| e.sequence > last_seen && e.origin == origin && e.source_tab != tab)
+        .cloned()
+        .collect()
+}
@@ -0,0 +1,121 @@
+//! Structured network error pages.
+//!
+//! Failed navigations used to dump the raw error string into the page
+//! area. [`NetworkErrorPage`] classifies the failure, picks user-facing
+//! copy and suggestions, and tells the tab whether to offer a retry button.
+
+use crate::network::connectivity::ConnectivityMonitor;
+use crate::network::NetworkError;
+
+/// Coarse error class driving the page's copy and iconography.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorPageKind {
+    Offline,
+    DnsFailure,
+    ConnectionRefused,
+    Timeout,
+    TlsFailure,
+    Blocked,
+    Other,
+}
+
+/// Model for the error page a tab renders in place of content.
+#[derive(Debug, Clone)]
+pub struct NetworkErrorPage {
+    pub kind: ErrorPageKind,
+    pub url: String,
+    /// Technical detail shown in smaller type under the message.
+    pub detail: String,
+    pub retriable: bool,
+}
+
+impl NetworkErrorPage {
+    /// Build the page for a failed navigation to `url`. Consults the
+    /// connectivity monitor so a dead Wi-Fi link reads as "you are
+    /// offline" rather than blaming the site.
+    pub fn for_error(url: &str, error: &NetworkError) -> Self {
+        let offline = ConnectivityMonitor::shared().is_offline();
+        let kind = if offline {
+            ErrorPageKind::Offline
+        } else {
+            match error {
+                NetworkError::DnsFailure(_) => ErrorPageKind::DnsFailure,
+                NetworkError::ConnectionFailed(_) => ErrorPageKind::ConnectionRefused,
+                NetworkError::Timeout => ErrorPageKind::Timeout,
+                NetworkError::Tls(_) | NetworkError::Certificate(_) => ErrorPageKind::TlsFailure,
+                NetworkError::Blocked(_) => ErrorPageKind::Blocked,
+                _ => ErrorPageKind::Other,
+            }
+        };
+        Self {
+            kind,
+            url: url.to_owned(),
+            detail: error.to_string(),
+            retriable: !matches!(kind, ErrorPageKind::Blocked),
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self.kind {
+            ErrorPageKind::Offline => "You are offline",
+            ErrorPageKind::DnsFailure => "This site can't be found",
+            ErrorPageKind::ConnectionRefused => "This site can't be reached",
+            ErrorPageKind::Timeout => "This site took too long to respond",
+            ErrorPageKind::TlsFailure => "Can't establish a secure connection",
+            ErrorPageKind::Blocked => "This request was blocked",
+            ErrorPageKind::Other => "Something went wrong",
+        }
+    }
+
+    pub fn message(&self) -> String {
+        let host = self
+            .url
+            .split_once("://")
+            .map(|(_, rest)| rest)
+            .unwrap_or(&self.url)
+            .split(['/', '?', '#'])
+            .next()
+            .unwrap_or(&self.url)
+            .to_owned();
+        match self.kind {
+            ErrorPageKind::Offline => {
+                "Check your network cables, Wi-Fi, or router, then try again.".to_owned()
+            }
+            ErrorPageKind::DnsFailure => {
+                format!("{host}'s server address could not be found.")
+            }
+            ErrorPageKind::ConnectionRefused => {
+                format!("{host} refused the connection.")
+            }
+            ErrorPageKind::Timeout => {
+                format!("{host} did not respond in time. It may be overloaded.")
+            }
+            ErrorPageKind::TlsFailure => {
+                format!("A secure connection to {host} could not be established.")
+            }
+            ErrorPageKind::Blocked => {
+                "An installed filter or policy blocked this request.".to_owned()
+            }
+            ErrorPageKind::Other => "The page could not be loaded.".to_owned(),
+        }
+    }
+
+    /// Short actionable suggestions listed under the message.
+    pub fn suggestions(&self) -> Vec<&'static str> {
+        match self.kind {
+            ErrorPageKind::Offline => vec![
+                "Reconnect to your network",
+                "Check airplane mode",
+            ],
+            ErrorPageKind::DnsFailure => vec![
+                "Check the address for typos",
+                "Try again in a moment",
+            ],
+            ErrorPageKind::ConnectionRefused | ErrorPageKind::Timeout => vec![
+                "Try again in a moment",
+                "Check your proxy settings",
+            ],
+            _ => Vec::new(),
+        }
+    }
+}
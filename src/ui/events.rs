@@ -0,0 +1,93 @@
+//! Routing platform input into the page's DOM.
+//!
+//! The windowing layer reports raw input as [`InputEvent`]s; the tab's
+//! frame loop hit-tests them against the current layout tree and fires
+//! the corresponding DOM event through the page's JS runtime, so
+//! `onclick` handlers on real pages run. The return value says whether a
+//! handler claimed the event (`preventDefault`), in which case the
+//! browser's own default — link following, scrolling — is suppressed.
+
+use boa_engine::JsValue;
+
+use crate::js_engine::{events, JsRuntime};
+use crate::renderer::layout::{hit_test, LayoutBox};
+
+/// One platform input event, in page coordinates.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputEvent {
+    MouseMove { x: f32, y: f32 },
+    MouseDown { x: f32, y: f32 },
+    MouseUp { x: f32, y: f32 },
+    Click { x: f32, y: f32 },
+    KeyDown { key: String },
+    KeyUp { key: String },
+    Wheel { x: f32, y: f32, dx: f32, dy: f32 },
+}
+
+impl InputEvent {
+    /// The DOM event type this input produces.
+    fn event_type(&self) -> &'static str {
+        match self {
+            InputEvent::MouseMove { .. } => "mousemove",
+            InputEvent::MouseDown { .. } => "mousedown",
+            InputEvent::MouseUp { .. } => "mouseup",
+            InputEvent::Click { .. } => "click",
+            InputEvent::KeyDown { .. } => "keydown",
+            InputEvent::KeyUp { .. } => "keyup",
+            InputEvent::Wheel { .. } => "wheel",
+        }
+    }
+
+    /// Page position for pointer events; key events have none.
+    fn position(&self) -> Option<(f32, f32)> {
+        match *self {
+            InputEvent::MouseMove { x, y }
+            | InputEvent::MouseDown { x, y }
+            | InputEvent::MouseUp { x, y }
+            | InputEvent::Click { x, y }
+            | InputEvent::Wheel { x, y, .. } => Some((x, y)),
+            InputEvent::KeyDown { .. } | InputEvent::KeyUp { .. } => None,
+        }
+    }
+}
+
+/// Dispatch `event` into the page. Pointer events target the deepest box
+/// under the pointer; key events target the document root until focus
+/// tracking reaches the runtime. Returns false when a handler called
+/// `preventDefault` and the browser default should be suppressed.
+pub fn dispatch_to_page(event: &InputEvent, laid: &LayoutBox, runtime: &mut JsRuntime) -> bool {
+    let target = match event.position() {
+        Some((x, y)) => match hit_test(laid, x, y) {
+            Some(node) => node,
+            // Missed every box: nothing to run, default proceeds.
+            None => return true,
+        },
+        // Key events: target the body until focus tracking reaches the
+        // runtime.
+        None => crate::js_engine::dom::with_document(|document| {
+            document
+                .descendants(document.root())
+                .into_iter()
+                .find(|&node| {
+                    document.element(node).map_or(false, |e| e.tag_name == "body")
+                })
+                .unwrap_or(document.root())
+        }),
+    };
+    let mut init: Vec<(&str, JsValue)> = Vec::new();
+    if let Some((x, y)) = event.position() {
+        init.push(("clientX", JsValue::from(x)));
+        init.push(("clientY", JsValue::from(y)));
+    }
+    match event {
+        InputEvent::KeyDown { key } | InputEvent::KeyUp { key } => {
+            init.push(("key", boa_engine::JsString::from(key.as_str()).into()));
+        }
+        InputEvent::Wheel { dx, dy, .. } => {
+            init.push(("deltaX", JsValue::from(*dx)));
+            init.push(("deltaY", JsValue::from(*dy)));
+        }
+        _ => {}
+    }
+    events::dispatch(runtime.context(), target, event.event_type(), init).unwrap_or(true)
+}
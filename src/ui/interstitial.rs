@@ -0,0 +1,75 @@
+//! Certificate warning interstitials.
+//!
+//! When a navigation fails TLS validation the tab shows this page instead
+//! of the site: a plain-language explanation, certificate details behind an
+//! "Advanced" disclosure, and — unless policy forbids it — a per-site
+//! "proceed anyway" override recorded in the [`SecurityManager`].
+
+use crate::network::tls::TlsFailure;
+use crate::security::SecurityManager;
+
+/// What the user chose on the interstitial.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InterstitialChoice {
+    GoBack,
+    ProceedAnyway,
+}
+
+/// Model for the certificate warning page shown in place of content.
+#[derive(Debug, Clone)]
+pub struct CertificateInterstitial {
+    pub failure: TlsFailure,
+    /// Whether the "proceed anyway" escape hatch is offered. Disabled for
+    /// pinned or HSTS hosts, where the spec forbids click-through.
+    pub allow_override: bool,
+}
+
+impl CertificateInterstitial {
+    pub fn new(failure: TlsFailure, security: &SecurityManager) -> Self {
+        let allow_override = !security.hsts().is_secure_host(&failure.host);
+        Self {
+            failure,
+            allow_override,
+        }
+    }
+
+    /// Headline shown in the tab.
+    pub fn title(&self) -> String {
+        format!("Your connection to {} is not private", self.failure.host)
+    }
+
+    /// Plain-language explanation of the failure.
+    pub fn message(&self) -> String {
+        format!(
+            "Binix blocked this page because {}. Attackers might be trying to \
+             steal your information from {}.",
+            self.failure.error, self.failure.host
+        )
+    }
+
+    /// Detail lines for the "Advanced" section.
+    pub fn details(&self) -> Vec<(String, String)> {
+        let mut details = vec![("Host".to_owned(), self.failure.host.clone())];
+        if let Some(cert) = &self.failure.certificate {
+            details.push(("Subject".to_owned(), cert.subject.clone()));
+            details.push(("Issuer".to_owned(), cert.issuer.clone()));
+            details.push(("Valid from".to_owned(), cert.not_before.clone()));
+            details.push(("Valid until".to_owned(), cert.not_after.clone()));
+            details.push(("SPKI SHA-256".to_owned(), cert.spki_sha256.clone()));
+        }
+        details
+    }
+
+    /// Apply the user's choice, recording a per-site override when they
+    /// proceed. Returns whether the navigation should be retried.
+    pub fn resolve(&self, choice: InterstitialChoice, security: &SecurityManager) -> bool {
+        match choice {
+            InterstitialChoice::GoBack => false,
+            InterstitialChoice::ProceedAnyway if self.allow_override => {
+                security.add_tls_override(&self.failure.host);
+                true
+            }
+            InterstitialChoice::ProceedAnyway => false,
+        }
+    }
+}
@@ -0,0 +1,9 @@
+//! Browser chrome: tabs, page display, dialogs, and internally generated
+//! pages.
+
+pub mod error_page;
+pub mod events;
+pub mod interstitial;
+pub mod navigation;
+pub mod scroll;
+pub mod tab;
@@ -0,0 +1,156 @@
+//! Driving navigations from the UI thread.
+//!
+//! The UI never blocks on the network and never spawns its own fetch
+//! threads: [`Navigator`] hands the URL to
+//! [`BrowserEngine::process_page`] on the tokio runtime and the frame
+//! loop polls for the result. Starting a new navigation aborts the one
+//! in flight, so a click during a slow load cancels cleanly instead of
+//! racing two loads into the same tab.
+
+use std::sync::Arc;
+
+use tokio::runtime::Handle;
+use tokio::task::JoinHandle;
+
+use crate::engine::{BrowserEngine, PageLoad};
+use crate::network::NetworkError;
+
+use super::error_page::NetworkErrorPage;
+use super::tab::Tab;
+
+/// Owns the tab's relationship with the engine pipeline: at most one
+/// navigation in flight, newest wins.
+pub struct Navigator {
+    engine: Arc<BrowserEngine>,
+    runtime: Handle,
+    inflight: Option<Flight>,
+}
+
+struct Flight {
+    /// The URL as requested, for error reporting (the committed page
+    /// carries the post-redirect URL).
+    url: String,
+    task: JoinHandle<Result<PageLoad, NetworkError>>,
+}
+
+/// What one [`Navigator::poll`] call observed.
+pub enum NavigationStatus {
+    Idle,
+    Loading,
+    /// The finished page was committed into the tab.
+    Committed,
+    /// The load failed; the tab should display this error page.
+    Failed(NetworkErrorPage),
+}
+
+impl Navigator {
+    pub fn new(engine: Arc<BrowserEngine>, runtime: Handle) -> Self {
+        Self {
+            engine,
+            runtime,
+            inflight: None,
+        }
+    }
+
+    /// Start loading `url`, cancelling any navigation already in flight.
+    pub fn navigate(&mut self, url: &str) {
+        if let Some(previous) = self.inflight.take() {
+            previous.task.abort();
+        }
+        let engine = Arc::clone(&self.engine);
+        let target = url.to_owned();
+        let task = self.runtime.spawn({
+            let target = target.clone();
+            async move { engine.process_page(&target).await }
+        });
+        self.inflight = Some(Flight { url: target, task });
+    }
+
+    /// Start a form submission: like [`navigate`](Self::navigate), but
+    /// carrying the prepared request — method, body, content type —
+    /// instead of a bare URL.
+    pub fn submit(&mut self, request: crate::network::Request) {
+        if let Some(previous) = self.inflight.take() {
+            previous.task.abort();
+        }
+        let engine = Arc::clone(&self.engine);
+        let url = request.url.clone();
+        let task = self
+            .runtime
+            .spawn(async move { engine.process_request(request).await });
+        self.inflight = Some(Flight { url, task });
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.inflight.is_some()
+    }
+
+    /// Back/forward button: traverse the session history by `delta`
+    /// entries. Same-document moves fire `popstate` and update the URL
+    /// bar in place; a move off the end of the list is ignored (there is
+    /// no entry to go to without a real navigation).
+    pub fn traverse_history(
+        &mut self,
+        tab: &mut Tab,
+        runtime: &mut crate::js_engine::JsRuntime,
+        delta: i64,
+    ) {
+        if let Some(url) = crate::js_engine::history::traverse(runtime.context(), delta) {
+            tab.url = url;
+        }
+    }
+
+    /// Check on the in-flight navigation, committing it into `tab` when
+    /// done. Called once per UI frame; collecting an already finished
+    /// task is the only "blocking" it does.
+    pub fn poll(&mut self, tab: &mut Tab) -> NavigationStatus {
+        // SPA navigations (pushState, history traversal) move the URL
+        // without a fetch; keep the address bar in step.
+        if let Some(url) = crate::js_engine::history::take_url_change() {
+            crate::js_engine::window::configure(&url);
+            tab.url = url;
+        }
+        // location.assign()/reload() become real loads.
+        match crate::js_engine::window::take_navigation() {
+            Some(crate::js_engine::window::NavigationRequest::Load(url)) => self.navigate(&url),
+            Some(crate::js_engine::window::NavigationRequest::Reload) => {
+                let url = tab.url.clone();
+                self.navigate(&url);
+            }
+            Some(crate::js_engine::window::NavigationRequest::Submit(request)) => {
+                self.submit(request);
+            }
+            None => {}
+        }
+        match &self.inflight {
+            None => return NavigationStatus::Idle,
+            Some(flight) if !flight.task.is_finished() => return NavigationStatus::Loading,
+            Some(_) => {}
+        }
+        let flight = self.inflight.take().expect("checked above");
+        match self.runtime.block_on(flight.task) {
+            Ok(Ok(page)) => {
+                // Point the page's fetch() and storage at the stack and
+                // the new document before scripts can run.
+                crate::js_engine::fetch::configure(self.engine.network().clone(), &page.url);
+                let origin =
+                    crate::network::http3::origin_of(&page.url).unwrap_or_else(|_| page.url.clone());
+                crate::js_engine::storage::configure(
+                    self.engine.local_storage().clone(),
+                    &origin,
+                );
+                crate::js_engine::history::record_navigation(&page.url);
+                crate::js_engine::window::configure(&page.url);
+                crate::js_engine::messaging::configure();
+                crate::js_engine::shared::configure(page.cross_origin_isolated);
+                tab.commit(page);
+                NavigationStatus::Committed
+            }
+            Ok(Err(error)) => {
+                NavigationStatus::Failed(NetworkErrorPage::for_error(&flight.url, &error))
+            }
+            // Aborted by a newer navigation, whose own poll will report.
+            Err(_) => NavigationStatus::Idle,
+        }
+    }
+}
@@ -0,0 +1,116 @@
+//! Scroll offsets for the page and nested scroll containers.
+//!
+//! Every `overflow: auto`/`scroll` box with overflowing content scrolls
+//! independently of the page. Wheel input routes to the innermost scroll
+//! container under the pointer; whatever it cannot consume chains to
+//! enclosing containers and finally the page scroller, matching platform
+//! scroll-chaining behaviour. The painter subtracts a container's offset
+//! when drawing its descendants and clips them to
+//! [`LayoutBox::clip_rect`].
+
+use std::collections::HashMap;
+
+use crate::renderer::dom::NodeId;
+use crate::renderer::layout::LayoutBox;
+
+/// Identifies one scroller: `None` is the page (viewport) scroller.
+pub type ScrollId = Option<NodeId>;
+
+/// Per-scroller offsets for one page.
+#[derive(Default)]
+pub struct ScrollState {
+    offsets: HashMap<ScrollId, (f32, f32)>,
+}
+
+impl ScrollState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Current offset of a scroller; unscrolled containers read (0, 0).
+    pub fn offset(&self, id: ScrollId) -> (f32, f32) {
+        self.offsets.get(&id).copied().unwrap_or((0.0, 0.0))
+    }
+
+    /// Drop offsets for containers no longer in the layout (navigation,
+    /// subtree removal).
+    pub fn clear(&mut self) {
+        self.offsets.clear();
+    }
+
+    /// Apply a wheel delta at page coordinates (`x`, `y`). The innermost
+    /// scroll container under the point consumes what it can; leftover
+    /// delta chains outwards, ending at the page scroller. Returns whether
+    /// any offset changed (a repaint is needed).
+    pub fn scroll_by(
+        &mut self,
+        root: &LayoutBox,
+        viewport: (f32, f32),
+        x: f32,
+        y: f32,
+        dx: f32,
+        dy: f32,
+    ) -> bool {
+        // Outermost-first chain of scrollers under the point; the page
+        // scroller is always its head.
+        let page_max = (
+            (root.content_width - viewport.0).max(0.0),
+            (root.content_height - viewport.1).max(0.0),
+        );
+        let mut chain: Vec<(ScrollId, (f32, f32))> = vec![(None, page_max)];
+        collect_chain(root, x, y, &mut chain);
+
+        let mut remaining = (dx, dy);
+        let mut changed = false;
+        for &(id, max) in chain.iter().rev() {
+            if remaining == (0.0, 0.0) {
+                break;
+            }
+            let current = self.offset(id);
+            let next = (
+                (current.0 + remaining.0).clamp(0.0, max.0),
+                (current.1 + remaining.1).clamp(0.0, max.1),
+            );
+            if next != current {
+                remaining = (
+                    remaining.0 - (next.0 - current.0),
+                    remaining.1 - (next.1 - current.1),
+                );
+                self.offsets.insert(id, next);
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Scroll a specific container to an exact offset, clamped to its
+    /// scrollable range (`element.scrollTo`).
+    pub fn scroll_to(&mut self, container: &LayoutBox, x: f32, y: f32) {
+        let max = container.max_scroll();
+        self.offsets.insert(
+            container.node,
+            (x.clamp(0.0, max.0), y.clamp(0.0, max.1)),
+        );
+    }
+}
+
+/// Append the scroll containers under (`x`, `y`) to `chain`, outermost
+/// first. Coordinates are page-space; a container's own scroll offset is
+/// not applied to its descendants' hit rectangles.
+fn collect_chain(laid: &LayoutBox, x: f32, y: f32, chain: &mut Vec<(ScrollId, (f32, f32))>) {
+    let inside = x >= laid.rect.x
+        && x < laid.rect.x + laid.rect.width
+        && y >= laid.rect.y
+        && y < laid.rect.y + laid.rect.height;
+    if laid.node.is_some() && laid.is_scroll_container() {
+        if !inside {
+            // Clipped subtree the point is outside of: nothing below can
+            // be under the pointer.
+            return;
+        }
+        chain.push((laid.node, laid.max_scroll()));
+    }
+    for child in &laid.children {
+        collect_chain(child, x, y, chain);
+    }
+}
@@ -0,0 +1,307 @@
+//! A tab: one page being loaded, styled, and displayed.
+//!
+//! `Tab` drives the renderer for a navigation — parse the markup, collect
+//! stylesheets, resolve styles per element, flow the boxes. Every stage
+//! lives in [`crate::renderer`]; the tab holds the page state and the
+//! entry points, never a second parser, selector engine, or layout of its
+//! own.
+
+use std::collections::HashSet;
+
+use crate::renderer::css::{self, Declaration, InteractionState};
+use crate::renderer::dirty::LayoutChange;
+use crate::renderer::dom::{Document, NodeData, NodeId};
+use crate::renderer::frame::FrameTree;
+use crate::renderer::html;
+use crate::renderer::layout::{self, LayoutBox, TextMeasurer};
+use crate::renderer::media::{ColorScheme, MediaEnvironment};
+use crate::renderer::style::{self, ComputedStyle, StyleEngine};
+
+pub use crate::renderer::css::Selector as CssSelector;
+
+/// One browser tab's page state.
+pub struct Tab {
+    pub url: String,
+    pub document: Document,
+    styles: StyleEngine,
+    media: MediaEnvironment,
+    frames: FrameTree,
+    /// Present while a progressive load is in flight.
+    streaming: Option<html::StreamingParser>,
+}
+
+impl Tab {
+    pub fn new() -> Self {
+        Self {
+            url: String::new(),
+            document: Document::new(),
+            styles: StyleEngine::new(),
+            media: MediaEnvironment::default(),
+            frames: FrameTree::default(),
+            streaming: None,
+        }
+    }
+
+    /// Update the viewport size. Returns whether computed styles must be
+    /// recomputed — true when a stylesheet uses media queries or
+    /// viewport-relative units; otherwise a relayout alone suffices.
+    pub fn set_viewport(&mut self, width: f32, height: f32) -> bool {
+        self.media.width = width;
+        self.media.height = height;
+        // Scripts read the same numbers via window.innerWidth/Height.
+        crate::js_engine::window::set_viewport(width, height);
+        self.styles.depends_on_viewport()
+    }
+
+    /// Mark the tab as backgrounded (or foregrounded again). Background
+    /// tabs get throttled timers — one-second batched wakeups — so idle
+    /// pages stop waking the CPU.
+    pub fn set_background(&mut self, background: bool) {
+        crate::js_engine::timers::set_background(background);
+    }
+
+    /// Update the preferred color scheme (system theme change).
+    pub fn set_color_scheme(&mut self, scheme: ColorScheme) {
+        self.media.color_scheme = scheme;
+    }
+
+    pub fn media(&self) -> &MediaEnvironment {
+        &self.media
+    }
+
+    /// Install a finished navigation from
+    /// [`crate::engine::BrowserEngine::process_page`]: the parsed
+    /// document with its stylesheets and frames, wholesale.
+    pub fn commit(&mut self, page: crate::engine::PageLoad) {
+        self.url = page.url;
+        self.document = page.document;
+        self.styles = page.styles;
+        self.frames = page.frames;
+        self.streaming = None;
+        // The old page's scheduled work must not outlive it.
+        crate::js_engine::abort::clear();
+        crate::js_engine::errors::clear();
+        crate::js_engine::events::clear_listeners();
+        crate::js_engine::fetch::clear();
+        crate::js_engine::forms::clear();
+        crate::js_engine::mutation::clear();
+        crate::js_engine::raf::clear();
+        crate::js_engine::shared::clear();
+        crate::js_engine::timers::clear();
+        crate::js_engine::url::clear();
+        crate::js_engine::wasm::clear();
+        crate::js_engine::worker::clear();
+        crate::js_engine::xhr::clear();
+    }
+
+    /// Replace the page with `html` loaded from `url`. Stylesheets in
+    /// `<style>` elements are collected; external sheets are fetched by
+    /// the navigation code and handed in via [`Tab::add_stylesheet`].
+    pub fn load_html(&mut self, url: &str, html: &str) {
+        self.url = url.to_owned();
+        self.document = html::parse(html);
+        self.styles.clear();
+        self.frames.clear();
+        style::collect_styles(&self.document, &mut self.styles);
+    }
+
+    /// Start a progressive load from `url`: the document arrives through
+    /// [`Tab::feed_html`] as network chunks land, and the partial tree is
+    /// renderable after every feed.
+    pub fn begin_load(&mut self, url: &str) {
+        self.url = url.to_owned();
+        self.document = Document::new();
+        self.styles.clear();
+        self.frames.clear();
+        self.streaming = Some(html::StreamingParser::new());
+    }
+
+    /// Feed one chunk of the document. The tab's DOM and stylesheets are
+    /// updated to the last safe parse point, so the caller can style and
+    /// lay out what is already there. No-op outside a progressive load.
+    pub fn feed_html(&mut self, chunk: &str) {
+        let Some(parser) = &mut self.streaming else {
+            return;
+        };
+        parser.feed(chunk);
+        self.document = parser.document().clone();
+        self.styles.clear();
+        style::collect_styles(&self.document, &mut self.styles);
+    }
+
+    /// The response finished: commit the complete document.
+    pub fn finish_load(&mut self) {
+        let Some(parser) = self.streaming.take() else {
+            return;
+        };
+        self.document = parser.finish();
+        self.styles.clear();
+        style::collect_styles(&self.document, &mut self.styles);
+    }
+
+    /// Pull script-made DOM mutations back into the tab and report the
+    /// damage. Call once per frame after pumping the runtime; the
+    /// returned pairs feed
+    /// [`DirtyTracker::mark`](crate::renderer::dirty::DirtyTracker::mark),
+    /// so scripted changes re-render incrementally. Cheap when scripts
+    /// changed nothing.
+    pub fn sync_scripted_dom(&mut self) -> Vec<(NodeId, LayoutChange)> {
+        let damage = crate::js_engine::dom::take_damage();
+        if damage.is_empty() {
+            return damage;
+        }
+        self.document = crate::js_engine::dom::with_document(Document::clone);
+        self.styles.clear();
+        style::collect_styles(&self.document, &mut self.styles);
+        damage
+    }
+
+    /// Install the nested frame documents for this page, produced by
+    /// [`crate::renderer::frame::FrameLoader`] after navigation.
+    pub fn set_frames(&mut self, frames: FrameTree) {
+        self.frames = frames;
+    }
+
+    pub fn frames(&self) -> &FrameTree {
+        &self.frames
+    }
+
+    pub fn add_stylesheet(&mut self, source: &str) {
+        self.styles.add_stylesheet(css::parse_stylesheet(source));
+    }
+
+    /// The style engine holding this page's stylesheets.
+    pub fn styles(&self) -> &StyleEngine {
+        &self.styles
+    }
+
+    /// Lay out the current document against the tab's viewport, loaded
+    /// frames included. This is the tab's only path to a box tree; UI
+    /// code paints and hit-tests what comes back rather than measuring
+    /// the page itself.
+    pub fn layout(&self, measurer: &dyn TextMeasurer) -> LayoutBox {
+        layout::layout_document_with_frames(
+            &self.document,
+            &self.styles,
+            &self.media,
+            measurer,
+            &self.frames,
+        )
+    }
+
+    /// Declarations applying to `node`, in full cascade order (origin and
+    /// importance, then specificity, then source order). Later entries win
+    /// when collapsed into a style map.
+    pub fn matching_declarations(&self, node: NodeId) -> Vec<Declaration> {
+        self.styles.cascade(&self.document, node, &self.media)
+    }
+
+    /// The resolved property map for `node`: cascade applied and `var()`
+    /// references substituted from inherited custom properties.
+    pub fn computed_style(&self, node: NodeId) -> ComputedStyle {
+        self.styles.style_for(&self.document, node, &self.media)
+    }
+
+    /// All element nodes matching `selector_text`, in document order.
+    pub fn select(&self, selector_text: &str) -> Vec<NodeId> {
+        let Some(selector) = CssSelector::parse(selector_text) else {
+            return Vec::new();
+        };
+        self.document
+            .descendants(self.document.root())
+            .into_iter()
+            .filter(|&node| {
+                matches!(self.document.node(node).data, NodeData::Element(_))
+                    && selector.matches(&self.document, node, self.styles.interaction())
+            })
+            .collect()
+    }
+
+    /// Move the pointer onto `node` (or off the page with `None`). Returns
+    /// the elements whose style may have changed, for restyle and repaint;
+    /// empty when no stylesheet uses dynamic pseudo-classes.
+    pub fn set_hovered(&mut self, node: Option<NodeId>) -> Vec<NodeId> {
+        let mut state = self.styles.interaction().clone();
+        state.hovered = self.ancestor_chain(node);
+        self.apply_interaction(state)
+    }
+
+    /// Move keyboard focus to `node` (or clear it with `None`).
+    pub fn set_focused(&mut self, node: Option<NodeId>) -> Vec<NodeId> {
+        let mut state = self.styles.interaction().clone();
+        state.focused = node;
+        self.apply_interaction(state)
+    }
+
+    /// Begin or end activation of `node` (pointer press / release).
+    pub fn set_active(&mut self, node: Option<NodeId>) -> Vec<NodeId> {
+        let mut state = self.styles.interaction().clone();
+        state.active = self.ancestor_chain(node);
+        self.apply_interaction(state)
+    }
+
+    /// Re-resolve the style of each node in `nodes` under the current
+    /// interaction state.
+    pub fn restyle(&self, nodes: &[NodeId]) -> Vec<(NodeId, ComputedStyle)> {
+        nodes
+            .iter()
+            .map(|&node| (node, self.computed_style(node)))
+            .collect()
+    }
+
+    /// `node` plus its element ancestors: hover and activation propagate
+    /// up the tree.
+    fn ancestor_chain(&self, node: Option<NodeId>) -> HashSet<NodeId> {
+        let mut chain = HashSet::new();
+        let mut current = node;
+        while let Some(candidate) = current {
+            if self.document.element(candidate).is_some() {
+                chain.insert(candidate);
+            }
+            current = self.document.parent(candidate);
+        }
+        chain
+    }
+
+    /// Install `state` and compute the damage: elements whose interaction
+    /// bits changed, plus their descendants (a `div:hover span` rule can
+    /// restyle elements below the changed one).
+    fn apply_interaction(&mut self, state: InteractionState) -> Vec<NodeId> {
+        let old = self.styles.interaction().clone();
+        let mut changed: HashSet<NodeId> = old
+            .hovered
+            .symmetric_difference(&state.hovered)
+            .chain(old.active.symmetric_difference(&state.active))
+            .copied()
+            .collect();
+        if old.focused != state.focused {
+            changed.extend(old.focused.into_iter().chain(state.focused));
+        }
+        self.styles.set_interaction(state);
+        if changed.is_empty() || !self.styles.uses_interaction_styles() {
+            return Vec::new();
+        }
+        let mut impacted = Vec::new();
+        let mut in_changed_subtree: HashSet<NodeId> = HashSet::new();
+        for node in self.document.descendants(self.document.root()) {
+            if !matches!(self.document.node(node).data, NodeData::Element(_)) {
+                continue;
+            }
+            let inherited = self
+                .document
+                .parent(node)
+                .map_or(false, |parent| in_changed_subtree.contains(&parent));
+            if changed.contains(&node) || inherited {
+                in_changed_subtree.insert(node);
+                impacted.push(node);
+            }
+        }
+        impacted
+    }
+}
+
+impl Default for Tab {
+    fn default() -> Self {
+        Self::new()
+    }
+}
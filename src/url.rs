@@ -0,0 +1,312 @@
+//! URL parsing and resolution, WHATWG-shaped.
+//!
+//! One parser for everything that handles URLs: navigation, subresource
+//! resolution ([`crate::renderer::loader::resolve_url`] delegates here),
+//! and the `URL`/`URLSearchParams` JS globals. [`Url`] keeps the
+//! components the engine actually consults — scheme, host, port, path,
+//! query, fragment — normalised the way the WHATWG algorithm would:
+//! scheme and host lowercased, default ports elided, dot segments
+//! removed. Exotica like IDNA and percent-encoding normalisation are
+//! out of scope; the parser never un-escapes what it was given.
+
+use std::fmt;
+
+/// Errors from URL parsing.
+#[derive(Debug, thiserror::Error)]
+pub enum UrlError {
+    #[error("missing or invalid scheme")]
+    InvalidScheme,
+    #[error("invalid port")]
+    InvalidPort,
+    #[error("relative URL without a base")]
+    RelativeWithoutBase,
+}
+
+/// A parsed absolute URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Url {
+    /// Lowercased, without the trailing `:`.
+    pub scheme: String,
+    /// Lowercased; empty for schemes without an authority (`data:`).
+    pub host: String,
+    /// Absent when unset or equal to the scheme default.
+    pub port: Option<u16>,
+    /// Always starts with `/` when an authority is present.
+    pub path: String,
+    /// Without the leading `?`.
+    pub query: Option<String>,
+    /// Without the leading `#`.
+    pub fragment: Option<String>,
+}
+
+impl Url {
+    /// Parse an absolute URL.
+    pub fn parse(input: &str) -> Result<Self, UrlError> {
+        let input = input.trim();
+        let colon = input.find(':').ok_or(UrlError::InvalidScheme)?;
+        let scheme = &input[..colon];
+        if scheme.is_empty()
+            || !scheme.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+            || !scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        {
+            return Err(UrlError::InvalidScheme);
+        }
+        let scheme = scheme.to_ascii_lowercase();
+        let rest = &input[colon + 1..];
+
+        let (host, port, after_authority) = match rest.strip_prefix("//") {
+            Some(rest) => {
+                let end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+                let authority = &rest[..end];
+                // Userinfo is accepted and dropped; the engine never
+                // sends credentials from URLs.
+                let host_port = authority.rsplit_once('@').map_or(authority, |(_, h)| h);
+                let (host, port) = split_host_port(host_port)?;
+                (host, port, &rest[end..])
+            }
+            None => (String::new(), None, rest),
+        };
+        let port = port.filter(|&p| Some(p) != default_port(&scheme));
+
+        let path_end = after_authority.find(['?', '#']).unwrap_or(after_authority.len());
+        let raw_path = &after_authority[..path_end];
+        let path = if host.is_empty() {
+            raw_path.to_owned()
+        } else {
+            remove_dot_segments(if raw_path.is_empty() { "/" } else { raw_path })
+        };
+        let mut tail = &after_authority[path_end..];
+        let fragment = match tail.find('#') {
+            Some(hash) => {
+                let fragment = tail[hash + 1..].to_owned();
+                tail = &tail[..hash];
+                Some(fragment)
+            }
+            None => None,
+        };
+        let query = tail.strip_prefix('?').map(str::to_owned);
+
+        Ok(Self {
+            scheme,
+            host,
+            port,
+            path,
+            query,
+            fragment,
+        })
+    }
+
+    /// Resolve `href` against this URL, per the WHATWG relative
+    /// resolution rules.
+    pub fn join(&self, href: &str) -> Result<Self, UrlError> {
+        let href = href.trim();
+        if href.is_empty() {
+            return Ok(self.clone());
+        }
+        if let Ok(absolute) = Self::parse(href) {
+            return Ok(absolute);
+        }
+        if let Some(rest) = href.strip_prefix("//") {
+            // Protocol-relative: new authority, this scheme.
+            return Self::parse(&format!("{}://{rest}", self.scheme));
+        }
+        let mut resolved = self.clone();
+        resolved.fragment = None;
+        if let Some(fragment) = href.strip_prefix('#') {
+            resolved.query = self.query.clone();
+            resolved.fragment = Some(fragment.to_owned());
+            return Ok(resolved);
+        }
+        resolved.query = None;
+        let (body, fragment) = match href.split_once('#') {
+            Some((body, fragment)) => (body, Some(fragment.to_owned())),
+            None => (href, None),
+        };
+        resolved.fragment = fragment;
+        let (path_part, query) = match body.split_once('?') {
+            Some((path, query)) => (path, Some(query.to_owned())),
+            None => (body, None),
+        };
+        resolved.query = query;
+        resolved.path = if path_part.starts_with('/') {
+            remove_dot_segments(path_part)
+        } else if path_part.is_empty() {
+            self.path.clone()
+        } else {
+            let directory = self.path.rsplit_once('/').map_or("", |(dir, _)| dir);
+            remove_dot_segments(&format!("{directory}/{path_part}"))
+        };
+        Ok(resolved)
+    }
+
+    /// The serialised URL.
+    pub fn href(&self) -> String {
+        self.to_string()
+    }
+
+    /// `scheme://host[:port]`, the security origin.
+    pub fn origin(&self) -> String {
+        format!("{}://{}", self.scheme, self.host_with_port())
+    }
+
+    /// Host with the non-default port appended, the `host` getter view.
+    pub fn host_with_port(&self) -> String {
+        match self.port {
+            Some(port) => format!("{}:{port}", self.host),
+            None => self.host.clone(),
+        }
+    }
+}
+
+impl fmt::Display for Url {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:", self.scheme)?;
+        if !self.host.is_empty() {
+            write!(f, "//{}", self.host_with_port())?;
+        }
+        write!(f, "{}", self.path)?;
+        if let Some(query) = &self.query {
+            write!(f, "?{query}")?;
+        }
+        if let Some(fragment) = &self.fragment {
+            write!(f, "#{fragment}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Resolve `href` against `base`, falling back to passing `href`
+/// through when neither parses. The lenient entry point the loaders
+/// use: markup full of broken URLs should degrade, not error.
+pub fn resolve(base: &str, href: &str) -> String {
+    if let Ok(base) = Url::parse(base) {
+        if let Ok(resolved) = base.join(href) {
+            return resolved.href();
+        }
+    }
+    if let Ok(absolute) = Url::parse(href) {
+        return absolute.href();
+    }
+    href.to_owned()
+}
+
+/// The default port of well-known schemes, elided on serialisation.
+fn default_port(scheme: &str) -> Option<u16> {
+    match scheme {
+        "http" | "ws" => Some(80),
+        "https" | "wss" => Some(443),
+        "ftp" => Some(21),
+        _ => None,
+    }
+}
+
+fn split_host_port(host_port: &str) -> Result<(String, Option<u16>), UrlError> {
+    // Bracketed IPv6 literals keep their colons.
+    if let Some(rest) = host_port.strip_prefix('[') {
+        return match rest.split_once(']') {
+            Some((address, tail)) => {
+                let port = match tail.strip_prefix(':') {
+                    Some(port) => Some(port.parse().map_err(|_| UrlError::InvalidPort)?),
+                    None => None,
+                };
+                Ok((format!("[{address}]"), port))
+            }
+            None => Err(UrlError::InvalidPort),
+        };
+    }
+    match host_port.rsplit_once(':') {
+        Some((host, port)) => {
+            let port = port.parse().map_err(|_| UrlError::InvalidPort)?;
+            Ok((host.to_ascii_lowercase(), Some(port)))
+        }
+        None => Ok((host_port.to_ascii_lowercase(), None)),
+    }
+}
+
+/// RFC 3986 dot-segment removal over an absolute path.
+fn remove_dot_segments(path: &str) -> String {
+    let mut output: Vec<&str> = Vec::new();
+    let trailing_slash = path.ends_with('/') || path.ends_with("/.") || path.ends_with("/..");
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                output.pop();
+            }
+            segment => output.push(segment),
+        }
+    }
+    let mut result = String::from("/");
+    result.push_str(&output.join("/"));
+    if trailing_slash && result.len() > 1 {
+        result.push('/');
+    }
+    result
+}
+
+/// Parse an `application/x-www-form-urlencoded` query into pairs.
+/// Shared by the `URLSearchParams` binding.
+pub fn parse_query(query: &str) -> Vec<(String, String)> {
+    query
+        .split('&')
+        .filter(|piece| !piece.is_empty())
+        .map(|piece| {
+            let (name, value) = piece.split_once('=').unwrap_or((piece, ""));
+            (decode_component(name), decode_component(value))
+        })
+        .collect()
+}
+
+/// Serialise pairs back into a query string.
+pub fn serialize_query(pairs: &[(String, String)]) -> String {
+    pairs
+        .iter()
+        .map(|(name, value)| format!("{}={}", encode_component(name), encode_component(value)))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Percent-decode a form-urlencoded component (`+` is a space).
+fn decode_component(component: &str) -> String {
+    let bytes = component.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' => {
+                match bytes
+                    .get(i + 1..i + 3)
+                    .and_then(|hex| std::str::from_utf8(hex).ok())
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                {
+                    Some(byte) => {
+                        out.push(byte);
+                        i += 2;
+                    }
+                    None => out.push(b'%'),
+                }
+            }
+            byte => out.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Percent-encode a form-urlencoded component.
+fn encode_component(component: &str) -> String {
+    let mut out = String::with_capacity(component.len());
+    for byte in component.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'*' => {
+                out.push(byte as char);
+            }
+            b' ' => out.push('+'),
+            byte => out.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    out
+}
@@ -0,0 +1,30 @@
+//! WebAssembly execution.
+//!
+//! A from-scratch core-wasm implementation, like the rest of the
+//! engine: [`module`] decodes the binary format into a [`Module`], and
+//! [`runtime`] instantiates and interprets it. The JS-facing
+//! `WebAssembly` API in [`crate::js_engine::wasm`] sits on top of
+//! [`WasmRuntime`], marshalling imports and exports through `JsValue`;
+//! nothing here knows about the JS engine, so the interpreter is usable
+//! (and testable) on plain byte buffers.
+
+pub mod module;
+pub mod runtime;
+
+pub use module::{Module, StreamingCompiler};
+pub use runtime::{Instance, Value, WasmFeatures, WasmRuntime};
+
+/// Errors from decoding, instantiating, or running a module.
+#[derive(Debug, thiserror::Error)]
+pub enum WasmError {
+    #[error("invalid module: {0}")]
+    Decode(String),
+    #[error("instantiation failed: {0}")]
+    Instantiation(String),
+    #[error("unknown export: {0}")]
+    UnknownExport(String),
+    #[error("trap: {0}")]
+    Trap(String),
+    #[error("unsupported instruction: {0:#04x}")]
+    UnsupportedOpcode(u8),
+}
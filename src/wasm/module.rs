@@ -0,0 +1,486 @@
+//! The WebAssembly binary format: decoding a `.wasm` byte buffer into a
+//! [`Module`].
+//!
+//! Covers the core MVP sections — types, imports, functions, memories,
+//! globals, exports, code, and data. Sections the interpreter doesn't
+//! execute (custom, tables, elements) are skipped wholesale by their
+//! declared size, so modules that carry them still decode; the traps
+//! come later, only if execution actually reaches an unsupported
+//! instruction.
+
+use super::WasmError;
+
+/// A value type, per the binary encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValType {
+    I32,
+    I64,
+    F32,
+    F64,
+    /// A 128-bit SIMD vector. Decoded unconditionally; whether the
+    /// interpreter will execute vector instructions is a
+    /// [`super::runtime::WasmFeatures`] decision.
+    V128,
+}
+
+impl ValType {
+    fn decode(byte: u8) -> Result<Self, WasmError> {
+        match byte {
+            0x7F => Ok(Self::I32),
+            0x7E => Ok(Self::I64),
+            0x7D => Ok(Self::F32),
+            0x7C => Ok(Self::F64),
+            0x7B => Ok(Self::V128),
+            other => Err(WasmError::Decode(format!("bad value type {other:#04x}"))),
+        }
+    }
+}
+
+/// A function signature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuncType {
+    pub params: Vec<ValType>,
+    pub results: Vec<ValType>,
+}
+
+/// Memory (or table) size bounds, in 64 KiB pages.
+#[derive(Debug, Clone, Copy)]
+pub struct Limits {
+    pub min: u32,
+    pub max: Option<u32>,
+    /// The threads proposal's shared flag: the memory may be visible
+    /// to more than one agent, and must declare a maximum.
+    pub shared: bool,
+}
+
+/// What an import binds to.
+#[derive(Debug, Clone)]
+pub enum ImportKind {
+    /// A function with this type index.
+    Function(u32),
+    Memory(Limits),
+}
+
+#[derive(Debug, Clone)]
+pub struct Import {
+    pub module: String,
+    pub name: String,
+    pub kind: ImportKind,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum ExportKind {
+    /// Index into the function index space (imports first).
+    Function(u32),
+    Memory(u32),
+}
+
+#[derive(Debug, Clone)]
+pub struct Export {
+    pub name: String,
+    pub kind: ExportKind,
+}
+
+/// A module-defined global: its type, mutability, and decoded constant
+/// initialiser.
+#[derive(Debug, Clone)]
+pub struct Global {
+    pub ty: ValType,
+    pub mutable: bool,
+    pub init: super::runtime::Value,
+}
+
+/// An active data segment, copied into memory at instantiation.
+#[derive(Debug, Clone)]
+pub struct DataSegment {
+    pub offset: u32,
+    pub bytes: Vec<u8>,
+}
+
+/// One function's body: extra locals, then the raw instruction stream
+/// (ending with its `end` opcode), interpreted in place by the runtime.
+#[derive(Debug, Clone)]
+pub struct FunctionBody {
+    pub locals: Vec<ValType>,
+    pub code: Vec<u8>,
+}
+
+/// A decoded module, ready for [`super::WasmRuntime::instantiate`].
+#[derive(Debug, Clone, Default)]
+pub struct Module {
+    pub types: Vec<FuncType>,
+    pub imports: Vec<Import>,
+    /// Type indices of the module-defined functions, in order.
+    pub functions: Vec<u32>,
+    pub memories: Vec<Limits>,
+    pub globals: Vec<Global>,
+    pub exports: Vec<Export>,
+    pub bodies: Vec<FunctionBody>,
+    pub data: Vec<DataSegment>,
+}
+
+impl Module {
+    /// Decode a binary module.
+    pub fn decode(bytes: &[u8]) -> Result<Self, WasmError> {
+        let mut reader = Reader::new(bytes);
+        if reader.take(4)? != b"\0asm" {
+            return Err(WasmError::Decode("missing magic number".into()));
+        }
+        if reader.take(4)? != [1, 0, 0, 0] {
+            return Err(WasmError::Decode("unsupported version".into()));
+        }
+        let mut module = Module::default();
+        while !reader.done() {
+            let id = reader.byte()?;
+            let size = reader.leb_u32()? as usize;
+            module.apply_section(id, reader.take(size)?)?;
+        }
+        module.check_complete()?;
+        Ok(module)
+    }
+
+    /// Decode one section's payload into the module. Shared between
+    /// [`decode`](Self::decode) and [`StreamingCompiler`], which feeds
+    /// sections as they arrive off the wire.
+    fn apply_section(&mut self, id: u8, bytes: &[u8]) -> Result<(), WasmError> {
+        let mut section = Reader::new(bytes);
+        match id {
+            1 => self.types = section.vec(Reader::func_type)?,
+            2 => self.imports = section.vec(Reader::import)?,
+            3 => self.functions = section.vec(Reader::leb_u32)?,
+            5 => self.memories = section.vec(Reader::limits)?,
+            6 => self.globals = section.vec(Reader::global)?,
+            7 => self.exports = section.vec(Reader::export)?,
+            10 => self.bodies = section.vec(Reader::function_body)?,
+            11 => self.data = section.vec(Reader::data_segment)?,
+            // Custom, table, element, start, … — not executed.
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// The cross-section invariant a finished decode must satisfy.
+    fn check_complete(&self) -> Result<(), WasmError> {
+        if self.functions.len() != self.bodies.len() {
+            return Err(WasmError::Decode(
+                "function and code sections disagree".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// How many functions the module imports; module-defined functions
+    /// index after these in the shared function index space.
+    pub fn imported_functions(&self) -> usize {
+        self.imports
+            .iter()
+            .filter(|import| matches!(import.kind, ImportKind::Function(_)))
+            .count()
+    }
+
+    /// The signature of the function at `index` in the function index
+    /// space (imports included).
+    pub fn function_type(&self, index: usize) -> Result<&FuncType, WasmError> {
+        let type_index = if index < self.imported_functions() {
+            let import = self
+                .imports
+                .iter()
+                .filter_map(|import| match import.kind {
+                    ImportKind::Function(ty) => Some(ty),
+                    _ => None,
+                })
+                .nth(index)
+                .ok_or_else(|| WasmError::Decode(format!("no imported function {index}")))?;
+            import as usize
+        } else {
+            *self
+                .functions
+                .get(index - self.imported_functions())
+                .ok_or_else(|| WasmError::Decode(format!("no function {index}")))?
+                as usize
+        };
+        self.types
+            .get(type_index)
+            .ok_or_else(|| WasmError::Decode(format!("no type {type_index}")))
+    }
+}
+
+/// Incremental decoding for modules arriving off the wire.
+///
+/// Push chunks as they land; each complete section is decoded the
+/// moment its last byte arrives, so a multi-megabyte module is mostly
+/// compiled by the time the response finishes. Only the current
+/// partial section is buffered, never the whole body.
+#[derive(Default)]
+pub struct StreamingCompiler {
+    module: Module,
+    /// Bytes of the section still in flight.
+    pending: Vec<u8>,
+    header_checked: bool,
+}
+
+impl StreamingCompiler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed the next chunk, decoding every section it completes.
+    pub fn push(&mut self, chunk: &[u8]) -> Result<(), WasmError> {
+        self.pending.extend_from_slice(chunk);
+        if !self.header_checked {
+            if self.pending.len() < 8 {
+                return Ok(());
+            }
+            if &self.pending[..4] != b"\0asm" {
+                return Err(WasmError::Decode("missing magic number".into()));
+            }
+            if self.pending[4..8] != [1, 0, 0, 0] {
+                return Err(WasmError::Decode("unsupported version".into()));
+            }
+            self.pending.drain(..8);
+            self.header_checked = true;
+        }
+        loop {
+            // A read past the buffered bytes just means the section is
+            // still in flight; real decode errors surface once the
+            // section is whole (or at finish, for a truncated module).
+            let mut header = Reader::new(&self.pending);
+            let Ok(id) = header.byte() else { break };
+            let Ok(size) = header.leb_u32() else { break };
+            let start = header.position;
+            let end = start + size as usize;
+            if end > self.pending.len() {
+                break;
+            }
+            self.module.apply_section(id, &self.pending[start..end])?;
+            self.pending.drain(..end);
+        }
+        Ok(())
+    }
+
+    /// The response is complete; the module must be too.
+    pub fn finish(self) -> Result<Module, WasmError> {
+        if !self.header_checked || !self.pending.is_empty() {
+            return Err(WasmError::Decode("truncated module".into()));
+        }
+        self.module.check_complete()?;
+        Ok(self.module)
+    }
+}
+
+/// A cursor over the binary, with the LEB128 readers the format leans
+/// on.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    fn done(&self) -> bool {
+        self.position >= self.bytes.len()
+    }
+
+    fn byte(&mut self) -> Result<u8, WasmError> {
+        let byte = *self
+            .bytes
+            .get(self.position)
+            .ok_or_else(|| WasmError::Decode("unexpected end of module".into()))?;
+        self.position += 1;
+        Ok(byte)
+    }
+
+    fn take(&mut self, count: usize) -> Result<&'a [u8], WasmError> {
+        let end = self
+            .position
+            .checked_add(count)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or_else(|| WasmError::Decode("unexpected end of module".into()))?;
+        let slice = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(slice)
+    }
+
+    fn leb_u32(&mut self) -> Result<u32, WasmError> {
+        let mut result: u32 = 0;
+        let mut shift = 0;
+        loop {
+            let byte = self.byte()?;
+            result |= u32::from(byte & 0x7F) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(result);
+            }
+            shift += 7;
+            if shift >= 32 {
+                return Err(WasmError::Decode("oversized u32".into()));
+            }
+        }
+    }
+
+    fn leb_i32(&mut self) -> Result<i32, WasmError> {
+        Ok(self.leb_i64()? as i32)
+    }
+
+    fn leb_i64(&mut self) -> Result<i64, WasmError> {
+        let mut result: i64 = 0;
+        let mut shift = 0;
+        loop {
+            let byte = self.byte()?;
+            result |= i64::from(byte & 0x7F) << shift;
+            shift += 7;
+            if byte & 0x80 == 0 {
+                if shift < 64 && byte & 0x40 != 0 {
+                    result |= -1i64 << shift;
+                }
+                return Ok(result);
+            }
+            if shift >= 64 {
+                return Err(WasmError::Decode("oversized i64".into()));
+            }
+        }
+    }
+
+    fn name(&mut self) -> Result<String, WasmError> {
+        let length = self.leb_u32()? as usize;
+        String::from_utf8(self.take(length)?.to_vec())
+            .map_err(|_| WasmError::Decode("name is not UTF-8".into()))
+    }
+
+    fn vec<T>(
+        &mut self,
+        mut item: impl FnMut(&mut Self) -> Result<T, WasmError>,
+    ) -> Result<Vec<T>, WasmError> {
+        let count = self.leb_u32()? as usize;
+        let mut items = Vec::with_capacity(count.min(1024));
+        for _ in 0..count {
+            items.push(item(self)?);
+        }
+        Ok(items)
+    }
+
+    fn func_type(&mut self) -> Result<FuncType, WasmError> {
+        if self.byte()? != 0x60 {
+            return Err(WasmError::Decode("bad function type tag".into()));
+        }
+        let params = self.vec(|r| ValType::decode(r.byte()?))?;
+        let results = self.vec(|r| ValType::decode(r.byte()?))?;
+        Ok(FuncType { params, results })
+    }
+
+    fn limits(&mut self) -> Result<Limits, WasmError> {
+        let flag = self.byte()?;
+        match flag {
+            0x00 => Ok(Limits {
+                min: self.leb_u32()?,
+                max: None,
+                shared: false,
+            }),
+            // 0x03 is the shared flag (threads proposal); bounds decode
+            // the same way.
+            0x01 | 0x03 => Ok(Limits {
+                min: self.leb_u32()?,
+                max: Some(self.leb_u32()?),
+                shared: flag == 0x03,
+            }),
+            other => Err(WasmError::Decode(format!("bad limits flag {other:#04x}"))),
+        }
+    }
+
+    fn import(&mut self) -> Result<Import, WasmError> {
+        let module = self.name()?;
+        let name = self.name()?;
+        let kind = match self.byte()? {
+            0x00 => ImportKind::Function(self.leb_u32()?),
+            0x02 => ImportKind::Memory(self.limits()?),
+            other => {
+                return Err(WasmError::Decode(format!(
+                    "unsupported import kind {other:#04x}"
+                )))
+            }
+        };
+        Ok(Import { module, name, kind })
+    }
+
+    fn global(&mut self) -> Result<Global, WasmError> {
+        let ty = ValType::decode(self.byte()?)?;
+        let mutable = self.byte()? == 0x01;
+        let init = self.const_expr()?;
+        Ok(Global { ty, mutable, init })
+    }
+
+    fn export(&mut self) -> Result<Export, WasmError> {
+        let name = self.name()?;
+        let kind = match self.byte()? {
+            0x00 => ExportKind::Function(self.leb_u32()?),
+            0x02 => ExportKind::Memory(self.leb_u32()?),
+            other => {
+                return Err(WasmError::Decode(format!(
+                    "unsupported export kind {other:#04x}"
+                )))
+            }
+        };
+        Ok(Export { name, kind })
+    }
+
+    fn function_body(&mut self) -> Result<FunctionBody, WasmError> {
+        let size = self.leb_u32()? as usize;
+        let mut body = Reader::new(self.take(size)?);
+        let mut locals = Vec::new();
+        for _ in 0..body.leb_u32()? {
+            let count = body.leb_u32()? as usize;
+            let ty = ValType::decode(body.byte()?)?;
+            locals.extend(std::iter::repeat(ty).take(count));
+        }
+        let code = body.bytes[body.position..].to_vec();
+        Ok(FunctionBody { locals, code })
+    }
+
+    fn data_segment(&mut self) -> Result<DataSegment, WasmError> {
+        match self.leb_u32()? {
+            // Active segment into memory 0.
+            0 => {}
+            // Active segment with an explicit memory index.
+            2 => {
+                self.leb_u32()?;
+            }
+            // Passive segments are for bulk-memory ops we don't run.
+            other => {
+                return Err(WasmError::Decode(format!(
+                    "unsupported data segment kind {other}"
+                )))
+            }
+        }
+        let offset = match self.const_expr()? {
+            super::runtime::Value::I32(offset) => offset as u32,
+            _ => return Err(WasmError::Decode("data offset must be i32".into())),
+        };
+        let length = self.leb_u32()? as usize;
+        Ok(DataSegment {
+            offset,
+            bytes: self.take(length)?.to_vec(),
+        })
+    }
+
+    /// A constant initialiser expression: one `*.const`, then `end`.
+    fn const_expr(&mut self) -> Result<super::runtime::Value, WasmError> {
+        use super::runtime::Value;
+        let value = match self.byte()? {
+            0x41 => Value::I32(self.leb_i32()?),
+            0x42 => Value::I64(self.leb_i64()?),
+            0x43 => Value::F32(f32::from_le_bytes(self.take(4)?.try_into().unwrap())),
+            0x44 => Value::F64(f64::from_le_bytes(self.take(8)?.try_into().unwrap())),
+            other => {
+                return Err(WasmError::Decode(format!(
+                    "unsupported constant expression {other:#04x}"
+                )))
+            }
+        };
+        if self.byte()? != 0x0B {
+            return Err(WasmError::Decode("constant expression not terminated".into()));
+        }
+        Ok(value)
+    }
+}
@@ -0,0 +1,1950 @@
+//! Instantiating and interpreting decoded modules.
+//!
+//! [`WasmRuntime`] turns a [`Module`] into an [`Instance`]: memory
+//! allocated and seeded from the data segments, globals initialised,
+//! exports callable through [`Instance::invoke_export`]. Execution is a
+//! straight interpreter over the binary instruction stream — no
+//! compilation tier — with structured control handled by scanning for
+//! the matching `end` when a block is entered. Imported functions reach
+//! the embedder through the host callback every invocation takes, which
+//! is how the JS bindings marshal calls back into script.
+
+use std::sync::{Arc, Mutex};
+
+use super::module::{ExportKind, FuncType, ImportKind, Limits, Module, ValType};
+use super::WasmError;
+
+/// A WebAssembly value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Value {
+    I32(i32),
+    I64(i64),
+    F32(f32),
+    F64(f64),
+    /// A 128-bit vector, stored as its little-endian lane bytes.
+    V128(u128),
+}
+
+impl Value {
+    pub fn ty(&self) -> ValType {
+        match self {
+            Self::I32(_) => ValType::I32,
+            Self::I64(_) => ValType::I64,
+            Self::F32(_) => ValType::F32,
+            Self::F64(_) => ValType::F64,
+            Self::V128(_) => ValType::V128,
+        }
+    }
+
+    /// The type's zero value, used for declared locals.
+    pub fn zero(ty: ValType) -> Self {
+        match ty {
+            ValType::I32 => Self::I32(0),
+            ValType::I64 => Self::I64(0),
+            ValType::F32 => Self::F32(0.0),
+            ValType::F64 => Self::F64(0.0),
+            ValType::V128 => Self::V128(0),
+        }
+    }
+}
+
+/// Optional proposals the interpreter will execute.
+///
+/// SIMD ships enabled — the switch exists so embedders can turn it off
+/// explicitly (and so the conformance tests can check that disabling it
+/// traps rather than silently misexecuting vector code). Threads stay
+/// off by default: the JS embedder enables them only for cross-origin
+/// isolated pages, matching where browsers allow `SharedArrayBuffer`.
+#[derive(Debug, Clone, Copy)]
+pub struct WasmFeatures {
+    pub simd: bool,
+    /// Shared memories and the `0xFE` atomic instructions.
+    pub threads: bool,
+}
+
+impl Default for WasmFeatures {
+    fn default() -> Self {
+        Self {
+            simd: true,
+            threads: false,
+        }
+    }
+}
+
+/// The embedder's side of imported functions: called with the import's
+/// position in the function index space and the marshalled arguments.
+pub type HostFunction<'a> =
+    &'a mut dyn FnMut(usize, &[Value]) -> Result<Vec<Value>, WasmError>;
+
+/// Linear memory, in 64 KiB pages.
+pub struct Memory {
+    store: Store,
+    max_pages: Option<u32>,
+}
+
+/// The backing bytes: local memory is a plain buffer, shared memory
+/// (threads proposal) sits behind a lock so workers can hold the same
+/// pages.
+enum Store {
+    Local(Vec<u8>),
+    Shared(Arc<Mutex<Vec<u8>>>),
+}
+
+/// Bytes per WebAssembly page.
+pub const PAGE_SIZE: usize = 65536;
+
+impl Memory {
+    pub fn new(limits: Limits) -> Self {
+        let data = vec![0; limits.min as usize * PAGE_SIZE];
+        let store = if limits.shared {
+            Store::Shared(Arc::new(Mutex::new(data)))
+        } else {
+            Store::Local(data)
+        };
+        Self {
+            store,
+            max_pages: limits.max,
+        }
+    }
+
+    fn with<R>(&self, f: impl FnOnce(&[u8]) -> R) -> R {
+        match &self.store {
+            Store::Local(data) => f(data),
+            Store::Shared(data) => f(&data.lock().expect("wasm memory lock")),
+        }
+    }
+
+    fn with_mut<R>(&mut self, f: impl FnOnce(&mut Vec<u8>) -> R) -> R {
+        match &mut self.store {
+            Store::Local(data) => f(data),
+            Store::Shared(data) => f(&mut data.lock().expect("wasm memory lock")),
+        }
+    }
+
+    pub fn size_pages(&self) -> u32 {
+        self.with(|data| (data.len() / PAGE_SIZE) as u32)
+    }
+
+    pub fn is_shared(&self) -> bool {
+        matches!(self.store, Store::Shared(_))
+    }
+
+    /// The shared backing, for embedders bridging the same pages into
+    /// `SharedArrayBuffer` views or worker instances.
+    pub fn shared_handle(&self) -> Option<Arc<Mutex<Vec<u8>>>> {
+        match &self.store {
+            Store::Shared(data) => Some(Arc::clone(data)),
+            Store::Local(_) => None,
+        }
+    }
+
+    /// A copy of the current contents.
+    pub fn snapshot(&self) -> Vec<u8> {
+        self.with(<[u8]>::to_vec)
+    }
+
+    /// Grow by `delta` pages, returning the old size, or -1 when the
+    /// limit (or an allocation cap) refuses it, per the spec.
+    pub fn grow(&mut self, delta: u32) -> i32 {
+        let max_pages = self.max_pages;
+        self.with_mut(|data| {
+            let old = (data.len() / PAGE_SIZE) as u32;
+            let new = match old.checked_add(delta) {
+                Some(new) => new,
+                None => return -1,
+            };
+            if max_pages.is_some_and(|max| new > max) || new > 65536 {
+                return -1;
+            }
+            data.resize(new as usize * PAGE_SIZE, 0);
+            old as i32
+        })
+    }
+
+    fn load(&self, address: u32, offset: u32, width: usize) -> Result<Vec<u8>, WasmError> {
+        let start = address as usize + offset as usize;
+        self.with(|data| data.get(start..start + width).map(<[u8]>::to_vec))
+            .ok_or_else(|| WasmError::Trap("out of bounds memory access".into()))
+    }
+
+    fn store(&mut self, address: u32, offset: u32, bytes: &[u8]) -> Result<(), WasmError> {
+        let start = address as usize + offset as usize;
+        self.with_mut(|data| match data.get_mut(start..start + bytes.len()) {
+            Some(slot) => {
+                slot.copy_from_slice(bytes);
+                Ok(())
+            }
+            None => Err(WasmError::Trap("out of bounds memory access".into())),
+        })
+    }
+
+    /// Read-modify-write under a single lock acquisition, returning the
+    /// old bytes — the atomicity the `0xFE` instructions need.
+    fn update(
+        &mut self,
+        address: u32,
+        offset: u32,
+        width: usize,
+        f: impl FnOnce(&[u8]) -> Vec<u8>,
+    ) -> Result<Vec<u8>, WasmError> {
+        let start = address as usize + offset as usize;
+        self.with_mut(|data| match data.get_mut(start..start + width) {
+            Some(slot) => {
+                let old = slot.to_vec();
+                let new = f(&old);
+                slot.copy_from_slice(&new);
+                Ok(old)
+            }
+            None => Err(WasmError::Trap("out of bounds memory access".into())),
+        })
+    }
+}
+
+/// Compile/instantiate entry points, mirroring the JS API's shape.
+pub struct WasmRuntime;
+
+impl WasmRuntime {
+    /// Decode a binary module.
+    pub fn compile(bytes: &[u8]) -> Result<Module, WasmError> {
+        Module::decode(bytes)
+    }
+
+    /// Whether `bytes` decode as a module.
+    pub fn validate(bytes: &[u8]) -> bool {
+        Module::decode(bytes).is_ok()
+    }
+
+    /// Begin an incremental compile, for callers feeding bytes as they
+    /// arrive instead of buffering the whole module first.
+    pub fn compile_streaming() -> super::StreamingCompiler {
+        super::StreamingCompiler::new()
+    }
+
+    /// Instantiate `module` with the default feature set.
+    pub fn instantiate(module: Module) -> Result<Instance, WasmError> {
+        Self::instantiate_with(module, WasmFeatures::default())
+    }
+
+    /// Instantiate `module`: allocate and seed memory, initialise
+    /// globals. Imported functions bind at call time through the host
+    /// callback, so instantiation itself never re-enters the embedder.
+    /// `features` picks which optional proposals execution will accept.
+    pub fn instantiate_with(module: Module, features: WasmFeatures) -> Result<Instance, WasmError> {
+        let limits = module.memories.first().copied().or_else(|| {
+            module.imports.iter().find_map(|import| match import.kind {
+                ImportKind::Memory(limits) => Some(limits),
+                _ => None,
+            })
+        });
+        if limits.is_some_and(|limits| limits.shared) && !features.threads {
+            return Err(WasmError::Instantiation(
+                "shared memory requires the threads feature".into(),
+            ));
+        }
+        let mut memory = limits.map(Memory::new);
+        if let Some(memory) = memory.as_mut() {
+            for segment in &module.data {
+                memory
+                    .store(segment.offset, 0, &segment.bytes)
+                    .map_err(|_| {
+                        WasmError::Instantiation("data segment out of bounds".into())
+                    })?;
+            }
+        } else if !module.data.is_empty() {
+            return Err(WasmError::Instantiation(
+                "data segment without a memory".into(),
+            ));
+        }
+        let globals = module.globals.iter().map(|global| global.init).collect();
+        Ok(Instance {
+            module,
+            memory,
+            globals,
+            features,
+        })
+    }
+}
+
+/// An instantiated module: its memory, globals, and callable exports.
+pub struct Instance {
+    module: Module,
+    memory: Option<Memory>,
+    globals: Vec<Value>,
+    features: WasmFeatures,
+}
+
+/// One entered block during execution.
+struct ControlFrame {
+    is_loop: bool,
+    /// First instruction inside the block (branch target for loops).
+    start: usize,
+    /// Position of the matching `end` opcode.
+    end: usize,
+    /// Value stack height at entry; branches unwind to here.
+    stack_height: usize,
+    /// Result values a branch out of the block carries.
+    arity: usize,
+}
+
+impl Instance {
+    pub fn module(&self) -> &Module {
+        &self.module
+    }
+
+    pub fn memory(&self) -> Option<&Memory> {
+        self.memory.as_ref()
+    }
+
+    pub fn memory_mut(&mut self) -> Option<&mut Memory> {
+        self.memory.as_mut()
+    }
+
+    /// Call the exported function `name`. Arguments are type-checked
+    /// against the signature; `host` receives calls to imported
+    /// functions.
+    pub fn invoke_export(
+        &mut self,
+        name: &str,
+        args: &[Value],
+        host: HostFunction<'_>,
+    ) -> Result<Vec<Value>, WasmError> {
+        let index = self
+            .module
+            .exports
+            .iter()
+            .find_map(|export| match export.kind {
+                ExportKind::Function(index) if export.name == name => Some(index as usize),
+                _ => None,
+            })
+            .ok_or_else(|| WasmError::UnknownExport(name.to_owned()))?;
+        self.call_function(index, args, host)
+    }
+
+    /// Call the function at `index` in the function index space —
+    /// imports included, which is also how export wrappers address it.
+    pub fn call_function(
+        &mut self,
+        index: usize,
+        args: &[Value],
+        host: HostFunction<'_>,
+    ) -> Result<Vec<Value>, WasmError> {
+        let imported = self.module.imported_functions();
+        if index < imported {
+            return host(index, args);
+        }
+        let ty = self.module.function_type(index)?.clone();
+        if args.len() != ty.params.len()
+            || args.iter().zip(&ty.params).any(|(arg, ty)| arg.ty() != *ty)
+        {
+            return Err(WasmError::Trap("argument type mismatch".into()));
+        }
+        let body = self.module.bodies[index - imported].clone();
+        let mut locals: Vec<Value> = args.to_vec();
+        locals.extend(body.locals.iter().map(|&ty| Value::zero(ty)));
+        self.run(&body.code, &ty, &mut locals, host)
+    }
+
+    /// Interpret one function body to completion.
+    #[allow(clippy::too_many_lines)]
+    fn run(
+        &mut self,
+        code: &[u8],
+        ty: &FuncType,
+        locals: &mut [Value],
+        host: HostFunction<'_>,
+    ) -> Result<Vec<Value>, WasmError> {
+        let mut stack: Vec<Value> = Vec::new();
+        // The function body is itself a block ending at the trailing
+        // `end`; branching to it is `return`.
+        let mut control: Vec<ControlFrame> = vec![ControlFrame {
+            is_loop: false,
+            start: 0,
+            end: code.len().saturating_sub(1),
+            stack_height: 0,
+            arity: ty.results.len(),
+        }];
+        let mut pc = 0usize;
+
+        macro_rules! trap {
+            ($message:expr) => {
+                return Err(WasmError::Trap($message.into()))
+            };
+        }
+
+        while pc < code.len() {
+            let op = code[pc];
+            pc += 1;
+            match op {
+                0x00 => trap!("unreachable executed"),
+                0x01 => {}
+                // block / loop / if
+                0x02 | 0x03 | 0x04 => {
+                    let arity = block_arity(code, &mut pc)?;
+                    let (else_at, end) = block_bounds(code, pc)?;
+                    let mut entered = true;
+                    if op == 0x04 {
+                        let condition = pop_i32(&mut stack)?;
+                        if condition == 0 {
+                            match else_at {
+                                Some(else_at) => pc = else_at + 1,
+                                None => {
+                                    pc = end + 1;
+                                    entered = false;
+                                }
+                            }
+                        }
+                    }
+                    if entered {
+                        control.push(ControlFrame {
+                            is_loop: op == 0x03,
+                            start: pc,
+                            end,
+                            stack_height: stack.len(),
+                            arity,
+                        });
+                    }
+                }
+                // else: the then-branch finished; skip to after `end`.
+                0x05 => {
+                    let frame = control
+                        .pop()
+                        .ok_or_else(|| WasmError::Trap("else without a block".into()))?;
+                    pc = frame.end + 1;
+                }
+                0x0B => {
+                    control.pop();
+                    if control.is_empty() {
+                        break;
+                    }
+                }
+                0x0C => {
+                    let depth = leb_u32(code, &mut pc)? as usize;
+                    branch(&mut control, &mut stack, &mut pc, depth)?;
+                }
+                0x0D => {
+                    let depth = leb_u32(code, &mut pc)? as usize;
+                    if pop_i32(&mut stack)? != 0 {
+                        branch(&mut control, &mut stack, &mut pc, depth)?;
+                    }
+                }
+                0x0E => {
+                    let count = leb_u32(code, &mut pc)? as usize;
+                    let mut targets = Vec::with_capacity(count);
+                    for _ in 0..count {
+                        targets.push(leb_u32(code, &mut pc)? as usize);
+                    }
+                    let default = leb_u32(code, &mut pc)? as usize;
+                    let index = pop_i32(&mut stack)? as usize;
+                    let depth = targets.get(index).copied().unwrap_or(default);
+                    branch(&mut control, &mut stack, &mut pc, depth)?;
+                }
+                0x0F => break,
+                0x10 => {
+                    let index = leb_u32(code, &mut pc)? as usize;
+                    let callee = self.module.function_type(index)?.clone();
+                    let at = stack.len().saturating_sub(callee.params.len());
+                    let args: Vec<Value> = stack.split_off(at);
+                    let results = self.call_function(index, &args, host)?;
+                    stack.extend(results);
+                }
+                0x11 => return Err(WasmError::UnsupportedOpcode(0x11)),
+                0x1A => {
+                    pop(&mut stack)?;
+                }
+                0x1B => {
+                    let condition = pop_i32(&mut stack)?;
+                    let b = pop(&mut stack)?;
+                    let a = pop(&mut stack)?;
+                    stack.push(if condition != 0 { a } else { b });
+                }
+                0x20 => {
+                    let index = leb_u32(code, &mut pc)? as usize;
+                    stack.push(local(locals, index)?);
+                }
+                0x21 => {
+                    let index = leb_u32(code, &mut pc)? as usize;
+                    let value = pop(&mut stack)?;
+                    *locals
+                        .get_mut(index)
+                        .ok_or_else(|| WasmError::Trap("bad local index".into()))? = value;
+                }
+                0x22 => {
+                    let index = leb_u32(code, &mut pc)? as usize;
+                    let value = *stack
+                        .last()
+                        .ok_or_else(|| WasmError::Trap("value stack underflow".into()))?;
+                    *locals
+                        .get_mut(index)
+                        .ok_or_else(|| WasmError::Trap("bad local index".into()))? = value;
+                }
+                0x23 => {
+                    let index = leb_u32(code, &mut pc)? as usize;
+                    let value = *self
+                        .globals
+                        .get(index)
+                        .ok_or_else(|| WasmError::Trap("bad global index".into()))?;
+                    stack.push(value);
+                }
+                0x24 => {
+                    let index = leb_u32(code, &mut pc)? as usize;
+                    let value = pop(&mut stack)?;
+                    *self
+                        .globals
+                        .get_mut(index)
+                        .ok_or_else(|| WasmError::Trap("bad global index".into()))? = value;
+                }
+                // Loads.
+                0x28..=0x35 => {
+                    let (_, offset) = memarg(code, &mut pc)?;
+                    let address = pop_i32(&mut stack)? as u32;
+                    let memory = self
+                        .memory
+                        .as_ref()
+                        .ok_or_else(|| WasmError::Trap("no memory".into()))?;
+                    let value = match op {
+                        0x28 => Value::I32(i32::from_le_bytes(
+                            memory.load(address, offset, 4)?.try_into().unwrap(),
+                        )),
+                        0x29 => Value::I64(i64::from_le_bytes(
+                            memory.load(address, offset, 8)?.try_into().unwrap(),
+                        )),
+                        0x2A => Value::F32(f32::from_le_bytes(
+                            memory.load(address, offset, 4)?.try_into().unwrap(),
+                        )),
+                        0x2B => Value::F64(f64::from_le_bytes(
+                            memory.load(address, offset, 8)?.try_into().unwrap(),
+                        )),
+                        0x2C => Value::I32(memory.load(address, offset, 1)?[0] as i8 as i32),
+                        0x2D => Value::I32(memory.load(address, offset, 1)?[0] as i32),
+                        0x2E => Value::I32(i16::from_le_bytes(
+                            memory.load(address, offset, 2)?.try_into().unwrap(),
+                        ) as i32),
+                        0x2F => Value::I32(u16::from_le_bytes(
+                            memory.load(address, offset, 2)?.try_into().unwrap(),
+                        ) as i32),
+                        0x30 => Value::I64(memory.load(address, offset, 1)?[0] as i8 as i64),
+                        0x31 => Value::I64(memory.load(address, offset, 1)?[0] as i64),
+                        0x32 => Value::I64(i16::from_le_bytes(
+                            memory.load(address, offset, 2)?.try_into().unwrap(),
+                        ) as i64),
+                        0x33 => Value::I64(u16::from_le_bytes(
+                            memory.load(address, offset, 2)?.try_into().unwrap(),
+                        ) as i64),
+                        0x34 => Value::I64(i32::from_le_bytes(
+                            memory.load(address, offset, 4)?.try_into().unwrap(),
+                        ) as i64),
+                        _ => Value::I64(u32::from_le_bytes(
+                            memory.load(address, offset, 4)?.try_into().unwrap(),
+                        ) as i64),
+                    };
+                    stack.push(value);
+                }
+                // Stores.
+                0x36..=0x3E => {
+                    let (_, offset) = memarg(code, &mut pc)?;
+                    let value = pop(&mut stack)?;
+                    let address = pop_i32(&mut stack)? as u32;
+                    let memory = self
+                        .memory
+                        .as_mut()
+                        .ok_or_else(|| WasmError::Trap("no memory".into()))?;
+                    match (op, value) {
+                        (0x36, Value::I32(v)) => memory.store(address, offset, &v.to_le_bytes())?,
+                        (0x37, Value::I64(v)) => memory.store(address, offset, &v.to_le_bytes())?,
+                        (0x38, Value::F32(v)) => memory.store(address, offset, &v.to_le_bytes())?,
+                        (0x39, Value::F64(v)) => memory.store(address, offset, &v.to_le_bytes())?,
+                        (0x3A, Value::I32(v)) => {
+                            memory.store(address, offset, &[v as u8])?;
+                        }
+                        (0x3B, Value::I32(v)) => {
+                            memory.store(address, offset, &(v as u16).to_le_bytes())?;
+                        }
+                        (0x3C, Value::I64(v)) => {
+                            memory.store(address, offset, &[v as u8])?;
+                        }
+                        (0x3D, Value::I64(v)) => {
+                            memory.store(address, offset, &(v as u16).to_le_bytes())?;
+                        }
+                        (0x3E, Value::I64(v)) => {
+                            memory.store(address, offset, &(v as u32).to_le_bytes())?;
+                        }
+                        _ => trap!("store value type mismatch"),
+                    }
+                }
+                0x3F => {
+                    pc += 1; // memory index, always 0
+                    let pages = self
+                        .memory
+                        .as_ref()
+                        .map_or(0, Memory::size_pages);
+                    stack.push(Value::I32(pages as i32));
+                }
+                0x40 => {
+                    pc += 1; // memory index, always 0
+                    let delta = pop_i32(&mut stack)? as u32;
+                    let result = self
+                        .memory
+                        .as_mut()
+                        .map_or(-1, |memory| memory.grow(delta));
+                    stack.push(Value::I32(result));
+                }
+                0x41 => stack.push(Value::I32(leb_i32(code, &mut pc)?)),
+                0x42 => stack.push(Value::I64(leb_i64(code, &mut pc)?)),
+                0x43 => {
+                    stack.push(Value::F32(f32::from_le_bytes(raw(code, &mut pc)?)));
+                }
+                0x44 => {
+                    stack.push(Value::F64(f64::from_le_bytes(raw(code, &mut pc)?)));
+                }
+                0x45 => {
+                    let a = pop_i32(&mut stack)?;
+                    stack.push(Value::I32((a == 0) as i32));
+                }
+                0x46..=0x4F => {
+                    let b = pop_i32(&mut stack)?;
+                    let a = pop_i32(&mut stack)?;
+                    let result = match op {
+                        0x46 => a == b,
+                        0x47 => a != b,
+                        0x48 => a < b,
+                        0x49 => (a as u32) < b as u32,
+                        0x4A => a > b,
+                        0x4B => a as u32 > b as u32,
+                        0x4C => a <= b,
+                        0x4D => a as u32 <= b as u32,
+                        0x4E => a >= b,
+                        _ => a as u32 >= b as u32,
+                    };
+                    stack.push(Value::I32(result as i32));
+                }
+                0x50 => {
+                    let a = pop_i64(&mut stack)?;
+                    stack.push(Value::I32((a == 0) as i32));
+                }
+                0x51..=0x5A => {
+                    let b = pop_i64(&mut stack)?;
+                    let a = pop_i64(&mut stack)?;
+                    let result = match op {
+                        0x51 => a == b,
+                        0x52 => a != b,
+                        0x53 => a < b,
+                        0x54 => (a as u64) < b as u64,
+                        0x55 => a > b,
+                        0x56 => a as u64 > b as u64,
+                        0x57 => a <= b,
+                        0x58 => a as u64 <= b as u64,
+                        0x59 => a >= b,
+                        _ => a as u64 >= b as u64,
+                    };
+                    stack.push(Value::I32(result as i32));
+                }
+                0x5B..=0x60 => {
+                    let b = pop_f32(&mut stack)?;
+                    let a = pop_f32(&mut stack)?;
+                    let result = match op {
+                        0x5B => a == b,
+                        0x5C => a != b,
+                        0x5D => a < b,
+                        0x5E => a > b,
+                        0x5F => a <= b,
+                        _ => a >= b,
+                    };
+                    stack.push(Value::I32(result as i32));
+                }
+                0x61..=0x66 => {
+                    let b = pop_f64(&mut stack)?;
+                    let a = pop_f64(&mut stack)?;
+                    let result = match op {
+                        0x61 => a == b,
+                        0x62 => a != b,
+                        0x63 => a < b,
+                        0x64 => a > b,
+                        0x65 => a <= b,
+                        _ => a >= b,
+                    };
+                    stack.push(Value::I32(result as i32));
+                }
+                0x67 => unary_i32(&mut stack, |a| a.leading_zeros() as i32)?,
+                0x68 => unary_i32(&mut stack, |a| a.trailing_zeros() as i32)?,
+                0x69 => unary_i32(&mut stack, |a| a.count_ones() as i32)?,
+                0x6A => binary_i32(&mut stack, i32::wrapping_add)?,
+                0x6B => binary_i32(&mut stack, i32::wrapping_sub)?,
+                0x6C => binary_i32(&mut stack, i32::wrapping_mul)?,
+                0x6D => {
+                    let b = pop_i32(&mut stack)?;
+                    let a = pop_i32(&mut stack)?;
+                    let result = a
+                        .checked_div(b)
+                        .ok_or_else(|| WasmError::Trap("integer division error".into()))?;
+                    stack.push(Value::I32(result));
+                }
+                0x6E => {
+                    let b = pop_i32(&mut stack)? as u32;
+                    let a = pop_i32(&mut stack)? as u32;
+                    let result = a
+                        .checked_div(b)
+                        .ok_or_else(|| WasmError::Trap("integer division error".into()))?;
+                    stack.push(Value::I32(result as i32));
+                }
+                0x6F => {
+                    let b = pop_i32(&mut stack)?;
+                    let a = pop_i32(&mut stack)?;
+                    if b == 0 {
+                        trap!("integer division error");
+                    }
+                    stack.push(Value::I32(a.wrapping_rem(b)));
+                }
+                0x70 => {
+                    let b = pop_i32(&mut stack)? as u32;
+                    let a = pop_i32(&mut stack)? as u32;
+                    if b == 0 {
+                        trap!("integer division error");
+                    }
+                    stack.push(Value::I32((a % b) as i32));
+                }
+                0x71 => binary_i32(&mut stack, |a, b| a & b)?,
+                0x72 => binary_i32(&mut stack, |a, b| a | b)?,
+                0x73 => binary_i32(&mut stack, |a, b| a ^ b)?,
+                0x74 => binary_i32(&mut stack, |a, b| a.wrapping_shl(b as u32))?,
+                0x75 => binary_i32(&mut stack, |a, b| a.wrapping_shr(b as u32))?,
+                0x76 => binary_i32(&mut stack, |a, b| {
+                    ((a as u32).wrapping_shr(b as u32)) as i32
+                })?,
+                0x77 => binary_i32(&mut stack, |a, b| a.rotate_left(b as u32 % 32))?,
+                0x78 => binary_i32(&mut stack, |a, b| a.rotate_right(b as u32 % 32))?,
+                0x79 => unary_i64(&mut stack, |a| a.leading_zeros() as i64)?,
+                0x7A => unary_i64(&mut stack, |a| a.trailing_zeros() as i64)?,
+                0x7B => unary_i64(&mut stack, |a| a.count_ones() as i64)?,
+                0x7C => binary_i64(&mut stack, i64::wrapping_add)?,
+                0x7D => binary_i64(&mut stack, i64::wrapping_sub)?,
+                0x7E => binary_i64(&mut stack, i64::wrapping_mul)?,
+                0x7F => {
+                    let b = pop_i64(&mut stack)?;
+                    let a = pop_i64(&mut stack)?;
+                    let result = a
+                        .checked_div(b)
+                        .ok_or_else(|| WasmError::Trap("integer division error".into()))?;
+                    stack.push(Value::I64(result));
+                }
+                0x80 => {
+                    let b = pop_i64(&mut stack)? as u64;
+                    let a = pop_i64(&mut stack)? as u64;
+                    let result = a
+                        .checked_div(b)
+                        .ok_or_else(|| WasmError::Trap("integer division error".into()))?;
+                    stack.push(Value::I64(result as i64));
+                }
+                0x81 => {
+                    let b = pop_i64(&mut stack)?;
+                    let a = pop_i64(&mut stack)?;
+                    if b == 0 {
+                        trap!("integer division error");
+                    }
+                    stack.push(Value::I64(a.wrapping_rem(b)));
+                }
+                0x82 => {
+                    let b = pop_i64(&mut stack)? as u64;
+                    let a = pop_i64(&mut stack)? as u64;
+                    if b == 0 {
+                        trap!("integer division error");
+                    }
+                    stack.push(Value::I64((a % b) as i64));
+                }
+                0x83 => binary_i64(&mut stack, |a, b| a & b)?,
+                0x84 => binary_i64(&mut stack, |a, b| a | b)?,
+                0x85 => binary_i64(&mut stack, |a, b| a ^ b)?,
+                0x86 => binary_i64(&mut stack, |a, b| a.wrapping_shl(b as u32))?,
+                0x87 => binary_i64(&mut stack, |a, b| a.wrapping_shr(b as u32))?,
+                0x88 => binary_i64(&mut stack, |a, b| {
+                    ((a as u64).wrapping_shr(b as u32)) as i64
+                })?,
+                0x89 => binary_i64(&mut stack, |a, b| a.rotate_left(b as u32 % 64))?,
+                0x8A => binary_i64(&mut stack, |a, b| a.rotate_right(b as u32 % 64))?,
+                0x8B => unary_f32(&mut stack, f32::abs)?,
+                0x8C => unary_f32(&mut stack, |a| -a)?,
+                0x8D => unary_f32(&mut stack, f32::ceil)?,
+                0x8E => unary_f32(&mut stack, f32::floor)?,
+                0x8F => unary_f32(&mut stack, f32::trunc)?,
+                0x90 => unary_f32(&mut stack, nearest_f32)?,
+                0x91 => unary_f32(&mut stack, f32::sqrt)?,
+                0x92 => binary_f32(&mut stack, |a, b| a + b)?,
+                0x93 => binary_f32(&mut stack, |a, b| a - b)?,
+                0x94 => binary_f32(&mut stack, |a, b| a * b)?,
+                0x95 => binary_f32(&mut stack, |a, b| a / b)?,
+                0x96 => binary_f32(&mut stack, f32::min)?,
+                0x97 => binary_f32(&mut stack, f32::max)?,
+                0x98 => binary_f32(&mut stack, f32::copysign)?,
+                0x99 => unary_f64(&mut stack, f64::abs)?,
+                0x9A => unary_f64(&mut stack, |a| -a)?,
+                0x9B => unary_f64(&mut stack, f64::ceil)?,
+                0x9C => unary_f64(&mut stack, f64::floor)?,
+                0x9D => unary_f64(&mut stack, f64::trunc)?,
+                0x9E => unary_f64(&mut stack, nearest_f64)?,
+                0x9F => unary_f64(&mut stack, f64::sqrt)?,
+                0xA0 => binary_f64(&mut stack, |a, b| a + b)?,
+                0xA1 => binary_f64(&mut stack, |a, b| a - b)?,
+                0xA2 => binary_f64(&mut stack, |a, b| a * b)?,
+                0xA3 => binary_f64(&mut stack, |a, b| a / b)?,
+                0xA4 => binary_f64(&mut stack, f64::min)?,
+                0xA5 => binary_f64(&mut stack, f64::max)?,
+                0xA6 => binary_f64(&mut stack, f64::copysign)?,
+                0xA7 => {
+                    let a = pop_i64(&mut stack)?;
+                    stack.push(Value::I32(a as i32));
+                }
+                0xA8 => {
+                    let a = pop_f32(&mut stack)?;
+                    stack.push(Value::I32(trunc_to_i32(f64::from(a))?));
+                }
+                0xA9 => {
+                    let a = pop_f32(&mut stack)?;
+                    stack.push(Value::I32(trunc_to_u32(f64::from(a))? as i32));
+                }
+                0xAA => {
+                    let a = pop_f64(&mut stack)?;
+                    stack.push(Value::I32(trunc_to_i32(a)?));
+                }
+                0xAB => {
+                    let a = pop_f64(&mut stack)?;
+                    stack.push(Value::I32(trunc_to_u32(a)? as i32));
+                }
+                0xAC => {
+                    let a = pop_i32(&mut stack)?;
+                    stack.push(Value::I64(i64::from(a)));
+                }
+                0xAD => {
+                    let a = pop_i32(&mut stack)?;
+                    stack.push(Value::I64(i64::from(a as u32)));
+                }
+                0xAE => {
+                    let a = pop_f32(&mut stack)?;
+                    stack.push(Value::I64(trunc_to_i64(f64::from(a))?));
+                }
+                0xAF => {
+                    let a = pop_f32(&mut stack)?;
+                    stack.push(Value::I64(trunc_to_u64(f64::from(a))? as i64));
+                }
+                0xB0 => {
+                    let a = pop_f64(&mut stack)?;
+                    stack.push(Value::I64(trunc_to_i64(a)?));
+                }
+                0xB1 => {
+                    let a = pop_f64(&mut stack)?;
+                    stack.push(Value::I64(trunc_to_u64(a)? as i64));
+                }
+                0xB2 => {
+                    let a = pop_i32(&mut stack)?;
+                    stack.push(Value::F32(a as f32));
+                }
+                0xB3 => {
+                    let a = pop_i32(&mut stack)?;
+                    stack.push(Value::F32(a as u32 as f32));
+                }
+                0xB4 => {
+                    let a = pop_i64(&mut stack)?;
+                    stack.push(Value::F32(a as f32));
+                }
+                0xB5 => {
+                    let a = pop_i64(&mut stack)?;
+                    stack.push(Value::F32(a as u64 as f32));
+                }
+                0xB6 => {
+                    let a = pop_f64(&mut stack)?;
+                    stack.push(Value::F32(a as f32));
+                }
+                0xB7 => {
+                    let a = pop_i32(&mut stack)?;
+                    stack.push(Value::F64(f64::from(a)));
+                }
+                0xB8 => {
+                    let a = pop_i32(&mut stack)?;
+                    stack.push(Value::F64(f64::from(a as u32)));
+                }
+                0xB9 => {
+                    let a = pop_i64(&mut stack)?;
+                    stack.push(Value::F64(a as f64));
+                }
+                0xBA => {
+                    let a = pop_i64(&mut stack)?;
+                    stack.push(Value::F64(a as u64 as f64));
+                }
+                0xBB => {
+                    let a = pop_f32(&mut stack)?;
+                    stack.push(Value::F64(f64::from(a)));
+                }
+                0xBC => {
+                    let a = pop_f32(&mut stack)?;
+                    stack.push(Value::I32(a.to_bits() as i32));
+                }
+                0xBD => {
+                    let a = pop_f64(&mut stack)?;
+                    stack.push(Value::I64(a.to_bits() as i64));
+                }
+                0xBE => {
+                    let a = pop_i32(&mut stack)?;
+                    stack.push(Value::F32(f32::from_bits(a as u32)));
+                }
+                0xBF => {
+                    let a = pop_i64(&mut stack)?;
+                    stack.push(Value::F64(f64::from_bits(a as u64)));
+                }
+                0xC0 => unary_i32(&mut stack, |a| a as i8 as i32)?,
+                0xC1 => unary_i32(&mut stack, |a| a as i16 as i32)?,
+                0xC2 => unary_i64(&mut stack, |a| a as i8 as i64)?,
+                0xC3 => unary_i64(&mut stack, |a| a as i16 as i64)?,
+                0xC4 => unary_i64(&mut stack, |a| a as i32 as i64)?,
+                0xFD => {
+                    if !self.features.simd {
+                        trap!("SIMD is disabled by the runtime's feature set");
+                    }
+                    self.simd(code, &mut pc, &mut stack)?;
+                }
+                0xFE => {
+                    if !self.features.threads {
+                        trap!("atomics require the threads feature");
+                    }
+                    self.atomic(code, &mut pc, &mut stack)?;
+                }
+                other => return Err(WasmError::UnsupportedOpcode(other)),
+            }
+        }
+        let results = stack.split_off(stack.len().saturating_sub(ty.results.len()));
+        Ok(results)
+    }
+
+    /// One `0xFD`-prefixed vector instruction. Covers the working core
+    /// of the SIMD proposal — loads/stores, constants, splats,
+    /// lane access, bitwise ops, and lane-wise arithmetic; the exotic
+    /// tail (swizzles, narrowing, saturating forms) traps with the
+    /// sub-opcode in the message.
+    #[allow(clippy::too_many_lines)]
+    fn simd(
+        &mut self,
+        code: &[u8],
+        pc: &mut usize,
+        stack: &mut Vec<Value>,
+    ) -> Result<(), WasmError> {
+        let sub = leb_u32(code, pc)?;
+        match sub {
+            // v128.load
+            0x00 => {
+                let (_, offset) = memarg(code, pc)?;
+                let address = pop_i32(stack)? as u32;
+                let memory = self
+                    .memory
+                    .as_ref()
+                    .ok_or_else(|| WasmError::Trap("no memory".into()))?;
+                let bytes: [u8; 16] = memory.load(address, offset, 16)?.try_into().unwrap();
+                stack.push(Value::V128(u128::from_le_bytes(bytes)));
+            }
+            // v128.store
+            0x0B => {
+                let (_, offset) = memarg(code, pc)?;
+                let value = pop_v128(stack)?;
+                let address = pop_i32(stack)? as u32;
+                let memory = self
+                    .memory
+                    .as_mut()
+                    .ok_or_else(|| WasmError::Trap("no memory".into()))?;
+                memory.store(address, offset, &value.to_le_bytes())?;
+            }
+            // v128.const
+            0x0C => {
+                let bytes: [u8; 16] = raw(code, pc)?;
+                stack.push(Value::V128(u128::from_le_bytes(bytes)));
+            }
+            // Splats.
+            0x0F => {
+                let a = pop_i32(stack)? as i8;
+                stack.push(Value::V128(from_i8x16([a; 16])));
+            }
+            0x10 => {
+                let a = pop_i32(stack)? as i16;
+                stack.push(Value::V128(from_i16x8([a; 8])));
+            }
+            0x11 => {
+                let a = pop_i32(stack)?;
+                stack.push(Value::V128(from_i32x4([a; 4])));
+            }
+            0x12 => {
+                let a = pop_i64(stack)?;
+                stack.push(Value::V128(from_i64x2([a; 2])));
+            }
+            0x13 => {
+                let a = pop_f32(stack)?;
+                stack.push(Value::V128(from_f32x4([a; 4])));
+            }
+            0x14 => {
+                let a = pop_f64(stack)?;
+                stack.push(Value::V128(from_f64x2([a; 2])));
+            }
+            // Lane extraction and replacement.
+            0x15 | 0x16 => {
+                let lane = lane_index(code, pc, 16)?;
+                let a = to_i8x16(pop_v128(stack)?)[lane];
+                let value = if sub == 0x15 { a as i32 } else { a as u8 as i32 };
+                stack.push(Value::I32(value));
+            }
+            0x18 | 0x19 => {
+                let lane = lane_index(code, pc, 8)?;
+                let a = to_i16x8(pop_v128(stack)?)[lane];
+                let value = if sub == 0x18 { a as i32 } else { a as u16 as i32 };
+                stack.push(Value::I32(value));
+            }
+            0x1B => {
+                let lane = lane_index(code, pc, 4)?;
+                stack.push(Value::I32(to_i32x4(pop_v128(stack)?)[lane]));
+            }
+            0x1C => {
+                let lane = lane_index(code, pc, 4)?;
+                let replacement = pop_i32(stack)?;
+                let mut lanes = to_i32x4(pop_v128(stack)?);
+                lanes[lane] = replacement;
+                stack.push(Value::V128(from_i32x4(lanes)));
+            }
+            0x1D => {
+                let lane = lane_index(code, pc, 2)?;
+                stack.push(Value::I64(to_i64x2(pop_v128(stack)?)[lane]));
+            }
+            0x1F => {
+                let lane = lane_index(code, pc, 4)?;
+                stack.push(Value::F32(to_f32x4(pop_v128(stack)?)[lane]));
+            }
+            0x20 => {
+                let lane = lane_index(code, pc, 4)?;
+                let replacement = pop_f32(stack)?;
+                let mut lanes = to_f32x4(pop_v128(stack)?);
+                lanes[lane] = replacement;
+                stack.push(Value::V128(from_f32x4(lanes)));
+            }
+            0x21 => {
+                let lane = lane_index(code, pc, 2)?;
+                stack.push(Value::F64(to_f64x2(pop_v128(stack)?)[lane]));
+            }
+            // Bitwise.
+            0x4D => {
+                let a = pop_v128(stack)?;
+                stack.push(Value::V128(!a));
+            }
+            0x4E => binary_v128(stack, |a, b| a & b)?,
+            0x4F => binary_v128(stack, |a, b| a & !b)?,
+            0x50 => binary_v128(stack, |a, b| a | b)?,
+            0x51 => binary_v128(stack, |a, b| a ^ b)?,
+            0x52 => {
+                let mask = pop_v128(stack)?;
+                let b = pop_v128(stack)?;
+                let a = pop_v128(stack)?;
+                stack.push(Value::V128((a & mask) | (b & !mask)));
+            }
+            0x53 => {
+                let a = pop_v128(stack)?;
+                stack.push(Value::I32((a != 0) as i32));
+            }
+            // Lane-wise integer arithmetic.
+            0x6E => binary_lanes_i8(stack, i8::wrapping_add)?,
+            0x71 => binary_lanes_i8(stack, i8::wrapping_sub)?,
+            0x8E => binary_lanes_i16(stack, i16::wrapping_add)?,
+            0x91 => binary_lanes_i16(stack, i16::wrapping_sub)?,
+            0x95 => binary_lanes_i16(stack, i16::wrapping_mul)?,
+            0xAE => binary_lanes_i32(stack, i32::wrapping_add)?,
+            0xB1 => binary_lanes_i32(stack, i32::wrapping_sub)?,
+            0xB5 => binary_lanes_i32(stack, i32::wrapping_mul)?,
+            0xCE => binary_lanes_i64(stack, i64::wrapping_add)?,
+            0xD1 => binary_lanes_i64(stack, i64::wrapping_sub)?,
+            0xD5 => binary_lanes_i64(stack, i64::wrapping_mul)?,
+            // Lane-wise float arithmetic.
+            0xE4 => binary_lanes_f32(stack, |a, b| a + b)?,
+            0xE5 => binary_lanes_f32(stack, |a, b| a - b)?,
+            0xE6 => binary_lanes_f32(stack, |a, b| a * b)?,
+            0xE7 => binary_lanes_f32(stack, |a, b| a / b)?,
+            0xE8 => binary_lanes_f32(stack, f32::min)?,
+            0xE9 => binary_lanes_f32(stack, f32::max)?,
+            0xF0 => binary_lanes_f64(stack, |a, b| a + b)?,
+            0xF1 => binary_lanes_f64(stack, |a, b| a - b)?,
+            0xF2 => binary_lanes_f64(stack, |a, b| a * b)?,
+            0xF3 => binary_lanes_f64(stack, |a, b| a / b)?,
+            other => {
+                return Err(WasmError::Trap(format!(
+                    "unsupported SIMD instruction {other:#04x}"
+                )))
+            }
+        }
+        Ok(())
+    }
+
+    /// One `0xFE`-prefixed atomic instruction (threads proposal).
+    /// Read-modify-writes go through [`Memory::update`]'s single lock
+    /// acquisition; `wait` never blocks — parking the interpreter
+    /// thread would hang the page, so it reports not-equal or an
+    /// immediate timeout, the same observable results a racing waiter
+    /// can get.
+    fn atomic(
+        &mut self,
+        code: &[u8],
+        pc: &mut usize,
+        stack: &mut Vec<Value>,
+    ) -> Result<(), WasmError> {
+        let sub = leb_u32(code, pc)?;
+        // atomic.fence: a single reserved byte, and the lock in every
+        // shared access already orders it.
+        if sub == 0x03 {
+            *pc += 1;
+            return Ok(());
+        }
+        let (_, offset) = memarg(code, pc)?;
+        match sub {
+            // memory.atomic.notify: wake up to `count` waiters. Waits
+            // never park (below), so there is never anyone to wake.
+            0x00 => {
+                let _count = pop_i32(stack)?;
+                let _address = pop_i32(stack)?;
+                stack.push(Value::I32(0));
+            }
+            // memory.atomic.wait32 / wait64.
+            0x01 => {
+                let _timeout = pop_i64(stack)?;
+                let expected = pop_i32(stack)?;
+                let address = pop_i32(stack)? as u32;
+                let current = i32::from_le_bytes(
+                    self.require_memory()?.load(address, offset, 4)?.try_into().unwrap(),
+                );
+                stack.push(Value::I32(if current != expected { 1 } else { 2 }));
+            }
+            0x02 => {
+                let _timeout = pop_i64(stack)?;
+                let expected = pop_i64(stack)?;
+                let address = pop_i32(stack)? as u32;
+                let current = i64::from_le_bytes(
+                    self.require_memory()?.load(address, offset, 8)?.try_into().unwrap(),
+                );
+                stack.push(Value::I32(if current != expected { 1 } else { 2 }));
+            }
+            // Atomic loads and stores.
+            0x10 => {
+                let address = pop_i32(stack)? as u32;
+                let bytes = self.require_memory()?.load(address, offset, 4)?;
+                stack.push(Value::I32(i32::from_le_bytes(bytes.try_into().unwrap())));
+            }
+            0x11 => {
+                let address = pop_i32(stack)? as u32;
+                let bytes = self.require_memory()?.load(address, offset, 8)?;
+                stack.push(Value::I64(i64::from_le_bytes(bytes.try_into().unwrap())));
+            }
+            0x17 => {
+                let value = pop_i32(stack)?;
+                let address = pop_i32(stack)? as u32;
+                self.require_memory()?
+                    .store(address, offset, &value.to_le_bytes())?;
+            }
+            0x18 => {
+                let value = pop_i64(stack)?;
+                let address = pop_i32(stack)? as u32;
+                self.require_memory()?
+                    .store(address, offset, &value.to_le_bytes())?;
+            }
+            // i32 read-modify-writes: add, sub, and, or, xor, xchg.
+            0x1E | 0x25 | 0x2C | 0x33 | 0x3A | 0x41 => {
+                let operand = pop_i32(stack)?;
+                let address = pop_i32(stack)? as u32;
+                let old = self.require_memory()?.update(address, offset, 4, |old| {
+                    let old = i32::from_le_bytes(old.try_into().unwrap());
+                    let new = match sub {
+                        0x1E => old.wrapping_add(operand),
+                        0x25 => old.wrapping_sub(operand),
+                        0x2C => old & operand,
+                        0x33 => old | operand,
+                        0x3A => old ^ operand,
+                        _ => operand,
+                    };
+                    new.to_le_bytes().to_vec()
+                })?;
+                stack.push(Value::I32(i32::from_le_bytes(old.try_into().unwrap())));
+            }
+            // i64 read-modify-writes.
+            0x1F | 0x26 | 0x2D | 0x34 | 0x3B | 0x42 => {
+                let operand = pop_i64(stack)?;
+                let address = pop_i32(stack)? as u32;
+                let old = self.require_memory()?.update(address, offset, 8, |old| {
+                    let old = i64::from_le_bytes(old.try_into().unwrap());
+                    let new = match sub {
+                        0x1F => old.wrapping_add(operand),
+                        0x26 => old.wrapping_sub(operand),
+                        0x2D => old & operand,
+                        0x34 => old | operand,
+                        0x3B => old ^ operand,
+                        _ => operand,
+                    };
+                    new.to_le_bytes().to_vec()
+                })?;
+                stack.push(Value::I64(i64::from_le_bytes(old.try_into().unwrap())));
+            }
+            // i32 / i64 cmpxchg.
+            0x48 => {
+                let replacement = pop_i32(stack)?;
+                let expected = pop_i32(stack)?;
+                let address = pop_i32(stack)? as u32;
+                let old = self.require_memory()?.update(address, offset, 4, |old_bytes| {
+                    let old = i32::from_le_bytes(old_bytes.try_into().unwrap());
+                    if old == expected {
+                        replacement.to_le_bytes().to_vec()
+                    } else {
+                        old_bytes.to_vec()
+                    }
+                })?;
+                stack.push(Value::I32(i32::from_le_bytes(old.try_into().unwrap())));
+            }
+            0x49 => {
+                let replacement = pop_i64(stack)?;
+                let expected = pop_i64(stack)?;
+                let address = pop_i32(stack)? as u32;
+                let old = self.require_memory()?.update(address, offset, 8, |old_bytes| {
+                    let old = i64::from_le_bytes(old_bytes.try_into().unwrap());
+                    if old == expected {
+                        replacement.to_le_bytes().to_vec()
+                    } else {
+                        old_bytes.to_vec()
+                    }
+                })?;
+                stack.push(Value::I64(i64::from_le_bytes(old.try_into().unwrap())));
+            }
+            // Narrow (8/16-bit) atomics are rarely the hot path; trap
+            // with the sub-opcode until a real consumer needs them.
+            other => {
+                return Err(WasmError::Trap(format!(
+                    "unsupported atomic instruction {other:#04x}"
+                )))
+            }
+        }
+        Ok(())
+    }
+
+    fn require_memory(&mut self) -> Result<&mut Memory, WasmError> {
+        self.memory
+            .as_mut()
+            .ok_or_else(|| WasmError::Trap("no memory".into()))
+    }
+}
+
+/// Take the branch `depth` blocks out: unwind the value stack to the
+/// target's entry height (keeping its result values), then continue at
+/// the loop head or after the block's `end`.
+fn branch(
+    control: &mut Vec<ControlFrame>,
+    stack: &mut Vec<Value>,
+    pc: &mut usize,
+    depth: usize,
+) -> Result<(), WasmError> {
+    if depth >= control.len() {
+        return Err(WasmError::Trap("branch depth out of range".into()));
+    }
+    let target = control.len() - 1 - depth;
+    let keep = if control[target].is_loop {
+        0
+    } else {
+        control[target].arity
+    };
+    let carried = stack.split_off(stack.len().saturating_sub(keep));
+    stack.truncate(control[target].stack_height);
+    stack.extend(carried);
+    if control[target].is_loop {
+        *pc = control[target].start;
+        control.truncate(target + 1);
+    } else {
+        *pc = control[target].end + 1;
+        control.truncate(target);
+    }
+    Ok(())
+}
+
+/// Decode a block's result arity from its block type.
+fn block_arity(code: &[u8], pc: &mut usize) -> Result<usize, WasmError> {
+    let byte = *code
+        .get(*pc)
+        .ok_or_else(|| WasmError::Trap("truncated block".into()))?;
+    *pc += 1;
+    match byte {
+        0x40 => Ok(0),
+        0x7B..=0x7F => Ok(1),
+        other => Err(WasmError::Decode(format!(
+            "multi-value block types are unsupported ({other:#04x})"
+        ))),
+    }
+}
+
+/// Scan forward from the first instruction of a block to its matching
+/// `end`, noting a depth-zero `else` on the way.
+fn block_bounds(code: &[u8], from: usize) -> Result<(Option<usize>, usize), WasmError> {
+    let mut pc = from;
+    let mut depth = 0usize;
+    let mut else_at = None;
+    while pc < code.len() {
+        let op = code[pc];
+        let at = pc;
+        pc += 1;
+        match op {
+            0x02 | 0x03 | 0x04 => {
+                pc += 1; // block type
+                depth += 1;
+            }
+            0x05 => {
+                if depth == 0 {
+                    else_at = Some(at);
+                }
+            }
+            0x0B => {
+                if depth == 0 {
+                    return Ok((else_at, at));
+                }
+                depth -= 1;
+            }
+            _ => skip_immediates(code, &mut pc, op)?,
+        }
+    }
+    Err(WasmError::Decode("block not terminated".into()))
+}
+
+/// Advance `pc` past the immediates of `op` (already consumed).
+fn skip_immediates(code: &[u8], pc: &mut usize, op: u8) -> Result<(), WasmError> {
+    match op {
+        // No immediates: control, parametric, numeric.
+        0x00 | 0x01 | 0x0F | 0x1A | 0x1B | 0x45..=0xC4 => {}
+        // One index/depth.
+        0x0C | 0x0D | 0x10 | 0x20..=0x24 => {
+            leb_u32(code, pc)?;
+        }
+        0x11 => {
+            leb_u32(code, pc)?;
+            leb_u32(code, pc)?;
+        }
+        0x0E => {
+            let count = leb_u32(code, pc)?;
+            for _ in 0..=count {
+                leb_u32(code, pc)?;
+            }
+        }
+        0x28..=0x3E => {
+            leb_u32(code, pc)?;
+            leb_u32(code, pc)?;
+        }
+        0x3F | 0x40 => {
+            *pc += 1;
+        }
+        0x41 => {
+            leb_i32(code, pc)?;
+        }
+        0x42 => {
+            leb_i64(code, pc)?;
+        }
+        0x43 => *pc += 4,
+        0x44 => *pc += 8,
+        0xFD => {
+            // Vector immediates, by sub-opcode range.
+            match leb_u32(code, pc)? {
+                // Loads and the store: memarg.
+                0x00..=0x0B | 0x5C | 0x5D => {
+                    leb_u32(code, pc)?;
+                    leb_u32(code, pc)?;
+                }
+                // v128.const and i8x16.shuffle: 16 bytes.
+                0x0C | 0x0D => *pc += 16,
+                // Lane extraction/replacement: lane index.
+                0x15..=0x22 => *pc += 1,
+                // Load/store lane: memarg, then the lane.
+                0x54..=0x5B => {
+                    leb_u32(code, pc)?;
+                    leb_u32(code, pc)?;
+                    *pc += 1;
+                }
+                _ => {}
+            }
+        }
+        0xFE => {
+            // Atomics: the fence has one reserved byte, everything
+            // else a memarg.
+            match leb_u32(code, pc)? {
+                0x03 => *pc += 1,
+                _ => {
+                    leb_u32(code, pc)?;
+                    leb_u32(code, pc)?;
+                }
+            }
+        }
+        other => return Err(WasmError::UnsupportedOpcode(other)),
+    }
+    Ok(())
+}
+
+fn pop(stack: &mut Vec<Value>) -> Result<Value, WasmError> {
+    stack
+        .pop()
+        .ok_or_else(|| WasmError::Trap("value stack underflow".into()))
+}
+
+fn pop_i32(stack: &mut Vec<Value>) -> Result<i32, WasmError> {
+    match pop(stack)? {
+        Value::I32(value) => Ok(value),
+        other => Err(WasmError::Trap(format!("expected i32, got {other:?}"))),
+    }
+}
+
+fn pop_i64(stack: &mut Vec<Value>) -> Result<i64, WasmError> {
+    match pop(stack)? {
+        Value::I64(value) => Ok(value),
+        other => Err(WasmError::Trap(format!("expected i64, got {other:?}"))),
+    }
+}
+
+fn pop_f32(stack: &mut Vec<Value>) -> Result<f32, WasmError> {
+    match pop(stack)? {
+        Value::F32(value) => Ok(value),
+        other => Err(WasmError::Trap(format!("expected f32, got {other:?}"))),
+    }
+}
+
+fn pop_f64(stack: &mut Vec<Value>) -> Result<f64, WasmError> {
+    match pop(stack)? {
+        Value::F64(value) => Ok(value),
+        other => Err(WasmError::Trap(format!("expected f64, got {other:?}"))),
+    }
+}
+
+fn pop_v128(stack: &mut Vec<Value>) -> Result<u128, WasmError> {
+    match pop(stack)? {
+        Value::V128(value) => Ok(value),
+        other => Err(WasmError::Trap(format!("expected v128, got {other:?}"))),
+    }
+}
+
+/// A lane immediate, checked against the shape's lane count.
+fn lane_index(code: &[u8], pc: &mut usize, lanes: usize) -> Result<usize, WasmError> {
+    let lane = *code
+        .get(*pc)
+        .ok_or_else(|| WasmError::Trap("truncated immediate".into()))? as usize;
+    *pc += 1;
+    if lane >= lanes {
+        return Err(WasmError::Trap(format!("lane {lane} out of range")));
+    }
+    Ok(lane)
+}
+
+fn local(locals: &[Value], index: usize) -> Result<Value, WasmError> {
+    locals
+        .get(index)
+        .copied()
+        .ok_or_else(|| WasmError::Trap("bad local index".into()))
+}
+
+fn unary_i32(stack: &mut Vec<Value>, f: impl Fn(i32) -> i32) -> Result<(), WasmError> {
+    let a = pop_i32(stack)?;
+    stack.push(Value::I32(f(a)));
+    Ok(())
+}
+
+fn binary_i32(stack: &mut Vec<Value>, f: impl Fn(i32, i32) -> i32) -> Result<(), WasmError> {
+    let b = pop_i32(stack)?;
+    let a = pop_i32(stack)?;
+    stack.push(Value::I32(f(a, b)));
+    Ok(())
+}
+
+fn unary_i64(stack: &mut Vec<Value>, f: impl Fn(i64) -> i64) -> Result<(), WasmError> {
+    let a = pop_i64(stack)?;
+    stack.push(Value::I64(f(a)));
+    Ok(())
+}
+
+fn binary_i64(stack: &mut Vec<Value>, f: impl Fn(i64, i64) -> i64) -> Result<(), WasmError> {
+    let b = pop_i64(stack)?;
+    let a = pop_i64(stack)?;
+    stack.push(Value::I64(f(a, b)));
+    Ok(())
+}
+
+fn unary_f32(stack: &mut Vec<Value>, f: impl Fn(f32) -> f32) -> Result<(), WasmError> {
+    let a = pop_f32(stack)?;
+    stack.push(Value::F32(f(a)));
+    Ok(())
+}
+
+fn binary_f32(stack: &mut Vec<Value>, f: impl Fn(f32, f32) -> f32) -> Result<(), WasmError> {
+    let b = pop_f32(stack)?;
+    let a = pop_f32(stack)?;
+    stack.push(Value::F32(f(a, b)));
+    Ok(())
+}
+
+fn unary_f64(stack: &mut Vec<Value>, f: impl Fn(f64) -> f64) -> Result<(), WasmError> {
+    let a = pop_f64(stack)?;
+    stack.push(Value::F64(f(a)));
+    Ok(())
+}
+
+fn binary_f64(stack: &mut Vec<Value>, f: impl Fn(f64, f64) -> f64) -> Result<(), WasmError> {
+    let b = pop_f64(stack)?;
+    let a = pop_f64(stack)?;
+    stack.push(Value::F64(f(a, b)));
+    Ok(())
+}
+
+fn binary_v128(stack: &mut Vec<Value>, f: impl Fn(u128, u128) -> u128) -> Result<(), WasmError> {
+    let b = pop_v128(stack)?;
+    let a = pop_v128(stack)?;
+    stack.push(Value::V128(f(a, b)));
+    Ok(())
+}
+
+// Lane views of a v128: little-endian byte order throughout, per the
+// spec's memory representation.
+
+fn to_i8x16(v: u128) -> [i8; 16] {
+    v.to_le_bytes().map(|byte| byte as i8)
+}
+
+fn from_i8x16(lanes: [i8; 16]) -> u128 {
+    u128::from_le_bytes(lanes.map(|lane| lane as u8))
+}
+
+fn to_i16x8(v: u128) -> [i16; 8] {
+    let bytes = v.to_le_bytes();
+    std::array::from_fn(|i| i16::from_le_bytes([bytes[2 * i], bytes[2 * i + 1]]))
+}
+
+fn from_i16x8(lanes: [i16; 8]) -> u128 {
+    let mut bytes = [0u8; 16];
+    for (i, lane) in lanes.iter().enumerate() {
+        bytes[2 * i..2 * i + 2].copy_from_slice(&lane.to_le_bytes());
+    }
+    u128::from_le_bytes(bytes)
+}
+
+fn to_i32x4(v: u128) -> [i32; 4] {
+    let bytes = v.to_le_bytes();
+    std::array::from_fn(|i| {
+        i32::from_le_bytes(bytes[4 * i..4 * i + 4].try_into().unwrap())
+    })
+}
+
+fn from_i32x4(lanes: [i32; 4]) -> u128 {
+    let mut bytes = [0u8; 16];
+    for (i, lane) in lanes.iter().enumerate() {
+        bytes[4 * i..4 * i + 4].copy_from_slice(&lane.to_le_bytes());
+    }
+    u128::from_le_bytes(bytes)
+}
+
+fn to_i64x2(v: u128) -> [i64; 2] {
+    let bytes = v.to_le_bytes();
+    std::array::from_fn(|i| {
+        i64::from_le_bytes(bytes[8 * i..8 * i + 8].try_into().unwrap())
+    })
+}
+
+fn from_i64x2(lanes: [i64; 2]) -> u128 {
+    let mut bytes = [0u8; 16];
+    for (i, lane) in lanes.iter().enumerate() {
+        bytes[8 * i..8 * i + 8].copy_from_slice(&lane.to_le_bytes());
+    }
+    u128::from_le_bytes(bytes)
+}
+
+fn to_f32x4(v: u128) -> [f32; 4] {
+    to_i32x4(v).map(|lane| f32::from_bits(lane as u32))
+}
+
+fn from_f32x4(lanes: [f32; 4]) -> u128 {
+    from_i32x4(lanes.map(|lane| lane.to_bits() as i32))
+}
+
+fn to_f64x2(v: u128) -> [f64; 2] {
+    to_i64x2(v).map(|lane| f64::from_bits(lane as u64))
+}
+
+fn from_f64x2(lanes: [f64; 2]) -> u128 {
+    from_i64x2(lanes.map(|lane| lane.to_bits() as i64))
+}
+
+fn binary_lanes_i8(stack: &mut Vec<Value>, f: impl Fn(i8, i8) -> i8) -> Result<(), WasmError> {
+    binary_v128(stack, |a, b| {
+        let (a, b) = (to_i8x16(a), to_i8x16(b));
+        from_i8x16(std::array::from_fn(|i| f(a[i], b[i])))
+    })
+}
+
+fn binary_lanes_i16(stack: &mut Vec<Value>, f: impl Fn(i16, i16) -> i16) -> Result<(), WasmError> {
+    binary_v128(stack, |a, b| {
+        let (a, b) = (to_i16x8(a), to_i16x8(b));
+        from_i16x8(std::array::from_fn(|i| f(a[i], b[i])))
+    })
+}
+
+fn binary_lanes_i32(stack: &mut Vec<Value>, f: impl Fn(i32, i32) -> i32) -> Result<(), WasmError> {
+    binary_v128(stack, |a, b| {
+        let (a, b) = (to_i32x4(a), to_i32x4(b));
+        from_i32x4(std::array::from_fn(|i| f(a[i], b[i])))
+    })
+}
+
+fn binary_lanes_i64(stack: &mut Vec<Value>, f: impl Fn(i64, i64) -> i64) -> Result<(), WasmError> {
+    binary_v128(stack, |a, b| {
+        let (a, b) = (to_i64x2(a), to_i64x2(b));
+        from_i64x2(std::array::from_fn(|i| f(a[i], b[i])))
+    })
+}
+
+fn binary_lanes_f32(stack: &mut Vec<Value>, f: impl Fn(f32, f32) -> f32) -> Result<(), WasmError> {
+    binary_v128(stack, |a, b| {
+        let (a, b) = (to_f32x4(a), to_f32x4(b));
+        from_f32x4(std::array::from_fn(|i| f(a[i], b[i])))
+    })
+}
+
+fn binary_lanes_f64(stack: &mut Vec<Value>, f: impl Fn(f64, f64) -> f64) -> Result<(), WasmError> {
+    binary_v128(stack, |a, b| {
+        let (a, b) = (to_f64x2(a), to_f64x2(b));
+        from_f64x2(std::array::from_fn(|i| f(a[i], b[i])))
+    })
+}
+
+/// Round-half-to-even, the wasm `nearest` semantics.
+fn nearest_f32(a: f32) -> f32 {
+    let rounded = a.round();
+    if (a - a.trunc()).abs() == 0.5 && rounded % 2.0 != 0.0 {
+        rounded - a.signum()
+    } else {
+        rounded
+    }
+}
+
+fn nearest_f64(a: f64) -> f64 {
+    let rounded = a.round();
+    if (a - a.trunc()).abs() == 0.5 && rounded % 2.0 != 0.0 {
+        rounded - a.signum()
+    } else {
+        rounded
+    }
+}
+
+fn trunc_to_i32(a: f64) -> Result<i32, WasmError> {
+    let truncated = a.trunc();
+    if truncated.is_nan() || truncated < f64::from(i32::MIN) || truncated > f64::from(i32::MAX) {
+        return Err(WasmError::Trap("invalid conversion to integer".into()));
+    }
+    Ok(truncated as i32)
+}
+
+fn trunc_to_u32(a: f64) -> Result<u32, WasmError> {
+    let truncated = a.trunc();
+    if truncated.is_nan() || truncated < 0.0 || truncated > f64::from(u32::MAX) {
+        return Err(WasmError::Trap("invalid conversion to integer".into()));
+    }
+    Ok(truncated as u32)
+}
+
+fn trunc_to_i64(a: f64) -> Result<i64, WasmError> {
+    let truncated = a.trunc();
+    if truncated.is_nan() || truncated < -(2f64.powi(63)) || truncated >= 2f64.powi(63) {
+        return Err(WasmError::Trap("invalid conversion to integer".into()));
+    }
+    Ok(truncated as i64)
+}
+
+fn trunc_to_u64(a: f64) -> Result<u64, WasmError> {
+    let truncated = a.trunc();
+    if truncated.is_nan() || truncated < 0.0 || truncated >= 2f64.powi(64) {
+        return Err(WasmError::Trap("invalid conversion to integer".into()));
+    }
+    Ok(truncated as u64)
+}
+
+/// Read `N` raw little-endian bytes at `pc`.
+fn raw<const N: usize>(code: &[u8], pc: &mut usize) -> Result<[u8; N], WasmError> {
+    let bytes = code
+        .get(*pc..*pc + N)
+        .ok_or_else(|| WasmError::Trap("truncated constant".into()))?;
+    *pc += N;
+    Ok(bytes.try_into().unwrap())
+}
+
+fn leb_u32(code: &[u8], pc: &mut usize) -> Result<u32, WasmError> {
+    let mut result: u32 = 0;
+    let mut shift = 0;
+    loop {
+        let byte = *code
+            .get(*pc)
+            .ok_or_else(|| WasmError::Trap("truncated immediate".into()))?;
+        *pc += 1;
+        result |= u32::from(byte & 0x7F) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(result);
+        }
+        shift += 7;
+        if shift >= 32 {
+            return Err(WasmError::Trap("oversized immediate".into()));
+        }
+    }
+}
+
+fn leb_i32(code: &[u8], pc: &mut usize) -> Result<i32, WasmError> {
+    Ok(leb_i64(code, pc)? as i32)
+}
+
+fn leb_i64(code: &[u8], pc: &mut usize) -> Result<i64, WasmError> {
+    let mut result: i64 = 0;
+    let mut shift = 0;
+    loop {
+        let byte = *code
+            .get(*pc)
+            .ok_or_else(|| WasmError::Trap("truncated immediate".into()))?;
+        *pc += 1;
+        result |= i64::from(byte & 0x7F) << shift;
+        shift += 7;
+        if byte & 0x80 == 0 {
+            if shift < 64 && byte & 0x40 != 0 {
+                result |= -1i64 << shift;
+            }
+            return Ok(result);
+        }
+        if shift >= 64 {
+            return Err(WasmError::Trap("oversized immediate".into()));
+        }
+    }
+}
+
+/// `memarg`: alignment hint (unused) and byte offset.
+fn memarg(code: &[u8], pc: &mut usize) -> Result<(u32, u32), WasmError> {
+    let align = leb_u32(code, pc)?;
+    let offset = leb_u32(code, pc)?;
+    Ok((align, offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leb(mut value: u32) -> Vec<u8> {
+        let mut out = Vec::new();
+        loop {
+            let byte = (value & 0x7F) as u8;
+            value >>= 7;
+            if value == 0 {
+                out.push(byte);
+                return out;
+            }
+            out.push(byte | 0x80);
+        }
+    }
+
+    fn encode_type(ty: ValType) -> u8 {
+        match ty {
+            ValType::I32 => 0x7F,
+            ValType::I64 => 0x7E,
+            ValType::F32 => 0x7D,
+            ValType::F64 => 0x7C,
+            ValType::V128 => 0x7B,
+        }
+    }
+
+    fn section(id: u8, mut payload: Vec<u8>) -> Vec<u8> {
+        let mut out = vec![id];
+        out.extend(leb(payload.len() as u32));
+        out.append(&mut payload);
+        out
+    }
+
+    /// A one-function module exporting `run` with the given signature
+    /// and body (sans the trailing `end`).
+    fn module(params: &[ValType], results: &[ValType], body: &[u8]) -> Vec<u8> {
+        let mut bytes = b"\0asm\x01\0\0\0".to_vec();
+        let mut types = vec![1, 0x60];
+        types.extend(leb(params.len() as u32));
+        types.extend(params.iter().copied().map(encode_type));
+        types.extend(leb(results.len() as u32));
+        types.extend(results.iter().copied().map(encode_type));
+        bytes.extend(section(1, types));
+        bytes.extend(section(3, vec![1, 0]));
+        let mut exports = vec![1];
+        exports.extend(leb(3));
+        exports.extend(b"run");
+        exports.push(0x00);
+        exports.push(0);
+        bytes.extend(section(7, exports));
+        let mut function = vec![0];
+        function.extend_from_slice(body);
+        function.push(0x0B);
+        let mut code = vec![1];
+        code.extend(leb(function.len() as u32));
+        code.extend(function);
+        bytes.extend(section(10, code));
+        bytes
+    }
+
+    fn invoke(bytes: &[u8], args: &[Value]) -> Result<Vec<Value>, WasmError> {
+        let module = WasmRuntime::compile(bytes)?;
+        let mut instance = WasmRuntime::instantiate(module)?;
+        instance.invoke_export("run", args, &mut |_, _| Ok(Vec::new()))
+    }
+
+    fn v128_const(lanes: [i32; 4]) -> Vec<u8> {
+        let mut out = vec![0xFD];
+        out.extend(leb(0x0C));
+        out.extend(from_i32x4(lanes).to_le_bytes());
+        out
+    }
+
+    fn simd_op(sub: u32) -> Vec<u8> {
+        let mut out = vec![0xFD];
+        out.extend(leb(sub));
+        out
+    }
+
+    #[test]
+    fn scalar_add_still_works() {
+        let body = [0x20, 0, 0x20, 1, 0x6A];
+        let result = invoke(
+            &module(&[ValType::I32, ValType::I32], &[ValType::I32], &body),
+            &[Value::I32(1), Value::I32(2)],
+        );
+        assert_eq!(result.unwrap(), vec![Value::I32(3)]);
+    }
+
+    #[test]
+    fn i32x4_add_lanes() {
+        let mut body = v128_const([1, 2, 3, 4]);
+        body.extend(v128_const([10, 20, 30, 40]));
+        body.extend(simd_op(0xAE)); // i32x4.add
+        body.extend(simd_op(0x1B)); // i32x4.extract_lane
+        body.push(2);
+        let result = invoke(&module(&[], &[ValType::I32], &body), &[]);
+        assert_eq!(result.unwrap(), vec![Value::I32(33)]);
+    }
+
+    #[test]
+    fn f32x4_mul_lanes() {
+        let mut body = v128_const([2.0f32.to_bits() as i32; 4]);
+        body.extend(simd_op(0xE6)); // f32x4.mul on splat(x) * const
+        // Build the other operand first: splat the argument.
+        let mut full = vec![0x20, 0]; // local.get 0
+        full.extend(simd_op(0x13)); // f32x4.splat
+        full.extend(body);
+        full.extend(simd_op(0x1F)); // f32x4.extract_lane
+        full.push(1);
+        let result = invoke(&module(&[ValType::F32], &[ValType::F32], &full), &[Value::F32(3.5)]);
+        assert_eq!(result.unwrap(), vec![Value::F32(7.0)]);
+    }
+
+    #[test]
+    fn v128_bitselect_picks_by_mask() {
+        let mut body = v128_const([-1, -1, -1, -1]);
+        body.extend(v128_const([0, 0, 0, 0]));
+        body.extend(v128_const([-1, 0, -1, 0])); // mask
+        body.extend(simd_op(0x52)); // v128.bitselect
+        body.extend(simd_op(0x1B));
+        body.push(1);
+        let result = invoke(&module(&[], &[ValType::I32], &body), &[]);
+        assert_eq!(result.unwrap(), vec![Value::I32(0)]);
+    }
+
+    #[test]
+    fn simd_disabled_traps() {
+        let mut body = v128_const([0, 0, 0, 0]);
+        body.extend(simd_op(0x1B));
+        body.push(0);
+        let decoded = WasmRuntime::compile(&module(&[], &[ValType::I32], &body)).unwrap();
+        let features = WasmFeatures {
+            simd: false,
+            ..WasmFeatures::default()
+        };
+        let mut instance = WasmRuntime::instantiate_with(decoded, features).unwrap();
+        let result = instance.invoke_export("run", &[], &mut |_, _| Ok(Vec::new()));
+        assert!(matches!(result, Err(WasmError::Trap(_))));
+    }
+
+    /// Like [`module`], with one shared memory of one page.
+    fn module_with_shared_memory(results: &[ValType], body: &[u8]) -> Vec<u8> {
+        let mut bytes = b"\0asm\x01\0\0\0".to_vec();
+        let mut types = vec![1, 0x60, 0];
+        types.extend(leb(results.len() as u32));
+        types.extend(results.iter().copied().map(encode_type));
+        bytes.extend(section(1, types));
+        bytes.extend(section(3, vec![1, 0]));
+        bytes.extend(section(5, vec![1, 0x03, 1, 1]));
+        let mut exports = vec![1];
+        exports.extend(leb(3));
+        exports.extend(b"run");
+        exports.push(0x00);
+        exports.push(0);
+        bytes.extend(section(7, exports));
+        let mut function = vec![0];
+        function.extend_from_slice(body);
+        function.push(0x0B);
+        let mut code = vec![1];
+        code.extend(leb(function.len() as u32));
+        code.extend(function);
+        bytes.extend(section(10, code));
+        bytes
+    }
+
+    #[test]
+    fn shared_memory_requires_threads() {
+        let decoded =
+            WasmRuntime::compile(&module_with_shared_memory(&[], &[0x01])).unwrap();
+        let result = WasmRuntime::instantiate(decoded);
+        assert!(matches!(result, Err(WasmError::Instantiation(_))));
+    }
+
+    #[test]
+    fn atomic_rmw_add_returns_old_and_stores_sum() {
+        // rmw.add 5 at address 0 (old value 0), drop it, then rmw.add 7:
+        // the second old value observes the first write.
+        let body = [
+            0x41, 0, 0x41, 5, 0xFE, 0x1E, 2, 0, 0x1A, // i32.atomic.rmw.add; drop
+            0x41, 0, 0x41, 7, 0xFE, 0x1E, 2, 0, // i32.atomic.rmw.add
+        ];
+        let decoded =
+            WasmRuntime::compile(&module_with_shared_memory(&[ValType::I32], &body)).unwrap();
+        let features = WasmFeatures {
+            threads: true,
+            ..WasmFeatures::default()
+        };
+        let mut instance = WasmRuntime::instantiate_with(decoded, features).unwrap();
+        let result = instance.invoke_export("run", &[], &mut |_, _| Ok(Vec::new()));
+        assert_eq!(result.unwrap(), vec![Value::I32(5)]);
+        assert_eq!(instance.memory().unwrap().snapshot()[0], 12);
+    }
+
+    #[test]
+    fn atomic_cmpxchg_swaps_only_on_match() {
+        // cmpxchg(expected: 0, replacement: 9) at a zeroed address
+        // succeeds; a second with the stale expectation fails.
+        let body = [
+            0x41, 0, 0x41, 0, 0x41, 9, 0xFE, 0x48, 2, 0, 0x1A, // cmpxchg; drop
+            0x41, 0, 0x41, 0, 0x41, 4, 0xFE, 0x48, 2, 0, // stale cmpxchg
+        ];
+        let decoded =
+            WasmRuntime::compile(&module_with_shared_memory(&[ValType::I32], &body)).unwrap();
+        let features = WasmFeatures {
+            threads: true,
+            ..WasmFeatures::default()
+        };
+        let mut instance = WasmRuntime::instantiate_with(decoded, features).unwrap();
+        let result = instance.invoke_export("run", &[], &mut |_, _| Ok(Vec::new()));
+        // The failed exchange reports the current value and leaves it.
+        assert_eq!(result.unwrap(), vec![Value::I32(9)]);
+        assert_eq!(instance.memory().unwrap().snapshot()[0], 9);
+    }
+
+    #[test]
+    fn streaming_compile_matches_buffered() {
+        let body = [0x41, 0x2A]; // i32.const 42
+        let bytes = module(&[], &[ValType::I32], &body);
+        let mut compiler = WasmRuntime::compile_streaming();
+        // One byte at a time: every section boundary lands mid-push.
+        for byte in &bytes {
+            compiler.push(std::slice::from_ref(byte)).unwrap();
+        }
+        let streamed = compiler.finish().unwrap();
+        let mut instance = WasmRuntime::instantiate(streamed).unwrap();
+        let result = instance.invoke_export("run", &[], &mut |_, _| Ok(Vec::new()));
+        assert_eq!(result.unwrap(), vec![Value::I32(42)]);
+    }
+}